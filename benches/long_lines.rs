@@ -0,0 +1,66 @@
+//benchmarks for editing a single very long line, like minified json. run with `cargo bench`
+
+use calliglyph::core::app::App;
+use calliglyph::core::cursor::CursorPosition;
+use calliglyph::core::editor::Editor;
+use calliglyph::input::input_action::{Direction, InputAction};
+use calliglyph::ui::ui::ui;
+use criterion::{criterion_group, criterion_main, Criterion};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+//a 500 KB line, long enough to be pathological for per-keystroke work on the line
+const LINE_LEN: usize = 500_000;
+const CHUNK: &str = "{\"key\": [1, 2, 3]}, ";
+
+fn long_line_editor() -> Editor {
+    let mut editor = Editor::from_text(&CHUNK.repeat(LINE_LEN / CHUNK.len()));
+    editor.set_viewport(120, 40);
+    editor
+        .set_cursor(CursorPosition::new(LINE_LEN / 2, 0))
+        .unwrap();
+    editor
+}
+
+fn editing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("long line");
+
+    let mut editor = long_line_editor();
+    group.bench_function("insert_char mid line", |b| {
+        b.iter(|| editor.insert_char('x').unwrap())
+    });
+    let mut editor = long_line_editor();
+    group.bench_function("delete_backward mid line", |b| {
+        b.iter(|| editor.delete_backward().unwrap())
+    });
+    let mut editor = long_line_editor();
+    group.bench_function("move cursor right", |b| b.iter(|| editor.move_cursor(1, 0)));
+    let mut editor = long_line_editor();
+    group.bench_function("move cursor left", |b| b.iter(|| editor.move_cursor(-1, 0)));
+    //typing a char and stepping back over it keeps the cursor in the middle of the line
+    let mut editor = long_line_editor();
+    group.bench_function("typed input action", |b| {
+        b.iter(|| {
+            editor
+                .handle_input_action(InputAction::WriteChar('x'))
+                .unwrap();
+            editor
+                .handle_input_action(InputAction::MoveCursor(Direction::Left))
+                .unwrap();
+        })
+    });
+
+    let mut app = App::new();
+    app.editor = long_line_editor();
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+    group.bench_function("render frame", |b| {
+        b.iter(|| {
+            terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, editing);
+criterion_main!(benches);
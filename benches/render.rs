@@ -0,0 +1,44 @@
+//benchmark for drawing a frame of buffers of very different sizes, frame time should only
+// depend on the lines in view. run with `cargo bench`
+
+use calliglyph::core::app::App;
+use calliglyph::core::cursor::CursorPosition;
+use calliglyph::core::editor::Editor;
+use calliglyph::ui::ui::ui;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+///app showing the middle of a buffer with line_count lines
+fn app_with_lines(line_count: usize) -> App {
+    let mut app = App::new();
+    let text: Vec<String> = (0..line_count)
+        .map(|i| format!("line {} {}", i, "lorem ipsum ".repeat(8)))
+        .collect();
+    app.editor = Editor::from_text(&text.join("\n"));
+    app.editor.set_viewport(160, 20);
+    let middle = line_count / 2;
+    app.editor
+        .set_cursor(CursorPosition::new(0, middle))
+        .unwrap();
+    app
+}
+
+fn rendering(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render frame");
+
+    for line_count in [1_000, 1_000_000] {
+        let mut app = app_with_lines(line_count);
+        let mut terminal = Terminal::new(TestBackend::new(160, 50)).unwrap();
+        group.bench_function(BenchmarkId::from_parameter(line_count), |b| {
+            b.iter(|| {
+                terminal.draw(|frame| ui(frame, &mut app)).unwrap();
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, rendering);
+criterion_main!(benches);
@@ -1,6 +1,117 @@
 //EDITOR SETTINGS
 pub mod editor_settings {
+    use crate::core::editor::words::WordChars;
+    use crate::core::settings::BellMode;
+
     pub const TAB_WIDTH: u16 = 4;
+    //indent with spaces instead of tabs, can be toggled with :set insertspaces on|off
+    pub const INSERT_SPACES: bool = false;
+    //vim-style normal/insert/visual modes, can be toggled with :set modal on|off
+    pub const MODAL_EDITING: bool = false;
+    //width :reflow breaks lines at without an explicit width
+    pub const REFLOW_WIDTH: usize = 80;
+    //cursor positions are restored when reopening one of the most recently closed files,
+    // can be toggled with :set restoreposition on|off
+    pub const RESTORE_POSITION: bool = true;
+    //the working dir changes to the dir of each opened file, so relative paths in commands
+    // start next to it. can be toggled with :set autochdir on|off
+    pub const AUTO_CHDIR: bool = false;
+    //named buffers with unsaved changes are saved when the terminal loses focus, without
+    // asking anything. can be toggled with :set autosave_on_focus_lost on|off
+    pub const AUTOSAVE_ON_FOCUS_LOST: bool = false;
+    //lines of an opened file looked at for guessing its indentation
+    pub const INDENT_SCAN_LINES: usize = 500;
+    //name suggested when saving an untitled buffer
+    pub const UNTITLED_FILE_NAME: &str = "untitled.txt";
+    //a save failing for lack of permissions offers to write the file through sudo or pkexec
+    // instead, on unix. can be toggled with :set elevatedsave on|off
+    pub const ELEVATED_SAVE: bool = true;
+    pub const SESSION_MAX_FILES: usize = 200;
+    //length of the recently used files list shown by :recent
+    pub const RECENT_FILES_MAX: usize = 30;
+    //paths in the :recent picker are shortened to the inner width of popups
+    pub const RECENT_PATH_WIDTH: usize = 58;
+    //files the ctrl+p finder lists at most, the walk of a huge dir stops there
+    pub const FINDER_MAX_FILES: usize = 20_000;
+    //smallest terminal the editor is drawn in, a smaller one only shows how much is needed
+    pub const MIN_TERMINAL_WIDTH: u16 = 10;
+    pub const MIN_TERMINAL_HEIGHT: u16 = 3;
+    //the info bar and then the line number gutter are left out below these sizes, so a
+    // small split keeps as much room for text as it can
+    pub const INFO_BAR_MIN_HEIGHT: u16 = 5;
+    pub const GUTTER_MIN_WIDTH: u16 = 20;
+    //buffers replaced by :open kept for :reopen, the oldest are dropped past this
+    pub const CLOSED_BUFFERS_MAX: usize = 10;
+    //recent files listed on the welcome screen of an untitled start
+    pub const WELCOME_RECENT_MAX: usize = 8;
+    //rows of tab completion candidates shown above the command line, the rest is cut off
+    pub const COMPLETION_ROWS_MAX: usize = 5;
+    //how often positions are written while running, besides on exit
+    pub const SESSION_SAVE_MS: u64 = 30_000;
+    //undo history is written per file on save and exit, and restored when the file is
+    // opened unchanged. can be toggled with :set persistentundo on|off
+    pub const PERSISTENT_UNDO: bool = false;
+    //largest undo history file, the oldest steps are dropped to stay below it
+    pub const UNDO_FILE_MAX_BYTES: usize = 1024 * 1024;
+    //buffers larger than this are saved on a separate thread so the ui keeps running,
+    // can be changed with :set backgroundsave <bytes>
+    pub const BACKGROUND_SAVE_BYTES: usize = 16 * 1024 * 1024;
+    //live word count in the info bar, can be toggled with :set wordcount on|off
+    pub const SHOW_WORD_COUNT: bool = false;
+    //keys worth knowing in the active area shown at the end of the bottom row,
+    // can be toggled with :set hints on|off
+    pub const SHOW_KEY_HINTS: bool = true;
+    //colored styles, off draws with bold, underline and reverse only. NO_COLOR and terminals
+    // without colors turn it off at startup, :set color on|off overrides it
+    pub const COLOR: bool = true;
+    //chars looked through for the partner of the bracket at the cursor beyond the visible
+    // lines, bounding the cost of highlighting it on every cursor move
+    pub const BRACKET_SCAN_LIMIT: usize = 2000;
+    //lines longer than this many bytes are rendered as just their visible slice, without
+    // highlighting or bracket matching, can be changed with :set longlinebytes <bytes>
+    pub const LONG_LINE_BYTES: usize = 20_000;
+    //long-line mode, can be turned off with :set longlinemode off to render every line fully
+    pub const LONG_LINE_MODE: bool = true;
+    //how long the main loop waits for input before running timers, like cursor blink
+    pub const INPUT_POLL_TIMEOUT_MS: u64 = 50;
+    pub const CURSOR_BLINK_MS: u64 = 500;
+    //how often :follow checks its file for new lines
+    pub const FOLLOW_POLL_MS: u64 = 500;
+    //feedback for key presses that do nothing, like undo with nothing to undo. flashes the
+    // info bar or beeps, can be changed with :set visualbell flash|beep|off
+    pub const VISUAL_BELL: BellMode = BellMode::Flash;
+    pub const BELL_FLASH_MS: u64 = 150;
+    //a bell asked for again within this time stays quiet, so held keys don't strobe
+    pub const BELL_INTERVAL_MS: u64 = 400;
+    //esc pressed again within this time closes the popup even if it ignores keys, a way
+    // out of a popup stuck in a broken state
+    pub const POPUP_FORCE_CLOSE_MS: u64 = 1000;
+    //upper bound of events handled before drawing, so a flood of events can't starve the ui
+    pub const MAX_EVENTS_PER_FRAME: usize = 1024;
+    //how long a status message stays visible in the command line
+    pub const STATUS_MESSAGE_MS: u64 = 4000;
+    //formats used by :insert date and :insert datetime, in UTC.
+    // supports %Y %m %d %H %M %S and %% for a literal percent sign
+    pub const DATE_FORMAT: &str = "%Y-%m-%d";
+    pub const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+    //extensions of the files :spell checks, so code files are never checked while typing
+    pub const SPELL_FILETYPES: &[&str] = &["md", "markdown", "txt"];
+    //keywords highlighted in the editor and listed by :todos,
+    // can be changed with :set annotations <comma separated keywords>
+    pub const ANNOTATION_KEYWORDS: &[&str] = &["TODO", "FIXME", "HACK", "NOTE"];
+    //chars making up words for word motions, completion and annotations, letters, digits
+    // and underscores. can be changed with :set wordboundary code|nonwhitespace|<extra chars>
+    pub const WORD_CHARS: WordChars = WordChars::Code;
+    //dir saves and extracts are refused outside of when no --restrict dir is given, for
+    // scripted use. None lets them write anywhere
+    pub const RESTRICT_TO_DIR: Option<&str> = None;
+    //debug log written when no --log path is given, None logs nothing
+    pub const LOG_FILE: Option<&str> = None;
+    //verbosity of the debug log when no --log-level is given, one of error, warn, info, debug
+    // or trace. buffer contents are only logged at debug and trace
+    pub const LOG_LEVEL: &str = "info";
+    //drawn in the gutter in front of the number of a bookmarked line
+    pub const BOOKMARK_MARKER: &str = "●";
 }
 
 // KEYBINDS
@@ -8,11 +119,17 @@ pub mod editor_settings {
 pub mod key_binds {
     use crossterm::event::{KeyCode, KeyModifiers};
 
+    const CONTROL_SHIFT: KeyModifiers = KeyModifiers::CONTROL.union(KeyModifiers::SHIFT);
+
     pub const KEYBIND_TOGGLE_AREA: (KeyModifiers, KeyCode) = (KeyModifiers::NONE, KeyCode::Esc);
     pub const KEYBIND_BACKSPACE: (KeyModifiers, KeyCode) = (KeyModifiers::NONE, KeyCode::Backspace);
     pub const KEYBIND_TAB: (KeyModifiers, KeyCode) = (KeyModifiers::NONE, KeyCode::Tab);
     pub const KEYBIND_ENTER: (KeyModifiers, KeyCode) = (KeyModifiers::NONE, KeyCode::Enter);
     pub const KEYBIND_DELETE: (KeyModifiers, KeyCode) = (KeyModifiers::NONE, KeyCode::Delete);
+    //open an indented line below or above the cursor line without splitting it
+    pub const KEYBIND_OPEN_LINE_BELOW: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::Enter);
+    pub const KEYBIND_OPEN_LINE_ABOVE: (KeyModifiers, KeyCode) = (CONTROL_SHIFT, KeyCode::Enter);
 
     //Cursor Movement
     pub const KEYBIND_UP: (KeyModifiers, KeyCode) = (KeyModifiers::NONE, KeyCode::Up);
@@ -29,22 +146,228 @@ pub mod key_binds {
     pub const KEYBIND_SELECTION_RIGHT: (KeyModifiers, KeyCode) =
         (KeyModifiers::SHIFT, KeyCode::Right);
 
+    //Motions
+    pub const KEYBIND_LINE_START: (KeyModifiers, KeyCode) = (KeyModifiers::NONE, KeyCode::Home);
+    pub const KEYBIND_LINE_END: (KeyModifiers, KeyCode) = (KeyModifiers::NONE, KeyCode::End);
+    pub const KEYBIND_WORD_LEFT: (KeyModifiers, KeyCode) = (KeyModifiers::CONTROL, KeyCode::Left);
+    pub const KEYBIND_WORD_RIGHT: (KeyModifiers, KeyCode) = (KeyModifiers::CONTROL, KeyCode::Right);
+    pub const KEYBIND_DOCUMENT_START: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::Home);
+    pub const KEYBIND_DOCUMENT_END: (KeyModifiers, KeyCode) = (KeyModifiers::CONTROL, KeyCode::End);
+
+    pub const KEYBIND_PARAGRAPH_UP: (KeyModifiers, KeyCode) = (KeyModifiers::CONTROL, KeyCode::Up);
+    pub const KEYBIND_PARAGRAPH_DOWN: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::Down);
+
+    //Text Selection with motions //extend selection
+    pub const KEYBIND_SELECTION_LINE_START: (KeyModifiers, KeyCode) =
+        (KeyModifiers::SHIFT, KeyCode::Home);
+    pub const KEYBIND_SELECTION_LINE_END: (KeyModifiers, KeyCode) =
+        (KeyModifiers::SHIFT, KeyCode::End);
+    pub const KEYBIND_SELECTION_WORD_LEFT: (KeyModifiers, KeyCode) = (CONTROL_SHIFT, KeyCode::Left);
+    pub const KEYBIND_SELECTION_WORD_RIGHT: (KeyModifiers, KeyCode) =
+        (CONTROL_SHIFT, KeyCode::Right);
+    pub const KEYBIND_SELECTION_DOCUMENT_START: (KeyModifiers, KeyCode) =
+        (CONTROL_SHIFT, KeyCode::Home);
+    pub const KEYBIND_SELECTION_DOCUMENT_END: (KeyModifiers, KeyCode) =
+        (CONTROL_SHIFT, KeyCode::End);
+    pub const KEYBIND_SELECTION_PARAGRAPH_UP: (KeyModifiers, KeyCode) =
+        (CONTROL_SHIFT, KeyCode::Up);
+    pub const KEYBIND_SELECTION_PARAGRAPH_DOWN: (KeyModifiers, KeyCode) =
+        (CONTROL_SHIFT, KeyCode::Down);
+
     //WITH MODIFIER AND CHAR  :: ShortCuts
     pub const KEYBIND_SAVE: (KeyModifiers, KeyCode) = (KeyModifiers::CONTROL, KeyCode::Char('s'));
+    pub const KEYBIND_QUIT: (KeyModifiers, KeyCode) = (KeyModifiers::CONTROL, KeyCode::Char('q'));
     pub const KEYBIND_COPY: (KeyModifiers, KeyCode) = (KeyModifiers::CONTROL, KeyCode::Char('c'));
+    pub const KEYBIND_COPY_APPEND: (KeyModifiers, KeyCode) = (CONTROL_SHIFT, KeyCode::Char('C'));
+    //followed by hex digits of a codepoint and enter
+    pub const KEYBIND_UNICODE: (KeyModifiers, KeyCode) = (CONTROL_SHIFT, KeyCode::Char('U'));
     pub const KEYBIND_CUT: (KeyModifiers, KeyCode) = (KeyModifiers::CONTROL, KeyCode::Char('x'));
+    //inserts a copy of the selection right after it
+    pub const KEYBIND_DUPLICATE_SELECTION: (KeyModifiers, KeyCode) =
+        (KeyModifiers::ALT, KeyCode::Char('d'));
 
     pub const KEYBIND_PASTE: (KeyModifiers, KeyCode) = (KeyModifiers::CONTROL, KeyCode::Char('f'));
 
     pub const KEYBIND_UNDO: (KeyModifiers, KeyCode) = (KeyModifiers::CONTROL, KeyCode::Char('z'));
 
     pub const KEYBIND_REDO: (KeyModifiers, KeyCode) = (KeyModifiers::CONTROL, KeyCode::Char('y'));
+
+    //suspends to the shell outside of insert mode in modal editing, where u undoes, unix only
+    pub const KEYBIND_SUSPEND: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::Char('z'));
+
+    pub const KEYBIND_REPEAT_LAST_CHANGE: (KeyModifiers, KeyCode) =
+        (KeyModifiers::NONE, KeyCode::F(4));
+
+    //Macros //followed by register char, play may be preceded by a count
+    pub const KEYBIND_MACRO_RECORD: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::Char('r'));
+    pub const KEYBIND_MACRO_PLAY: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::Char('t'));
+    //selects inside the enclosing brackets or quotes, pressing again expands it
+    pub const KEYBIND_SELECT_ENCLOSING: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::Char('w'));
+    //types text once for every selected line, at the left column of the selection
+    pub const KEYBIND_LINE_INSERT: (KeyModifiers, KeyCode) =
+        (KeyModifiers::ALT, KeyCode::Char('i'));
+    //jumps the cursor to the other end of the selection, for extending it on that side
+    pub const KEYBIND_SWAP_SELECTION_ENDS: (KeyModifiers, KeyCode) =
+        (KeyModifiers::ALT, KeyCode::Char('o'));
+    //marks, followed by the mark letter
+    pub const KEYBIND_MARK_SET: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::Char('k'));
+    pub const KEYBIND_MARK_JUMP: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::Char('j'));
+    //bookmarks of lines, shown in the gutter and jumped between
+    pub const KEYBIND_BOOKMARK_TOGGLE: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::F(2));
+    pub const KEYBIND_BOOKMARK_NEXT: (KeyModifiers, KeyCode) = (KeyModifiers::NONE, KeyCode::F(2));
+    pub const KEYBIND_BOOKMARK_PREVIOUS: (KeyModifiers, KeyCode) =
+        (KeyModifiers::SHIFT, KeyCode::F(2));
+    //changes the number at the cursor, or the first number of each selected line
+    pub const KEYBIND_INCREMENT: (KeyModifiers, KeyCode) = (KeyModifiers::ALT, KeyCode::Char('a'));
+    pub const KEYBIND_DECREMENT: (KeyModifiers, KeyCode) = (KeyModifiers::ALT, KeyCode::Char('x'));
+    //completes the word before the cursor from words in the buffer, pressing again cycles
+    pub const KEYBIND_COMPLETE_NEXT: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::Char('n'));
+    pub const KEYBIND_COMPLETE_PREVIOUS: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::Char('p'));
+    //fuzzy finds a file under the working dir to open, the same keys only cycle back
+    // through completions while completing
+    pub const KEYBIND_FIND_FILE: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::Char('p'));
+    //lists the open buffer and those set aside by opening others, to switch to one
+    pub const KEYBIND_SWITCH_BUFFER: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::Char('b'));
+    //scrolls the cursor line to the middle, top or bottom of the viewport
+    pub const KEYBIND_VIEW_CENTER: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::Char('l'));
+    pub const KEYBIND_VIEW_TOP: (KeyModifiers, KeyCode) = (KeyModifiers::ALT, KeyCode::Char('t'));
+    pub const KEYBIND_VIEW_BOTTOM: (KeyModifiers, KeyCode) =
+        (KeyModifiers::ALT, KeyCode::Char('b'));
+    //scroll the view without moving the cursor, by a line or half a page
+    pub const KEYBIND_SCROLL_LINE_UP: (KeyModifiers, KeyCode) = (KeyModifiers::ALT, KeyCode::Up);
+    pub const KEYBIND_SCROLL_LINE_DOWN: (KeyModifiers, KeyCode) =
+        (KeyModifiers::ALT, KeyCode::Down);
+    pub const KEYBIND_SCROLL_HALF_PAGE_UP: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::Char('u'));
+    pub const KEYBIND_SCROLL_HALF_PAGE_DOWN: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::Char('d'));
+    //opens the file path or url under the cursor, a path may end in :line
+    pub const KEYBIND_OPEN_UNDER_CURSOR: (KeyModifiers, KeyCode) =
+        (KeyModifiers::CONTROL, KeyCode::Char('g'));
 }
 
 //COMMAND BINDS
 pub mod command_binds {
     pub const COMMAND_EXIT_DONT_SAVE: &str = "q";
     pub const COMMAND_SAVE_DONT_EXIT: &str = "w";
+    pub const COMMAND_SAVE: &str = "save";
     pub const COMMAND_SAVE_AND_EXIT: &str = "wq";
+    pub const COMMAND_SAVE_ALL: &str = "wa";
+    pub const COMMAND_SAVE_ALL_AND_EXIT: &str = "xa";
     pub const COMMAND_HELP: &str = "h";
+    pub const COMMAND_MACROS: &str = "macros";
+    pub const COMMAND_SET: &str = "set";
+    pub const COMMAND_MARK: &str = "mark";
+    pub const COMMAND_GOTO: &str = "goto";
+    pub const COMMAND_MARKS: &str = "marks";
+    pub const COMMAND_INSERT: &str = "insert";
+    pub const COMMAND_WORD_COUNT: &str = "wc";
+    pub const COMMAND_INFO: &str = "info";
+    pub const COMMAND_CALC: &str = "calc";
+    pub const COMMAND_CALC_INSERT: &str = "calc!";
+    pub const COMMAND_REVERSE: &str = "reverse";
+    pub const COMMAND_UNIQUE: &str = "unique";
+    pub const COMMAND_ALIGN: &str = "align";
+    pub const COMMAND_REFLOW: &str = "reflow";
+    pub const COMMAND_RETAB: &str = "retab";
+    pub const COMMAND_OPEN: &str = "open";
+    pub const COMMAND_RECENT: &str = "recent";
+    pub const COMMAND_REOPEN: &str = "reopen";
+    pub const COMMAND_ENCODE: &str = "encode";
+    pub const COMMAND_DECODE: &str = "decode";
+    pub const COMMAND_INCREMENT: &str = "inc";
+    pub const COMMAND_DECREMENT: &str = "dec";
+    pub const COMMAND_SPELL: &str = "spell";
+    pub const COMMAND_TODOS: &str = "todos";
+    pub const COMMAND_DIFF: &str = "diff";
+    pub const COMMAND_HEXVIEW: &str = "hexview";
+    pub const COMMAND_COPY: &str = "copy";
+    pub const COMMAND_UNICODE: &str = "unicode";
+    pub const COMMAND_EXTRACT: &str = "extract";
+    pub const COMMAND_UNDOLIST: &str = "undolist";
+    pub const COMMAND_UNDO: &str = "undo";
+    pub const COMMAND_REDO: &str = "redo";
+    pub const COMMAND_BLANK: &str = "blank";
+    pub const COMMAND_FOLLOW: &str = "follow";
+    pub const COMMAND_CD: &str = "cd";
+    pub const COMMAND_PWD: &str = "pwd";
+    pub const COMMAND_SURROUND: &str = "surround";
+    pub const COMMAND_UNSURROUND: &str = "unsurround";
+    pub const COMMAND_BOOKMARKS: &str = "bookmarks";
+    pub const COMMAND_PENDING: &str = "pending";
+    pub const COMMAND_CASE: &str = "case";
+    pub const COMMAND_VIEW: &str = "view";
+    pub const COMMAND_SUSPEND: &str = "suspend";
+    pub const COMMAND_SUBSTITUTE: &str = "s";
+    pub const COMMAND_TRIM: &str = "trim";
+
+    //every command bind, offered when completing command names with tab
+    pub const COMMAND_BINDS: &[&str] = &[
+        COMMAND_EXIT_DONT_SAVE,
+        COMMAND_SAVE_DONT_EXIT,
+        COMMAND_SAVE,
+        COMMAND_SAVE_AND_EXIT,
+        COMMAND_SAVE_ALL,
+        COMMAND_SAVE_ALL_AND_EXIT,
+        COMMAND_HELP,
+        COMMAND_MACROS,
+        COMMAND_SET,
+        COMMAND_MARK,
+        COMMAND_GOTO,
+        COMMAND_MARKS,
+        COMMAND_INSERT,
+        COMMAND_WORD_COUNT,
+        COMMAND_INFO,
+        COMMAND_CALC,
+        COMMAND_CALC_INSERT,
+        COMMAND_REVERSE,
+        COMMAND_UNIQUE,
+        COMMAND_ALIGN,
+        COMMAND_REFLOW,
+        COMMAND_RETAB,
+        COMMAND_OPEN,
+        COMMAND_RECENT,
+        COMMAND_REOPEN,
+        COMMAND_ENCODE,
+        COMMAND_DECODE,
+        COMMAND_INCREMENT,
+        COMMAND_DECREMENT,
+        COMMAND_SPELL,
+        COMMAND_TODOS,
+        COMMAND_DIFF,
+        COMMAND_HEXVIEW,
+        COMMAND_COPY,
+        COMMAND_UNICODE,
+        COMMAND_EXTRACT,
+        COMMAND_UNDOLIST,
+        COMMAND_UNDO,
+        COMMAND_REDO,
+        COMMAND_BLANK,
+        COMMAND_FOLLOW,
+        COMMAND_CD,
+        COMMAND_PWD,
+        COMMAND_SURROUND,
+        COMMAND_UNSURROUND,
+        COMMAND_BOOKMARKS,
+        COMMAND_PENDING,
+        COMMAND_CASE,
+        COMMAND_VIEW,
+        COMMAND_SUSPEND,
+        COMMAND_SUBSTITUTE,
+        COMMAND_TRIM,
+    ];
 }
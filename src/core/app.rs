@@ -1,20 +1,60 @@
+use super::background_save::BackgroundSave;
+use super::bell::Bell;
 use super::clipboard::Clipboard;
+use super::command_line::commands::file::open_command;
 use super::command_line::{command, command_executor, CommandLine};
+use super::cursor::CursorPosition;
+use super::editor::indent::{self, Indent};
+use super::editor::invariants;
+use super::editor::marks::MarkChord;
+use super::editor::undo_redo::{self, UndoMark};
 use super::editor::Editor;
+use super::elevated_save;
+use super::errors::editor_errors::EditorError;
 use super::errors::error::AppError;
 use super::errors::error::AppError::EditorFailure;
+use super::file_finder::FileFinder;
+use super::file_format::FileFormat;
+use super::file_io;
+use super::follow::{Follow, Update};
+use super::hex_view::HexView;
+use super::job_control::{self, JobControl};
+use super::links::{self, Target};
+use super::lock::{self, FileLock, LockError, LockOwner};
+use super::macros::{MacroChord, MacroRecorder};
+use super::modal::{EditorMode, ModalState};
+use super::session::{absolute_path, shorten_path, FilePosition, Session};
+use super::settings::Settings;
+use super::spell::{self, SpellChecker};
+use super::terminal;
+use super::undo_file;
+use super::unicode;
+use super::viewer;
+use crate::config::editor_settings;
+use crate::input::input;
 use crate::input::input::handle_input;
-use crate::input::input_action::InputAction;
+use crate::input::input_action::{Direction, InputAction};
+use crate::ui::popups::confirmation_popup::ConfirmationPopup;
 use crate::ui::popups::error_popup::ErrorPopup;
+use crate::ui::popups::input_popup::InputPopup;
+use crate::ui::popups::list_popup::ListPopup;
 use crate::ui::popups::popup::{Popup, PopupResult, PopupType};
 use crate::ui::ui::ui;
 use color_eyre::Result;
-use ratatui::DefaultTerminal;
+use crossterm::cursor::SetCursorStyle;
+use crossterm::event::Event;
+use crossterm::execute;
+use crossterm::style::Print;
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashSet;
+use std::env;
 use std::fs;
-use std::fs::{File, OpenOptions};
-use std::io::{BufReader, BufWriter, Read, Write};
-use std::path::Path;
-use std::time::{Duration, Instant};
+use std::fs::File;
+use std::io;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use tracing::{debug, info, warn};
 #[derive(Debug)]
 pub struct App {
     /// Is the application running?
@@ -24,19 +64,222 @@ pub struct App {
     pub command_line: CommandLine,
     pub(crate) cursor_visible: bool,
     last_tick: Instant,
-    pub(crate) terminal_height: i16,
+    pub(crate) terminal_height: usize,
     pub clipboard: Clipboard,
     pub file_path: Option<String>,
     pub popup: Option<Box<dyn Popup>>,
     pub popup_result: PopupResult,
+    //when esc was last pressed on the open popup, a second press soon after force closes it
+    last_popup_escape: Option<Instant>,
     pub pending_states: Vec<PendingState>,
+    pub macros: MacroRecorder,
+    pub settings: Settings,
+    pub modal: ModalState,
+    //transient message shown in the command line, with the time it was set
+    pub status_message: Option<(String, Instant)>,
+    //mode the terminal cursor shape was last set for, None means the default shape
+    applied_cursor_mode: Option<EditorMode>,
+    //remembered cursor positions of files, restored when they are opened again
+    pub session: Session,
+    last_session_save: Instant,
+    //save of a large buffer running on its own thread
+    pub(crate) background_save: Option<BackgroundSave>,
+    //state of the buffer's undo history when it was last opened or saved
+    saved_mark: UndoMark,
+    //buffer differs from its file in a way the undo history doesn't tell, like after a failed
+    // save or a changed encoding
+    pub(crate) dirty: bool,
+    //loaded while spell checking is turned on with :spell
+    pub spell: Option<SpellChecker>,
+    //set while :hexview shows the file's bytes, holding the text editor it replaced
+    pub hex_view: Option<HexView>,
+    //hex digits typed after the unicode chord, until enter inserts their character
+    pub unicode_input: Option<String>,
+    //set while :follow appends the lines written to the open file, the buffer is read-only
+    pub follow: Option<Follow>,
+    //advisory lock on the open file, telling other instances it is being edited
+    pub(crate) lock: Option<FileLock>,
+    //the open file was opened read-only as another instance has it locked
+    pub read_only: bool,
+    //flashes or beeps on key presses that did nothing
+    pub bell: Bell,
+    //encoding and final newline the open file is saved with, detected when it is opened
+    pub file_format: FileFormat,
+    //highlighted row of the recent files on the welcome screen
+    pub welcome_selected: usize,
+    //buffers replaced by opening another file, the last one is brought back by :reopen
+    pub closed_buffers: Vec<ClosedBuffer>,
+    //resolved dir saves and extracts have to write under, set with --restrict
+    restrict_dir: Option<PathBuf>,
+    //when the open file was modified as it was last read or written, a later time means
+    // something else wrote it since
+    disk_mtime: Option<SystemTime>,
+    //what the run loop does next with the terminal handed back to the shell
+    terminal_task: Option<TerminalTask>,
+    //walk of the working dir listing files in the open ctrl+p finder
+    pub(crate) file_finder: Option<FileFinder>,
 }
 
-#[derive(Debug, PartialEq)]
+///something done outside the tui, with the terminal the way the shell left it
+#[derive(Debug, Clone, PartialEq)]
+pub enum TerminalTask {
+    //pipes the buffer to a program and its args, for :view
+    View(Vec<String>),
+    //stops the editor until the shell continues it
+    Suspend,
+}
+
+///a buffer set aside when another file replaced it, kept with its cursor, scroll and undo
+/// history in its editor
+#[derive(Debug)]
+pub struct ClosedBuffer {
+    pub path: Option<String>,
+    editor: Editor,
+    file_format: FileFormat,
+    //saved_mark and dirty of the app when it was closed
+    saved_mark: UndoMark,
+    dirty: bool,
+    read_only: bool,
+    disk_mtime: Option<SystemTime>,
+}
+
+impl ClosedBuffer {
+    ///file name of the buffer, untitled without one
+    fn name(&self) -> String {
+        self.path
+            .as_deref()
+            .map_or_else(|| "untitled".to_string(), file_name)
+    }
+
+    ///checks if the buffer was edited since its file was last read or written
+    fn has_unsaved_changes(&self) -> bool {
+        self.dirty || self.editor.is_modified_since(self.saved_mark)
+    }
+
+    ///writes the buffer to its file, which it then matches
+    fn save(&mut self) -> Result<(), AppError> {
+        let Some(path) = &self.path else {
+            return Err(AppError::InternalError(
+                "an untitled buffer has no file to save to".to_string(),
+            ));
+        };
+        file_io::write_file(path, &self.editor.editor_content, self.file_format)?;
+        self.saved_mark = self.editor.undo_mark();
+        self.dirty = false;
+        self.disk_mtime = file_io::modified_time(path);
+        Ok(())
+    }
+}
+
+///what saving every buffer with unsaved changes came to, for :wa
+#[derive(Debug, Default)]
+pub struct SaveAllReport {
+    pub saved: Vec<String>,
+    pub failed: Vec<(String, AppError)>,
+    //files changed on disk since they were read, queued to ask before overwriting them
+    pub confirming: Vec<String>,
+}
+
+impl SaveAllReport {
+    ///one line telling how it went, like "saved 3, failed 1: /etc/hosts permission denied"
+    pub fn summary(&self) -> String {
+        if self.saved.is_empty() && self.failed.is_empty() && self.confirming.is_empty() {
+            return "nothing to save".to_string();
+        }
+        let mut summary = format!("saved {}", self.saved.len());
+        if !self.failed.is_empty() {
+            let reasons: Vec<String> = self
+                .failed
+                .iter()
+                .map(|(path, e)| format!("{} {}", path, short_reason(e)))
+                .collect();
+            summary.push_str(&format!(
+                ", failed {}: {}",
+                self.failed.len(),
+                reasons.join(", ")
+            ));
+        }
+        if !self.confirming.is_empty() {
+            summary.push_str(&format!(", {} to confirm", self.confirming.len()));
+        }
+        summary
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum PendingState {
     None,
     Saving(String),
+    //waiting for a file name for an untitled buffer
+    NamingFile,
+    //waiting for the user to decide about unsaved changes before quitting
+    QuittingUnsaved,
     Quitting,
+    //showing the recent files picker, with the full paths of its entries
+    PickingRecent(Vec<String>),
+    //showing the :todos list, with the positions of its entries
+    PickingAnnotation(Vec<CursorPosition>),
+    //waiting to overwrite a file with the selection, cutting it afterwards if delete is set
+    Extracting { path: String, delete: bool },
+    //waiting to create the missing directory of a file before saving to it
+    CreatingDirectory(String),
+    //waiting to create the missing directory of a file before extracting the selection to it
+    CreatingExtractDirectory { path: String, delete: bool },
+    //showing the :unicode name matches, with their characters
+    PickingUnicode(Vec<char>),
+    //showing the :undolist history, with the undo state each entry leads to
+    PickingUndoState(Vec<usize>),
+    //waiting to open a file another running instance has locked, anyway or read-only
+    OpeningLocked { path: String, owner: LockOwner },
+    //showing the :bookmarks list, with the bookmarked lines
+    PickingBookmark(Vec<usize>),
+    //showing the ctrl+p file finder, its files are kept by the app's file finder
+    FindingFile,
+    //waiting to retry a save the user lacked the rights for with sudo
+    SavingElevated(String),
+    //confirmed elevated save, written by the run loop once it handed over the terminal
+    WritingElevated(String),
+    //waiting to overwrite a file changed on disk since its buffer read it, for :wa
+    OverwritingChanged(String),
+    //showing the ctrl+b buffer switcher, the open buffer first and then those set aside
+    PickingBuffer,
+    //waiting to drop the buffer set aside at the index, which has unsaved changes
+    ClosingBuffer(usize),
+}
+
+impl PendingState {
+    ///short name shown in the info bar while the state waits
+    pub fn label(&self) -> &'static str {
+        match self {
+            PendingState::None => "none",
+            PendingState::Saving(_) | PendingState::OverwritingChanged(_) => "save",
+            PendingState::NamingFile => "name",
+            PendingState::QuittingUnsaved => "unsaved",
+            PendingState::Quitting => "quit",
+            PendingState::PickingRecent(_) => "recent",
+            PendingState::PickingAnnotation(_) => "todos",
+            PendingState::Extracting { .. } => "extract",
+            PendingState::CreatingDirectory(_) | PendingState::CreatingExtractDirectory { .. } => {
+                "mkdir"
+            }
+            PendingState::PickingUnicode(_) => "unicode",
+            PendingState::PickingUndoState(_) => "undolist",
+            PendingState::OpeningLocked { .. } => "locked",
+            PendingState::PickingBookmark(_) => "bookmarks",
+            PendingState::FindingFile => "find",
+            PendingState::PickingBuffer => "buffers",
+            PendingState::ClosingBuffer(_) => "close",
+            PendingState::SavingElevated(_) | PendingState::WritingElevated(_) => "sudo",
+        }
+    }
+
+    ///checks if the state is answered through a popup, a queued quit has none of its own
+    fn waits_on_popup(&self) -> bool {
+        !matches!(
+            self,
+            PendingState::None | PendingState::Quitting | PendingState::WritingElevated(_)
+        )
+    }
 }
 
 #[derive(PartialEq, Debug, Default)]
@@ -61,7 +304,32 @@ impl Default for App {
             file_path: None,
             popup: None,
             popup_result: PopupResult::None,
+            last_popup_escape: None,
             pending_states: vec![],
+            macros: MacroRecorder::new(),
+            settings: Settings::new(),
+            modal: ModalState::new(),
+            status_message: None,
+            applied_cursor_mode: None,
+            session: Session::in_memory(),
+            last_session_save: Instant::now(),
+            background_save: None,
+            saved_mark: UndoMark::default(),
+            dirty: false,
+            spell: None,
+            hex_view: None,
+            unicode_input: None,
+            follow: None,
+            lock: None,
+            read_only: false,
+            bell: Bell::new(),
+            file_format: FileFormat::default(),
+            welcome_selected: 0,
+            closed_buffers: vec![],
+            restrict_dir: None,
+            disk_mtime: None,
+            terminal_task: None,
+            file_finder: None,
         }
     }
 }
@@ -73,361 +341,4766 @@ impl App {
     }
 
     /// Run the application's main loop.
-    pub fn run(mut self, mut terminal: DefaultTerminal, file_path: Option<String>) -> Result<()> {
+    pub fn run(
+        mut self,
+        mut terminal: DefaultTerminal,
+        file_path: Option<String>,
+        show_recent: bool,
+    ) -> Result<()> {
         //SETUP
 
-        self.running = true;
-        self.active_area = ActiveArea::Editor;
-        self.file_path = file_path;
-
-        // Read file contents if a file path is provided
-        self.editor.editor_content = if let Some(ref path) = self.file_path {
-            match File::open(path) {
-                Ok(f) => {
-                    let mut buff_read_file = BufReader::new(f);
-                    let mut contents = String::new();
-                    match buff_read_file.read_to_string(&mut contents) {
-                        Ok(_size) => contents.lines().map(String::from).collect(),
-                        Err(err) => {
-                            //if file not found create new
-                            self.running = false;
-                            panic!("Failed to create file '{}': {}", path, err);
-                        }
-                    }
-                }
-                Err(_err) => {
-                    match File::create(path) {
-                        //create file, if ok then return else quit and panic
-                        Ok(_) => {
-                            vec![String::new()] // Return an empty string as the content
-                        }
-                        Err(create_err) => {
-                            self.running = false;
-                            panic!("Failed to create file '{}': {}", path, create_err);
-                        }
-                    }
-                }
-            }
-        } else {
-            vec![String::new()] // Start with an empty editor if no file is provided
-        };
+        self.session = Session::load_default();
+        self.start(file_path, show_recent);
 
         //LOGIC
 
-        // Handle cursor blinking (toggle cursor visibility every 500ms)
-        if self.last_tick.elapsed() >= Duration::from_millis(500) {
-            self.cursor_visible = !self.cursor_visible;
-            self.last_tick = Instant::now();
-        }
+        //a missing signal handler only costs redrawing after being stopped from elsewhere
+        let job_control = JobControl::install().ok();
 
-        while self.running {
-            terminal.draw(|frame| ui(frame, &mut self))?;
-            handle_input(&mut self)?;
+        loop {
+            self.run_until_quit(&mut terminal, job_control.as_ref())?;
+            //quitting waits for a running save, showing what it waits for
+            let Some(save) = &self.background_save else {
+                break;
+            };
+            self.set_status(format!("waiting for {} to be saved…", save.path));
+            terminal.draw(|frame| self.render(frame))?;
+            if self.wait_for_background_save().is_ok() {
+                break;
+            }
+            //the failed save left its error popup and the buffer as it was, nothing is lost
+            self.running = true;
         }
+        self.save_undo_history();
+        self.save_session();
         Ok(())
     }
 
-    ///function to process input action, responsible for calling the related active area,
-    /// with the gotten input action.
-    pub fn process_input_action(&mut self, action: InputAction) {
-        self.check_for_app_related_input_actions(action.clone());
-        match self.active_area {
-            ActiveArea::Editor => {
-                if let Err(e) = self.editor.handle_input_action(action) {
-                    let popup = Box::new(ErrorPopup::new("Editor Error", EditorFailure(e)));
-                    self.open_popup(popup);
-                }
+    ///handles input and ticks, drawing what changed, until the app is quit
+    fn run_until_quit(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        job_control: Option<&JobControl>,
+    ) -> Result<()> {
+        //only draw when input or a timer changed something, a burst of events is drawn once
+        let mut needs_redraw = true;
+        while self.running {
+            if needs_redraw {
+                terminal.draw(|frame| self.render(frame))?;
+                self.update_cursor_style(terminal)?;
             }
-            ActiveArea::CommandLine => {
-                //check for ENTER on commandline, to execute commands,
-                // since entering on a command needs app related function execution,
-                // else handle input on commandline, like editing command line content.
-                if action == InputAction::ENTER {
-                    self.on_command_enter()
-                } else {
-                    self.command_line.handle_input_action(action)
-                }
+            let mut had_input = handle_input(self)?;
+            if self.elevated_save_due() {
+                //the password prompt needs the terminal the way the shell left it
+                self.hand_over_terminal(terminal, Self::run_elevated_save)?;
+                had_input = true;
             }
-            ActiveArea::Popup => {
-                if let Some(popup) = self.popup.as_mut() {
-                    let res = popup.handle_input_action(action);
-                    self.popup_result = res;
+            if let Some(task) = self.terminal_task.take() {
+                self.hand_over_terminal(terminal, |app| app.run_terminal_task(task))?;
+                had_input = true;
+            }
+            //the stop took the terminal as it was, the shell may have changed its mode since
+            if job_control.is_some_and(JobControl::take_continued) {
+                terminal::setup_terminal()?;
+                self.applied_cursor_mode = None;
+                terminal.clear()?;
+                had_input = true;
+            }
+            let ticked = self.on_tick();
+            if self.bell.take_beep() {
+                execute!(terminal.backend_mut(), Print('\x07'))?;
+            }
+            needs_redraw = had_input || ticked;
 
-                    match popup.get_popup_type() {
-                        PopupType::Confirmation => self.handle_confirmation_popup_response(),
-                        PopupType::Error => self.handle_error_popup_response(),
-                        _ => {}
-                    }
-                }
+            if self.last_session_save.elapsed()
+                >= Duration::from_millis(editor_settings::SESSION_SAVE_MS)
+            {
+                self.save_session();
             }
         }
+        Ok(())
     }
 
-    ///function to check for app related input actions,
-    /// i.e. input action that should result in app related functionality,
-    /// like quitting should call method quit in app.rs
-    fn check_for_app_related_input_actions(&mut self, action: InputAction) {
-        match action {
-            //check for active area toggling,
-            //because toggle active area should be handled by the app centrally.
-            InputAction::ToggleActiveArea => self.toggle_active_area(),
-            //check for quitting,
-            //because quitting should be handled by the app centrally
-            InputAction::QUIT => self.quit(),
-            InputAction::NoOp => {}
-            _ => {}
-        }
+    ///runs f with the terminal given back to the shell, then takes it again and clears it,
+    /// so the next draw repaints everything at whatever size the terminal has by then
+    fn hand_over_terminal(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        f: impl FnOnce(&mut Self),
+    ) -> Result<()> {
+        terminal::restore_terminal()?;
+        f(self);
+        terminal::setup_terminal()?;
+        self.applied_cursor_mode = None;
+        terminal.clear()?;
+        Ok(())
     }
 
-    //command line command execution
-    ///handles checking command and executing said command with given args
-    fn on_command_enter(&mut self) {
-        //split commandline input to command and arguments
-        //if successful parse to command and use the executor to execute commands
-        //open popup for error if execution unsuccessful
-        match self.command_line.split_command_bind_and_args() {
-            Ok((bind, args)) => {
-                let command = command::parse_command(bind, args);
-                if let Err(e) = command_executor::execute_command(self, command) {
-                    let popup = Box::new(ErrorPopup::new(
-                        "Command Failed",
-                        AppError::CommandFailure(e),
-                    ));
-                    self.open_popup(popup);
-                }
-            }
-            Err(error) => {
-                let popup = Box::new(ErrorPopup::new(
-                    "Command Parse Failed",
-                    AppError::InternalError(error.to_string()),
-                ));
-                self.open_popup(popup);
+    ///prepares the app for handling events, with file_path opened or the recent files
+    /// picker shown. run does this itself, a program driving the app without a terminal,
+    /// like a test, calls it before feeding events with handle_event
+    pub fn start(&mut self, file_path: Option<String>, show_recent: bool) {
+        self.running = true;
+        self.active_area = ActiveArea::Editor;
+
+        // Read file contents if a file path is provided, a file that can't be read
+        // is reported and the buffer starts untitled, so it can't overwrite the file later
+        self.editor.content_replaced(vec![String::new()]);
+        if let Some(path) = file_path {
+            if let Err(e) = self.open_file(path) {
+                self.open_popup(Box::new(ErrorPopup::new("Failed to open file", e)));
             }
         }
+        if show_recent && self.popup.is_none() {
+            self.show_recent_files();
+        }
     }
 
-    //SCROLL
-    ///moves the scroll offset
-    pub(crate) fn move_scroll_offset(&mut self, offset: i16) {
-        self.editor.move_scroll_offset(offset);
+    ///updates the app with a single terminal event, like a key press or a mouse scroll
+    pub fn handle_event(&mut self, event: Event) {
+        input::handle_event(self, event);
+        //keep the cursor visible while typing
+        self.cursor_visible = true;
+        self.last_tick = Instant::now();
     }
 
-    //PANEL HANDLING
-    ///toggles the active area of the app, between editor and command line
-    pub(crate) fn toggle_active_area(&mut self) {
-        match self.active_area {
-            ActiveArea::Editor => {
-                self.active_area = ActiveArea::CommandLine;
-            }
-            ActiveArea::CommandLine => {
-                self.active_area = ActiveArea::Editor;
-            }
+    ///forgets the changes made so far, the buffer now matches its file
+    fn mark_clean(&mut self) {
+        self.saved_mark = self.text_editor().undo_mark();
+        self.dirty = false;
+    }
 
-            _ => {}
-        }
+    ///draws the app into frame, the terminal cursor included
+    pub fn render(&mut self, frame: &mut Frame) {
+        ui(frame, self);
     }
 
-    ///handles creating popup to confirm if file should be overridden
-    pub fn handle_confirmation_popup_response(&mut self) {
-        if let Some(pending) = self.pending_states.first() {
-            println!("Confirmation Popup response:{:?}", self.pending_states);
-            match (pending, self.popup_result.clone()) {
-                (PendingState::Saving(path), PopupResult::Bool(true)) => {
-                    match self.save_to_path(path.clone()) {
-                        Ok(()) => {
-                            self.pending_states.remove(0);
-                            self.close_popup();
-                        }
-                        Err(e) => {
-                            let popup = Box::new(ErrorPopup::new(
-                                "Failed to save file",
-                                AppError::InternalError(e.to_string()),
-                            ));
-                            self.open_popup(popup);
-                            // Keep the pending state so user can retry
-                        }
-                    }
-                }
-                (PendingState::Quitting, _) => {
-                    self.pending_states.clear();
-                    self.quit()
-                }
-                (_, PopupResult::Bool(false)) => {
-                    self.pending_states.remove(0);
-                    self.close_popup(); // user canceled
+    ///checks if the app is still running, false once it quit
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    //FILES
+    ///loads the file at path into a fresh editor, restoring the cursor position it was left at.
+    /// a file another running instance has locked asks first whether to open it anyway or
+    /// read-only.
+    pub fn open_file(&mut self, path: String) -> Result<(), AppError> {
+        //a running save belongs to the buffer about to be replaced
+        self.wait_for_background_save()?;
+        let (content, format) = load_file(&path)?;
+        //reopening the open file keeps its lock, or stays read-only
+        let reopening = self.lock.as_ref().is_some_and(|lock| lock.is_for(&path))
+            || (self.read_only && self.file_path.as_deref() == Some(path.as_str()));
+        let lock = if reopening {
+            self.lock.take()
+        } else {
+            match lock::acquire(&path) {
+                Ok(lock) => Some(lock),
+                Err(LockError::Held(owner)) => {
+                    self.queue_pending(PendingState::OpeningLocked { path, owner });
+                    return Ok(());
                 }
-                _ => {}
+                //a file that can't be locked anywhere still opens, just unguarded
+                Err(LockError::Io(_)) => None,
             }
+        };
+        let read_only = reopening && self.read_only;
+        self.replace_buffer(path, (content, format), lock, read_only);
+        Ok(())
+    }
 
-            self.popup_result = PopupResult::None;
-
-            // Check again if there's more to do (like Quitting after Saving)
-            if !self.pending_states.is_empty() {
-                self.handle_confirmation_popup_response();
-            }
+    ///opens a file another instance has locked without taking the lock, read-only keeps
+    /// it from being edited
+    fn open_locked_file(&mut self, path: String, read_only: bool) -> Result<(), AppError> {
+        self.wait_for_background_save()?;
+        let loaded = load_file(&path)?;
+        self.replace_buffer(path, loaded, None, read_only);
+        if read_only {
+            self.set_status("opened read-only");
         }
+        Ok(())
     }
 
-    ///handles response from error popup, should only close popup
-    pub fn handle_error_popup_response(&mut self) {
-        if self.popup_result == PopupResult::Affirmed {
-            self.close_popup();
+    ///puts content loaded from path into a fresh editor, remembering what is left behind
+    fn replace_buffer(
+        &mut self,
+        path: String,
+        (content, format): (Vec<String>, FileFormat),
+        lock: Option<FileLock>,
+        read_only: bool,
+    ) {
+        let mut editor = Editor::new();
+        editor.content_replaced(content);
+        self.close_buffer(editor);
+        self.file_format = format;
+        self.mark_clean();
+        self.session.add_recent(&path);
+        self.disk_mtime = file_io::modified_time(&path);
+        self.file_path = Some(path);
+        //the lock of the file left behind is dropped, removing it
+        self.lock = lock;
+        self.read_only = read_only;
+        self.detect_indent();
+        self.warn_long_lines();
+        self.restore_position();
+        self.restore_undo_history();
+        if self.settings.auto_chdir {
+            self.change_dir_to_file();
         }
     }
 
-    ///handles setting popup with defined popup object
-    pub fn open_popup(&mut self, popup: Box<dyn Popup>) {
-        self.popup = Some(popup);
-        self.active_area = ActiveArea::Popup;
-    }
+    ///swaps editor in for the open buffer, which is set aside for :reopen. an untouched
+    /// untitled buffer isn't worth bringing back and is dropped.
+    fn close_buffer(&mut self, mut editor: Editor) {
+        self.follow = None;
+        self.leave_hex_view();
+        self.remember_position();
+        self.save_undo_history();
 
-    pub fn close_popup(&mut self) {
-        self.popup = None;
-        self.active_area = ActiveArea::Editor; // Go back to editor
+        let dirty = self.has_unsaved_changes();
+        let blank = self.file_path.is_none() && !dirty && self.editor.lines() == [""];
+        editor.editor_height = self.editor.editor_height;
+        editor.editor_width = self.editor.editor_width;
+        editor.set_word_chars(self.settings.word_chars.clone());
+        let closed = ClosedBuffer {
+            path: self.file_path.take(),
+            editor: std::mem::replace(&mut self.editor, editor),
+            file_format: self.file_format,
+            saved_mark: self.saved_mark,
+            dirty: self.dirty,
+            read_only: self.read_only,
+            disk_mtime: self.disk_mtime.take(),
+        };
+        if !blank && !closed.editor.editor_content.is_empty() {
+            self.closed_buffers.push(closed);
+            let excess = self
+                .closed_buffers
+                .len()
+                .saturating_sub(editor_settings::CLOSED_BUFFERS_MAX);
+            self.closed_buffers.drain(..excess);
+        }
     }
 
-    //Basic Commands
-
-    /// Set running == false, to quit the application.
-    pub(crate) fn quit(&mut self) {
-        self.running = false;
+    ///brings back the buffer last replaced by opening another file, setting the open one
+    /// aside in its place so reopening again swaps back. unsaved changes stay unsaved.
+    pub fn reopen_buffer(&mut self) -> Result<(), AppError> {
+        match self.closed_buffers.len().checked_sub(1) {
+            Some(last) => self.switch_to_buffer(last),
+            None => Ok(()),
+        }
     }
 
-    ///saves contents to file at path
-    pub fn save_to_path(&mut self, path: String) -> Result<(), AppError> {
-        let new_content = self.editor.editor_content.join("\n");
+    ///brings back the buffer set aside at index in closed_buffers, setting the open one
+    /// aside as the newest in its place
+    pub fn switch_to_buffer(&mut self, index: usize) -> Result<(), AppError> {
+        //a running save belongs to the buffer about to be replaced
+        self.wait_for_background_save()?;
+        if index >= self.closed_buffers.len() {
+            return Ok(());
+        }
+        let closed = self.closed_buffers.remove(index);
+        //the file may have been locked by another instance since, then it can only be read
+        let (lock, locked_by) = match &closed.path {
+            Some(path) if !closed.read_only => match lock::acquire(path) {
+                Ok(lock) => (Some(lock), None),
+                Err(LockError::Held(owner)) => (None, Some(owner.pid)),
+                Err(LockError::Io(_)) => (None, None),
+            },
+            _ => (None, None),
+        };
 
-        let path_ref = Path::new(&path);
-        if let Some(parent) = path_ref.parent() {
-            fs::create_dir_all(parent)?;
+        let name = closed.name();
+        self.close_buffer(closed.editor);
+        self.file_format = closed.file_format;
+        self.saved_mark = closed.saved_mark;
+        self.dirty = closed.dirty;
+        self.disk_mtime = closed.disk_mtime;
+        self.lock = lock;
+        self.read_only = closed.read_only || locked_by.is_some();
+        if let Some(path) = closed.path {
+            self.session.add_recent(&path);
+            self.file_path = Some(path);
+            if self.settings.auto_chdir {
+                self.change_dir_to_file();
+            }
+        }
+        match locked_by {
+            Some(pid) => self.set_status(format!(
+                "reopened {} read-only, locked by pid {}",
+                name, pid
+            )),
+            None => self.set_status(format!("reopened {}", name)),
         }
+        Ok(())
+    }
 
-        let file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(&path)?;
-        let mut writer = BufWriter::new(file);
-        writer.write_all(new_content.as_bytes())?;
-        writer.flush()?;
+    ///opens the buffer switcher, listing the open buffer and then those set aside newest
+    /// first, each with its file name, a [+] if it has unsaved changes and its cursor line.
+    /// the newest set aside is picked, so enter goes back to the buffer used before.
+    pub fn show_buffers(&mut self) {
+        let entry = |number: usize, name: String, unsaved: bool, y: usize| {
+            let marker = if unsaved { " [+]" } else { "" };
+            format!("{} {}{} line {}", number, name, marker, y + 1)
+        };
+        let open_name = self
+            .file_path
+            .as_deref()
+            .map_or_else(|| "untitled".to_string(), file_name);
+        let mut items = vec![entry(
+            1,
+            open_name,
+            self.has_unsaved_changes(),
+            self.editor.cursor.y,
+        )];
+        items.extend(
+            self.closed_buffers
+                .iter()
+                .rev()
+                .enumerate()
+                .map(|(i, closed)| {
+                    entry(
+                        i + 2,
+                        closed.name(),
+                        closed.has_unsaved_changes(),
+                        closed.editor.cursor.y,
+                    )
+                }),
+        );
+        let popup = ListPopup::new("Buffers", items)
+            .with_filter()
+            .with_remove()
+            .with_selected(1);
+        self.open_popup(Box::new(popup));
+        self.pending_states.push(PendingState::PickingBuffer);
+    }
 
-        self.file_path = Some(path); // optionally update file_path
-        Ok(())
+    ///index in closed_buffers of the set aside buffer listed at position, newest first
+    fn listed_buffer_index(&self, position: usize) -> Option<usize> {
+        self.closed_buffers.len().checked_sub(position + 1)
     }
 
-    ///checks if file has changes and returns boolean
-    pub(crate) fn file_has_changes(
-        &self,
-        editor_content: String,
-        file_path: String,
-    ) -> Result<bool> {
-        let file = File::open(file_path)?;
-        let mut buff_read_file = BufReader::new(file);
-        let mut read_file_contents = String::new();
-
-        buff_read_file
-            .read_to_string(&mut read_file_contents)
-            .expect("TODO: panic message");
-        //if has changes, return true else return false
-        if !read_file_contents.eq(&editor_content) {
-            Ok(true)
+    ///closes the buffer at entry i of the switcher, asking first if it has unsaved changes.
+    /// the open buffer is closed by quitting or opening another file instead.
+    fn close_listed_buffer(&mut self, i: usize) {
+        let Some(index) = i.checked_sub(1).and_then(|i| self.listed_buffer_index(i)) else {
+            self.set_status("the open buffer can't be closed from the list");
+            return;
+        };
+        if self.closed_buffers[index].has_unsaved_changes() {
+            self.queue_pending(PendingState::ClosingBuffer(index));
         } else {
-            Ok(false)
+            self.drop_closed_buffer(index);
         }
     }
-}
 
-//████████╗███████╗███████╗████████╗███████╗
-//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
-//   ██║   █████╗  ███████╗   ██║   ███████╗
-//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
-//   ██║   ███████╗███████║   ██║   ███████║
-//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+    ///drops the buffer set aside at index in closed_buffers, its unsaved changes are lost
+    fn drop_closed_buffer(&mut self, index: usize) {
+        if index < self.closed_buffers.len() {
+            let closed = self.closed_buffers.remove(index);
+            info!(buffer = %closed.name(), "set aside buffer closed");
+            self.set_status(format!("closed {}", closed.name()));
+        }
+    }
 
-#[cfg(test)]
-mod unit_app_tests {
-    use super::super::app::*;
+    ///changes the working dir of the process to dir. the open file's path is made absolute
+    /// first, so saving still writes the file it was opened from.
+    pub fn change_dir(&mut self, dir: &Path) -> Result<(), AppError> {
+        //a running save writes to a path that may be relative
+        self.wait_for_background_save()?;
+        if let Some(path) = &self.file_path {
+            self.file_path = Some(absolute_path(path));
+        }
+        if let Some(follow) = &mut self.follow {
+            follow.path = absolute_path(&follow.path);
+        }
+        env::set_current_dir(dir).map_err(|source| AppError::DirectoryChangeFailed {
+            path: dir.display().to_string(),
+            source,
+        })
+    }
 
-    fn create_app() -> App {
-        let app = App::new();
-        app
+    ///changes the working dir to the dir of the open file, for :set autochdir
+    fn change_dir_to_file(&mut self) {
+        let Some(path) = &self.file_path else {
+            return;
+        };
+        let path = absolute_path(path);
+        let Some(dir) = Path::new(&path).parent() else {
+            return;
+        };
+        if let Err(e) = self.change_dir(dir) {
+            self.set_status(e.to_string());
+        }
     }
 
-    #[test]
-    fn test_toggle_to_command_line() {
-        let mut app = create_app();
-        app.active_area = ActiveArea::Editor;
-        app.editor.cursor.x = 5;
-        app.editor.cursor.y = 3;
+    ///opens the file path or url under the cursor, a path opens like :open does and jumps
+    /// to its :line suffix. problems are reported in the command line.
+    pub fn open_under_cursor(&mut self) {
+        //relative paths are relative to the open file, or the working dir for untitled buffers
+        let base_dir = self
+            .file_path
+            .as_deref()
+            .and_then(|path| Path::new(path).parent())
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let line = &self.editor.editor_content[self.editor.cursor.y];
+        match links::target_at(line, self.editor.cursor.x, &base_dir) {
+            None => self.set_status("nothing to open under the cursor"),
+            Some(Target::Url(url)) => match links::open_url(&url) {
+                Ok(()) => self.set_status(format!("opened {}", url)),
+                Err(e) => self.set_status(format!("could not open {}: {}", url, e)),
+            },
+            Some(Target::File { path, line }) => self.open_file_at_line(&path, line),
+        }
+    }
 
-        app.toggle_active_area();
-        assert_eq!(app.active_area, ActiveArea::CommandLine);
-        assert_eq!(app.command_line.cursor.x, 0);
-        assert_eq!(app.command_line.cursor.y, 0);
-        assert_eq!(app.editor.cursor.x, 5);
-        assert_eq!(app.editor.cursor.y, 3);
+    ///opens path, unless it is the open file, and moves the cursor to line, counted from 1
+    fn open_file_at_line(&mut self, path: &Path, line: Option<usize>) {
+        if !path.is_file() {
+            self.set_status(format!("no such file: {}", path.display()));
+            return;
+        }
+        let is_open = self
+            .file_path
+            .as_ref()
+            .is_some_and(|open| fs::canonicalize(open).ok() == fs::canonicalize(path).ok());
+        if !is_open {
+            let args = vec![path.to_string_lossy().into_owned()];
+            if let Err(e) = open_command(self, args, HashSet::new()) {
+                self.set_status(e.to_string());
+                return;
+            }
+        }
+        if let Some(line) = line {
+            let y = line
+                .saturating_sub(1)
+                .min(self.editor.editor_content.len() - 1);
+            let _ = self.editor.set_cursor(CursorPosition::new(0, y));
+        }
     }
 
-    #[test]
-    fn test_toggle_to_editor() {
-        let mut app = create_app();
-        app.active_area = ActiveArea::CommandLine;
-        app.editor.cursor.x = 5;
-        app.editor.cursor.y = 3;
+    ///opens a popup listing the recently used files, files that no longer exist are dimmed
+    pub fn show_recent_files(&mut self) {
+        let paths = self.session.recent_files().to_vec();
+        let home = std::env::var("HOME").ok();
+        let items = paths
+            .iter()
+            .map(|path| shorten_path(path, home.as_deref(), editor_settings::RECENT_PATH_WIDTH))
+            .collect();
+        let dimmed = paths.iter().map(|path| !Path::new(path).exists()).collect();
+        self.open_popup(Box::new(
+            ListPopup::new("Recent files", items).with_dimmed(dimmed),
+        ));
+        self.pending_states.push(PendingState::PickingRecent(paths));
+    }
 
-        app.toggle_active_area();
-        assert_eq!(app.active_area, ActiveArea::Editor);
-        assert_eq!(app.editor.cursor.x, 5);
-        assert_eq!(app.editor.cursor.y, 3);
+    //WELCOME
+    ///checks if the welcome screen is shown instead of the buffer, which it is while the
+    /// buffer is untitled, empty and untouched
+    pub fn shows_welcome(&self) -> bool {
+        self.file_path.is_none()
+            && self.hex_view.is_none()
+            && self.editor.editor_content == [""]
+            && !self.has_unsaved_changes()
     }
-}
-#[cfg(test)]
-mod unit_app_command_tests {
-    use super::super::app::*;
 
-    fn create_app(s: String) -> App {
-        let mut app = App::new();
-        app.command_line.input = s;
-        app
+    ///recent files listed on the welcome screen, most recent first
+    pub fn welcome_recent_files(&self) -> &[String] {
+        let recent = self.session.recent_files();
+        &recent[..recent.len().min(editor_settings::WELCOME_RECENT_MAX)]
     }
 
-    #[test]
-    fn test_valid_command_with_args() {
-        let mut app = create_app(":command arg1 arg2".to_string());
+    ///moves the highlight through the welcome screen's recent files and opens the
+    /// highlighted one on enter. returns true if the action was used up.
+    fn handle_welcome_input_action(&mut self, action: &InputAction) -> bool {
+        if !self.shows_welcome() {
+            return false;
+        }
+        let count = self.welcome_recent_files().len();
+        if count == 0 {
+            return false;
+        }
+        match action {
+            InputAction::MoveCursor(Direction::Up) => {
+                self.welcome_selected = self.welcome_selected.saturating_sub(1);
+            }
+            InputAction::MoveCursor(Direction::Down) => {
+                self.welcome_selected = (self.welcome_selected + 1).min(count - 1);
+            }
+            InputAction::ENTER => {
+                let path =
+                    self.welcome_recent_files()[self.welcome_selected.min(count - 1)].clone();
+                self.open_recent_file(path);
+                //a missing file is dropped from the list, the highlight stays on it
+                self.welcome_selected = self
+                    .welcome_selected
+                    .min(self.welcome_recent_files().len().saturating_sub(1));
+            }
+            _ => return false,
+        }
+        true
+    }
 
-        let result = app.command_line.split_command_bind_and_args();
-        assert!(result.is_ok());
-        let (cmd, args) = result.unwrap();
-        assert_eq!(cmd, "command");
-        assert_eq!(args, vec!["arg1", "arg2"]);
+    ///opens the picked recent file like :open does, a file that no longer exists is
+    /// removed from the list instead
+    fn open_recent_file(&mut self, path: String) {
+        if !Path::new(&path).exists() {
+            self.session.remove_recent(&path);
+            self.set_status(format!("removed missing file {}", path));
+            return;
+        }
+        self.open_with_command(path);
     }
 
-    #[test]
-    fn test_valid_command_no_args() {
-        let mut app = create_app(":hello".to_string());
+    ///opens path like :open does, showing why it couldn't in an error popup
+    fn open_with_command(&mut self, path: String) {
+        if let Err(e) = open_command(self, vec![path], HashSet::new()) {
+            let popup = Box::new(ErrorPopup::new(
+                "Failed to open file",
+                AppError::CommandFailure(e),
+            ));
+            self.open_popup(popup);
+        }
+    }
 
-        let result = app.command_line.split_command_bind_and_args();
-        assert!(result.is_ok());
-        let (cmd, args) = result.unwrap();
-        assert_eq!(cmd, "hello");
-        assert!(args.is_empty());
+    //FILE FINDER
+    ///opens the fuzzy finder over the files under the working dir, listing them as the
+    /// walk finds them
+    pub fn find_file(&mut self) {
+        self.find_file_in(PathBuf::from("."));
     }
 
-    #[test]
-    fn test_missing_command() {
-        let mut app = create_app("not_a_command arg1".to_string());
+    pub(crate) fn find_file_in(&mut self, dir: PathBuf) {
+        self.file_finder = Some(FileFinder::start(dir, editor_settings::FINDER_MAX_FILES));
+        self.open_popup(Box::new(ListPopup::new("Find file", vec![]).with_filter()));
+        self.pending_states.push(PendingState::FindingFile);
+    }
+
+    ///adds the files the finder's walk found since the last poll to its popup, returns
+    /// true if there were any. the walk is dropped once the finder isn't shown anymore.
+    fn poll_file_finder(&mut self) -> bool {
+        let Some(finder) = self.file_finder.as_mut() else {
+            return false;
+        };
+        if self.pending_states.first() != Some(&PendingState::FindingFile) {
+            self.file_finder = None;
+            return false;
+        }
+        let was_done = finder.is_done();
+        let files = finder.poll();
+        let capped = !was_done && finder.is_done() && finder.is_capped();
+        let found = !files.is_empty();
+        if found {
+            if let Some(popup) = self.popup.as_mut() {
+                popup.add_items(files);
+            }
+        }
+        if capped {
+            self.set_status(format!(
+                "listing the first {} files only",
+                editor_settings::FINDER_MAX_FILES
+            ));
+        }
+        found
+    }
+
+    ///stores the cursor position of the open file in the session
+    fn remember_position(&mut self) {
+        let Some(path) = &self.file_path else {
+            return;
+        };
+        if !self.settings.restore_position {
+            return;
+        }
+        let position = FilePosition {
+            x: self.editor.cursor.x,
+            y: self.editor.cursor.y,
+            scroll_offset: self.editor.scroll_offset,
+        };
+        self.session.remember(path, position);
+    }
+
+    ///moves the cursor to the position the open file was left at, clamped to its content
+    ///sets tabwidth and insertspaces to how the open file is indented, judging by its first
+    /// lines. a file without indentation gets the configured ones, :set overrides either.
+    fn detect_indent(&mut self) {
+        let lines = self.editor.editor_content.iter().map(String::as_str);
+        let detected = indent::detect(lines.take(editor_settings::INDENT_SCAN_LINES));
+        match detected {
+            Some(Indent::Tabs) => self.settings.insert_spaces = false,
+            Some(Indent::Spaces(width)) => {
+                self.settings.insert_spaces = true;
+                self.settings.tab_width = width;
+            }
+            None => {
+                self.settings.insert_spaces = editor_settings::INSERT_SPACES;
+                self.settings.tab_width = editor_settings::TAB_WIDTH as usize;
+            }
+        }
+        if let Some(indent) = detected {
+            self.set_status(format!("indent: {}", indent));
+        }
+    }
+
+    ///tells once on load that the file has lines long enough to be rendered in long-line mode
+    fn warn_long_lines(&mut self) {
+        let Some(limit) = self.settings.long_line_limit() else {
+            return;
+        };
+        let longest = self.editor.editor_content.iter().map(String::len).max();
+        if let Some(longest) = longest.filter(|&len| len > limit) {
+            self.set_status(format!(
+                "file contains lines up to {}; long-line mode enabled",
+                format_bytes(longest)
+            ));
+        }
+    }
+
+    fn restore_position(&mut self) {
+        let Some(path) = &self.file_path else {
+            return;
+        };
+        if !self.settings.restore_position {
+            return;
+        }
+        if let Some(position) = self.session.get(path) {
+            self.editor.set_cursor_position(&CursorPosition {
+                x: position.x,
+                y: position.y,
+            });
+            self.editor.scroll_offset = position.scroll_offset.min(self.editor.cursor.y);
+        }
+    }
+
+    ///writes the undo history of the open file when persistentundo is on. a buffer with
+    /// unsaved changes is skipped, its history doesn't lead to the file's content.
+    fn save_undo_history(&self) {
+        let (Some(path), Some(dir)) = (&self.file_path, self.session.dir()) else {
+            return;
+        };
+        if !self.settings.persistent_undo || self.has_unsaved_changes() {
+            return;
+        }
+        let editor = self.text_editor();
+        let (history, applied) = editor.undo_history();
+        //like the session, the history is only a convenience and failures are ignored
+        let _ = undo_file::write(
+            &undo_file::history_path(dir, path),
+            &editor.editor_content,
+            &history,
+            applied,
+            editor_settings::UNDO_FILE_MAX_BYTES,
+        );
+    }
+
+    ///restores the undo history kept for the open file when persistentundo is on, a
+    /// history of other content than the file's is ignored
+    fn restore_undo_history(&mut self) {
+        let (Some(path), Some(dir)) = (&self.file_path, self.session.dir()) else {
+            return;
+        };
+        if !self.settings.persistent_undo {
+            return;
+        }
+        let history_path = undo_file::history_path(dir, path);
+        if let Some((history, applied)) =
+            undo_file::read(&history_path, &self.editor.editor_content)
+        {
+            self.editor.restore_undo_history(history, applied);
+            //the history was kept for this content, its applied state is the file's
+            self.saved_mark = self.editor.undo_mark();
+        }
+    }
+
+    ///writes the session, failures are ignored as positions are only a convenience
+    fn save_session(&mut self) {
+        self.remember_position();
+        let _ = self.session.save();
+        self.last_session_save = Instant::now();
+    }
+
+    ///runs timer driven updates like cursor blinking, returns true if a redraw is needed
+    pub fn on_tick(&mut self) -> bool {
+        let mut changed = false;
+        if self.last_tick.elapsed() >= Duration::from_millis(editor_settings::CURSOR_BLINK_MS) {
+            self.cursor_visible = !self.cursor_visible;
+            self.last_tick = Instant::now();
+            changed = true;
+        }
+        if self.poll_background_save() {
+            changed = true;
+        }
+        if self.poll_follow() {
+            changed = true;
+        }
+        if self.poll_file_finder() {
+            changed = true;
+        }
+        if self.bell.on_tick() {
+            changed = true;
+        }
+        if let Some((_, set_at)) = &self.status_message {
+            if set_at.elapsed() >= Duration::from_millis(editor_settings::STATUS_MESSAGE_MS) {
+                self.status_message = None;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    ///spell checker to use for the open file, None if spell checking is off or the file
+    /// isn't one of the spell checked filetypes
+    pub fn active_spell_checker(&self) -> Option<&SpellChecker> {
+        self.spell
+            .as_ref()
+            .filter(|_| self.hex_view.is_none() && spell::checks_file(self.file_path.as_deref()))
+    }
+
+    ///why the buffer can't be edited right now, None if it can
+    pub(crate) fn read_only_reason(&self) -> Option<&'static str> {
+        if self.hex_view.is_some() {
+            Some("hex view is read-only, :hexview off to edit")
+        } else if self.follow.is_some() {
+            Some("following the file is read-only, :follow off to edit")
+        } else if self.read_only {
+            Some("opened read-only, another instance is editing the file")
+        } else {
+            None
+        }
+    }
+
+    //FOLLOW
+    ///reloads the file at path and follows it from there, appending the lines written
+    /// to it as they come in with the view kept at the end
+    pub fn follow_file(&mut self, path: String) -> Result<(), AppError> {
+        self.open_file(path.clone())?;
+        let follow = Follow::start(&path).map_err(|source| AppError::FileReadFailed {
+            path: path.clone(),
+            source,
+        })?;
+        self.follow = Some(follow);
+        self.move_to_end();
+        Ok(())
+    }
+
+    ///checks if the view follows the end of the file, it pauses once the cursor leaves the
+    /// last line or the view is scrolled away from it
+    pub fn is_follow_pinned(&self) -> bool {
+        self.editor.cursor.y + 1 >= self.editor.editor_content.len()
+            && self.editor.is_cursor_in_view()
+    }
+
+    fn move_to_end(&mut self) {
+        let y = self.editor.editor_content.len().saturating_sub(1);
+        self.editor.set_cursor_position(&CursorPosition::new(0, y));
+        self.editor.scroll_to_cursor();
+    }
+
+    ///appends the lines written to the followed file since the last poll, returns true
+    /// if the buffer changed
+    fn poll_follow(&mut self) -> bool {
+        let pinned = self.is_follow_pinned();
+        let Some(follow) = self.follow.as_mut().filter(|follow| follow.is_due()) else {
+            return false;
+        };
+        let path = follow.path.clone();
+        match follow.poll() {
+            Ok(None) => return false,
+            Ok(Some(Update::Appended {
+                text,
+                continues_line,
+            })) => self.editor.append_from_file(&text, continues_line),
+            //a rotated log starts over
+            Ok(Some(Update::Truncated)) => match self.follow_file(path.clone()) {
+                Ok(()) => self.set_status(format!("{} was truncated, reloaded it", path)),
+                Err(e) => self.set_status(format!("stopped following {}: {}", path, e)),
+            },
+            Err(e) => {
+                self.follow = None;
+                self.set_status(format!("stopped following {}: {}", path, e));
+            }
+        }
+        if pinned && self.follow.is_some() {
+            self.move_to_end();
+        }
+        true
+    }
+
+    //HEX VIEW
+    ///editor holding the text of the buffer, set aside while the hex view is shown
+    pub fn text_editor(&self) -> &Editor {
+        self.hex_view
+            .as_ref()
+            .map_or(&self.editor, |hex_view| &hex_view.text_editor)
+    }
+
+    ///shows a hex dump of the file's bytes on disk in place of its text, read-only
+    pub fn enter_hex_view(&mut self, bytes: Vec<u8>) {
+        if self.hex_view.is_some() {
+            self.leave_hex_view();
+        }
+        let mut hex_view = HexView::new(bytes, Editor::new());
+        hex_view.text_editor = std::mem::replace(&mut self.editor, hex_view.dump_editor());
+        self.hex_view = Some(hex_view);
+    }
+
+    ///puts the text editor back as it was left, does nothing outside the hex view
+    pub fn leave_hex_view(&mut self) {
+        if let Some(hex_view) = self.hex_view.take() {
+            self.editor = hex_view.text_editor;
+        }
+    }
+
+    ///shows a transient message in the command line
+    pub fn set_status(&mut self, message: impl Into<String>) {
+        self.status_message = Some((message.into(), Instant::now()));
+    }
+
+    ///reports how much the clipboard holds after appending to it
+    pub(crate) fn report_clipboard_append(&mut self) {
+        let lines = self.editor.clipboard.copied_text.len();
+        self.set_status(format!("appended (now {} lines in clipboard)", lines));
+    }
+
+    ///returns the status message currently shown, if any
+    pub fn status(&self) -> Option<&str> {
+        self.status_message
+            .as_ref()
+            .map(|(message, _)| message.as_str())
+    }
+
+    ///sets the terminal cursor shape to match the modal editing mode,
+    /// block in normal and visual mode and a bar in insert mode
+    fn update_cursor_style(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        let wanted = self.settings.modal.then_some(self.modal.mode);
+        if wanted == self.applied_cursor_mode {
+            return Ok(());
+        }
+        let style = match wanted {
+            Some(EditorMode::Insert) => SetCursorStyle::SteadyBar,
+            Some(_) => SetCursorStyle::SteadyBlock,
+            None => SetCursorStyle::DefaultUserShape,
+        };
+        execute!(terminal.backend_mut(), style)?;
+        self.applied_cursor_mode = wanted;
+        Ok(())
+    }
+
+    ///function to process input action, responsible for calling the related active area,
+    /// with the gotten input action.
+    pub fn process_input_action(&mut self, action: InputAction) {
+        self.drop_orphaned_pending();
+        if self.active_area == ActiveArea::Editor && self.handle_macro_input_action(&action) {
+            return;
+        }
+        if self.active_area == ActiveArea::Editor && self.handle_mark_input_action(&action) {
+            return;
+        }
+        if self.active_area == ActiveArea::Editor && self.handle_unicode_input_action(&action) {
+            return;
+        }
+        if self.active_area == ActiveArea::Editor && self.handle_line_insert_input_action(&action) {
+            return;
+        }
+        if self.active_area == ActiveArea::Editor && self.handle_welcome_input_action(&action) {
+            return;
+        }
+        //modal keys edit the buffer directly, read-only views are navigated with the arrow keys
+        if self.read_only_reason().is_none() && self.handle_modal_input_action(&action) {
+            return;
+        }
+        self.check_for_app_related_input_actions(action.clone());
+        //saving, quitting and opening can change the active area or the buffer, they must
+        // not reach the new one
+        if matches!(
+            action,
+            InputAction::Save
+                | InputAction::RequestQuit
+                | InputAction::OpenUnderCursor
+                | InputAction::FindFile
+                | InputAction::SwitchBuffer
+                | InputAction::Suspend
+        ) {
+            return;
+        }
+        //the clipboard belongs to the editor, other areas get its text pasted in
+        let action = match action {
+            InputAction::PASTE if self.active_area != ActiveArea::Editor => {
+                InputAction::InsertText(self.editor.clipboard.paste().join("\n"))
+            }
+            action => action,
+        };
+        match self.active_area {
+            ActiveArea::Editor if action.edits_content() && self.read_only_reason().is_some() => {
+                self.set_status(self.read_only_reason().unwrap_or_default());
+            }
+            ActiveArea::Editor => {
+                self.macros.record(&action);
+                let appended = action == InputAction::CopyAppend;
+                let hits_edge = self.hits_buffer_edge(&action);
+                match self.editor.handle_input_action(action) {
+                    Ok(()) if appended => self.report_clipboard_append(),
+                    Ok(()) if hits_edge => self.ring_bell(),
+                    Ok(()) => {}
+                    //nothing to do is no error worth a popup
+                    Err(
+                        e @ (EditorError::NothingToUndo
+                        | EditorError::NothingToRedo
+                        | EditorError::EmptyClipboard
+                        | EditorError::NoBookmarks),
+                    ) => {
+                        self.set_status(e.to_string());
+                        self.ring_bell();
+                    }
+                    Err(e) => {
+                        let popup = Box::new(ErrorPopup::new("Editor Error", EditorFailure(e)));
+                        self.open_popup(popup);
+                    }
+                }
+                self.report_repairs();
+            }
+            ActiveArea::CommandLine => {
+                //check for ENTER on commandline, to execute commands,
+                // since entering on a command needs app related function execution,
+                // else handle input on commandline, like editing command line content.
+                if action == InputAction::ENTER {
+                    self.on_command_enter()
+                } else {
+                    self.command_line.handle_input_action(action)
+                }
+            }
+            ActiveArea::Popup => {
+                if action == InputAction::ToggleActiveArea && self.is_repeated_popup_escape() {
+                    self.force_close_popup();
+                    return;
+                }
+                if let Some(popup) = self.popup.as_mut() {
+                    //esc dismisses every popup through its response handler, so the
+                    // pending state it asks about is cleaned up with it
+                    let res = match action {
+                        InputAction::ToggleActiveArea => popup.dismiss_result(),
+                        action => popup.handle_input_action(action),
+                    };
+                    self.popup_result = res;
+
+                    match popup.get_popup_type() {
+                        PopupType::Confirmation => self.handle_confirmation_popup_response(),
+                        PopupType::Error => self.handle_error_popup_response(),
+                        PopupType::List => self.handle_list_popup_response(),
+                        PopupType::Input => self.handle_input_popup_response(),
+                        PopupType::Text | PopupType::Warning | PopupType::None => {
+                            self.handle_text_popup_response()
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    ///checks if action does nothing where the cursor is, like backspace at the start of the
+    /// buffer or moving right at its end
+    fn hits_buffer_edge(&self, action: &InputAction) -> bool {
+        let editor = &self.editor;
+        if editor.is_text_selected() || editor.is_completing() {
+            return false;
+        }
+        let (x, y) = (editor.cursor.x, editor.cursor.y);
+        let line_len = editor.line(y).map_or(0, |line| line.chars().count());
+        let at_start = x == 0 && y == 0;
+        let at_end = y + 1 >= editor.line_count() && x >= line_len;
+        match action {
+            InputAction::BACKSPACE | InputAction::MoveCursor(Direction::Left) => at_start,
+            InputAction::DELETE | InputAction::MoveCursor(Direction::Right) => at_end,
+            InputAction::MoveCursor(Direction::Up) => y == 0,
+            _ => false,
+        }
+    }
+
+    ///rings the bell the way :set visualbell says
+    pub fn ring_bell(&mut self) {
+        self.bell.ring(self.settings.visual_bell);
+    }
+
+    ///function to check for app related input actions,
+    /// i.e. input action that should result in app related functionality,
+    /// like quitting should call method quit in app.rs
+    fn check_for_app_related_input_actions(&mut self, action: InputAction) {
+        match action {
+            //check for active area toggling,
+            //because toggle active area should be handled by the app centrally.
+            InputAction::ToggleActiveArea => self.toggle_active_area(),
+            //check for quitting,
+            //because quitting should be handled by the app centrally
+            InputAction::QUIT => self.quit(),
+            InputAction::Save => self.save_file(),
+            InputAction::RequestQuit => self.request_quit(),
+            InputAction::Suspend => self.request_suspend(),
+            InputAction::OpenUnderCursor => self.open_under_cursor(),
+            InputAction::FindFile => self.find_file(),
+            InputAction::SwitchBuffer => self.show_buffers(),
+            InputAction::NoOp => {}
+            _ => {}
+        }
+    }
+
+    //MACROS
+    ///handles macro key chords, returns true if the action was consumed by the macro recorder
+    fn handle_macro_input_action(&mut self, action: &InputAction) -> bool {
+        if self.macros.pending_chord.is_some() {
+            if let Some((chord, register)) = self.macros.feed_chord(action) {
+                match chord {
+                    MacroChord::Record => self.macros.start_recording(register),
+                    MacroChord::Play { count } => self.play_macro(register, count.max(1)),
+                }
+            }
+            return true;
+        }
+
+        match action {
+            InputAction::ToggleMacroRecording => {
+                if self.macros.recording_register().is_some() {
+                    self.macros.stop_recording();
+                } else {
+                    self.macros.pending_chord = Some(MacroChord::Record);
+                }
+                true
+            }
+            InputAction::PlayMacro => {
+                self.macros.pending_chord = Some(MacroChord::Play { count: 0 });
+                true
+            }
+            _ => false,
+        }
+    }
+
+    ///replays the macro in register count times, each replay is a single undo step
+    pub fn play_macro(&mut self, register: char, count: usize) {
+        let Some(actions) = self.macros.get(register) else {
+            let popup = Box::new(ErrorPopup::new(
+                "Macro Failed",
+                AppError::EmptyMacroRegister(register),
+            ));
+            self.open_popup(popup);
+            return;
+        };
+
+        for _ in 0..count {
+            let result = self.editor.undo_group(|editor| {
+                actions
+                    .iter()
+                    .try_for_each(|action| editor.handle_input_action(action.clone()))
+            });
+
+            if let Err(e) = result {
+                let popup = Box::new(ErrorPopup::new("Macro Failed", EditorFailure(e)));
+                self.open_popup(popup);
+                return;
+            }
+        }
+    }
+
+    //MARKS
+    ///handles mark key chords, returns true if the action was consumed
+    fn handle_mark_input_action(&mut self, action: &InputAction) -> bool {
+        if self.editor.marks.pending_chord.is_some() {
+            match self.editor.marks.feed_chord(action) {
+                Some((MarkChord::Set, name)) => self.editor.set_mark(name),
+                Some((MarkChord::Jump, name)) => {
+                    if let Err(e) = self.editor.jump_to_mark(name) {
+                        let popup = Box::new(ErrorPopup::new("Mark Failed", EditorFailure(e)));
+                        self.open_popup(popup);
+                    }
+                }
+                None => {}
+            }
+            return true;
+        }
+
+        let chord = match action {
+            InputAction::SetMark => MarkChord::Set,
+            InputAction::JumpToMark => MarkChord::Jump,
+            _ => return false,
+        };
+        self.editor.marks.pending_chord = Some(chord);
+        true
+    }
+
+    //UNICODE INPUT
+    ///collects hex digits after the unicode chord and inserts their character on enter,
+    /// returns true if the action was consumed. esc or any other key cancels it.
+    fn handle_unicode_input_action(&mut self, action: &InputAction) -> bool {
+        let Some(digits) = self.unicode_input.as_mut() else {
+            if *action != InputAction::StartUnicodeInput {
+                return false;
+            }
+            if let Some(reason) = self.read_only_reason() {
+                self.set_status(reason);
+            } else {
+                self.unicode_input = Some(String::new());
+                self.set_status("U+");
+            }
+            return true;
+        };
+        match action {
+            InputAction::WriteChar(c) | InputAction::ModalKey(c)
+                if c.is_ascii_hexdigit() && digits.len() < unicode::MAX_HEX_DIGITS =>
+            {
+                digits.push(c.to_ascii_uppercase());
+                let status = format!("U+{}", digits);
+                self.set_status(status);
+            }
+            //digits past the longest codepoint are ignored
+            InputAction::WriteChar(c) | InputAction::ModalKey(c) if c.is_ascii_hexdigit() => {}
+            InputAction::BACKSPACE => {
+                digits.pop();
+                let status = format!("U+{}", digits);
+                self.set_status(status);
+            }
+            InputAction::ENTER => {
+                let digits = self.unicode_input.take().unwrap_or_default();
+                match unicode::parse_codepoint(&digits) {
+                    Ok(c) => {
+                        if let Err(e) = self.editor.insert_char(c) {
+                            let popup = Box::new(ErrorPopup::new("Editor Error", EditorFailure(e)));
+                            self.open_popup(popup);
+                        }
+                        self.status_message = None;
+                    }
+                    Err(e) => self.set_status(e),
+                }
+            }
+            _ => {
+                self.unicode_input = None;
+                self.set_status("unicode input cancelled");
+            }
+        }
+        true
+    }
+
+    ///handles typing text once for every selected line. chars and backspace edit it on the
+    /// first line, enter and esc copy it to the others, anything else copies it and goes on
+    /// as usual. returns true if the action was consumed.
+    fn handle_line_insert_input_action(&mut self, action: &InputAction) -> bool {
+        if !self.editor.is_line_inserting() {
+            if *action != InputAction::StartLineInsert {
+                return false;
+            }
+            if let Some(reason) = self.read_only_reason() {
+                self.set_status(reason);
+            } else if self.editor.start_line_insert() {
+                //typed chars reach the editor in insert mode
+                if self.settings.modal {
+                    self.modal.mode = EditorMode::Insert;
+                }
+                self.set_status("insert on every line, enter or esc to apply");
+            } else {
+                self.set_status("select several lines to insert on each");
+            }
+            return true;
+        }
+        match action {
+            InputAction::WriteChar(_) => false,
+            InputAction::InsertText(text) if !text.contains('\n') => false,
+            InputAction::BACKSPACE if self.editor.can_erase_line_insert() => false,
+            //the text can't be erased past where it started
+            InputAction::BACKSPACE => true,
+            InputAction::ENTER | InputAction::ToggleActiveArea | InputAction::ClearSelection => {
+                self.finish_line_insert();
+                true
+            }
+            _ => {
+                self.finish_line_insert();
+                false
+            }
+        }
+    }
+
+    ///copies the text typed for every selected line to them
+    fn finish_line_insert(&mut self) {
+        let changed = self.editor.finish_line_insert();
+        let plural = if changed == 1 { "" } else { "s" };
+        self.set_status(format!("inserted on {} line{}", changed, plural));
+    }
+
+    //MODAL EDITING
+    ///handles mode switching keys of modal editing, and resolves normal mode keys
+    /// to editor actions. returns true if the action was consumed.
+    fn handle_modal_input_action(&mut self, action: &InputAction) -> bool {
+        match action {
+            InputAction::ModalKey(c) => {
+                let was_visual = self.modal.mode == EditorMode::Visual;
+                let line_len = self
+                    .editor
+                    .editor_content
+                    .get(self.editor.cursor.y)
+                    .map(|line| line.chars().count())
+                    .unwrap_or(0);
+                let at_line_end = self.editor.cursor.x >= line_len;
+
+                for resolved in self.modal.resolve_key(*c, at_line_end) {
+                    self.process_input_action(resolved);
+                }
+                if was_visual && self.modal.mode != EditorMode::Visual {
+                    self.editor.clear_selection();
+                }
+                true
+            }
+            InputAction::EnterNormalMode => {
+                self.modal.enter_normal_mode();
+                self.editor.clear_selection();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    ///warns about broken editor states that were clamped back into the content, the text
+    /// around them may not be what a save should write
+    fn report_repairs(&mut self) {
+        if let Some(repair) = self.editor.take_repairs().pop() {
+            self.set_status(format!(
+                "repaired editor state {}, check the text before saving",
+                repair
+            ));
+        }
+    }
+
+    //command line command execution
+    ///handles checking command and executing said command with given args
+    fn on_command_enter(&mut self) {
+        //split commandline input to command and arguments
+        //if successful parse to command and use the executor to execute commands
+        //open popup for error if execution unsuccessful
+        match self.command_line.split_command_bind_and_args() {
+            //nothing to run, like a lone colon
+            Ok(None) => {
+                self.command_line.clear();
+                self.active_area = ActiveArea::Editor;
+            }
+            Ok(Some((bind, args))) => {
+                //arguments can hold text of the buffer, only debug logs them
+                info!(command = %bind, args = args.len(), "running command");
+                let command = command::parse_command(bind, args);
+                debug!(?command, "parsed command");
+                //kept to name the command if it leaves the editor in a broken state
+                let checked_command = invariants::ENABLED.then(|| command.clone());
+                let result = command_executor::execute_command(self, command);
+                if let Some(command) = checked_command {
+                    self.editor.check_invariants(&command);
+                    self.report_repairs();
+                }
+                match result {
+                    //back to the editor, so status messages of the command are visible
+                    Ok(()) if self.active_area == ActiveArea::CommandLine => {
+                        self.command_line.clear();
+                        self.active_area = ActiveArea::Editor;
+                    }
+                    Ok(()) => {}
+                    Err(e) => {
+                        let popup = Box::new(ErrorPopup::new(
+                            "Command Failed",
+                            AppError::CommandFailure(e),
+                        ));
+                        self.open_popup(popup);
+                    }
+                }
+            }
+            Err(error) => {
+                let popup = Box::new(ErrorPopup::new(
+                    "Command Parse Failed",
+                    AppError::InternalError(error.to_string()),
+                ));
+                self.open_popup(popup);
+            }
+        }
+    }
+
+    //SCROLL
+    ///moves the view without the cursor, like the mouse wheel does
+    pub(crate) fn move_scroll_offset(&mut self, offset: isize) {
+        self.editor.scroll_view(offset);
+    }
+
+    //PANEL HANDLING
+    ///toggles the active area of the app, between editor and command line
+    pub(crate) fn toggle_active_area(&mut self) {
+        match self.active_area {
+            ActiveArea::Editor => {
+                self.active_area = ActiveArea::CommandLine;
+            }
+            ActiveArea::CommandLine => {
+                self.active_area = ActiveArea::Editor;
+            }
+
+            _ => {}
+        }
+    }
+
+    //PENDING STATES
+    ///queues a state waiting for the user. only the first state is asked about, the
+    /// others wait until every state before them is resolved.
+    pub fn queue_pending(&mut self, state: PendingState) {
+        debug!(
+            state = state.label(),
+            queued = self.pending_states.len(),
+            "pending queued"
+        );
+        self.pending_states.push(state);
+        if self.pending_states.len() == 1 {
+            self.start_next_pending();
+        }
+    }
+
+    ///resolves the first pending state and moves on to the next one
+    fn finish_pending(&mut self) {
+        if !self.pending_states.is_empty() {
+            let finished = self.pending_states.remove(0);
+            debug!(state = finished.label(), "pending finished");
+        }
+        self.popup_result = PopupResult::None;
+        self.close_popup();
+        self.start_next_pending();
+    }
+
+    ///opens the popup asking about the first pending state. a queued quit runs once it is
+    /// first and no save is running, asking about unsaved changes if the saves before it
+    /// didn't happen.
+    fn start_next_pending(&mut self) {
+        let Some(state) = self.pending_states.first() else {
+            return;
+        };
+        debug!(state = state.label(), "pending started");
+        let popup: Box<dyn Popup> = match state {
+            PendingState::Saving(path) | PendingState::Extracting { path, .. } => Box::new(
+                ConfirmationPopup::new(&format!("Confirm Overwrite of {}", path)),
+            ),
+            PendingState::CreatingDirectory(path)
+            | PendingState::CreatingExtractDirectory { path, .. } => {
+                Box::new(ConfirmationPopup::new(&format!(
+                    "Directory '{}' does not exist — create it?",
+                    parent_name(path)
+                )))
+            }
+            PendingState::NamingFile => Box::new(
+                InputPopup::new("Save as").with_suggestion(editor_settings::UNTITLED_FILE_NAME),
+            ),
+            PendingState::QuittingUnsaved => Box::new(ConfirmationPopup::new(
+                "Unsaved changes, save before quitting? (Esc to cancel)",
+            )),
+            PendingState::OverwritingChanged(path) => Box::new(ConfirmationPopup::new(&format!(
+                "{} changed on disk since it was read, overwrite it?",
+                file_name(path)
+            ))),
+            PendingState::ClosingBuffer(index) => Box::new(ConfirmationPopup::new(&format!(
+                "{} has unsaved changes, close it anyway?",
+                self.closed_buffers
+                    .get(*index)
+                    .map_or_else(|| "the buffer".to_string(), ClosedBuffer::name)
+            ))),
+            PendingState::SavingElevated(path) => Box::new(ConfirmationPopup::new(&format!(
+                "Permission denied writing {}, retry with elevated privileges?",
+                file_name(path)
+            ))),
+            PendingState::OpeningLocked { path, owner } => Box::new(
+                ConfirmationPopup::new(&format!(
+                    "{} is open in another calliglyph (pid {}, locked {}), Esc to abort",
+                    file_name(path),
+                    owner.pid,
+                    undo_redo::age(owner.held_for())
+                ))
+                .with_labels("Open anyway", "Read-only"),
+            ),
+            //a save still being written decides whether the quit goes on, once it is done
+            PendingState::Quitting if self.is_saving() => return,
+            PendingState::Quitting if self.has_unsaved_changes() => {
+                self.pending_states[0] = PendingState::QuittingUnsaved;
+                return self.start_next_pending();
+            }
+            PendingState::Quitting => {
+                self.pending_states.clear();
+                self.quit();
+                return;
+            }
+            //pickers open their own popup, none waits for an answer before them
+            _ => return,
+        };
+        self.open_popup(popup);
+    }
+
+    ///drops pending states whose popup was closed without an answer, and an answer no popup
+    /// is left to give. left alone they would take the answer meant for a later popup.
+    fn drop_orphaned_pending(&mut self) {
+        if self.popup.is_some() {
+            return;
+        }
+        self.popup_result = PopupResult::None;
+        while self
+            .pending_states
+            .first()
+            .is_some_and(PendingState::waits_on_popup)
+        {
+            let dropped = self.pending_states.remove(0);
+            debug!(state = dropped.label(), "stale pending dropped");
+            self.set_status(format!("dropped stale pending {}", dropped.label()));
+            self.start_next_pending();
+            if self.popup.is_some() {
+                return;
+            }
+        }
+    }
+
+    ///the queued pending states in the order they are asked about, like "pending: save→quit"
+    pub fn pending_summary(&self) -> Option<String> {
+        if self.pending_states.is_empty() {
+            return None;
+        }
+        let labels: Vec<&str> = self
+            .pending_states
+            .iter()
+            .map(PendingState::label)
+            .collect();
+        Some(format!("pending: {}", labels.join("→")))
+    }
+
+    ///drops every pending state without asking about them, for :pending clear
+    pub fn clear_pending_states(&mut self) {
+        debug!(summary = ?self.pending_summary(), "pending cleared");
+        self.pending_states.clear();
+        self.popup_result = PopupResult::None;
+    }
+
+    ///handles the answer to the confirmation popup of the first pending state
+    pub fn handle_confirmation_popup_response(&mut self) {
+        if self.popup_result == PopupResult::Cancelled {
+            self.cancel_pending_states();
+            return;
+        }
+        let Some(state) = self.pending_states.first().cloned() else {
+            return;
+        };
+        //a queued quit has no question of its own, it goes on once it is first
+        if state == PendingState::Quitting {
+            self.popup_result = PopupResult::None;
+            self.start_next_pending();
+            return;
+        }
+        let PopupResult::Bool(confirmed) = self.popup_result.clone() else {
+            return;
+        };
+        match (state, confirmed) {
+            (PendingState::Saving(path), true) => match self.save(path.clone()) {
+                Err(e) if self.can_save_elevated(&e) => {
+                    self.pending_states.remove(0);
+                    self.offer_elevated_save(path);
+                }
+                result => self.finish_pending_with(result, "Failed to save file"),
+            },
+            (PendingState::SavingElevated(path), true) => {
+                //written from the run loop, which can hand the terminal to the password prompt
+                self.pending_states[0] = PendingState::WritingElevated(path);
+                self.popup_result = PopupResult::None;
+                self.close_popup();
+            }
+            (PendingState::OverwritingChanged(path), true) => {
+                let result = self.save_buffer(&path);
+                match &result {
+                    Ok(()) => self.set_status(format!("saved {}", path)),
+                    //:xa only quits once every save succeeded or was skipped
+                    Err(_) => self
+                        .pending_states
+                        .retain(|state| *state != PendingState::Quitting),
+                }
+                self.finish_pending_with(result, "Failed to save file");
+            }
+            (PendingState::OverwritingChanged(path), false) => {
+                self.set_status(format!("skipped {}", path));
+                self.finish_pending();
+            }
+            (PendingState::SavingElevated(path), false) => {
+                self.set_status(format!("not saved, no permission to write {}", path));
+                self.finish_pending();
+            }
+            (PendingState::Extracting { path, delete }, true) => {
+                let result = self.extract_selection(path, delete);
+                self.finish_pending_with(result, "Failed to extract");
+            }
+            (PendingState::CreatingDirectory(path), true) => {
+                let result =
+                    file_io::create_parent_dirs(&path).and_then(|()| self.save(path.clone()));
+                if result.is_ok() && !self.is_saving() {
+                    self.set_status(format!("saved {}", path));
+                }
+                self.finish_pending_with(result, "Failed to save file");
+            }
+            (PendingState::CreatingDirectory(path), false) => {
+                self.set_status(format!(
+                    "not saved, directory '{}' was not created",
+                    parent_name(&path)
+                ));
+                self.finish_pending();
+            }
+            (PendingState::CreatingExtractDirectory { path, delete }, true) => {
+                let result = file_io::create_parent_dirs(&path)
+                    .and_then(|()| self.extract_selection(path, delete));
+                self.finish_pending_with(result, "Failed to extract");
+            }
+            (PendingState::CreatingExtractDirectory { path, .. }, false) => {
+                self.set_status(format!(
+                    "not extracted, directory '{}' was not created",
+                    parent_name(&path)
+                ));
+                self.finish_pending();
+            }
+            (PendingState::ClosingBuffer(index), true) => {
+                self.drop_closed_buffer(index);
+                self.finish_pending();
+            }
+            (PendingState::OpeningLocked { path, .. }, open_anyway) => {
+                let result = self.open_locked_file(path, !open_anyway);
+                self.finish_pending_with(result, "Failed to open file");
+            }
+            (PendingState::QuittingUnsaved, true) => {
+                //the save runs first, the quit after it asks again if it didn't happen
+                self.pending_states[0] = PendingState::Quitting;
+                match self.file_path.clone() {
+                    Some(path) => {
+                        self.popup_result = PopupResult::None;
+                        self.close_popup();
+                        if self.save_to_path_with_status(path) {
+                            self.start_next_pending();
+                        }
+                    }
+                    None => {
+                        self.pending_states.insert(0, PendingState::NamingFile);
+                        self.popup_result = PopupResult::None;
+                        self.close_popup();
+                        self.start_next_pending();
+                    }
+                }
+            }
+            (PendingState::QuittingUnsaved, false) => {
+                self.pending_states.clear();
+                self.quit();
+            }
+            //a rejected save or extract only drops itself, a quit after it still runs
+            _ => self.finish_pending(),
+        }
+    }
+
+    ///resolves the first pending state once it was carried out. a failure is shown in an
+    /// error popup and the states after it wait until that is closed.
+    fn finish_pending_with(&mut self, result: Result<(), AppError>, title: &str) {
+        match result {
+            Ok(()) => self.finish_pending(),
+            Err(e) => {
+                self.pending_states.remove(0);
+                self.popup_result = PopupResult::None;
+                self.open_popup(Box::new(ErrorPopup::new(title, e)));
+            }
+        }
+    }
+
+    ///handles response from input popup asking for a file name, saves to the given name
+    pub fn handle_input_popup_response(&mut self) {
+        match self.popup_result.clone() {
+            PopupResult::Cancelled => self.cancel_pending_states(),
+            PopupResult::String(path) => {
+                self.popup_result = PopupResult::None;
+                self.close_popup();
+                if self.pending_states.first() == Some(&PendingState::NamingFile) {
+                    self.pending_states.remove(0);
+                }
+                //a name that is taken asks before overwriting, like :w does
+                if self.file_has_changes(&path).unwrap_or(false) {
+                    self.pending_states.insert(0, PendingState::Saving(path));
+                    self.start_next_pending();
+                    return;
+                }
+                if self.save_to_path_with_status(path) {
+                    self.start_next_pending();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    ///drops every pending state, closes the popup and reports the cancel
+    fn cancel_pending_states(&mut self) {
+        debug!(summary = ?self.pending_summary(), "pending cancelled");
+        self.pending_states.clear();
+        self.popup_result = PopupResult::None;
+        self.close_popup();
+        self.set_status("cancelled");
+    }
+
+    ///handles response from error popup, closing it lets the pending states go on
+    pub fn handle_error_popup_response(&mut self) {
+        if self.popup_result == PopupResult::Affirmed {
+            self.popup_result = PopupResult::None;
+            self.close_popup();
+            self.start_next_pending();
+        }
+    }
+
+    ///handles response from read only popups, closing them
+    pub fn handle_text_popup_response(&mut self) {
+        if matches!(
+            self.popup_result,
+            PopupResult::Affirmed | PopupResult::Cancelled
+        ) {
+            self.popup_result = PopupResult::None;
+            self.close_popup();
+        }
+    }
+
+    ///handles response from list popup, closes popup once an entry is chosen
+    pub fn handle_list_popup_response(&mut self) {
+        if self.popup_result == PopupResult::None {
+            return;
+        }
+        let result = std::mem::replace(&mut self.popup_result, PopupResult::None);
+        self.close_popup();
+
+        if !matches!(
+            self.pending_states.first(),
+            Some(
+                PendingState::PickingRecent(_)
+                    | PendingState::PickingAnnotation(_)
+                    | PendingState::PickingUnicode(_)
+                    | PendingState::PickingUndoState(_)
+                    | PendingState::PickingBookmark(_)
+                    | PendingState::FindingFile
+                    | PendingState::PickingBuffer
+            )
+        ) {
+            return;
+        }
+        let picked = self.pending_states.remove(0);
+        let finder = self.file_finder.take();
+        if let (PendingState::PickingBuffer, PopupResult::Removed(i)) = (&picked, &result) {
+            self.close_listed_buffer(*i);
+            return;
+        }
+        let PopupResult::Index(i) = result else {
+            return;
+        };
+        match picked {
+            PendingState::PickingRecent(paths) => {
+                if let Some(path) = paths.get(i) {
+                    self.open_recent_file(path.clone());
+                }
+            }
+            PendingState::PickingAnnotation(positions) => {
+                if let Some(&pos) = positions.get(i) {
+                    //positions were taken when the list opened, the content can't change since
+                    let _ = self.editor.set_cursor(pos);
+                }
+            }
+            PendingState::PickingBookmark(lines) => {
+                if let Some(&y) = lines.get(i) {
+                    let _ = self.editor.set_cursor(CursorPosition::new(0, y));
+                }
+            }
+            PendingState::PickingUnicode(chars) => {
+                if let Some(&c) = chars.get(i) {
+                    let _ = self.editor.insert_char(c);
+                }
+            }
+            PendingState::FindingFile => {
+                let path = finder.and_then(|finder| {
+                    let file = finder.files.get(i)?;
+                    Some(finder.path(file))
+                });
+                if let Some(path) = path {
+                    self.open_with_command(path);
+                }
+            }
+            PendingState::PickingBuffer => {
+                //the first entry is the open buffer, the others were set aside newest first
+                if let Some(index) = i.checked_sub(1).and_then(|i| self.listed_buffer_index(i)) {
+                    if let Err(e) = self.switch_to_buffer(index) {
+                        self.open_popup(Box::new(ErrorPopup::new("Failed to switch buffer", e)));
+                    }
+                }
+            }
+            PendingState::PickingUndoState(states) => {
+                if let Some(&state) = states.get(i) {
+                    //the history can't change while the list is open
+                    let _ = self.editor.revert_to(state);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    ///handles setting popup with defined popup object
+    pub fn open_popup(&mut self, popup: Box<dyn Popup>) {
+        debug!(popup = ?popup.get_popup_type(), "popup opened");
+        self.popup = Some(popup);
+        self.last_popup_escape = None;
+        self.active_area = ActiveArea::Popup;
+    }
+
+    ///checks if esc was already pressed on the open popup a moment ago, and remembers this
+    /// press for the next one. a held esc repeats quickly enough to count as well.
+    fn is_repeated_popup_escape(&mut self) -> bool {
+        let now = Instant::now();
+        let window = Duration::from_millis(editor_settings::POPUP_FORCE_CLOSE_MS);
+        let repeated = self
+            .last_popup_escape
+            .is_some_and(|pressed| now.duration_since(pressed) < window);
+        self.last_popup_escape = Some(now);
+        repeated
+    }
+
+    ///closes the popup without asking it, for a popup that doesn't react to keys anymore.
+    /// the first pending state is dropped as cancelled, the states queued after it go on.
+    fn force_close_popup(&mut self) {
+        warn!(
+            popup = ?self.popup.as_ref().map(|popup| popup.get_popup_type()),
+            pending = ?self.pending_summary(),
+            "popup force closed with a repeated esc"
+        );
+        if !self.pending_states.is_empty() {
+            self.pending_states.remove(0);
+        }
+        self.close_popup();
+        self.popup_result = PopupResult::Cancelled;
+        self.set_status("popup force closed");
+        self.start_next_pending();
+    }
+
+    pub fn close_popup(&mut self) {
+        if let Some(popup) = self.popup.take() {
+            debug!(popup = ?popup.get_popup_type(), "popup closed");
+        }
+        self.active_area = ActiveArea::Editor; // Go back to editor
+    }
+
+    //Basic Commands
+
+    /// Set running == false, to quit the application.
+    pub(crate) fn quit(&mut self) {
+        self.running = false;
+    }
+
+    ///saves to the current file, asks for a file name first if the buffer is untitled
+    pub fn save_file(&mut self) {
+        match self.file_path.clone() {
+            Some(path) => {
+                self.save_to_path_with_status(path);
+            }
+            None => self.queue_pending(PendingState::NamingFile),
+        }
+    }
+
+    ///makes saves and extracts refuse to write anywhere outside dir, which has to exist.
+    /// there is no way to lift it while running, not even --force.
+    pub fn restrict_writes_to(&mut self, dir: &Path) -> io::Result<()> {
+        self.restrict_dir = Some(fs::canonicalize(dir)?);
+        Ok(())
+    }
+
+    ///checks path may be written, which it always may unless writes are restricted to a
+    /// dir. the path is resolved first, so neither .. nor a symlink can lead out of it.
+    pub(crate) fn check_write_allowed(&self, path: &str) -> Result<(), AppError> {
+        let Some(dir) = &self.restrict_dir else {
+            return Ok(());
+        };
+        let resolved =
+            file_io::resolve_path(Path::new(path)).map_err(|source| AppError::FileWriteFailed {
+                path: path.to_string(),
+                source,
+            })?;
+        if !resolved.starts_with(dir) {
+            return Err(AppError::OutsideRestrictedDir {
+                path: resolved,
+                dir: dir.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    ///saves to path and reports the outcome, returns true if the save succeeded or started
+    /// in the background. a failure is shown in an error popup. a missing directory is
+    /// asked about first, before anything else pending.
+    fn save_to_path_with_status(&mut self, path: String) -> bool {
+        //refused before asking to create a dir for it
+        if let Err(e) = self.check_write_allowed(&path) {
+            self.open_popup(Box::new(ErrorPopup::new("Failed to save file", e)));
+            return false;
+        }
+        if file_io::missing_parent(&path).is_some() {
+            self.pending_states
+                .insert(0, PendingState::CreatingDirectory(path));
+            self.start_next_pending();
+            return false;
+        }
+        match self.save(path.clone()) {
+            Ok(()) => {
+                if !self.is_saving() {
+                    self.set_status(format!("saved {}", path));
+                }
+                true
+            }
+            Err(e) if self.can_save_elevated(&e) => {
+                self.offer_elevated_save(path);
+                false
+            }
+            Err(e) => {
+                self.open_popup(Box::new(ErrorPopup::new("Failed to save file", e)));
+                false
+            }
+        }
+    }
+
+    ///checks if the failed save e can be retried with sudo, which needs unix, the
+    /// elevatedsave setting and a failure that is only about permissions
+    pub(crate) fn can_save_elevated(&self, e: &AppError) -> bool {
+        cfg!(unix) && self.settings.elevated_save && elevated_save::is_permission_denied(e)
+    }
+
+    ///asks whether to retry the save to path with sudo, before anything else pending
+    fn offer_elevated_save(&mut self, path: String) {
+        info!(path = %path, "save offers elevated privileges");
+        self.popup_result = PopupResult::None;
+        self.close_popup();
+        self.pending_states
+            .insert(0, PendingState::SavingElevated(path));
+        self.start_next_pending();
+    }
+
+    ///checks if a confirmed elevated save waits for the run loop to write it
+    pub fn elevated_save_due(&self) -> bool {
+        matches!(
+            self.pending_states.first(),
+            Some(PendingState::WritingElevated(_))
+        )
+    }
+
+    ///writes the buffer with the first of sudo and pkexec found, for the confirmed elevated
+    /// save first in the pending states. it may prompt for a password on the terminal, the
+    /// caller hands it over first.
+    pub fn run_elevated_save(&mut self) {
+        let result = match elevated_save::find_program() {
+            Some(program) => self.write_elevated(program),
+            None => Err(AppError::ElevatedWriteFailed {
+                path: self.file_path.clone().unwrap_or_default(),
+                program: elevated_save::PROGRAMS.join(" or "),
+                reason: "not found in PATH".to_string(),
+            }),
+        };
+        self.finish_pending_with(result, "Failed to save file with elevated privileges");
+    }
+
+    ///writes the buffer with program for the first pending elevated save
+    fn write_elevated(&mut self, program: &str) -> Result<(), AppError> {
+        let Some(PendingState::WritingElevated(path)) = self.pending_states.first().cloned() else {
+            return Err(AppError::InternalError(
+                "no elevated save is pending".to_string(),
+            ));
+        };
+        self.check_write_allowed(&path)?;
+        info!(path = %path, program, "saving with elevated privileges");
+        elevated_save::write_with(
+            program,
+            &path,
+            &self.text_editor().editor_content,
+            self.file_format,
+        )?;
+        self.set_status(format!("saved {} with {}", path, program));
+        self.saved_to(path);
+        Ok(())
+    }
+
+    ///leaves task for the run loop, which hands over the terminal for it
+    pub fn request_terminal(&mut self, task: TerminalTask) {
+        self.terminal_task = Some(task);
+    }
+
+    ///takes the task asked for, for a caller that can't hand over the terminal
+    pub fn take_terminal_task(&mut self) -> Option<TerminalTask> {
+        self.terminal_task.take()
+    }
+
+    ///does task, the caller hands over the terminal first. scroll, cursor and popups stay
+    /// as they were.
+    pub fn run_terminal_task(&mut self, task: TerminalTask) {
+        match task {
+            TerminalTask::View(command) => {
+                info!(command = %command.join(" "), "piping buffer to viewer");
+                if let Err(e) = viewer::pipe_to(&command, &self.text_editor().editor_content) {
+                    self.open_popup(Box::new(ErrorPopup::new("Failed to view buffer", e)));
+                }
+            }
+            TerminalTask::Suspend => {
+                info!("suspending to the shell");
+                if let Err(e) = job_control::suspend() {
+                    self.set_status(format!("can't suspend: {}", e));
+                }
+            }
+        }
+    }
+
+    ///stops the editor until the shell continues it, where the shell has job control
+    pub fn request_suspend(&mut self) {
+        if job_control::can_suspend() {
+            self.request_terminal(TerminalTask::Suspend);
+        } else {
+            self.set_status("suspending needs a unix shell");
+        }
+    }
+
+    ///saves the buffer if :set autosave_on_focus_lost is on, once the terminal lost focus
+    pub(crate) fn on_focus_lost(&mut self) {
+        if self.settings.autosave_on_focus_lost {
+            self.autosave();
+        }
+    }
+
+    ///saves a named buffer with unsaved changes without asking anything. the outcome is
+    /// a status message, a buffer that can't be saved this way is left for the user.
+    pub fn autosave(&mut self) {
+        let Some(path) = self.file_path.clone() else {
+            return;
+        };
+        if !self.has_unsaved_changes()
+            || self.read_only_reason().is_some()
+            || self.is_saving()
+            //creating the dir would need asking first
+            || file_io::missing_parent(&path).is_some()
+        {
+            return;
+        }
+        //written right away, a background save could end in an error popup
+        match self.save_to_path(path.clone()) {
+            Ok(()) => self.set_status(format!("autosaved {}", path)),
+            Err(e) => self.set_status(format!("autosave failed: {}", e)),
+        }
+    }
+
+    ///saves every named buffer with unsaved changes, the open one and those set aside. a
+    /// file changed on disk since it was read is only overwritten once confirmed, the
+    /// questions are queued one after another. failures are shown in one error popup.
+    pub fn save_all(&mut self) -> SaveAllReport {
+        let mut paths: Vec<String> = vec![];
+        if self.has_unsaved_changes() && self.read_only_reason().is_none() {
+            paths.extend(self.file_path.clone());
+        }
+        //most recently set aside first, like :reopen brings them back
+        for closed in self.closed_buffers.iter().rev() {
+            if closed.has_unsaved_changes() && !closed.read_only {
+                paths.extend(closed.path.clone());
+            }
+        }
+        //a file open in several buffers is saved from the newest one only
+        let mut seen = HashSet::new();
+        paths.retain(|path| seen.insert(path.clone()));
+
+        let mut report = SaveAllReport::default();
+        for path in paths {
+            if self.changed_on_disk(&path) {
+                report.confirming.push(path);
+                continue;
+            }
+            match self.save_buffer(&path) {
+                Ok(()) => report.saved.push(path),
+                Err(e) => report.failed.push((path, e)),
+            }
+        }
+        info!(
+            saved = report.saved.len(),
+            failed = report.failed.len(),
+            confirming = report.confirming.len(),
+            "saved all"
+        );
+
+        let idle = self.pending_states.is_empty();
+        self.pending_states.extend(
+            report
+                .confirming
+                .iter()
+                .cloned()
+                .map(PendingState::OverwritingChanged),
+        );
+        //the questions wait until the failures were seen
+        if !report.failed.is_empty() {
+            let errors = report.failed.iter().map(|(_, e)| e.to_string()).collect();
+            let title = format!("Failed to save {} files", report.failed.len());
+            self.open_popup(Box::new(ErrorPopup::new(
+                &title,
+                AppError::SavesFailed(errors),
+            )));
+        } else if idle {
+            self.start_next_pending();
+        }
+        self.set_status(report.summary());
+        report
+    }
+
+    ///writes the buffer of the file at path, the open one or one set aside, right away
+    fn save_buffer(&mut self, path: &str) -> Result<(), AppError> {
+        self.check_write_allowed(path)?;
+        if self.file_path.as_deref() == Some(path) {
+            if self.is_saving() {
+                return Err(AppError::SaveInProgress(path.to_string()));
+            }
+            return self.save_to_path(path.to_string());
+        }
+        match self
+            .closed_buffers
+            .iter_mut()
+            .rev()
+            .find(|closed| closed.path.as_deref() == Some(path))
+        {
+            Some(closed) => closed.save(),
+            None => Err(AppError::InternalError(format!(
+                "no buffer is open for {}",
+                path
+            ))),
+        }
+    }
+
+    ///checks if the file at path was written by something else since its buffer last read
+    /// or wrote it. a time that can't be told isn't a change.
+    fn changed_on_disk(&self, path: &str) -> bool {
+        let known = if self.file_path.as_deref() == Some(path) {
+            self.disk_mtime
+        } else {
+            self.closed_buffers
+                .iter()
+                .rev()
+                .find(|closed| closed.path.as_deref() == Some(path))
+                .and_then(|closed| closed.disk_mtime)
+        };
+        match (known, file_io::modified_time(path)) {
+            (Some(known), Some(current)) => current != known,
+            _ => false,
+        }
+    }
+
+    ///quits, asking what to do first if the buffer has unsaved changes
+    pub fn request_quit(&mut self) {
+        self.queue_pending(PendingState::Quitting);
+    }
+
+    ///checks if the buffer was edited since it was opened or last saved, undoing the edits
+    /// leaves it unchanged again
+    pub(crate) fn has_unsaved_changes(&self) -> bool {
+        self.dirty || self.text_editor().is_modified_since(self.saved_mark)
+    }
+
+    ///saves the buffer to path, buffers above the backgroundsave size are written on a
+    /// separate thread and reported once done
+    pub fn save(&mut self, path: String) -> Result<(), AppError> {
+        if let Some(save) = &self.background_save {
+            return Err(AppError::SaveInProgress(save.path.clone()));
+        }
+        self.check_write_allowed(&path)?;
+        let len = file_io::content_len(&self.text_editor().editor_content, self.file_format);
+        let background = len > self.settings.background_save_bytes;
+        info!(path = %path, bytes = len, background, "saving");
+        if !background {
+            return self.save_to_path(path);
+        }
+        self.set_status(format!("saving {}…", path));
+        let snapshot = self.text_editor().editor_content.to_vec();
+        self.background_save = Some(BackgroundSave::start(path, snapshot, self.file_format));
+        //the file gets exactly what the buffer holds now, edits from here on dirty it again
+        self.mark_clean();
+        Ok(())
+    }
+
+    ///checks if a save is running in the background
+    pub fn is_saving(&self) -> bool {
+        self.background_save.is_some()
+    }
+
+    ///reports a finished background save, returns true if one finished
+    fn poll_background_save(&mut self) -> bool {
+        let Some(result) = self.background_save.as_ref().and_then(BackgroundSave::poll) else {
+            return false;
+        };
+        let path = self.background_save.take().map(|save| save.path);
+        self.finish_background_save(path.unwrap_or_default(), result);
+        true
+    }
+
+    ///blocks until the background save is done, used before exiting
+    pub(crate) fn wait_for_background_save(&mut self) -> Result<(), AppError> {
+        let Some(save) = self.background_save.take() else {
+            return Ok(());
+        };
+        let result = save.wait();
+        let failed = result.as_ref().err().map(|e| e.to_string());
+        self.finish_background_save(save.path, result);
+        match failed {
+            Some(e) => Err(AppError::InternalError(e)),
+            None => Ok(()),
+        }
+    }
+
+    ///takes the outcome of a background save. a quit waiting for it goes on once it
+    /// succeeded, a failed one keeps the app running with the buffer unsaved.
+    fn finish_background_save(&mut self, path: String, result: Result<(), AppError>) {
+        let quit_waiting = self.pending_states.first() == Some(&PendingState::Quitting);
+        match result {
+            Ok(()) => {
+                self.session.add_recent(&path);
+                self.set_status(format!("saved {}", path));
+                self.disk_mtime = file_io::modified_time(&path);
+                self.file_path = Some(path);
+                self.save_undo_history();
+                if quit_waiting {
+                    self.start_next_pending();
+                }
+            }
+            //asked about before the quit, which asks again about the unsaved buffer if declined
+            Err(e) if self.can_save_elevated(&e) && quit_waiting => {
+                self.dirty = true;
+                self.offer_elevated_save(path);
+            }
+            Err(e) if self.can_save_elevated(&e) => {
+                self.dirty = true;
+                self.queue_pending(PendingState::SavingElevated(path));
+            }
+            Err(e) => {
+                self.dirty = true;
+                if quit_waiting {
+                    self.pending_states.remove(0);
+                }
+                self.open_popup(Box::new(ErrorPopup::new("Failed to save file", e)));
+            }
+        }
+    }
+
+    ///writes the selection to the file at path, then cuts it from the buffer if delete is
+    /// set, which leaves it in the clipboard too. a failed write leaves the buffer as it was.
+    pub fn extract_selection(&mut self, path: String, delete: bool) -> Result<(), AppError> {
+        self.check_write_allowed(&path)?;
+        let lines = self.editor.copy_selected_text().map_err(EditorFailure)?;
+        file_io::write_file(&path, &lines, FileFormat::default())?;
+        if delete {
+            self.editor.cut().map_err(EditorFailure)?;
+            self.set_status(format!("moved {} lines to {}", lines.len(), path));
+        } else {
+            self.set_status(format!("wrote {} lines to {}", lines.len(), path));
+        }
+        Ok(())
+    }
+
+    ///saves contents to file at path
+    pub fn save_to_path(&mut self, path: String) -> Result<(), AppError> {
+        self.check_write_allowed(&path)?;
+        file_io::write_file(&path, &self.text_editor().editor_content, self.file_format)?;
+        self.saved_to(path);
+        Ok(())
+    }
+
+    ///the buffer now matches the file at path, which becomes its file
+    fn saved_to(&mut self, path: String) {
+        self.mark_clean();
+        self.session.add_recent(&path);
+        self.disk_mtime = file_io::modified_time(&path);
+        self.file_path = Some(path); // optionally update file_path
+        self.save_undo_history();
+    }
+
+    ///checks if the file at file_path differs from the buffer, comparing it in chunks so
+    /// neither side is held in memory as a whole
+    pub(crate) fn file_has_changes(&self, file_path: &str) -> Result<bool, AppError> {
+        let read_failed = |source| AppError::FileReadFailed {
+            path: file_path.to_string(),
+            source,
+        };
+        let file = File::open(file_path).map_err(read_failed)?;
+        let lines = &self.text_editor().editor_content;
+        //a different length is a change without reading the file
+        let file_len = file.metadata().map_err(read_failed)?.len();
+        if file_len != file_io::content_len(lines, self.file_format) as u64 {
+            return Ok(true);
+        }
+        let lines = lines.iter().map(String::as_str);
+        file_io::content_differs(file, lines, self.file_format).map_err(read_failed)
+    }
+}
+
+///what went wrong with a save in a few words, like "permission denied"
+fn short_reason(e: &AppError) -> String {
+    match e {
+        AppError::FileWriteFailed { source, .. } => source.kind().to_string(),
+        e => e.to_string(),
+    }
+}
+
+///a byte count in the largest fitting unit with one decimal, like 2.1 MB
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: &[&str] = &["KB", "MB", "GB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+///the missing directory of path as the user typed it, for status messages
+fn parent_name(path: &str) -> String {
+    file_io::missing_parent(path)
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+///name of the file at path without its dir
+fn file_name(path: &str) -> String {
+    Path::new(path).file_name().map_or_else(
+        || path.to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
+///reads the lines of the file at path with the format they are in, creates the file if it
+/// doesn't exist yet
+fn load_file(path: &str) -> Result<(Vec<String>, FileFormat), AppError> {
+    match fs::read(path) {
+        Ok(bytes) => Ok(FileFormat::read(bytes)),
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            File::create(path).map_err(|source| AppError::FileWriteFailed {
+                path: path.to_string(),
+                source,
+            })?;
+            Ok((vec![String::new()], FileFormat::default()))
+        }
+        Err(source) => Err(AppError::FileReadFailed {
+            path: path.to_string(),
+            source,
+        }),
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+
+#[cfg(test)]
+mod unit_app_tests {
+    use super::super::app::*;
+
+    fn create_app() -> App {
+        App::new()
+    }
+
+    #[test]
+    fn test_toggle_to_command_line() {
+        let mut app = create_app();
+        app.active_area = ActiveArea::Editor;
+        app.editor.cursor.x = 5;
+        app.editor.cursor.y = 3;
+
+        app.toggle_active_area();
+        assert_eq!(app.active_area, ActiveArea::CommandLine);
+        assert_eq!(app.command_line.cursor.x, 0);
+        assert_eq!(app.command_line.cursor.y, 0);
+        assert_eq!(app.editor.cursor.x, 5);
+        assert_eq!(app.editor.cursor.y, 3);
+    }
+
+    #[test]
+    fn test_on_tick_toggles_cursor_after_blink_interval() {
+        let mut app = create_app();
+        assert!(!app.on_tick());
+        assert!(app.cursor_visible);
+
+        app.last_tick = Instant::now() - Duration::from_millis(editor_settings::CURSOR_BLINK_MS);
+        assert!(app.on_tick());
+        assert!(!app.cursor_visible);
+    }
+
+    #[test]
+    fn test_toggle_to_editor() {
+        let mut app = create_app();
+        app.active_area = ActiveArea::CommandLine;
+        app.editor.cursor.x = 5;
+        app.editor.cursor.y = 3;
+
+        app.toggle_active_area();
+        assert_eq!(app.active_area, ActiveArea::Editor);
+        assert_eq!(app.editor.cursor.x, 5);
+        assert_eq!(app.editor.cursor.y, 3);
+    }
+}
+#[cfg(test)]
+mod unit_app_macro_tests {
+    use super::super::app::*;
+
+    fn create_app() -> App {
+        let mut app = App::new();
+        app.editor.editor_content = vec![String::new()].into();
+        app
+    }
+
+    fn record_macro(app: &mut App, register: char, actions: Vec<InputAction>) {
+        app.process_input_action(InputAction::ToggleMacroRecording);
+        app.process_input_action(InputAction::WriteChar(register));
+        for action in actions {
+            app.process_input_action(action);
+        }
+        app.process_input_action(InputAction::ToggleMacroRecording);
+    }
+
+    #[test]
+    fn test_record_macro_applies_and_stores_actions() {
+        let mut app = create_app();
+        record_macro(
+            &mut app,
+            'a',
+            vec![InputAction::WriteChar('x'), InputAction::WriteChar('y')],
+        );
+
+        assert_eq!(app.editor.editor_content[0], "xy");
+        assert_eq!(app.macros.recording_register(), None);
+        assert_eq!(app.macros.get('a').unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_play_macro_with_count() {
+        let mut app = create_app();
+        record_macro(
+            &mut app,
+            'a',
+            vec![InputAction::WriteChar('x'), InputAction::WriteChar('y')],
+        );
+
+        app.process_input_action(InputAction::PlayMacro);
+        app.process_input_action(InputAction::WriteChar('2'));
+        app.process_input_action(InputAction::WriteChar('a'));
+
+        assert_eq!(app.editor.editor_content[0], "xyxyxy");
+    }
+
+    #[test]
+    fn test_play_macro_single_undo_per_iteration() {
+        let mut app = create_app();
+        record_macro(
+            &mut app,
+            'a',
+            vec![InputAction::WriteChar('x'), InputAction::WriteChar('y')],
+        );
+
+        app.process_input_action(InputAction::PlayMacro);
+        app.process_input_action(InputAction::WriteChar('2'));
+        app.process_input_action(InputAction::WriteChar('a'));
+        app.process_input_action(InputAction::UNDO);
+
+        assert_eq!(app.editor.editor_content[0], "xyxy");
+    }
+
+    #[test]
+    fn test_play_empty_register_opens_error_popup() {
+        let mut app = create_app();
+        app.process_input_action(InputAction::PlayMacro);
+        app.process_input_action(InputAction::WriteChar('z'));
+
+        assert!(app.popup.is_some());
+        assert_eq!(app.active_area, ActiveArea::Popup);
+    }
+
+    #[test]
+    fn test_macros_command_opens_list_popup() {
+        let mut app = create_app();
+        record_macro(&mut app, 'a', vec![InputAction::WriteChar('x')]);
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = ":macros".to_string();
+        app.process_input_action(InputAction::ENTER);
+
+        assert!(app.popup.is_some());
+        assert_eq!(app.active_area, ActiveArea::Popup);
+        app.process_input_action(InputAction::ENTER);
+        assert!(app.popup.is_none());
+    }
+}
+#[cfg(test)]
+mod unit_app_mark_tests {
+    use super::super::app::*;
+    use crate::core::cursor::CursorPosition;
+    use crate::core::position::{Position, Range};
+
+    fn create_app(lines: &[&str]) -> App {
+        let mut app = App::new();
+        app.editor.editor_content = lines.iter().map(|l| l.to_string()).collect();
+        app.editor.editor_height = 10; //since testing doesnt start ui.rs, height isnt set
+        app
+    }
+
+    fn run_command(app: &mut App, command: &str) {
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = command.to_string();
+        app.process_input_action(InputAction::ENTER);
+    }
+
+    #[test]
+    fn test_mark_follows_text_when_lines_inserted_above() {
+        let mut app = create_app(&["one", "two", "three"]);
+        app.editor.cursor.y = 2;
+        app.editor.cursor.x = 3;
+        app.process_input_action(InputAction::SetMark);
+        app.process_input_action(InputAction::WriteChar('a'));
+
+        app.editor.cursor.y = 0;
+        app.editor.cursor.x = 0;
+        app.process_input_action(InputAction::ENTER);
+        app.process_input_action(InputAction::ENTER);
+
+        app.process_input_action(InputAction::JumpToMark);
+        app.process_input_action(InputAction::WriteChar('a'));
+
+        assert_eq!(app.editor.cursor.y, 4);
+        assert_eq!(app.editor.cursor.x, 3);
+        assert_eq!(app.editor.editor_content[4], "three");
+    }
+
+    #[test]
+    fn test_mark_and_goto_commands() {
+        let mut app = create_app(&["one", "two", "three"]);
+        app.editor.cursor.y = 1;
+        run_command(&mut app, ":mark b");
+        app.editor.cursor.y = 0;
+        run_command(&mut app, ":goto 'b");
+
+        assert_eq!(app.editor.cursor.y, 1);
+        assert!(app.popup.is_none());
+    }
+
+    #[test]
+    fn test_goto_lines_center_the_view() {
+        let lines: Vec<String> = (1..=100).map(|i| format!("  line {}", i)).collect();
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        let mut app = create_app(&lines);
+        run_command(&mut app, ":goto 50");
+        assert_eq!((app.editor.cursor.x, app.editor.cursor.y), (2, 49));
+        assert_eq!(app.editor.scroll_offset, 45);
+
+        run_command(&mut app, ":goto +10");
+        assert_eq!(app.editor.cursor.y, 59);
+        run_command(&mut app, ":goto -100");
+        assert_eq!(app.editor.cursor.y, 0);
+        assert_eq!(app.editor.scroll_offset, 0);
+        run_command(&mut app, ":goto 100%");
+        assert_eq!(app.editor.cursor.y, 99);
+        assert_eq!(app.editor.scroll_offset, 90);
+        run_command(&mut app, ":goto +1");
+        assert_eq!(app.editor.cursor.y, 99);
+        assert!(app.popup.is_none());
+    }
+
+    #[test]
+    fn test_goto_unknown_mark_opens_error_popup() {
+        let mut app = create_app(&["one"]);
+        run_command(&mut app, ":goto z");
+        assert_eq!(app.active_area, ActiveArea::Popup);
+
+        let mut app = create_app(&["one"]);
+        app.process_input_action(InputAction::JumpToMark);
+        app.process_input_action(InputAction::WriteChar('z'));
+        assert_eq!(app.active_area, ActiveArea::Popup);
+    }
+
+    #[test]
+    fn test_marks_command_lists_marks() {
+        let mut app = create_app(&["one", "two"]);
+        app.editor.marks.set('a', CursorPosition { x: 1, y: 1 });
+        run_command(&mut app, ":marks");
+        assert_eq!(app.active_area, ActiveArea::Popup);
+        assert_eq!(
+            app.editor.marks.describe(&app.editor.editor_content),
+            vec!["a: (1, 1) two".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_jump_scrolls_mark_into_view() {
+        let lines: Vec<String> = (0..40).map(|i| i.to_string()).collect();
+        let mut app = App::new();
+        app.editor.editor_content = lines.into();
+        app.editor.editor_height = 10;
+        app.editor.marks.set('a', CursorPosition { x: 0, y: 30 });
+        app.editor.jump_to_mark('a').unwrap();
+
+        assert_eq!(app.editor.cursor.y, 30);
+        assert_eq!(app.editor.scroll_offset, 21);
+    }
+
+    #[test]
+    fn test_marks_follow_edits_between_identical_lines_and_undo() {
+        let mut app = create_app(&["x", "x", "x"]);
+        app.editor.marks.set('a', CursorPosition { x: 1, y: 1 });
+        app.editor.set_cursor(CursorPosition::new(0, 0)).unwrap();
+        app.editor.insert_str("x\n").unwrap();
+        assert_eq!(
+            app.editor.marks.get('a'),
+            Some(CursorPosition { x: 1, y: 2 })
+        );
+
+        //as many lines replaced in place keep the mark on its line
+        run_command(&mut app, ":s/x/y/");
+        assert_eq!(
+            app.editor.marks.get('a'),
+            Some(CursorPosition { x: 1, y: 2 })
+        );
+
+        run_command(&mut app, ":undo 2");
+        assert_eq!(
+            app.editor.marks.get('a'),
+            Some(CursorPosition { x: 1, y: 1 })
+        );
+    }
+
+    #[test]
+    fn test_bookmarks_jump_with_wraparound_and_follow_edits() {
+        let mut app = create_app(&["one", "two", "three", "four"]);
+        app.editor.cursor.y = 1;
+        app.process_input_action(InputAction::ToggleBookmark);
+        app.editor.cursor.y = 3;
+        app.process_input_action(InputAction::ToggleBookmark);
+
+        app.process_input_action(InputAction::JumpToBookmark(true));
+        assert_eq!(app.editor.cursor.y, 1);
+        app.process_input_action(InputAction::JumpToBookmark(false));
+        assert_eq!(app.editor.cursor.y, 3);
+
+        //a line opened above both moves them down
+        app.editor.cursor.y = 0;
+        app.editor.cursor.x = 0;
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(app.editor.bookmarks.lines().collect::<Vec<_>>(), vec![2, 4]);
+
+        app.process_input_action(InputAction::JumpToBookmark(true));
+        assert_eq!(app.editor.editor_content[app.editor.cursor.y], "two");
+    }
+
+    #[test]
+    fn test_bookmarks_follow_edits_between_identical_lines_and_undo() {
+        let mut app = create_app(&["x", "x", "x"]);
+        app.editor.bookmarks.toggle(1);
+        app.editor.set_cursor(CursorPosition::new(0, 0)).unwrap();
+        app.editor.insert_str("x\n").unwrap();
+        assert_eq!(app.editor.bookmarks.lines().collect::<Vec<_>>(), vec![2]);
+
+        run_command(&mut app, ":undo");
+        assert_eq!(app.editor.bookmarks.lines().collect::<Vec<_>>(), vec![1]);
+
+        //a deleted line takes its bookmark along
+        app.editor
+            .set_selection(Range::new(Position::new(1, 0), Position::new(2, 0)))
+            .unwrap();
+        app.editor.cut().unwrap();
+        assert_eq!(app.editor.bookmarks.lines().count(), 0);
+    }
+
+    #[test]
+    fn test_jump_without_bookmarks_sets_status() {
+        let mut app = create_app(&["one"]);
+        app.process_input_action(InputAction::JumpToBookmark(true));
+        assert!(app.popup.is_none());
+        assert!(app.status().is_some());
+    }
+
+    #[test]
+    fn test_bookmarks_command_lists_picks_and_clears() {
+        let mut app = create_app(&["one", "  two", "three"]);
+        app.editor.bookmarks.toggle(1);
+        app.editor.bookmarks.toggle(2);
+        run_command(&mut app, ":bookmarks");
+        assert_eq!(app.active_area, ActiveArea::Popup);
+
+        app.popup_result = PopupResult::Index(0);
+        app.handle_list_popup_response();
+        assert!(app.pending_states.is_empty());
+        assert_eq!(app.editor.cursor(), CursorPosition::new(0, 1));
+
+        run_command(&mut app, ":bookmarks clear");
+        assert!(app.editor.bookmarks.is_empty());
+    }
+}
+#[cfg(test)]
+mod unit_app_modal_tests {
+    use super::super::app::*;
+
+    fn create_modal_app(vec: Vec<String>) -> App {
+        let mut app = App::new();
+        app.editor.editor_content = vec.into();
+        app.editor.editor_height = 10; //since testing doesnt start ui.rs, height isnt set
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = ":set modal on".to_string();
+        app.process_input_action(InputAction::ENTER);
+        app.active_area = ActiveArea::Editor;
+        app
+    }
+
+    fn press(app: &mut App, keys: &str) {
+        for c in keys.chars() {
+            app.process_input_action(InputAction::ModalKey(c));
+        }
+    }
+
+    #[test]
+    fn test_set_modal_on_starts_in_normal_mode() {
+        let app = create_modal_app(vec!["abc".to_string()]);
+        assert!(app.settings.modal);
+        assert_eq!(app.modal.mode, EditorMode::Normal);
+    }
+
+    #[test]
+    fn test_modal_is_off_by_default() {
+        let app = App::new();
+        assert!(!app.settings.modal);
+    }
+
+    #[test]
+    fn test_normal_mode_navigation_and_insert() {
+        let mut app = create_modal_app(vec!["abc".to_string(), "def".to_string()]);
+        press(&mut app, "jl");
+        assert_eq!(app.editor.cursor.y, 1);
+        assert_eq!(app.editor.cursor.x, 1);
+
+        press(&mut app, "i");
+        assert_eq!(app.modal.mode, EditorMode::Insert);
+        app.process_input_action(InputAction::WriteChar('X'));
+        app.process_input_action(InputAction::EnterNormalMode);
+
+        assert_eq!(app.editor.editor_content[1], "dXef");
+        assert_eq!(app.modal.mode, EditorMode::Normal);
+    }
+
+    #[test]
+    fn test_dd_then_p_moves_line() {
+        let mut app = create_modal_app(vec!["first".to_string(), "second".to_string()]);
+        press(&mut app, "dd");
+        assert_eq!(app.editor.editor_content, vec!["second"]);
+        press(&mut app, "p");
+        assert_eq!(app.editor.editor_content, vec!["first", "second"]);
+    }
+
+    #[test]
+    fn test_visual_yank_copies_selection_and_returns_to_normal() {
+        let mut app = create_modal_app(vec!["hello".to_string()]);
+        press(&mut app, "vlly");
+
+        assert_eq!(app.editor.clipboard.copied_text, vec!["he"]);
+        assert_eq!(app.modal.mode, EditorMode::Normal);
+        assert!(app.editor.selection.is_none());
+    }
+
+    #[test]
+    fn test_x_cuts_char_under_cursor() {
+        let mut app = create_modal_app(vec!["hello".to_string()]);
+        press(&mut app, "x");
+        assert_eq!(app.editor.editor_content[0], "ello");
+        assert_eq!(app.editor.clipboard.copied_text, vec!["h"]);
+    }
+}
+#[cfg(test)]
+mod unit_app_command_tests {
+    use super::super::app::*;
+    use crate::core::position::{Position, Range};
+
+    fn create_app(s: String) -> App {
+        let mut app = App::new();
+        app.command_line.input = s;
+        app
+    }
+
+    #[test]
+    fn test_valid_command_with_args() {
+        let app = create_app(":command arg1 arg2".to_string());
+
+        let result = app.command_line.split_command_bind_and_args();
+        assert!(result.is_ok());
+        let (cmd, args) = result.unwrap().unwrap();
+        assert_eq!(cmd, "command");
+        assert_eq!(args, vec!["arg1", "arg2"]);
+    }
+
+    #[test]
+    fn test_valid_command_no_args() {
+        let app = create_app(":hello".to_string());
+
+        let result = app.command_line.split_command_bind_and_args();
+        assert!(result.is_ok());
+        let (cmd, args) = result.unwrap().unwrap();
+        assert_eq!(cmd, "hello");
+        assert!(args.is_empty());
+    }
+
+    #[test]
+    fn test_missing_command() {
+        let app = create_app("not_a_command arg1".to_string());
+
+        let result = app.command_line.split_command_bind_and_args();
+        assert!(result.is_err());
+        assert_eq!(result.err().unwrap(), "No valid command found");
+    }
+
+    #[test]
+    fn test_word_count_command_reports_in_status() {
+        let mut app = create_app(":wc".to_string());
+        app.editor.editor_content = vec!["grüße an alle".to_string(), "zwei".to_string()].into();
+        app.active_area = ActiveArea::CommandLine;
+        app.process_input_action(InputAction::ENTER);
+
+        assert_eq!(app.status(), Some("2 lines, 4 words, 18 chars, 20 bytes"));
+        assert_eq!(app.active_area, ActiveArea::Editor);
+        assert!(app.command_line.input.is_empty());
+    }
+
+    #[test]
+    fn test_word_count_command_counts_selection() {
+        let mut app = create_app(":wc".to_string());
+        app.editor.editor_content = vec!["one two three".to_string()].into();
+        app.editor.selection = Some(Range::new(Position::new(0, 0), Position::new(0, 7)));
+        app.active_area = ActiveArea::CommandLine;
+        app.process_input_action(InputAction::ENTER);
+
+        assert_eq!(
+            app.status(),
+            Some("selection: 1 lines, 2 words, 7 chars, 7 bytes")
+        );
+    }
+
+    #[test]
+    fn test_empty_input() {
+        for input in ["", "  ", ":", "  :  ", "::"] {
+            let app = create_app(input.to_string());
+            assert_eq!(app.command_line.split_command_bind_and_args(), Ok(None));
+        }
+    }
+
+    #[test]
+    fn test_surrounding_whitespace_and_pasted_colon_are_dropped() {
+        for input in ["  :save   foo.txt  ", "::save foo.txt", ": :save\tfoo.txt"] {
+            let app = create_app(input.to_string());
+            let result = app.command_line.split_command_bind_and_args();
+            assert_eq!(
+                result,
+                Ok(Some(("save".to_string(), vec!["foo.txt".to_string()]))),
+                "{:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_quoted_arguments_keep_their_spaces() {
+        let app = create_app(r#":align "  =  " 'a  b'   c"#.to_string());
+        let (_, args) = app
+            .command_line
+            .split_command_bind_and_args()
+            .unwrap()
+            .unwrap();
+        assert_eq!(args, vec!["  =  ", "a  b", "c"]);
+
+        //quotes that don't close a token are plain chars
+        let app = create_app(r#":surround "  :goto 'a  x"y"z"#.to_string());
+        let (_, args) = app
+            .command_line
+            .split_command_bind_and_args()
+            .unwrap()
+            .unwrap();
+        assert_eq!(args, vec!["\"", ":goto", "'a", "x\"y\"z"]);
+    }
+
+    #[test]
+    fn test_substitution_is_one_argument_as_typed() {
+        let app = create_app(r#":s/a b/"c"/g"#.to_string());
+        let result = app.command_line.split_command_bind_and_args();
+        assert_eq!(
+            result,
+            Ok(Some(("s".to_string(), vec![r#"/a b/"c"/g"#.to_string()])))
+        );
+
+        let app = create_app(":set color off".to_string());
+        let (bind, _) = app
+            .command_line
+            .split_command_bind_and_args()
+            .unwrap()
+            .unwrap();
+        assert_eq!(bind, "set");
+    }
+
+    #[test]
+    fn test_empty_command_returns_to_editor() {
+        let mut app = create_app(" : ".to_string());
+        app.active_area = ActiveArea::CommandLine;
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(app.active_area, ActiveArea::Editor);
+        assert!(app.popup.is_none());
+        assert!(app.command_line.input.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod unit_app_save_quit_tests {
+    use super::super::app::*;
+    use super::load_file;
+    use crate::core::command_line::commands::quit::pending_command;
+    use crate::core::file_format::FileEncoding;
+    use crate::input::input_action::Direction;
+    use tempfile::TempDir;
+
+    fn create_app(content: &str) -> App {
+        let mut app = App::new();
+        app.running = true;
+        app.editor.editor_content = vec![content.to_string()].into();
+        app
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.process_input_action(InputAction::WriteChar(c));
+        }
+    }
+
+    ///app with content typed into an empty buffer, so it has unsaved changes
+    fn create_edited_app(content: &str) -> App {
+        let mut app = create_app("");
+        type_text(&mut app, content);
+        app
+    }
+
+    ///app with writes restricted to a project dir inside a temp dir
+    fn create_restricted_app(dir: &TempDir) -> App {
+        let project = dir.path().join("project");
+        fs::create_dir(&project).unwrap();
+        let mut app = create_edited_app("text");
+        app.restrict_writes_to(&project).unwrap();
+        app
+    }
+
+    fn path_in(dir: &TempDir, name: &str) -> String {
+        dir.path().join(name).to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_restricted_save_writes_inside_dir() {
+        let dir = TempDir::new().unwrap();
+        let mut app = create_restricted_app(&dir);
+        let path = path_in(&dir, "project/new/file.txt");
+        run_command(&mut app, &format!(":w --mkdir {}", path));
+        assert!(app.popup.is_none());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "text");
+    }
+
+    #[test]
+    fn test_restricted_save_refuses_outside_dir_even_forced() {
+        let dir = TempDir::new().unwrap();
+        let mut app = create_restricted_app(&dir);
+        for path in ["outside.txt", "project/../outside.txt", "elsewhere/new.txt"] {
+            run_command(&mut app, &format!(":w --force {}", path_in(&dir, path)));
+            let popup = app.popup.take().expect(path);
+            assert!(matches!(popup.get_popup_type(), PopupType::Error));
+            app.close_popup();
+        }
+        assert!(!dir.path().join("outside.txt").exists());
+        assert!(!dir.path().join("elsewhere").exists());
+        assert!(app.pending_states.is_empty());
+    }
+
+    #[test]
+    fn test_restricted_save_reports_resolved_path() {
+        let dir = TempDir::new().unwrap();
+        let app = create_restricted_app(&dir);
+        let err = app
+            .check_write_allowed(&path_in(&dir, "project/../outside.txt"))
+            .unwrap_err();
+        let resolved = fs::canonicalize(dir.path()).unwrap().join("outside.txt");
+        assert!(
+            err.to_string().contains(&resolved.display().to_string()),
+            "{}",
+            err
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_restricted_save_catches_symlink_escape() {
+        let dir = TempDir::new().unwrap();
+        let mut app = create_restricted_app(&dir);
+        fs::create_dir(dir.path().join("outside")).unwrap();
+        std::os::unix::fs::symlink(dir.path().join("outside"), dir.path().join("project/link"))
+            .unwrap();
+        let path = path_in(&dir, "project/link/file.txt");
+        assert!(matches!(
+            app.save(path),
+            Err(AppError::OutsideRestrictedDir { .. })
+        ));
+        assert!(!dir.path().join("outside/file.txt").exists());
+    }
+
+    #[test]
+    fn test_restricted_extract_refuses_outside_dir() {
+        use crate::core::position::{Position, Range};
+        let dir = TempDir::new().unwrap();
+        let mut app = create_restricted_app(&dir);
+        let all = Range::new(Position::new(0, 0), Position::new(0, 4));
+        app.editor.set_selection(all).unwrap();
+        run_command(
+            &mut app,
+            &format!(":extract --force {}", path_in(&dir, "out.txt")),
+        );
+        assert!(app.popup.is_some());
+        assert!(!dir.path().join("out.txt").exists());
+    }
+
+    #[test]
+    fn test_save_writes_current_file_and_reports_path() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.txt").to_string_lossy().to_string();
+        let mut app = create_app("hello");
+        app.file_path = Some(path.clone());
+
+        app.process_input_action(InputAction::Save);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "hello");
+        assert_eq!(app.status(), Some(format!("saved {}", path).as_str()));
+    }
+
+    #[test]
+    fn test_save_untitled_asks_for_name() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("b.txt").to_string_lossy().to_string();
+        let mut app = create_app("hello");
+
+        app.process_input_action(InputAction::Save);
+        assert_eq!(app.active_area, ActiveArea::Popup);
+        type_text(&mut app, &path);
+        app.process_input_action(InputAction::ENTER);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "hello");
+        assert_eq!(app.file_path, Some(path));
+        assert!(app.pending_states.is_empty());
+        assert!(app.popup.is_none());
+    }
+
+    #[test]
+    fn test_save_untitled_suggests_a_name() {
+        let mut app = create_app("hello");
+        app.process_input_action(InputAction::Save);
+        app.process_input_action(InputAction::ENTER);
+        let saved = fs::read_to_string(editor_settings::UNTITLED_FILE_NAME);
+        let _ = fs::remove_file(editor_settings::UNTITLED_FILE_NAME);
+        assert_eq!(saved.unwrap(), "hello");
+        assert_eq!(
+            app.file_path.as_deref(),
+            Some(editor_settings::UNTITLED_FILE_NAME)
+        );
+    }
+
+    #[test]
+    fn test_save_as_an_existing_file_asks_to_overwrite() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("b.txt").to_string_lossy().to_string();
+        fs::write(&path, "precious").unwrap();
+        let mut app = create_app("hello");
+
+        app.process_input_action(InputAction::Save);
+        type_text(&mut app, &path);
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(app.pending_states, vec![PendingState::Saving(path.clone())]);
+        assert!(matches!(
+            app.popup.as_ref().map(|popup| popup.get_popup_type()),
+            Some(PopupType::Confirmation)
+        ));
+
+        app.process_input_action(InputAction::MoveCursor(Direction::Right));
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "precious");
+        assert!(app.file_path.is_none());
+    }
+
+    #[test]
+    fn test_save_as_into_missing_directory_creates_it_once_confirmed() {
+        let dir = TempDir::new().unwrap();
+        let path = dir
+            .path()
+            .join("new/dir/b.txt")
+            .to_string_lossy()
+            .to_string();
+        let mut app = create_app("hello");
+
+        app.process_input_action(InputAction::Save);
+        type_text(&mut app, &path);
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(
+            app.pending_states,
+            vec![PendingState::CreatingDirectory(path.clone())]
+        );
+        assert!(!dir.path().join("new").exists());
+
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "hello");
+        assert_eq!(app.status(), Some(format!("saved {}", path).as_str()));
+        assert!(app.popup.is_none());
+    }
+
+    #[test]
+    fn test_failed_save_shows_error_and_keeps_buffer() {
+        let dir = TempDir::new().unwrap();
+        let blocker = dir.path().join("not_a_dir");
+        fs::write(&blocker, "").unwrap();
+        let path = blocker.join("a.txt").to_string_lossy().to_string();
+        let mut app = create_app("unsaved work");
+        app.file_path = Some(path);
+
+        app.process_input_action(InputAction::Save);
+
+        assert!(app.running);
+        assert_eq!(app.active_area, ActiveArea::Popup);
+        assert_eq!(app.editor.editor_content, vec!["unsaved work".to_string()]);
+    }
+
+    #[test]
+    fn test_save_to_path_error_names_the_file() {
+        let dir = TempDir::new().unwrap();
+        let blocker = dir.path().join("not_a_dir");
+        fs::write(&blocker, "").unwrap();
+        let path = blocker.join("a.txt").to_string_lossy().to_string();
+        let mut app = create_app("text");
+
+        let result = app.save_to_path(path.clone());
+
+        assert!(matches!(result, Err(AppError::FileWriteFailed { path: p, .. }) if p == path));
+        assert!(app.file_path.is_none());
+    }
+
+    #[test]
+    fn test_load_unreadable_file_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+        assert!(matches!(
+            load_file(&path),
+            Err(AppError::FileReadFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_load_missing_file_creates_it() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("new.txt").to_string_lossy().to_string();
+        assert_eq!(load_file(&path).unwrap().0, vec![String::new()]);
+        assert!(Path::new(&path).exists());
+    }
+
+    #[test]
+    fn test_edits_dirty_the_buffer_until_saved() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("d.txt").to_string_lossy().to_string();
+        let mut app = create_app("");
+        assert!(!app.has_unsaved_changes());
+
+        type_text(&mut app, "a");
+        assert!(app.has_unsaved_changes());
+        app.save(path).unwrap();
+        assert!(!app.has_unsaved_changes());
+
+        //undoing past the save leaves the saved file behind, redoing comes back to it
+        app.process_input_action(InputAction::UNDO);
+        assert!(app.has_unsaved_changes());
+        app.process_input_action(InputAction::REDO);
+        assert!(!app.has_unsaved_changes());
+    }
+
+    #[test]
+    fn test_undoing_back_to_the_saved_state_is_clean() {
+        let dir = TempDir::new().unwrap();
+        let path = existing_file(&dir, "u.txt");
+        let mut app = create_app("");
+        app.open_file(path.clone()).unwrap();
+
+        type_text(&mut app, "x");
+        assert!(app.has_unsaved_changes());
+        app.process_input_action(InputAction::UNDO);
+        assert!(!app.has_unsaved_changes());
+
+        //a new edit after undoing is another state, even with the same text
+        type_text(&mut app, "y");
+        app.save(path).unwrap();
+        app.process_input_action(InputAction::UNDO);
+        type_text(&mut app, "y");
+        assert_eq!(app.editor.editor_content, vec!["yold"]);
+        assert!(app.has_unsaved_changes());
+    }
+
+    #[test]
+    fn test_failed_save_stays_unsaved_after_undo_and_redo() {
+        let mut app = create_app("");
+        type_text(&mut app, "a");
+        app.mark_clean();
+        app.dirty = true;
+        app.process_input_action(InputAction::UNDO);
+        app.process_input_action(InputAction::REDO);
+        assert!(app.has_unsaved_changes());
+    }
+
+    #[test]
+    fn test_request_quit_without_changes_quits() {
+        let mut app = create_app("");
+        app.process_input_action(InputAction::RequestQuit);
+        assert!(!app.running);
+    }
+
+    #[test]
+    fn test_request_quit_with_changes_can_be_cancelled() {
+        let mut app = create_edited_app("unsaved");
+        app.process_input_action(InputAction::RequestQuit);
+        assert_eq!(app.active_area, ActiveArea::Popup);
+
+        app.process_input_action(InputAction::ToggleActiveArea);
+
+        assert!(app.running);
+        assert!(app.pending_states.is_empty());
+        assert!(app.popup.is_none());
+        assert_eq!(app.status(), Some("cancelled"));
+    }
+
+    #[test]
+    fn test_request_quit_declining_save_quits_without_saving() {
+        let mut app = create_edited_app("unsaved");
+        app.process_input_action(InputAction::RequestQuit);
+        app.process_input_action(InputAction::MoveCursor(Direction::Right));
+        app.process_input_action(InputAction::ENTER);
+
+        assert!(!app.running);
+        assert!(app.file_path.is_none());
+    }
+
+    #[test]
+    fn test_request_quit_saving_untitled_names_file_then_quits() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("c.txt").to_string_lossy().to_string();
+        let mut app = create_edited_app("unsaved");
+
+        app.process_input_action(InputAction::RequestQuit);
+        app.process_input_action(InputAction::ENTER);
+        assert!(app.running);
+        type_text(&mut app, &path);
+        app.process_input_action(InputAction::ENTER);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "unsaved");
+        assert!(!app.running);
+        assert!(app.pending_states.is_empty());
+    }
+
+    ///runs ticks until the background save is reported, like the main loop does
+    fn tick_until_saved(app: &mut App) {
+        let start = Instant::now();
+        while app.is_saving() {
+            assert!(
+                start.elapsed() < Duration::from_secs(10),
+                "save never finished"
+            );
+            app.on_tick();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_large_buffer_saves_in_background_from_snapshot() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("big.txt").to_string_lossy().to_string();
+        let mut app = create_app("snapshot");
+        app.settings.set("backgroundsave", "1").unwrap();
+        app.file_path = Some(path.clone());
+
+        app.process_input_action(InputAction::Save);
+        assert!(app.is_saving());
+        assert_eq!(app.status(), Some(format!("saving {}…", path).as_str()));
+        assert!(!app.has_unsaved_changes());
+
+        //edits made while saving don't reach the file
+        type_text(&mut app, "edit ");
+        assert!(app.has_unsaved_changes());
+        tick_until_saved(&mut app);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "snapshot");
+        assert_eq!(app.status(), Some(format!("saved {}", path).as_str()));
+        assert!(app.has_unsaved_changes());
+    }
+
+    #[test]
+    fn test_failed_background_save_opens_error_popup() {
+        let dir = TempDir::new().unwrap();
+        let blocker = dir.path().join("file");
+        fs::write(&blocker, "").unwrap();
+        let path = blocker.join("big.txt").to_string_lossy().to_string();
+        let mut app = create_app("content");
+        app.settings.set("backgroundsave", "1").unwrap();
+
+        app.save(path).unwrap();
+        tick_until_saved(&mut app);
+
+        assert_eq!(app.active_area, ActiveArea::Popup);
+        assert_eq!(app.file_path, None);
+    }
+
+    #[test]
+    fn test_second_save_while_saving_is_refused() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("big.txt").to_string_lossy().to_string();
+        let mut app = create_app("content");
+        app.settings.set("backgroundsave", "1").unwrap();
+
+        app.save(path.clone()).unwrap();
+        assert!(matches!(
+            app.save(path.clone()),
+            Err(AppError::SaveInProgress(_))
+        ));
+        app.wait_for_background_save().unwrap();
+        assert!(!app.is_saving());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "content");
+    }
+
+    #[test]
+    fn test_quitting_after_saving_waits_for_background_save() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("big.txt").to_string_lossy().to_string();
+        let mut app = create_edited_app("unsaved");
+        app.settings.set("backgroundsave", "1").unwrap();
+        app.file_path = Some(path.clone());
+        fs::write(&path, "old").unwrap();
+
+        app.process_input_action(InputAction::RequestQuit);
+        app.process_input_action(InputAction::ENTER);
+        assert!(app.is_saving());
+        assert!(app.running);
+
+        tick_until_saved(&mut app);
+        assert!(!app.running);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "unsaved");
+    }
+
+    #[test]
+    fn test_background_save_failing_while_quitting_keeps_the_buffer() {
+        let dir = TempDir::new().unwrap();
+        let blocker = dir.path().join("file");
+        fs::write(&blocker, "").unwrap();
+        let path = blocker.join("big.txt").to_string_lossy().to_string();
+        let mut app = create_edited_app("unsaved");
+        app.settings.set("backgroundsave", "1").unwrap();
+        app.file_path = Some(path);
+
+        app.process_input_action(InputAction::RequestQuit);
+        app.process_input_action(InputAction::ENTER);
+        assert!(app.is_saving());
+        tick_until_saved(&mut app);
+
+        assert!(app.running);
+        assert_eq!(app.active_area, ActiveArea::Popup);
+        assert!(app.pending_states.is_empty());
+        assert!(app.has_unsaved_changes());
+        assert_eq!(app.text_editor().editor_content.to_vec(), vec!["unsaved"]);
+
+        //quitting again asks about the unsaved buffer instead of dropping it
+        app.close_popup();
+        app.process_input_action(InputAction::RequestQuit);
+        assert_eq!(app.pending_states, vec![PendingState::QuittingUnsaved]);
+    }
+
+    #[test]
+    fn test_small_buffer_saves_right_away() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("small.txt").to_string_lossy().to_string();
+        let mut app = create_app("small");
+        app.save(path.clone()).unwrap();
+        assert!(!app.is_saving());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "small");
+    }
+
+    ///file holding old text next to the buffer, so saving to it asks first
+    fn existing_file(dir: &TempDir, name: &str) -> String {
+        let path = dir.path().join(name).to_string_lossy().to_string();
+        fs::write(&path, "old").unwrap();
+        path
+    }
+
+    #[test]
+    fn test_rejected_save_still_runs_queued_quit() {
+        let dir = TempDir::new().unwrap();
+        let path = existing_file(&dir, "a.txt");
+        let mut app = create_edited_app("new");
+        app.queue_pending(PendingState::Saving(path.clone()));
+        app.queue_pending(PendingState::Quitting);
+
+        //declining the overwrite leaves the quit, which asks about the unsaved buffer
+        app.process_input_action(InputAction::MoveCursor(Direction::Right));
+        app.process_input_action(InputAction::ENTER);
+        assert!(app.running);
+        assert_eq!(app.pending_states, vec![PendingState::QuittingUnsaved]);
+        assert_eq!(app.active_area, ActiveArea::Popup);
+
+        app.process_input_action(InputAction::MoveCursor(Direction::Right));
+        app.process_input_action(InputAction::ENTER);
+        assert!(!app.running);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+    }
+
+    #[test]
+    fn test_each_queued_save_gets_its_own_confirmation() {
+        let dir = TempDir::new().unwrap();
+        let first = existing_file(&dir, "a.txt");
+        let second = existing_file(&dir, "b.txt");
+        let mut app = create_edited_app("new");
+        app.queue_pending(PendingState::Saving(first.clone()));
+        app.queue_pending(PendingState::Saving(second.clone()));
+
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(fs::read_to_string(&first).unwrap(), "new");
+        assert_eq!(fs::read_to_string(&second).unwrap(), "old");
+        assert_eq!(
+            app.pending_states,
+            vec![PendingState::Saving(second.clone())]
+        );
+        assert_eq!(app.active_area, ActiveArea::Popup);
+
+        app.process_input_action(InputAction::MoveCursor(Direction::Right));
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(fs::read_to_string(&second).unwrap(), "old");
+        assert!(app.pending_states.is_empty());
+        assert!(app.popup.is_none());
+    }
+
+    #[test]
+    fn test_failed_save_error_is_shown_before_next_state() {
+        let dir = TempDir::new().unwrap();
+        let blocker = existing_file(&dir, "not_a_dir");
+        let broken = Path::new(&blocker)
+            .join("a.txt")
+            .to_string_lossy()
+            .to_string();
+        let second = existing_file(&dir, "b.txt");
+        let mut app = create_edited_app("new");
+        app.queue_pending(PendingState::Saving(broken));
+        app.queue_pending(PendingState::Saving(second.clone()));
+        app.queue_pending(PendingState::Quitting);
+
+        app.process_input_action(InputAction::ENTER);
+        assert!(matches!(
+            app.popup.as_ref().map(|popup| popup.get_popup_type()),
+            Some(PopupType::Error)
+        ));
+        assert_eq!(app.pending_states.len(), 2);
+
+        //closing the error moves on to the second save, then the quit
+        app.process_input_action(InputAction::ENTER);
+        assert!(matches!(
+            app.popup.as_ref().map(|popup| popup.get_popup_type()),
+            Some(PopupType::Confirmation)
+        ));
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(fs::read_to_string(&second).unwrap(), "new");
+        assert!(!app.running);
+        assert!(app.pending_states.is_empty());
+    }
+
+    fn run_command(app: &mut App, command: &str) {
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = command.to_string();
+        app.process_input_action(InputAction::ENTER);
+    }
+
+    #[test]
+    fn test_focus_lost_autosaves_named_buffers_when_enabled() {
+        let dir = TempDir::new().unwrap();
+        let path = existing_file(&dir, "a.txt");
+        let mut app = create_edited_app("new");
+        app.file_path = Some(path.clone());
+
+        app.handle_event(Event::FocusLost);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+
+        app.settings.set("autosave_on_focus_lost", "on").unwrap();
+        app.handle_event(Event::FocusGained);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+        app.handle_event(Event::FocusLost);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(app.status(), Some(format!("autosaved {}", path).as_str()));
+        assert!(!app.has_unsaved_changes());
+    }
+
+    #[test]
+    fn test_autosave_never_asks() {
+        let dir = TempDir::new().unwrap();
+        let mut app = create_edited_app("new");
+        app.settings.set("autosave_on_focus_lost", "on").unwrap();
+
+        //untitled buffers are skipped instead of asking for a name
+        app.handle_event(Event::FocusLost);
+        assert!(app.pending_states.is_empty());
+        assert!(app.popup.is_none());
+
+        //a failed save is reported in the status, not a popup
+        let blocker = existing_file(&dir, "not_a_dir");
+        app.file_path = Some(format!("{}/a.txt", blocker));
+        app.handle_event(Event::FocusLost);
+        assert!(app.popup.is_none());
+        assert!(app.status().unwrap().starts_with("autosave failed"));
+        assert!(app.has_unsaved_changes());
+    }
+
+    #[test]
+    fn test_esc_on_overwrite_then_save_asks_about_the_new_file_only() {
+        let dir = TempDir::new().unwrap();
+        let first = existing_file(&dir, "a.txt");
+        let second = existing_file(&dir, "b.txt");
+        let mut app = create_edited_app("new");
+
+        run_command(&mut app, &format!(":w {}", first));
+        assert_eq!(
+            app.pending_states,
+            vec![PendingState::Saving(first.clone())]
+        );
+        app.process_input_action(InputAction::ToggleActiveArea);
+        assert!(app.pending_states.is_empty());
+
+        run_command(&mut app, &format!(":w {}", second));
+        assert_eq!(
+            app.pending_states,
+            vec![PendingState::Saving(second.clone())]
+        );
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(fs::read_to_string(&first).unwrap(), "old");
+        assert_eq!(fs::read_to_string(&second).unwrap(), "new");
+    }
+
+    #[test]
+    fn test_save_whose_popup_closed_unanswered_is_dropped() {
+        let dir = TempDir::new().unwrap();
+        let first = existing_file(&dir, "a.txt");
+        let second = existing_file(&dir, "b.txt");
+        let mut app = create_edited_app("new");
+
+        run_command(&mut app, &format!(":w {}", first));
+        //the confirmation goes away without an answer
+        app.close_popup();
+        run_command(&mut app, &format!(":w {}", second));
+        assert_eq!(
+            app.pending_states,
+            vec![PendingState::Saving(second.clone())]
+        );
+
+        //the confirmation shown is the second file's, it must not save the first
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(fs::read_to_string(&first).unwrap(), "old");
+        assert_eq!(fs::read_to_string(&second).unwrap(), "new");
+        assert!(app.pending_states.is_empty());
+    }
+
+    #[test]
+    fn test_stale_popup_answer_does_not_skip_confirmation() {
+        let dir = TempDir::new().unwrap();
+        let path = existing_file(&dir, "a.txt");
+        let mut app = create_edited_app("new");
+        app.popup_result = PopupResult::Bool(true);
+
+        run_command(&mut app, &format!(":w {}", path));
+
+        assert_eq!(app.pending_states, vec![PendingState::Saving(path.clone())]);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+    }
+
+    #[test]
+    fn test_pending_command_shows_and_clears_the_queue() {
+        let dir = TempDir::new().unwrap();
+        let path = existing_file(&dir, "a.txt");
+        let mut app = create_edited_app("new");
+        app.queue_pending(PendingState::Saving(path.clone()));
+        app.queue_pending(PendingState::Quitting);
+        assert_eq!(app.pending_summary().unwrap(), "pending: save→quit");
+
+        pending_command(&mut app, vec![]).unwrap();
+        assert_eq!(app.status(), Some("pending: save→quit"));
+        pending_command(&mut app, vec!["clear".to_string()]).unwrap();
+        assert!(app.pending_states.is_empty());
+        assert_eq!(app.status(), Some("cleared 2 pending"));
+        assert!(pending_command(&mut app, vec!["all".to_string()]).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+        assert!(app.running);
+    }
+
+    #[test]
+    fn test_unedited_files_save_byte_identical() {
+        let dir = TempDir::new().unwrap();
+        let files: [&[u8]; 4] = [
+            b"caf\xe9\n\xa9 1999\n",
+            b"\xEF\xBB\xBFbom\nno newline",
+            "utf8 é\n\n".as_bytes(),
+            b"",
+        ];
+        for (i, bytes) in files.into_iter().enumerate() {
+            let path = dir.path().join(format!("{}.txt", i));
+            fs::write(&path, bytes).unwrap();
+            let path = path.to_string_lossy().to_string();
+            let mut app = create_app("");
+            app.open_file(path.clone()).unwrap();
+            assert!(!app.file_has_changes(&path).unwrap());
+            app.save_to_path(path.clone()).unwrap();
+            assert_eq!(fs::read(&path).unwrap(), bytes, "file {}", i);
+        }
+    }
+
+    #[test]
+    fn test_latin1_file_opens_decoded_and_saves_edits_in_latin1() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.txt").to_string_lossy().to_string();
+        fs::write(&path, b"caf\xe9\n").unwrap();
+        let mut app = create_app("");
+        app.open_file(path.clone()).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["café"]);
+        assert_eq!(app.file_format.encoding, FileEncoding::Latin1);
+
+        app.editor.cursor.x = 4;
+        type_text(&mut app, "!");
+        app.save_to_path(path.clone()).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"caf\xe9!\n");
+    }
+
+    #[test]
+    fn test_set_fileencoding_changes_how_the_buffer_is_saved() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.txt").to_string_lossy().to_string();
+        let mut app = create_app("é");
+        app.file_path = Some(path.clone());
+
+        run_command(&mut app, ":set fileencoding utf8bom");
+        assert!(app.has_unsaved_changes());
+        app.save_file();
+        assert_eq!(fs::read(&path).unwrap(), "\u{feff}é".as_bytes());
+
+        run_command(&mut app, ":set fileencoding latin1");
+        app.save_file();
+        assert_eq!(fs::read(&path).unwrap(), b"\xe9");
+
+        run_command(&mut app, ":set fileencoding ebcdic");
+        assert_eq!(app.file_format.encoding, FileEncoding::Latin1);
+    }
+
+    #[test]
+    fn test_latin1_save_names_the_first_unencodable_char() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.txt").to_string_lossy().to_string();
+        fs::write(&path, "old").unwrap();
+        let mut app = create_app("ok");
+        app.editor.editor_content.push("5 € and ✓".to_string());
+        app.file_format.encoding = FileEncoding::Latin1;
+
+        let result = app.save_to_path(path.clone());
+
+        assert!(matches!(
+            result,
+            Err(AppError::UnencodableChar {
+                char: '€',
+                line: 2,
+                col: 3,
+                ..
+            })
+        ));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+        assert!(app.file_path.is_none());
+    }
+
+    fn permission_denied(path: &str) -> AppError {
+        AppError::FileWriteFailed {
+            path: path.to_string(),
+            source: io::Error::from(ErrorKind::PermissionDenied),
+        }
+    }
+
+    #[test]
+    fn test_only_denied_saves_offer_elevated_retry() {
+        let mut app = create_app("text");
+        assert_eq!(
+            app.can_save_elevated(&permission_denied("/etc/hosts")),
+            cfg!(unix)
+        );
+        assert!(!app.can_save_elevated(&AppError::SaveInProgress("/etc/hosts".to_string())));
+        app.settings.elevated_save = false;
+        assert!(!app.can_save_elevated(&permission_denied("/etc/hosts")));
+    }
+
+    //env runs tee as the user, standing in for sudo
+    #[cfg(unix)]
+    #[test]
+    fn test_confirmed_elevated_save_waits_for_run_loop_then_writes() {
+        let dir = TempDir::new().unwrap();
+        let path = existing_file(&dir, "hosts");
+        let mut app = create_edited_app("new");
+        //like a quit that was answered with saving first
+        app.pending_states.push(PendingState::Quitting);
+        app.offer_elevated_save(path.clone());
+        assert!(matches!(
+            app.popup.as_ref().map(|popup| popup.get_popup_type()),
+            Some(PopupType::Confirmation)
+        ));
+
+        app.process_input_action(InputAction::ENTER);
+        assert!(app.popup.is_none());
+        assert!(app.elevated_save_due());
+        //not an answer waiting on a closed popup, input before the run loop keeps it
+        app.process_input_action(InputAction::MoveCursor(Direction::Right));
+        assert!(app.elevated_save_due());
+
+        let result = app.write_elevated("env");
+        app.finish_pending_with(result, "Failed to save file with elevated privileges");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(app.file_path, Some(path));
+        assert!(!app.has_unsaved_changes());
+        //the quit queued behind the save runs once it is done
+        assert!(!app.running);
+    }
+
+    #[test]
+    fn test_declined_elevated_save_leaves_buffer_unsaved() {
+        let dir = TempDir::new().unwrap();
+        let path = existing_file(&dir, "hosts");
+        let mut app = create_edited_app("new");
+        app.offer_elevated_save(path.clone());
+
+        app.process_input_action(InputAction::MoveCursor(Direction::Right));
+        app.process_input_action(InputAction::ENTER);
+        assert!(!app.elevated_save_due());
+        assert!(app.pending_states.is_empty());
+        assert!(app.status().unwrap().starts_with("not saved"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+    }
+    ///app with a.txt and b.txt edited, a.txt set aside by opening b.txt after it
+    fn create_two_edited_buffers(dir: &TempDir) -> (App, String, String) {
+        let first = existing_file(dir, "a.txt");
+        let second = existing_file(dir, "b.txt");
+        let mut app = create_app("");
+        app.open_file(first.clone()).unwrap();
+        type_text(&mut app, "new ");
+        app.open_file(second.clone()).unwrap();
+        type_text(&mut app, "new ");
+        (app, first, second)
+    }
+
+    ///makes the file at path look written since it was read
+    fn touch_long_ago(path: &str) {
+        let file = File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH).unwrap();
+    }
+
+    #[test]
+    fn test_save_all_saves_set_aside_buffers_too() {
+        let dir = TempDir::new().unwrap();
+        let (mut app, first, second) = create_two_edited_buffers(&dir);
+
+        run_command(&mut app, ":wa");
+        assert_eq!(fs::read_to_string(&first).unwrap(), "new old");
+        assert_eq!(fs::read_to_string(&second).unwrap(), "new old");
+        assert_eq!(app.status(), Some("saved 2"));
+        assert!(app.popup.is_none());
+        assert!(!app.has_unsaved_changes());
+        assert!(!app.closed_buffers[0].has_unsaved_changes());
+
+        run_command(&mut app, ":wa");
+        assert_eq!(app.status(), Some("nothing to save"));
+    }
+
+    #[test]
+    fn test_save_all_shows_failures_in_one_popup() {
+        let dir = TempDir::new().unwrap();
+        let (mut app, first, _) = create_two_edited_buffers(&dir);
+        let blocker = existing_file(&dir, "not_a_dir");
+        app.file_path = Some(format!("{}/b.txt", blocker));
+
+        run_command(&mut app, ":wa");
+        assert_eq!(fs::read_to_string(&first).unwrap(), "new old");
+        assert!(app
+            .status()
+            .unwrap()
+            .starts_with(&format!("saved 1, failed 1: {}/b.txt ", blocker)));
+        assert!(matches!(
+            app.popup.as_ref().map(|popup| popup.get_popup_type()),
+            Some(PopupType::Error)
+        ));
+        assert!(app.has_unsaved_changes());
+    }
+
+    #[test]
+    fn test_save_all_asks_before_overwriting_files_changed_on_disk() {
+        let dir = TempDir::new().unwrap();
+        let (mut app, first, second) = create_two_edited_buffers(&dir);
+        touch_long_ago(&first);
+        touch_long_ago(&second);
+
+        run_command(&mut app, ":wa");
+        assert_eq!(app.status(), Some("saved 0, 2 to confirm"));
+        assert_eq!(
+            app.pending_states,
+            vec![
+                PendingState::OverwritingChanged(second.clone()),
+                PendingState::OverwritingChanged(first.clone()),
+            ]
+        );
+        //the open file is skipped, the one set aside overwritten
+        app.process_input_action(InputAction::MoveCursor(Direction::Right));
+        app.process_input_action(InputAction::ENTER);
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(fs::read_to_string(&second).unwrap(), "old");
+        assert_eq!(fs::read_to_string(&first).unwrap(), "new old");
+        assert!(app.pending_states.is_empty());
+        assert!(app.has_unsaved_changes());
+    }
+
+    #[test]
+    fn test_save_all_and_quit_only_quits_once_everything_is_saved() {
+        let dir = TempDir::new().unwrap();
+        let (mut app, first, _) = create_two_edited_buffers(&dir);
+        let blocker = existing_file(&dir, "not_a_dir");
+        let second = app.file_path.replace(format!("{}/b.txt", blocker));
+        touch_long_ago(&first);
+
+        //the failure is shown first, the question after it has no quit queued behind it
+        run_command(&mut app, ":xa");
+        assert_eq!(
+            app.pending_states,
+            vec![PendingState::OverwritingChanged(first.clone())]
+        );
+        app.process_input_action(InputAction::ENTER);
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(fs::read_to_string(&first).unwrap(), "new old");
+        assert!(app.pending_states.is_empty());
+        assert!(app.running);
+
+        app.file_path = second;
+        run_command(&mut app, ":xa");
+        assert!(!app.has_unsaved_changes());
+        assert!(!app.running);
+    }
+
+    #[test]
+    fn test_buffer_switcher_enter_toggles_between_the_two_newest_buffers() {
+        let dir = TempDir::new().unwrap();
+        let (mut app, first, second) = create_two_edited_buffers(&dir);
+
+        app.process_input_action(InputAction::SwitchBuffer);
+        assert_eq!(app.pending_states, vec![PendingState::PickingBuffer]);
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(app.file_path.as_deref(), Some(first.as_str()));
+        assert_eq!(app.status(), Some("reopened a.txt"));
+        assert!(app.has_unsaved_changes());
+
+        app.process_input_action(InputAction::SwitchBuffer);
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(app.file_path.as_deref(), Some(second.as_str()));
+        assert!(app.pending_states.is_empty());
+
+        //typing filters the list, the open buffer picked by name stays open
+        app.process_input_action(InputAction::SwitchBuffer);
+        type_text(&mut app, "b.txt");
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(app.file_path.as_deref(), Some(second.as_str()));
+        assert_eq!(app.closed_buffers.len(), 1);
+    }
+
+    #[test]
+    fn test_buffer_switcher_closes_set_aside_buffers_asking_about_unsaved_changes() {
+        let dir = TempDir::new().unwrap();
+        let (mut app, first, _) = create_two_edited_buffers(&dir);
+        app.open_file(existing_file(&dir, "c.txt")).unwrap();
+
+        //b.txt is picked, the newest set aside
+        app.process_input_action(InputAction::SwitchBuffer);
+        app.process_input_action(InputAction::DELETE);
+        assert_eq!(app.pending_states, vec![PendingState::ClosingBuffer(1)]);
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(app.status(), Some("closed b.txt"));
+        assert_eq!(app.closed_buffers.len(), 1);
+        assert_eq!(app.closed_buffers[0].path.as_deref(), Some(first.as_str()));
+
+        //declined, a.txt stays with its changes
+        app.process_input_action(InputAction::SwitchBuffer);
+        app.process_input_action(InputAction::DELETE);
+        app.process_input_action(InputAction::MoveCursor(Direction::Right));
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(app.closed_buffers.len(), 1);
+        assert!(app.pending_states.is_empty());
+
+        app.process_input_action(InputAction::SwitchBuffer);
+        app.process_input_action(InputAction::MoveCursor(Direction::Up));
+        app.process_input_action(InputAction::DELETE);
+        assert_eq!(
+            app.status(),
+            Some("the open buffer can't be closed from the list")
+        );
+        assert!(app.popup.is_none());
+        assert_eq!(app.closed_buffers.len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod unit_app_popup_tests {
+    use super::super::app::*;
+    use crate::ui::popups::diff_popup::DiffPopup;
+    use crate::ui::theme::Theme;
+    use tempfile::TempDir;
+
+    fn create_app() -> App {
+        let mut app = App::new();
+        app.running = true;
+        app.editor.editor_content = vec!["new".to_string()].into();
+        app.dirty = true;
+        app
+    }
+
+    fn existing_file(dir: &TempDir, name: &str) -> String {
+        let path = dir.path().join(name).to_string_lossy().to_string();
+        fs::write(&path, "old").unwrap();
+        path
+    }
+
+    fn press_esc(app: &mut App) {
+        app.process_input_action(InputAction::ToggleActiveArea);
+    }
+
+    fn assert_dismissed(app: &App) {
+        assert!(app.popup.is_none());
+        assert_eq!(app.active_area, ActiveArea::Editor);
+        assert_eq!(app.popup_result, PopupResult::None);
+        assert!(app.pending_states.is_empty());
+    }
+
+    #[test]
+    fn test_esc_on_confirmation_cancels_it_and_whats_queued_after() {
+        let dir = TempDir::new().unwrap();
+        let path = existing_file(&dir, "a.txt");
+        let mut app = create_app();
+        app.queue_pending(PendingState::Saving(path.clone()));
+        app.queue_pending(PendingState::Quitting);
+
+        press_esc(&mut app);
+
+        assert_dismissed(&app);
+        assert!(app.running);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+        assert_eq!(app.status(), Some("cancelled"));
+    }
+
+    #[test]
+    fn test_esc_on_error_acknowledges_it() {
+        let dir = TempDir::new().unwrap();
+        let path = existing_file(&dir, "a.txt");
+        let mut app = create_app();
+        app.open_popup(Box::new(ErrorPopup::new(
+            "Failed",
+            AppError::InternalError("boom".to_string()),
+        )));
+        press_esc(&mut app);
+        assert_dismissed(&app);
+
+        //the states waiting behind the error go on once it is acknowledged
+        app.pending_states.push(PendingState::Saving(path.clone()));
+        app.open_popup(Box::new(ErrorPopup::new(
+            "Failed",
+            AppError::InternalError("boom".to_string()),
+        )));
+        press_esc(&mut app);
+        assert!(matches!(
+            app.popup.as_ref().map(|popup| popup.get_popup_type()),
+            Some(PopupType::Confirmation)
+        ));
+    }
+
+    #[test]
+    fn test_esc_on_input_cancels_naming() {
+        let mut app = create_app();
+        app.queue_pending(PendingState::NamingFile);
+        app.process_input_action(InputAction::WriteChar('a'));
+
+        press_esc(&mut app);
+
+        assert_dismissed(&app);
+        assert!(app.file_path.is_none());
+    }
+
+    #[test]
+    fn test_esc_on_list_drops_its_picker() {
+        let mut app = create_app();
+        app.editor.bookmarks.toggle(0);
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = ":bookmarks".to_string();
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(
+            app.pending_states,
+            vec![PendingState::PickingBookmark(vec![0])]
+        );
+
+        press_esc(&mut app);
+
+        assert_dismissed(&app);
+    }
+
+    #[test]
+    fn test_esc_on_text_closes_it() {
+        let mut app = create_app();
+        app.open_popup(Box::new(DiffPopup::new("diff", vec!["+a".to_string()])));
+
+        press_esc(&mut app);
+
+        assert_dismissed(&app);
+    }
+
+    ///a popup stuck ignoring every key, esc included
+    struct StuckPopup;
+
+    impl Popup for StuckPopup {
+        fn render(&self, _frame: &mut Frame, _area: ratatui::layout::Rect, _theme: Theme) {}
+
+        fn get_popup_type(&self) -> PopupType {
+            PopupType::Confirmation
+        }
+
+        fn handle_input_action(&mut self, _action: InputAction) -> PopupResult {
+            PopupResult::None
+        }
+
+        fn dismiss_result(&self) -> PopupResult {
+            PopupResult::None
+        }
+    }
+
+    #[test]
+    fn test_double_esc_force_closes_stuck_popup() {
+        let dir = TempDir::new().unwrap();
+        let path = existing_file(&dir, "a.txt");
+        let mut app = create_app();
+        app.pending_states.push(PendingState::NamingFile);
+        app.pending_states.push(PendingState::Saving(path.clone()));
+        app.open_popup(Box::new(StuckPopup));
+
+        press_esc(&mut app);
+        assert_eq!(app.active_area, ActiveArea::Popup);
+        assert_eq!(app.pending_states.len(), 2);
+
+        press_esc(&mut app);
+        //only the state the stuck popup asked about is dropped, the save is asked next
+        assert_eq!(app.pending_states, vec![PendingState::Saving(path)]);
+        assert_eq!(app.popup_result, PopupResult::Cancelled);
+        assert!(matches!(
+            app.popup.as_ref().map(|popup| popup.get_popup_type()),
+            Some(PopupType::Confirmation)
+        ));
+        assert_eq!(app.status(), Some("popup force closed"));
+    }
+
+    #[test]
+    fn test_force_close_without_pending_returns_to_editor() {
+        let mut app = create_app();
+        app.open_popup(Box::new(StuckPopup));
+        press_esc(&mut app);
+        press_esc(&mut app);
+        assert!(app.popup.is_none());
+        assert_eq!(app.active_area, ActiveArea::Editor);
+    }
+
+    #[test]
+    fn test_esc_presses_far_apart_leave_stuck_popup_open() {
+        let mut app = create_app();
+        app.open_popup(Box::new(StuckPopup));
+        press_esc(&mut app);
+        app.last_popup_escape = Some(
+            Instant::now() - Duration::from_millis(editor_settings::POPUP_FORCE_CLOSE_MS + 100),
+        );
+        press_esc(&mut app);
+        assert_eq!(app.active_area, ActiveArea::Popup);
+    }
+
+    #[test]
+    fn test_quick_esc_on_next_popup_dismisses_it_normally() {
+        let dir = TempDir::new().unwrap();
+        let path = existing_file(&dir, "a.txt");
+        let mut app = create_app();
+        app.pending_states.push(PendingState::Saving(path.clone()));
+        app.open_popup(Box::new(ErrorPopup::new(
+            "Failed",
+            AppError::InternalError("boom".to_string()),
+        )));
+        press_esc(&mut app);
+        press_esc(&mut app);
+
+        assert_dismissed(&app);
+        assert_eq!(app.status(), Some("cancelled"));
+    }
+}
 
-        let result = app.command_line.split_command_bind_and_args();
-        assert!(result.is_err());
-        assert_eq!(result.err().unwrap(), "No valid command found");
+#[cfg(test)]
+mod unit_app_session_tests {
+    use super::super::app::*;
+    use crate::core::command_line::command::{Command, CommandFlag};
+    use crate::input::input_action::Direction;
+    use std::collections::HashSet;
+    use tempfile::tempdir;
+
+    fn write_lines(path: &Path, count: usize) {
+        let lines: Vec<String> = (0..count).map(|i| format!("line {}", i)).collect();
+        fs::write(path, lines.join("\n")).unwrap();
     }
 
     #[test]
-    fn test_empty_input() {
-        let mut app = create_app("".to_string());
+    fn test_reopened_file_restores_position() {
+        let dir = tempdir().unwrap();
+        let first = dir.path().join("first.txt");
+        let second = dir.path().join("second.txt");
+        write_lines(&first, 20);
+        write_lines(&second, 3);
 
-        let result = app.command_line.split_command_bind_and_args();
-        assert!(result.is_err());
-        assert_eq!(result.err().unwrap(), "No valid command found");
+        let mut app = App::new();
+        app.open_file(first.to_str().unwrap().to_string()).unwrap();
+        app.editor.cursor.y = 12;
+        app.editor.cursor.x = 4;
+        app.editor.scroll_offset = 5;
+
+        app.open_file(second.to_str().unwrap().to_string()).unwrap();
+        assert_eq!(app.editor.cursor.y, 0);
+
+        app.open_file(first.to_str().unwrap().to_string()).unwrap();
+        assert_eq!((app.editor.cursor.x, app.editor.cursor.y), (4, 12));
+        assert_eq!(app.editor.scroll_offset, 5);
+    }
+
+    #[test]
+    fn test_opened_file_sets_its_indentation() {
+        let dir = tempdir().unwrap();
+        let js = dir.path().join("a.js");
+        fs::write(&js, "if (a) {\n  b();\n  if (c) {\n    d();\n  }\n}\n").unwrap();
+        let plain = dir.path().join("notes.txt");
+        write_lines(&plain, 3);
+
+        let mut app = App::new();
+        app.open_file(js.to_str().unwrap().to_string()).unwrap();
+        assert!(app.settings.insert_spaces);
+        assert_eq!(app.settings.tab_width, 2);
+        assert_eq!(app.status(), Some("indent: 2 spaces"));
+
+        //:set still overrides what was detected
+        app.settings.set("tabwidth", "8").unwrap();
+        assert_eq!(app.settings.tab_width, 8);
+
+        app.open_file(plain.to_str().unwrap().to_string()).unwrap();
+        assert_eq!(app.settings.insert_spaces, editor_settings::INSERT_SPACES);
+        assert_eq!(app.settings.tab_width, editor_settings::TAB_WIDTH as usize);
+    }
+
+    #[test]
+    fn test_opened_file_with_long_lines_warns_once() {
+        let dir = tempdir().unwrap();
+        let long = dir.path().join("long.json");
+        fs::write(&long, format!("{{\n{}\n}}\n", "x".repeat(2_200_000))).unwrap();
+        let path = long.to_str().unwrap().to_string();
+
+        let mut app = App::new();
+        app.open_file(path.clone()).unwrap();
+        assert_eq!(
+            app.status(),
+            Some("file contains lines up to 2.1 MB; long-line mode enabled")
+        );
+
+        //with the mode off the file is treated like any other
+        app.settings.set("longlinemode", "off").unwrap();
+        app.status_message = None;
+        app.open_file(path).unwrap();
+        assert_eq!(app.status(), None);
+    }
+
+    #[test]
+    fn test_restored_position_is_clamped_to_shorter_file() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("file.txt");
+        write_lines(&file, 20);
+        let path = file.to_str().unwrap().to_string();
+
+        let mut app = App::new();
+        app.open_file(path.clone()).unwrap();
+        app.editor.cursor.y = 15;
+        app.editor.scroll_offset = 10;
+        app.open_file(path.clone()).unwrap();
+        assert_eq!(app.editor.cursor.y, 15);
+
+        write_lines(&file, 2);
+        app.editor.cursor.y = 15;
+        app.open_file(path).unwrap();
+        assert_eq!(app.editor.cursor.y, 1);
+        assert!(app.editor.scroll_offset <= 1);
+    }
+
+    #[test]
+    fn test_restore_position_can_be_turned_off() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("file.txt");
+        write_lines(&file, 20);
+        let path = file.to_str().unwrap().to_string();
+
+        let mut app = App::new();
+        app.settings.set("restoreposition", "off").unwrap();
+        app.open_file(path.clone()).unwrap();
+        app.editor.cursor.y = 15;
+        app.open_file(path).unwrap();
+        assert_eq!(app.editor.cursor.y, 0);
+    }
+
+    #[test]
+    fn test_open_command_refuses_unsaved_changes_without_force() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("file.txt");
+        write_lines(&file, 1);
+        let path = file.to_str().unwrap().to_string();
+
+        let mut app = App::new();
+        app.editor.insert_str("unsaved").unwrap();
+        let open = |flags: HashSet<CommandFlag>| Command::Open {
+            args: vec![path.clone()],
+            flags,
+        };
+        assert!(command_executor::execute_command(&mut app, open(HashSet::new())).is_err());
+        assert_eq!(app.editor.editor_content, vec!["unsaved"]);
+
+        let force = HashSet::from([CommandFlag::Force]);
+        command_executor::execute_command(&mut app, open(force)).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["line 0"]);
+        assert_eq!(app.file_path, Some(path));
+    }
+
+    #[test]
+    fn test_reopen_brings_back_buffer_replaced_by_forced_open() {
+        let dir = tempdir().unwrap();
+        let (first, second) = (dir.path().join("first.txt"), dir.path().join("second.txt"));
+        write_lines(&first, 30);
+        write_lines(&second, 1);
+        let first = first.to_str().unwrap().to_string();
+        let second = second.to_str().unwrap().to_string();
+
+        let mut app = App::new();
+        app.editor.editor_height = 10;
+        app.open_file(first.clone()).unwrap();
+        app.editor.set_cursor(CursorPosition::new(2, 20)).unwrap();
+        app.editor.insert_str("x").unwrap();
+        let open = Command::Open {
+            args: vec![second.clone()],
+            flags: HashSet::from([CommandFlag::Force]),
+        };
+        command_executor::execute_command(&mut app, open).unwrap();
+        assert_eq!(app.file_path.as_deref(), Some(second.as_str()));
+
+        command_executor::execute_command(&mut app, Command::Reopen).unwrap();
+        assert_eq!(app.file_path.as_deref(), Some(first.as_str()));
+        assert_eq!(app.editor.editor_content[20], "lixne 20");
+        assert_eq!(app.editor.cursor(), CursorPosition::new(3, 20));
+        assert!(app.has_unsaved_changes());
+        assert_eq!(app.status(), Some("reopened first.txt"));
+        //undo history came back with it
+        app.editor.undo().unwrap();
+        assert_eq!(app.editor.editor_content[20], "line 20");
+
+        //reopening again swaps back to the buffer it replaced
+        command_executor::execute_command(&mut app, Command::Reopen).unwrap();
+        assert_eq!(app.file_path.as_deref(), Some(second.as_str()));
+        assert!(!app.has_unsaved_changes());
+    }
+
+    #[test]
+    fn test_reopen_without_closed_buffer_fails() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("file.txt");
+        write_lines(&file, 1);
+
+        let mut app = App::new();
+        app.editor.editor_content = vec![String::new()].into();
+        assert!(command_executor::execute_command(&mut app, Command::Reopen).is_err());
+        //the empty untitled buffer left behind isn't kept
+        app.open_file(file.to_str().unwrap().to_string()).unwrap();
+        assert!(app.closed_buffers.is_empty());
+    }
+
+    #[test]
+    fn test_closed_buffers_are_capped() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("file.txt");
+        write_lines(&file, 1);
+        let path = file.to_str().unwrap().to_string();
+
+        let mut app = App::new();
+        for _ in 0..editor_settings::CLOSED_BUFFERS_MAX + 3 {
+            app.open_file(path.clone()).unwrap();
+        }
+        assert_eq!(
+            app.closed_buffers.len(),
+            editor_settings::CLOSED_BUFFERS_MAX
+        );
+    }
+
+    #[test]
+    fn test_opened_and_saved_files_are_recent() {
+        let dir = tempdir().unwrap();
+        let opened = dir.path().join("opened.txt");
+        let saved = dir.path().join("saved.txt");
+        write_lines(&opened, 1);
+
+        let mut app = App::new();
+        app.open_file(opened.to_str().unwrap().to_string()).unwrap();
+        app.save_to_path(saved.to_str().unwrap().to_string())
+            .unwrap();
+        app.open_file(opened.to_str().unwrap().to_string()).unwrap();
+
+        let recent: Vec<&Path> = app.session.recent_files().iter().map(Path::new).collect();
+        assert_eq!(recent.len(), 2);
+        assert!(recent[0].ends_with("opened.txt"));
+        assert!(recent[1].ends_with("saved.txt"));
+    }
+
+    #[test]
+    fn test_recent_picker_opens_selected_file() {
+        let dir = tempdir().unwrap();
+        let first = dir.path().join("first.txt");
+        let second = dir.path().join("second.txt");
+        write_lines(&first, 1);
+        write_lines(&second, 2);
+
+        let mut app = App::new();
+        app.open_file(first.to_str().unwrap().to_string()).unwrap();
+        app.open_file(second.to_str().unwrap().to_string()).unwrap();
+
+        command_executor::execute_command(&mut app, Command::Recent).unwrap();
+        assert_eq!(app.active_area, ActiveArea::Popup);
+        app.process_input_action(InputAction::MoveCursor(Direction::Down));
+        app.process_input_action(InputAction::ENTER);
+
+        assert!(app.popup.is_none());
+        assert!(app.pending_states.is_empty());
+        assert_eq!(app.editor.editor_content, vec!["line 0"]);
+        assert!(app.file_path.unwrap().ends_with("first.txt"));
+    }
+
+    ///app keeping its session and undo histories in state, with persistentundo on
+    fn persistent_undo_app(state: &Path) -> App {
+        let mut app = App::new();
+        app.session = Session::load(state.to_path_buf());
+        app.settings.set("persistentundo", "on").unwrap();
+        app
+    }
+
+    #[test]
+    fn test_undo_history_survives_save_and_reopen() {
+        let dir = tempdir().unwrap();
+        let state = dir.path().join("state");
+        let file = dir.path().join("file.txt");
+        write_lines(&file, 1);
+        let path = file.to_str().unwrap().to_string();
+
+        let mut app = persistent_undo_app(&state);
+        app.open_file(path.clone()).unwrap();
+        app.editor.insert_str("new ").unwrap();
+        app.save_to_path(path.clone()).unwrap();
+
+        let mut app = persistent_undo_app(&state);
+        app.open_file(path.clone()).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["new line 0"]);
+        app.editor.undo().unwrap();
+        assert_eq!(app.editor.editor_content, vec!["line 0"]);
+
+        //the file changed behind the history's back, it is dropped
+        write_lines(&file, 2);
+        let mut app = persistent_undo_app(&state);
+        app.open_file(path).unwrap();
+        assert!(app.editor.undo().is_err());
+    }
+
+    #[test]
+    fn test_undo_history_is_not_kept_when_off_or_unsaved() {
+        let dir = tempdir().unwrap();
+        let state = dir.path().join("state");
+        let file = dir.path().join("file.txt");
+        write_lines(&file, 1);
+        let path = file.to_str().unwrap().to_string();
+
+        let mut app = persistent_undo_app(&state);
+        app.settings.set("persistentundo", "off").unwrap();
+        app.open_file(path.clone()).unwrap();
+        app.editor.insert_str("new ").unwrap();
+        app.save_to_path(path.clone()).unwrap();
+        assert!(!state.join("undo").exists());
+
+        //leaving a buffer with unsaved changes keeps nothing of them
+        let mut app = persistent_undo_app(&state);
+        app.open_file(path.clone()).unwrap();
+        app.editor.insert_str("more ").unwrap();
+        app.open_file(path.clone()).unwrap();
+        assert!(!state.join("undo").exists());
+    }
+
+    #[test]
+    fn test_missing_recent_file_is_pruned_when_picked() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("gone.txt");
+        write_lines(&file, 1);
+
+        let mut app = App::new();
+        app.open_file(file.to_str().unwrap().to_string()).unwrap();
+        app.file_path = None;
+        app.editor.editor_content = vec![String::new()].into();
+        fs::remove_file(&file).unwrap();
+
+        app.show_recent_files();
+        app.process_input_action(InputAction::ENTER);
+
+        assert!(app.session.recent_files().is_empty());
+        assert_eq!(app.file_path, None);
+        assert!(!file.exists());
+    }
+
+    #[test]
+    fn test_opened_file_is_locked_until_app_is_dropped() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        write_lines(&file, 2);
+        let lock_file = dir.path().join(".notes.txt.calli-lock");
+
+        let mut app = App::new();
+        app.open_file(file.to_str().unwrap().to_string()).unwrap();
+        let content = fs::read_to_string(&lock_file).unwrap();
+        assert!(content.starts_with(&format!("{}\t", std::process::id())));
+        //reopening keeps the lock
+        app.open_file(file.to_str().unwrap().to_string()).unwrap();
+        assert!(lock_file.exists());
+
+        drop(app);
+        assert!(!lock_file.exists());
+    }
+
+    #[test]
+    fn test_file_locked_by_live_instance_asks_how_to_open() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        write_lines(&file, 2);
+        let path = file.to_str().unwrap().to_string();
+        //pid 1 is always running
+        let lock_file = dir.path().join(".notes.txt.calli-lock");
+        fs::write(&lock_file, "1\t1700000000\n").unwrap();
+
+        let mut app = App::new();
+        app.open_file(path.clone()).unwrap();
+        assert_eq!(app.active_area, ActiveArea::Popup);
+        assert_eq!(app.file_path, None);
+        app.process_input_action(InputAction::ToggleActiveArea);
+        assert_eq!(app.file_path, None);
+        assert!(app.pending_states.is_empty());
+
+        //read-only refuses edits
+        app.open_file(path.clone()).unwrap();
+        app.process_input_action(InputAction::MoveCursor(Direction::Right));
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(app.file_path.as_deref(), Some(path.as_str()));
+        assert!(app.read_only);
+        app.process_input_action(InputAction::WriteChar('x'));
+        assert_eq!(app.editor.editor_content[0], "line 0");
+
+        let mut other = App::new();
+        other.open_file(path.clone()).unwrap();
+        other.process_input_action(InputAction::ENTER);
+        assert!(!other.read_only);
+        other.process_input_action(InputAction::WriteChar('x'));
+        assert_eq!(other.editor.editor_content[0], "xline 0");
+        //the other instance's lock is left alone
+        drop(other);
+        assert_eq!(fs::read_to_string(&lock_file).unwrap(), "1\t1700000000\n");
+    }
+}
+#[cfg(test)]
+mod unit_app_open_under_cursor_tests {
+    use super::super::app::*;
+    use crate::core::cursor::Cursor;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_relative_path_opens_next_to_file_and_jumps_to_line() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        let notes = dir.path().join("notes.md");
+        fs::write(&notes, "see src/lib.rs:3 for details").unwrap();
+        fs::write(dir.path().join("src/lib.rs"), "a\nb\nc\nd").unwrap();
+
+        let mut app = App::new();
+        app.editor.editor_height = 10;
+        app.open_file(notes.to_str().unwrap().to_string()).unwrap();
+        app.editor.cursor.x = 6;
+        app.process_input_action(InputAction::OpenUnderCursor);
+
+        assert!(app.file_path.as_ref().unwrap().ends_with("lib.rs"));
+        assert_eq!(app.editor.cursor(), CursorPosition::new(0, 2));
+        assert_eq!(app.editor.editor_content[0], "a");
+    }
+
+    #[test]
+    fn test_line_suffix_into_open_file_only_jumps() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("todo.txt");
+        fs::write(&file, "first\nsecond\ngoto todo.txt:99").unwrap();
+        let mut app = App::new();
+        app.open_file(file.to_str().unwrap().to_string()).unwrap();
+        app.editor.insert_char('x').unwrap();
+        app.editor.cursor = Cursor { x: 6, y: 2 };
+
+        app.open_under_cursor();
+        //unsaved changes are kept, the line is clamped to the last one
+        assert_eq!(app.editor.line(0), Some("xfirst"));
+        assert_eq!(app.editor.cursor(), CursorPosition::new(0, 2));
+    }
+
+    #[test]
+    fn test_missing_file_is_reported_and_not_created() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("index.md");
+        fs::write(&file, "[link](missing.md)").unwrap();
+        let mut app = App::new();
+        app.open_file(file.to_str().unwrap().to_string()).unwrap();
+        app.editor.cursor.x = 9;
+
+        app.open_under_cursor();
+        assert!(app.status().unwrap().starts_with("no such file"));
+        assert!(!dir.path().join("missing.md").exists());
+        assert_eq!(app.file_path, Some(file.to_str().unwrap().to_string()));
+    }
+}
+
+#[cfg(test)]
+mod unit_app_unicode_input_tests {
+    use super::*;
+
+    fn create_app() -> App {
+        let mut app = App::new();
+        app.editor.editor_content = vec!["ab".to_string()].into();
+        app.editor.cursor.x = 1;
+        app
+    }
+
+    fn type_chord(app: &mut App, digits: &str) {
+        app.process_input_action(InputAction::StartUnicodeInput);
+        for c in digits.chars() {
+            app.process_input_action(InputAction::WriteChar(c));
+        }
+    }
+
+    #[test]
+    fn test_chord_inserts_typed_codepoint_on_enter() {
+        let mut app = create_app();
+        type_chord(&mut app, "2192");
+        assert_eq!(app.status(), Some("U+2192"));
+        assert_eq!(app.editor.editor_content, vec!["ab"]);
+
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(app.editor.editor_content, vec!["a→b"]);
+        assert!(app.unicode_input.is_none());
+        assert_eq!(app.status(), None);
+    }
+
+    #[test]
+    fn test_invalid_codepoint_is_reported_and_other_keys_cancel() {
+        let mut app = create_app();
+        type_chord(&mut app, "d800");
+        app.process_input_action(InputAction::ENTER);
+        assert!(app.status().unwrap().contains("surrogate"));
+        assert_eq!(app.editor.editor_content, vec!["ab"]);
+
+        type_chord(&mut app, "41x");
+        assert!(app.unicode_input.is_none());
+        assert_eq!(app.status(), Some("unicode input cancelled"));
+        assert_eq!(app.editor.editor_content, vec!["ab"]);
+    }
+}
+#[cfg(test)]
+mod unit_app_bell_tests {
+    use super::*;
+    use crate::core::settings::BellMode;
+
+    fn create_app(lines: &[&str]) -> App {
+        let mut app = App::new();
+        app.editor.editor_content = lines.iter().map(|line| line.to_string()).collect();
+        app.settings.visual_bell = BellMode::Beep;
+        app
+    }
+
+    #[test]
+    fn test_key_presses_at_buffer_edges_ring() {
+        let mut app = create_app(&["ab"]);
+        app.process_input_action(InputAction::BACKSPACE);
+        assert!(app.bell.take_beep());
+
+        let mut app = create_app(&["ab"]);
+        app.process_input_action(InputAction::MoveCursor(Direction::Right));
+        assert!(!app.bell.take_beep());
+        app.process_input_action(InputAction::MoveCursor(Direction::Right));
+        app.process_input_action(InputAction::DELETE);
+        assert!(app.bell.take_beep());
+        assert_eq!(app.editor.editor_content, vec!["ab"]);
+    }
+
+    #[test]
+    fn test_nothing_to_undo_rings_instead_of_popup() {
+        let mut app = create_app(&[""]);
+        app.settings.visual_bell = BellMode::Flash;
+        app.process_input_action(InputAction::UNDO);
+        assert!(app.popup.is_none());
+        assert_eq!(app.status(), Some("Nothing to undo"));
+        assert!(app.bell.is_flashing());
+
+        let mut app = create_app(&[""]);
+        app.settings.set("visualbell", "off").unwrap();
+        app.process_input_action(InputAction::PASTE);
+        assert!(app.popup.is_none());
+        assert!(!app.bell.is_flashing());
+        assert!(!app.bell.take_beep());
+    }
+}
+
+#[cfg(test)]
+mod unit_app_selection_tests {
+    use super::super::app::*;
+    use crate::core::position::{Position, Range};
+    use crate::input::input_action::{Direction, Motion};
+
+    ///app with the first word of "TODO one two" selected
+    fn create_app() -> App {
+        let mut app = App::new();
+        app.running = true;
+        let lines =
+            std::iter::once("TODO one two".to_string()).chain((0..40).map(|i| i.to_string()));
+        app.editor.editor_content = lines.collect();
+        app.editor.editor_height = 10; //since testing doesnt start ui.rs, height isnt set
+        for _ in 0..4 {
+            app.process_input_action(InputAction::MoveSelectionCursor(Direction::Right));
+        }
+        app
+    }
+
+    fn selected() -> Option<Range> {
+        Some(Range::new(Position::new(0, 0), Position::new(0, 4)))
+    }
+
+    fn run_command(app: &mut App, command: &str) {
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = command.to_string();
+        app.process_input_action(InputAction::ENTER);
+    }
+
+    #[test]
+    fn test_scrolling_keeps_selection() {
+        let mut app = create_app();
+        assert_eq!(app.editor.selection, selected());
+        app.process_input_action(InputAction::ScrollLines(5));
+        app.process_input_action(InputAction::ScrollHalfPages(-1));
+        app.move_scroll_offset(3);
+        assert_eq!(app.editor.selection, selected());
+    }
+
+    #[test]
+    fn test_toggling_to_command_line_and_back_keeps_selection() {
+        let mut app = create_app();
+        app.process_input_action(InputAction::ToggleActiveArea);
+        assert_eq!(app.active_area, ActiveArea::CommandLine);
+        app.process_input_action(InputAction::ToggleActiveArea);
+        assert_eq!(app.active_area, ActiveArea::Editor);
+        assert_eq!(app.editor.selection, selected());
+    }
+
+    #[test]
+    fn test_colon_from_visual_mode_and_back_keeps_selection() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        let mut app = create_app();
+        app.process_input_action(InputAction::MoveCursorTo(Motion::LineStart));
+        run_command(&mut app, ":set modal on");
+        for c in "vllll:".chars() {
+            app.handle_event(key(KeyCode::Char(c)));
+        }
+        assert_eq!(app.active_area, ActiveArea::CommandLine);
+        app.handle_event(key(KeyCode::Esc));
+        assert_eq!(app.active_area, ActiveArea::Editor);
+        assert_eq!(app.modal.mode, EditorMode::Visual);
+        assert_eq!(app.editor.selection, selected());
+    }
+
+    #[test]
+    fn test_cancelling_a_popup_keeps_selection() {
+        let mut app = create_app();
+        run_command(&mut app, ":todos");
+        assert!(app.popup.is_some());
+        app.process_input_action(InputAction::ToggleActiveArea);
+        assert!(app.popup.is_none());
+        assert_eq!(app.editor.selection, selected());
+    }
+
+    #[test]
+    fn test_non_mutating_command_keeps_selection() {
+        let mut app = create_app();
+        run_command(&mut app, ":wc");
+        assert_eq!(
+            app.status(),
+            Some("selection: 1 lines, 1 words, 4 chars, 4 bytes")
+        );
+        assert_eq!(app.editor.selection, selected());
+    }
+
+    #[test]
+    fn test_cursor_moves_and_edits_clear_selection() {
+        let mut app = create_app();
+        app.process_input_action(InputAction::MoveCursor(Direction::Right));
+        assert_eq!(app.editor.selection, None);
+
+        let mut app = create_app();
+        app.process_input_action(InputAction::WriteChar('x'));
+        assert_eq!(app.editor.selection, None);
+        assert_eq!(app.editor.editor_content[0], "x one two");
+    }
+}
+
+#[cfg(test)]
+mod unit_app_line_insert_tests {
+    use super::super::app::*;
+    use crate::core::position::{Position, Range};
+
+    fn create_app(lines: &[&str], selection: Range) -> App {
+        let mut app = App::new();
+        app.running = true;
+        app.editor.editor_content = lines.iter().map(|line| line.to_string()).collect();
+        app.editor.editor_height = 10;
+        app.editor.selection = Some(selection);
+        app
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.process_input_action(InputAction::WriteChar(c));
+        }
+    }
+
+    #[test]
+    fn test_prefix_is_typed_once_for_every_line() {
+        let lines = ["one", "two", "three", "four"];
+        //ending at the start of the last line leaves it out
+        let mut app = create_app(&lines, Range::new(Position::new(0, 0), Position::new(3, 0)));
+        app.process_input_action(InputAction::StartLineInsert);
+        type_text(&mut app, "- ");
+        //shown on the first line only until applied
+        assert_eq!(
+            app.editor.editor_content,
+            vec!["- one", "two", "three", "four"]
+        );
+
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(
+            app.editor.editor_content,
+            vec!["- one", "- two", "- three", "four"]
+        );
+        assert_eq!(app.status(), Some("inserted on 3 lines"));
+        assert_eq!(app.active_area, ActiveArea::Editor);
+
+        app.editor.undo().unwrap();
+        assert_eq!(app.editor.editor_content, lines);
+    }
+
+    #[test]
+    fn test_text_goes_at_the_left_column_skipping_short_lines() {
+        let lines = ["abcd", "a", "abcd"];
+        let mut app = create_app(&lines, Range::new(Position::new(0, 3), Position::new(2, 2)));
+        app.process_input_action(InputAction::StartLineInsert);
+        type_text(&mut app, "XY");
+        //backspace stops where the text started
+        for _ in 0..3 {
+            app.process_input_action(InputAction::BACKSPACE);
+        }
+        type_text(&mut app, "Z");
+        //esc applies it too, and stays in the editor
+        app.process_input_action(InputAction::ToggleActiveArea);
+        assert_eq!(app.editor.editor_content, vec!["abZcd", "a", "abZcd"]);
+        assert_eq!(app.active_area, ActiveArea::Editor);
+    }
+
+    #[test]
+    fn test_other_keys_apply_the_text_and_go_on() {
+        let lines = ["a", "b"];
+        let mut app = create_app(&lines, Range::new(Position::new(0, 0), Position::new(1, 1)));
+        app.process_input_action(InputAction::StartLineInsert);
+        type_text(&mut app, "#");
+        app.process_input_action(InputAction::MoveCursor(
+            crate::input::input_action::Direction::Down,
+        ));
+        assert_eq!(app.editor.editor_content, vec!["#a", "#b"]);
+        assert!(!app.editor.is_line_inserting());
+        assert_eq!(app.editor.cursor.y, 1);
+    }
+
+    #[test]
+    fn test_needs_a_selection_over_several_lines() {
+        let mut app = create_app(
+            &["ab"],
+            Range::new(Position::new(0, 0), Position::new(0, 1)),
+        );
+        app.process_input_action(InputAction::StartLineInsert);
+        assert!(!app.editor.is_line_inserting());
+        assert_eq!(app.status(), Some("select several lines to insert on each"));
+    }
+}
+
+#[cfg(test)]
+mod unit_app_file_finder_tests {
+    use super::super::app::*;
+    use std::thread;
+    use tempfile::tempdir;
+
+    fn walk_until_done(app: &mut App) {
+        while app.file_finder.as_ref().is_some_and(|f| !f.is_done()) {
+            app.on_tick();
+            thread::yield_now();
+        }
+    }
+
+    #[test]
+    fn test_found_file_opens_on_enter() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/app.rs"), "fn app() {}\n").unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        let mut app = App::new();
+        app.start(None, false);
+        app.find_file_in(dir.path().to_path_buf());
+        assert_eq!(app.active_area, ActiveArea::Popup);
+        walk_until_done(&mut app);
+
+        for c in "mai".chars() {
+            app.process_input_action(InputAction::WriteChar(c));
+        }
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(app.editor.editor_content, vec!["fn main() {}"]);
+        assert!(app.file_path.as_ref().unwrap().ends_with("main.rs"));
+        assert!(app.pending_states.is_empty());
+        assert!(app.file_finder.is_none());
+    }
+
+    #[test]
+    fn test_closing_the_finder_drops_its_walk() {
+        let dir = tempdir().unwrap();
+        let mut app = App::new();
+        app.start(None, false);
+        app.find_file_in(dir.path().to_path_buf());
+        app.process_input_action(InputAction::ToggleActiveArea);
+        assert!(app.popup.is_none());
+        assert!(app.file_finder.is_none());
+        assert!(app.pending_states.is_empty());
     }
 }
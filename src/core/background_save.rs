@@ -0,0 +1,46 @@
+//writing large buffers on a separate thread, so saving them doesn't freeze the ui
+
+use crate::core::errors::error::AppError;
+use crate::core::file_format::FileFormat;
+use crate::core::file_io;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+/// a save running on its own thread, writing a snapshot of the buffer so edits made in
+/// the meantime can't end up half written in the file
+#[derive(Debug)]
+pub struct BackgroundSave {
+    pub path: String,
+    result: Receiver<Result<(), AppError>>,
+}
+
+impl BackgroundSave {
+    ///starts writing lines to path in format on a new thread
+    pub fn start(path: String, lines: Vec<String>, format: FileFormat) -> Self {
+        let (sender, result) = mpsc::channel();
+        let thread_path = path.clone();
+        thread::spawn(move || {
+            //the receiver is gone only if the app is, then nobody is left to tell
+            let _ = sender.send(file_io::write_file(&thread_path, &lines, format));
+        });
+        Self { path, result }
+    }
+
+    ///outcome of the save once it is done, None while it is still writing
+    pub fn poll(&self) -> Option<Result<(), AppError>> {
+        match self.result.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(thread_stopped())),
+        }
+    }
+
+    ///blocks until the save is done and returns its outcome
+    pub fn wait(&self) -> Result<(), AppError> {
+        self.result.recv().unwrap_or_else(|_| Err(thread_stopped()))
+    }
+}
+
+fn thread_stopped() -> AppError {
+    AppError::InternalError("save thread stopped without finishing".to_string())
+}
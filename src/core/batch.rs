@@ -0,0 +1,223 @@
+//running commands from a script without the tui, for using the editor's commands from
+// shell scripts like a stream editor. nothing is drawn and nothing is asked, a command that
+// would ask the user something fails the script instead.
+
+use super::app::{App, TerminalTask};
+use super::command_line::command::{Command, CommandFlag};
+use super::command_line::{command, command_executor};
+use super::errors::error::AppError;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum BatchError {
+    #[error("{0}")]
+    Open(AppError),
+
+    #[error("{0}, batch mode can't answer")]
+    OpenNeedsAnswer(String),
+
+    #[error("line {line}, {command}: {reason}")]
+    Command {
+        line: usize,
+        command: String,
+        reason: String,
+    },
+}
+
+///opens file_path, if given, and runs the commands of script on it, one per line with or
+/// without the leading colon. blank lines and lines starting with # are skipped. status
+/// messages of the commands are written to out, the first failing command stops the
+/// script, as does a quit.
+///
+/// ```
+/// use calliglyph::core::app::App;
+/// use calliglyph::core::batch;
+///
+/// let mut app = App::new();
+/// let mut out = vec![];
+/// batch::run(&mut app, None, "insert date\n:wc", &mut out)?;
+/// assert!(String::from_utf8(out).unwrap().starts_with("1 lines, 1 words"));
+/// # Ok::<(), batch::BatchError>(())
+/// ```
+pub fn run(
+    app: &mut App,
+    file_path: Option<String>,
+    script: &str,
+    out: &mut impl Write,
+) -> Result<(), BatchError> {
+    app.start(None, false);
+    //saved right away, so a failed save is reported at the line that asked for it
+    app.settings.background_save_bytes = usize::MAX;
+    if let Some(path) = file_path {
+        app.open_file(path).map_err(BatchError::Open)?;
+        //a file locked by another instance asks whether to open it anyway
+        if let Some(summary) = app.pending_summary() {
+            return Err(BatchError::OpenNeedsAnswer(summary));
+        }
+    }
+    for (i, line) in script.lines().enumerate() {
+        let text = line.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        run_line(app, text.strip_prefix(':').unwrap_or(text), out).map_err(|reason| {
+            BatchError::Command {
+                line: i + 1,
+                command: text.to_string(),
+                reason,
+            }
+        })?;
+        if !app.is_running() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+///runs the command in text, returns why it failed
+fn run_line(app: &mut App, text: &str, out: &mut impl Write) -> Result<(), String> {
+    app.status_message = None;
+    app.command_line.input = format!(":{}", text);
+    let split = app.command_line.split_command_bind_and_args();
+    app.command_line.clear();
+    let Some((bind, args)) = split? else {
+        return Ok(());
+    };
+    let mut command = command::parse_command(bind, args);
+    confirm_saving_opened_file(app, &mut command);
+    let result = command_executor::execute_command(app, command);
+    //whatever the command opened would wait for a key press forever
+    let pending = app.pending_summary();
+    app.clear_pending_states();
+    app.close_popup();
+    result.map_err(|e| e.to_string())?;
+    match app.take_terminal_task() {
+        Some(TerminalTask::View(_)) => {
+            return Err("can't view the buffer without a terminal".to_string());
+        }
+        Some(TerminalTask::Suspend) => return Err("can't suspend without a terminal".to_string()),
+        None => {}
+    }
+    if let Some(pending) = pending {
+        return Err(format!(
+            "needs an answer to {}, try --force or --mkdir",
+            pending
+        ));
+    }
+    if let Some(status) = app.status() {
+        //a closed stdout doesn't fail the script, the commands still ran
+        let _ = writeln!(out, "{}", status);
+    }
+    Ok(())
+}
+
+///saving the file the script runs on back to it is what the script is for, it isn't asked
+/// about like overwriting another file is
+fn confirm_saving_opened_file(app: &App, command: &mut Command) {
+    if let Command::Save { args, flags } | Command::SaveAndExit { args, flags } = command {
+        if app.file_path.is_some()
+            && args.first().or(app.file_path.as_ref()) == app.file_path.as_ref()
+        {
+            flags.insert(CommandFlag::Force);
+        }
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_batch_tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn file(dir: &TempDir, text: &str) -> String {
+        let path = dir.path().join("list.txt").to_string_lossy().to_string();
+        fs::write(&path, text).unwrap();
+        path
+    }
+
+    fn run_script(path: &str, script: &str) -> (Result<(), BatchError>, String) {
+        let mut out = vec![];
+        let result = run(&mut App::new(), Some(path.to_string()), script, &mut out);
+        (result, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_commands_run_in_order_and_save() {
+        let dir = TempDir::new().unwrap();
+        let path = file(&dir, "b\nb\na\nc");
+        let script = "# sort of\nunique\n\n:reverse\nw --force\nwc\n";
+        let (result, out) = run_script(&path, script);
+        result.unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "c\na\nb");
+        assert_eq!(
+            out,
+            "removed 1 duplicate lines\n3 lines, 3 words, 5 chars, 5 bytes\n"
+        );
+    }
+
+    #[test]
+    fn test_failing_command_stops_script_with_its_line() {
+        let dir = TempDir::new().unwrap();
+        let path = file(&dir, "b\na");
+        let (result, _) = run_script(&path, "reverse\n\ngoto nowhere\nw --force");
+        let Err(BatchError::Command { line, command, .. }) = result else {
+            panic!("unexpected result {:?}", result);
+        };
+        assert_eq!((line, command.as_str()), (3, "goto nowhere"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "b\na");
+    }
+
+    #[test]
+    fn test_question_fails_instead_of_waiting() {
+        let dir = TempDir::new().unwrap();
+        let path = file(&dir, "old");
+        let other = dir.path().join("other.txt");
+        fs::write(&other, "precious").unwrap();
+        let script = format!("w {}", other.display());
+        let (result, _) = run_script(&path, &script);
+        let Err(BatchError::Command {
+            line: 1, reason, ..
+        }) = result
+        else {
+            panic!("unexpected result {:?}", result);
+        };
+        assert!(reason.contains("pending: save"), "{}", reason);
+        assert_eq!(fs::read_to_string(&other).unwrap(), "precious");
+    }
+
+    #[test]
+    fn test_saving_the_opened_file_needs_no_answer() {
+        let dir = TempDir::new().unwrap();
+        let path = file(&dir, "a  \nb");
+        let (result, out) = run_script(&path, "trim\nsave\nreverse\nwq");
+        result.unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "b\na");
+        assert!(out.starts_with("trimmed 1 lines\n"), "{}", out);
+    }
+
+    #[test]
+    fn test_view_fails_without_a_terminal() {
+        let dir = TempDir::new().unwrap();
+        let path = file(&dir, "a");
+        let (result, _) = run_script(&path, "view less");
+        let Err(BatchError::Command { reason, .. }) = result else {
+            panic!("unexpected result {:?}", result);
+        };
+        assert!(reason.contains("without a terminal"), "{}", reason);
+    }
+
+    #[test]
+    fn test_quit_ends_the_script() {
+        let dir = TempDir::new().unwrap();
+        let path = file(&dir, "a");
+        let (result, _) = run_script(&path, "q\nbogus");
+        result.unwrap();
+    }
+}
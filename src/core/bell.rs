@@ -0,0 +1,105 @@
+//feedback for key presses that did nothing, like backspace at the start of the buffer
+
+use super::settings::BellMode;
+use crate::config::editor_settings;
+use std::time::{Duration, Instant};
+
+///rings on refused key presses, flashing the info bar or beeping
+#[derive(Debug, Default)]
+pub struct Bell {
+    //when it was last asked to ring, rung or not
+    last_request: Option<Instant>,
+    //until when the info bar shows inverted
+    flash_until: Option<Instant>,
+    //a beep waiting to be written to the terminal
+    beep_pending: bool,
+}
+
+impl Bell {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///rings in mode, unless it was asked to within the bell interval. a held key keeps
+    /// asking, so it rings once instead of strobing.
+    pub fn ring(&mut self, mode: BellMode) {
+        let now = Instant::now();
+        let recent = self.last_request.is_some_and(|last| {
+            now.duration_since(last) < Duration::from_millis(editor_settings::BELL_INTERVAL_MS)
+        });
+        self.last_request = Some(now);
+        if recent {
+            return;
+        }
+        match mode {
+            BellMode::Off => {}
+            BellMode::Flash => {
+                self.flash_until =
+                    Some(now + Duration::from_millis(editor_settings::BELL_FLASH_MS));
+            }
+            BellMode::Beep => self.beep_pending = true,
+        }
+    }
+
+    ///checks if the info bar is shown inverted right now
+    pub fn is_flashing(&self) -> bool {
+        self.flash_until.is_some_and(|until| Instant::now() < until)
+    }
+
+    ///ends a flash that ran out, returns true if it did so the screen is redrawn
+    pub fn on_tick(&mut self) -> bool {
+        if self.flash_until.is_some() && !self.is_flashing() {
+            self.flash_until = None;
+            return true;
+        }
+        false
+    }
+
+    ///takes the beep waiting to be written to the terminal
+    pub fn take_beep(&mut self) -> bool {
+        std::mem::take(&mut self.beep_pending)
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_bell_tests {
+    use super::*;
+    use std::thread::sleep;
+
+    #[test]
+    fn test_held_key_rings_once_until_it_pauses() {
+        let mut bell = Bell::new();
+        bell.ring(BellMode::Beep);
+        assert!(bell.take_beep());
+        bell.ring(BellMode::Beep);
+        bell.ring(BellMode::Beep);
+        assert!(!bell.take_beep());
+
+        sleep(Duration::from_millis(editor_settings::BELL_INTERVAL_MS));
+        bell.ring(BellMode::Beep);
+        assert!(bell.take_beep());
+    }
+
+    #[test]
+    fn test_flash_ends_on_tick_and_off_does_nothing() {
+        let mut bell = Bell::new();
+        bell.ring(BellMode::Off);
+        assert!(!bell.is_flashing());
+        assert!(!bell.take_beep());
+
+        sleep(Duration::from_millis(editor_settings::BELL_INTERVAL_MS));
+        bell.ring(BellMode::Flash);
+        assert!(bell.is_flashing());
+        assert!(!bell.on_tick());
+        sleep(Duration::from_millis(editor_settings::BELL_FLASH_MS));
+        assert!(bell.on_tick());
+        assert!(!bell.is_flashing());
+        assert!(!bell.on_tick());
+    }
+}
@@ -0,0 +1,341 @@
+//arithmetic expressions for :calc. integers stay exact until a float or an uneven division
+// comes in, errors point at the column of the expression they were found at.
+
+use std::fmt;
+
+///result of an expression
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+    Int(i64),
+    Float(f64),
+}
+
+impl Number {
+    fn as_float(self) -> f64 {
+        match self {
+            Number::Int(n) => n as f64,
+            Number::Float(f) => f,
+        }
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Number::Int(n) => write!(f, "{}", n),
+            Number::Float(x) => write!(f, "{}", x),
+        }
+    }
+}
+
+///why an expression has no value, with the char column it was found at
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalcError {
+    pub message: String,
+    pub col: usize,
+}
+
+impl CalcError {
+    fn new(message: impl Into<String>, col: usize) -> Self {
+        Self {
+            message: message.into(),
+            col,
+        }
+    }
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at column {}", self.message, self.col + 1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Number(Number),
+    Op(char),
+    Open,
+    Close,
+    End,
+}
+
+///evaluates expr made of integers, floats, 0x hex literals, + - * / % and parentheses.
+/// dividing integers stays an integer when it comes out even.
+///
+/// ```
+/// use calliglyph::core::calc::{evaluate, Number};
+///
+/// assert_eq!(evaluate("0x10 * (3 + 1)"), Ok(Number::Int(64)));
+/// assert_eq!(evaluate("7 / 2"), Ok(Number::Float(3.5)));
+/// assert_eq!(evaluate("1 / 0").unwrap_err().to_string(), "division by zero at column 3");
+/// ```
+pub fn evaluate(expr: &str) -> Result<Number, CalcError> {
+    let mut parser = Parser {
+        tokens: tokenize(expr)?,
+        next: 0,
+    };
+    let value = parser.expression()?;
+    match parser.peek() {
+        (Token::End, _) => Ok(value),
+        (Token::Close, col) => Err(CalcError::new("unmatched ')'", col)),
+        (_, col) => Err(CalcError::new("expected an operator", col)),
+    }
+}
+
+///splits expr into tokens with the char columns they start at, ending in Token::End
+fn tokenize(expr: &str) -> Result<Vec<(Token, usize)>, CalcError> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let token = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '+' | '-' | '*' | '/' | '%' => Token::Op(c),
+            '(' => Token::Open,
+            ')' => Token::Close,
+            c if c.is_ascii_digit() || c == '.' => {
+                let len = number_len(&chars[i..]);
+                let text: String = chars[i..i + len].iter().collect();
+                tokens.push((Token::Number(parse_number(&text, i)?), i));
+                i += len;
+                continue;
+            }
+            c => return Err(CalcError::new(format!("unexpected '{}'", c), i)),
+        };
+        tokens.push((token, i));
+        i += 1;
+    }
+    tokens.push((Token::End, chars.len()));
+    Ok(tokens)
+}
+
+///chars the number literal at the start of chars takes, letters included so a typo like
+/// 12px is reported as a bad number rather than a missing operator
+fn number_len(chars: &[char]) -> usize {
+    let mut len = 0;
+    while let Some(&c) = chars.get(len) {
+        let exponent_sign = (c == '+' || c == '-')
+            && len > 0
+            && matches!(chars[len - 1], 'e' | 'E')
+            && !is_hex(chars);
+        if !(c.is_ascii_alphanumeric() || c == '.' || exponent_sign) {
+            break;
+        }
+        len += 1;
+    }
+    len
+}
+
+fn is_hex(chars: &[char]) -> bool {
+    matches!(chars, ['0', 'x' | 'X', ..])
+}
+
+fn parse_number(text: &str, col: usize) -> Result<Number, CalcError> {
+    let invalid = || CalcError::new(format!("invalid number '{}'", text), col);
+    if let Some(digits) = text.strip_prefix("0x").or(text.strip_prefix("0X")) {
+        return i64::from_str_radix(digits, 16)
+            .map(Number::Int)
+            .map_err(|_| invalid());
+    }
+    if text.bytes().all(|b| b.is_ascii_digit()) {
+        return text.parse().map(Number::Int).map_err(|_| invalid());
+    }
+    //rust also takes inf and nan, which aren't literals here
+    if text.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        if let Ok(f) = text.parse::<f64>() {
+            return Ok(Number::Float(f));
+        }
+    }
+    Err(invalid())
+}
+
+///recursive descent over the tokens, one method per precedence level
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    next: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> (Token, usize) {
+        self.tokens[self.next]
+    }
+
+    fn advance(&mut self) -> (Token, usize) {
+        let token = self.peek();
+        if token.0 != Token::End {
+            self.next += 1;
+        }
+        token
+    }
+
+    //expression := term (('+' | '-') term)*
+    fn expression(&mut self) -> Result<Number, CalcError> {
+        let mut value = self.term()?;
+        while let (Token::Op(op @ ('+' | '-')), col) = self.peek() {
+            self.advance();
+            let rhs = self.term()?;
+            value = apply(op, value, rhs, col)?;
+        }
+        Ok(value)
+    }
+
+    //term := unary (('*' | '/' | '%') unary)*
+    fn term(&mut self) -> Result<Number, CalcError> {
+        let mut value = self.unary()?;
+        while let (Token::Op(op @ ('*' | '/' | '%')), col) = self.peek() {
+            self.advance();
+            let rhs = self.unary()?;
+            value = apply(op, value, rhs, col)?;
+        }
+        Ok(value)
+    }
+
+    //unary := ('-' | '+') unary | primary
+    fn unary(&mut self) -> Result<Number, CalcError> {
+        match self.peek() {
+            (Token::Op('-'), col) => {
+                self.advance();
+                apply('-', Number::Int(0), self.unary()?, col)
+            }
+            (Token::Op('+'), _) => {
+                self.advance();
+                self.unary()
+            }
+            _ => self.primary(),
+        }
+    }
+
+    //primary := number | '(' expression ')'
+    fn primary(&mut self) -> Result<Number, CalcError> {
+        match self.advance() {
+            (Token::Number(n), _) => Ok(n),
+            (Token::Open, col) => {
+                let value = self.expression()?;
+                match self.advance() {
+                    (Token::Close, _) => Ok(value),
+                    (Token::End, _) => Err(CalcError::new("unclosed '('", col)),
+                    (_, col) => Err(CalcError::new("expected ')'", col)),
+                }
+            }
+            (Token::End, col) => Err(CalcError::new("expected a number, got the end", col)),
+            (_, col) => Err(CalcError::new("expected a number", col)),
+        }
+    }
+}
+
+///applies the binary operator op found at col
+fn apply(op: char, lhs: Number, rhs: Number, col: usize) -> Result<Number, CalcError> {
+    let divides = matches!(op, '/' | '%');
+    if divides && rhs.as_float() == 0.0 {
+        return Err(CalcError::new("division by zero", col));
+    }
+    if let (Number::Int(a), Number::Int(b)) = (lhs, rhs) {
+        let exact = match op {
+            '+' => a.checked_add(b),
+            '-' => a.checked_sub(b),
+            '*' => a.checked_mul(b),
+            '/' => match a.checked_rem(b) {
+                Some(0) => a.checked_div(b),
+                //an uneven division goes on as a float
+                Some(_) => return Ok(Number::Float(a as f64 / b as f64)),
+                None => None,
+            },
+            _ => a.checked_rem(b),
+        };
+        return exact
+            .map(Number::Int)
+            .ok_or_else(|| CalcError::new("integer overflow", col));
+    }
+    let (a, b) = (lhs.as_float(), rhs.as_float());
+    Ok(Number::Float(match op {
+        '+' => a + b,
+        '-' => a - b,
+        '*' => a * b,
+        '/' => a / b,
+        _ => a % b,
+    }))
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_calc_tests {
+    use super::*;
+
+    fn error_at(expr: &str) -> (String, usize) {
+        let e = evaluate(expr).unwrap_err();
+        (e.message, e.col)
+    }
+
+    #[test]
+    fn test_precedence_and_parentheses() {
+        assert_eq!(evaluate("2 + 3 * 4"), Ok(Number::Int(14)));
+        assert_eq!(evaluate("(2 + 3) * 4"), Ok(Number::Int(20)));
+        assert_eq!(evaluate("10 - 4 - 3"), Ok(Number::Int(3)));
+        assert_eq!(evaluate("17 % 5 * 2"), Ok(Number::Int(4)));
+        assert_eq!(evaluate("-(3 - 5) * --2"), Ok(Number::Int(4)));
+    }
+
+    #[test]
+    fn test_literals() {
+        assert_eq!(evaluate("0xff + 0X10"), Ok(Number::Int(271)));
+        assert_eq!(evaluate("1.5 * 2"), Ok(Number::Float(3.0)));
+        assert_eq!(evaluate(".5 + 1e-1"), Ok(Number::Float(0.6)));
+        assert_eq!(evaluate("2.5e2"), Ok(Number::Float(250.0)));
+    }
+
+    #[test]
+    fn test_division_stays_integer_when_even() {
+        assert_eq!(evaluate("12 / 4"), Ok(Number::Int(3)));
+        assert_eq!(evaluate("7 / 2"), Ok(Number::Float(3.5)));
+        assert_eq!(evaluate("-7 % 3"), Ok(Number::Int(-1)));
+        assert_eq!(evaluate("7.5 % 2"), Ok(Number::Float(1.5)));
+    }
+
+    #[test]
+    fn test_division_by_zero_points_at_operator() {
+        assert_eq!(error_at("4 / (2 - 2)"), ("division by zero".to_string(), 2));
+        assert_eq!(error_at("4 % 0.0"), ("division by zero".to_string(), 2));
+    }
+
+    #[test]
+    fn test_syntax_errors_point_at_column() {
+        assert_eq!(
+            error_at("2 * (3 +"),
+            ("expected a number, got the end".to_string(), 8)
+        );
+        assert_eq!(error_at("(1 + 2"), ("unclosed '('".to_string(), 0));
+        assert_eq!(error_at("1 + 2)"), ("unmatched ')'".to_string(), 5));
+        assert_eq!(error_at("2 3"), ("expected an operator".to_string(), 2));
+        assert_eq!(error_at("2 ^ 3"), ("unexpected '^'".to_string(), 2));
+        assert_eq!(
+            error_at("12px + 1"),
+            ("invalid number '12px'".to_string(), 0)
+        );
+        assert_eq!(error_at("0xg"), ("invalid number '0xg'".to_string(), 0));
+        assert_eq!(
+            error_at(""),
+            ("expected a number, got the end".to_string(), 0)
+        );
+    }
+
+    #[test]
+    fn test_overflow_is_an_error() {
+        assert_eq!(
+            error_at("9223372036854775807 + 1"),
+            ("integer overflow".to_string(), 20)
+        );
+        assert_eq!(
+            error_at("99999999999999999999"),
+            ("invalid number '99999999999999999999'".to_string(), 0)
+        );
+    }
+}
@@ -14,6 +14,12 @@ impl Clipboard {
         self.copied_text = text.to_owned();
     }
 
+    ///adds text below what was copied before, so snippets can be collected and pasted
+    /// as one block
+    pub fn append(&mut self, text: &[String]) {
+        self.copied_text.extend_from_slice(text);
+    }
+
     pub fn paste(&self) -> Vec<String> {
         self.copied_text.clone()
     }
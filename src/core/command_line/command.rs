@@ -10,19 +10,166 @@ pub enum Command {
         args: Vec<String>,
         flags: HashSet<CommandFlag>,
     },
+    SaveAll,
+    SaveAllAndExit,
     QuitForce,
     Help,
+    Macros,
+    Set {
+        args: Vec<String>,
+    },
+    Mark {
+        args: Vec<String>,
+    },
+    Goto {
+        args: Vec<String>,
+    },
+    Marks,
+    Insert {
+        args: Vec<String>,
+    },
+    WordCount,
+    Info,
+    Calc {
+        args: Vec<String>,
+    },
+    CalcInsert {
+        args: Vec<String>,
+    },
+    Reverse,
+    Unique,
+    Align {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    Reflow {
+        args: Vec<String>,
+    },
+    Retab {
+        flags: HashSet<CommandFlag>,
+    },
+    Open {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    Recent,
+    Reopen,
+    Encode {
+        args: Vec<String>,
+    },
+    Decode {
+        args: Vec<String>,
+    },
+    Increment {
+        args: Vec<String>,
+    },
+    Decrement {
+        args: Vec<String>,
+    },
+    Spell {
+        args: Vec<String>,
+    },
+    Todos,
+    Diff {
+        flags: HashSet<CommandFlag>,
+    },
+    HexView {
+        args: Vec<String>,
+    },
+    Copy {
+        flags: HashSet<CommandFlag>,
+    },
+    Unicode {
+        args: Vec<String>,
+    },
+    Blank {
+        args: Vec<String>,
+    },
+    Follow {
+        args: Vec<String>,
+    },
+    Cd {
+        args: Vec<String>,
+    },
+    Pwd,
+    Surround {
+        args: Vec<String>,
+    },
+    Unsurround,
+    Bookmarks {
+        args: Vec<String>,
+    },
+    Pending {
+        args: Vec<String>,
+    },
+    Extract {
+        args: Vec<String>,
+        flags: HashSet<CommandFlag>,
+    },
+    UndoList,
+    Undo {
+        args: Vec<String>,
+    },
+    Redo {
+        args: Vec<String>,
+    },
+    Case {
+        args: Vec<String>,
+    },
+    View {
+        args: Vec<String>,
+    },
+    Suspend,
+    Substitute {
+        args: Vec<String>,
+    },
+    Trim,
     Unknown {
         name: String,
         args: Vec<String>,
     },
 }
 
+impl Command {
+    ///checks if the command changes the buffer content, these are refused in read-only views
+    pub fn edits_content(&self) -> bool {
+        matches!(
+            self,
+            Command::Insert { .. }
+                | Command::CalcInsert { .. }
+                | Command::Reverse
+                | Command::Unique
+                | Command::Align { .. }
+                | Command::Reflow { .. }
+                | Command::Retab { .. }
+                | Command::Encode { .. }
+                | Command::Decode { .. }
+                | Command::Increment { .. }
+                | Command::Decrement { .. }
+                | Command::Unicode { .. }
+                | Command::UndoList
+                | Command::Undo { .. }
+                | Command::Redo { .. }
+                | Command::Blank { .. }
+                | Command::Surround { .. }
+                | Command::Unsurround
+                | Command::Case { .. }
+                | Command::Substitute { .. }
+                | Command::Trim
+        ) || matches!(self, Command::Extract { flags, .. } if flags.contains(&CommandFlag::Delete))
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum CommandFlag {
     Force,
     DryRun,
     Backup,
+    All,
+    Stat,
+    Append,
+    Delete,
+    Mkdir,
 }
 
 ///function to parse flags and args to respective data structure
@@ -41,6 +188,21 @@ fn parse_flags_and_args(raw_args: Vec<String>) -> (Vec<String>, HashSet<CommandF
             "--backup" => {
                 flags.insert(CommandFlag::Backup);
             }
+            "--all" => {
+                flags.insert(CommandFlag::All);
+            }
+            "--stat" => {
+                flags.insert(CommandFlag::Stat);
+            }
+            "--append" => {
+                flags.insert(CommandFlag::Append);
+            }
+            "--delete" => {
+                flags.insert(CommandFlag::Delete);
+            }
+            "--mkdir" => {
+                flags.insert(CommandFlag::Mkdir);
+            }
             _ => args.push(arg),
         }
     }
@@ -50,13 +212,66 @@ fn parse_flags_and_args(raw_args: Vec<String>) -> (Vec<String>, HashSet<CommandF
 
 ///function to parse a command bind string to a Command enum, with possible arguments
 pub fn parse_command(bind: String, raw_args: Vec<String>) -> Command {
+    //the viewer's args are its own, --force and the like included
+    if bind == COMMAND_VIEW {
+        return Command::View { args: raw_args };
+    }
+    //so is the text to substitute
+    if bind == COMMAND_SUBSTITUTE {
+        return Command::Substitute { args: raw_args };
+    }
     let (args, flags) = parse_flags_and_args(raw_args);
 
     match bind.as_str() {
-        COMMAND_SAVE_DONT_EXIT => Command::Save { args, flags },
+        COMMAND_SAVE_DONT_EXIT | COMMAND_SAVE => Command::Save { args, flags },
         COMMAND_SAVE_AND_EXIT => Command::SaveAndExit { args, flags },
+        COMMAND_SAVE_ALL => Command::SaveAll,
+        COMMAND_SAVE_ALL_AND_EXIT => Command::SaveAllAndExit,
         COMMAND_EXIT_DONT_SAVE => Command::QuitForce,
         COMMAND_HELP => Command::Help,
+        COMMAND_MACROS => Command::Macros,
+        COMMAND_SET => Command::Set { args },
+        COMMAND_MARK => Command::Mark { args },
+        COMMAND_GOTO => Command::Goto { args },
+        COMMAND_MARKS => Command::Marks,
+        COMMAND_INSERT => Command::Insert { args },
+        COMMAND_WORD_COUNT => Command::WordCount,
+        COMMAND_INFO => Command::Info,
+        COMMAND_CALC => Command::Calc { args },
+        COMMAND_CALC_INSERT => Command::CalcInsert { args },
+        COMMAND_REVERSE => Command::Reverse,
+        COMMAND_UNIQUE => Command::Unique,
+        COMMAND_ALIGN => Command::Align { args, flags },
+        COMMAND_REFLOW => Command::Reflow { args },
+        COMMAND_RETAB => Command::Retab { flags },
+        COMMAND_OPEN => Command::Open { args, flags },
+        COMMAND_RECENT => Command::Recent,
+        COMMAND_REOPEN => Command::Reopen,
+        COMMAND_ENCODE => Command::Encode { args },
+        COMMAND_DECODE => Command::Decode { args },
+        COMMAND_INCREMENT => Command::Increment { args },
+        COMMAND_DECREMENT => Command::Decrement { args },
+        COMMAND_SPELL => Command::Spell { args },
+        COMMAND_TODOS => Command::Todos,
+        COMMAND_DIFF => Command::Diff { flags },
+        COMMAND_HEXVIEW => Command::HexView { args },
+        COMMAND_COPY => Command::Copy { flags },
+        COMMAND_UNICODE => Command::Unicode { args },
+        COMMAND_EXTRACT => Command::Extract { args, flags },
+        COMMAND_UNDOLIST => Command::UndoList,
+        COMMAND_UNDO => Command::Undo { args },
+        COMMAND_REDO => Command::Redo { args },
+        COMMAND_BLANK => Command::Blank { args },
+        COMMAND_FOLLOW => Command::Follow { args },
+        COMMAND_CD => Command::Cd { args },
+        COMMAND_PWD => Command::Pwd,
+        COMMAND_SURROUND => Command::Surround { args },
+        COMMAND_UNSURROUND => Command::Unsurround,
+        COMMAND_BOOKMARKS => Command::Bookmarks { args },
+        COMMAND_PENDING => Command::Pending { args },
+        COMMAND_CASE => Command::Case { args },
+        COMMAND_SUSPEND => Command::Suspend,
+        COMMAND_TRIM => Command::Trim,
         _ => Command::Unknown { name: bind, args },
     }
 }
@@ -4,19 +4,83 @@ use crate::core::command_line::commands;
 use crate::core::errors::command_errors::CommandError;
 
 pub fn execute_command(app: &mut App, command: Command) -> Result<(), CommandError> {
+    if let Some(reason) = app.read_only_reason().filter(|_| command.edits_content()) {
+        return Err(CommandError::InvalidState(reason.to_string()));
+    }
+    //whatever a command changes undoes as one step, however many edits it makes
+    if command.edits_content() {
+        app.editor.begin_undo_group();
+        let result = run_command(app, command);
+        app.editor.end_undo_group();
+        return result;
+    }
+    run_command(app, command)
+}
+
+fn run_command(app: &mut App, command: Command) -> Result<(), CommandError> {
     match command {
         Command::Save { args, flags } => commands::file::save_command(app, args, flags),
         Command::SaveAndExit { args, flags } => {
             commands::quit::save_and_exit_command(app, args, flags)
         }
+        Command::SaveAll => commands::file::save_all_command(app),
+        Command::SaveAllAndExit => commands::quit::save_all_and_exit_command(app),
         Command::QuitForce => {
             app.quit();
             Ok(())
         }
+        Command::Suspend => {
+            app.request_suspend();
+            Ok(())
+        }
         Command::Help => {
             // TODO: Show help popup or render help screen
             Ok(())
         }
+        Command::Macros => commands::macros::list_macros_command(app),
+        Command::Set { args } => commands::settings::set_command(app, args),
+        Command::Mark { args } => commands::marks::mark_command(app, args),
+        Command::Goto { args } => commands::marks::goto_command(app, args),
+        Command::Marks => commands::marks::list_marks_command(app),
+        Command::Bookmarks { args } => commands::marks::bookmarks_command(app, args),
+        Command::Pending { args } => commands::quit::pending_command(app, args),
+        Command::Insert { args } => commands::insert::insert_command(app, args),
+        Command::WordCount => commands::stats::word_count_command(app),
+        Command::Info => commands::stats::info_command(app),
+        Command::Calc { args } => commands::numbers::calc_command(app, args, false),
+        Command::CalcInsert { args } => commands::numbers::calc_command(app, args, true),
+        Command::Reverse => commands::lines::reverse_command(app),
+        Command::Unique => commands::lines::unique_command(app),
+        Command::Align { args, flags } => commands::lines::align_command(app, args, flags),
+        Command::Reflow { args } => commands::lines::reflow_command(app, args),
+        Command::Retab { flags } => commands::lines::retab_command(app, flags),
+        Command::Follow { args } => commands::file::follow_command(app, args),
+        Command::Cd { args } => commands::file::cd_command(app, args),
+        Command::Pwd => commands::file::pwd_command(app),
+        Command::Surround { args } => commands::insert::surround_command(app, args),
+        Command::Substitute { args } => commands::lines::substitute_command(app, args),
+        Command::Trim => commands::lines::trim_command(app),
+        Command::Unsurround => commands::insert::unsurround_command(app),
+        Command::Blank { args } => commands::lines::blank_command(app, args),
+        Command::Open { args, flags } => commands::file::open_command(app, args, flags),
+        Command::Recent => commands::file::recent_command(app),
+        Command::Reopen => commands::file::reopen_command(app),
+        Command::Encode { args } => commands::encoding::encode_command(app, args),
+        Command::Decode { args } => commands::encoding::decode_command(app, args),
+        Command::Increment { args } => commands::numbers::increment_command(app, args, 1),
+        Command::Decrement { args } => commands::numbers::increment_command(app, args, -1),
+        Command::Spell { args } => commands::spell::spell_command(app, args),
+        Command::Todos => commands::annotations::todos_command(app),
+        Command::Diff { flags } => commands::file::diff_command(app, flags),
+        Command::HexView { args } => commands::hex::hexview_command(app, args),
+        Command::Copy { flags } => commands::clipboard::copy_command(app, flags),
+        Command::Unicode { args } => commands::insert::unicode_command(app, args),
+        Command::Extract { args, flags } => commands::file::extract_command(app, args, flags),
+        Command::UndoList => commands::undo::undolist_command(app),
+        Command::Undo { args } => commands::undo::undo_command(app, args, false),
+        Command::Redo { args } => commands::undo::undo_command(app, args, true),
+        Command::Case { args } => commands::case::case_command(app, args),
+        Command::View { args } => commands::view::view_command(app, args),
         Command::Unknown { name, .. } => Err(CommandError::UnknownCommand(name)),
     }
 }
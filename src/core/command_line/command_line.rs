@@ -1,10 +1,18 @@
 use super::super::cursor::Cursor;
+use super::completion;
+use crate::config::command_binds::COMMAND_SUBSTITUTE;
+use crate::core::editor::line_index;
 use crate::input::input_action::InputAction;
+use ratatui::text::Span;
 
 #[derive(Debug, Default)]
 pub struct CommandLine {
     pub input: String,
     pub cursor: Cursor,
+    ///candidates of the last tab completion that was ambiguous, shown above the line
+    pub completions: Vec<String>,
+    ///first char shown when the input is wider than the command line
+    pub scroll: usize,
 }
 
 impl CommandLine {
@@ -12,13 +20,19 @@ impl CommandLine {
         Self {
             input: String::new(),
             cursor: Cursor::new(),
+            completions: vec![],
+            scroll: 0,
         }
     }
 
     ///function to handle input action on command line,
     /// responsible for dispatching action to corret internal method.
     pub fn handle_input_action(&mut self, action: InputAction) {
+        if action != InputAction::TAB {
+            self.completions.clear();
+        }
         match action {
+            InputAction::TAB => self.complete(),
             InputAction::MoveCursor(direction) => {
                 let (x, _y) = direction.to_vector();
                 self.move_cursor(x);
@@ -28,64 +42,238 @@ impl CommandLine {
             InputAction::WriteChar(c) => {
                 self.write_char(c);
             }
+            InputAction::InsertText(text) => self.insert_text(&text),
             InputAction::NoOp => {}
             _ => {}
         }
     }
 
-    ///to split command line text into a command and arguments
-    pub fn split_command_bind_and_args(&mut self) -> Result<(String, Vec<String>), String> {
-        let mut command_bind: Option<String> = None;
-        let mut command_args = vec![];
-        let mut parts = self.input.split_whitespace();
-
-        if let Some(first) = parts.next() {
-            if let Some(':') = first.chars().next() {
-                command_bind = Some(first.chars().skip(1).collect());
+    ///to split command line text into a command and arguments. whitespace around the
+    /// text and between tokens is dropped, quoted tokens keep theirs. None if there is no
+    /// command, just the colon or nothing at all.
+    pub fn split_command_bind_and_args(&self) -> Result<Option<(String, Vec<String>)>, String> {
+        let input = self.input.trim();
+        let Some(command) = input.strip_prefix(':') else {
+            if input.is_empty() {
+                return Ok(None);
             }
-        }
+            return Err("No valid command found".to_string());
+        };
+        //a command pasted after the typed colon brings its own
+        let command = command.trim_start();
+        let command = command.strip_prefix(':').unwrap_or(command);
 
-        if let Some(ref cmd) = command_bind {
-            command_args.extend(parts.map(String::from));
-            return Ok((cmd.clone(), command_args));
+        //s/foo/bar/ is taken as it was typed, spaces and quotes in what it replaces included
+        if let Some(substitution) = command
+            .strip_prefix(COMMAND_SUBSTITUTE)
+            .filter(|rest| rest.starts_with(|c: char| c.is_ascii_punctuation()))
+        {
+            return Ok(Some((
+                COMMAND_SUBSTITUTE.to_string(),
+                vec![substitution.to_string()],
+            )));
         }
 
-        Err("No valid command found".to_string())
+        let mut tokens = split_tokens(command).into_iter();
+        Ok(tokens.next().map(|bind| (bind, tokens.collect())))
+    }
+
+    ///empties the input, after a command ran
+    pub fn clear(&mut self) {
+        self.input.clear();
+        self.cursor.x = 0;
+        self.scroll = 0;
+        self.completions.clear();
+    }
+
+    ///completes the word before the cursor. a single candidate replaces it, followed by a
+    /// space unless it is a dir to go on in. several are completed as far as they agree
+    /// and listed in completions.
+    pub fn complete(&mut self) {
+        let x = self.cursor.x.min(line_index::char_count(&self.input));
+        let before = &self.input[..line_index::byte_index(&self.input, x)];
+        let candidates = completion::candidates(before);
+        let replacement = match candidates.as_slice() {
+            [] => return,
+            [only] if only.ends_with('/') => only.clone(),
+            [only] => format!("{} ", only),
+            _ => completion::common_prefix(&candidates).to_string(),
+        };
+        //the command name starts after the colon
+        let word_start = before.rfind(char::is_whitespace).map_or(1, |i| {
+            i + before[i..].chars().next().map_or(1, char::len_utf8)
+        });
+        let word_chars = line_index::char_count(&before[word_start..]);
+        self.input
+            .replace_range(word_start..before.len(), &replacement);
+        self.cursor.x = x - word_chars;
+        self.move_cursor(line_index::char_count(&replacement) as isize);
+        if candidates.len() > 1 {
+            self.completions = candidates;
+        }
     }
 
     //writing
     ///writes char to line, with x position
     pub fn write_char(&mut self, c: char) {
-        let line = &mut self.input;
-        if line.len() < self.cursor.x as usize {
-            self.cursor.x = line.len() as i16;
-        }
-        line.insert(self.cursor.x as usize, c);
-        self.move_cursor(1);
+        self.insert_text(c.encode_utf8(&mut [0; 4]));
     }
+
+    ///inserts text at the cursor and moves the cursor after it. the command line is a
+    /// single line, so line breaks in pasted text become spaces.
+    pub fn insert_text(&mut self, text: &str) {
+        let text = text.replace("\r\n", " ").replace(['\n', '\r'], " ");
+        let x = self.cursor.x.min(line_index::char_count(&self.input));
+        self.input
+            .insert_str(line_index::byte_index(&self.input, x), &text);
+        self.cursor.x = x;
+        self.move_cursor(line_index::char_count(&text) as isize);
+    }
+
     ///backspaces on x position
     pub fn backspace(&mut self) {
-        let line = &mut self.input;
-        if self.cursor.x > 0 && self.cursor.x <= line.len() as i16 {
-            line.remove(self.cursor.x as usize - 1);
+        if self.cursor.x > 0 && self.cursor.x <= line_index::char_count(&self.input) {
+            let byte = line_index::byte_index(&self.input, self.cursor.x - 1);
+            self.input.remove(byte);
             self.move_cursor(-1);
         }
     }
 
     ///deletes on x position
     pub fn delete(&mut self) {
-        let line = &mut self.input;
-        if line.len() > 0 && self.cursor.x < line.len() as i16 {
-            line.remove(self.cursor.x as usize);
+        if self.cursor.x < line_index::char_count(&self.input) {
+            let byte = line_index::byte_index(&self.input, self.cursor.x);
+            self.input.remove(byte);
+        }
+    }
+
+    //scrolling
+    ///scrolls the input the least needed to show the cursor in width columns, off the
+    /// < and > marking input cut off on either side. wide chars, like those of cjk text,
+    /// take two columns.
+    pub fn scroll_to_cursor(&mut self, width: usize) {
+        let widths = self.char_widths();
+        let len = widths.len();
+        let x = self.cursor.x.min(len);
+        let total: usize = widths.iter().sum();
+        //the cursor after the last char takes a column too
+        let cursor_end = widths[..x].iter().sum::<usize>() + widths.get(x).copied().unwrap_or(1);
+        if total < width {
+            self.scroll = 0;
+            return;
+        }
+        //the scroll showing input from the first char starting at or after col on
+        let scroll_from = |col: usize| {
+            let mut start = 0;
+            widths
+                .iter()
+                .take_while(|&&w| {
+                    start += w;
+                    start - w < col
+                })
+                .count()
+        };
+        //too narrow for markers, the cursor is kept in the last column
+        if width < 3 {
+            self.scroll = scroll_from(cursor_end.saturating_sub(width));
+            return;
+        }
+        let start: usize = widths[..self.scroll.min(len)].iter().sum();
+        if x <= self.scroll {
+            self.scroll = x.saturating_sub(1);
+        } else if x == len {
+            self.scroll = self.scroll.max(scroll_from(total + 1 - width));
+        } else if cursor_end > start + width - 1 {
+            self.scroll = scroll_from(cursor_end + 1 - width);
+        }
+        //deleting chars pulls the input back in instead of leaving the end empty
+        self.scroll = self.scroll.min(scroll_from(total + 1 - width));
+    }
+
+    ///the part of the input shown in width columns from scroll on, with < and > where
+    /// input is cut off, and the column of the cursor in it
+    pub fn visible(&self, width: usize) -> (String, usize) {
+        let widths = self.char_widths();
+        let len = widths.len();
+        let mut shown: Vec<String> = vec![];
+        let mut used = 0;
+        for (c, &w) in self.input.chars().zip(&widths).skip(self.scroll) {
+            if used + w > width {
+                break;
+            }
+            shown.push(c.to_string());
+            used += w;
         }
+        //a marker over a wide char keeps its columns, so the chars after it don't move
+        if width >= 3 {
+            if self.scroll > 0 {
+                if let Some(first) = shown.first_mut() {
+                    *first = format!("<{}", " ".repeat(str_width(first).saturating_sub(1)));
+                }
+            }
+            if self.scroll + shown.len() < len {
+                match shown.last_mut() {
+                    //a wide char that didn't fit left its column free
+                    Some(_) if used < width => shown.push(">".to_string()),
+                    Some(last) => {
+                        *last = format!("{}>", " ".repeat(str_width(last).saturating_sub(1)))
+                    }
+                    None => {}
+                }
+            }
+        }
+        let start: usize = widths[..self.scroll.min(len)].iter().sum();
+        let col: usize = widths[..self.cursor.x.min(len)].iter().sum();
+        (shown.concat(), col.saturating_sub(start))
+    }
+
+    ///columns each char of the input takes on the terminal
+    fn char_widths(&self) -> Vec<usize> {
+        self.input.chars().map(char_width).collect()
     }
 
     //cursor
-    ///moves cursor by x amounts in commandline
-    pub fn move_cursor(&mut self, x: i16) {
-        let max_x_pos: i16 = self.input.len() as i16;
-        self.cursor.x = (self.cursor.x + x).clamp(0, max_x_pos);
+    ///moves cursor by x amounts in commandline, x counts chars
+    pub fn move_cursor(&mut self, x: isize) {
+        let max_x_pos = line_index::char_count(&self.input);
+        self.cursor.x = self.cursor.x.saturating_add_signed(x).min(max_x_pos);
+    }
+}
+
+///splits text into whitespace separated tokens. a token wrapped in matching quotes keeps
+/// its whitespace, without the quotes. a quote that isn't closed is a plain char, like
+/// the one in :surround "
+fn split_tokens(text: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+        let quoted = rest
+            .chars()
+            .next()
+            .filter(|&c| c == '"' || c == '\'')
+            .and_then(|quote| {
+                let inner = &rest[1..];
+                let end = inner.find(quote)?;
+                let after = &inner[end + 1..];
+                let ends_token = after.is_empty() || after.starts_with(char::is_whitespace);
+                ends_token.then(|| (&inner[..end], after))
+            });
+        let (token, after) = quoted
+            .unwrap_or_else(|| rest.split_at(rest.find(char::is_whitespace).unwrap_or(rest.len())));
+        tokens.push(token.to_string());
+        rest = after.trim_start();
     }
+    tokens
+}
+
+///columns c takes on the terminal
+fn char_width(c: char) -> usize {
+    str_width(c.encode_utf8(&mut [0; 4]))
+}
+
+///columns text takes on the terminal, as ratatui draws it
+fn str_width(text: &str) -> usize {
+    Span::raw(text).width()
 }
 
 //████████╗███████╗███████╗████████╗███████╗
@@ -97,6 +285,8 @@ impl CommandLine {
 #[cfg(test)]
 mod unit_commandline_command_line_tests {
     use super::super::super::super::core::command_line::CommandLine;
+    use super::str_width;
+    use crate::input::input_action::{Direction, InputAction};
 
     fn create_command_line_with_command_input(s: String) -> CommandLine {
         let mut command_line = CommandLine::new();
@@ -167,4 +357,130 @@ mod unit_commandline_command_line_tests {
         assert_eq!(command_line.input, "Tet");
         assert_eq!(command_line.cursor.x, 2);
     }
+
+    //pasting into command line
+    #[test]
+    fn test_insert_text_with_multi_byte_chars_mid_input() {
+        let mut command_line = create_command_line_with_command_input(":e ".to_string());
+        command_line.cursor.x = 3;
+        command_line.insert_text("ünï/\r\nf.txt\n");
+        assert_eq!(command_line.input, ":e ünï/ f.txt ");
+        assert_eq!(command_line.cursor.x, 14);
+
+        command_line.cursor.x = 5;
+        command_line.write_char('→');
+        command_line.backspace();
+        command_line.backspace();
+        command_line.delete();
+        assert_eq!(command_line.input, ":e ü/ f.txt ");
+        assert_eq!(command_line.cursor.x, 4);
+    }
+
+    //tab completion
+    #[test]
+    fn test_single_candidate_completes_with_a_space() {
+        let mut command_line = create_command_line_with_command_input(":set mod".to_string());
+        command_line.cursor.x = 8;
+        command_line.complete();
+        assert_eq!(command_line.input, ":set modal ");
+        assert_eq!(command_line.cursor.x, 11);
+        assert!(command_line.completions.is_empty());
+
+        command_line.write_char('o');
+        command_line.complete();
+        assert_eq!(command_line.input, ":set modal o");
+        assert_eq!(command_line.completions, vec!["off", "on"]);
+    }
+
+    #[test]
+    fn test_ambiguous_command_name_completes_common_part() {
+        let mut command_line = create_command_line_with_command_input(":u x".to_string());
+        command_line.cursor.x = 2;
+        command_line.complete();
+        assert_eq!(command_line.input, ":un x");
+        assert_eq!(command_line.cursor.x, 3);
+        assert_eq!(command_line.completions.len(), 5);
+
+        command_line.handle_input_action(InputAction::MoveCursor(Direction::Left));
+        assert!(command_line.completions.is_empty());
+    }
+
+    //scrolling long input
+    #[test]
+    fn test_short_input_is_not_scrolled() {
+        let mut command_line = create_command_line_with_command_input(":set".to_string());
+        command_line.cursor.x = 4;
+        command_line.scroll_to_cursor(10);
+        assert_eq!(command_line.visible(10), (":set".to_string(), 4));
+    }
+
+    #[test]
+    fn test_long_input_scrolls_with_the_cursor_and_marks_cut_off_sides() {
+        let input: String = ":".chars().chain(('a'..='z').cycle().take(29)).collect();
+        let mut command_line = create_command_line_with_command_input(input);
+        command_line.cursor.x = 30;
+        command_line.scroll_to_cursor(10);
+        //the cursor after the end takes the last column
+        assert_eq!(command_line.visible(10), ("<vwxyzabc".to_string(), 9));
+
+        //moving left keeps the cursor off the < marker
+        command_line.move_cursor(-8);
+        command_line.scroll_to_cursor(10);
+        assert_eq!(command_line.visible(10), ("<vwxyzabc".to_string(), 1));
+        command_line.move_cursor(-1);
+        command_line.scroll_to_cursor(10);
+        assert_eq!(command_line.visible(10), ("<uvwxyzabc".to_string(), 1));
+        command_line.move_cursor(-1);
+        command_line.scroll_to_cursor(10);
+        assert_eq!(command_line.visible(10), ("<tuvwxyza>".to_string(), 1));
+
+        command_line.move_cursor(-30);
+        command_line.scroll_to_cursor(10);
+        assert_eq!(command_line.visible(10), (":abcdefgh>".to_string(), 0));
+
+        //moving right stops before the > marker
+        command_line.move_cursor(9);
+        command_line.scroll_to_cursor(10);
+        assert_eq!(command_line.visible(10), ("<bcdefghi>".to_string(), 8));
+    }
+
+    #[test]
+    fn test_wide_chars_scroll_by_the_columns_they_take() {
+        let mut command_line = CommandLine::new();
+        for c in format!(":{}", "日本語".repeat(4)).chars() {
+            command_line.handle_input_action(InputAction::WriteChar(c));
+        }
+        command_line.scroll_to_cursor(10);
+        //the marker keeps both columns of the char it covers
+        assert_eq!(command_line.visible(10), ("< 日本語".to_string(), 8));
+
+        command_line.move_cursor(-13);
+        command_line.scroll_to_cursor(10);
+        //the column a wide char didn't fit into takes the marker
+        assert_eq!(command_line.visible(10), (":日本語日>".to_string(), 0));
+
+        //the cursor is on 本, drawn in the 7th column
+        command_line.move_cursor(5);
+        command_line.scroll_to_cursor(10);
+        assert_eq!(command_line.visible(10), ("< 語日本 >".to_string(), 6));
+        for x in 0..=13 {
+            command_line.cursor.x = x;
+            command_line.scroll_to_cursor(10);
+            let (shown, col) = command_line.visible(10);
+            assert!(str_width(&shown) <= 10 && col < 10, "{} {}", shown, col);
+        }
+    }
+
+    #[test]
+    fn test_deleting_pulls_scrolled_input_back_in() {
+        let mut command_line = create_command_line_with_command_input(":".repeat(20));
+        command_line.cursor.x = 20;
+        command_line.scroll_to_cursor(10);
+        assert_eq!(command_line.scroll, 11);
+        command_line.input.truncate(12);
+        command_line.cursor.x = 12;
+        command_line.scroll_to_cursor(10);
+        assert_eq!(command_line.scroll, 3);
+        assert_eq!(command_line.visible(10).1, 9);
+    }
 }
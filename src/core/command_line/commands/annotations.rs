@@ -0,0 +1,73 @@
+//annotation commands: listing keywords like TODO in the buffer
+
+use crate::core::app::{App, PendingState};
+use crate::core::editor::annotations;
+use crate::core::errors::command_errors::CommandError;
+use crate::ui::popups::list_popup::ListPopup;
+
+///opens a popup listing every annotation in the buffer, picking one jumps to it
+pub fn todos_command(app: &mut App) -> Result<(), CommandError> {
+    let found = annotations::collect(
+        &app.editor.editor_content,
+        &app.settings.annotation_keywords,
+        &app.settings.word_chars,
+    );
+    if found.is_empty() {
+        app.set_status(format!(
+            "no {} found",
+            app.settings.annotation_keywords.join(", ")
+        ));
+        return Ok(());
+    }
+    let items = found
+        .iter()
+        .map(|annotation| annotation.describe())
+        .collect();
+    let positions = found.iter().map(|annotation| annotation.pos).collect();
+    app.open_popup(Box::new(ListPopup::new("Annotations", items)));
+    app.pending_states
+        .push(PendingState::PickingAnnotation(positions));
+    Ok(())
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_annotations_command_tests {
+    use super::*;
+    use crate::core::cursor::CursorPosition;
+    use crate::ui::popups::popup::PopupResult;
+
+    fn create_app(lines: &[&str]) -> App {
+        let mut app = App::new();
+        app.editor.editor_content = lines.iter().map(|l| l.to_string()).collect();
+        app.editor.editor_height = 10;
+        app
+    }
+
+    #[test]
+    fn test_picking_a_todo_jumps_to_it() {
+        let mut app = create_app(&["// TODO one", "code", "  x // FIXME two"]);
+        todos_command(&mut app).unwrap();
+        assert!(app.popup.is_some());
+
+        app.popup_result = PopupResult::Index(1);
+        app.handle_list_popup_response();
+        assert!(app.popup.is_none());
+        assert!(app.pending_states.is_empty());
+        assert_eq!(app.editor.cursor(), CursorPosition::new(7, 2));
+    }
+
+    #[test]
+    fn test_no_todos_sets_status() {
+        let mut app = create_app(&["all done"]);
+        app.settings.annotation_keywords = vec!["TODO".to_string()];
+        todos_command(&mut app).unwrap();
+        assert!(app.popup.is_none());
+        assert_eq!(app.status(), Some("no TODO found"));
+    }
+}
@@ -0,0 +1,95 @@
+//case commands: converting the selection or the name under the cursor between letter
+// cases and naming conventions
+
+use crate::core::app::App;
+use crate::core::editor::case::{Case, CASE_NAMES};
+use crate::core::errors::command_errors::CommandError;
+
+///converts the selection, or the name under the cursor without one, to the case given:
+/// upper, lower, title, snake, camel, kebab or pascal
+pub fn case_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    let Some(case) = args.first().and_then(|name| Case::parse(name)) else {
+        return Err(CommandError::InvalidArguments {
+            command: "case".to_string(),
+            reason: format!("expected one of {}", CASE_NAMES.join(", ")),
+        });
+    };
+    match app.editor.convert_case(case) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(CommandError::ExecutionFailed(
+            "nothing to convert, select text or move onto a name".to_string(),
+        )),
+        Err(e) => Err(CommandError::ExecutionFailed(e.to_string())),
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_case_command_tests {
+    use super::*;
+    use crate::core::position::{Position, Range};
+
+    fn create_app(lines: &[&str]) -> App {
+        let mut app = App::new();
+        app.editor.editor_content = lines.iter().map(|l| l.to_string()).collect();
+        app.editor.editor_height = 10;
+        app
+    }
+
+    fn case(app: &mut App, name: &str) -> Result<(), CommandError> {
+        case_command(app, vec![name.to_string()])
+    }
+
+    #[test]
+    fn test_converts_the_name_under_the_cursor_as_one_undo_step() {
+        let mut app = create_app(&["let HTTPServer = 1;"]);
+        app.editor.cursor.x = 6;
+        case(&mut app, "snake").unwrap();
+        assert_eq!(app.editor.editor_content, vec!["let http_server = 1;"]);
+        assert_eq!(app.editor.cursor.x, 6);
+        assert_eq!(app.editor.selection, None);
+
+        //the cursor stays in a name that got shorter
+        app.editor.cursor.x = 15;
+        case(&mut app, "camel").unwrap();
+        assert_eq!(app.editor.editor_content, vec!["let httpServer = 1;"]);
+        assert_eq!(app.editor.cursor.x, 14);
+
+        app.editor.undo().unwrap();
+        assert_eq!(app.editor.editor_content, vec!["let http_server = 1;"]);
+    }
+
+    #[test]
+    fn test_converts_the_selection_and_keeps_it_selected() {
+        let mut app = create_app(&["fooBar bazQux", "next"]);
+        app.editor.selection = Some(Range::new(Position::new(0, 0), Position::new(0, 13)));
+        case(&mut app, "kebab").unwrap();
+        assert_eq!(app.editor.editor_content, vec!["foo-bar-baz-qux", "next"]);
+        assert_eq!(
+            app.editor.selection,
+            Some(Range::new(Position::new(0, 0), Position::new(0, 15)))
+        );
+        app.editor.undo().unwrap();
+        assert_eq!(app.editor.editor_content, vec!["fooBar bazQux", "next"]);
+    }
+
+    #[test]
+    fn test_refuses_unknown_cases_and_nothing_to_convert() {
+        let mut app = create_app(&["a = b"]);
+        assert!(matches!(
+            case(&mut app, "screaming"),
+            Err(CommandError::InvalidArguments { .. })
+        ));
+        app.editor.cursor.x = 2;
+        assert!(matches!(
+            case(&mut app, "snake"),
+            Err(CommandError::ExecutionFailed(_))
+        ));
+        assert_eq!(app.editor.editor_content, vec!["a = b"]);
+    }
+}
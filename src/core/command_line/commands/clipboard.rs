@@ -0,0 +1,71 @@
+//clipboard commands: copying the selection, replacing or adding to the clipboard
+
+use crate::core::app::App;
+use crate::core::command_line::command::CommandFlag;
+use crate::core::errors::command_errors::CommandError;
+use std::collections::HashSet;
+
+///copies the selection to the clipboard, with --append below what it already holds
+pub fn copy_command(app: &mut App, flags: HashSet<CommandFlag>) -> Result<(), CommandError> {
+    let no_selection = |e| CommandError::InvalidState(format!("{}", e));
+    if flags.contains(&CommandFlag::Append) {
+        app.editor.copy_append().map_err(no_selection)?;
+        app.report_clipboard_append();
+    } else {
+        app.editor.copy().map_err(no_selection)?;
+        let lines = app.editor.clipboard.copied_text.len();
+        app.set_status(format!("copied {} lines", lines));
+    }
+    Ok(())
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_clipboard_command_tests {
+    use super::*;
+    use crate::core::position::{Position, Range};
+    use crate::input::input_action::InputAction;
+
+    fn create_app(lines: &[&str]) -> App {
+        let mut app = App::new();
+        app.editor.editor_content = lines.iter().map(|l| l.to_string()).collect();
+        app
+    }
+
+    fn select(app: &mut App, start: (usize, usize), end: (usize, usize)) {
+        let range = Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1));
+        app.editor.set_selection(range).unwrap();
+    }
+
+    #[test]
+    fn test_append_collects_snippets_below_each_other() {
+        let mut app = create_app(&["alpha beta", "gamma", "delta"]);
+        select(&mut app, (0, 0), (0, 5));
+        copy_command(&mut app, HashSet::new()).unwrap();
+        assert_eq!(app.status(), Some("copied 1 lines"));
+
+        select(&mut app, (1, 0), (2, 3));
+        copy_command(&mut app, HashSet::from([CommandFlag::Append])).unwrap();
+        assert_eq!(app.status(), Some("appended (now 3 lines in clipboard)"));
+        assert_eq!(app.editor.clipboard.paste(), vec!["alpha", "gamma", "del"]);
+
+        //a regular copy replaces the collected snippets
+        select(&mut app, (0, 6), (0, 10));
+        app.process_input_action(InputAction::COPY);
+        assert_eq!(app.editor.clipboard.paste(), vec!["beta"]);
+    }
+
+    #[test]
+    fn test_append_key_reports_in_status_and_needs_a_selection() {
+        let mut app = create_app(&["one two"]);
+        select(&mut app, (0, 4), (0, 7));
+        app.process_input_action(InputAction::CopyAppend);
+        assert_eq!(app.status(), Some("appended (now 1 lines in clipboard)"));
+        assert!(copy_command(&mut app, HashSet::from([CommandFlag::Append])).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+//encoding related commands: base64 and url encoding of the selection
+
+use crate::core::app::App;
+use crate::core::errors::command_errors::CommandError;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+///encodes the selected text in place, expects the encoding: base64 or url
+pub fn encode_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    transform_selection(app, "encode", &args, |encoding, text| match encoding {
+        "base64" => Ok(base64_encode(text.as_bytes())),
+        _ => Ok(url_encode(text)),
+    })
+}
+
+///decodes the selected text in place, expects the encoding: base64 or url.
+/// invalid input leaves the text as it is.
+pub fn decode_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    transform_selection(app, "decode", &args, |encoding, text| {
+        let bytes = match encoding {
+            "base64" => base64_decode(text)?,
+            _ => url_decode(text)?,
+        };
+        String::from_utf8(bytes).map_err(|_| "decoded text is not valid UTF-8".to_string())
+    })
+}
+
+///replaces the selection with the result of transform for the encoding named in args
+fn transform_selection(
+    app: &mut App,
+    command: &str,
+    args: &[String],
+    transform: impl FnOnce(&str, &str) -> Result<String, String>,
+) -> Result<(), CommandError> {
+    let encoding = match args.first().map(String::as_str) {
+        Some(encoding @ ("base64" | "url")) => encoding,
+        _ => {
+            return Err(CommandError::InvalidArguments {
+                command: command.to_string(),
+                reason: "expected base64 or url".to_string(),
+            })
+        }
+    };
+    let text = app
+        .editor
+        .selected_text()
+        .ok_or_else(|| CommandError::InvalidState("no text selected".to_string()))?;
+
+    let result = transform(encoding, &text).map_err(CommandError::ExecutionFailed)?;
+    app.editor
+        .replace_selected_text(&result)
+        .map_err(|e| CommandError::ExecutionFailed(e.to_string()))
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+///decodes base64, ignoring whitespace so wrapped input works
+fn base64_decode(text: &str) -> Result<Vec<u8>, String> {
+    let chars: Vec<u8> = text.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+    if !chars.len().is_multiple_of(4) {
+        return Err("invalid base64 length".to_string());
+    }
+
+    let mut out = vec![];
+    for (index, chunk) in chars.chunks(4).enumerate() {
+        let is_last = index == chars.len() / 4 - 1;
+        let padding = chunk.iter().rev().take_while(|b| **b == b'=').count();
+        if padding > 2 || (padding > 0 && !is_last) {
+            return Err("invalid base64 padding".to_string());
+        }
+
+        let mut n = 0u32;
+        for &b in &chunk[..4 - padding] {
+            let value = BASE64_ALPHABET
+                .iter()
+                .position(|a| *a == b)
+                .ok_or_else(|| format!("invalid base64 character '{}'", b as char))?;
+            n = n << 6 | value as u32;
+        }
+        n <<= 6 * padding;
+        out.extend(&[(n >> 16) as u8, (n >> 8) as u8, n as u8][..3 - padding]);
+    }
+    Ok(out)
+}
+
+///percent encodes everything besides unreserved url characters
+fn url_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn url_decode(text: &str) -> Result<Vec<u8>, String> {
+    let bytes = text.as_bytes();
+    let mut out = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = bytes
+                .get(i + 1..i + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| format!("invalid percent escape at {}", i))?;
+            out.push(hex);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Ok(out)
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_encoding_command_tests {
+    use super::*;
+    use crate::core::position::{Position, Range};
+
+    fn create_app_with_selection(
+        lines: &[&str],
+        start: (usize, usize),
+        end: (usize, usize),
+    ) -> App {
+        let mut app = App::new();
+        app.editor.editor_content = lines.iter().map(|l| l.to_string()).collect();
+        app.editor.editor_height = 10;
+        app.editor.selection = Some(Range::new(
+            Position::new(start.1, start.0),
+            Position::new(end.1, end.0),
+        ));
+        app
+    }
+
+    fn arg(s: &str) -> Vec<String> {
+        vec![s.to_string()]
+    }
+
+    #[test]
+    fn test_base64_round_trip() {
+        for text in ["", "f", "fo", "foo", "foob", "hello wörld"] {
+            let encoded = base64_encode(text.as_bytes());
+            assert_eq!(base64_decode(&encoded).unwrap(), text.as_bytes());
+        }
+        assert_eq!(base64_encode(b"foob"), "Zm9vYg==");
+        assert!(base64_decode("Zm9=vYg=").is_err());
+        assert!(base64_decode("Zm9").is_err());
+    }
+
+    #[test]
+    fn test_url_round_trip() {
+        assert_eq!(url_encode("a b/ø"), "a%20b%2F%C3%B8");
+        assert_eq!(url_decode("a%20b%2F%C3%B8").unwrap(), "a b/ø".as_bytes());
+        assert!(url_decode("%2").is_err());
+    }
+
+    #[test]
+    fn test_encode_multi_line_selection_keeps_it_selected() {
+        let mut app = create_app_with_selection(&["x ab", "cd y"], (2, 0), (2, 1));
+        encode_command(&mut app, arg("base64")).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["x YWIKY2Q= y"]);
+        assert_eq!(
+            app.editor.selection.map(|selection| selection.end),
+            Some(Position::new(0, 10))
+        );
+
+        decode_command(&mut app, arg("base64")).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["x ab", "cd y"]);
+
+        app.editor.undo().unwrap();
+        assert_eq!(app.editor.editor_content, vec!["x YWIKY2Q= y"]);
+    }
+
+    #[test]
+    fn test_invalid_decode_leaves_buffer() {
+        let mut app = create_app_with_selection(&["%zz"], (0, 0), (3, 0));
+        assert!(matches!(
+            decode_command(&mut app, arg("url")),
+            Err(CommandError::ExecutionFailed(_))
+        ));
+        assert_eq!(app.editor.editor_content, vec!["%zz"]);
+    }
+
+    #[test]
+    fn test_requires_selection_and_encoding() {
+        let mut app = create_app_with_selection(&["a"], (0, 0), (1, 0));
+        assert!(encode_command(&mut app, arg("rot13")).is_err());
+        app.editor.clear_selection();
+        assert!(encode_command(&mut app, arg("url")).is_err());
+    }
+}
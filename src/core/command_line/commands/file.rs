@@ -3,48 +3,498 @@
 use crate::core::app::App;
 use crate::core::errors::command_errors::CommandError;
 use std::collections::HashSet;
+use tracing::info;
 
 use crate::core::app::PendingState;
 use crate::core::command_line::command::CommandFlag;
-use crate::ui::popups::confirmation_popup::ConfirmationPopup;
+use crate::core::diff::{self, DiffStat};
+use crate::core::file_io;
+use crate::ui::popups::diff_popup::DiffPopup;
 use crate::ui::popups::popup::PopupResult;
-use std::path::Path;
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
 
+///saves to the path given or the current file. a changed file is only overwritten and
+/// a missing directory only created once confirmed, --force skips both questions and
+/// --mkdir the second one. an untitled buffer is named first, --force writes it to untitled.
 pub fn save_command(
     app: &mut App,
     args: Vec<String>,
     flags: HashSet<CommandFlag>,
 ) -> Result<(), CommandError> {
-    let path = if !args.is_empty() {
-        args.first().unwrap().clone()
+    let force = flags.contains(&CommandFlag::Force);
+    let path = if let Some(arg) = args.first() {
+        arg.clone()
     } else if let Some(current) = app.file_path.clone() {
         current
-    } else {
+    } else if force {
+        //scripts without a name to give still get the file they always did
         "untitled".to_string()
+    } else {
+        info!("save asks for a file name");
+        app.queue_pending(PendingState::NamingFile);
+        return Ok(());
     };
 
-    let new_content = app.editor.editor_content.join("\n");
     let path_ref = Path::new(&path);
+    //nothing is asked about a path that can't be written anyway
+    app.check_write_allowed(&path)
+        .map_err(|e| CommandError::ExecutionFailed(e.to_string()))?;
+
+    //a typo in a path shouldn't quietly create directories, scripts skip the question
+    let creates_dirs = force || flags.contains(&CommandFlag::Mkdir);
+    if !creates_dirs && file_io::missing_parent(&path).is_some() {
+        info!(path = %path, "save asks to create the directory");
+        app.queue_pending(PendingState::CreatingDirectory(path));
+        return Ok(());
+    }
+    if creates_dirs {
+        file_io::create_parent_dirs(&path)
+            .map_err(|e| CommandError::ExecutionFailed(e.to_string()))?;
+    }
 
     // If flag force is not inputted and file exists and is different, prompt confirmation
-    if !flags.contains(&CommandFlag::Force) && path_ref.exists() {
-        let has_changes = app.file_has_changes(new_content.clone(), path.clone());
+    if !force && path_ref.exists() {
+        let has_changes = app
+            .file_has_changes(&path)
+            .map_err(|e| CommandError::ExecutionFailed(e.to_string()))?;
 
-        if has_changes.unwrap() && app.popup_result == PopupResult::None {
-            let popup = Box::new(ConfirmationPopup::new("Confirm Overwrite of file"));
-            app.open_popup(popup);
-            app.pending_states.push(PendingState::Saving(path));
+        if has_changes && app.popup_result == PopupResult::None {
+            info!(path = %path, has_changes, force, "save asks to overwrite");
+            app.queue_pending(PendingState::Saving(path));
             return Ok(());
         }
     }
+    info!(path = %path, force, "save needs no confirmation");
     //confirmation wasn't needed, try to save file,
     //if it fails return error else return Ok()
-    if let Err(e) = app.save_to_path(path) {
-        Err(CommandError::ExecutionFailed(format!(
+    match app.save(path.clone()) {
+        Err(e) if app.can_save_elevated(&e) => {
+            app.queue_pending(PendingState::SavingElevated(path));
+            Ok(())
+        }
+        Err(e) => Err(CommandError::ExecutionFailed(format!(
             "failed to save file: {}",
             e
-        )))
-    } else {
-        Ok(())
+        ))),
+        Ok(()) => Ok(()),
+    }
+}
+
+///saves every named buffer with unsaved changes, reporting how many were saved and
+/// which failed. files changed on disk since they were read are asked about one by one.
+pub fn save_all_command(app: &mut App) -> Result<(), CommandError> {
+    app.save_all();
+    Ok(())
+}
+
+///opens the file at the path given, refusing to drop unsaved changes without --force
+pub fn open_command(
+    app: &mut App,
+    args: Vec<String>,
+    flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    if args.is_empty() {
+        return Err(CommandError::InvalidArguments {
+            command: "open".to_string(),
+            reason: "expected a file path".to_string(),
+        });
+    }
+    if !flags.contains(&CommandFlag::Force) && app.has_unsaved_changes() {
+        return Err(CommandError::InvalidState(
+            "unsaved changes, save first or use --force".to_string(),
+        ));
+    }
+    app.open_file(args.join(" "))
+        .map_err(|e| CommandError::ExecutionFailed(e.to_string()))
+}
+
+///changes the working dir to the dir given, or the home dir without one. a leading ~
+/// is the home dir.
+pub fn cd_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let dir = match args.join(" ") {
+        arg if arg.is_empty() || arg == "~" => home,
+        arg => match arg.strip_prefix("~/") {
+            Some(rest) => home.map(|home| home.join(rest)),
+            None => Some(PathBuf::from(arg)),
+        },
+    }
+    .ok_or_else(|| CommandError::ExecutionFailed("HOME is not set".to_string()))?;
+    if !dir.is_dir() {
+        return Err(CommandError::InvalidArguments {
+            command: "cd".to_string(),
+            reason: format!("'{}' is not a directory", dir.display()),
+        });
+    }
+    app.change_dir(&dir)
+        .map_err(|e| CommandError::ExecutionFailed(e.to_string()))?;
+    pwd_command(app)
+}
+
+///shows the working dir in the command line
+pub fn pwd_command(app: &mut App) -> Result<(), CommandError> {
+    let dir = env::current_dir().map_err(|e| CommandError::ExecutionFailed(e.to_string()))?;
+    app.set_status(dir.display().to_string());
+    Ok(())
+}
+
+///follows the open file like tail -f, appending the lines written to it while the buffer
+/// is read-only. expects on or off, toggling without.
+pub fn follow_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    let on = match args.first().map(String::as_str) {
+        None => app.follow.is_none(),
+        Some("on") => true,
+        Some("off") => false,
+        Some(other) => {
+            return Err(CommandError::InvalidArguments {
+                command: "follow".to_string(),
+                reason: format!("unknown follow '{}', expected on or off", other),
+            })
+        }
+    };
+    if !on {
+        if let Some(follow) = app.follow.take() {
+            app.set_status(format!("stopped following {}", follow.path));
+        }
+        return Ok(());
+    }
+    if app.follow.is_some() {
+        return Ok(());
+    }
+    let Some(path) = app.file_path.clone() else {
+        return Err(CommandError::MissingContext(
+            "following needs a file, save the buffer first".to_string(),
+        ));
+    };
+    if app.hex_view.is_some() {
+        return Err(CommandError::InvalidState(
+            "the hex view is on, :hexview off first".to_string(),
+        ));
+    }
+    //the file is reloaded, which would drop them
+    if app.has_unsaved_changes() {
+        return Err(CommandError::InvalidState(
+            "unsaved changes, save them before following".to_string(),
+        ));
+    }
+    app.follow_file(path.clone())
+        .map_err(|e| CommandError::ExecutionFailed(e.to_string()))?;
+    app.set_status(format!("following {}", path));
+    Ok(())
+}
+
+///shows how the buffer differs from its file as a unified diff, or only the counts of
+/// added and removed lines with --stat. an untitled buffer or missing file is all added.
+pub fn diff_command(app: &mut App, flags: HashSet<CommandFlag>) -> Result<(), CommandError> {
+    let disk = match &app.file_path {
+        Some(path) => read_disk_lines(path)?,
+        None => vec![],
+    };
+    let buffer = &app.text_editor().editor_content;
+    let name = app.file_path.as_deref().unwrap_or("untitled");
+
+    if flags.contains(&CommandFlag::Stat) {
+        let stat = DiffStat::of(&diff::diff(&disk, buffer));
+        app.set_status(format!("+{} −{} lines", stat.added, stat.removed));
+        return Ok(());
+    }
+    let lines = diff::unified(&disk, buffer, name, &format!("{} (buffer)", name));
+    let title = format!("Diff {}", name);
+    app.open_popup(Box::new(DiffPopup::new(&title, lines)));
+    Ok(())
+}
+
+///lines of the file at path like they are loaded, none if it doesn't exist
+fn read_disk_lines(path: &str) -> Result<Vec<String>, CommandError> {
+    match fs::read_to_string(path) {
+        Ok(contents) if contents.is_empty() => Ok(vec![String::new()]),
+        Ok(contents) => Ok(contents.lines().map(String::from).collect()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(CommandError::ExecutionFailed(format!(
+            "could not read {}: {}",
+            path, e
+        ))),
+    }
+}
+
+///writes the selection to the file at the path given, asking before it overwrites one or
+/// creates a missing directory, --force skips both questions and --mkdir the second one.
+/// with --delete the selection is then cut from the buffer, only once the write succeeded.
+pub fn extract_command(
+    app: &mut App,
+    args: Vec<String>,
+    flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    if args.is_empty() {
+        return Err(CommandError::InvalidArguments {
+            command: "extract".to_string(),
+            reason: "expected a file path".to_string(),
+        });
+    }
+    if !app.editor.is_text_selected() {
+        return Err(CommandError::InvalidState(
+            "no text selected, select the text to extract first".to_string(),
+        ));
+    }
+    let path = args.join(" ");
+    let delete = flags.contains(&CommandFlag::Delete);
+    app.check_write_allowed(&path)
+        .map_err(|e| CommandError::ExecutionFailed(e.to_string()))?;
+    let force = flags.contains(&CommandFlag::Force);
+    //like :w, a missing directory is only created once confirmed or asked for
+    if force || flags.contains(&CommandFlag::Mkdir) {
+        file_io::create_parent_dirs(&path)
+            .map_err(|e| CommandError::ExecutionFailed(e.to_string()))?;
+    } else if file_io::missing_parent(&path).is_some() {
+        app.queue_pending(PendingState::CreatingExtractDirectory { path, delete });
+        return Ok(());
+    }
+    if !force && Path::new(&path).exists() {
+        app.queue_pending(PendingState::Extracting { path, delete });
+        return Ok(());
+    }
+    app.extract_selection(path, delete)
+        .map_err(|e| CommandError::ExecutionFailed(e.to_string()))
+}
+
+///opens the recent files picker
+pub fn recent_command(app: &mut App) -> Result<(), CommandError> {
+    app.show_recent_files();
+    Ok(())
+}
+
+///brings back the buffer the last :open replaced
+pub fn reopen_command(app: &mut App) -> Result<(), CommandError> {
+    if app.closed_buffers.is_empty() {
+        return Err(CommandError::InvalidState(
+            "no closed buffer to reopen".to_string(),
+        ));
+    }
+    app.reopen_buffer()
+        .map_err(|e| CommandError::ExecutionFailed(e.to_string()))
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_file_command_tests {
+    use super::*;
+    use crate::core::position::{Position, Range};
+    use tempfile::tempdir;
+
+    fn stat_flag() -> HashSet<CommandFlag> {
+        HashSet::from([CommandFlag::Stat])
+    }
+
+    #[test]
+    fn test_save_asks_before_creating_missing_directory() {
+        let dir = tempdir().unwrap();
+        let typo = dir.path().join("srx");
+        let path = typo.join("main.rs").to_str().unwrap().to_string();
+        let mut app = App::new();
+        app.editor.editor_content = vec!["fn main() {}".to_string()].into();
+
+        save_command(&mut app, vec![path.clone()], HashSet::new()).unwrap();
+        assert_eq!(
+            app.pending_states,
+            vec![PendingState::CreatingDirectory(path.clone())]
+        );
+        app.popup_result = PopupResult::Bool(false);
+        app.handle_confirmation_popup_response();
+        assert!(!typo.exists());
+        assert!(app.status().unwrap().starts_with("not saved"));
+        assert!(app.pending_states.is_empty());
+
+        save_command(
+            &mut app,
+            vec![path.clone()],
+            HashSet::from([CommandFlag::Mkdir]),
+        )
+        .unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fn main() {}");
+    }
+
+    #[test]
+    fn test_diff_stat_counts_changed_lines_against_disk() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        fs::write(&path, "one\ntwo\nthree\nfour").unwrap();
+        let mut app = App::new();
+        app.open_file(path.to_str().unwrap().to_string()).unwrap();
+        app.editor.editor_content = ["one", "2", "three", "four", "five", "six"]
+            .iter()
+            .map(|l| l.to_string())
+            .collect();
+
+        diff_command(&mut app, stat_flag()).unwrap();
+        assert_eq!(app.status(), Some("+3 −1 lines"));
+        assert!(app.popup.is_none());
+    }
+
+    #[test]
+    fn test_diff_of_untitled_or_missing_file_is_all_added() {
+        let mut app = App::new();
+        app.editor.editor_content = vec!["a".to_string(), "b".to_string()].into();
+        diff_command(&mut app, stat_flag()).unwrap();
+        assert_eq!(app.status(), Some("+2 −0 lines"));
+
+        let dir = tempdir().unwrap();
+        let missing = dir.path().join("gone.txt");
+        app.file_path = Some(missing.to_str().unwrap().to_string());
+        diff_command(&mut app, HashSet::new()).unwrap();
+        assert!(app.popup.is_some());
+        assert!(!missing.exists());
+    }
+
+    fn create_selected_app(lines: &[&str], start: (usize, usize), end: (usize, usize)) -> App {
+        let mut app = App::new();
+        app.editor.editor_content = lines.iter().map(|l| l.to_string()).collect();
+        let range = Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1));
+        app.editor.set_selection(range).unwrap();
+        app
+    }
+
+    #[test]
+    fn test_extract_with_delete_moves_selection_to_file_and_clipboard() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("block.txt");
+        let path_arg = path.to_str().unwrap().to_string();
+        let mut app = create_selected_app(&["keep", "move a", "move b", "keep"], (1, 0), (3, 0));
+
+        let delete = HashSet::from([CommandFlag::Delete]);
+        extract_command(&mut app, vec![path_arg.clone()], delete).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "move a\nmove b\n");
+        assert_eq!(app.editor.editor_content, vec!["keep", "keep"]);
+        assert_eq!(app.editor.clipboard.paste(), vec!["move a", "move b", ""]);
+        assert!(app.has_unsaved_changes());
+        assert_eq!(
+            app.status(),
+            Some(format!("moved 3 lines to {}", path_arg).as_str())
+        );
+    }
+
+    #[test]
+    fn test_extract_asks_before_creating_missing_directory() {
+        let dir = tempdir().unwrap();
+        let typo = dir.path().join("srx");
+        let path = typo.join("a.txt").to_str().unwrap().to_string();
+        let mut app = create_selected_app(&["abc def"], (0, 4), (0, 7));
+
+        extract_command(&mut app, vec![path.clone()], HashSet::new()).unwrap();
+        assert_eq!(
+            app.pending_states,
+            vec![PendingState::CreatingExtractDirectory {
+                path: path.clone(),
+                delete: false
+            }]
+        );
+        app.popup_result = PopupResult::Bool(false);
+        app.handle_confirmation_popup_response();
+        assert!(!typo.exists());
+        assert!(app.status().unwrap().starts_with("not extracted"));
+
+        extract_command(&mut app, vec![path.clone()], HashSet::new()).unwrap();
+        app.popup_result = PopupResult::Bool(true);
+        app.handle_confirmation_popup_response();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "def");
+        assert!(app.pending_states.is_empty());
+
+        let other = dir.path().join("new/b.txt").to_str().unwrap().to_string();
+        let mkdir = HashSet::from([CommandFlag::Mkdir]);
+        extract_command(&mut app, vec![other.clone()], mkdir).unwrap();
+        assert_eq!(fs::read_to_string(&other).unwrap(), "def");
+    }
+
+    #[test]
+    fn test_extract_asks_before_overwriting_and_failed_write_keeps_buffer() {
+        let dir = tempdir().unwrap();
+        let existing = dir.path().join("old.txt");
+        fs::write(&existing, "old").unwrap();
+        let mut app = create_selected_app(&["abc def"], (0, 4), (0, 7));
+
+        let args = vec![existing.to_str().unwrap().to_string()];
+        extract_command(&mut app, args, HashSet::new()).unwrap();
+        assert!(app.popup.is_some());
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "old");
+        app.popup_result = PopupResult::Bool(true);
+        app.handle_confirmation_popup_response();
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "def");
+        assert!(app.pending_states.is_empty());
+
+        //a directory can't be written as a file
+        let range = Range::new(Position::new(0, 0), Position::new(0, 3));
+        app.editor.set_selection(range).unwrap();
+        let dir_arg = vec![dir.path().to_str().unwrap().to_string()];
+        let force_delete = HashSet::from([CommandFlag::Force, CommandFlag::Delete]);
+        assert!(extract_command(&mut app, dir_arg, force_delete).is_err());
+        assert_eq!(app.editor.editor_content, vec!["abc def"]);
+        assert!(app.editor.is_text_selected());
+    }
+
+    #[test]
+    fn test_follow_appends_new_lines_and_blocks_edits() {
+        use crate::config::editor_settings;
+        use crate::input::input_action::InputAction;
+        use std::fs::OpenOptions;
+        use std::io::Write;
+        use std::time::Duration;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(&path, "start\n").unwrap();
+        let mut app = App::new();
+        app.editor.editor_height = 5;
+        app.open_file(path.to_str().unwrap().to_string()).unwrap();
+        follow_command(&mut app, vec![]).unwrap();
+        assert!(app.follow.is_some());
+
+        app.process_input_action(InputAction::WriteChar('x'));
+        assert_eq!(app.editor.editor_content, vec!["start"]);
+        assert!(app.status().unwrap().contains(":follow off"));
+
+        let mut log = OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(log, "one\ntwo\nthree\nfour\nfive\nsix").unwrap();
+        std::thread::sleep(Duration::from_millis(editor_settings::FOLLOW_POLL_MS));
+        assert!(app.on_tick());
+        assert_eq!(app.editor.editor_content.len(), 7);
+        assert_eq!(app.editor.cursor.y, 6);
+        assert!(app.editor.is_cursor_in_view());
+        assert!(!app.has_unsaved_changes());
+
+        //scrolled up, new lines don't move the view
+        app.editor.cursor.y = 1;
+        app.editor.scroll_offset = 0;
+        writeln!(log, "seven").unwrap();
+        std::thread::sleep(Duration::from_millis(editor_settings::FOLLOW_POLL_MS));
+        app.on_tick();
+        assert_eq!(app.editor.editor_content[7], "seven");
+        assert_eq!(app.editor.cursor.y, 1);
+        assert!(!app.is_follow_pinned());
+
+        follow_command(&mut app, vec!["off".to_string()]).unwrap();
+        assert!(app.follow.is_none());
+        app.process_input_action(InputAction::WriteChar('x'));
+        assert_eq!(app.editor.editor_content[1], "xone");
+    }
+
+    #[test]
+    fn test_follow_needs_a_saved_file() {
+        let mut app = App::new();
+        assert!(follow_command(&mut app, vec![]).is_err());
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("a.log");
+        fs::write(&path, "a").unwrap();
+        app.open_file(path.to_str().unwrap().to_string()).unwrap();
+        app.editor.insert_str("edit").unwrap();
+        assert!(follow_command(&mut app, vec!["on".to_string()]).is_err());
+        assert!(follow_command(&mut app, vec!["maybe".to_string()]).is_err());
     }
 }
@@ -0,0 +1,154 @@
+//hex view commands: showing the file's bytes and searching them
+
+use crate::core::app::App;
+use crate::core::errors::command_errors::CommandError;
+use crate::core::hex_view;
+use std::fs;
+
+///shows the open file as a hex dump without arguments or with on, expects off to go back
+/// to the text or find to search the bytes
+pub fn hexview_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    match args.first().map(String::as_str) {
+        None | Some("on") => turn_on(app),
+        Some("off") => {
+            app.leave_hex_view();
+            app.set_status("hex view off");
+            Ok(())
+        }
+        Some("find") => find(app, &args[1..].join(" ")),
+        Some(other) => Err(CommandError::InvalidArguments {
+            command: "hexview".to_string(),
+            reason: format!("unknown hexview '{}', expected on, off or find", other),
+        }),
+    }
+}
+
+///dumps the bytes on disk, not the buffer, so stray bytes the text view hides are shown
+fn turn_on(app: &mut App) -> Result<(), CommandError> {
+    let Some(path) = app.file_path.clone() else {
+        return Err(CommandError::MissingContext(
+            "the hex view shows a file on disk, save the buffer first".to_string(),
+        ));
+    };
+    if app.follow.is_some() {
+        return Err(CommandError::InvalidState(
+            "following the file, :follow off first".to_string(),
+        ));
+    }
+    let bytes = fs::read(&path)
+        .map_err(|e| CommandError::ExecutionFailed(format!("could not read {}: {}", path, e)))?;
+    let len = bytes.len();
+    app.enter_hex_view(bytes);
+    app.set_status(format!("hex view of {}, {} bytes", path, len));
+    Ok(())
+}
+
+///moves the cursor to the next match of pattern after it, text or hex bytes after 0x
+fn find(app: &mut App, pattern: &str) -> Result<(), CommandError> {
+    let invalid = |reason: &str| CommandError::InvalidArguments {
+        command: "hexview find".to_string(),
+        reason: reason.to_string(),
+    };
+    let Some(hex_view) = &app.hex_view else {
+        return Err(CommandError::InvalidState(
+            "not in the hex view, turn it on with :hexview".to_string(),
+        ));
+    };
+    if pattern.is_empty() {
+        return Err(invalid("expected text or hex bytes like 0x0d0a"));
+    }
+    let bytes = hex_view::parse_pattern(pattern)
+        .ok_or_else(|| invalid("hex bytes need two digits each, like 0x0d0a"))?;
+    let from = hex_view::offset_at(app.editor.cursor()) + 1;
+    match hex_view.find(&bytes, from) {
+        Some(offset) => {
+            let _ = app.editor.set_cursor(hex_view::position_of(offset));
+            app.set_status(format!("found at 0x{:08x}", offset));
+        }
+        None => app.set_status(format!("{} not found", pattern)),
+    }
+    Ok(())
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_hex_command_tests {
+    use super::*;
+    use crate::core::command_line::command::parse_command;
+    use crate::core::command_line::command_executor::execute_command;
+    use crate::core::cursor::CursorPosition;
+    use crate::input::input_action::{Direction, InputAction};
+    use tempfile::tempdir;
+
+    fn run(app: &mut App, line: &str) -> Result<(), CommandError> {
+        let mut words = line.split_whitespace().map(String::from);
+        let bind = words.next().unwrap();
+        execute_command(app, parse_command(bind, words.collect()))
+    }
+
+    fn create_app(dir: &tempfile::TempDir, bytes: &[u8]) -> App {
+        let path = dir.path().join("data.txt");
+        fs::write(&path, bytes).unwrap();
+        let mut app = App::new();
+        app.open_file(path.to_str().unwrap().to_string()).unwrap();
+        app.editor.editor_height = 10;
+        app
+    }
+
+    #[test]
+    fn test_switching_back_restores_text_cursor_and_blocks_edits() {
+        let dir = tempdir().unwrap();
+        let lines: Vec<String> = (0..40).map(|i| format!("line {}\r", i)).collect();
+        let mut app = create_app(&dir, lines.join("\n").as_bytes());
+        app.editor.set_cursor(CursorPosition::new(3, 30)).unwrap();
+        let scroll = app.editor.scroll_offset();
+
+        run(&mut app, "hexview").unwrap();
+        assert!(app
+            .editor
+            .line(0)
+            .unwrap()
+            .starts_with("00000000  6c 69 6e 65"));
+        app.process_input_action(InputAction::MoveCursor(Direction::Down));
+        app.process_input_action(InputAction::WriteChar('x'));
+        assert!(app.status().unwrap().contains("read-only"));
+        assert!(run(&mut app, "reverse").is_err());
+        assert!(!app.has_unsaved_changes());
+
+        run(&mut app, "hexview off").unwrap();
+        assert!(app.hex_view.is_none());
+        assert_eq!(app.editor.cursor(), CursorPosition::new(3, 30));
+        assert_eq!(app.editor.scroll_offset(), scroll);
+        //the text view drops the carriage returns the dump showed
+        assert_eq!(app.editor.line(0), Some("line 0"));
+    }
+
+    #[test]
+    fn test_find_moves_to_matches_in_hex_and_text() {
+        let dir = tempdir().unwrap();
+        let mut app = create_app(&dir, b"first line\r\nsecond line\r\n");
+        run(&mut app, "hexview").unwrap();
+
+        run(&mut app, "hexview find 0x0d0a").unwrap();
+        assert_eq!(app.editor.cursor(), hex_view::position_of(10));
+        run(&mut app, "hexview find 0x0d0a").unwrap();
+        assert_eq!(app.editor.cursor(), hex_view::position_of(23));
+        run(&mut app, "hexview find second line").unwrap();
+        assert_eq!(app.editor.cursor(), hex_view::position_of(12));
+        run(&mut app, "hexview find nope").unwrap();
+        assert_eq!(app.status(), Some("nope not found"));
+        assert!(run(&mut app, "hexview find 0x0").is_err());
+    }
+
+    #[test]
+    fn test_untitled_buffer_has_no_hex_view() {
+        let mut app = App::new();
+        assert!(run(&mut app, "hexview").is_err());
+        assert!(run(&mut app, "hexview find a").is_err());
+    }
+}
@@ -0,0 +1,349 @@
+//insert commands: putting dates, uuids or other files at the cursor, or delimiters
+// around the selection
+
+use crate::config::editor_settings;
+use crate::core::app::App;
+use crate::core::app::PendingState;
+use crate::core::editor::text_objects;
+use crate::core::errors::command_errors::CommandError;
+use crate::core::unicode;
+use crate::ui::popups::list_popup::ListPopup;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+///inserts generated text or a file at the cursor, expects what to insert:
+/// date, datetime, uuid or file followed by a path
+pub fn insert_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    let lines = match args.first().map(String::as_str) {
+        Some("date") => vec![format_timestamp(now(), editor_settings::DATE_FORMAT)],
+        Some("datetime") => vec![format_timestamp(now(), editor_settings::DATETIME_FORMAT)],
+        Some("uuid") => vec![uuid_v4(fastrand::u128(..))],
+        Some("file") if args.len() > 1 => read_file_lines(&args[1..].join(" "))?,
+        Some("file") => {
+            return Err(CommandError::InvalidArguments {
+                command: "insert".to_string(),
+                reason: "expected a file path".to_string(),
+            })
+        }
+        Some(other) => {
+            return Err(CommandError::InvalidArguments {
+                command: "insert".to_string(),
+                reason: format!(
+                    "unknown insert '{}', expected date, datetime, uuid or file",
+                    other
+                ),
+            })
+        }
+        None => {
+            return Err(CommandError::InvalidArguments {
+                command: "insert".to_string(),
+                reason: "expected date, datetime, uuid or file".to_string(),
+            })
+        }
+    };
+
+    app.editor.insert_text(&lines);
+    Ok(())
+}
+
+///inserts the character at a hex codepoint, or with name followed by words looks it up
+/// by name. several matches are listed to pick from.
+pub fn unicode_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    let invalid = |reason: String| CommandError::InvalidArguments {
+        command: "unicode".to_string(),
+        reason,
+    };
+    let c = match args.first().map(String::as_str) {
+        None => return Err(invalid("expected a hex codepoint or name".to_string())),
+        Some("name") => {
+            let query = args[1..].join(" ");
+            let found = unicode::lookup(&query);
+            match found.as_slice() {
+                [] => return Err(invalid(format!("no character named like '{}'", query))),
+                [(_, c)] => *c,
+                _ => {
+                    let items = found
+                        .iter()
+                        .map(|(name, c)| unicode::describe(name, *c))
+                        .collect();
+                    let chars = found.iter().map(|(_, c)| *c).collect();
+                    app.open_popup(Box::new(ListPopup::new("Unicode", items)));
+                    app.pending_states.push(PendingState::PickingUnicode(chars));
+                    return Ok(());
+                }
+            }
+        }
+        Some(codepoint) => unicode::parse_codepoint(codepoint).map_err(invalid)?,
+    };
+    app.editor
+        .insert_char(c)
+        .map_err(|e| CommandError::ExecutionFailed(e.to_string()))
+}
+
+///reads the lines of the file at path
+fn read_file_lines(path: &str) -> Result<Vec<String>, CommandError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| CommandError::ExecutionFailed(format!("could not read {}: {}", path, e)))?;
+    Ok(content.lines().map(String::from).collect())
+}
+
+///seconds since the unix epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+///formats seconds since the unix epoch as UTC with a strftime like format,
+/// unknown specifiers are kept as they are
+pub(super) fn format_timestamp(secs: u64, format: &str) -> String {
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let secs_of_day = secs % 86_400;
+    let (hour, minute, second) = (
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60,
+    );
+
+    let mut out = String::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => out.push_str(&format!("{:04}", year)),
+            Some('m') => out.push_str(&format!("{:02}", month)),
+            Some('d') => out.push_str(&format!("{:02}", day)),
+            Some('H') => out.push_str(&format!("{:02}", hour)),
+            Some('M') => out.push_str(&format!("{:02}", minute)),
+            Some('S') => out.push_str(&format!("{:02}", second)),
+            Some('%') => out.push('%'),
+            Some(other) => {
+                out.push('%');
+                out.push(other);
+            }
+            None => out.push('%'),
+        }
+    }
+    out
+}
+
+///converts days since the unix epoch to a (year, month, day) date,
+/// after Howard Hinnant's civil_from_days
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+///formats random bits as a version 4 uuid
+fn uuid_v4(random: u128) -> String {
+    let bits = (random & !(0xF << 76) | (0x4 << 76)) & !(0x3 << 62) | (0x2 << 62);
+    let hex = format!("{:032x}", bits);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+///wraps the selection in the delimiter pair of the char given, a bracket from either side
+/// or a char like a quote going on both sides
+pub fn surround_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    let mut chars = args.first().map(|arg| arg.chars()).into_iter().flatten();
+    let (Some(c), None) = (chars.next(), chars.next()) else {
+        return Err(CommandError::InvalidArguments {
+            command: "surround".to_string(),
+            reason: "expected a single character, like ( or \"".to_string(),
+        });
+    };
+    let (open, close) = text_objects::surround_pair(c);
+    app.editor
+        .surround_selection(open, close)
+        .map_err(|e| CommandError::ExecutionFailed(e.to_string()))
+}
+
+///removes the delimiter pair enclosing the selection
+pub fn unsurround_command(app: &mut App) -> Result<(), CommandError> {
+    match app.editor.unsurround_selection() {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(CommandError::ExecutionFailed(
+            "no matching pair around the selection".to_string(),
+        )),
+        Err(e) => Err(CommandError::ExecutionFailed(e.to_string())),
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_insert_command_tests {
+    use super::*;
+    use crate::core::position::{Position, Range};
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn create_app(lines: &[&str]) -> App {
+        let mut app = App::new();
+        app.editor.editor_content = lines.iter().map(|l| l.to_string()).collect();
+        app.editor.editor_height = 10;
+        app
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        //2024-02-29 13:05:09 UTC
+        let secs = 1_709_211_909;
+        assert_eq!(format_timestamp(secs, "%Y-%m-%d"), "2024-02-29");
+        assert_eq!(
+            format_timestamp(secs, "%d.%m.%Y %H:%M:%S %% %x"),
+            "29.02.2024 13:05:09 % %x"
+        );
+        assert_eq!(format_timestamp(0, "%Y-%m-%d %H:%M"), "1970-01-01 00:00");
+    }
+
+    #[test]
+    fn test_uuid_has_version_and_variant() {
+        let uuid = uuid_v4(u128::MAX);
+        assert_eq!(uuid, "ffffffff-ffff-4fff-bfff-ffffffffffff");
+        assert_eq!(uuid_v4(0), "00000000-0000-4000-8000-000000000000");
+    }
+
+    #[test]
+    fn test_insert_uuid_at_cursor() {
+        let mut app = create_app(&["()"]);
+        app.editor.cursor.x = 1;
+        insert_command(&mut app, vec!["uuid".to_string()]).unwrap();
+        assert_eq!(app.editor.editor_content[0].chars().count(), 38);
+        assert_eq!(app.editor.cursor.x, 37);
+    }
+
+    #[test]
+    fn test_insert_file_is_one_undo_step() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "one\ntwo").unwrap();
+        let path = file.path().to_str().unwrap().to_string();
+
+        let mut app = create_app(&["ab"]);
+        app.editor.cursor.x = 1;
+        insert_command(&mut app, vec!["file".to_string(), path]).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["aone", "twob"]);
+        assert_eq!((app.editor.cursor.x, app.editor.cursor.y), (3, 1));
+
+        app.editor.undo().unwrap();
+        assert_eq!(app.editor.editor_content, vec!["ab"]);
+    }
+
+    #[test]
+    fn test_unicode_inserts_codepoints_and_refuses_invalid_ones() {
+        let mut app = create_app(&["a b"]);
+        app.editor.cursor.x = 2;
+        unicode_command(&mut app, vec!["2192".to_string()]).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["a →b"]);
+        for bad in ["d800", "110000", "xyz"] {
+            assert!(matches!(
+                unicode_command(&mut app, vec![bad.to_string()]),
+                Err(CommandError::InvalidArguments { .. })
+            ));
+        }
+        assert_eq!(app.editor.editor_content, vec!["a →b"]);
+    }
+
+    #[test]
+    fn test_unicode_name_inserts_single_match_or_lists_several() {
+        let mut app = create_app(&[""]);
+        unicode_command(&mut app, vec!["name".to_string(), "snowman".to_string()]).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["☃"]);
+
+        unicode_command(&mut app, vec!["name".to_string(), "rightwards".to_string()]).unwrap();
+        assert!(app.popup.is_some());
+        app.popup_result = crate::ui::popups::popup::PopupResult::Index(0);
+        app.handle_list_popup_response();
+        assert!(app.pending_states.is_empty());
+        assert!(app.editor.editor_content[0].ends_with('→'));
+    }
+
+    #[test]
+    fn test_insert_errors() {
+        let mut app = create_app(&[""]);
+        assert!(matches!(
+            insert_command(&mut app, vec!["nonsense".to_string()]),
+            Err(CommandError::InvalidArguments { .. })
+        ));
+        assert!(matches!(
+            insert_command(
+                &mut app,
+                vec!["file".to_string(), "/no/such/file".to_string()]
+            ),
+            Err(CommandError::ExecutionFailed(_))
+        ));
+        assert_eq!(app.editor.editor_content, vec![""]);
+    }
+
+    fn select(app: &mut App, start: (usize, usize), end: (usize, usize)) {
+        let range = Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1));
+        app.editor.set_selection(range).unwrap();
+    }
+
+    #[test]
+    fn test_surround_wraps_selection_over_lines_as_one_undo_step() {
+        let mut app = create_app(&["say hello", "world now"]);
+        select(&mut app, (0, 4), (1, 5));
+        surround_command(&mut app, vec![")".to_string()]).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["say (hello", "world) now"]);
+        assert_eq!(app.editor.selected_text().as_deref(), Some("hello\nworld"));
+
+        app.editor.undo().unwrap();
+        assert_eq!(app.editor.editor_content, vec!["say hello", "world now"]);
+
+        let mut app = create_app(&["a quote"]);
+        select(&mut app, (0, 2), (0, 7));
+        surround_command(&mut app, vec!["\"".to_string()]).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["a \"quote\""]);
+        assert_eq!(app.editor.selected_text().as_deref(), Some("quote"));
+        assert!(surround_command(&mut app, vec!["ab".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_unsurround_removes_pair_around_or_at_ends_of_selection() {
+        let mut app = create_app(&["x <tag> y"]);
+        select(&mut app, (0, 3), (0, 6));
+        unsurround_command(&mut app).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["x tag y"]);
+        assert_eq!(app.editor.selected_text().as_deref(), Some("tag"));
+
+        let mut app = create_app(&["`code`"]);
+        select(&mut app, (0, 0), (0, 6));
+        unsurround_command(&mut app).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["code"]);
+        app.editor.undo().unwrap();
+        assert_eq!(app.editor.editor_content, vec!["`code`"]);
+
+        let mut app = create_app(&["(a]"]);
+        select(&mut app, (0, 1), (0, 2));
+        assert!(unsurround_command(&mut app).is_err());
+        assert_eq!(app.editor.editor_content, vec!["(a]"]);
+    }
+}
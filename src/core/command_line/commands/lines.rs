@@ -0,0 +1,650 @@
+//line related commands: reversing, removing duplicate or blank lines, aligning, reflowing,
+// retabbing, substituting text and trimming trailing whitespace
+
+use crate::config::editor_settings;
+use crate::core::app::App;
+use crate::core::command_line::command::CommandFlag;
+use crate::core::cursor::CursorPosition;
+use crate::core::editor::{reflow, retab};
+use crate::core::errors::command_errors::CommandError;
+use std::collections::HashSet;
+
+///reverses the order of the selected lines, or of all lines without a selection
+pub fn reverse_command(app: &mut App) -> Result<(), CommandError> {
+    app.editor.transform_lines(|mut lines| {
+        lines.reverse();
+        lines
+    });
+    Ok(())
+}
+
+///removes adjacent duplicates of the selected lines, or of all lines without a selection
+pub fn unique_command(app: &mut App) -> Result<(), CommandError> {
+    let mut removed = 0;
+    app.editor.transform_lines(|mut lines| {
+        let before = lines.len();
+        lines.dedup();
+        removed = before - lines.len();
+        lines
+    });
+    app.set_status(format!("removed {} duplicate lines", removed));
+    Ok(())
+}
+
+///removes blank and whitespace-only lines from the selected lines, or all lines without a
+/// selection. expects delete to remove every one, or squeeze to keep the first of each run.
+/// without a selection the cursor stays on its text line.
+pub fn blank_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    let squeeze = match args.first().map(String::as_str) {
+        Some("delete") => false,
+        Some("squeeze") => true,
+        _ => {
+            return Err(CommandError::InvalidArguments {
+                command: "blank".to_string(),
+                reason: "expected delete or squeeze".to_string(),
+            })
+        }
+    };
+
+    let range = app.editor.selected_line_range();
+    let is_blank = |line: &String| line.trim().is_empty();
+    let lines = &app.editor.editor_content[range.clone()];
+    let mut keep: Vec<bool> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| !is_blank(line) || (squeeze && (i == 0 || !is_blank(&lines[i - 1]))))
+        .collect();
+    //the buffer keeps one line
+    if range.len() == app.editor.editor_content.len() && !keep.contains(&true) {
+        keep[0] = true;
+    }
+    let removed = keep.iter().filter(|kept| !**kept).count();
+    if removed > 0 {
+        let cursor = app.editor.cursor_position();
+        let selected = app.editor.is_text_selected();
+        app.editor.transform_line_range(range.clone(), |lines| {
+            lines
+                .into_iter()
+                .zip(&keep)
+                .filter(|(_, kept)| **kept)
+                .map(|(line, _)| line)
+                .collect()
+        });
+        if !selected && range.contains(&cursor.y) {
+            //a removed cursor line moves the cursor to the line that followed it
+            let i = cursor.y - range.start;
+            let above = keep[..i].iter().filter(|kept| !**kept).count();
+            let y = (cursor.y - above).min(app.editor.editor_content.len() - 1);
+            let x = if keep[i] { cursor.x } else { 0 };
+            app.editor.set_cursor_position(&CursorPosition { x, y });
+        }
+    }
+    app.set_status(format!("removed {} blank lines", removed));
+    Ok(())
+}
+
+///pads the selected lines, or all lines without a selection, so the first occurrence of
+/// the delimiter lines up. with --all every occurrence is aligned, like columns of a table.
+pub fn align_command(
+    app: &mut App,
+    args: Vec<String>,
+    flags: HashSet<CommandFlag>,
+) -> Result<(), CommandError> {
+    let delimiter = args.join(" ");
+    if delimiter.is_empty() {
+        return Err(CommandError::InvalidArguments {
+            command: "align".to_string(),
+            reason: "expected a delimiter".to_string(),
+        });
+    }
+    let all = flags.contains(&CommandFlag::All);
+    let tab_width = editor_settings::TAB_WIDTH as usize;
+    app.editor
+        .transform_lines(|lines| align_lines(lines, &delimiter, all, tab_width));
+    Ok(())
+}
+
+///aligns occurrences of delimiter by padding spaces in front of them, column by column.
+/// lines without the delimiter are kept as they are, the others get their tabs expanded.
+fn align_lines(lines: Vec<String>, delimiter: &str, all: bool, tab_width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = lines
+        .into_iter()
+        .map(|line| {
+            if line.contains(delimiter) {
+                expand_tabs(&line, tab_width)
+            } else {
+                line
+            }
+        })
+        .collect();
+
+    let mut occurrence = 0;
+    loop {
+        //byte index and column of the delimiter occurrence on each line having it
+        let positions: Vec<Option<(usize, usize)>> = lines
+            .iter()
+            .map(|line| {
+                let index = line.match_indices(delimiter).nth(occurrence)?.0;
+                Some((index, line[..index].chars().count()))
+            })
+            .collect();
+        let Some(target) = positions.iter().flatten().map(|(_, col)| *col).max() else {
+            break;
+        };
+
+        for (line, position) in lines.iter_mut().zip(&positions) {
+            if let Some((index, col)) = position {
+                line.insert_str(*index, &" ".repeat(target - col));
+            }
+        }
+
+        occurrence += 1;
+        if !all {
+            break;
+        }
+    }
+    lines
+}
+
+///re-breaks the selected lines, or the paragraph at the cursor, so no line exceeds the width.
+/// expects an optional width, defaulting to the configured reflow width.
+pub fn reflow_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    let width = match args.first() {
+        Some(arg) => arg
+            .parse::<usize>()
+            .ok()
+            .filter(|width| *width > 0)
+            .ok_or_else(|| CommandError::InvalidArguments {
+                command: "reflow".to_string(),
+                reason: format!("expected a width, got '{}'", arg),
+            })?,
+        None => editor_settings::REFLOW_WIDTH,
+    };
+
+    let range = if app.editor.is_text_selected() {
+        app.editor.selected_line_range()
+    } else {
+        app.editor.paragraph_range(app.editor.cursor.y)
+    };
+    app.editor
+        .transform_line_range(range, |lines| reflow::reflow_lines(lines, width));
+    Ok(())
+}
+
+///rewrites the indentation of the selected lines, or of all lines without a selection,
+/// with tabs or spaces as set by insertspaces. with --all whitespace inside lines is converted too.
+pub fn retab_command(app: &mut App, flags: HashSet<CommandFlag>) -> Result<(), CommandError> {
+    let all = flags.contains(&CommandFlag::All);
+    let tab_width = app.settings.tab_width;
+    let insert_spaces = app.settings.insert_spaces;
+
+    //the cursor keeps its column, so it stays on the same char
+    let cursor = app.editor.cursor_position();
+    let column = app
+        .editor
+        .editor_content
+        .get(cursor.y)
+        .map(|line| retab::column_at(line, cursor.x, tab_width));
+
+    let mut changed = 0;
+    app.editor.transform_lines(|lines| {
+        lines
+            .into_iter()
+            .map(|line| {
+                let new_line = retab::retab_line(&line, tab_width, insert_spaces, all);
+                if new_line != line {
+                    changed += 1;
+                }
+                new_line
+            })
+            .collect()
+    });
+
+    if let (Some(column), Some(line)) = (column, app.editor.editor_content.get(cursor.y)) {
+        let x = retab::index_at_column(line, column, tab_width);
+        app.editor
+            .set_cursor_position(&CursorPosition { x, y: cursor.y });
+    }
+    app.set_status(format!("retabbed {} lines", changed));
+    Ok(())
+}
+
+///replaces text in the selection, or all lines without a selection. expects
+/// /pattern/replacement/ with g after it to replace every occurrence on a line instead of
+/// the first. the pattern is plain text, any punctuation may stand in for the slashes.
+pub fn substitute_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    let invalid = |reason: &str| CommandError::InvalidArguments {
+        command: "s".to_string(),
+        reason: reason.to_string(),
+    };
+    let Some((pattern, replacement, flags)) = args.first().and_then(|arg| parse_substitution(arg))
+    else {
+        return Err(invalid("expected /pattern/replacement/"));
+    };
+    if pattern.is_empty() {
+        return Err(invalid("the pattern is empty"));
+    }
+    let global = match flags.as_str() {
+        "" => false,
+        "g" => true,
+        _ => return Err(invalid(&format!("unknown flags '{}', expected g", flags))),
+    };
+
+    let mut replaced = 0;
+    let mut replace = |text: &str| {
+        let found = text.matches(pattern.as_str()).count();
+        if global {
+            replaced += found;
+            text.replace(&pattern, &replacement)
+        } else {
+            replaced += found.min(1);
+            text.replacen(&pattern, &replacement, 1)
+        }
+    };
+
+    //a selection limits the matches to the selected text itself, not its whole lines.
+    // without a match nothing changes, so no undo step is left behind.
+    if app.editor.is_text_selected() {
+        app.editor
+            .transform_selected_spans(replace)
+            .map_err(|e| CommandError::ExecutionFailed(e.to_string()))?;
+    } else if app
+        .editor
+        .editor_content
+        .iter()
+        .any(|line| line.contains(pattern.as_str()))
+    {
+        app.editor
+            .transform_lines(|lines| lines.iter().map(|line| replace(line)).collect());
+    }
+    if replaced == 0 {
+        return Err(CommandError::ExecutionFailed(format!(
+            "pattern not found: {}",
+            pattern
+        )));
+    }
+    app.set_status(format!("replaced {} occurrences", replaced));
+    Ok(())
+}
+
+///splits /pattern/replacement/flags at the delimiter it starts with. a backslash keeps the
+/// delimiter, or another backslash, in the text, the last delimiter may be left out.
+fn parse_substitution(arg: &str) -> Option<(String, String, String)> {
+    let mut chars = arg.chars();
+    let delimiter = chars
+        .next()
+        .filter(|c| c.is_ascii_punctuation() && *c != '\\')?;
+    let mut parts = vec![];
+    let mut part = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(next) if next == delimiter || next == '\\' => part.push(next),
+                Some(next) => {
+                    part.push(c);
+                    part.push(next);
+                }
+                None => part.push(c),
+            },
+            //the flags after the last delimiter are taken as they are
+            c if c == delimiter && parts.len() < 2 => parts.push(std::mem::take(&mut part)),
+            c => part.push(c),
+        }
+    }
+    parts.push(part);
+    let mut parts = parts.into_iter();
+    let pattern = parts.next()?;
+    let replacement = parts.next()?;
+    Some((pattern, replacement, parts.next().unwrap_or_default()))
+}
+
+///removes trailing whitespace from the selected lines, or all lines without a selection
+pub fn trim_command(app: &mut App) -> Result<(), CommandError> {
+    let mut trimmed = 0;
+    app.editor.transform_lines(|lines| {
+        lines
+            .into_iter()
+            .map(|line| {
+                let kept = line.trim_end();
+                if kept.len() == line.len() {
+                    return line;
+                }
+                trimmed += 1;
+                kept.to_string()
+            })
+            .collect()
+    });
+    app.set_status(format!("trimmed {} lines", trimmed));
+    Ok(())
+}
+
+///replaces tabs with spaces up to the next tab stop
+fn expand_tabs(line: &str, tab_width: usize) -> String {
+    let mut out = String::new();
+    let mut col = 0;
+    for c in line.chars() {
+        if c == '\t' {
+            let spaces = tab_width - col % tab_width;
+            out.push_str(&" ".repeat(spaces));
+            col += spaces;
+        } else {
+            out.push(c);
+            col += 1;
+        }
+    }
+    out
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_lines_command_tests {
+    use super::*;
+    use crate::core::position::{Position, Range};
+
+    fn create_app(lines: &[&str]) -> App {
+        let mut app = App::new();
+        app.editor.editor_content = lines.iter().map(|l| l.to_string()).collect();
+        app.editor.editor_height = 10;
+        app
+    }
+
+    fn select(app: &mut App, start: (usize, usize), end: (usize, usize)) {
+        app.editor.selection = Some(Range::new(
+            Position::new(start.1, start.0),
+            Position::new(end.1, end.0),
+        ));
+    }
+
+    #[test]
+    fn test_reverse_whole_buffer() {
+        let mut app = create_app(&["a", "b", "c"]);
+        reverse_command(&mut app).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["c", "b", "a"]);
+    }
+
+    #[test]
+    fn test_reverse_widens_partial_selection_and_keeps_it() {
+        let mut app = create_app(&["first", "second", "third", "fourth"]);
+        select(&mut app, (3, 1), (2, 2));
+        reverse_command(&mut app).unwrap();
+
+        assert_eq!(
+            app.editor.editor_content,
+            vec!["first", "third", "second", "fourth"]
+        );
+        assert_eq!(
+            app.editor.selection,
+            Some(Range::new(Position::new(1, 0), Position::new(2, 6)))
+        );
+    }
+
+    #[test]
+    fn test_selection_ending_at_line_start_excludes_that_line() {
+        let mut app = create_app(&["a", "b", "c"]);
+        select(&mut app, (0, 0), (0, 2));
+        reverse_command(&mut app).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_unique_removes_adjacent_duplicates_and_reports_count() {
+        let mut app = create_app(&["a", "a", "b", "a", "a", "a"]);
+        unique_command(&mut app).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["a", "b", "a"]);
+        assert_eq!(app.status(), Some("removed 3 duplicate lines"));
+    }
+
+    #[test]
+    fn test_blank_delete_keeps_cursor_on_its_text() {
+        let mut app = create_app(&["", "a", "  ", "", "b", "\t", "c"]);
+        app.editor.cursor.y = 4;
+        app.editor.cursor.x = 1;
+        blank_command(&mut app, vec!["delete".to_string()]).unwrap();
+
+        assert_eq!(app.editor.editor_content, vec!["a", "b", "c"]);
+        assert_eq!((app.editor.cursor.x, app.editor.cursor.y), (1, 1));
+        assert_eq!(app.status(), Some("removed 4 blank lines"));
+
+        app.editor.undo().unwrap();
+        assert_eq!(
+            app.editor.editor_content,
+            vec!["", "a", "  ", "", "b", "\t", "c"]
+        );
+    }
+
+    #[test]
+    fn test_blank_squeeze_keeps_one_of_each_run_in_selection() {
+        let mut app = create_app(&["a", "", "", "b", "", " ", "", "c", "", ""]);
+        select(&mut app, (0, 0), (0, 7));
+        blank_command(&mut app, vec!["squeeze".to_string()]).unwrap();
+
+        assert_eq!(
+            app.editor.editor_content,
+            vec!["a", "", "b", "", "c", "", ""]
+        );
+        assert_eq!(app.status(), Some("removed 3 blank lines"));
+    }
+
+    #[test]
+    fn test_blank_on_a_removed_cursor_line_and_all_blank_buffer() {
+        let mut app = create_app(&["a", "", "", "b"]);
+        app.editor.cursor.y = 2;
+        blank_command(&mut app, vec!["squeeze".to_string()]).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["a", "", "b"]);
+        assert_eq!(app.editor.cursor.y, 2);
+
+        let mut app = create_app(&["", " ", ""]);
+        blank_command(&mut app, vec!["delete".to_string()]).unwrap();
+        assert_eq!(app.editor.editor_content, vec![""]);
+
+        assert!(blank_command(&mut app, vec![]).is_err());
+        assert!(blank_command(&mut app, vec!["trim".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_substitute_first_or_every_occurrence_per_line() {
+        let mut app = create_app(&["foo foo", "bar", "foo"]);
+        substitute_command(&mut app, vec!["/foo/baz/".to_string()]).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["baz foo", "bar", "baz"]);
+        assert_eq!(app.status(), Some("replaced 2 occurrences"));
+
+        let mut app = create_app(&["foo foo", "bar", "foo"]);
+        substitute_command(&mut app, vec!["/foo/baz/g".to_string()]).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["baz baz", "bar", "baz"]);
+        assert_eq!(app.status(), Some("replaced 3 occurrences"));
+
+        app.editor.undo().unwrap();
+        assert_eq!(app.editor.editor_content, vec!["foo foo", "bar", "foo"]);
+    }
+
+    #[test]
+    fn test_substitute_in_selection_with_other_delimiter() {
+        let mut app = create_app(&["a/b", "a/b", "a/b"]);
+        select(&mut app, (0, 1), (3, 1));
+        substitute_command(&mut app, vec![r"#/#\##".to_string()]).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["a/b", "a#b", "a/b"]);
+    }
+
+    #[test]
+    fn test_substitute_in_selection_skips_matches_outside_it_and_keeps_it() {
+        let mut app = create_app(&["foo foo foo", "foo foo", "foo"]);
+        select(&mut app, (4, 0), (3, 1));
+        substitute_command(&mut app, vec!["/foo/x/g".to_string()]).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["foo x x", "x foo", "foo"]);
+        assert_eq!(app.status(), Some("replaced 3 occurrences"));
+        //the end moved back with the shorter text, so a refinement sees the same span
+        assert_eq!(
+            app.editor.selection(),
+            Some(Range::new(Position::new(0, 4), Position::new(1, 1)))
+        );
+
+        substitute_command(&mut app, vec!["/x/long/".to_string()]).unwrap();
+        assert_eq!(
+            app.editor.editor_content,
+            vec!["foo long x", "long foo", "foo"]
+        );
+        assert_eq!(app.editor.selected_text().as_deref(), Some("long x\nlong"));
+
+        //a match only partly inside the selection isn't replaced
+        select(&mut app, (0, 2), (2, 2));
+        assert!(substitute_command(&mut app, vec!["/foo/x/".to_string()]).is_err());
+        assert_eq!(app.editor.editor_content[2], "foo");
+        app.editor.undo().unwrap();
+        app.editor.undo().unwrap();
+        assert_eq!(
+            app.editor.editor_content,
+            vec!["foo foo foo", "foo foo", "foo"]
+        );
+    }
+
+    #[test]
+    fn test_substitute_parses_escapes_and_rejects_bad_input() {
+        assert_eq!(
+            parse_substitution(r"/a\/b/c\d"),
+            Some(("a/b".to_string(), r"c\d".to_string(), String::new()))
+        );
+        assert_eq!(
+            parse_substitution("/a/b/g"),
+            Some(("a".to_string(), "b".to_string(), "g".to_string()))
+        );
+        assert_eq!(parse_substitution("/a"), None);
+        assert_eq!(parse_substitution("a/b/"), None);
+
+        let mut app = create_app(&["text"]);
+        for arg in ["/a", "//b/", "/t/T/x", "/missing/x/"] {
+            assert!(substitute_command(&mut app, vec![arg.to_string()]).is_err());
+        }
+        assert_eq!(app.editor.editor_content, vec!["text"]);
+    }
+
+    #[test]
+    fn test_trim_removes_trailing_whitespace_and_clamps_cursor() {
+        let mut app = create_app(&["a  ", "b", "\tc\t "]);
+        app.editor.cursor.y = 2;
+        app.editor.cursor.x = 4;
+        trim_command(&mut app).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["a", "b", "\tc"]);
+        assert_eq!((app.editor.cursor.x, app.editor.cursor.y), (2, 2));
+        assert_eq!(app.status(), Some("trimmed 2 lines"));
+    }
+
+    #[test]
+    fn test_align_first_occurrence() {
+        let mut app = create_app(&["a = 1", "long_name = 2", "no delimiter", "bc = 3 = 4"]);
+        align_command(&mut app, vec!["=".to_string()], HashSet::new()).unwrap();
+        assert_eq!(
+            app.editor.editor_content,
+            vec![
+                "a         = 1",
+                "long_name = 2",
+                "no delimiter",
+                "bc        = 3 = 4"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_align_all_occurrences_as_columns() {
+        let mut app = create_app(&["| a | bbb |", "| cccc | d |"]);
+        let flags = HashSet::from([CommandFlag::All]);
+        align_command(&mut app, vec!["|".to_string()], flags).unwrap();
+        assert_eq!(
+            app.editor.editor_content,
+            vec!["| a    | bbb |", "| cccc | d   |"]
+        );
+    }
+
+    #[test]
+    fn test_align_expands_tabs_and_is_one_undo_step() {
+        let mut app = create_app(&["\tx: 1", "longer: 2"]);
+        align_command(&mut app, vec![":".to_string()], HashSet::new()).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["    x : 1", "longer: 2"]);
+
+        app.editor.undo().unwrap();
+        assert_eq!(app.editor.editor_content, vec!["\tx: 1", "longer: 2"]);
+    }
+
+    #[test]
+    fn test_align_without_delimiter_fails() {
+        let mut app = create_app(&["a"]);
+        assert!(align_command(&mut app, vec![], HashSet::new()).is_err());
+    }
+
+    #[test]
+    fn test_reflow_paragraph_at_cursor() {
+        let mut app = create_app(&["keep this", "", "one two three", "four", "", "and this"]);
+        app.editor.cursor.y = 3;
+        reflow_command(&mut app, vec!["9".to_string()]).unwrap();
+        assert_eq!(
+            app.editor.editor_content,
+            vec!["keep this", "", "one two", "three", "four", "", "and this"]
+        );
+        assert!((2..5).contains(&app.editor.cursor.y));
+
+        app.editor.undo().unwrap();
+        assert_eq!(
+            app.editor.editor_content,
+            vec!["keep this", "", "one two three", "four", "", "and this"]
+        );
+    }
+
+    #[test]
+    fn test_reflow_keeps_cursor_in_shortened_paragraph() {
+        let mut app = create_app(&["a", "b", "c", "d"]);
+        app.editor.cursor.y = 3;
+        reflow_command(&mut app, vec![]).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["a b c d"]);
+        assert_eq!(app.editor.cursor.y, 0);
+    }
+
+    #[test]
+    fn test_reflow_invalid_width_fails() {
+        let mut app = create_app(&["a"]);
+        assert!(reflow_command(&mut app, vec!["0".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_retab_to_spaces_keeps_cursor_on_char() {
+        let mut app = create_app(&["\tfn x() {", "\t\treturn;\t// y", "}"]);
+        app.settings.insert_spaces = true;
+        app.editor.cursor.y = 1;
+        app.editor.cursor.x = 2;
+        retab_command(&mut app, HashSet::new()).unwrap();
+
+        assert_eq!(
+            app.editor.editor_content,
+            vec!["    fn x() {", "        return;\t// y", "}"]
+        );
+        assert_eq!(app.editor.cursor.x, 8);
+        assert_eq!(app.status(), Some("retabbed 2 lines"));
+    }
+
+    #[test]
+    fn test_retab_all_to_tabs_is_one_undo_step() {
+        let mut app = create_app(&["    a   b"]);
+        let flags = HashSet::from([CommandFlag::All]);
+        retab_command(&mut app, flags).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["\ta\tb"]);
+
+        app.editor.undo().unwrap();
+        assert_eq!(app.editor.editor_content, vec!["    a   b"]);
+    }
+
+    #[test]
+    fn test_unique_is_one_undo_step() {
+        let mut app = create_app(&["x", "y", "y", "z"]);
+        select(&mut app, (0, 1), (1, 2));
+        unique_command(&mut app).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["x", "y", "z"]);
+
+        app.editor.undo().unwrap();
+        assert_eq!(app.editor.editor_content, vec!["x", "y", "y", "z"]);
+        app.editor.redo().unwrap();
+        assert_eq!(app.editor.editor_content, vec!["x", "y", "z"]);
+    }
+}
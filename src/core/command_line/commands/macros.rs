@@ -0,0 +1,12 @@
+//macro related commands: listing recorded macros
+
+use crate::core::app::App;
+use crate::core::errors::command_errors::CommandError;
+use crate::ui::popups::list_popup::ListPopup;
+
+///opens a popup listing the recorded macros by register
+pub fn list_macros_command(app: &mut App) -> Result<(), CommandError> {
+    let popup = Box::new(ListPopup::new("Macros", app.macros.describe_registers()));
+    app.open_popup(popup);
+    Ok(())
+}
@@ -0,0 +1,174 @@
+//mark related commands: setting, jumping to and listing marks and bookmarks
+
+use crate::core::app::{App, PendingState};
+use crate::core::errors::command_errors::CommandError;
+use crate::ui::popups::list_popup::ListPopup;
+
+///sets a mark at the cursor, expects the mark letter
+pub fn mark_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    let name = parse_mark_name("mark", &args)?;
+    app.editor.set_mark(name);
+    Ok(())
+}
+
+///jumps to a line or a mark. takes a line number, +n or -n lines from the cursor, a
+/// percentage of the file like 50%, or a mark letter optionally prefixed with '
+pub fn goto_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    if let Some(target) = args.first().and_then(|arg| LineTarget::parse(arg)) {
+        let y = target.resolve(app.editor.cursor.y, app.editor.line_count());
+        app.editor.goto_line(y);
+        return Ok(());
+    }
+    let name = parse_mark_name("goto", &args).map_err(|_| CommandError::InvalidArguments {
+        command: "goto".to_string(),
+        reason: "expected a line like 12, +3 or -3, a percentage like 50% or a mark letter"
+            .to_string(),
+    })?;
+    app.editor
+        .jump_to_mark(name)
+        .map_err(|e| CommandError::ExecutionFailed(e.to_string()))
+}
+
+///line a :goto argument leads to
+#[derive(Debug, PartialEq)]
+enum LineTarget {
+    //line number counted from 1, like the gutter shows
+    Absolute(usize),
+    //lines below the cursor, above when negative
+    Relative(isize),
+    Percent(usize),
+}
+
+impl LineTarget {
+    fn parse(arg: &str) -> Option<Self> {
+        let number = |digits: &str| {
+            digits
+                .bytes()
+                .all(|b| b.is_ascii_digit())
+                .then(|| digits.parse::<usize>().ok())
+                .flatten()
+        };
+        if let Some(digits) = arg.strip_prefix('+') {
+            return number(digits).and_then(|n| isize::try_from(n).ok().map(Self::Relative));
+        }
+        if let Some(digits) = arg.strip_prefix('-') {
+            return number(digits)
+                .and_then(|n| isize::try_from(n).ok().map(|n| Self::Relative(-n)));
+        }
+        if let Some(digits) = arg.strip_suffix('%') {
+            return number(digits).map(Self::Percent);
+        }
+        number(arg).map(Self::Absolute)
+    }
+
+    ///the line index the target is at from line y, clamped to the line_count lines
+    fn resolve(&self, y: usize, line_count: usize) -> usize {
+        let last = line_count.saturating_sub(1);
+        let y = match *self {
+            Self::Absolute(line) => line.saturating_sub(1),
+            Self::Relative(lines) => y.saturating_add_signed(lines),
+            //rounded up like vim does
+            Self::Percent(percent) => (percent.min(100) * line_count)
+                .div_ceil(100)
+                .saturating_sub(1),
+        };
+        y.min(last)
+    }
+}
+
+///opens a popup listing the marks with their positions
+pub fn list_marks_command(app: &mut App) -> Result<(), CommandError> {
+    let items = app.editor.marks.describe(&app.editor.editor_content);
+    app.open_popup(Box::new(ListPopup::new("Marks", items)));
+    Ok(())
+}
+
+///opens a popup listing the bookmarked lines with a preview, picking one jumps to it.
+/// with clear removes every bookmark instead
+pub fn bookmarks_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    match args.first().map(String::as_str) {
+        None => {}
+        Some("clear") => {
+            app.editor.bookmarks.clear();
+            app.set_status("cleared all bookmarks");
+            return Ok(());
+        }
+        Some(_) => {
+            return Err(CommandError::InvalidArguments {
+                command: "bookmarks".to_string(),
+                reason: "expected nothing or clear".to_string(),
+            })
+        }
+    }
+    let lines: Vec<usize> = app.editor.bookmarks.lines().collect();
+    if lines.is_empty() {
+        app.set_status("no bookmarks, toggle one on the cursor line with ctrl+f2");
+        return Ok(());
+    }
+    let items = lines
+        .iter()
+        .map(|&y| {
+            let preview = app.editor.editor_content.get(y).map_or("", |l| l.trim());
+            format!("line {}: {}", y + 1, preview)
+        })
+        .collect();
+    app.open_popup(Box::new(ListPopup::new("Bookmarks", items)));
+    app.pending_states
+        .push(PendingState::PickingBookmark(lines));
+    Ok(())
+}
+
+///reads a single mark letter from the first argument, like a or 'a
+fn parse_mark_name(command: &str, args: &[String]) -> Result<char, CommandError> {
+    let arg = args.first().map(|a| a.trim_start_matches('\''));
+    let mut chars = arg.unwrap_or("").chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphabetic() => Ok(c),
+        _ => Err(CommandError::InvalidArguments {
+            command: command.to_string(),
+            reason: "expected a mark letter, like a".to_string(),
+        }),
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_goto_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line_targets() {
+        assert_eq!(LineTarget::parse("12"), Some(LineTarget::Absolute(12)));
+        assert_eq!(LineTarget::parse("+10"), Some(LineTarget::Relative(10)));
+        assert_eq!(LineTarget::parse("-5"), Some(LineTarget::Relative(-5)));
+        assert_eq!(LineTarget::parse("50%"), Some(LineTarget::Percent(50)));
+        for arg in ["a", "'a", "+", "++3", "-+3", "%", "5%%", "1.5"] {
+            assert_eq!(LineTarget::parse(arg), None, "{}", arg);
+        }
+    }
+
+    #[test]
+    fn test_relative_targets_clamp_at_first_and_last_line() {
+        assert_eq!(LineTarget::Relative(10).resolve(5, 100), 15);
+        assert_eq!(LineTarget::Relative(-5).resolve(3, 100), 0);
+        assert_eq!(LineTarget::Relative(10).resolve(95, 100), 99);
+        assert_eq!(LineTarget::Relative(-1).resolve(0, 1), 0);
+    }
+
+    #[test]
+    fn test_absolute_and_percent_targets_clamp() {
+        assert_eq!(LineTarget::Absolute(1).resolve(50, 100), 0);
+        assert_eq!(LineTarget::Absolute(0).resolve(50, 100), 0);
+        assert_eq!(LineTarget::Absolute(500).resolve(0, 100), 99);
+        assert_eq!(LineTarget::Percent(0).resolve(50, 100), 0);
+        assert_eq!(LineTarget::Percent(50).resolve(0, 100), 49);
+        assert_eq!(LineTarget::Percent(100).resolve(0, 100), 99);
+        assert_eq!(LineTarget::Percent(250).resolve(0, 100), 99);
+        assert_eq!(LineTarget::Percent(50).resolve(0, 1), 0);
+    }
+}
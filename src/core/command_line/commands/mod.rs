@@ -1,3 +1,18 @@
+pub mod annotations;
+pub mod case;
+pub mod clipboard;
+pub mod encoding;
 pub mod file;
 pub mod help;
+pub mod hex;
+pub mod insert;
+pub mod lines;
+pub mod macros;
+pub mod marks;
+pub mod numbers;
 pub mod quit;
+pub mod settings;
+pub mod spell;
+pub mod stats;
+pub mod undo;
+pub mod view;
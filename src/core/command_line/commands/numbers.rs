@@ -0,0 +1,167 @@
+//number related commands: incrementing, decrementing and calculating
+
+use crate::core::app::App;
+use crate::core::calc;
+use crate::core::errors::command_errors::CommandError;
+
+///changes the number at the cursor, or the first number of each selected line, by an
+/// optional amount in direction. seq numbers the selected lines, line n changing by n times the amount.
+pub fn increment_command(
+    app: &mut App,
+    args: Vec<String>,
+    direction: i64,
+) -> Result<(), CommandError> {
+    let command = if direction < 0 { "dec" } else { "inc" };
+    let mut amount = 1;
+    let mut sequence = false;
+    for arg in &args {
+        match arg.as_str() {
+            "seq" => sequence = true,
+            _ => {
+                amount = arg.parse().map_err(|_| CommandError::InvalidArguments {
+                    command: command.to_string(),
+                    reason: format!("expected an amount or seq, got '{}'", arg),
+                })?
+            }
+        }
+    }
+
+    if app.editor.increment_numbers(amount * direction, sequence) {
+        Ok(())
+    } else {
+        Err(CommandError::ExecutionFailed("no number found".to_string()))
+    }
+}
+
+///evaluates the arithmetic expression in args and shows the result, or with insert puts
+/// it at the cursor. a syntax error marks where it was found with a ^
+pub fn calc_command(app: &mut App, args: Vec<String>, insert: bool) -> Result<(), CommandError> {
+    let expr = args.join(" ");
+    if expr.is_empty() {
+        return Err(CommandError::InvalidArguments {
+            command: "calc".to_string(),
+            reason: "expected an expression, like 2 * (3 + 4)".to_string(),
+        });
+    }
+    let value = calc::evaluate(&expr).map_err(|e| {
+        let (before, after) = expr.split_at(
+            expr.char_indices()
+                .nth(e.col)
+                .map_or(expr.len(), |(i, _)| i),
+        );
+        CommandError::InvalidArguments {
+            command: "calc".to_string(),
+            reason: format!("{}: {}^{}", e, before, after),
+        }
+    })?;
+    if insert {
+        app.editor.insert_text(&[value.to_string()]);
+    } else {
+        app.set_status(format!("{} = {}", expr, value));
+    }
+    Ok(())
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_numbers_command_tests {
+    use super::*;
+    use crate::core::position::{Position, Range};
+
+    fn create_app(lines: &[&str]) -> App {
+        let mut app = App::new();
+        app.editor.editor_content = lines.iter().map(|l| l.to_string()).collect();
+        app.editor.editor_height = 10;
+        app
+    }
+
+    fn args(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_increment_by_amount_moves_cursor_after_number() {
+        let mut app = create_app(&["width: 8px"]);
+        increment_command(&mut app, args(&["5"]), 1).unwrap();
+        assert_eq!(app.editor.editor_content[0], "width: 13px");
+        assert_eq!(app.editor.cursor.x, 9);
+    }
+
+    #[test]
+    fn test_decrement_selected_lines() {
+        let mut app = create_app(&["a 5", "b 5", "c 5"]);
+        app.editor.selection = Some(Range::new(Position::new(0, 0), Position::new(1, 1)));
+        increment_command(&mut app, args(&[]), -1).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["a 4", "b 4", "c 5"]);
+    }
+
+    #[test]
+    fn test_sequence_renumbers_list() {
+        let mut app = create_app(&["0. one", "0. two", "0. three"]);
+        app.editor.selection = Some(Range::new(Position::new(0, 0), Position::new(2, 8)));
+        increment_command(&mut app, args(&["seq"]), 1).unwrap();
+        assert_eq!(
+            app.editor.editor_content,
+            vec!["1. one", "2. two", "3. three"]
+        );
+
+        app.editor.undo().unwrap();
+        assert_eq!(
+            app.editor.editor_content,
+            vec!["0. one", "0. two", "0. three"]
+        );
+    }
+
+    #[test]
+    fn test_no_number_and_bad_amount_fail() {
+        let mut app = create_app(&["text"]);
+        assert!(matches!(
+            increment_command(&mut app, args(&[]), 1),
+            Err(CommandError::ExecutionFailed(_))
+        ));
+        assert!(matches!(
+            increment_command(&mut app, args(&["lots"]), 1),
+            Err(CommandError::InvalidArguments { .. })
+        ));
+    }
+
+    #[test]
+    fn test_calc_shows_result_in_status() {
+        let mut app = create_app(&["size: "]);
+        calc_command(&mut app, args(&["0x20", "*", "(3+1)"]), false).unwrap();
+        assert_eq!(app.status(), Some("0x20 * (3+1) = 128"));
+        assert_eq!(app.editor.editor_content, vec!["size: "]);
+    }
+
+    #[test]
+    fn test_calc_bang_inserts_result_at_cursor() {
+        let mut app = create_app(&["size: px"]);
+        app.editor.cursor.x = 6;
+        calc_command(&mut app, args(&["7", "/", "2"]), true).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["size: 3.5px"]);
+    }
+
+    #[test]
+    fn test_calc_error_marks_column_with_caret() {
+        let mut app = create_app(&[""]);
+        let e = calc_command(&mut app, args(&["2", "*", "(3", "+"]), false).unwrap_err();
+        assert!(
+            e.to_string()
+                .ends_with("expected a number, got the end at column 9: 2 * (3 +^"),
+            "{}",
+            e
+        );
+        let e = calc_command(&mut app, args(&["1/0"]), false).unwrap_err();
+        assert!(
+            e.to_string()
+                .ends_with("division by zero at column 2: 1^/0"),
+            "{}",
+            e
+        );
+    }
+}
@@ -1,4 +1,5 @@
-// commands related to closing app, quit, exit, save_and_quit
+// commands related to closing app, quit, exit, save_and_quit, and pending which shows or
+// clears the questions they queue
 
 use crate::core::app::App;
 use crate::core::app::PendingState;
@@ -12,21 +13,42 @@ pub(crate) fn save_and_exit_command(
     args: Vec<String>,
     flags: HashSet<CommandFlag>,
 ) -> Result<(), CommandError> {
-    match file::save_command(app, args, flags) {
-        Ok(_) => {
-            // If a save confirmation is needed, push Quit AFTER Saving
-            if app
-                .pending_states
-                .iter()
-                .any(|s| matches!(s, PendingState::Saving(_)))
-            {
-                app.pending_states.push(PendingState::Quitting); // Add Quit to the queue
-                return Ok(());
-            }
-            app.quit();
+    file::save_command(app, args, flags)?;
+    //quits once the save and anything queued before it is resolved
+    app.queue_pending(PendingState::Quitting);
+    Ok(())
+}
+
+///saves every named buffer like :wa, then quits once the questions it queued are answered.
+/// nothing is quit if a save failed, a file not overwritten when asked counts as skipped.
+pub(crate) fn save_all_and_exit_command(app: &mut App) -> Result<(), CommandError> {
+    let report = app.save_all();
+    if report.failed.is_empty() {
+        app.queue_pending(PendingState::Quitting);
+    }
+    Ok(())
+}
 
-            Ok(())
+///shows the queued pending states, with clear drops them without asking about them
+pub(crate) fn pending_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    match args.first().map(String::as_str) {
+        None => {
+            let status = app
+                .pending_summary()
+                .unwrap_or_else(|| "nothing pending".to_string());
+            app.set_status(status);
+        }
+        Some("clear") => {
+            let count = app.pending_states.len();
+            app.clear_pending_states();
+            app.set_status(format!("cleared {} pending", count));
+        }
+        Some(_) => {
+            return Err(CommandError::InvalidArguments {
+                command: "pending".to_string(),
+                reason: "expected nothing or clear".to_string(),
+            })
         }
-        Err(e) => Err(e),
     }
+    Ok(())
 }
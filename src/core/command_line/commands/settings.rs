@@ -0,0 +1,51 @@
+//settings related commands: set
+
+use crate::core::app::App;
+use crate::core::errors::command_errors::CommandError;
+use crate::core::file_format::{FileEncoding, ENCODING_NAMES};
+use crate::core::modal::EditorMode;
+
+///sets a setting, expects the setting name followed by its value
+pub fn set_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    let (Some(key), Some(value)) = (args.first(), args.get(1)) else {
+        return Err(CommandError::InvalidArguments {
+            command: "set".to_string(),
+            reason: "expected a setting name and a value".to_string(),
+        });
+    };
+
+    let result = match key.as_str() {
+        "fileencoding" => set_file_encoding(app, value),
+        _ => app.settings.set(key, value),
+    };
+    result.map_err(|reason| CommandError::InvalidArguments {
+        command: "set".to_string(),
+        reason,
+    })?;
+
+    if key == "wordboundary" {
+        app.editor.set_word_chars(app.settings.word_chars.clone());
+    }
+    //switching modal editing on always starts out in normal mode
+    if key == "modal" && app.settings.modal {
+        app.modal.mode = EditorMode::Normal;
+    }
+    Ok(())
+}
+
+///sets the encoding the open buffer is saved in, returns reason on failure
+fn set_file_encoding(app: &mut App, value: &str) -> Result<(), String> {
+    let encoding = FileEncoding::parse(value).ok_or_else(|| {
+        format!(
+            "expected one of {}, got '{}'",
+            ENCODING_NAMES.join(", "),
+            value
+        )
+    })?;
+    if encoding != app.file_format.encoding {
+        app.file_format.encoding = encoding;
+        //the file on disk no longer matches what saving would write
+        app.dirty = true;
+    }
+    Ok(())
+}
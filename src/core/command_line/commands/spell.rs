@@ -0,0 +1,178 @@
+//spell commands: turning spell checking on and off, jumping to and adding flagged words
+
+use crate::config::editor_settings;
+use crate::core::app::App;
+use crate::core::cursor::CursorPosition;
+use crate::core::errors::command_errors::CommandError;
+use crate::core::spell::{self, SpellChecker};
+
+///toggles spell checking without arguments, or expects on, off, next or add
+pub fn spell_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    match args.first().map(String::as_str) {
+        None if app.spell.is_some() => turn_off(app),
+        None | Some("on") => turn_on(app),
+        Some("off") => turn_off(app),
+        Some("next") => next_misspelled(app),
+        Some("add") => add_word_at_cursor(app),
+        Some(other) => Err(CommandError::InvalidArguments {
+            command: "spell".to_string(),
+            reason: format!("unknown spell '{}', expected on, off, next or add", other),
+        }),
+    }
+}
+
+fn turn_on(app: &mut App) -> Result<(), CommandError> {
+    if app.spell.is_none() {
+        app.spell = Some(SpellChecker::load_default());
+    }
+    if app.active_spell_checker().is_some() {
+        app.set_status("spell checking on");
+    } else {
+        app.set_status(format!(
+            "spell checking on, only for {} files",
+            editor_settings::SPELL_FILETYPES.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+fn turn_off(app: &mut App) -> Result<(), CommandError> {
+    app.spell = None;
+    app.set_status("spell checking off");
+    Ok(())
+}
+
+///moves the cursor to the start of the next misspelled word after it, wrapping around at
+/// the end of the buffer
+fn next_misspelled(app: &mut App) -> Result<(), CommandError> {
+    let spell = active_spell_checker(app)?;
+    let content = &app.editor.editor_content;
+    let cursor = app.editor.cursor;
+    let line_count = content.len();
+    //the cursor line comes last again, for the words before the cursor
+    let found = (0..=line_count).find_map(|offset| {
+        let y = (cursor.y + offset) % line_count;
+        spell
+            .misspelled(&content[y])
+            .into_iter()
+            .map(|range| range.start)
+            .find(|&x| match offset {
+                0 => x > cursor.x,
+                _ if offset == line_count => x <= cursor.x,
+                _ => true,
+            })
+            .map(|x| CursorPosition::new(x, y))
+    });
+    match found {
+        Some(pos) => app
+            .editor
+            .set_cursor(pos)
+            .map_err(|e| CommandError::ExecutionFailed(e.to_string())),
+        None => {
+            app.set_status("no misspelled words");
+            Ok(())
+        }
+    }
+}
+
+///adds the word at the cursor to the user dictionary
+fn add_word_at_cursor(app: &mut App) -> Result<(), CommandError> {
+    active_spell_checker(app)?;
+    let line = &app.editor.editor_content[app.editor.cursor.y];
+    let Some((_, word)) = spell::word_at(line, app.editor.cursor.x) else {
+        return Err(CommandError::InvalidState(
+            "no word at the cursor".to_string(),
+        ));
+    };
+    let word = word.to_string();
+    if let Some(spell) = app.spell.as_mut() {
+        spell
+            .add_word(&word)
+            .map_err(|e| CommandError::ExecutionFailed(format!("could not add word: {}", e)))?;
+    }
+    app.set_status(format!("added '{}' to the dictionary", word));
+    Ok(())
+}
+
+fn active_spell_checker(app: &App) -> Result<&SpellChecker, CommandError> {
+    match (&app.spell, app.active_spell_checker()) {
+        (_, Some(spell)) => Ok(spell),
+        (None, _) => Err(CommandError::InvalidState(
+            "spell checking is off, turn it on with :spell".to_string(),
+        )),
+        (Some(_), None) => Err(CommandError::InvalidState(format!(
+            "spell checking is only for {} files",
+            editor_settings::SPELL_FILETYPES.join(", ")
+        ))),
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_spell_command_tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn create_app(lines: &[&str], file: &str) -> App {
+        let mut app = App::new();
+        app.editor.editor_content = lines.iter().map(|l| l.to_string()).collect();
+        app.editor.editor_height = 10;
+        app.file_path = Some(file.to_string());
+        app.spell = Some(SpellChecker::new(None));
+        app
+    }
+
+    #[test]
+    fn test_next_jumps_to_misspelled_words_and_wraps() {
+        let mut app = create_app(&["teh first line", "all good", "a secnd one"], "notes.md");
+        spell_command(&mut app, vec!["next".to_string()]).unwrap();
+        assert_eq!(app.editor.cursor(), CursorPosition::new(2, 2));
+        spell_command(&mut app, vec!["next".to_string()]).unwrap();
+        assert_eq!(app.editor.cursor(), CursorPosition::new(0, 0));
+        spell_command(&mut app, vec!["next".to_string()]).unwrap();
+        assert_eq!(app.editor.cursor(), CursorPosition::new(2, 2));
+    }
+
+    #[test]
+    fn test_add_word_at_cursor() {
+        let dir = tempdir().unwrap();
+        let dictionary = dir.path().join("dictionary");
+        let mut app = create_app(&["see calliglyph notes"], "notes.txt");
+        app.spell = Some(SpellChecker::new(Some(dictionary.clone())));
+        app.editor.cursor.x = 8;
+
+        spell_command(&mut app, vec!["add".to_string()]).unwrap();
+        assert_eq!(fs::read_to_string(dictionary).unwrap(), "calliglyph\n");
+        assert_eq!(app.status(), Some("added 'calliglyph' to the dictionary"));
+        spell_command(&mut app, vec!["next".to_string()]).unwrap();
+        assert_eq!(app.status(), Some("no misspelled words"));
+    }
+
+    #[test]
+    fn test_spell_only_runs_for_prose_files() {
+        let mut app = create_app(&["fn mian() {}"], "main.rs");
+        assert!(app.active_spell_checker().is_none());
+        assert!(matches!(
+            spell_command(&mut app, vec!["next".to_string()]),
+            Err(CommandError::InvalidState(_))
+        ));
+    }
+
+    #[test]
+    fn test_spell_toggles() {
+        let mut app = create_app(&[""], "notes.md");
+        spell_command(&mut app, vec![]).unwrap();
+        assert!(app.spell.is_none());
+        assert!(matches!(
+            spell_command(&mut app, vec!["add".to_string()]),
+            Err(CommandError::InvalidState(_))
+        ));
+        spell_command(&mut app, vec!["nope".to_string()]).unwrap_err();
+    }
+}
@@ -0,0 +1,141 @@
+//stats related commands: word count and buffer info
+
+use super::insert::format_timestamp;
+use crate::core::app::App;
+use crate::core::editor::stats::TextStats;
+use crate::core::errors::command_errors::CommandError;
+use crate::core::session::absolute_path;
+use crate::ui::popups::list_popup::ListPopup;
+use std::fs::{self, Metadata, Permissions};
+use std::time::UNIX_EPOCH;
+
+///shows line, word, char and byte counts of the selection, or of the whole content
+pub fn word_count_command(app: &mut App) -> Result<(), CommandError> {
+    let status = match app.editor.selection_stats() {
+        Some(stats) => format!("selection: {}", stats),
+        None => app.editor.content_stats().to_string(),
+    };
+    app.set_status(status);
+    Ok(())
+}
+
+///opens a popup describing the buffer and its file on disk. untitled buffers only get the
+/// in-memory counts, file details that can't be read show why instead of failing the popup
+pub fn info_command(app: &mut App) -> Result<(), CommandError> {
+    //the hex view shows a dump in place of the text, count the text it set aside
+    let stats = match app.hex_view {
+        Some(_) => TextStats::of_lines(&app.text_editor().editor_content),
+        None => app.editor.content_stats(),
+    };
+    let mut items = vec![
+        format!("lines: {}", stats.lines),
+        format!("chars: {}", stats.chars),
+    ];
+    let Some(path) = app.file_path.clone() else {
+        items.insert(0, "untitled buffer".to_string());
+        app.open_popup(Box::new(ListPopup::new("Info", items)));
+        return Ok(());
+    };
+    items.insert(0, format!("path: {}", absolute_path(&path)));
+    let format = app.file_format;
+    items.push(format!("encoding: {}", format.encoding.name()));
+    //lines are split on \n and \r\n alike and always written back with \n
+    items.push(format!(
+        "line ending: lf{}",
+        if format.final_newline {
+            ", final newline"
+        } else {
+            ""
+        }
+    ));
+    items.extend(disk_info(fs::metadata(&path)));
+    items.push(format!(
+        "read-only: {}",
+        app.read_only_reason().unwrap_or("no")
+    ));
+    let differs = match app.file_has_changes(&path) {
+        Ok(true) => "yes".to_string(),
+        Ok(false) => "no".to_string(),
+        Err(e) => format!("unknown, {}", e),
+    };
+    items.push(format!("differs from disk: {}", differs));
+    app.open_popup(Box::new(ListPopup::new("Info", items)));
+    Ok(())
+}
+
+///size, permissions and modification time of a file, each field falls back on its own
+fn disk_info(metadata: std::io::Result<Metadata>) -> Vec<String> {
+    let metadata = match metadata {
+        Ok(metadata) => metadata,
+        Err(e) => return vec![format!("on disk: {}", e)],
+    };
+    let modified = metadata
+        .modified()
+        .map_err(|e| e.to_string())
+        .and_then(|time| time.duration_since(UNIX_EPOCH).map_err(|e| e.to_string()))
+        .map(|since| format_timestamp(since.as_secs(), "%Y-%m-%d %H:%M:%S UTC"))
+        .unwrap_or_else(|e| format!("unknown, {}", e));
+    vec![
+        format!("size on disk: {} bytes", metadata.len()),
+        format!(
+            "permissions: {}",
+            describe_permissions(&metadata.permissions())
+        ),
+        format!("modified: {}", modified),
+    ]
+}
+
+///permissions as an ls like rwx string where the platform has modes, else if it's writable
+fn describe_permissions(permissions: &Permissions) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = permissions.mode();
+        let bits = "rwxrwxrwx";
+        bits.chars()
+            .enumerate()
+            .map(|(i, c)| if mode & (0o400 >> i) != 0 { c } else { '-' })
+            .collect()
+    }
+    #[cfg(not(unix))]
+    {
+        if permissions.readonly() {
+            "read-only".to_string()
+        } else {
+            "writable".to_string()
+        }
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_info_tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn test_missing_file_is_one_field_not_an_error() {
+        let items = disk_info(Err(io::Error::from(io::ErrorKind::NotFound)));
+        assert_eq!(items.len(), 1);
+        assert!(items[0].starts_with("on disk: "), "{}", items[0]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_permissions_read_like_ls() {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(
+            describe_permissions(&Permissions::from_mode(0o644)),
+            "rw-r--r--"
+        );
+        assert_eq!(
+            describe_permissions(&Permissions::from_mode(0o750)),
+            "rwxr-x---"
+        );
+    }
+}
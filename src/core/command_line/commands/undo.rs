@@ -0,0 +1,184 @@
+//undo history commands: undoing and redoing a number of steps, listing the undo steps and
+// jumping to the state after one
+
+use crate::core::app::{App, PendingState};
+use crate::core::editor::undo_redo;
+use crate::core::errors::command_errors::CommandError;
+use crate::ui::popups::list_popup::ListPopup;
+
+///undoes the number of steps given, one without, or redoes them if redo. fewer steps than
+/// asked for are all undone.
+pub fn undo_command(app: &mut App, args: Vec<String>, redo: bool) -> Result<(), CommandError> {
+    let name = if redo { "redo" } else { "undo" };
+    let count = match args.first() {
+        None => 1,
+        Some(count) => match count.parse::<usize>() {
+            Ok(count) if count > 0 => count,
+            _ => {
+                return Err(CommandError::InvalidArguments {
+                    command: name.to_string(),
+                    reason: format!("expected a number of steps, got '{}'", count),
+                })
+            }
+        },
+    };
+    let steps = if redo {
+        app.editor.redo_steps(count)
+    } else {
+        app.editor.undo_steps(count)
+    }
+    .map_err(|e| CommandError::ExecutionFailed(e.to_string()))?;
+    let verb = if redo { "redid" } else { "undid" };
+    let plural = if steps == 1 { "" } else { "s" };
+    app.set_status(format!("{} {} step{}", verb, steps, plural));
+    Ok(())
+}
+
+///opens a popup listing the undo history newest first, picking an entry undoes or redoes
+/// steps until the content is as it was right after it. undone steps are dimmed and the
+/// current state is marked with *.
+pub fn undolist_command(app: &mut App) -> Result<(), CommandError> {
+    let (history, applied) = app.editor.undo_history();
+    if history.is_empty() {
+        app.set_status("nothing to undo");
+        return Ok(());
+    }
+    let states: Vec<usize> = (0..=history.len()).rev().collect();
+    let items = states
+        .iter()
+        .map(|&state| {
+            let marker = if state == applied { '*' } else { ' ' };
+            match state.checked_sub(1).map(|i| &history[i]) {
+                None => format!("{} original", marker),
+                Some(entry) => {
+                    let pos = entry.position();
+                    format!(
+                        "{} {} at {}:{}  \"{}\"  {}",
+                        marker,
+                        entry.kind(),
+                        pos.y + 1,
+                        pos.x + 1,
+                        entry.preview(),
+                        undo_redo::age(entry.recorded.elapsed())
+                    )
+                }
+            }
+        })
+        .collect();
+    let dimmed = states.iter().map(|&state| state > applied).collect();
+    let mut popup = ListPopup::new("Undo history", items).with_dimmed(dimmed);
+    popup.selected = history.len() - applied;
+    app.open_popup(Box::new(popup));
+    app.pending_states
+        .push(PendingState::PickingUndoState(states));
+    Ok(())
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_undo_command_tests {
+    use super::*;
+    use crate::core::app::ActiveArea;
+    use crate::core::command_line::command::parse_command;
+    use crate::core::command_line::command_executor::execute_command;
+    use crate::core::position::{Position, Range};
+    use crate::input::input_action::InputAction;
+    use crate::ui::popups::popup::PopupResult;
+    use std::time::Duration;
+
+    #[test]
+    fn test_picking_an_entry_reverts_to_it() {
+        let mut app = App::new();
+        app.editor.editor_content = vec![String::new()].into();
+        for c in "xyz".chars() {
+            app.editor.insert_char(c).unwrap();
+        }
+        app.editor.undo().unwrap();
+
+        undolist_command(&mut app).unwrap();
+        assert_eq!(
+            app.pending_states,
+            vec![PendingState::PickingUndoState(vec![3, 2, 1, 0])]
+        );
+        //list is newest first, pick the first x
+        app.popup_result = PopupResult::Index(2);
+        app.handle_list_popup_response();
+        assert_eq!(app.editor.editor_content, vec!["x"]);
+        assert!(app.pending_states.is_empty());
+
+        undolist_command(&mut app).unwrap();
+        app.popup_result = PopupResult::Index(0);
+        app.handle_list_popup_response();
+        assert_eq!(app.editor.editor_content, vec!["xyz"]);
+    }
+
+    #[test]
+    fn test_empty_history_sets_status() {
+        let mut app = App::new();
+        undolist_command(&mut app).unwrap();
+        assert!(app.popup.is_none());
+        assert_eq!(app.status(), Some("nothing to undo"));
+        assert_eq!(undo_redo::age(Duration::from_secs(150)), "2m ago");
+    }
+
+    #[test]
+    fn test_undo_and_redo_take_counts() {
+        let mut app = App::new();
+        app.editor.editor_content = vec![String::new()].into();
+        for c in "xyz".chars() {
+            app.editor.insert_char(c).unwrap();
+        }
+
+        undo_command(&mut app, vec!["2".to_string()], false).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["x"]);
+        assert_eq!(app.status(), Some("undid 2 steps"));
+        //only as many as there are
+        undo_command(&mut app, vec!["5".to_string()], true).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["xyz"]);
+        assert_eq!(app.status(), Some("redid 2 steps"));
+
+        assert!(undo_command(&mut app, vec![], true).is_err());
+        assert!(undo_command(&mut app, vec!["0".to_string()], false).is_err());
+        undo_command(&mut app, vec![], false).unwrap();
+        assert_eq!(app.editor.editor_content, vec!["xy"]);
+    }
+
+    #[test]
+    fn test_command_over_many_lines_undoes_in_one_step() {
+        let mut app = App::new();
+        app.editor.editor_content = vec!["a b".to_string(); 100].into();
+        app.editor.selection = Some(Range::new(Position::new(0, 0), Position::new(99, 3)));
+        let run = |app: &mut App, bind: &str, args: &[&str]| {
+            let args = args.iter().map(|arg| arg.to_string()).collect();
+            execute_command(app, parse_command(bind.to_string(), args))
+        };
+        run(&mut app, "case", &["snake"]).unwrap();
+        assert!(app.editor.editor_content.iter().all(|line| line == "a_b"));
+
+        run(&mut app, "undo", &[]).unwrap();
+        assert!(app.editor.editor_content.iter().all(|line| line == "a b"));
+        assert!(!app.editor.can_undo());
+    }
+
+    #[test]
+    fn test_substitute_over_many_lines_undoes_in_one_undo() {
+        let mut app = App::new();
+        app.editor.editor_content = vec!["a a".to_string(); 100].into();
+        let type_command = |app: &mut App, command: &str| {
+            app.active_area = ActiveArea::CommandLine;
+            app.command_line.input = command.to_string();
+            app.process_input_action(InputAction::ENTER);
+        };
+        type_command(&mut app, ":s/a/b/g");
+        assert!(app.editor.editor_content.iter().all(|line| line == "b b"));
+
+        type_command(&mut app, ":undo");
+        assert!(app.editor.editor_content.iter().all(|line| line == "a a"));
+        assert!(!app.editor.can_undo());
+    }
+}
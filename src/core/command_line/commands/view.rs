@@ -0,0 +1,45 @@
+//view command: piping the buffer to a pager or other external program, like :view less
+
+use crate::core::app::{App, TerminalTask};
+use crate::core::errors::command_errors::CommandError;
+
+///pipes the buffer to the program and args given, like :view bat -l rust. the editor steps
+/// aside until it exits and comes back as it was.
+pub fn view_command(app: &mut App, args: Vec<String>) -> Result<(), CommandError> {
+    if args.is_empty() {
+        return Err(CommandError::InvalidArguments {
+            command: "view".to_string(),
+            reason: "expected a program to view the buffer with, like :view less".to_string(),
+        });
+    }
+    app.request_terminal(TerminalTask::View(args));
+    Ok(())
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_view_command_tests {
+    use super::*;
+    use crate::core::command_line::command::parse_command;
+    use crate::core::command_line::command_executor::execute_command;
+
+    #[test]
+    fn test_view_needs_a_program() {
+        let mut app = App::new();
+        assert!(view_command(&mut app, vec![]).is_err());
+        assert_eq!(app.take_terminal_task(), None);
+    }
+
+    #[test]
+    fn test_viewer_args_keep_their_flags() {
+        let mut app = App::new();
+        let args = vec!["bat".to_string(), "--force".to_string()];
+        execute_command(&mut app, parse_command("view".to_string(), args.clone())).unwrap();
+        assert_eq!(app.take_terminal_task(), Some(TerminalTask::View(args)));
+    }
+}
@@ -0,0 +1,252 @@
+//tab completion in the command line, of command names and of the arguments of commands
+// that have an argument completer
+
+use crate::config::command_binds::*;
+use crate::core::editor::case::CASE_NAMES;
+use crate::core::settings::{BUFFER_SETTINGS, SETTINGS};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+///completes the word being typed, given the arguments before it. returns every candidate
+/// the word could become.
+type ArgumentCompleter = fn(&[&str], &str) -> Vec<String>;
+
+///argument completer of a command, commands without one only complete their name
+fn argument_completer(command: &str) -> Option<ArgumentCompleter> {
+    match command {
+        COMMAND_SET => Some(complete_set),
+        COMMAND_BLANK => Some(complete_blank),
+        COMMAND_CASE => Some(complete_case),
+        COMMAND_BOOKMARKS | COMMAND_PENDING => Some(complete_clear),
+        COMMAND_CD => Some(complete_dir),
+        COMMAND_OPEN | COMMAND_SAVE_DONT_EXIT | COMMAND_SAVE_AND_EXIT | COMMAND_EXTRACT => {
+            Some(complete_path)
+        }
+        _ => None,
+    }
+}
+
+///candidates for the word at the end of input, sorted. the first word completes to
+/// command names, later ones to what the command's completer offers.
+pub fn candidates(input: &str) -> Vec<String> {
+    let Some(input) = input.strip_prefix(':') else {
+        return vec![];
+    };
+    let mut words: Vec<&str> = input.split_whitespace().collect();
+    //a trailing space starts a new, empty word
+    if input.is_empty() || input.ends_with(char::is_whitespace) {
+        words.push("");
+    }
+    let (word, before) = words.split_last().unwrap_or((&"", &[]));
+    let mut candidates = match before.split_first() {
+        None => COMMAND_BINDS
+            .iter()
+            .filter(|bind| bind.starts_with(word))
+            .map(|bind| bind.to_string())
+            .collect(),
+        Some((command, args)) => match argument_completer(command) {
+            Some(completer) => completer(args, word),
+            None => vec![],
+        },
+    };
+    candidates.sort();
+    candidates.dedup();
+    candidates
+}
+
+///longest start all candidates share
+pub fn common_prefix(candidates: &[String]) -> &str {
+    let Some(first) = candidates.first() else {
+        return "";
+    };
+    let len = candidates[1..].iter().fold(first.len(), |len, candidate| {
+        first[..len]
+            .char_indices()
+            .zip(candidate.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(len.min(candidate.len()), |((i, _), _)| i)
+    });
+    &first[..len]
+}
+
+///setting names, then the values the named setting takes
+fn complete_set(args: &[&str], word: &str) -> Vec<String> {
+    let offered: Vec<&str> = match args {
+        [] => SETTINGS
+            .iter()
+            .chain(BUFFER_SETTINGS)
+            .map(|(name, _)| *name)
+            .collect(),
+        [name] => SETTINGS
+            .iter()
+            .chain(BUFFER_SETTINGS)
+            .find(|(setting, _)| setting == name)
+            .map_or(vec![], |(_, values)| values.to_vec()),
+        _ => vec![],
+    };
+    offered
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(word))
+        .map(String::from)
+        .collect()
+}
+
+///what :blank does with the blank lines
+fn complete_blank(args: &[&str], word: &str) -> Vec<String> {
+    if !args.is_empty() {
+        return vec![];
+    }
+    ["delete", "squeeze"]
+        .into_iter()
+        .filter(|action| action.starts_with(word))
+        .map(String::from)
+        .collect()
+}
+
+///the cases :case converts to
+fn complete_case(args: &[&str], word: &str) -> Vec<String> {
+    if !args.is_empty() {
+        return vec![];
+    }
+    CASE_NAMES
+        .iter()
+        .filter(|name| name.starts_with(word))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+///the clear argument of commands listing what they can clear
+fn complete_clear(args: &[&str], word: &str) -> Vec<String> {
+    if args.is_empty() && "clear".starts_with(word) {
+        return vec!["clear".to_string()];
+    }
+    vec![]
+}
+
+///files and dirs starting with word, relative to the working dir or ~. dirs end in a
+/// slash so completing can go on inside them, hidden ones are offered once word starts
+/// their name with a dot.
+fn complete_path(_args: &[&str], word: &str) -> Vec<String> {
+    let (dir, prefix) = match word.rfind('/') {
+        Some(i) => word.split_at(i + 1),
+        None => ("", word),
+    };
+    let Some(dir_path) = expand_dir(dir) else {
+        return vec![];
+    };
+    let Ok(entries) = fs::read_dir(dir_path) else {
+        return vec![];
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            let slash = if entry.path().is_dir() { "/" } else { "" };
+            Some(format!("{}{}{}", dir, name, slash))
+        })
+        .collect()
+}
+
+///dirs starting with word, like complete_path
+fn complete_dir(args: &[&str], word: &str) -> Vec<String> {
+    complete_path(args, word)
+        .into_iter()
+        .filter(|candidate| candidate.ends_with('/'))
+        .collect()
+}
+
+///the dir part of a typed path, with a leading ~ as the home dir
+fn expand_dir(dir: &str) -> Option<PathBuf> {
+    if dir.is_empty() {
+        return Some(PathBuf::from("."));
+    }
+    match dir.strip_prefix("~/") {
+        Some(rest) => Some(Path::new(&env::var_os("HOME")?).join(rest)),
+        None => Some(PathBuf::from(dir)),
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_completion_tests {
+    use super::*;
+    use crate::core::command_line::command::{parse_command, Command};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_command_names_complete_from_every_bind() {
+        assert_eq!(
+            candidates(":un"),
+            vec!["undo", "undolist", "unicode", "unique", "unsurround"]
+        );
+        assert_eq!(candidates(":se"), vec!["set"]);
+        assert!(candidates(":zz").is_empty());
+        assert!(candidates("set").is_empty());
+        assert_eq!(candidates(":").len(), COMMAND_BINDS.len());
+        for bind in COMMAND_BINDS {
+            let command = parse_command(bind.to_string(), vec![]);
+            assert!(!matches!(command, Command::Unknown { .. }), "{}", bind);
+        }
+    }
+
+    #[test]
+    fn test_set_completes_names_then_values() {
+        assert_eq!(candidates(":set re"), vec!["restoreposition"]);
+        assert_eq!(
+            candidates(":set ").len(),
+            SETTINGS.len() + BUFFER_SETTINGS.len()
+        );
+        assert_eq!(candidates(":set fileencoding l"), vec!["latin1"]);
+        assert_eq!(candidates(":set modal "), vec!["off", "on"]);
+        assert_eq!(candidates(":set modal of"), vec!["off"]);
+        assert!(candidates(":set tabwidth ").is_empty());
+        assert!(candidates(":set modal on ").is_empty());
+        assert!(candidates(":macros ").is_empty());
+        assert_eq!(candidates(":blank s"), vec!["squeeze"]);
+    }
+
+    #[test]
+    fn test_paths_complete_with_slash_after_dirs() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("notes")).unwrap();
+        fs::write(dir.path().join("notes.md"), "").unwrap();
+        fs::write(dir.path().join(".hidden"), "").unwrap();
+        let base = format!("{}/", dir.path().display());
+
+        assert_eq!(
+            candidates(&format!(":open {}no", base)),
+            vec![format!("{}notes.md", base), format!("{}notes/", base)]
+        );
+        assert_eq!(candidates(&format!(":w {}", base)).len(), 2);
+        assert_eq!(
+            candidates(&format!(":cd {}", base)),
+            vec![format!("{}notes/", base)]
+        );
+        assert_eq!(
+            candidates(&format!(":open {}.h", base)),
+            vec![format!("{}.hidden", base)]
+        );
+    }
+
+    #[test]
+    fn test_common_prefix_of_candidates() {
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            common_prefix(&names(&["undolist", "unicode", "unique"])),
+            "un"
+        );
+        assert_eq!(common_prefix(&names(&["notes/", "notes.md"])), "notes");
+        assert_eq!(common_prefix(&names(&["ä1", "ä2"])), "ä");
+        assert_eq!(common_prefix(&names(&["off"])), "off");
+        assert_eq!(common_prefix(&[]), "");
+    }
+}
@@ -1,7 +1,9 @@
 pub mod command;
 pub mod command_executor;
+#[allow(clippy::module_inception)]
 mod command_line;
 pub mod commands;
+pub mod completion;
 
 // Re-export the Editor struct for simpler imports elsewhere
 pub use command_line::CommandLine;
@@ -3,8 +3,8 @@ use std::ops::{Add, Sub};
 /// handles cursor
 #[derive(Debug, Clone, Copy, Default)]
 pub struct Cursor {
-    pub x: i16,
-    pub y: i16,
+    pub x: usize,
+    pub y: usize,
 }
 
 impl Cursor {
@@ -13,10 +13,18 @@ impl Cursor {
     }
 }
 
-#[derive(Debug, Copy, Clone, Default)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
 pub struct CursorPosition {
-    pub(crate) x: usize,
-    pub(crate) y: usize,
+    ///char index in the line
+    pub x: usize,
+    ///line index
+    pub y: usize,
+}
+
+impl CursorPosition {
+    pub fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
 }
 
 impl Add<CursorPosition> for CursorPosition {
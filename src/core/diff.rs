@@ -0,0 +1,279 @@
+//line diffs between two versions of a buffer, with myers' algorithm
+
+///lines around a change shown with it in unified diffs
+const CONTEXT_LINES: usize = 3;
+
+///a step turning the old lines into the new ones, with the line indexes it refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Equal { old: usize, new: usize },
+    Delete { old: usize },
+    Insert { new: usize },
+}
+
+///counts of the lines a diff adds and removes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DiffStat {
+    pub added: usize,
+    pub removed: usize,
+}
+
+impl DiffStat {
+    pub fn of(changes: &[Change]) -> Self {
+        let mut stat = Self::default();
+        for change in changes {
+            match change {
+                Change::Insert { .. } => stat.added += 1,
+                Change::Delete { .. } => stat.removed += 1,
+                Change::Equal { .. } => {}
+            }
+        }
+        stat
+    }
+}
+
+///the shortest list of changes turning old into new, deletes before inserts where they
+/// replace each other
+pub fn diff<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Change> {
+    //lines equal at both ends are kept out of the search
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut changes: Vec<Change> = (0..prefix)
+        .map(|i| Change::Equal { old: i, new: i })
+        .collect();
+    let middle = myers(
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+    changes.extend(middle.into_iter().map(|change| match change {
+        Change::Equal { old, new } => Change::Equal {
+            old: old + prefix,
+            new: new + prefix,
+        },
+        Change::Delete { old } => Change::Delete { old: old + prefix },
+        Change::Insert { new } => Change::Insert { new: new + prefix },
+    }));
+    changes.extend((0..suffix).map(|i| Change::Equal {
+        old: old.len() - suffix + i,
+        new: new.len() - suffix + i,
+    }));
+    changes
+}
+
+///unified diff of old and new with a few lines of context around each change,
+/// empty if they are equal
+pub fn unified(old: &[String], new: &[String], old_name: &str, new_name: &str) -> Vec<String> {
+    let changes = diff(old, new);
+    let changed: Vec<usize> = changes
+        .iter()
+        .enumerate()
+        .filter(|(_, change)| !matches!(change, Change::Equal { .. }))
+        .map(|(i, _)| i)
+        .collect();
+    if changed.is_empty() {
+        return vec![];
+    }
+
+    //changes closer than twice the context share a hunk
+    let mut hunks: Vec<(usize, usize)> = vec![];
+    for &i in &changed {
+        let start = i.saturating_sub(CONTEXT_LINES);
+        let end = (i + CONTEXT_LINES + 1).min(changes.len());
+        match hunks.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut out = vec![format!("--- {}", old_name), format!("+++ {}", new_name)];
+    for (start, end) in hunks {
+        //lines of old and new before the hunk
+        let (old_before, new_before) =
+            changes[..start]
+                .iter()
+                .fold((0, 0), |(o, n), change| match change {
+                    Change::Equal { .. } => (o + 1, n + 1),
+                    Change::Delete { .. } => (o + 1, n),
+                    Change::Insert { .. } => (o, n + 1),
+                });
+        let hunk = &changes[start..end];
+        let stat = DiffStat::of(hunk);
+        let equal = hunk.len() - stat.added - stat.removed;
+        let (old_len, new_len) = (equal + stat.removed, equal + stat.added);
+        //an empty side starts at the line before it, like diff -u
+        let old_start = old_before + usize::from(old_len > 0);
+        let new_start = new_before + usize::from(new_len > 0);
+        out.push(format!(
+            "@@ -{},{} +{},{} @@",
+            old_start, old_len, new_start, new_len
+        ));
+        out.extend(hunk.iter().map(|change| match *change {
+            Change::Equal { old: i, .. } => format!(" {}", old[i]),
+            Change::Delete { old: i } => format!("-{}", old[i]),
+            Change::Insert { new: i } => format!("+{}", new[i]),
+        }));
+    }
+    out
+}
+
+///myers' greedy diff, keeping the furthest reaching path of every diagonal per edit
+/// count to walk the shortest edit script back from the end
+fn myers<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Change> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = n + m;
+    let offset = max as usize;
+    let index = |k: isize| (k + offset as isize) as usize;
+    let mut v = vec![0isize; 2 * offset + 2];
+    let mut trace: Vec<Vec<isize>> = vec![];
+
+    'search: for d in 0..=max {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+                v[index(k + 1)]
+            } else {
+                v[index(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut changes = vec![];
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = v[index(prev_k)];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            changes.push(Change::Equal {
+                old: x as usize,
+                new: y as usize,
+            });
+        }
+        if d > 0 {
+            if x == prev_x {
+                changes.push(Change::Insert {
+                    new: (y - 1) as usize,
+                });
+            } else {
+                changes.push(Change::Delete {
+                    old: (x - 1) as usize,
+                });
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    changes.reverse();
+    changes
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_diff_tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(String::from).collect()
+    }
+
+    ///applies changes to old, checking they produce new
+    fn apply(changes: &[Change], old: &[String], new: &[String]) -> Vec<String> {
+        changes
+            .iter()
+            .filter_map(|change| match *change {
+                Change::Equal { old: o, new: n } => {
+                    assert_eq!(old[o], new[n]);
+                    Some(new[n].clone())
+                }
+                Change::Insert { new: n } => Some(new[n].clone()),
+                Change::Delete { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_is_minimal_and_rebuilds_new() {
+        let old = lines("a\nb\nc\na\nb\nb\na");
+        let new = lines("c\nb\na\nb\na\nc");
+        let changes = diff(&old, &new);
+        assert_eq!(apply(&changes, &old, &new), new);
+        //the classic example from myers' paper needs 5 edits
+        let stat = DiffStat::of(&changes);
+        assert_eq!(stat.added + stat.removed, 5);
+    }
+
+    #[test]
+    fn test_diff_of_empty_sides() {
+        let text = lines("x\ny");
+        assert_eq!(DiffStat::of(&diff(&[], &text)).added, 2);
+        assert_eq!(DiffStat::of(&diff(&text, &[])).removed, 2);
+        assert!(diff::<String>(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_unified_groups_changes_into_hunks_with_context() {
+        let old: Vec<String> = (1..=20).map(|i| i.to_string()).collect();
+        let mut new = old.clone();
+        new[1] = "two".to_string();
+        new.insert(15, "new".to_string());
+
+        let out = unified(&old, &new, "disk", "buffer");
+        assert_eq!(
+            out,
+            vec![
+                "--- disk",
+                "+++ buffer",
+                "@@ -1,5 +1,5 @@",
+                " 1",
+                "-2",
+                "+two",
+                " 3",
+                " 4",
+                " 5",
+                "@@ -13,6 +13,7 @@",
+                " 13",
+                " 14",
+                " 15",
+                "+new",
+                " 16",
+                " 17",
+                " 18",
+            ]
+        );
+        assert!(unified(&old, &old, "a", "b").is_empty());
+    }
+
+    #[test]
+    fn test_unified_of_added_file_starts_at_zero() {
+        let out = unified(&[], &lines("a\nb"), "disk", "buffer");
+        assert_eq!(out[2], "@@ -0,0 +1,2 @@");
+    }
+}
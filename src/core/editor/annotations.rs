@@ -0,0 +1,138 @@
+use super::words::{is_word_char, WordChars};
+use crate::core::cursor::CursorPosition;
+use std::ops::Range;
+
+///longest preview of an annotated line in the :todos list, in chars
+const PREVIEW_CHARS: usize = 50;
+
+/// a keyword like TODO found in the content
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    ///start of the keyword, x in chars
+    pub pos: CursorPosition,
+    pub keyword: String,
+    ///the trimmed line, shortened to fit a popup
+    pub preview: String,
+}
+
+impl Annotation {
+    ///entry of the annotation in the :todos list, with its line number like the side bar
+    pub fn describe(&self) -> String {
+        format!("{}: {}", self.pos.y, self.preview)
+    }
+}
+
+///char columns of the keywords in line. keywords match case sensitively and only as
+/// whole words, so TODOS or NOTED aren't annotations
+pub fn find_in_line(line: &str, keywords: &[String], word_chars: &WordChars) -> Vec<Range<usize>> {
+    let mut found = vec![];
+    let mut prev: Option<char> = None;
+    for (col, (byte, c)) in line.char_indices().enumerate() {
+        let at_word_start = !prev.is_some_and(|c| is_word_char(c, word_chars));
+        prev = Some(c);
+        if at_word_start {
+            let rest = &line[byte..];
+            let matched = keywords.iter().find(|keyword| {
+                !keyword.is_empty()
+                    && rest.starts_with(keyword.as_str())
+                    && !rest[keyword.len()..]
+                        .chars()
+                        .next()
+                        .is_some_and(|c| is_word_char(c, word_chars))
+            });
+            if let Some(keyword) = matched {
+                found.push(col..col + keyword.chars().count());
+            }
+        }
+    }
+    found
+}
+
+///every annotation in content, in order
+pub fn collect(content: &[String], keywords: &[String], word_chars: &WordChars) -> Vec<Annotation> {
+    content
+        .iter()
+        .enumerate()
+        .flat_map(|(y, line)| {
+            find_in_line(line, keywords, word_chars)
+                .into_iter()
+                .map(move |range| Annotation {
+                    pos: CursorPosition::new(range.start, y),
+                    keyword: line.chars().skip(range.start).take(range.len()).collect(),
+                    preview: preview(line),
+                })
+        })
+        .collect()
+}
+
+///line trimmed and cut to PREVIEW_CHARS
+fn preview(line: &str) -> String {
+    let line = line.trim();
+    if line.chars().count() <= PREVIEW_CHARS {
+        return line.to_string();
+    }
+    let cut: String = line.chars().take(PREVIEW_CHARS - 1).collect();
+    format!("{}…", cut)
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_annotations_tests {
+    use super::*;
+
+    fn keywords() -> Vec<String> {
+        ["TODO", "FIXME", "HACK", "NOTE"]
+            .iter()
+            .map(|k| k.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_keywords_match_as_whole_words() {
+        assert_eq!(
+            find_in_line(
+                "// TODO: æ FIXME(x) TODOS NOTED todo _NOTE",
+                &keywords(),
+                &WordChars::Code
+            ),
+            vec![3..7, 11..16]
+        );
+        assert_eq!(
+            find_in_line("NOTE", &keywords(), &WordChars::Code),
+            vec![0..4]
+        );
+    }
+
+    #[test]
+    fn test_extra_word_chars_join_keywords_to_words() {
+        let css = WordChars::Extra("-".to_string());
+        assert_eq!(
+            find_in_line("TODO-list", &keywords(), &WordChars::Code),
+            vec![0..4]
+        );
+        assert!(find_in_line("TODO-list", &keywords(), &css).is_empty());
+    }
+
+    #[test]
+    fn test_collect_reports_lines_and_trimmed_previews() {
+        let long = format!("    # HACK {}", "x".repeat(60));
+        let content = vec![
+            "fn main() {".to_string(),
+            "    // TODO: handle errors  ".to_string(),
+            long,
+        ];
+        let found = collect(&content, &keywords(), &WordChars::Code);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].pos, CursorPosition::new(7, 1));
+        assert_eq!(found[0].keyword, "TODO");
+        assert_eq!(found[0].describe(), "1: // TODO: handle errors");
+        assert_eq!(found[1].keyword, "HACK");
+        assert_eq!(found[1].preview.chars().count(), PREVIEW_CHARS);
+        assert!(found[1].preview.ends_with('…'));
+    }
+}
@@ -0,0 +1,151 @@
+use super::edit_events::LineShift;
+use super::marks::changed_lines;
+use std::collections::BTreeSet;
+
+/// bookmarked lines, toggled on the cursor line and jumped between in order. unlike marks
+/// they have no name and are shown in the gutter.
+#[derive(Debug, Default)]
+pub struct Bookmarks {
+    lines: BTreeSet<usize>,
+}
+
+impl Bookmarks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn contains(&self, y: usize) -> bool {
+        self.lines.contains(&y)
+    }
+
+    ///bookmarked lines, first to last
+    pub fn lines(&self) -> impl Iterator<Item = usize> + '_ {
+        self.lines.iter().copied()
+    }
+
+    ///bookmarks line y, or removes its bookmark. returns true if it is bookmarked now
+    pub fn toggle(&mut self, y: usize) -> bool {
+        if self.lines.remove(&y) {
+            return false;
+        }
+        self.lines.insert(y);
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    ///the next bookmarked line after y, or the one before it going back. wraps around at
+    /// the ends, so a single bookmark is found from itself.
+    pub fn next(&self, y: usize, forward: bool) -> Option<usize> {
+        if forward {
+            self.lines
+                .range(y + 1..)
+                .next()
+                .or_else(|| self.lines.first())
+                .copied()
+        } else {
+            self.lines
+                .range(..y)
+                .next_back()
+                .or_else(|| self.lines.last())
+                .copied()
+        }
+    }
+
+    ///moves bookmarks along with the lines an edit inserted or deleted. bookmarks of
+    /// deleted lines are dropped.
+    pub fn shift(&mut self, shift: &LineShift) {
+        self.lines = self.lines.iter().filter_map(|&y| shift.line(y)).collect();
+    }
+
+    ///moves bookmarks along with lines inserted or deleted between old and new content, for
+    /// content replaced as a whole where no edits tell what changed. bookmarks of deleted
+    /// lines are dropped.
+    pub fn adjust(&mut self, old: &[String], new: &[String]) {
+        let Some((prefix, old_end, new_end)) = changed_lines(old, new) else {
+            return;
+        };
+        self.lines = self
+            .lines
+            .iter()
+            .filter_map(|&y| match y {
+                y if y < prefix => Some(y),
+                y if y >= old_end => Some(y + new_end - old_end),
+                y if y >= new_end => None,
+                y => Some(y),
+            })
+            .collect();
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_bookmarks_tests {
+    use super::*;
+
+    fn lines(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn bookmarks_at(lines: &[usize]) -> Bookmarks {
+        let mut bookmarks = Bookmarks::new();
+        for &y in lines {
+            bookmarks.toggle(y);
+        }
+        bookmarks
+    }
+
+    #[test]
+    fn test_toggle_and_jump_with_wraparound() {
+        let mut bookmarks = bookmarks_at(&[2, 5]);
+        assert_eq!(bookmarks.next(0, true), Some(2));
+        assert_eq!(bookmarks.next(2, true), Some(5));
+        assert_eq!(bookmarks.next(5, true), Some(2));
+        assert_eq!(bookmarks.next(2, false), Some(5));
+        assert_eq!(bookmarks.next(4, false), Some(2));
+
+        assert!(!bookmarks.toggle(5));
+        assert_eq!(bookmarks.next(2, true), Some(2));
+        bookmarks.clear();
+        assert_eq!(bookmarks.next(2, true), None);
+    }
+
+    #[test]
+    fn test_bookmarks_follow_inserted_and_deleted_lines() {
+        let old = lines(&["a", "b", "c", "d"]);
+        let mut bookmarks = bookmarks_at(&[0, 2, 3]);
+        bookmarks.adjust(&old, &lines(&["a", "new", "b", "c", "d"]));
+        assert_eq!(bookmarks.lines().collect::<Vec<_>>(), vec![0, 3, 4]);
+
+        let mut bookmarks = bookmarks_at(&[0, 2, 3]);
+        bookmarks.adjust(&old, &lines(&["a", "b", "d"]));
+        assert_eq!(bookmarks.lines().collect::<Vec<_>>(), vec![0, 2]);
+
+        //a line split in its middle keeps its bookmark
+        let mut bookmarks = bookmarks_at(&[1]);
+        bookmarks.adjust(&old, &lines(&["a", "b", "", "c", "d"]));
+        assert_eq!(bookmarks.lines().collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn test_shift_moves_bookmarks_below_and_drops_deleted_ones() {
+        let mut bookmarks = bookmarks_at(&[0, 2, 3, 5]);
+        bookmarks.shift(&LineShift {
+            first: 2,
+            removed: 2,
+            added: 1,
+        });
+        assert_eq!(bookmarks.lines().collect::<Vec<_>>(), vec![0, 2, 4]);
+    }
+}
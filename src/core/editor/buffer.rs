@@ -0,0 +1,369 @@
+//line storage of the editor and the primitive edits on it. positions are checked rather than
+// clamped, the editor keeps the cursor, selection and undo history in step around them
+
+use super::line_index::{byte_index, char_count};
+use crate::core::errors::editor_errors::EditorError;
+use crate::core::position::{Position, Range};
+use std::ops::{Deref, Range as StdRange};
+
+///the lines of a text, without their line breaks. reads go through the lines it derefs
+/// to, edits through the methods below.
+///
+/// ```
+/// use calliglyph::core::editor::buffer::Buffer;
+/// use calliglyph::core::position::{Position, Range};
+///
+/// let mut buffer = Buffer::from(vec!["hello world".to_string()]);
+/// buffer.split_line(Position::new(0, 5))?;
+/// assert_eq!(buffer, vec!["hello", " world"]);
+/// buffer.merge_lines(0)?;
+/// let removed = buffer.delete_range(Range::new(Position::new(0, 0), Position::new(0, 6)))?;
+/// assert_eq!(removed, "hello ");
+/// assert_eq!(buffer, vec!["world"]);
+/// # Ok::<(), calliglyph::core::errors::editor_errors::EditorError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Buffer {
+    lines: Vec<String>,
+}
+
+impl Buffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///takes the lines out of the buffer
+    pub fn into_lines(self) -> Vec<String> {
+        self.lines
+    }
+
+    ///checks that pos points at a line and at most one past its last char
+    pub fn check_position(&self, pos: Position) -> Result<(), EditorError> {
+        let line_len = self.lines.get(pos.line).map(|line| char_count(line));
+        match line_len {
+            Some(line_len) if pos.col <= line_len => Ok(()),
+            line_len => Err(EditorError::CursorOutOfBounds {
+                x: pos.col,
+                y: pos.line,
+                line_len: line_len.unwrap_or(0),
+            }),
+        }
+    }
+
+    ///inserts c before the char at pos, or at the end of its line
+    pub fn insert_char(&mut self, pos: Position, c: char) -> Result<(), EditorError> {
+        self.check_position(pos)?;
+        let line = &mut self.lines[pos.line];
+        line.insert(byte_index(line, pos.col), c);
+        Ok(())
+    }
+
+    ///removes the text in range, joining its first and last line, and returns it with
+    /// line breaks as \n
+    pub fn delete_range(&mut self, range: Range) -> Result<String, EditorError> {
+        let removed = self.slice(range)?;
+        let Range { start, end } = range.normalized();
+        let end_line = &self.lines[end.line];
+        let tail = end_line[byte_index(end_line, end.col)..].to_string();
+        let first = &mut self.lines[start.line];
+        first.truncate(byte_index(first, start.col));
+        first.push_str(&tail);
+        self.lines.drain(start.line + 1..=end.line);
+        Ok(removed)
+    }
+
+    ///breaks the line at pos in two, the text from pos on moves to a new line below
+    pub fn split_line(&mut self, pos: Position) -> Result<(), EditorError> {
+        self.check_position(pos)?;
+        let line = &mut self.lines[pos.line];
+        let right = line.split_off(byte_index(line, pos.col));
+        self.lines.insert(pos.line + 1, right);
+        Ok(())
+    }
+
+    ///appends the line below i to line i, returns the char column they were joined at
+    pub fn merge_lines(&mut self, i: usize) -> Result<usize, EditorError> {
+        if i + 1 >= self.lines.len() {
+            return Err(EditorError::LineOutOfBounds {
+                line: i + 1,
+                line_count: self.lines.len(),
+            });
+        }
+        let below = self.lines.remove(i + 1);
+        let line = &mut self.lines[i];
+        let col = char_count(line);
+        line.push_str(&below);
+        Ok(col)
+    }
+
+    ///inserts text without line breaks before the char at pos, or at the end of its line
+    pub fn insert_str(&mut self, pos: Position, text: &str) -> Result<(), EditorError> {
+        self.check_position(pos)?;
+        let line = &mut self.lines[pos.line];
+        line.insert_str(byte_index(line, pos.col), text);
+        Ok(())
+    }
+
+    ///puts text in place of line i, returns the line it replaced
+    pub fn set_line(&mut self, i: usize, text: String) -> Result<String, EditorError> {
+        let line_count = self.lines.len();
+        match self.lines.get_mut(i) {
+            Some(line) => Ok(std::mem::replace(line, text)),
+            None => Err(EditorError::LineOutOfBounds {
+                line: i,
+                line_count,
+            }),
+        }
+    }
+
+    ///adds empty lines at the end until there are at least count
+    pub fn ensure_lines(&mut self, count: usize) {
+        if self.lines.len() < count {
+            self.lines.resize(count, String::new());
+        }
+    }
+
+    ///inserts lines before line at, or after the last line if at is the line count
+    pub fn insert_lines(&mut self, at: usize, lines: Vec<String>) -> Result<(), EditorError> {
+        self.replace_lines(at..at, lines).map(|_| ())
+    }
+
+    ///removes the lines in range, returns them
+    pub fn remove_lines(&mut self, range: StdRange<usize>) -> Result<Vec<String>, EditorError> {
+        self.replace_lines(range, vec![])
+    }
+
+    ///puts new in place of the lines in range, returns the lines it replaced
+    pub fn replace_lines(
+        &mut self,
+        range: StdRange<usize>,
+        new: Vec<String>,
+    ) -> Result<Vec<String>, EditorError> {
+        if range.start > range.end || range.end > self.lines.len() {
+            return Err(EditorError::LineOutOfBounds {
+                line: range.end,
+                line_count: self.lines.len(),
+            });
+        }
+        Ok(self.lines.splice(range, new).collect())
+    }
+
+    ///the text in range with line breaks as \n
+    pub fn slice(&self, range: Range) -> Result<String, EditorError> {
+        let Range { start, end } = range.normalized();
+        self.check_position(start)?;
+        self.check_position(end)?;
+        let first = &self.lines[start.line];
+        let from = byte_index(first, start.col);
+        if start.line == end.line {
+            return Ok(first[from..byte_index(first, end.col)].to_string());
+        }
+        let mut text = first[from..].to_string();
+        for line in &self.lines[start.line + 1..end.line] {
+            text.push('\n');
+            text.push_str(line);
+        }
+        let last = &self.lines[end.line];
+        text.push('\n');
+        text.push_str(&last[..byte_index(last, end.col)]);
+        Ok(text)
+    }
+}
+
+impl Deref for Buffer {
+    type Target = Vec<String>;
+
+    fn deref(&self) -> &Vec<String> {
+        &self.lines
+    }
+}
+
+//tests set up and change the content like the Vec it used to be, everywhere else edits
+// go through the checked methods above
+#[cfg(test)]
+impl std::ops::DerefMut for Buffer {
+    fn deref_mut(&mut self) -> &mut Vec<String> {
+        &mut self.lines
+    }
+}
+
+impl From<Vec<String>> for Buffer {
+    fn from(lines: Vec<String>) -> Self {
+        Self { lines }
+    }
+}
+
+impl FromIterator<String> for Buffer {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
+        Self {
+            lines: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> PartialEq<Vec<T>> for Buffer
+where
+    String: PartialEq<T>,
+{
+    fn eq(&self, other: &Vec<T>) -> bool {
+        self.lines == *other
+    }
+}
+
+impl<T, const N: usize> PartialEq<[T; N]> for Buffer
+where
+    String: PartialEq<T>,
+{
+    fn eq(&self, other: &[T; N]) -> bool {
+        self.lines == *other
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_buffer_tests {
+    use super::*;
+
+    fn buffer(lines: &[&str]) -> Buffer {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    fn range(start: (usize, usize), end: (usize, usize)) -> Range {
+        Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1))
+    }
+
+    #[test]
+    fn test_insert_char_counts_columns_in_chars() {
+        let mut b = buffer(&["héllo"]);
+        b.insert_char(Position::new(0, 2), 'X').unwrap();
+        b.insert_char(Position::new(0, 6), '!').unwrap();
+        assert_eq!(b, vec!["héXllo!"]);
+    }
+
+    #[test]
+    fn test_insert_char_out_of_bounds_leaves_text() {
+        let mut b = buffer(&["ab"]);
+        assert_eq!(
+            b.insert_char(Position::new(0, 3), 'x'),
+            Err(EditorError::CursorOutOfBounds {
+                x: 3,
+                y: 0,
+                line_len: 2
+            })
+        );
+        assert!(b.insert_char(Position::new(1, 0), 'x').is_err());
+        assert_eq!(b, vec!["ab"]);
+    }
+
+    #[test]
+    fn test_delete_range_within_a_line() {
+        let mut b = buffer(&["grüße"]);
+        assert_eq!(b.delete_range(range((0, 1), (0, 4))).unwrap(), "rüß");
+        assert_eq!(b, vec!["ge"]);
+    }
+
+    #[test]
+    fn test_delete_range_over_lines_joins_ends() {
+        let mut b = buffer(&["one", "two", "three", "four"]);
+        assert_eq!(
+            b.delete_range(range((0, 2), (2, 3))).unwrap(),
+            "e\ntwo\nthr"
+        );
+        assert_eq!(b, vec!["onee", "four"]);
+    }
+
+    #[test]
+    fn test_delete_range_backwards_and_empty() {
+        let mut b = buffer(&["ab", "cd"]);
+        assert_eq!(b.delete_range(range((1, 1), (0, 1))).unwrap(), "b\nc");
+        assert_eq!(b, vec!["ad"]);
+        assert_eq!(b.delete_range(range((0, 1), (0, 1))).unwrap(), "");
+        assert_eq!(b, vec!["ad"]);
+        assert!(b.delete_range(range((0, 0), (1, 0))).is_err());
+        assert_eq!(b, vec!["ad"]);
+    }
+
+    #[test]
+    fn test_split_line() {
+        let mut b = buffer(&["añb"]);
+        b.split_line(Position::new(0, 2)).unwrap();
+        assert_eq!(b, vec!["añ", "b"]);
+        b.split_line(Position::new(1, 1)).unwrap();
+        assert_eq!(b, vec!["añ", "b", ""]);
+        assert!(b.split_line(Position::new(3, 0)).is_err());
+    }
+
+    #[test]
+    fn test_merge_lines_returns_join_column() {
+        let mut b = buffer(&["añ", "b", ""]);
+        assert_eq!(b.merge_lines(0), Ok(2));
+        assert_eq!(b, vec!["añb", ""]);
+        assert_eq!(b.merge_lines(0), Ok(3));
+        assert_eq!(
+            b.merge_lines(0),
+            Err(EditorError::LineOutOfBounds {
+                line: 1,
+                line_count: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_insert_str_and_set_line() {
+        let mut b = buffer(&["añb"]);
+        b.insert_str(Position::new(0, 2), "xy").unwrap();
+        assert_eq!(b, vec!["añxyb"]);
+        assert!(b.insert_str(Position::new(0, 6), "z").is_err());
+        assert_eq!(b.set_line(0, "c".to_string()), Ok("añxyb".to_string()));
+        assert_eq!(
+            b.set_line(1, "d".to_string()),
+            Err(EditorError::LineOutOfBounds {
+                line: 1,
+                line_count: 1
+            })
+        );
+        assert_eq!(b, vec!["c"]);
+    }
+
+    #[test]
+    fn test_line_edits() {
+        let mut b = buffer(&["one", "four"]);
+        b.insert_lines(1, vec!["two".to_string(), "three".to_string()])
+            .unwrap();
+        b.insert_lines(4, vec!["five".to_string()]).unwrap();
+        assert_eq!(b, vec!["one", "two", "three", "four", "five"]);
+        assert_eq!(b.remove_lines(1..3).unwrap(), vec!["two", "three"]);
+        assert_eq!(
+            b.replace_lines(0..1, vec!["1".to_string(), "2".to_string()])
+                .unwrap(),
+            vec!["one"]
+        );
+        assert_eq!(b, vec!["1", "2", "four", "five"]);
+        assert!(b.remove_lines(3..5).is_err());
+        assert!(b.insert_lines(5, vec![]).is_err());
+        assert_eq!(b, vec!["1", "2", "four", "five"]);
+    }
+
+    #[test]
+    fn test_ensure_lines_only_adds() {
+        let mut b = buffer(&["a"]);
+        b.ensure_lines(3);
+        assert_eq!(b, vec!["a", "", ""]);
+        b.ensure_lines(1);
+        assert_eq!(b, vec!["a", "", ""]);
+    }
+
+    #[test]
+    fn test_slice() {
+        let b = buffer(&["one", "", "thrée"]);
+        assert_eq!(b.slice(range((0, 1), (0, 3))).unwrap(), "ne");
+        assert_eq!(b.slice(range((0, 3), (2, 4))).unwrap(), "\n\nthré");
+        assert_eq!(b.slice(range((2, 0), (0, 0))).unwrap(), "one\n\n");
+        assert!(b.slice(range((0, 0), (0, 4))).is_err());
+    }
+}
@@ -0,0 +1,263 @@
+//converting text between letter cases and naming conventions like snake_case or camelCase
+
+use std::ops::Range;
+
+///names :case takes, offered when completing it
+pub const CASE_NAMES: &[&str] = &[
+    "upper", "lower", "title", "snake", "camel", "kebab", "pascal",
+];
+
+///a letter case or naming convention text can be converted to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Case {
+    Upper,
+    Lower,
+    //words capitalized and spaced, like Http Server
+    Title,
+    Snake,
+    Camel,
+    Kebab,
+    Pascal,
+}
+
+impl Case {
+    ///the case named name, one of CASE_NAMES
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "upper" => Some(Case::Upper),
+            "lower" => Some(Case::Lower),
+            "title" => Some(Case::Title),
+            "snake" => Some(Case::Snake),
+            "camel" => Some(Case::Camel),
+            "kebab" => Some(Case::Kebab),
+            "pascal" => Some(Case::Pascal),
+            _ => None,
+        }
+    }
+}
+
+///text converted to case. upper and lower only change letters, the naming conventions
+/// split each run of words and separators into words and join them again. punctuation
+/// other than _ and - is kept and splits runs, so a(fooBar) becomes a(foo_bar).
+///
+/// ```
+/// use calliglyph::core::editor::case::{convert, Case};
+///
+/// assert_eq!(convert("HTTPServer", Case::Snake), "http_server");
+/// assert_eq!(convert("http_server", Case::Camel), "httpServer");
+/// assert_eq!(convert("let myVar = 1;", Case::Kebab), "let-my-var = 1;");
+/// ```
+pub fn convert(text: &str, case: Case) -> String {
+    match case {
+        Case::Upper => text.to_uppercase(),
+        Case::Lower => text.to_lowercase(),
+        _ => text
+            .split('\n')
+            .map(|line| convert_line(line, case))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+///checks if c belongs to a run of words converted as one name
+pub fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+///chars of the name at or right before x in line, like the identifier under the cursor.
+/// dashes only count inside a name, so a - b has no name at the dash.
+pub fn name_at(line: &str, x: usize) -> Option<Range<usize>> {
+    let chars: Vec<char> = line.chars().collect();
+    let x = x.min(chars.len());
+    let mut start = x - chars[..x]
+        .iter()
+        .rev()
+        .take_while(|c| is_name_char(**c))
+        .count();
+    let mut end = x + chars[x..].iter().take_while(|c| is_name_char(**c)).count();
+    while start < end && chars[start] == '-' {
+        start += 1;
+    }
+    while end > start && chars[end - 1] == '-' {
+        end -= 1;
+    }
+    chars[start..end]
+        .iter()
+        .any(|c| c.is_alphanumeric())
+        .then_some(start..end)
+}
+
+///line with every run of words and the separators between them converted to case
+fn convert_line(line: &str, case: Case) -> String {
+    let mut converted = String::new();
+    let mut run = String::new();
+    for c in line.chars() {
+        if is_name_char(c) || c.is_whitespace() {
+            run.push(c);
+        } else {
+            converted.push_str(&convert_run(&run, case));
+            run.clear();
+            converted.push(c);
+        }
+    }
+    converted.push_str(&convert_run(&run, case));
+    converted
+}
+
+///run joined in case, the separators around its words are kept, like the _ of _private
+fn convert_run(run: &str, case: Case) -> String {
+    let inner = run.trim_matches(|c: char| !c.is_alphanumeric());
+    if inner.is_empty() {
+        return run.to_string();
+    }
+    let start = run.len() - run.trim_start_matches(|c: char| !c.is_alphanumeric()).len();
+    let words = split_words(inner);
+    let joined = match case {
+        Case::Upper => inner.to_uppercase(),
+        Case::Lower => inner.to_lowercase(),
+        Case::Title => join(&words, " ", capitalize),
+        Case::Snake => join(&words, "_", str::to_lowercase),
+        Case::Kebab => join(&words, "-", str::to_lowercase),
+        Case::Pascal => join(&words, "", capitalize),
+        Case::Camel => {
+            let rest = join(&words[1..], "", capitalize);
+            format!("{}{}", words[0].to_lowercase(), rest)
+        }
+    };
+    format!("{}{}{}", &run[..start], joined, &run[start + inner.len()..])
+}
+
+///the words of text, split at anything but letters and digits and where the case changes.
+/// a run of capitals is one word up to the capital starting the next, so HTTPServer
+/// is HTTP and Server.
+fn split_words(text: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut words = vec![];
+    let mut start = None;
+    for (i, &(at, c)) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if let Some(from) = start.take() {
+                words.push(&text[from..at]);
+            }
+            continue;
+        }
+        let prev = i.checked_sub(1).map(|i| chars[i].1);
+        let next = chars.get(i + 1).map(|&(_, c)| c);
+        let boundary = c.is_uppercase()
+            && prev.is_some_and(|prev| {
+                prev.is_lowercase()
+                    || prev.is_numeric()
+                    || (prev.is_uppercase() && next.is_some_and(char::is_lowercase))
+            });
+        match start {
+            Some(from) if boundary => {
+                words.push(&text[from..at]);
+                start = Some(at);
+            }
+            Some(_) => {}
+            None => start = Some(at),
+        }
+    }
+    if let Some(from) = start {
+        words.push(&text[from..]);
+    }
+    words
+}
+
+///words changed by f and joined with separator
+fn join(words: &[&str], separator: &str, f: impl Fn(&str) -> String) -> String {
+    words
+        .iter()
+        .map(|word| f(word))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+///word with its first letter upper and the rest lower case
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_case_tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        let cases = [
+            ("HTTPServer", Case::Snake, "http_server"),
+            ("HTTPServer", Case::Camel, "httpServer"),
+            ("getHTTPResponseCode", Case::Snake, "get_http_response_code"),
+            ("parse_json_file", Case::Pascal, "ParseJsonFile"),
+            ("parse_json_file", Case::Camel, "parseJsonFile"),
+            ("parse_json_file", Case::Kebab, "parse-json-file"),
+            ("parse-json-file", Case::Snake, "parse_json_file"),
+            ("ParseJsonFile", Case::Title, "Parse Json File"),
+            ("hello world", Case::Snake, "hello_world"),
+            ("hello world", Case::Title, "Hello World"),
+            ("utf8Encode", Case::Snake, "utf8_encode"),
+            ("MAX_VALUE", Case::Camel, "maxValue"),
+            ("_private_name", Case::Camel, "_privateName"),
+            (
+                "call(fooBar, bazQux);",
+                Case::Snake,
+                "call(foo_bar, baz_qux);",
+            ),
+            ("  indentedName", Case::Kebab, "  indented-name"),
+            ("mixed Case_text", Case::Upper, "MIXED CASE_TEXT"),
+            ("Straße", Case::Lower, "straße"),
+            ("fooBar\nbazQux", Case::Snake, "foo_bar\nbaz_qux"),
+            ("", Case::Snake, ""),
+        ];
+        for (text, case, expected) in cases {
+            assert_eq!(convert(text, case), expected, "{} to {:?}", text, case);
+        }
+    }
+
+    #[test]
+    fn test_clean_identifiers_round_trip() {
+        let names = [
+            ("http_server_config", Case::Snake),
+            ("httpServerConfig", Case::Camel),
+            ("http-server-config", Case::Kebab),
+            ("HttpServerConfig", Case::Pascal),
+            ("Http Server Config", Case::Title),
+        ];
+        for (name, case) in names {
+            for (_, other) in names {
+                assert_eq!(convert(&convert(name, other), case), name, "{:?}", other);
+            }
+        }
+    }
+
+    #[test]
+    fn test_name_at_cursor() {
+        assert_eq!(name_at("let fooBar = 1;", 6), Some(4..10));
+        //right after the name
+        assert_eq!(name_at("let fooBar = 1;", 10), Some(4..10));
+        assert_eq!(name_at("x = kebab-name;", 4), Some(4..14));
+        assert_eq!(name_at("a - b", 2), None);
+        assert_eq!(name_at("", 0), None);
+    }
+
+    #[test]
+    fn test_parse_names() {
+        for name in CASE_NAMES {
+            assert!(Case::parse(name).is_some());
+        }
+        assert_eq!(Case::parse("screaming"), None);
+    }
+}
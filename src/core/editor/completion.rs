@@ -0,0 +1,186 @@
+use super::words::{is_word_char, WordChars};
+use crate::core::cursor::CursorPosition;
+use std::collections::HashSet;
+
+///unique words of the editor content, rebuilt only when the content revision changed
+#[derive(Debug, Default)]
+pub struct WordIndex {
+    revision: Option<u64>,
+    words: Vec<String>,
+}
+
+impl WordIndex {
+    pub fn new() -> Self {
+        Self {
+            revision: None,
+            words: vec![],
+        }
+    }
+
+    ///returns the words of content in order of first appearance, rescanning content only
+    /// if it changed since the last call
+    pub fn words(
+        &mut self,
+        revision: u64,
+        content: &[String],
+        word_chars: &WordChars,
+    ) -> &[String] {
+        if self.revision != Some(revision) {
+            let mut seen = HashSet::new();
+            self.words = content
+                .iter()
+                .flat_map(|line| line.split(|c: char| !is_word_char(c, word_chars)))
+                .filter(|word| !word.is_empty() && seen.insert(word.to_string()))
+                .map(String::from)
+                .collect();
+            self.revision = Some(revision);
+        }
+        &self.words
+    }
+}
+
+/// an active completion, cycling through the endings of words matching the typed prefix
+#[derive(Debug)]
+pub struct CompletionSession {
+    ///position right after the typed prefix, where the endings are inserted
+    pub pos: CursorPosition,
+    suffixes: Vec<String>,
+    ///index of the shown ending, None shows the typed prefix alone
+    selected: Option<usize>,
+}
+
+impl CompletionSession {
+    ///starts a completion of prefix at pos, None if no word matches.
+    /// words match case insensitively, only their ending is inserted so the typed
+    /// prefix keeps its case.
+    pub fn new(pos: CursorPosition, prefix: &str, words: &[String]) -> Option<Self> {
+        let prefix_len = prefix.chars().count();
+        let lower_prefix = prefix.to_lowercase();
+        let mut seen = HashSet::new();
+        let suffixes: Vec<String> = words
+            .iter()
+            .filter(|word| {
+                word.chars().count() > prefix_len && word.to_lowercase().starts_with(&lower_prefix)
+            })
+            .map(|word| word.chars().skip(prefix_len).collect::<String>())
+            .filter(|suffix| seen.insert(suffix.clone()))
+            .collect();
+
+        if suffixes.is_empty() {
+            return None;
+        }
+        Some(Self {
+            pos,
+            suffixes,
+            selected: None,
+        })
+    }
+
+    ///ending currently shown after the prefix
+    pub fn current(&self) -> &str {
+        self.selected
+            .map(|i| self.suffixes[i].as_str())
+            .unwrap_or("")
+    }
+
+    ///moves to the next or previous ending, passing the bare prefix between the last
+    /// and the first ending
+    pub fn cycle(&mut self, forward: bool) {
+        let len = self.suffixes.len();
+        self.selected = match (self.selected, forward) {
+            (None, true) => Some(0),
+            (None, false) => Some(len - 1),
+            (Some(i), true) if i + 1 < len => Some(i + 1),
+            (Some(i), false) if i > 0 => Some(i - 1),
+            _ => None,
+        };
+    }
+}
+
+///returns the partial word right before x on line
+pub fn prefix_before(line: &str, x: usize, word_chars: &WordChars) -> String {
+    let chars: Vec<char> = line.chars().take(x).collect();
+    let start = chars
+        .iter()
+        .rposition(|c| !is_word_char(*c, word_chars))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    chars[start..].iter().collect()
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_completion_tests {
+    use super::*;
+
+    fn words(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_index_collects_unique_words_in_order() {
+        let mut index = WordIndex::new();
+        let content = words(&["let value = other_value;", "value + 1"]);
+        assert_eq!(
+            index.words(0, &content, &WordChars::Code),
+            &words(&["let", "value", "other_value", "1"])[..]
+        );
+    }
+
+    #[test]
+    fn test_index_is_only_rebuilt_on_new_revision() {
+        let mut index = WordIndex::new();
+        index.words(0, &words(&["old"]), &WordChars::Code);
+        assert_eq!(
+            index.words(0, &words(&["new"]), &WordChars::Code),
+            &words(&["old"])[..]
+        );
+        assert_eq!(
+            index.words(1, &words(&["new"]), &WordChars::Code),
+            &words(&["new"])[..]
+        );
+    }
+
+    #[test]
+    fn test_session_cycles_through_endings_and_back_to_prefix() {
+        let pos = CursorPosition { x: 2, y: 0 };
+        let mut session =
+            CompletionSession::new(pos, "he", &words(&["hello", "help", "he", "world"])).unwrap();
+        session.cycle(true);
+        assert_eq!(session.current(), "llo");
+        session.cycle(true);
+        assert_eq!(session.current(), "lp");
+        session.cycle(true);
+        assert_eq!(session.current(), "");
+        session.cycle(false);
+        assert_eq!(session.current(), "lp");
+    }
+
+    #[test]
+    fn test_session_keeps_case_of_prefix() {
+        let pos = CursorPosition { x: 2, y: 0 };
+        let mut session = CompletionSession::new(pos, "HE", &words(&["hello"])).unwrap();
+        session.cycle(true);
+        assert_eq!(session.current(), "llo");
+    }
+
+    #[test]
+    fn test_no_match_has_no_session() {
+        let pos = CursorPosition { x: 2, y: 0 };
+        assert!(CompletionSession::new(pos, "zz", &words(&["hello"])).is_none());
+    }
+
+    #[test]
+    fn test_prefix_before_cursor() {
+        assert_eq!(prefix_before("foo(bar_b", 9, &WordChars::Code), "bar_b");
+        assert_eq!(prefix_before("foo bar", 3, &WordChars::Code), "foo");
+        assert_eq!(prefix_before("foo ", 4, &WordChars::Code), "");
+        let css = WordChars::Extra("-".to_string());
+        assert_eq!(prefix_before("a: font-we", 10, &css), "font-we");
+    }
+}
@@ -0,0 +1,283 @@
+//change notifications of the editor, so features depending on the buffer can follow its
+// edits instead of comparing the whole buffer again
+
+use super::editor::EditAction;
+use crate::core::cursor::CursorPosition;
+use std::ops::Range;
+
+///a change to the editor content, positions are char indexes like the cursor's.
+/// undo and redo report the change they make, so undoing an insert reports a delete.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EditEvent {
+    ///text inserted at a position, line breaks in it split the line
+    Inserted { at: CursorPosition, text: String },
+    ///text that was between the start and end of range removed
+    Deleted {
+        range: Range<CursorPosition>,
+        text: String,
+    },
+    ///the text between the start and end of range replaced by new
+    Replaced {
+        range: Range<CursorPosition>,
+        old: String,
+        new: String,
+    },
+}
+
+impl EditEvent {
+    ///the whole lines the event inserted or deleted, None if every line kept its place.
+    /// a line whose start was removed counts as deleted, one split or joined at a later
+    /// char stays where it was.
+    pub fn line_shift(&self) -> Option<LineShift> {
+        let (at, removed, added) = match self {
+            EditEvent::Inserted { at, text } => (*at, 0, line_breaks(text)),
+            EditEvent::Deleted { range, .. } => (range.start, range.end.y - range.start.y, 0),
+            EditEvent::Replaced { range, new, .. } => {
+                (range.start, range.end.y - range.start.y, line_breaks(new))
+            }
+        };
+        if removed == added {
+            return None;
+        }
+        Some(LineShift {
+            first: if at.x == 0 { at.y } else { at.y + 1 },
+            removed,
+            added,
+        })
+    }
+}
+
+///lines first..first + removed of the content before an edit, which are first..first + added
+/// after it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineShift {
+    pub first: usize,
+    pub removed: usize,
+    pub added: usize,
+}
+
+impl LineShift {
+    ///where line y of the content before the edit is after it, None if it was deleted.
+    /// lines replaced by as many new ones keep their place.
+    pub fn line(&self, y: usize) -> Option<usize> {
+        if y < self.first {
+            Some(y)
+        } else if y >= self.first + self.removed {
+            Some(y - self.removed + self.added)
+        } else if y - self.first < self.added {
+            Some(y)
+        } else {
+            None
+        }
+    }
+}
+
+///appends the events describing action to events, in the order the changes are made
+pub(crate) fn push_events(action: &EditAction, events: &mut Vec<EditEvent>) {
+    let event = match action {
+        EditAction::Batch { actions } => {
+            for action in actions {
+                push_events(action, events);
+            }
+            return;
+        }
+        EditAction::Insert { pos, c } => EditEvent::Inserted {
+            at: *pos,
+            text: c.to_string(),
+        },
+        EditAction::Delete { pos, deleted_char } => EditEvent::Deleted {
+            range: *pos..CursorPosition::new(pos.x + 1, pos.y),
+            text: deleted_char.to_string(),
+        },
+        EditAction::Replace {
+            start,
+            end,
+            old,
+            new,
+        } => EditEvent::Replaced {
+            range: *start..*end,
+            old: old.to_string(),
+            new: new.to_string(),
+        },
+        EditAction::ReplaceRange {
+            start,
+            end,
+            old,
+            new,
+        } => EditEvent::Replaced {
+            range: *start..*end,
+            old: old.join("\n"),
+            new: new.join("\n"),
+        },
+        EditAction::ReplaceLines { start, old, new } => EditEvent::Replaced {
+            range: CursorPosition::new(0, start.y)..CursorPosition::new(0, start.y + old.len()),
+            old: whole_lines(old),
+            new: whole_lines(new),
+        },
+        //whole lines go in before the line at start, each with its line break
+        EditAction::InsertLines { start, lines } => EditEvent::Inserted {
+            at: CursorPosition::new(0, start.y),
+            text: whole_lines(lines),
+        },
+        EditAction::DeleteLines { start, deleted } => EditEvent::Deleted {
+            range: CursorPosition::new(0, start.y)..CursorPosition::new(0, start.y + deleted.len()),
+            text: whole_lines(deleted),
+        },
+        EditAction::InsertRange { start, lines, .. } => EditEvent::Inserted {
+            at: *start,
+            text: lines.join("\n"),
+        },
+        EditAction::DeleteRange {
+            start,
+            end,
+            deleted,
+        } => EditEvent::Deleted {
+            range: *start..*end,
+            text: deleted.join("\n"),
+        },
+        //split and join positions are byte indexes into the line
+        EditAction::SplitLine { pos, left, .. } => EditEvent::Inserted {
+            at: CursorPosition::new(left.chars().count(), pos.y),
+            text: "\n".to_string(),
+        },
+        EditAction::JoinLine { pos, merged } => {
+            let x = merged
+                .get(..pos.x)
+                .map_or(pos.x, |left| left.chars().count());
+            EditEvent::Deleted {
+                range: CursorPosition::new(x, pos.y)..CursorPosition::new(0, pos.y + 1),
+                text: "\n".to_string(),
+            }
+        }
+    };
+    events.push(event);
+}
+
+fn line_breaks(text: &str) -> usize {
+    text.matches('\n').count()
+}
+
+fn whole_lines(lines: &[String]) -> String {
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_edit_events_tests {
+    use super::*;
+
+    fn events(action: EditAction) -> Vec<EditEvent> {
+        let mut events = vec![];
+        push_events(&action, &mut events);
+        events
+    }
+
+    #[test]
+    fn test_batch_reports_every_action_in_order() {
+        let batch = EditAction::Batch {
+            actions: vec![
+                EditAction::Insert {
+                    pos: CursorPosition::new(0, 0),
+                    c: 'a',
+                },
+                EditAction::Delete {
+                    pos: CursorPosition::new(3, 1),
+                    deleted_char: 'b',
+                },
+            ],
+        };
+        assert_eq!(
+            events(batch),
+            vec![
+                EditEvent::Inserted {
+                    at: CursorPosition::new(0, 0),
+                    text: "a".to_string(),
+                },
+                EditEvent::Deleted {
+                    range: CursorPosition::new(3, 1)..CursorPosition::new(4, 1),
+                    text: "b".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_line_shifts_of_whole_and_partial_lines() {
+        let shift = |action| events(action)[0].line_shift();
+        let p = CursorPosition::new;
+        let lines = |text: &[&str]| text.iter().map(|line| line.to_string()).collect();
+
+        //a split at the line start moves the line down, a later one leaves it in place
+        let split = |x| EditAction::SplitLine {
+            pos: p(x, 2),
+            left: "ab".get(..x).unwrap().to_string(),
+            right: String::new(),
+        };
+        let (at_start, later) = (shift(split(0)).unwrap(), shift(split(1)).unwrap());
+        assert_eq!((at_start.line(2), at_start.line(1)), (Some(3), Some(1)));
+        assert_eq!((later.line(2), later.line(3)), (Some(2), Some(4)));
+
+        let deleted = shift(EditAction::DeleteLines {
+            start: p(0, 1),
+            deleted: lines(&["a", "b"]),
+        })
+        .unwrap();
+        assert_eq!(
+            (0..4).map(|y| deleted.line(y)).collect::<Vec<_>>(),
+            vec![Some(0), None, None, Some(1)]
+        );
+
+        let replaced = |new: &[&str]| {
+            shift(EditAction::ReplaceLines {
+                start: p(0, 1),
+                old: lines(&["a", "b"]),
+                new: lines(new),
+            })
+        };
+        assert_eq!(replaced(&["c", "d"]), None);
+        let shrunk = replaced(&["c"]).unwrap();
+        assert_eq!(
+            (1..4).map(|y| shrunk.line(y)).collect::<Vec<_>>(),
+            vec![Some(1), None, Some(2)]
+        );
+        assert_eq!(
+            shift(EditAction::Insert {
+                pos: p(0, 0),
+                c: 'a'
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_split_and_join_report_char_positions_of_the_line_break() {
+        let split = EditAction::SplitLine {
+            pos: CursorPosition::new(4, 2),
+            left: "æø".to_string(),
+            right: "x".to_string(),
+        };
+        assert_eq!(
+            events(split),
+            vec![EditEvent::Inserted {
+                at: CursorPosition::new(2, 2),
+                text: "\n".to_string(),
+            }]
+        );
+        let join = EditAction::JoinLine {
+            pos: CursorPosition::new(4, 2),
+            merged: "æøx".to_string(),
+        };
+        assert_eq!(
+            events(join),
+            vec![EditEvent::Deleted {
+                range: CursorPosition::new(2, 2)..CursorPosition::new(0, 3),
+                text: "\n".to_string(),
+            }]
+        );
+    }
+}
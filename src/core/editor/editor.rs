@@ -1,13 +1,25 @@
 use super::super::super::core::clipboard::Clipboard;
 use super::super::cursor::Cursor;
 use super::super::cursor::CursorPosition;
-use super::super::errors::editor_errors::EditorError::{
-    ClipboardFailure, RedoFailure, TextSelectionFailure, UndoFailure,
-};
-use super::super::errors::editor_errors::{ClipboardError, EditorError, TextSelectionError};
-use super::undo_redo::UndoRedoManager;
+use super::super::errors::editor_errors::EditorError;
+use super::bookmarks::Bookmarks;
+use super::buffer::Buffer;
+use super::case::{self, Case};
+use super::completion::{self, CompletionSession, WordIndex};
+use super::edit_events::{self, EditEvent};
+use super::invariants;
+use super::line_index::{self, ColumnCache};
+use super::marks::Marks;
+use super::numbers;
+use super::reflow;
+use super::stats::{StatsCache, TextStats};
+use super::text_objects::{self, BracketMatch, BracketMatchCache};
+use super::undo_redo::{UndoEntry, UndoMark, UndoRedoManager};
+use super::words::{is_word_char, WordChars};
 use crate::config::editor_settings;
-use crate::input::input_action::InputAction;
+use crate::core::position::{Position, Range};
+use crate::input::input_action::{InputAction, Motion, ViewAlign};
+use std::fmt::Debug;
 
 #[derive(Debug, Clone)]
 pub enum EditAction {
@@ -32,6 +44,12 @@ pub enum EditAction {
         old: Vec<String>,
         new: Vec<String>,
     },
+    //whole lines from start replaced by others, as many or not
+    ReplaceLines {
+        start: CursorPosition,
+        old: Vec<String>,
+        new: Vec<String>,
+    },
     // Insert lines of strings
     InsertLines {
         start: CursorPosition,
@@ -65,48 +83,364 @@ pub enum EditAction {
         pos: CursorPosition, // position of the split
         merged: String,      // the full merged text
     },
+    //several actions undone and redone as one step
+    Batch {
+        actions: Vec<EditAction>,
+    },
+}
+
+///text typed once for the lines of a selection, on the first of them until it is copied
+#[derive(Debug, Clone)]
+struct LineInsert {
+    lines: std::ops::Range<usize>,
+    //left column of the selection, where the text goes on every line
+    col: usize,
+    //where it is typed on the first line, which may end before col
+    at: usize,
 }
 
-/// handles editor content
+/// handles editor content. outside the crate it is edited through the methods below,
+/// which keep the cursor, selection, undo history and marks consistent.
+///
+/// ```
+/// use calliglyph::core::editor::Editor;
+///
+/// let mut editor = Editor::from_text("fn main() {}");
+/// editor.insert_str("// entry point\n")?;
+/// assert_eq!(editor.line_count(), 2);
+/// assert_eq!(editor.line(0), Some("// entry point"));
+/// # Ok::<(), calliglyph::core::errors::editor_errors::EditorError>(())
+/// ```
 #[derive(Debug)]
 pub struct Editor {
-    pub editor_content: Vec<String>,
-    pub visual_cursor_x: i16,
-    pub cursor: Cursor, //to save position in editor, when toggling area
-    pub text_selection_start: Option<CursorPosition>,
-    pub text_selection_end: Option<CursorPosition>,
-    pub editor_width: i16,
-    pub scroll_offset: i16,
-    pub editor_height: u16,
-    pub clipboard: Clipboard,
+    //the lines, edited through the buffer primitives while this keeps the cursor, selection
+    // and view in step
+    pub(crate) editor_content: Buffer,
+    pub(crate) visual_cursor_x: usize,
+    pub(crate) cursor: Cursor, //to save position in editor, when toggling area
+    //selected text, kept with start before end. edits, copying and plain cursor moves clear
+    // it, scrolling, switching areas, popups and commands that don't edit leave it alone
+    pub(crate) selection: Option<Range>,
+    pub(crate) editor_width: usize,
+    pub(crate) scroll_offset: usize,
+    pub(crate) editor_height: u16,
+    pub(crate) clipboard: Clipboard,
     undo_redo_manager: UndoRedoManager,
+    //actions making up the last change, replayed by RepeatLastChange
+    pub(crate) last_change: Vec<InputAction>,
+    insert_run_open: bool,
+    pub(crate) marks: Marks,
+    pub(crate) bookmarks: Bookmarks,
+    //bumped on every content change, keys the cached word index
+    pub(crate) revision: u64,
+    word_index: WordIndex,
+    //chars making up words for word motions and completion
+    word_chars: WordChars,
+    completion: Option<CompletionSession>,
+    stats_cache: StatsCache,
+    bracket_cache: BracketMatchCache,
+    //column of the cursor on its line, so moves along a long line stay cheap
+    column_cache: ColumnCache,
+    //text being typed once for every selected line, see start_line_insert
+    line_insert: Option<LineInsert>,
+    //changes made since the owner last took them, None until it asks for them
+    events: Option<Vec<EditEvent>>,
+    //broken states found by check_invariants and clamped, until the owner takes them
+    repairs: Vec<String>,
 }
 
 impl Editor {
     pub fn new() -> Self {
         Self {
-            editor_content: vec![],
+            editor_content: Buffer::new(),
             visual_cursor_x: 0,
-            text_selection_start: None,
-            text_selection_end: None,
+            selection: None,
             cursor: Cursor::new(),
             editor_width: 0,
             scroll_offset: 0,
             editor_height: 0,
             clipboard: Clipboard::new(),
             undo_redo_manager: UndoRedoManager::new(),
+            last_change: vec![],
+            insert_run_open: false,
+            marks: Marks::new(),
+            bookmarks: Bookmarks::new(),
+            revision: 0,
+            word_index: WordIndex::new(),
+            word_chars: editor_settings::WORD_CHARS,
+            completion: None,
+            stats_cache: StatsCache::new(),
+            bracket_cache: BracketMatchCache::default(),
+            column_cache: ColumnCache::default(),
+            line_insert: None,
+            events: None,
+            repairs: vec![],
+        }
+    }
+
+    ///editor holding text, with the cursor at its start. lines are split on `\n`, so
+    /// empty text gives one empty line.
+    ///
+    /// ```
+    /// use calliglyph::core::editor::Editor;
+    ///
+    /// let editor = Editor::from_text("one\ntwo");
+    /// assert_eq!(editor.line_count(), 2);
+    /// assert_eq!(editor.line(1), Some("two"));
+    /// assert_eq!(editor.to_string(), "one\ntwo");
+    /// ```
+    pub fn from_text(text: &str) -> Self {
+        let mut editor = Self::new();
+        editor.content_replaced(text.split('\n').map(String::from).collect());
+        editor
+    }
+
+    ///replaces the whole content with lines, like a file read in its place. the cursor,
+    /// selection and view are clamped into the new lines and marks and bookmarks follow the
+    /// lines that changed. it isn't an edit: nothing is reported and the undo history,
+    /// which can't be applied to other content, is dropped.
+    ///
+    /// ```
+    /// use calliglyph::core::cursor::CursorPosition;
+    /// use calliglyph::core::editor::Editor;
+    ///
+    /// let mut editor = Editor::from_text("one\ntwo\nthree");
+    /// editor.set_cursor(CursorPosition::new(5, 2))?;
+    /// editor.content_replaced(vec!["1".to_string()]);
+    /// assert_eq!(editor.cursor(), CursorPosition::new(1, 0));
+    /// assert!(!editor.can_undo());
+    /// # Ok::<(), calliglyph::core::errors::editor_errors::EditorError>(())
+    /// ```
+    pub fn content_replaced(&mut self, lines: Vec<String>) {
+        let old = std::mem::replace(&mut self.editor_content, lines.into());
+        //an empty file still has a line to type on
+        self.editor_content.ensure_lines(1);
+        self.stats_cache.invalidate();
+        self.marks.adjust(&old, &self.editor_content);
+        self.bookmarks.adjust(&old, &self.editor_content);
+        self.undo_redo_manager.clear();
+        self.insert_run_open = false;
+        self.completion = None;
+        self.line_insert = None;
+        self.revision += 1;
+        self.clamp_positions();
+        //no further down than needed to show the last line, like align_view
+        let height = (self.editor_height as usize).max(1);
+        let last_offset = self.editor_content.len().saturating_sub(height);
+        self.scroll_offset = self.scroll_offset.min(last_offset);
+        self.scroll_to_cursor();
+    }
+
+    ///number of lines in the editor
+    pub fn line_count(&self) -> usize {
+        self.editor_content.len()
+    }
+
+    ///line at index i, None past the last line
+    pub fn line(&self, i: usize) -> Option<&str> {
+        self.editor_content.get(i).map(String::as_str)
+    }
+
+    ///all lines of the editor
+    pub fn lines(&self) -> &[String] {
+        &self.editor_content
+    }
+
+    ///position of the cursor, x counted in chars
+    pub fn cursor(&self) -> CursorPosition {
+        self.cursor_position()
+    }
+
+    ///moves the cursor to pos, dropping the selection. pos may be one past the last char
+    /// of its line.
+    ///
+    /// ```
+    /// use calliglyph::core::cursor::CursorPosition;
+    /// use calliglyph::core::editor::Editor;
+    ///
+    /// let mut editor = Editor::from_text("hello");
+    /// editor.set_cursor(CursorPosition::new(5, 0))?;
+    /// editor.insert_str(", world")?;
+    /// assert_eq!(editor.line(0), Some("hello, world"));
+    /// assert!(editor.set_cursor(CursorPosition::new(0, 3)).is_err());
+    /// # Ok::<(), calliglyph::core::errors::editor_errors::EditorError>(())
+    /// ```
+    pub fn set_cursor(&mut self, pos: CursorPosition) -> Result<(), EditorError> {
+        self.check_position_in_bounds(pos)?;
+        self.clear_selection();
+        self.set_cursor_position(&pos);
+        self.scroll_to_cursor();
+        Ok(())
+    }
+
+    ///the selected text, start before end, None without a selection
+    pub fn selection(&self) -> Option<Range> {
+        self.selection
+    }
+
+    ///selects the text in range, which may be backwards, leaving the cursor at its end
+    ///
+    /// ```
+    /// use calliglyph::core::editor::Editor;
+    /// use calliglyph::core::position::{Position, Range};
+    ///
+    /// let mut editor = Editor::from_text("hello world");
+    /// editor.set_selection(Range::new(Position::new(0, 11), Position::new(0, 6)))?;
+    /// assert_eq!(editor.selected_text().as_deref(), Some("world"));
+    /// editor.insert_str("there")?;
+    /// assert_eq!(editor.to_string(), "hello there");
+    /// # Ok::<(), calliglyph::core::errors::editor_errors::EditorError>(())
+    /// ```
+    pub fn set_selection(&mut self, range: Range) -> Result<(), EditorError> {
+        let range = range.normalized();
+        self.check_position_in_bounds(range.start)?;
+        self.check_position_in_bounds(range.end)?;
+        self.set_cursor_position(&range.end.into());
+        self.selection = Some(range);
+        self.scroll_to_cursor();
+        Ok(())
+    }
+
+    ///appends lines read from the buffer's file after the content, the first one continuing
+    /// the last line if continue_last_line is set. it isn't an edit of the buffer, so it
+    /// can't be undone and reports no change.
+    pub fn append_from_file(&mut self, text: &str, continue_last_line: bool) {
+        let mut lines = text
+            .split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line).to_string());
+        let last = self.editor_content.len().saturating_sub(1);
+        if let Some(y) = self
+            .editor_content
+            .len()
+            .checked_sub(1)
+            .filter(|_| continue_last_line)
+        {
+            let end = Position::new(y, line_index::char_count(&self.editor_content[y]));
+            let _ = self
+                .editor_content
+                .insert_str(end, &lines.next().unwrap_or_default());
+        }
+        let at = self.editor_content.len();
+        //inserting after the last line can't fail
+        let _ = self.editor_content.insert_lines(at, lines.collect());
+        //only the last line and those after it are counted again
+        let added = self.editor_content.len() - last;
+        self.stats_cache.lines_replaced(last, 1, added);
+        self.revision += 1;
+    }
+
+    ///types c at the cursor, replacing the selection if there is one
+    pub fn insert_char(&mut self, c: char) -> Result<(), EditorError> {
+        self.handle_input_action(InputAction::WriteChar(c))
+    }
+
+    ///inserts text at the cursor as one undo step, replacing the selection if there is
+    /// one. the cursor ends up after the inserted text.
+    pub fn insert_str(&mut self, text: &str) -> Result<(), EditorError> {
+        if self.is_text_selected() {
+            self.replace_selected_text(text)?;
+            self.clear_selection();
+        } else {
+            let lines: Vec<String> = text.split('\n').map(String::from).collect();
+            self.insert_text(&lines);
         }
+        Ok(())
+    }
+
+    ///splits the line at the cursor, moving the cursor to the start of the new line
+    pub fn insert_newline(&mut self) -> Result<(), EditorError> {
+        self.handle_input_action(InputAction::ENTER)
+    }
+
+    ///deletes the char before the cursor, or the selection if there is one
+    ///
+    /// ```
+    /// use calliglyph::core::editor::Editor;
+    ///
+    /// let mut editor = Editor::new();
+    /// for c in "abc".chars() {
+    ///     editor.insert_char(c)?;
+    /// }
+    /// editor.delete_backward()?;
+    /// assert_eq!(editor.to_string(), "ab");
+    /// editor.undo()?;
+    /// assert_eq!(editor.to_string(), "abc");
+    /// # Ok::<(), calliglyph::core::errors::editor_errors::EditorError>(())
+    /// ```
+    pub fn delete_backward(&mut self) -> Result<(), EditorError> {
+        self.handle_input_action(InputAction::BACKSPACE)
+    }
+
+    ///deletes the char under the cursor, or the selection if there is one
+    pub fn delete_forward(&mut self) -> Result<(), EditorError> {
+        self.handle_input_action(InputAction::DELETE)
+    }
+
+    ///sets the size of the area the editor is shown in, which scrolling keeps the cursor
+    /// inside of
+    pub fn set_viewport(&mut self, width: u16, height: u16) {
+        self.editor_width = width as usize;
+        self.editor_height = height;
+        self.scroll_to_cursor();
+    }
+
+    ///index of the first line shown in the viewport
+    pub fn scroll_offset(&self) -> usize {
+        self.scroll_offset
+    }
+
+    ///column the cursor is drawn at, with tabs expanded
+    pub fn visual_cursor_x(&self) -> usize {
+        self.visual_cursor_x
     }
 
     ///function to handle input action on editor,
     /// responsible for dispatching action to correct internal method.
     pub fn handle_input_action(&mut self, action: InputAction) -> Result<(), EditorError> {
+        if self.handle_completion_action(&action) {
+            return Ok(());
+        }
+        if changes_content(&action) {
+            self.revision += 1;
+        }
+
+        //kept to name the action if it breaks an invariant
+        let checked_action = invariants::ENABLED.then(|| action.clone());
+
+        //scrolling leaves the cursor behind, anything else brings the view back to it
+        let snaps_back = !matches!(
+            action,
+            InputAction::ScrollLines(_) | InputAction::ScrollHalfPages(_) | InputAction::NoOp
+        );
+        let result = if action == InputAction::RepeatLastChange {
+            self.repeat_last_change()
+        } else {
+            //a failed action changed nothing to repeat, it only ends an insertion run
+            let recorded = action.clone();
+            let result = self.dispatch_input_action(action);
+            match result {
+                Ok(()) => self.record_change(&recorded),
+                Err(_) => self.insert_run_open = false,
+            }
+            result
+        };
+        if snaps_back {
+            self.scroll_to_cursor();
+        }
+
+        if let Some(action) = checked_action {
+            self.check_invariants(&action);
+        }
+        result
+    }
+
+    ///dispatches action to the internal method, without recording it as a change
+    fn dispatch_input_action(&mut self, action: InputAction) -> Result<(), EditorError> {
         match action {
             InputAction::MoveCursor(direction) => {
                 let (x, y) = direction.to_vector();
                 self.move_cursor(x, y);
-                self.text_selection_start = None;
-                self.text_selection_end = None;
+                self.selection = None;
                 Ok(())
             }
             InputAction::MoveSelectionCursor(direction) => {
@@ -114,14 +448,56 @@ impl Editor {
                 self.move_selection_cursor(x, y);
                 Ok(())
             }
-            InputAction::TAB => {
-                self.tab();
+            InputAction::MoveCursorTo(motion) => {
+                self.move_cursor_to(motion);
+                self.clear_selection();
                 Ok(())
             }
-            InputAction::ENTER => {
-                self.enter();
+            InputAction::MoveSelectionCursorTo(motion) => {
+                self.move_selection_cursor_to(motion);
+                Ok(())
+            }
+            InputAction::SelectEnclosing => {
+                self.select_enclosing();
+                Ok(())
+            }
+            InputAction::SwapSelectionEnds => {
+                self.swap_selection_ends();
+                Ok(())
+            }
+            InputAction::AlignView(align) => {
+                self.align_view(align);
+                Ok(())
+            }
+            InputAction::ScrollLines(lines) => {
+                self.scroll_view(lines);
+                Ok(())
+            }
+            InputAction::ScrollHalfPages(pages) => {
+                let half_page = (self.editor_height as isize / 2).max(1);
+                self.scroll_view(pages * half_page);
+                Ok(())
+            }
+            InputAction::ClearSelection => {
+                self.clear_selection();
                 Ok(())
             }
+            InputAction::TAB => {
+                if self.is_text_selected() {
+                    self.tab_text_is_selected()
+                } else {
+                    self.tab();
+                    Ok(())
+                }
+            }
+            InputAction::ENTER => {
+                if self.is_text_selected() {
+                    self.enter_text_is_selected()
+                } else {
+                    self.enter();
+                    Ok(())
+                }
+            }
             InputAction::BACKSPACE => {
                 if self.is_text_selected() {
                     self.backspace_text_is_selected();
@@ -142,10 +518,19 @@ impl Editor {
                 Ok(()) => Ok(()),
                 Err(e) => Err(e),
             },
+            InputAction::CopyAppend => self.copy_append().map(|_| ()),
             InputAction::CUT => match self.cut() {
                 Ok(()) => Ok(()),
                 Err(e) => Err(e),
             },
+            InputAction::CutLine => {
+                self.cut_line();
+                Ok(())
+            }
+            InputAction::CopyLine => {
+                self.copy_line();
+                Ok(())
+            }
             InputAction::PASTE => match self.paste() {
                 Ok(()) => Ok(()),
                 Err(e) => Err(e),
@@ -158,6 +543,20 @@ impl Editor {
                 Ok(()) => Ok(()),
                 Err(e) => Err(e),
             },
+            InputAction::IncrementNumber(delta) => {
+                self.increment_numbers(delta, false);
+                Ok(())
+            }
+            InputAction::OpenLine(below) => {
+                self.open_line(below);
+                Ok(())
+            }
+            InputAction::DuplicateSelection => self.duplicate_selection(),
+            InputAction::ToggleBookmark => {
+                self.toggle_bookmark();
+                Ok(())
+            }
+            InputAction::JumpToBookmark(forward) => self.jump_to_bookmark(forward),
             InputAction::WriteChar(c) => {
                 if self.is_text_selected() {
                     self.write_char_text_is_selected(c)
@@ -166,34 +565,165 @@ impl Editor {
                 }
                 Ok(())
             }
+            //terminals may paste windows or old mac line endings
+            InputAction::InsertText(text) => {
+                self.insert_str(&text.replace("\r\n", "\n").replace('\r', "\n"))
+            }
             _ => Ok(()),
         }
     }
 
+    //repeat last change
+    ///remembers the last mutating operation so it can be replayed,
+    /// consecutive typing is recorded as one insertion run
+    fn record_change(&mut self, action: &InputAction) {
+        match action {
+            InputAction::WriteChar(_)
+            | InputAction::InsertText(_)
+            | InputAction::TAB
+            | InputAction::ENTER
+            | InputAction::BACKSPACE => {
+                if !self.insert_run_open {
+                    self.last_change.clear();
+                    self.insert_run_open = true;
+                }
+                self.last_change.push(action.clone());
+            }
+            InputAction::DELETE
+            | InputAction::PASTE
+            | InputAction::CUT
+            | InputAction::CutLine
+            | InputAction::IncrementNumber(_)
+            | InputAction::OpenLine(_)
+            | InputAction::DuplicateSelection => {
+                self.last_change = vec![action.clone()];
+                self.insert_run_open = false;
+            }
+            //anything else ends an insertion run
+            _ => self.insert_run_open = false,
+        }
+    }
+
+    ///replays the last change at the current cursor position, as a single undo step
+    pub fn repeat_last_change(&mut self) -> Result<(), EditorError> {
+        self.insert_run_open = false;
+        let actions = self.last_change.clone();
+        self.undo_group(|editor| {
+            actions
+                .into_iter()
+                .try_for_each(|action| editor.dispatch_input_action(action))
+        })
+    }
+
     //undo manager
     ///undo wrapper function, that calls the UndoRedoManager
     pub fn undo(&mut self) -> Result<(), EditorError> {
-        match self.undo_redo_manager.undo() {
-            Ok(action) => {
-                self.apply_action(&action);
-                Ok(())
-            }
-            Err(e) => Err(UndoFailure(e)),
+        let action = self.undo_redo_manager.undo()?;
+        //the selected text may be gone, a selection left over would point past it
+        self.selection = None;
+        self.revision += 1;
+        //a step that doesn't fit the content stays the one to undo
+        if let Err(e) = self.apply_action(&action) {
+            let _ = self.undo_redo_manager.redo();
+            return Err(e);
         }
+        self.report_edit(&action);
+        Ok(())
     }
     ///redo wrapper function, that calls the UndoRedoManager
     pub fn redo(&mut self) -> Result<(), EditorError> {
-        match self.undo_redo_manager.redo() {
-            Ok(action) => {
-                self.apply_action(&action);
-                Ok(())
-            }
-            Err(e) => Err(RedoFailure(e)),
+        let action = self.undo_redo_manager.redo()?;
+        //the selected text may be gone, a selection left over would point past it
+        self.selection = None;
+        self.revision += 1;
+        //a step that doesn't fit the content stays the one to redo
+        if let Err(e) = self.apply_action(&action) {
+            let _ = self.undo_redo_manager.undo();
+            return Err(e);
+        }
+        self.report_edit(&action);
+        Ok(())
+    }
+
+    ///checks if there is a step to undo
+    pub fn can_undo(&self) -> bool {
+        self.undo_depth() > 0
+    }
+
+    ///checks if there is an undone step to redo
+    pub fn can_redo(&self) -> bool {
+        self.redo_depth() > 0
+    }
+
+    ///number of steps undo can go back
+    pub fn undo_depth(&self) -> usize {
+        self.undo_redo_manager.applied()
+    }
+
+    ///number of undone steps redo can bring back
+    pub fn redo_depth(&self) -> usize {
+        self.undo_redo_manager.undone()
+    }
+
+    ///the current state of the content in the undo history, for is_modified_since
+    pub fn undo_mark(&self) -> UndoMark {
+        self.undo_redo_manager.mark()
+    }
+
+    ///checks if the content left the state mark was taken in. undoing and redoing back to
+    /// it counts as unmodified, changes not recorded for undo aren't seen.
+    ///
+    /// ```
+    /// use calliglyph::core::editor::Editor;
+    ///
+    /// let mut editor = Editor::from_text("saved");
+    /// let saved = editor.undo_mark();
+    /// editor.insert_str("!")?;
+    /// assert!(editor.is_modified_since(saved));
+    /// editor.undo()?;
+    /// assert!(!editor.is_modified_since(saved));
+    /// # Ok::<(), calliglyph::core::errors::editor_errors::EditorError>(())
+    /// ```
+    pub fn is_modified_since(&self, mark: UndoMark) -> bool {
+        self.undo_mark() != mark
+    }
+
+    ///the recorded steps oldest first, and how many of them are applied to the content
+    pub fn undo_history(&self) -> (Vec<UndoEntry>, usize) {
+        let history = self.undo_redo_manager.history().cloned().collect();
+        (history, self.undo_redo_manager.applied())
+    }
+
+    ///replaces the undo history with one from undo_history, like one kept between runs.
+    /// the content has to be the state it leads to.
+    pub fn restore_undo_history(&mut self, history: Vec<UndoEntry>, applied: usize) {
+        self.undo_redo_manager.restore(history, applied);
+    }
+
+    ///undoes or redoes steps until the first state steps of the history are applied, 0
+    /// going back before the first one. the cursor goes to where the step leading into
+    /// that state changed the text, the first step for the state before it.
+    pub fn revert_to(&mut self, state: usize) -> Result<(), EditorError> {
+        let (history, _) = self.undo_history();
+        if state > history.len() {
+            return Err(EditorError::NothingToRedo);
+        }
+        while self.undo_redo_manager.applied() > state {
+            self.undo()?;
+        }
+        while self.undo_redo_manager.applied() < state {
+            self.redo()?;
+        }
+        if let Some(entry) = history.get(state.saturating_sub(1)) {
+            self.clear_selection();
+            self.set_cursor_position(&entry.position());
+            self.scroll_to_cursor();
         }
+        Ok(())
     }
 
-    /// applies an EditAction
-    fn apply_action(&mut self, action: &EditAction) {
+    /// applies an EditAction, failing if it doesn't fit the content
+    fn apply_action(&mut self, action: &EditAction) -> Result<(), EditorError> {
         match action {
             EditAction::Insert { pos, c } => {
                 self.insert_char_at(*pos, *c);
@@ -223,18 +753,24 @@ impl Editor {
                 let last_line_len = new.last().map(|s| s.len()).unwrap_or(0);
                 let additive_pos = CursorPosition {
                     x: last_line_len,
-                    y: new.iter().count(),
+                    y: new.len(),
                 };
                 let end: CursorPosition = *start + additive_pos;
                 self.set_cursor_position(&end);
             }
+            EditAction::ReplaceLines { start, old, new } => {
+                let end = (start.y + old.len()).min(self.editor_content.len());
+                self.editor_content
+                    .replace_lines(start.y.min(end)..end, new.clone())?;
+                self.set_cursor_position(start);
+            }
             EditAction::InsertLines { start, lines } => {
                 self.insert_lines_at(*start, lines.clone());
                 //get additive position to get new cursor pos at end of insertion
                 let last_line_len = lines.last().map(|s| s.len()).unwrap_or(0);
                 let additive_pos = CursorPosition {
                     x: last_line_len,
-                    y: lines.iter().count(),
+                    y: lines.len(),
                 };
                 let end: CursorPosition = *start + additive_pos;
                 self.set_cursor_position(&end);
@@ -245,7 +781,7 @@ impl Editor {
                 let last_line_len = deleted.last().map(|s| s.len()).unwrap_or(0);
                 let negated_pos = CursorPosition {
                     x: last_line_len,
-                    y: deleted.iter().count(),
+                    y: deleted.len(),
                 };
                 let end: CursorPosition = *start - negated_pos;
                 self.set_cursor_position(&end);
@@ -261,7 +797,7 @@ impl Editor {
                 let last_line_len = lines.last().map(|s| s.len()).unwrap_or(0);
                 let additive_pos = CursorPosition {
                     x: last_line_len,
-                    y: lines.iter().count(),
+                    y: lines.len(),
                 };
                 let end: CursorPosition = *start + additive_pos;
                 self.set_cursor_position(&end);
@@ -275,28 +811,149 @@ impl Editor {
 
                 self.set_cursor_position(start);
             }
-            EditAction::SplitLine { pos, left, right } => {
-                // overwrite current line with the left part
-                self.editor_content[pos.y] = left.clone();
-
-                // insert the right part as a new line
-                self.editor_content.insert(pos.y + 1, right.clone());
+            EditAction::SplitLine { pos, left, .. } => {
+                // the left part stays, the right part moves to a new line below
+                let split_at = Position::new(pos.y, line_index::char_count(left));
+                self.editor_content.split_line(split_at)?;
 
                 //at start of new line
                 let temp_pos = CursorPosition { x: 0, y: pos.y + 1 };
 
                 self.set_cursor_position(&temp_pos);
             }
-            EditAction::JoinLine { pos, merged } => {
-                // overwrite current line with the merged part
-                self.editor_content[pos.y] = merged.clone();
-
-                // remove next line
-                self.editor_content.remove(pos.y + 1);
+            EditAction::JoinLine { pos, .. } => {
+                // append the next line to the current one
+                self.editor_content.merge_lines(pos.y)?;
 
                 self.set_cursor_position(pos);
             }
+            EditAction::Batch { actions } => {
+                for action in actions {
+                    self.apply_action(action)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    ///starts an undo group, all edits until end_undo_group undo as one step
+    pub fn begin_undo_group(&mut self) {
+        self.undo_redo_manager.begin_group();
+    }
+
+    ///ends an undo group started with begin_undo_group
+    pub fn end_undo_group(&mut self) {
+        self.undo_redo_manager.end_group();
+    }
+
+    ///runs f inside an undo group, so every edit it makes undoes as one step. the group
+    /// ends however f returns, one that failed before editing leaves no step.
+    ///
+    /// ```
+    /// use calliglyph::core::editor::Editor;
+    ///
+    /// let mut editor = Editor::from_text("");
+    /// editor.undo_group(|editor| {
+    ///     editor.insert_str("a")?;
+    ///     editor.insert_str("b")
+    /// })?;
+    /// editor.undo()?;
+    /// assert_eq!(editor.lines(), [""]);
+    /// # Ok::<(), calliglyph::core::errors::editor_errors::EditorError>(())
+    /// ```
+    pub fn undo_group<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.begin_undo_group();
+        let result = f(self);
+        self.end_undo_group();
+        result
+    }
+
+    ///undoes up to count steps, returns how many there were
+    pub fn undo_steps(&mut self, count: usize) -> Result<usize, EditorError> {
+        let steps = count.min(self.undo_depth());
+        if steps == 0 {
+            return Err(EditorError::NothingToUndo);
+        }
+        for _ in 0..steps {
+            self.undo()?;
+        }
+        Ok(steps)
+    }
+
+    ///redoes up to count undone steps, returns how many there were
+    pub fn redo_steps(&mut self, count: usize) -> Result<usize, EditorError> {
+        let steps = count.min(self.redo_depth());
+        if steps == 0 {
+            return Err(EditorError::NothingToRedo);
+        }
+        for _ in 0..steps {
+            self.redo()?;
+        }
+        Ok(steps)
+    }
+
+    ///records a change for undo and reports it to what follows the content
+    fn record_edit(&mut self, action: EditAction) {
+        self.report_edit(&action);
+        self.undo_redo_manager.record_undo(action);
+    }
+
+    ///moves the marks and bookmarks along with the lines a change made to the content
+    /// inserted or deleted, as told by its events, has the lines it touched counted again
+    /// and queues the events for the owner if it watches them
+    fn report_edit(&mut self, action: &EditAction) {
+        let mut events = vec![];
+        edit_events::push_events(action, &mut events);
+        for shift in events.iter().filter_map(EditEvent::line_shift) {
+            self.marks.shift(&shift);
+            self.bookmarks.shift(&shift);
+        }
+        for event in &events {
+            self.stats_cache.apply(event);
         }
+        if let Some(queue) = &mut self.events {
+            queue.extend(events);
+        }
+    }
+
+    ///starts queueing the events of changes made to the content, for take_events. without
+    /// it no events are kept, so an editor nobody watches doesn't pile them up.
+    pub fn watch_events(&mut self) {
+        self.events.get_or_insert_with(Vec::new);
+    }
+
+    ///the changes made to the content since the last call, oldest first. empty unless
+    /// watch_events was called.
+    ///
+    /// ```
+    /// use calliglyph::core::cursor::CursorPosition;
+    /// use calliglyph::core::editor::edit_events::EditEvent;
+    /// use calliglyph::core::editor::Editor;
+    ///
+    /// let mut editor = Editor::new();
+    /// editor.watch_events();
+    /// editor.insert_char('a')?;
+    /// editor.undo()?;
+    /// let at = CursorPosition::new(0, 0);
+    /// assert_eq!(
+    ///     editor.take_events(),
+    ///     vec![
+    ///         EditEvent::Inserted { at, text: "a".to_string() },
+    ///         EditEvent::Deleted { range: at..CursorPosition::new(1, 0), text: "a".to_string() },
+    ///     ]
+    /// );
+    /// assert!(editor.take_events().is_empty());
+    /// # Ok::<(), calliglyph::core::errors::editor_errors::EditorError>(())
+    /// ```
+    pub fn take_events(&mut self) -> Vec<EditEvent> {
+        self.events.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    ///checks if changes were made since the events were last taken
+    pub fn has_events(&self) -> bool {
+        self.events
+            .as_ref()
+            .is_some_and(|events| !events.is_empty())
     }
 
     //copy, cut and paste
@@ -306,49 +963,29 @@ impl Editor {
         match self.copy_selected_text() {
             Ok(selected_text) => {
                 //copy to clipboard
-                self.clipboard.copy(&*selected_text);
+                self.clipboard.copy(&selected_text);
                 //reset text selection
-                self.text_selection_start = None;
-                self.text_selection_end = None;
+                self.selection = None;
                 Ok(())
             }
             Err(e) => Err(e),
         }
     }
 
+    ///copies the selected text below the clipboard content, returns the number of lines
+    /// the clipboard holds now
+    pub fn copy_append(&mut self) -> Result<usize, EditorError> {
+        let selected_text = self.copy_selected_text()?;
+        self.clipboard.append(&selected_text);
+        self.selection = None;
+        Ok(self.clipboard.copied_text.len())
+    }
+
     ///copies text within bound of text selected to copied_text
     pub fn copy_selected_text(&mut self) -> Result<Vec<String>, EditorError> {
-        if let (Some(start), Some(end)) = (self.text_selection_start, self.text_selection_end) {
-            let mut selected_text: Vec<String> = Vec::new();
-            let lines = &self.editor_content[start.y..=end.y];
-
-            if lines.len() > 1 {
-                for (y, line) in lines.iter().enumerate() {
-                    let mut line_chars: Vec<char> = line.chars().collect();
-                    let extracted_text: String;
-
-                    //if first line drain all from start x,
-                    // else if last line drain to end .x, else drain all
-                    if y == 0 {
-                        extracted_text = line_chars.drain(start.x..).collect();
-                    } else if y == lines.len() - 1 {
-                        extracted_text = line_chars.drain(..end.x).collect();
-                    } else {
-                        extracted_text = line_chars.into_iter().collect();
-                    }
-
-                    selected_text.push(extracted_text);
-                }
-            } else {
-                let mut line_chars: Vec<char> = self.editor_content[start.y].chars().collect();
-                let extracted_text: String = line_chars.drain(start.x..end.x).collect();
-                selected_text.push(extracted_text);
-            }
-
-            Ok(selected_text)
-        } else {
-            Err(TextSelectionFailure(TextSelectionError::NoTextSelected))
-        }
+        let (start, end) = self.selection_bounds().ok_or(EditorError::NoSelection)?;
+        let text = self.editor_content.slice(Range::new(start, end))?;
+        Ok(text.split('\n').map(String::from).collect())
     }
 
     ///base function for cut that cuts if text is selected
@@ -356,10 +993,9 @@ impl Editor {
         match self.cut_selected_text() {
             Ok(selected_text) => {
                 //copy to clipboard
-                self.clipboard.copy(&*selected_text);
+                self.clipboard.copy(&selected_text);
                 //reset text selection
-                self.text_selection_start = None;
-                self.text_selection_end = None;
+                self.selection = None;
                 Ok(())
             }
             Err(e) => Err(e),
@@ -368,60 +1004,77 @@ impl Editor {
 
     ///cuts text within bound of text selected to copied_text
     pub fn cut_selected_text(&mut self) -> Result<Vec<String>, EditorError> {
-        if let (Some(start), Some(end)) = (self.text_selection_start, self.text_selection_end) {
-            let mut selected_text: Vec<String> = Vec::new();
-            let mut lines_to_remove: Vec<usize> = Vec::new(); //lines that should be removed
-            let lines = self.editor_content[start.y..=end.y].as_mut();
-            let line_length = lines.len();
-            if lines.len() > 1 {
-                for (y, line) in lines.iter_mut().enumerate() {
-                    let mut line_chars: Vec<char> = line.as_mut().chars().collect();
-                    let extracted_text: String;
-
-                    //if first line drain all from start x,
-                    // else if last line drain to end .x, else drain all
-
-                    if y == 0 {
-                        extracted_text = line_chars.drain(start.x..).collect();
-                    } else if y == line_length - 1 {
-                        extracted_text = line_chars.drain(..end.x).collect();
-                    } else {
-                        extracted_text = line_chars.drain(..).collect();
-                    }
-                    //if line manipulated is empty,
-                    // means we want to delete it as well
-                    if line_chars.is_empty() {
-                        lines_to_remove.push(start.y + y);
-                    }
-
-                    selected_text.push(extracted_text);
-                    *line = line_chars.into_iter().collect();
-                }
-            } else {
-                let lines = self.editor_content[start.y..start.y + 1].as_mut();
-                let line = lines.iter_mut().next().unwrap();
-                let mut line_chars: Vec<char> = line.as_mut().chars().collect();
-                let extracted_text: String = line_chars.drain(start.x..end.x).collect();
-                selected_text.push(extracted_text);
-
-                *line = line_chars.into_iter().collect();
-            }
+        let (start, end) = self.selection_bounds().ok_or(EditorError::NoSelection)?;
+        self.check_position_in_bounds(start)?;
+        self.check_position_in_bounds(end)?;
+        let selected_text = self.copy_selected_text()?;
+        //only a cut from the very start can take every line
+        let whole = (start == Position::default()).then(|| self.editor_content.clone());
+        //the same removal undo and redo do, so the recorded range matches the content
+        self.delete_text_at_range(&start.into(), &end.into());
+        if let (true, Some(whole)) = (self.editor_content.is_empty(), whole) {
+            //an empty line is left, like cutting the last line does. recorded as replacing
+            // the lines, as a range can't be put back into no lines at all
+            self.editor_content = whole;
+            self.replace_lines(0..self.editor_content.len(), vec![String::new()]);
+            self.set_cursor_position(&CursorPosition::new(0, 0));
+            return Ok(selected_text);
+        }
+        self.record_edit(EditAction::DeleteRange {
+            start: start.into(),
+            end: end.into(),
+            deleted: selected_text.clone(),
+        });
+        self.set_cursor_position(&start.into());
+        Ok(selected_text)
+    }
 
-            // remove fully cut lines, from last to first to avoid index shift
-            for &y in lines_to_remove.iter().rev() {
-                self.editor_content.remove(y);
-            }
+    ///cuts the whole cursor line to the clipboard, including its line break
+    pub(crate) fn cut_line(&mut self) {
+        let y = self.cursor.y;
+        if y >= self.editor_content.len() {
+            return;
+        }
 
-            // record undo (DeleteRange)
-            self.undo_redo_manager.record_undo(EditAction::DeleteRange {
-                start,
-                end,
-                deleted: selected_text.clone(),
+        let line = if self.editor_content.len() == 1 {
+            //last remaining line is emptied instead of removed
+            let line = self
+                .editor_content
+                .set_line(0, String::new())
+                .unwrap_or_default();
+            self.record_edit(EditAction::DeleteRange {
+                start: CursorPosition { x: 0, y: 0 },
+                end: CursorPosition {
+                    x: line.chars().count(),
+                    y: 0,
+                },
+                deleted: vec![line.clone()],
             });
-
-            Ok(selected_text)
+            line
         } else {
-            Err(TextSelectionFailure(TextSelectionError::NoTextSelected))
+            //y was checked to be a line above
+            let line = self
+                .editor_content
+                .remove_lines(y..y + 1)
+                .unwrap_or_default()
+                .concat();
+            self.record_edit(EditAction::DeleteLines {
+                start: CursorPosition { x: 0, y },
+                deleted: vec![line.clone()],
+            });
+            line
+        };
+
+        self.clipboard.copy(&[line, String::new()]);
+        self.selection = None;
+        self.set_cursor_position(&CursorPosition { x: 0, y });
+    }
+
+    ///copies the whole cursor line to the clipboard, including its line break
+    pub(crate) fn copy_line(&mut self) {
+        if let Some(line) = self.editor_content.get(self.cursor.y) {
+            let line = line.clone();
+            self.clipboard.copy(&[line, String::new()]);
         }
     }
 
@@ -437,15 +1090,17 @@ impl Editor {
     pub fn paste_selected_text(&mut self, copied_text: Vec<String>) -> Result<(), EditorError> {
         //if no text in copied text
         if copied_text.is_empty() {
-            return Err(ClipboardFailure(ClipboardError::NoCopiedText));
+            return Err(EditorError::EmptyClipboard);
         }
 
-        let insert_y = self.cursor.y as usize;
-        let insert_x = self.cursor.x as usize;
+        let insert_y = self.cursor.y;
+        let insert_x = self.cursor.x;
+        //the pasted lines shift the text a selection was made on
+        self.selection = None;
 
-        while self.editor_content.len() < insert_y + copied_text.len() - 1 {
-            self.editor_content.push(String::new());
-        }
+        //pasting below the last line extends the content up to the cursor line
+        self.editor_content
+            .ensure_lines((insert_y + copied_text.len() - 1).max(insert_y + 1));
 
         let current_line = &self.editor_content[insert_y];
 
@@ -461,7 +1116,7 @@ impl Editor {
                 copied_text[0],
                 after_cursor.iter().collect::<String>()
             );
-            self.editor_content[insert_y] = new_line;
+            self.editor_content.set_line(insert_y, new_line)?;
         } else {
             // Multi-line paste
             let mut new_lines = Vec::new();
@@ -487,7 +1142,8 @@ impl Editor {
             ));
 
             // Replace the current line and insert new lines
-            self.editor_content.splice(insert_y..=insert_y, new_lines);
+            self.editor_content
+                .replace_lines(insert_y..insert_y + 1, new_lines)?;
         }
 
         // Clear copied text after pasting
@@ -495,32 +1151,49 @@ impl Editor {
         Ok(())
     }
 
+    ///inserts lines at the cursor as one undo step, leaving the cursor after them
+    pub(crate) fn insert_text(&mut self, lines: &[String]) {
+        self.editor_content.ensure_lines(self.cursor.y + 1);
+        self.clear_selection();
+
+        self.undo_group(|editor| {
+            for (i, line) in lines.iter().enumerate() {
+                if i > 0 {
+                    editor.enter();
+                }
+                for c in line.chars() {
+                    editor.write_char(c);
+                }
+            }
+        });
+        self.revision += 1;
+        self.scroll_to_cursor();
+    }
+
     //editor writing
     ///writes char to y position line, with x position
-    pub fn write_char(&mut self, c: char) {
+    pub(crate) fn write_char(&mut self, c: char) {
         //creating lines until y position of cursor
-        while self.editor_content.len() <= self.cursor.y as usize {
-            self.editor_content.push(String::new());
-        }
-
-        let line = &mut self.editor_content[self.cursor.y as usize];
+        self.editor_content.ensure_lines(self.cursor.y + 1);
 
-        let char_count = line.chars().count();
+        let char_count = line_index::char_count(&self.editor_content[self.cursor.y]);
         //position cursor to line end in chars count
-        if char_count < self.cursor.x as usize {
-            self.cursor.x = char_count as i16;
+        if char_count < self.cursor.x {
+            self.cursor.x = char_count;
         }
 
-        let mut line_chars_vec: Vec<char> = line.chars().collect();
-
-        line_chars_vec.insert(self.cursor.x as usize, c);
-
-        *line = line_chars_vec.into_iter().collect();
+        if self
+            .editor_content
+            .insert_char(self.cursor_position().into(), c)
+            .is_err()
+        {
+            return;
+        }
         //record undo action (action done)
-        self.undo_redo_manager.record_undo(EditAction::Insert {
+        self.record_edit(EditAction::Insert {
             pos: CursorPosition {
-                x: self.cursor.x as usize,
-                y: self.cursor.y as usize,
+                x: self.cursor.x,
+                y: self.cursor.y,
             },
             c,
         });
@@ -529,111 +1202,98 @@ impl Editor {
     }
 
     ///replaces all selected text with char to y position line, with x position
-    pub fn write_char_text_is_selected(&mut self, c: char) {
-        let start = self.text_selection_start.unwrap();
-        let end = self.text_selection_end.unwrap();
-        let lines = &mut self.editor_content[start.y..=end.y];
-        let lines_length = lines.len();
-        if lines_length > 1 {
-            let mut line_indexes_to_remove: Vec<u16> = vec![];
-            for (y, line) in lines.iter_mut().enumerate() {
-                let mut line_chars_vec: Vec<char> = line.chars().collect();
-                //first line
-                if y == 0 {
-                    line_chars_vec.drain(start.x..line.chars().count());
-                    line_chars_vec.insert(start.x, c); //write chat to start position
-                } else if y == lines_length - 1 {
-                    //last line selected
-                    line_chars_vec.drain(0..end.x);
-                } else {
-                    line_chars_vec.drain(0..line.chars().count());
-                    line_indexes_to_remove.push((start.y + y) as u16);
-                }
-                *line = line_chars_vec.into_iter().collect();
-            }
-            // remove the lines that became empty in reverse order
-            for &i in line_indexes_to_remove.iter().rev() {
-                self.editor_content.remove(i as usize);
-            }
-            //move content of last line selected to first line start point
-            let line = &mut self
-                .editor_content
-                .remove(end.y - line_indexes_to_remove.len());
-            self.editor_content[start.y].push_str(line);
-        } else {
-            let line = &mut self.editor_content[start.y];
-            let mut line_chars_vec: Vec<char> = line.chars().collect();
-            line_chars_vec.drain(start.x..end.x);
-            line_chars_vec.insert(start.x, c);
-            *line = line_chars_vec.into_iter().collect();
-        }
-        self.cursor.x = self.text_selection_start.unwrap().x as i16;
-        self.cursor.y = self.text_selection_start.unwrap().y as i16;
-        self.text_selection_start = None;
-        self.text_selection_end = None;
-        self.move_cursor(1, 0);
+    pub(crate) fn write_char_text_is_selected(&mut self, c: char) {
+        //replacing records the removed text, so it is one undo step
+        if self.replace_selected_text(&c.to_string()).is_ok() {
+            self.clear_selection();
+        }
     }
 
     //editor tab character
     ///handles TAB action in editor, by writing \t to editor content.
-    pub fn tab(&mut self) {
-        let line = &mut self.editor_content[self.cursor.y as usize];
-
-        let mut line_chars_vec: Vec<char> = line.chars().collect();
-
-        line_chars_vec.insert(self.cursor.x as usize, '\t');
-
-        *line = line_chars_vec.into_iter().collect();
+    pub(crate) fn tab(&mut self) {
+        if self
+            .editor_content
+            .insert_char(self.cursor_position().into(), '\t')
+            .is_err()
+        {
+            return;
+        }
 
-        self.undo_redo_manager.record_undo(EditAction::Insert {
+        self.record_edit(EditAction::Insert {
             pos: CursorPosition {
-                x: self.cursor.x as usize,
-                y: self.cursor.y as usize,
+                x: self.cursor.x,
+                y: self.cursor.y,
             },
             c: '\t',
         });
         self.move_cursor(1, 0)
     }
 
-    //editor enter
-    ///handles enter new line, with possible move of text
-    pub fn enter(&mut self) {
-        let line = &mut self.editor_content[self.cursor.y as usize];
-        //if at end of line len, then just move cursor and make new line, else move text too
-        if self.cursor.x >= line.chars().count() as i16 {
-            self.editor_content
-                .insert(self.cursor.y as usize + 1, String::new());
+    ///replaces selected text within a line with a tab, a selection over several lines is
+    /// indented by a tab instead and stays selected. either is one undo step.
+    pub(crate) fn tab_text_is_selected(&mut self) -> Result<(), EditorError> {
+        let spans_lines = self
+            .selection_bounds()
+            .is_some_and(|(start, end)| start.line < end.line);
+        if spans_lines {
+            //empty lines are left without trailing whitespace
+            self.transform_lines(|lines| {
+                lines
+                    .into_iter()
+                    .map(|line| {
+                        if line.is_empty() {
+                            line
+                        } else {
+                            format!("\t{}", line)
+                        }
+                    })
+                    .collect()
+            });
+            return Ok(());
+        }
+        self.replace_selected_text("\t")?;
+        self.clear_selection();
+        Ok(())
+    }
+
+    //editor enter
+    ///handles enter new line, with possible move of text
+    pub(crate) fn enter(&mut self) {
+        //creating lines until y position of cursor, like write_char
+        self.editor_content.ensure_lines(self.cursor.y + 1);
+        let split_y = self.cursor.y;
+        let line_len = line_index::char_count(&self.editor_content[split_y]);
+        let split_at = Position::new(split_y, self.cursor.x.min(line_len));
+        if self.editor_content.split_line(split_at).is_err() {
+            return;
+        }
+        //if at end of line len, then just move cursor and make new line, else move text too
+        if self.cursor.x >= line_len {
             //record undo
-            self.undo_redo_manager.record_undo(EditAction::InsertLines {
+            self.record_edit(EditAction::InsertLines {
                 start: CursorPosition {
-                    x: self.cursor.x as usize,
-                    y: self.cursor.y as usize + 1, //+1 y to insert after current index
+                    x: self.cursor.x,
+                    y: self.cursor.y + 1, //+1 y to insert after current index
                 },
                 lines: vec![String::new()],
             });
             self.move_cursor(0, 1);
         } else {
-            //split current line and remove split part
-            let mut line_chars_vec: Vec<char> = line.chars().collect();
-            let line_end = line_chars_vec.split_off(self.cursor.x as usize);
-            let left: String = line_chars_vec.into_iter().collect();
-            let right: String = line_end.clone().into_iter().collect();
-
-            *line = left.clone();
+            let left = self.editor_content[split_y].clone();
+            let right = self.editor_content[split_y + 1].clone();
 
-            //move down and insert split line to next line
+            //move down to the split off part, splitting first keeps move_cursor from
+            // padding a line below the last one
             self.move_cursor(0, 1);
-            self.editor_content
-                .insert(self.cursor.y as usize, String::new());
-            self.editor_content[self.cursor.y as usize] = right.clone();
             //enter to split line, should go to start of line
             self.cursor.x = 0;
-            self.visual_cursor_x = self.calculate_visual_x() as i16;
+            self.visual_cursor_x = self.calculate_visual_x();
             // record undo
-            self.undo_redo_manager.record_undo(EditAction::SplitLine {
+            self.record_edit(EditAction::SplitLine {
                 pos: CursorPosition {
                     x: left.len(),
-                    y: (self.cursor.y - 1) as usize, // original line before split
+                    y: split_y, // original line before split
                 },
                 left,
                 right,
@@ -641,145 +1301,134 @@ impl Editor {
         }
     }
 
+    ///deletes the selected text and splits the line where it was, as one undo step
+    pub(crate) fn enter_text_is_selected(&mut self) -> Result<(), EditorError> {
+        self.replace_selected_text("\n")?;
+        self.clear_selection();
+        Ok(())
+    }
+
     //editor backspace
     ///handles backspace in editor, removes char at y line x position and sets new cursor position
-    pub fn backspace(&mut self) {
+    pub(crate) fn backspace(&mut self) {
         let mut deleted_char: Option<char> = None;
-        let line_char_count = self.editor_content[self.cursor.y as usize].chars().count() as i16;
+        let line_char_count = line_index::char_count(&self.editor_content[self.cursor.y]);
         //if x is more than 0 and less than max line index : should delete char and move back
         // else if y is more than 0, move line up
         if self.cursor.x > 0 && self.cursor.x <= line_char_count {
-            let line = &mut self.editor_content[self.cursor.y as usize];
-            let mut line_chars_vec: Vec<char> = line.chars().collect();
-            let char = line_chars_vec.remove(self.cursor.x as usize - 1);
-            deleted_char = Some(char);
-
-            *line = line_chars_vec.into_iter().collect();
-            //line.remove(self.editor.cursor.x as usize -1);
+            let end = Position::from(self.cursor);
+            let start = Position::new(end.line, end.col - 1);
+            deleted_char = self
+                .editor_content
+                .delete_range(Range::new(start, end))
+                .ok()
+                .and_then(|text| text.chars().next());
             self.move_cursor(-1, 0);
         } else if self.cursor.y > 0 {
-            let line = &mut self.editor_content.remove(self.cursor.y as usize);
-            let new_x_value = self.editor_content[(self.cursor.y - 1) as usize]
-                .chars()
-                .count() as i16;
+            //recorded in bytes, like the split undoing it takes it
+            let join_x = self.editor_content[self.cursor.y - 1].len();
+            let Ok(new_x_value) = self.editor_content.merge_lines(self.cursor.y - 1) else {
+                return;
+            };
             self.cursor.y -= 1;
             self.cursor.x = new_x_value;
-            self.editor_content[self.cursor.y as usize].push_str(line);
+            let merged = self.editor_content[self.cursor.y].clone();
+            self.record_edit(EditAction::JoinLine {
+                pos: CursorPosition {
+                    x: join_x,
+                    y: self.cursor.y,
+                },
+                merged,
+            });
         }
 
         if let Some(char) = deleted_char {
-            self.undo_redo_manager.record_undo(EditAction::Delete {
+            self.record_edit(EditAction::Delete {
                 pos: CursorPosition {
-                    x: self.cursor.x as usize,
-                    y: self.cursor.y as usize,
+                    x: self.cursor.x,
+                    y: self.cursor.y,
                 },
-                deleted_char: char.clone(),
+                deleted_char: char,
             });
         }
     }
 
-    ///handles backspace in editor, removes char at y line x position and sets new cursor position
-    pub fn backspace_text_is_selected(&mut self) {
-        let start = self.text_selection_start.unwrap();
-        let end = self.text_selection_end.unwrap();
-        let lines = &mut self.editor_content[start.y..=end.y];
-        let lines_length = lines.len();
-        if lines_length > 1 {
-            let mut line_indexes_to_remove: Vec<u16> = vec![];
-            for (y, line) in lines.iter_mut().enumerate() {
-                let mut line_chars_vec: Vec<char> = line.chars().collect();
-                //first line
-                if y == 0 {
-                    line_chars_vec.drain(start.x..line.chars().count());
-                } else if y == lines_length - 1 {
-                    //last line selected
-                    line_chars_vec.drain(0..end.x);
-                } else {
-                    line_chars_vec.drain(0..line.chars().count());
-                    line_indexes_to_remove.push((start.y + y) as u16);
-                }
-                *line = line_chars_vec.into_iter().collect();
-            }
-            // remove the lines that became empty in reverse order
-            for &i in line_indexes_to_remove.iter().rev() {
-                self.editor_content.remove(i as usize);
-            }
-            //move content of last line selected to first line start point
-            let line = &mut self
-                .editor_content
-                .remove(end.y - line_indexes_to_remove.len());
-            self.editor_content[start.y].push_str(line);
-        } else {
-            let line = &mut self.editor_content[start.y];
-            let mut line_chars_vec: Vec<char> = line.chars().collect();
-            line_chars_vec.drain(start.x..end.x);
-            *line = line_chars_vec.into_iter().collect();
-        }
-        self.cursor.x = self.text_selection_start.unwrap().x as i16;
-        self.cursor.y = self.text_selection_start.unwrap().y as i16;
-        self.text_selection_start = None;
-        self.text_selection_end = None;
-        //replace visual cursor
-        self.visual_cursor_x = self.calculate_visual_x() as i16;
+    ///removes the selected text as one undo step, leaving the cursor where it started
+    pub(crate) fn backspace_text_is_selected(&mut self) {
+        if self.replace_selected_text("").is_ok() {
+            self.clear_selection();
+        }
     }
 
     //editor delete functions
 
     ///handles DELETE action, of deleting char in editor at x +1 position
     pub(crate) fn delete(&mut self) {
-        let current_line_len = self.editor_content[self.cursor.y as usize].chars().count() as i16;
+        let current_line_len = self.editor_content[self.cursor.y].chars().count();
 
         if current_line_len == 0 {
             return;
         }
         //if at line end, move line below up,  else if current line length is bigger than current cursor x pos, remove char
-        if self.cursor.x >= current_line_len - 1
-            && self.editor_content.len() > (self.cursor.y + 1) as usize
+        if self.cursor.x >= current_line_len - 1 && self.editor_content.len() > (self.cursor.y + 1)
         {
-            let line = &mut self.editor_content.remove((self.cursor.y + 1) as usize);
-            self.editor_content[self.cursor.y as usize].push_str(line);
-            self.undo_redo_manager.record_undo(EditAction::DeleteLines {
-                start: CursorPosition {
-                    x: self.cursor.x as usize,
-                    y: self.cursor.y as usize,
+            let join_x = self.editor_content[self.cursor.y].len();
+            if self.editor_content.merge_lines(self.cursor.y).is_err() {
+                return;
+            }
+            let merged = self.editor_content[self.cursor.y].clone();
+            self.record_edit(EditAction::JoinLine {
+                pos: CursorPosition {
+                    x: join_x,
+                    y: self.cursor.y,
                 },
-                deleted: vec![(*line).parse().unwrap()],
+                merged,
             });
         } else if current_line_len > (self.cursor.x + 1) {
-            let line = &mut self.editor_content[self.cursor.y as usize];
-            let mut line_chars_vec: Vec<char> = line.chars().collect();
-            let char = line_chars_vec.remove(self.cursor.x as usize + 1);
+            let start = Position::new(self.cursor.y, self.cursor.x + 1);
+            let end = Position::new(start.line, start.col + 1);
+            let Some(char) = self
+                .editor_content
+                .delete_range(Range::new(start, end))
+                .ok()
+                .and_then(|text| text.chars().next())
+            else {
+                return;
+            };
 
-            self.undo_redo_manager.record_undo(EditAction::Delete {
+            self.record_edit(EditAction::Delete {
                 pos: CursorPosition {
-                    x: self.cursor.x as usize + 1,
-                    y: self.cursor.y as usize,
+                    x: self.cursor.x + 1,
+                    y: self.cursor.y,
                 },
-                deleted_char: char.clone(),
+                deleted_char: char,
             });
-
-            *line = line_chars_vec.into_iter().collect();
-            //line.remove((self.editor.cursor.x+1) as usize);
         }
     }
 
     ///handles delete in editor, removes char at y line x position and sets new cursor position
-    pub fn delete_text_is_selected(&mut self) {
-        let start = self.text_selection_start.unwrap();
-        let end = self.text_selection_end.unwrap();
-        let lines = &mut self.editor_content[start.y..=end.y];
+    pub(crate) fn delete_text_is_selected(&mut self) {
+        let Some((start, end)) = self.selection_bounds() else {
+            return;
+        };
+        if self.check_position_in_bounds(start).is_err()
+            || self.check_position_in_bounds(end).is_err()
+        {
+            return;
+        }
+        let mut lines = self.editor_content[start.line..=end.line].to_vec();
         let lines_length = lines.len();
         if lines_length > 1 {
             for (y, line) in lines.iter_mut().enumerate() {
                 let mut line_chars_vec: Vec<char> = line.chars().collect();
                 //first line
                 if y == 0 {
-                    line_chars_vec.drain(start.x..line.chars().count());
+                    line_chars_vec.drain(start.col..line.chars().count());
                 } else if y == lines_length - 1 {
                     //last line selected
-                    //line_chars_vec.drain(0..end.x);   this takes away the chars
+                    //line_chars_vec.drain(0..end.col);   this takes away the chars
                     //this solution replaces with whitespace
-                    for i in 0..end.x.min(line_chars_vec.len()) {
+                    for i in 0..end.col.min(line_chars_vec.len()) {
                         line_chars_vec[i] = ' ';
                     }
                 } else {
@@ -789,178 +1438,920 @@ impl Editor {
                 *line = line_chars_vec.into_iter().collect();
             }
         } else {
-            let line = &mut self.editor_content[start.y];
-            let mut line_chars_vec: Vec<char> = line.chars().collect();
-            line_chars_vec[start.x..end.x].fill(' ');
-            //line_chars_vec.drain(start.x..end.x);
-            *line = line_chars_vec.into_iter().collect();
-        }
-        self.cursor.x = self.text_selection_end.unwrap().x as i16;
-        self.cursor.y = self.text_selection_end.unwrap().y as i16;
-        self.text_selection_start = None;
-        self.text_selection_end = None;
+            let mut line_chars_vec: Vec<char> = lines[0].chars().collect();
+            line_chars_vec[start.col..end.col].fill(' ');
+            //line_chars_vec.drain(start.col..end.col);
+            lines[0] = line_chars_vec.into_iter().collect();
+        }
+        //replacing the lines records them, so the blanked text comes back on undo
+        self.replace_lines(start.line..end.line + 1, lines);
+        self.cursor = Cursor::from(end);
+        self.selection = None;
         //replace visual cursor
-        self.visual_cursor_x = self.calculate_visual_x() as i16;
+        self.visual_cursor_x = self.calculate_visual_x();
     }
 
     //editor cursor moving
 
     ///moves the cursor in relation to editor content
-    pub fn move_cursor(&mut self, x: i16, y: i16) {
+    pub fn move_cursor(&mut self, x: isize, y: isize) {
         if self.cursor.y == 0 && y == -1 {
             return;
         }
+        let target_y = self.cursor.y.saturating_add_signed(y);
         //if wanting to go beyond current length of editor
-        while self.editor_content.len() <= (self.cursor.y + y) as usize {
-            self.editor_content.push(String::new());
-        }
+        self.editor_content.ensure_lines(target_y + 1);
 
-        let max_x_pos = self.editor_content[(self.cursor.y + y) as usize]
-            .chars()
-            .count() as i16;
-        //let current_line = &self.editor.editor_content[self.editor.cursor.y as usize];
+        let max_x_pos = line_index::char_count(&self.editor_content[target_y]);
+        //let current_line = &self.editor.editor_content[self.editor.cursor.y];
 
         // Moving Right →
         if x > 0 && self.cursor.x < max_x_pos {
-            self.cursor.x += x;
+            self.cursor.x += x.unsigned_abs();
         } else if x == 1
-            && self.cursor.x >= self.editor_content[self.cursor.y as usize].chars().count() as i16
-            && self.editor_content.len() > self.cursor.y as usize + 1
+            && self.cursor.x >= line_index::char_count(&self.editor_content[self.cursor.y])
+            && self.editor_content.len() > self.cursor.y + 1
         {
             //else if end of line and more lines
             self.cursor.y += 1;
             self.cursor.x = 0;
-            self.visual_cursor_x = self.calculate_visual_x() as i16;
+            self.visual_cursor_x = self.calculate_visual_x();
+            self.scroll_to_cursor();
             return;
         }
 
         // Moving Left ←
         if x < 0 && self.cursor.x > 0 {
-            self.cursor.x += x;
+            self.cursor.x = self.cursor.x.saturating_add_signed(x);
         } else if self.cursor.x == 0 && x == -1 && self.cursor.y != 0 {
             //else if start of line and more lines
             self.cursor.y -= 1;
-            self.cursor.x = self.editor_content[self.cursor.y as usize].chars().count() as i16;
-            self.visual_cursor_x = self.calculate_visual_x() as i16;
+            self.cursor.x = line_index::char_count(&self.editor_content[self.cursor.y]);
+            self.visual_cursor_x = self.calculate_visual_x();
+            self.scroll_to_cursor();
             return;
         }
 
-        let (top, bottom) = self.is_cursor_top_or_bottom_of_editor();
-        //to offset scroll
-        if (y == 1 && bottom) || (y == -1 && top) {
-            self.scroll_offset = (self.scroll_offset + y).clamp(0, i16::MAX);
-            return;
-        }
-
-        self.cursor.x = self.cursor.x.clamp(0, max_x_pos);
-        self.cursor.y = (self.cursor.y + y).clamp(0, i16::MAX);
-        self.visual_cursor_x = self.calculate_visual_x() as i16;
+        self.cursor.x = self.cursor.x.min(max_x_pos);
+        self.cursor.y = target_y;
+        self.visual_cursor_x = self.calculate_visual_x();
+        //scroll when moving past the top or bottom row
+        self.scroll_to_cursor();
     }
 
     ///moves selection cursor
-    pub(crate) fn move_selection_cursor(&mut self, x: i16, y: i16) {
-        let old_x = self.cursor.x;
-        let old_y = self.cursor.y;
+    pub(crate) fn move_selection_cursor(&mut self, x: isize, y: isize) {
+        let old_pos = self.cursor_position();
         self.move_cursor(x, y);
-        let new_x = self.cursor.x;
-        let new_y = self.cursor.y;
+        if x != 0 || y != 0 {
+            self.extend_selection(old_pos, x > 0 || y > 0);
+        }
+    }
 
-        let new_pos = CursorPosition {
-            x: new_x as usize,
-            y: new_y as usize,
+    ///moves selection cursor with a motion, like to line end or by a word
+    pub(crate) fn move_selection_cursor_to(&mut self, motion: Motion) {
+        let old_pos = self.cursor_position();
+        self.move_cursor_to(motion);
+        let new_pos = self.cursor_position();
+        let forward = (new_pos.y, new_pos.x) > (old_pos.y, old_pos.x);
+        self.extend_selection(old_pos, forward);
+    }
+
+    ///moves the selection endpoint the cursor was on from old_pos to the cursor,
+    /// the other endpoint stays as anchor
+    fn extend_selection(&mut self, old_pos: CursorPosition, forward: bool) {
+        let old_pos = Position::from(old_pos);
+        let new_pos = Position::from(self.cursor);
+        //the first move starts an empty selection at where the cursor was
+        let mut range = self.selection.unwrap_or(Range::new(old_pos, old_pos));
+
+        let at_start = range.start == old_pos;
+        let at_end = range.end == old_pos;
+        if at_start && !at_end {
+            //is at start pos and should move start instead of end
+            range.start = new_pos;
+        } else if at_end && !at_start {
+            //is at end pos and should move end instead of start
+            range.end = new_pos;
+        } else if forward {
+            //is at both start and end, moving right/down should move end
+            range.end = new_pos;
+        } else {
+            //is at both start and end, moving left/up should move start
+            range.start = new_pos;
+        }
+
+        //a jump across the anchor leaves start after end, turn it so start stays first
+        self.selection = Some(range.normalized());
+    }
+
+    ///moves the cursor to the end of the selection it isn't on, the selection stays. the
+    /// next selection move then extends or shrinks it from that side.
+    pub fn swap_selection_ends(&mut self) {
+        let Some((start, end)) = self.selection_bounds() else {
+            return;
+        };
+        let other = if Position::from(self.cursor) == start {
+            end
+        } else {
+            start
         };
-        let old_pos = CursorPosition {
-            x: old_x as usize,
-            y: old_y as usize,
+        self.set_cursor_position(&other.into());
+        self.scroll_to_cursor();
+    }
+
+    ///drops the current text selection, leaving the cursor where it is
+    pub fn clear_selection(&mut self) {
+        self.selection = None;
+    }
+
+    //MOTIONS
+    ///moves the cursor with a motion that jumps further than a single step
+    pub fn move_cursor_to(&mut self, motion: Motion) {
+        self.editor_content.ensure_lines(1);
+        let y = self.cursor.y.min(self.editor_content.len() - 1);
+        let chars: Vec<char> = self.editor_content[y].chars().collect();
+        let x = self.cursor.x.min(chars.len());
+
+        let target = match motion {
+            Motion::LineStart => CursorPosition { x: 0, y },
+            Motion::LineEnd => CursorPosition { x: chars.len(), y },
+            Motion::DocumentStart => CursorPosition { x: 0, y: 0 },
+            Motion::DocumentEnd => {
+                let last = self.editor_content.len() - 1;
+                CursorPosition {
+                    x: self.editor_content[last].chars().count(),
+                    y: last,
+                }
+            }
+            Motion::WordLeft if x == 0 && y > 0 => CursorPosition {
+                x: self.editor_content[y - 1].chars().count(),
+                y: y - 1,
+            },
+            Motion::WordLeft => CursorPosition {
+                x: previous_word_boundary(&chars, x, &self.word_chars),
+                y,
+            },
+            Motion::WordRight if x == chars.len() && y + 1 < self.editor_content.len() => {
+                CursorPosition { x: 0, y: y + 1 }
+            }
+            Motion::WordRight => CursorPosition {
+                x: next_word_boundary(&chars, x, &self.word_chars),
+                y,
+            },
+            Motion::ParagraphUp => previous_paragraph_boundary(&self.editor_content, y),
+            Motion::ParagraphDown => next_paragraph_boundary(&self.editor_content, y),
         };
 
-        if self.text_selection_start.is_none() {
-            // Initialize selection start on first move
-            self.text_selection_start = Some(old_pos);
+        self.set_cursor_position(&target);
+        self.scroll_to_cursor();
+    }
+
+    ///adjusts the scroll offset so the cursor line is inside the editor viewport
+    pub(crate) fn scroll_to_cursor(&mut self) {
+        let height = (self.editor_height as usize).max(1);
+        if self.cursor.y < self.scroll_offset {
+            self.scroll_offset = self.cursor.y;
+        } else if self.cursor.y >= self.scroll_offset + height {
+            self.scroll_offset = self.cursor.y - height + 1;
         }
+    }
+
+    ///scrolls so the cursor line sits at the top, middle or bottom of the viewport. the
+    /// offset stays within the content, so lines near its ends can't be moved all the way.
+    pub fn align_view(&mut self, align: ViewAlign) {
+        let height = (self.editor_height as usize).max(1);
+        let y = self.cursor.y;
+        let offset = match align {
+            ViewAlign::Top => y,
+            ViewAlign::Center => y.saturating_sub((height - 1) / 2),
+            ViewAlign::Bottom => (y + 1).saturating_sub(height),
+        };
+        let last_offset = self.editor_content.len().saturating_sub(height);
+        self.scroll_offset = offset.min(last_offset);
+    }
 
-        if self.text_selection_end.is_none() {
-            // Initialize selection end on first move
-            self.text_selection_end = Some(old_pos);
+    //TEXT OBJECTS
+    ///selects the inside of the innermost bracket or quote pair around the selection or
+    /// cursor, repeated calls include the delimiters and then the next enclosing pair
+    pub fn select_enclosing(&mut self) {
+        let (start, end) = match self.selection_bounds() {
+            Some((start, end)) => (start.into(), end.into()),
+            None => (self.cursor_position(), self.cursor_position()),
+        };
+        if let Some((start, end)) =
+            text_objects::expand_to_enclosing(&self.editor_content, start, end)
+        {
+            self.selection = Some(Range::new(start.into(), end.into()));
+            self.set_cursor_position(&end);
+            self.scroll_to_cursor();
         }
+    }
 
-        let (at_start, at_end) = self.is_selection_cursor_start_or_end(old_pos);
+    //MARKS
+    ///stores the cursor position under name
+    pub fn set_mark(&mut self, name: char) {
+        let pos = self.cursor_position();
+        self.marks.set(name, pos);
+    }
 
-        if x > 0 || y > 0 {
-            // Moving right/down → Extend selection
+    ///moves the cursor to the mark with name, scrolling it into view
+    pub fn jump_to_mark(&mut self, name: char) -> Result<(), EditorError> {
+        let mark = self.marks.get(name).ok_or(EditorError::UnknownMark(name))?;
+        self.set_cursor_position(&mark);
+        self.clear_selection();
+        self.scroll_to_cursor();
+        Ok(())
+    }
 
-            if at_start && !at_end {
-                //is at start pos and should move start instead of end
-                self.text_selection_start = Some(new_pos);
-            } else if at_end && !at_start {
-                //is at end pos and should move end instead of start
-                self.text_selection_end = Some(new_pos);
-            } else {
-                //is at both start and end, should move end
-                self.text_selection_end = Some(new_pos);
+    ///moves the cursor to the first non-blank char of line y, clamped to the content, and
+    /// centers the view on it
+    pub fn goto_line(&mut self, y: usize) {
+        let y = y.min(self.editor_content.len().saturating_sub(1));
+        let x = self.editor_content.get(y).map_or(0, |line| {
+            line.chars().take_while(|c| c.is_whitespace()).count()
+        });
+        self.set_cursor_position(&CursorPosition { x, y });
+        self.clear_selection();
+        self.align_view(ViewAlign::Center);
+    }
+
+    //INVARIANTS
+    ///the cursor and selection endpoints that are outside the content, described
+    pub(crate) fn invariant_violations(&self) -> Vec<String> {
+        let mut positions = vec![("cursor", Position::from(self.cursor))];
+        if let Some(range) = self.selection {
+            positions.push(("selection start", range.start));
+            positions.push(("selection end", range.end));
+        }
+        positions
+            .into_iter()
+            .filter(|&(_, pos)| !invariants::in_bounds(&self.editor_content, pos))
+            .map(|(name, pos)| {
+                format!(
+                    "{} at ({}, {}) with {} lines",
+                    name,
+                    pos.col,
+                    pos.line,
+                    self.editor_content.len()
+                )
+            })
+            .collect()
+    }
+
+    ///checks the cursor and the selection are inside the content after operation. debug
+    /// builds panic on a broken state, release builds with the validate feature clamp it
+    /// back and note the repair for the app to warn about
+    pub(crate) fn check_invariants(&mut self, operation: &dyn Debug) {
+        if !invariants::ENABLED {
+            return;
+        }
+        let violations = self.invariant_violations();
+        if violations.is_empty() {
+            return;
+        }
+        let report = format!("after {:?}: {}", operation, violations.join(", "));
+        if cfg!(debug_assertions) {
+            panic!("editor invariant broken {}", report);
+        }
+        self.clamp_positions();
+        self.repairs.push(report);
+    }
+
+    ///moves the cursor and the selection endpoints back inside the content
+    pub(crate) fn clamp_positions(&mut self) {
+        let cursor = invariants::clamp(&self.editor_content, self.cursor.into());
+        self.set_cursor_position(&cursor.into());
+        if let Some(range) = self.selection {
+            self.selection = Some(Range::new(
+                invariants::clamp(&self.editor_content, range.start),
+                invariants::clamp(&self.editor_content, range.end),
+            ));
+        }
+    }
+
+    ///broken states clamped back since the last call, described
+    pub fn take_repairs(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.repairs)
+    }
+
+    //BOOKMARKS
+    ///bookmarks the cursor line, or removes its bookmark
+    pub fn toggle_bookmark(&mut self) {
+        self.bookmarks.toggle(self.cursor.y);
+    }
+
+    ///moves the cursor to the start of the next bookmarked line, or the previous one going
+    /// back, wrapping around at the ends of the buffer
+    pub fn jump_to_bookmark(&mut self, forward: bool) -> Result<(), EditorError> {
+        let y = self
+            .bookmarks
+            .next(self.cursor.y, forward)
+            .ok_or(EditorError::NoBookmarks)?;
+        self.set_cursor_position(&CursorPosition::new(0, y));
+        self.clear_selection();
+        self.scroll_to_cursor();
+        Ok(())
+    }
+
+    //WORD COMPLETION
+    pub fn is_completing(&self) -> bool {
+        self.completion.is_some()
+    }
+
+    ///routes actions while a completion is active or starts one,
+    /// returns true if the action was consumed. other actions accept the completion.
+    fn handle_completion_action(&mut self, action: &InputAction) -> bool {
+        match (self.is_completing(), action) {
+            (false, InputAction::CompleteNext) => self.start_completion(true),
+            (false, InputAction::CompletePrevious) => self.start_completion(false),
+            (false, _) => return false,
+            (true, InputAction::CompleteNext) => self.cycle_completion(true),
+            (true, InputAction::CompletePrevious) => self.cycle_completion(false),
+            (true, InputAction::CancelCompletion) => self.cancel_completion(),
+            //enter only accepts, instead of also breaking the line
+            (true, InputAction::ENTER) => self.accept_completion(),
+            (true, _) => {
+                self.accept_completion();
+                return false;
             }
-        } else if x < 0 || y < 0 {
-            // Moving left/up → Adjust start instead of resetting
-            if at_start && !at_end {
-                //is at start pos and should move start instead of end
-                self.text_selection_start = Some(new_pos);
-            } else if at_end && !at_start {
-                //is at end pos and should move end instead of start
-                self.text_selection_end = Some(new_pos);
-            } else {
-                //is at both start and end, should move start
-                self.text_selection_start = Some(new_pos);
+        }
+        true
+    }
+
+    ///changes which chars make up words for word motions and completion
+    pub fn set_word_chars(&mut self, word_chars: WordChars) {
+        if self.word_chars != word_chars {
+            self.word_chars = word_chars;
+            //the cached words were split under the old chars
+            self.word_index = WordIndex::new();
+        }
+    }
+
+    ///completes the partial word before the cursor with the first or last matching
+    /// word of the buffer
+    pub fn start_completion(&mut self, forward: bool) {
+        let y = self.cursor.y;
+        let Some(line) = self.editor_content.get(y) else {
+            return;
+        };
+        let x = (self.cursor.x).min(line.chars().count());
+        let prefix = completion::prefix_before(line, x, &self.word_chars);
+        if prefix.is_empty() {
+            return;
+        }
+
+        let words = self
+            .word_index
+            .words(self.revision, &self.editor_content, &self.word_chars);
+        self.completion = CompletionSession::new(CursorPosition { x, y }, &prefix, words);
+        self.clear_selection();
+        self.cycle_completion(forward);
+    }
+
+    ///shows the next or previous candidate in place of the current one
+    pub fn cycle_completion(&mut self, forward: bool) {
+        let Some(session) = self.completion.as_mut() else {
+            return;
+        };
+        let shown_len = session.current().chars().count();
+        session.cycle(forward);
+        let pos = session.pos;
+        let shown = session.current().to_string();
+        self.replace_completion_text(pos, shown_len, &shown);
+    }
+
+    ///keeps the shown candidate as a single undo step
+    pub fn accept_completion(&mut self) {
+        let Some(session) = self.completion.take() else {
+            return;
+        };
+        let pos = session.pos;
+        //the text is already in the buffer, only the undo history is missing it
+        self.undo_group(|editor| {
+            for (i, c) in session.current().chars().enumerate() {
+                editor.record_edit(EditAction::Insert {
+                    pos: CursorPosition {
+                        x: pos.x + i,
+                        y: pos.y,
+                    },
+                    c,
+                });
             }
+        });
+        self.revision += 1;
+    }
+
+    ///removes the shown candidate, restoring the typed partial word
+    pub fn cancel_completion(&mut self) {
+        let Some(session) = self.completion.take() else {
+            return;
+        };
+        let shown_len = session.current().chars().count();
+        self.replace_completion_text(session.pos, shown_len, "");
+    }
+
+    ///replaces len chars at pos with text, placing the cursor after it
+    fn replace_completion_text(&mut self, pos: CursorPosition, len: usize, text: &str) {
+        let start = Position::from(pos);
+        let end = Position::new(start.line, start.col + len);
+        //the session's text is still at pos, so the range is in the line
+        let _ = self.editor_content.delete_range(Range::new(start, end));
+        let _ = self.editor_content.insert_str(start, text);
+        //the shown candidate isn't an edit until it is accepted
+        self.stats_cache.line_changed(pos.y);
+
+        let end = CursorPosition {
+            x: pos.x + text.chars().count(),
+            y: pos.y,
+        };
+        self.set_cursor_position(&end);
+    }
+
+    //LINE TRANSFORMS
+    ///lines covered by the selection, widened to whole lines, or all lines without one.
+    /// a selection ending at the start of a line doesn't include that line.
+    pub fn selected_line_range(&self) -> std::ops::Range<usize> {
+        match self.selection_bounds() {
+            Some((start, end)) => {
+                let last = if end.col == 0 && end.line > start.line {
+                    end.line - 1
+                } else {
+                    end.line
+                };
+                start.line..(last + 1).min(self.editor_content.len())
+            }
+            _ => 0..self.editor_content.len(),
         }
     }
 
-    fn is_selection_cursor_start_or_end(&self, current_pos: CursorPosition) -> (bool, bool) {
-        let start = current_pos.x == self.text_selection_start.unwrap().x
-            && current_pos.y == self.text_selection_start.unwrap().y;
-        let end = current_pos.x == self.text_selection_end.unwrap().x
-            && current_pos.y == self.text_selection_end.unwrap().y;
-        (start, end)
+    ///replaces the selected lines with the result of transform as one undo step,
+    /// an active selection is widened to cover the resulting lines
+    pub fn transform_lines(&mut self, transform: impl FnOnce(Vec<String>) -> Vec<String>) {
+        self.transform_line_range(self.selected_line_range(), transform);
     }
 
-    //SCROLL
-    ///moves the scroll offset
-    pub(crate) fn move_scroll_offset(&mut self, offset: i16) {
-        let (top, bottom) = self.is_cursor_top_or_bottom_of_editor();
+    ///replaces the lines in range with the result of transform as one undo step. an active
+    /// selection is widened to cover the resulting lines, else the cursor is kept inside them.
+    pub fn transform_line_range(
+        &mut self,
+        range: std::ops::Range<usize>,
+        transform: impl FnOnce(Vec<String>) -> Vec<String>,
+    ) {
+        if range.is_empty() {
+            return;
+        }
+        let new = transform(self.editor_content[range.clone()].to_vec());
+        let start = CursorPosition {
+            x: 0,
+            y: range.start,
+        };
+        let new_len = new.len();
+        self.replace_lines(range.clone(), new);
+
+        if self.is_text_selected() && new_len > 0 {
+            let last_y = range.start + new_len - 1;
+            let end = CursorPosition {
+                x: self.editor_content[last_y].chars().count(),
+                y: last_y,
+            };
+            self.selection = Some(Range::new(start.into(), end.into()));
+            self.set_cursor_position(&end);
+        } else {
+            self.clear_selection();
+            let mut cursor = self.cursor_position();
+            cursor.y = cursor
+                .y
+                .clamp(range.start, range.start + new_len.max(1) - 1);
+            self.set_cursor_position(&cursor);
+        }
+        self.scroll_to_cursor();
+    }
 
-        //if on way down and at bottom, move scroll
-        if (offset == 1 && bottom) || (offset == -1 && top) {
-            self.scroll_offset = (self.scroll_offset + offset).clamp(0, i16::MAX);
+    ///replaces the selected part of each selected line with the result of transform as one
+    /// undo step, the text before and after the selection is kept. the selection stays, its
+    /// end moving as the length of its last line changes.
+    pub fn transform_selected_spans(
+        &mut self,
+        mut transform: impl FnMut(&str) -> String,
+    ) -> Result<(), EditorError> {
+        let Some((start, end)) = self.selection_bounds() else {
+            return Err(EditorError::NoSelection);
+        };
+        self.check_position_in_bounds(start)?;
+        self.check_position_in_bounds(end)?;
+
+        let mut end_col = end.col;
+        let lines: Vec<String> = (start.line..=end.line)
+            .map(|y| {
+                let line = &self.editor_content[y];
+                let from = if y == start.line { start.col } else { 0 };
+                let to = if y == end.line {
+                    end.col
+                } else {
+                    line_index::char_count(line)
+                };
+                let (from, to) = (
+                    line_index::byte_index(line, from),
+                    line_index::byte_index(line, to),
+                );
+                let span = transform(&line[from..to]);
+                if y == end.line {
+                    end_col = end_col + line_index::char_count(&span)
+                        - line_index::char_count(&line[from..to]);
+                }
+                format!("{}{}{}", &line[..from], span, &line[to..])
+            })
+            .collect();
+        if lines[..] == self.editor_content[start.line..=end.line] {
+            return Ok(());
+        }
+
+        self.replace_lines(start.line..end.line + 1, lines);
+        let new_end = Position::new(end.line, end_col);
+        self.selection = Some(Range::new(start, new_end));
+        self.set_cursor_position(&new_end.into());
+        self.scroll_to_cursor();
+        Ok(())
+    }
+
+    //LINE INSERT
+    ///starts typing text once for every line of a selection over several lines. it is
+    /// typed on the first line at the left column of the selection and copied to the other
+    /// lines by finish_line_insert, all as one undo step. returns false without such a
+    /// selection.
+    pub fn start_line_insert(&mut self) -> bool {
+        let lines = self.selected_line_range();
+        let Some((start, end)) = self.selection_bounds() else {
+            return false;
+        };
+        if lines.len() < 2 || self.line_insert.is_some() {
+            return false;
+        }
+        let col = start.col.min(end.col);
+        let first_len = self.editor_content[lines.start].chars().count();
+        let at = col.min(first_len);
+        self.clear_selection();
+        self.set_cursor_position(&CursorPosition {
+            x: at,
+            y: lines.start,
+        });
+        //closed by finish_line_insert, the typed chars and the copies undo together
+        self.begin_undo_group();
+        self.line_insert = Some(LineInsert { lines, col, at });
+        true
+    }
+
+    ///checks if text is being typed for every selected line
+    pub fn is_line_inserting(&self) -> bool {
+        self.line_insert.is_some()
+    }
+
+    ///checks if the cursor is past the start of the text typed for every line, where
+    /// backspace may delete it
+    pub fn can_erase_line_insert(&self) -> bool {
+        self.line_insert
+            .as_ref()
+            .is_some_and(|insert| self.cursor.y == insert.lines.start && self.cursor.x > insert.at)
+    }
+
+    ///inserts the text typed on the first line at the same column of every other line,
+    /// lines too short to reach it are left alone. returns the number of lines changed.
+    pub fn finish_line_insert(&mut self) -> usize {
+        let Some(insert) = self.line_insert.take() else {
+            return 0;
+        };
+        let first = &self.editor_content[insert.lines.start];
+        let len = first.chars().count();
+        let at = insert.at.min(len);
+        let end = self.cursor.x.clamp(at, len);
+        let text: String = first.chars().skip(at).take(end - at).collect();
+        let mut changed = 0;
+        if !text.is_empty() && self.cursor.y == insert.lines.start {
+            let rest = insert.lines.start + 1..insert.lines.end.min(self.editor_content.len());
+            let new = self.editor_content[rest.clone()]
+                .iter()
+                .map(|line| {
+                    if line.chars().count() < insert.col {
+                        return line.clone();
+                    }
+                    changed += 1;
+                    let at = line_index::byte_index(line, insert.col);
+                    format!("{}{}{}", &line[..at], text, &line[at..])
+                })
+                .collect();
+            let cursor = self.cursor_position();
+            self.replace_lines(rest, new);
+            self.set_cursor_position(&cursor);
+            changed += 1;
+        }
+        self.end_undo_group();
+        self.scroll_to_cursor();
+        changed
+    }
+
+    ///lines of the paragraph at y, up to the surrounding blank lines. empty on a blank line.
+    pub fn paragraph_range(&self, y: usize) -> std::ops::Range<usize> {
+        let is_text = |y: &usize| !reflow::is_blank(&self.editor_content[*y]);
+        if y >= self.editor_content.len() || !is_text(&y) {
+            return y..y;
+        }
+        let start = (0..y).rev().take_while(is_text).last().unwrap_or(y);
+        let end = (y..self.editor_content.len())
+            .take_while(is_text)
+            .last()
+            .unwrap_or(y);
+        start..end + 1
+    }
+
+    ///replaces the lines in range with new as one undo step
+    fn replace_lines(&mut self, range: std::ops::Range<usize>, new: Vec<String>) {
+        let start = CursorPosition {
+            x: 0,
+            y: range.start,
+        };
+        //the buffer keeps a line, which undo has to know about to take it back out
+        let new = if new.is_empty() && range.len() == self.editor_content.len() {
+            vec![String::new()]
+        } else {
+            new
+        };
+        let Ok(old) = self.editor_content.replace_lines(range, new.clone()) else {
             return;
+        };
+
+        self.record_edit(EditAction::ReplaceLines { start, old, new });
+        self.revision += 1;
+    }
+
+    ///opens an empty line below the cursor line, or above it, indented like the cursor
+    /// line. the cursor moves onto it without splitting its line, as one undo step
+    pub fn open_line(&mut self, below: bool) {
+        self.editor_content.ensure_lines(1);
+        let y = self.cursor.y.min(self.editor_content.len() - 1);
+        let indent: String = self.editor_content[y]
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .collect();
+        let x = indent.chars().count();
+        let y = if below { y + 1 } else { y };
+        self.replace_lines(y..y, vec![indent]);
+        self.clear_selection();
+        self.set_cursor_position(&CursorPosition::new(x, y));
+    }
+
+    ///inserts a copy of the selected text right after it as one undo step, selecting the
+    /// copy so pressing again keeps duplicating
+    pub fn duplicate_selection(&mut self) -> Result<(), EditorError> {
+        let (_, end) = self.selection_bounds().ok_or(EditorError::NoSelection)?;
+        let lines = self.copy_selected_text()?;
+        self.set_cursor_position(&end.into());
+        self.insert_text(&lines);
+        let copy_end = Position::new(self.cursor.y, self.cursor.x);
+        self.selection = Some(Range::new(end, copy_end));
+        Ok(())
+    }
+
+    ///selected text with lines joined by line breaks, None without a selection
+    pub fn selected_text(&mut self) -> Option<String> {
+        if !self.is_text_selected() {
+            return None;
         }
+        self.copy_selected_text().ok().map(|lines| lines.join("\n"))
+    }
 
-        self.move_cursor(0, offset);
+    ///replaces the selected text with text as one undo step, selecting the inserted text
+    pub fn replace_selected_text(&mut self, text: &str) -> Result<(), EditorError> {
+        let Some((start, end)) = self.selection_bounds() else {
+            return Err(EditorError::NoSelection);
+        };
+        self.check_position_in_bounds(start)?;
+        self.check_position_in_bounds(end)?;
+
+        let before: String = self.editor_content[start.line]
+            .chars()
+            .take(start.col)
+            .collect();
+        let after: String = self.editor_content[end.line]
+            .chars()
+            .skip(end.col)
+            .collect();
+        let mut lines: Vec<String> = text.split('\n').map(String::from).collect();
+        let last = lines.len() - 1;
+        let end_x = lines[last].chars().count() + if last == 0 { start.col } else { 0 };
+        lines[0].insert_str(0, &before);
+        lines[last].push_str(&after);
+
+        self.replace_lines(start.line..end.line + 1, lines);
+        let new_end = Position::new(start.line + last, end_x);
+        self.selection = Some(Range::new(start, new_end));
+        self.set_cursor_position(&new_end.into());
+        self.scroll_to_cursor();
+        Ok(())
     }
 
-    ///calculates the visual position of the cursor
-    fn calculate_visual_x(&mut self) -> usize {
-        let line = &self.editor_content[self.cursor.y as usize];
-        let cursor_x = self.cursor.x as usize;
-        let tab_width = editor_settings::TAB_WIDTH as usize;
-        let mut visual_x = 0;
-        for (i, c) in line.chars().enumerate() {
-            if i == cursor_x {
-                break;
+    ///wraps the selection in open and close as one undo step. the wrapped text stays
+    /// selected, the delimiters go right before its start and after its end.
+    pub fn surround_selection(&mut self, open: char, close: char) -> Result<(), EditorError> {
+        let text = self.selected_text().ok_or(EditorError::NoSelection)?;
+        self.replace_selected_text(&format!("{}{}{}", open, text, close))?;
+        if let Some((start, end)) = self.selection_bounds() {
+            let start = Position::new(start.line, start.col + 1);
+            let end = Position::new(end.line, end.col - 1);
+            self.selection = Some(Range::new(start, end));
+            self.set_cursor_position(&end.into());
+        }
+        Ok(())
+    }
+
+    ///removes the delimiter pair right around the selection, or else the one at its first
+    /// and last char, as one undo step. returns false if neither is a pair.
+    pub fn unsurround_selection(&mut self) -> Result<bool, EditorError> {
+        let (start, end) = self.selection_bounds().ok_or(EditorError::NoSelection)?;
+        let char_at = |pos: Position| {
+            self.editor_content
+                .get(pos.line)
+                .and_then(|line| line.chars().nth(pos.col))
+        };
+        let is_pair = |open: Option<char>, close: Option<char>| match (open, close) {
+            (Some(open), Some(close)) => text_objects::surround_pair(open) == (open, close),
+            _ => false,
+        };
+        let before = (start.col > 0).then(|| Position::new(start.line, start.col - 1));
+        let last = (end.col > 0).then(|| Position::new(end.line, end.col - 1));
+        let outer = match before {
+            Some(before) if is_pair(char_at(before), char_at(end)) => {
+                Range::new(before, Position::new(end.line, end.col + 1))
+            }
+            _ if (start.line, start.col + 1) < (end.line, end.col)
+                && is_pair(char_at(start), last.and_then(char_at)) =>
+            {
+                Range::new(start, end)
             }
+            _ => return Ok(false),
+        };
 
-            if c == '\t' {
-                visual_x += tab_width - (visual_x % tab_width);
-            } else {
-                visual_x += 1;
+        self.selection = Some(outer);
+        let text = self.selected_text().ok_or(EditorError::NoSelection)?;
+        let mut inner = text.chars();
+        inner.next();
+        inner.next_back();
+        self.replace_selected_text(inner.as_str())?;
+        Ok(true)
+    }
+
+    //CASE
+    ///converts the selection, or else the name under the cursor, to case as one undo step.
+    /// converted selected text stays selected, a converted name keeps the cursor in it.
+    /// returns false if there is nothing to convert.
+    pub fn convert_case(&mut self, case: Case) -> Result<bool, EditorError> {
+        if let Some(text) = self.selected_text() {
+            let converted = case::convert(&text, case);
+            if converted != text {
+                self.replace_selected_text(&converted)?;
             }
+            return Ok(true);
+        }
+        let (x, y) = (self.cursor.x, self.cursor.y);
+        let Some(name) = self
+            .editor_content
+            .get(y)
+            .and_then(|line| case::name_at(line, x))
+        else {
+            return Ok(false);
+        };
+        self.selection = Some(Range::new(
+            Position::new(y, name.start),
+            Position::new(y, name.end),
+        ));
+        let text = self.selected_text().ok_or(EditorError::NoSelection)?;
+        let converted = case::convert(&text, case);
+        if converted != text {
+            self.replace_selected_text(&converted)?;
         }
+        let end = name.start + converted.chars().count();
+        self.clear_selection();
+        self.set_cursor_position(&CursorPosition { x: x.min(end), y });
+        Ok(true)
+    }
+
+    //NUMBERS
+    ///adds delta to the number at or after the cursor. with a selection over several lines
+    /// the first number of each line changes instead, in sequence mode by delta times the
+    /// position of the line in the selection. returns false if no number was found.
+    pub fn increment_numbers(&mut self, delta: i64, sequence: bool) -> bool {
+        let multi_line = self.is_text_selected() && self.selected_line_range().len() > 1;
+        if !multi_line {
+            let y = self.cursor.y;
+            let Some(line) = self.editor_content.get(y) else {
+                return false;
+            };
+            let Some((new_line, end)) = numbers::change_number(line, self.cursor.x, delta) else {
+                return false;
+            };
+            self.replace_lines(y..y + 1, vec![new_line]);
+            self.clear_selection();
+            self.set_cursor_position(&CursorPosition { x: end, y });
+            return true;
+        }
+
+        let mut changed = false;
+        let mut step = 0;
+        self.transform_lines(|lines| {
+            lines
+                .into_iter()
+                .map(|line| {
+                    step += 1;
+                    let line_delta = if sequence { delta * step } else { delta };
+                    match numbers::change_number(&line, 0, line_delta) {
+                        Some((new_line, _)) => {
+                            changed = true;
+                            new_line
+                        }
+                        None => line,
+                    }
+                })
+                .collect()
+        });
+        changed
+    }
+
+    //STATS
+    ///counts of the whole content
+    pub fn content_stats(&mut self) -> TextStats {
+        self.stats_cache.stats(&self.editor_content)
+    }
+
+    ///the bracket next to the cursor and its partner, for highlighting them while the
+    /// visible lines are shown
+    pub fn bracket_match(&mut self, visible: std::ops::Range<usize>) -> Option<BracketMatch> {
+        self.bracket_cache.get(
+            self.revision,
+            &self.editor_content,
+            self.cursor_position(),
+            visible,
+            editor_settings::BRACKET_SCAN_LIMIT,
+        )
+    }
+
+    ///counts of the selected text, None without a selection
+    pub fn selection_stats(&mut self) -> Option<TextStats> {
+        if !self.is_text_selected() {
+            return None;
+        }
+        let selected = self.copy_selected_text().ok()?;
+        Some(TextStats::of_lines(&selected))
+    }
+
+    ///lines and chars of the selection, counted from its bounds without copying the text.
+    /// line breaks count as a char each, like in the stats.
+    pub fn selection_size(&self) -> Option<(usize, usize)> {
+        let (start, end) = self.selection_bounds()?;
+        let line_len = |y: usize| {
+            self.editor_content
+                .get(y)
+                .map_or(0, |line| line_index::char_count(line))
+        };
+        let lines = end.line - start.line + 1;
+        if lines == 1 {
+            return Some((1, end.col.min(line_len(end.line)).saturating_sub(start.col)));
+        }
+        let middle: usize = (start.line + 1..end.line).map(|y| line_len(y) + 1).sum();
+        let first = line_len(start.line).saturating_sub(start.col) + 1;
+        Some((lines, first + middle + end.col.min(line_len(end.line))))
+    }
+
+    //SCROLL
+    ///moves the view by lines, negative up, without moving the cursor. the next action
+    /// moving the cursor or editing scrolls back to it.
+    pub fn scroll_view(&mut self, lines: isize) {
+        let height = (self.editor_height as usize).max(1);
+        let last_offset = self.editor_content.len().saturating_sub(height);
+        self.scroll_offset = self
+            .scroll_offset
+            .saturating_add_signed(lines)
+            .min(last_offset);
+    }
 
-        visual_x
+    ///checks if the cursor line is inside the viewport
+    pub fn is_cursor_in_view(&self) -> bool {
+        let height = (self.editor_height as usize).max(1);
+        (self.scroll_offset..self.scroll_offset + height).contains(&self.cursor.y)
     }
-    ///checks if cursor is at top or bottom of the screen
-    fn is_cursor_top_or_bottom_of_editor(&self) -> (bool, bool) {
-        let top = self.cursor.y == self.scroll_offset;
-        let bottom = self.cursor.y == self.scroll_offset + (self.editor_height as i16);
-        (top, bottom)
+
+    ///calculates the visual position of the cursor
+    fn calculate_visual_x(&mut self) -> usize {
+        //content emptied by a cut has no line to measure
+        let Some(line) = self.editor_content.get(self.cursor.y) else {
+            return 0;
+        };
+        self.column_cache.visual_column(
+            self.revision,
+            self.cursor.y,
+            line,
+            self.cursor.x,
+            editor_settings::TAB_WIDTH as usize,
+        )
     }
 }
 
@@ -973,50 +2364,65 @@ impl Editor {
 
 impl Editor {
     ///function to check if some text is selected
-    fn is_text_selected(&self) -> bool {
-        self.text_selection_start.is_some() && self.text_selection_end.is_some()
+    pub(crate) fn is_text_selected(&self) -> bool {
+        self.selection.is_some()
+    }
+
+    ///start and end of the selection, start first
+    fn selection_bounds(&self) -> Option<(Position, Position)> {
+        self.selection
+            .map(Range::normalized)
+            .map(|range| (range.start, range.end))
+    }
+
+    ///checks that pos points at a line and at most one past its last char
+    fn check_position_in_bounds(&self, pos: impl Into<Position>) -> Result<(), EditorError> {
+        self.editor_content.check_position(pos.into())
+    }
+
+    ///returns the cursor as a position in editor content
+    pub(crate) fn cursor_position(&self) -> CursorPosition {
+        CursorPosition {
+            x: self.cursor.x,
+            y: self.cursor.y,
+        }
     }
 
     /// sets cursor position to specified position
     pub(crate) fn set_cursor_position(&mut self, pos: &CursorPosition) {
         //clamp set position to at maximum go to last position available
         //y is len of editor -1
-        self.cursor.y = (pos.y as i16).clamp(0, self.editor_content.len() as i16 - 1);
+        self.cursor.y = pos.y.min(self.editor_content.len().saturating_sub(1));
         //length of cursor.y line , aka nr. of chars on line.
         let line_len = self
             .editor_content
-            .get(self.cursor.y as usize)
-            .map(|s| s.len())
+            .get(self.cursor.y)
+            .map(|s| line_index::char_count(s))
             .unwrap_or(0);
-        self.cursor.x = (pos.x as i16).clamp(0, line_len as i16);
+        self.cursor.x = pos.x.min(line_len);
         //calculate visual x pos again.
-        self.visual_cursor_x = self.calculate_visual_x() as i16;
+        self.visual_cursor_x = self.calculate_visual_x();
     }
 
     /// Insert a character at the specified position (buffer-only: does not touch undo/redo, does not move main cursor)
     pub(crate) fn insert_char_at(&mut self, pos: CursorPosition, c: char) {
         // Ensure the target line exists
-        while self.editor_content.len() <= pos.y {
-            self.editor_content.push(String::new());
-        }
-        let line = &mut self.editor_content[pos.y];
-        let mut chars: Vec<char> = line.chars().collect();
+        self.editor_content.ensure_lines(pos.y + 1);
         // Clamp to actual line length
-        let x = pos.x.min(chars.len());
-        chars.insert(x, c);
-        *line = chars.into_iter().collect();
+        let x = pos
+            .x
+            .min(line_index::char_count(&self.editor_content[pos.y]));
+        //the line exists and x is clamped to it, so this can't fail
+        let _ = self.editor_content.insert_char(Position::new(pos.y, x), c);
         // cursor not updated
     }
 
     /// Delete a character at the specified position (buffer-only)
     pub(crate) fn delete_char_at(&mut self, pos: CursorPosition) {
-        if let Some(line) = self.editor_content.get_mut(pos.y) {
-            let mut chars: Vec<char> = line.chars().collect();
-            if pos.x < chars.len() {
-                chars.remove(pos.x);
-                *line = chars.into_iter().collect();
-            }
-        }
+        let start = Position::from(pos);
+        let end = Position::new(start.line, start.col + 1);
+        //past the end of the line there is nothing to delete, which is left as it is
+        let _ = self.editor_content.delete_range(Range::new(start, end));
     }
 
     /// Replace a text selection (from start to end) with new char
@@ -1027,14 +2433,16 @@ impl Editor {
         new: char,
     ) {
         if start.y == end.y {
-            if let Some(line) = self.editor_content.get_mut(start.y) {
+            if let Some(line) = self.editor_content.get(start.y) {
                 let mut chars: Vec<char> = line.chars().collect();
                 // Clamp positions
                 let start_x = start.x.min(chars.len());
                 let end_x = end.x.min(chars.len());
                 chars.drain(start_x..end_x);
                 chars.insert(start_x, new);
-                *line = chars.into_iter().collect();
+                let _ = self
+                    .editor_content
+                    .set_line(start.y, chars.into_iter().collect());
             }
         }
     }
@@ -1048,11 +2456,13 @@ impl Editor {
     ) {
         if start.y == end.y {
             // Single line selection
-            if let Some(line) = self.editor_content.get_mut(start.y) {
+            if let Some(line) = self.editor_content.get(start.y) {
                 let mut chars: Vec<char> = line.chars().collect();
                 let end_x = end.x.min(chars.len()); // prevent out of bounds
                 chars.splice(start.x..end_x, new_lines.join("\n").chars());
-                *line = chars.into_iter().collect();
+                let _ = self
+                    .editor_content
+                    .set_line(start.y, chars.into_iter().collect());
             }
         } else {
             // Multi-line selection
@@ -1061,33 +2471,27 @@ impl Editor {
             let start_y = start.y.min(max_y);
             let end_y = end.y.min(max_y);
 
-            self.editor_content.drain(start_y..=end_y);
-
-            for (i, line) in new_lines.into_iter().enumerate() {
-                self.editor_content.insert(start_y + i, line);
-            }
+            let _ = self
+                .editor_content
+                .replace_lines(start_y..end_y + 1, new_lines);
         }
     }
 
     ///insert text lines at position without just inserting as lines,
     ///but if necessary, between already present text
-    fn insert_text_at(&mut self, start: &CursorPosition, lines: &Vec<String>) {
+    fn insert_text_at(&mut self, start: &CursorPosition, lines: &[String]) {
         if lines.is_empty() {
             return;
         }
 
         // Ensure there's at least one line at start.y
-        if start.y >= self.editor_content.len() {
-            self.editor_content.resize(start.y + 1, String::new());
-        }
+        self.editor_content.ensure_lines(start.y + 1);
 
         let original_line = self.editor_content[start.y].clone();
 
         // Special case: multi-line insert at column 0
         if lines.len() > 1 && start.x == 0 {
-            for (i, s) in lines.iter().enumerate() {
-                self.editor_content.insert(start.y + i, s.clone());
-            }
+            let _ = self.editor_content.insert_lines(start.y, lines.to_vec());
             return;
         }
 
@@ -1099,20 +2503,24 @@ impl Editor {
 
         if lines.len() == 1 {
             // Single-line insert: left + insert + right
-            self.editor_content[start.y] = format!(
-                "{}{}{}",
-                first_line_left_half, lines[0], first_line_right_half
+            let _ = self.editor_content.set_line(
+                start.y,
+                format!(
+                    "{}{}{}",
+                    first_line_left_half, lines[0], first_line_right_half
+                ),
             );
         } else {
             // Multi-line insert
 
             // Replace current line with left + first inserted line
-            self.editor_content[start.y] = format!("{}{}", first_line_left_half, lines[0]);
+            let _ = self
+                .editor_content
+                .set_line(start.y, format!("{}{}", first_line_left_half, lines[0]));
 
             // Insert middle lines (if any)
-            for (i, middle) in lines.iter().enumerate().skip(1).take(lines.len() - 2) {
-                self.editor_content.insert(start.y + i, middle.clone());
-            }
+            let middle = lines[1..lines.len() - 1].to_vec();
+            let _ = self.editor_content.insert_lines(start.y + 1, middle);
 
             // Replace the last line with last_insert + right_half
             let last_index = start.y + lines.len() - 1;
@@ -1120,58 +2528,38 @@ impl Editor {
             if last_index < self.editor_content.len() {
                 let last_right = self.editor_content[last_index].clone();
                 let last_with_right = format!("{}{}", lines.last().unwrap(), last_right);
-                self.editor_content[last_index] = last_with_right;
+                let _ = self.editor_content.set_line(last_index, last_with_right);
             } else {
                 let last_with_right = format!("{}{}", lines.last().unwrap(), first_line_right_half);
-                self.editor_content.push(last_with_right);
+                let at = self.editor_content.len();
+                let _ = self.editor_content.insert_lines(at, vec![last_with_right]);
             }
         }
     }
 
-    ///delete text lines at start to end,
+    ///delete text lines at start to end, a range from the start of a line to the end of
+    /// another takes its lines along
     fn delete_text_at_range(&mut self, start: &CursorPosition, end: &CursorPosition) {
-        if start.y == end.y {
-            // deleting within a single line
-            if let Some(line) = self.editor_content.get_mut(start.y) {
-                let mut chars: Vec<char> = line.chars().collect();
-                let range_start = start.x.min(chars.len());
-                let range_end = end.x.min(chars.len());
-                if range_start < range_end {
-                    chars.drain(range_start..range_end);
-                    *line = chars.into_iter().collect();
-                }
-            }
-        } else {
-            // deleting across multiple lines
-
-            // get suffix of last line
-            let suffix = if let Some(last_line) = self.editor_content.get(end.y) {
-                last_line.chars().skip(end.x).collect::<String>()
-            } else {
-                String::new()
-            };
-
-            if start.x == 0 {
-                // delete the first line entirely
-                self.editor_content.drain(start.y..=end.y);
-                if !suffix.is_empty() {
-                    self.editor_content.insert(start.y, suffix);
-                }
-            } else {
-                // truncate first line
-                if let Some(first_line) = self.editor_content.get_mut(start.y) {
-                    let mut first_chars: Vec<char> = first_line.chars().collect();
-                    first_chars.truncate(start.x);
-                    *first_line = first_chars.into_iter().collect();
-                    // append suffix from last line
-                    first_line.push_str(&suffix);
-                }
-
-                // remove all lines in between start.y and end.y (excluding start.y)
-                if end.y > start.y {
-                    self.editor_content.drain(start.y + 1..=end.y);
-                }
-            }
+        let takes_lines = start.y < end.y
+            && start.x == 0
+            && self
+                .editor_content
+                .get(end.y)
+                .is_some_and(|line| line_index::char_count(line) <= end.x);
+        if takes_lines {
+            let _ = self.editor_content.remove_lines(start.y..end.y + 1);
+            return;
+        }
+        let clamp = |pos: &CursorPosition| {
+            let line_len = self
+                .editor_content
+                .get(pos.y)
+                .map_or(0, |line| line_index::char_count(line));
+            Position::new(pos.y, pos.x.min(line_len))
+        };
+        let (start, end) = (clamp(start), clamp(end));
+        if start < end {
+            let _ = self.editor_content.delete_range(Range::new(start, end));
         }
     }
 
@@ -1179,30 +2567,134 @@ impl Editor {
     pub(crate) fn insert_lines_at(&mut self, start: CursorPosition, lines: Vec<String>) {
         //calculate start y to insert
         let y = start.y.min(self.editor_content.len());
-        for (i, line) in lines.into_iter().enumerate() {
-            self.editor_content.insert(y + i, line);
-        }
+        let _ = self.editor_content.insert_lines(y, lines);
     }
 
     /// Delete lines starting at a position
     pub(crate) fn delete_lines_at(&mut self, start: CursorPosition, count: usize) {
-        let y = start.y;
-        for _ in 0..count {
-            if y < self.editor_content.len() {
-                self.editor_content.remove(y);
-            } else {
-                break;
+        let len = self.editor_content.len();
+        let y = start.y.min(len);
+        let _ = self.editor_content.remove_lines(y..(y + count).min(len));
+    }
+}
+
+///checks if action can change the editor content
+fn changes_content(action: &InputAction) -> bool {
+    matches!(
+        action,
+        InputAction::WriteChar(_)
+            | InputAction::TAB
+            | InputAction::ENTER
+            | InputAction::BACKSPACE
+            | InputAction::DELETE
+            | InputAction::CUT
+            | InputAction::CutLine
+            | InputAction::PASTE
+            | InputAction::UNDO
+            | InputAction::REDO
+            | InputAction::IncrementNumber(_)
+            | InputAction::OpenLine(_)
+            | InputAction::DuplicateSelection
+            | InputAction::RepeatLastChange
+    )
+}
+
+///checks if line counts as a paragraph boundary
+fn is_blank_line(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+///finds the next blank line below y, a run of blank lines counts as one boundary.
+/// without one the end of the last line is returned.
+fn next_paragraph_boundary(content: &[String], y: usize) -> CursorPosition {
+    let mut i = y;
+    while i < content.len() && is_blank_line(&content[i]) {
+        i += 1;
+    }
+    while i < content.len() && !is_blank_line(&content[i]) {
+        i += 1;
+    }
+    match content.get(i) {
+        Some(_) => CursorPosition { x: 0, y: i },
+        None => {
+            let last = content.len().saturating_sub(1);
+            CursorPosition {
+                x: content.get(last).map(|l| l.chars().count()).unwrap_or(0),
+                y: last,
             }
         }
     }
 }
 
+///finds the previous blank line above y, a run of blank lines counts as one boundary.
+/// without one the start of the first line is returned.
+fn previous_paragraph_boundary(content: &[String], y: usize) -> CursorPosition {
+    let mut i = y.min(content.len().saturating_sub(1));
+    while i > 0 && is_blank_line(&content[i]) {
+        i -= 1;
+    }
+    while i > 0 && !is_blank_line(&content[i]) {
+        i -= 1;
+    }
+    CursorPosition { x: 0, y: i }
+}
+
+///finds the x position of the end of the word at or after x
+fn next_word_boundary(chars: &[char], x: usize, word_chars: &WordChars) -> usize {
+    let mut i = x;
+    while i < chars.len() && chars[i].is_whitespace() {
+        i += 1;
+    }
+    if i < chars.len() && is_word_char(chars[i], word_chars) {
+        while i < chars.len() && is_word_char(chars[i], word_chars) {
+            i += 1;
+        }
+    } else {
+        //a run of punctuation counts as its own word
+        while i < chars.len() && !is_word_char(chars[i], word_chars) && !chars[i].is_whitespace() {
+            i += 1;
+        }
+    }
+    i
+}
+
+///finds the x position of the start of the word at or before x
+fn previous_word_boundary(chars: &[char], x: usize, word_chars: &WordChars) -> usize {
+    let mut i = x;
+    while i > 0 && chars[i - 1].is_whitespace() {
+        i -= 1;
+    }
+    if i > 0 && is_word_char(chars[i - 1], word_chars) {
+        while i > 0 && is_word_char(chars[i - 1], word_chars) {
+            i -= 1;
+        }
+    } else {
+        while i > 0 && !is_word_char(chars[i - 1], word_chars) && !chars[i - 1].is_whitespace() {
+            i -= 1;
+        }
+    }
+    i
+}
+
 impl Default for Editor {
     fn default() -> Self {
         Self::new()
     }
 }
 
+///the lines of the editor joined by line breaks
+impl std::fmt::Display for Editor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, line) in self.editor_content.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+            f.write_str(line)?;
+        }
+        Ok(())
+    }
+}
+
 //████████╗███████╗███████╗████████╗███████╗
 //╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
 //   ██║   █████╗  ███████╗   ██║   ███████╗
@@ -1214,15 +2706,50 @@ mod unit_editor_write_tests {
     use super::super::super::cursor::CursorPosition;
     use super::super::editor::*;
     use crate::config::editor_settings;
+    use crate::input::input_action::Direction;
 
     //init functions
     fn create_editor_with_editor_content(vec: Vec<String>) -> Editor {
         let mut editor = Editor::new();
-        editor.editor_content = vec;
+        editor.editor_content = vec.into();
         editor.editor_height = 10; //since testing doesnt start ui.rs, height isnt set
         editor
     }
 
+    //the counts follow every kind of edit as if the whole content was counted again
+    #[test]
+    fn test_content_stats_follow_edits() {
+        use crate::core::editor::stats::TextStats;
+        let mut editor =
+            create_editor_with_editor_content(vec!["one two".to_string(), "three".to_string()]);
+        let check = |editor: &mut Editor| {
+            let counted = TextStats::of_lines(&editor.editor_content);
+            assert_eq!(editor.content_stats(), counted);
+        };
+        check(&mut editor);
+        editor.cursor.x = 3;
+        editor.enter();
+        check(&mut editor);
+        editor.write_char(' ');
+        editor.write_char('x');
+        check(&mut editor);
+        editor.backspace();
+        editor.set_cursor_position(&CursorPosition { x: 0, y: 1 });
+        editor.backspace();
+        check(&mut editor);
+        editor.cut_line();
+        check(&mut editor);
+        editor.undo().unwrap();
+        editor.undo().unwrap();
+        check(&mut editor);
+        editor.redo().unwrap();
+        check(&mut editor);
+        editor.append_from_file(" four\nfive six", true);
+        check(&mut editor);
+        editor.content_replaced(vec!["seven".to_string()]);
+        check(&mut editor);
+    }
+
     #[test]
     fn test_write_char() {
         let mut editor = Editor::new();
@@ -1243,40 +2770,213 @@ mod unit_editor_write_tests {
     }
 
     #[test]
-    fn test_write_char_special_characters() {
-        let mut editor = Editor::new();
-        editor.write_char('ᚠ');
-        editor.write_char('Ω');
-        editor.write_char('₿');
-        editor.write_char('😎');
-        assert_eq!(editor.editor_content[0], "ᚠΩ₿😎");
-        assert_eq!(editor.cursor.x, 4);
+    fn test_write_char_special_characters() {
+        let mut editor = Editor::new();
+        editor.write_char('ᚠ');
+        editor.write_char('Ω');
+        editor.write_char('₿');
+        editor.write_char('😎');
+        assert_eq!(editor.editor_content[0], "ᚠΩ₿😎");
+        assert_eq!(editor.cursor.x, 4);
+    }
+
+    #[test]
+    fn test_write_char_at_line_10() {
+        let mut editor = Editor::new();
+        editor.cursor.y = 10;
+        editor.write_char('a');
+        assert_eq!(editor.editor_content[10], "a");
+        assert_eq!(editor.cursor.x, 1);
+    }
+
+    #[test]
+    fn test_write_char_at_100_x() {
+        let mut editor = Editor::new();
+        editor.cursor.x = 100;
+        editor.write_char('a');
+        assert_eq!(editor.editor_content[0], "a");
+        assert_eq!(editor.cursor.x, 1);
+    }
+
+    #[test]
+    fn test_write_char_and_backspace_mid_multibyte_line() {
+        let mut editor = Editor::new();
+        editor.editor_content = vec!["æøå".to_string()].into();
+        editor.cursor.x = 2;
+        editor.write_char('x');
+        assert_eq!(editor.editor_content[0], "æøxå");
+        editor.backspace();
+        editor.backspace();
+        assert_eq!(editor.editor_content[0], "æå");
+        assert_eq!(editor.cursor.x, 1);
+    }
+
+    #[test]
+    fn test_cursor_moves_past_old_i16_column_limit() {
+        let mut editor = Editor::new();
+        editor.editor_content = vec!["x".repeat(i16::MAX as usize + 10)].into();
+        editor.cursor.x = i16::MAX as usize - 1;
+        editor.move_cursor(1, 0);
+        editor.move_cursor(1, 0);
+        assert_eq!(editor.cursor.x, i16::MAX as usize + 1);
+        assert_eq!(editor.visual_cursor_x, i16::MAX as usize + 1);
+    }
+
+    #[test]
+    fn test_cursor_reaches_lines_past_old_i16_limit() {
+        let line_count = i16::MAX as usize + 10;
+        let mut editor = Editor::from_text(&"x\n".repeat(line_count));
+        editor.editor_height = 10;
+        editor
+            .set_cursor(CursorPosition::new(0, line_count - 1))
+            .unwrap();
+        editor.write_char('y');
+        assert_eq!(editor.cursor(), CursorPosition::new(1, line_count - 1));
+        assert_eq!(editor.line(line_count - 1), Some("yx"));
+        assert_eq!(editor.scroll_offset(), line_count - 10);
+    }
+
+    #[test]
+    fn test_moving_past_bottom_row_scrolls_one_line() {
+        let mut editor = Editor::from_text(&"x\n".repeat(30));
+        editor.editor_height = 10;
+        for _ in 0..9 {
+            editor.move_cursor(0, 1);
+        }
+        assert_eq!((editor.cursor.y, editor.scroll_offset), (9, 0));
+        editor.move_cursor(0, 1);
+        assert_eq!((editor.cursor.y, editor.scroll_offset), (10, 1));
+    }
+
+    #[test]
+    fn test_scrolling_leaves_cursor_until_it_moves() {
+        let mut editor = Editor::from_text(&"x\n".repeat(99));
+        editor.editor_height = 10;
+        editor.set_cursor(CursorPosition::new(0, 5)).unwrap();
+
+        editor
+            .handle_input_action(InputAction::ScrollHalfPages(3))
+            .unwrap();
+        editor
+            .handle_input_action(InputAction::ScrollLines(2))
+            .unwrap();
+        assert_eq!((editor.cursor.y, editor.scroll_offset), (5, 17));
+        assert!(!editor.is_cursor_in_view());
+        //the view stops with the last line at the bottom
+        editor.scroll_view(1000);
+        assert_eq!(editor.scroll_offset, 90);
+
+        editor
+            .handle_input_action(InputAction::MoveCursor(Direction::Down))
+            .unwrap();
+        assert_eq!((editor.cursor.y, editor.scroll_offset), (6, 6));
+        assert!(editor.is_cursor_in_view());
+
+        editor.scroll_view(-3);
+        assert_eq!(editor.scroll_offset, 3);
+        editor.insert_char('y').unwrap();
+        assert_eq!(editor.scroll_offset, 3);
+        editor.scroll_view(20);
+        editor.insert_char('z').unwrap();
+        assert_eq!((editor.cursor.y, editor.scroll_offset), (6, 6));
+    }
+
+    #[test]
+    fn test_align_view_places_cursor_line_and_clamps_at_ends() {
+        let mut editor = Editor::from_text(&"x\n".repeat(99));
+        editor.editor_height = 10;
+        editor.set_cursor(CursorPosition::new(0, 50)).unwrap();
+
+        editor.align_view(ViewAlign::Center);
+        assert_eq!(editor.scroll_offset(), 46);
+        editor.align_view(ViewAlign::Top);
+        assert_eq!(editor.scroll_offset(), 50);
+        editor.align_view(ViewAlign::Bottom);
+        assert_eq!(editor.scroll_offset(), 41);
+        assert_eq!(editor.cursor(), CursorPosition::new(0, 50));
+
+        //the first and last lines can't leave the viewport edge
+        editor.set_cursor(CursorPosition::new(0, 2)).unwrap();
+        editor.align_view(ViewAlign::Center);
+        assert_eq!(editor.scroll_offset(), 0);
+        editor.set_cursor(CursorPosition::new(0, 97)).unwrap();
+        editor.align_view(ViewAlign::Top);
+        assert_eq!(editor.scroll_offset(), 90);
+    }
+
+    #[test]
+    fn test_selection_size_counts_lines_and_chars_from_bounds() {
+        let mut editor = create_editor_with_editor_content(vec![
+            "héllo".to_string(),
+            "wörld".to_string(),
+            "end".to_string(),
+        ]);
+        assert_eq!(editor.selection_size(), None);
+        editor.selection = Some(Range::new(Position::new(0, 1), Position::new(0, 4)));
+        assert_eq!(editor.selection_size(), Some((1, 3)));
+
+        //reversed selections count the same, line breaks are a char each
+        editor.selection = Some(Range::new(Position::new(2, 2), Position::new(0, 3)));
+        assert_eq!(editor.selection_size(), Some((3, 2 + 1 + 5 + 1 + 2)));
+        let copied = editor.copy_selected_text().unwrap();
+        assert_eq!(TextStats::of_lines(&copied).chars, 11);
     }
 
     #[test]
-    fn test_write_char_at_line_10() {
-        let mut editor = Editor::new();
-        editor.cursor.y = 10;
-        editor.write_char('a');
-        assert_eq!(editor.editor_content[10], "a");
-        assert_eq!(editor.cursor.x, 1);
+    fn test_enter_with_selection_over_lines_splits_at_its_start() {
+        let mut editor = create_editor_with_editor_content(vec![
+            "one two".to_string(),
+            "three".to_string(),
+            "four five".to_string(),
+        ]);
+        editor.selection = Some(Range::new(Position::new(0, 3), Position::new(2, 4)));
+        editor.handle_input_action(InputAction::ENTER).unwrap();
+        assert_eq!(editor.editor_content, vec!["one", " five"]);
+        assert_eq!(editor.cursor(), CursorPosition::new(0, 1));
+        assert!(!editor.is_text_selected());
+
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content, vec!["one two", "three", "four five"]);
     }
 
     #[test]
-    fn test_write_char_at_100_x() {
-        let mut editor = Editor::new();
-        editor.cursor.x = 100;
-        editor.write_char('a');
-        assert_eq!(editor.editor_content[0], "a");
-        assert_eq!(editor.cursor.x, 1);
+    fn test_tab_replaces_selection_within_a_line() {
+        let mut editor = create_editor_with_editor_content(vec!["key   value".to_string()]);
+        editor.selection = Some(Range::new(Position::new(0, 3), Position::new(0, 6)));
+        editor.handle_input_action(InputAction::TAB).unwrap();
+        assert_eq!(editor.editor_content, vec!["key\tvalue"]);
+        assert_eq!(editor.cursor(), CursorPosition::new(4, 0));
+
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content, vec!["key   value"]);
+    }
+
+    #[test]
+    fn test_tab_with_selection_over_lines_indents_them() {
+        let mut editor = create_editor_with_editor_content(vec![
+            "fn a() {".to_string(),
+            "".to_string(),
+            "}".to_string(),
+            "after".to_string(),
+        ]);
+        //ending at the start of a line leaves that line out
+        editor.selection = Some(Range::new(Position::new(0, 4), Position::new(3, 0)));
+        editor.handle_input_action(InputAction::TAB).unwrap();
+        assert_eq!(
+            editor.editor_content,
+            vec!["\tfn a() {", "", "\t}", "after"]
+        );
+        assert!(editor.is_text_selected());
+
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content, vec!["fn a() {", "", "}", "after"]);
     }
 
     //Write char to editor with selected text
     #[test]
     fn test_write_char_with_selected_text() {
         let mut editor = create_editor_with_editor_content(vec!["Hello Denmark".to_string()]);
-        editor.text_selection_start = Some(CursorPosition { x: 6, y: 0 });
-        editor.text_selection_end = Some(CursorPosition { x: 13, y: 0 });
+        editor.selection = Some(Range::new(Position::new(0, 6), Position::new(0, 13)));
         editor.cursor.x = 6;
         editor.write_char_text_is_selected('W');
         assert_eq!(editor.editor_content[0], "Hello W");
@@ -1290,8 +2990,7 @@ mod unit_editor_write_tests {
             "Hello Denmark".to_string(),
             "Hello Sudetenland".to_string(),
         ]);
-        editor.text_selection_start = Some(CursorPosition { x: 6, y: 1 });
-        editor.text_selection_end = Some(CursorPosition { x: 13, y: 2 });
+        editor.selection = Some(Range::new(Position::new(1, 6), Position::new(2, 13)));
         editor.cursor.x = 6;
         editor.write_char_text_is_selected('W');
         assert_eq!(editor.editor_content[0], "test");
@@ -1310,9 +3009,9 @@ mod unit_editor_write_tests {
         ]);
 
         // Set a selection range (e.g., "Denmark")
-        editor.text_selection_start = Some(CursorPosition { x: 2, y: 1 }); // middle of "test"
-        editor.text_selection_end = Some(CursorPosition { x: 13, y: 3 }); // End of "sudeten"
-                                                                          // Call the function to simulate a backspace with text selected
+        //middle of "test" to End of "sudeten"
+        editor.selection = Some(Range::new(Position::new(1, 2), Position::new(3, 13)));
+        // Call the function to simulate a backspace with text selected
         editor.backspace_text_is_selected();
 
         assert_eq!(editor.editor_content.len(), 2);
@@ -1322,8 +3021,7 @@ mod unit_editor_write_tests {
         assert_eq!(editor.editor_content[1], "teland");
 
         // Assert that the selection is cleared after the operation
-        assert!(editor.text_selection_start.is_none());
-        assert!(editor.text_selection_end.is_none());
+        assert!(editor.selection.is_none());
 
         // Assert that the cursor is moved to the correct position
         assert_eq!(editor.cursor.x, 2);
@@ -1333,8 +3031,7 @@ mod unit_editor_write_tests {
     #[test]
     fn test_write_char_with_selected_text_special_characters() {
         let mut editor = create_editor_with_editor_content(vec!["ᚠΩ₿😎".to_string()]);
-        editor.text_selection_start = Some(CursorPosition { x: 1, y: 0 });
-        editor.text_selection_end = Some(CursorPosition { x: 2, y: 0 });
+        editor.selection = Some(Range::new(Position::new(0, 1), Position::new(0, 2)));
         editor.cursor.x = 1;
 
         editor.write_char_text_is_selected('a');
@@ -1350,7 +3047,7 @@ mod unit_editor_write_tests {
 
         assert_eq!(editor.cursor.y, 0); // Cursor should stay on line
         assert_eq!(editor.editor_content.len(), 1); // New line added
-        assert_eq!(editor.visual_cursor_x, editor_settings::TAB_WIDTH as i16);
+        assert_eq!(editor.visual_cursor_x, editor_settings::TAB_WIDTH as usize);
     }
 
     #[test]
@@ -1360,7 +3057,7 @@ mod unit_editor_write_tests {
 
         assert_eq!(editor.cursor.y, 0); // Cursor should stay on line
         assert_eq!(editor.editor_content.len(), 1); // New line added
-        assert_eq!(editor.visual_cursor_x, editor_settings::TAB_WIDTH as i16);
+        assert_eq!(editor.visual_cursor_x, editor_settings::TAB_WIDTH as usize);
     }
 
     #[test]
@@ -1396,7 +3093,7 @@ mod unit_editor_write_tests {
     #[test]
     fn test_enter_in_editor_at_end_of_line() {
         let mut editor = create_editor_with_editor_content(vec!["Hello World".to_string()]);
-        editor.cursor.x = editor.editor_content[0].len() as i16; // Set cursor to end of line
+        editor.cursor.x = editor.editor_content[0].len(); // Set cursor to end of line
         editor.enter();
 
         assert_eq!(editor.cursor.y, 1); // Cursor should move to the next line
@@ -1414,16 +3111,86 @@ mod unit_editor_write_tests {
         assert_eq!(editor.cursor.x, 0); // Cursor resets to start of new line
         assert_eq!(editor.editor_content[0], "Hello"); // Line before cursor is kept intact
         assert_eq!(editor.editor_content[1], " World"); // Line after cursor is moved to new line
+        assert_eq!(editor.editor_content.len(), 2); // No extra line below the split one
+    }
+
+    #[test]
+    fn test_edits_on_content_without_lines() {
+        let mut editor = create_editor_with_editor_content(vec![]);
+        editor.enter();
+        assert_eq!(editor.editor_content.to_vec(), vec!["", ""]);
+        assert_eq!((editor.cursor.x, editor.cursor.y), (0, 1));
+
+        let mut editor = create_editor_with_editor_content(vec![]);
+        editor.move_cursor(1, 0);
+        editor.move_cursor(-1, 0);
+        editor.write_char('a');
+        assert_eq!(editor.editor_content.to_vec(), vec!["a"]);
+        assert_eq!(editor.cursor.x, 1);
+    }
+
+    #[test]
+    fn test_open_line_mid_line_keeps_line_and_indent() {
+        let mut editor =
+            create_editor_with_editor_content(vec!["    let x = 1;".to_string(), "}".to_string()]);
+        editor.set_cursor_position(&CursorPosition::new(7, 0));
+        editor
+            .handle_input_action(InputAction::OpenLine(true))
+            .unwrap();
+        assert_eq!(editor.editor_content, vec!["    let x = 1;", "    ", "}"]);
+        assert_eq!(editor.cursor(), CursorPosition::new(4, 1));
+
+        editor.set_cursor_position(&CursorPosition::new(2, 0));
+        editor
+            .handle_input_action(InputAction::OpenLine(false))
+            .unwrap();
+        assert_eq!(
+            editor.editor_content,
+            vec!["    ", "    let x = 1;", "    ", "}"]
+        );
+        assert_eq!(editor.cursor(), CursorPosition::new(4, 0));
+
+        editor.undo().unwrap();
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content, vec!["    let x = 1;", "}"]);
+    }
+
+    #[test]
+    fn test_duplicate_selection_inserts_copy_after_it() {
+        let mut editor = create_editor_with_editor_content(vec!["foo(bar)".to_string()]);
+        editor.selection = Some(Range::new(Position::new(0, 4), Position::new(0, 7)));
+        editor
+            .handle_input_action(InputAction::DuplicateSelection)
+            .unwrap();
+        assert_eq!(editor.editor_content, vec!["foo(barbar)"]);
+        assert_eq!(editor.selected_text().as_deref(), Some("bar"));
+        assert_eq!(editor.cursor(), CursorPosition::new(10, 0));
+
+        //over lines the copy starts where the selection ends
+        let mut editor =
+            create_editor_with_editor_content(vec!["ab".to_string(), "cd".to_string()]);
+        editor.selection = Some(Range::new(Position::new(0, 1), Position::new(1, 1)));
+        editor
+            .handle_input_action(InputAction::DuplicateSelection)
+            .unwrap();
+        assert_eq!(editor.editor_content, vec!["ab", "cb", "cd"]);
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content, vec!["ab", "cd"]);
+
+        editor.clear_selection();
+        assert_eq!(
+            editor.handle_input_action(InputAction::DuplicateSelection),
+            Err(EditorError::NoSelection)
+        );
     }
 }
 #[cfg(test)]
 mod unit_editor_delete_tests {
-    use super::super::super::cursor::CursorPosition;
     use super::super::editor::*;
 
     fn create_editor_with_editor_content(vec: Vec<String>) -> Editor {
         let mut editor = Editor::new();
-        editor.editor_content = vec;
+        editor.editor_content = vec.into();
         editor.editor_height = 10; //since testing doesnt start ui.rs, height isnt set
         editor
     }
@@ -1467,17 +3234,16 @@ mod unit_editor_delete_tests {
         let mut editor = create_editor_with_editor_content(vec!["Hello Denmark".to_string()]);
 
         // Set a selection range (e.g., "Denmark")
-        editor.text_selection_start = Some(CursorPosition { x: 6, y: 0 }); // Start of "Denmark"
-        editor.text_selection_end = Some(CursorPosition { x: 13, y: 0 }); // End of "Denmark"
-                                                                          // Call the function to simulate a backspace with text selected
+        //Start of "Denmark" to End of "Denmark"
+        editor.selection = Some(Range::new(Position::new(0, 6), Position::new(0, 13)));
+        // Call the function to simulate a backspace with text selected
         editor.backspace_text_is_selected();
 
         // Assert that the selected text is removed
         assert_eq!(editor.editor_content, vec!["Hello "]);
 
         // Assert that the selection is cleared after the operation
-        assert!(editor.text_selection_start.is_none());
-        assert!(editor.text_selection_end.is_none());
+        assert!(editor.selection.is_none());
 
         // Assert that the cursor is moved to the correct position
         assert_eq!(editor.cursor.x, 6);
@@ -1494,9 +3260,9 @@ mod unit_editor_delete_tests {
         ]);
 
         // Set a selection range (e.g., "Denmark")
-        editor.text_selection_start = Some(CursorPosition { x: 6, y: 1 }); // Start of "Denmark"
-        editor.text_selection_end = Some(CursorPosition { x: 13, y: 2 }); // End of "sudeten"
-                                                                          // Call the function to simulate a backspace with text selected
+        //Start of "Denmark" to End of "sudeten"
+        editor.selection = Some(Range::new(Position::new(1, 6), Position::new(2, 13)));
+        // Call the function to simulate a backspace with text selected
         editor.backspace_text_is_selected();
 
         assert_eq!(editor.editor_content.len(), 2);
@@ -1506,8 +3272,7 @@ mod unit_editor_delete_tests {
         assert_eq!(editor.editor_content[1], "Hello land");
 
         // Assert that the selection is cleared after the operation
-        assert!(editor.text_selection_start.is_none());
-        assert!(editor.text_selection_end.is_none());
+        assert!(editor.selection.is_none());
 
         // Assert that the cursor is moved to the correct position
         assert_eq!(editor.cursor.x, 6);
@@ -1525,9 +3290,9 @@ mod unit_editor_delete_tests {
         ]);
 
         // Set a selection range (e.g., "Denmark")
-        editor.text_selection_start = Some(CursorPosition { x: 2, y: 1 }); // middle of "test"
-        editor.text_selection_end = Some(CursorPosition { x: 13, y: 3 }); // End of "sudeten"
-                                                                          // Call the function to simulate a backspace with text selected
+        //middle of "test" to End of "sudeten"
+        editor.selection = Some(Range::new(Position::new(1, 2), Position::new(3, 13)));
+        // Call the function to simulate a backspace with text selected
         editor.backspace_text_is_selected();
 
         assert_eq!(editor.editor_content.len(), 2);
@@ -1536,8 +3301,7 @@ mod unit_editor_delete_tests {
         assert_eq!(editor.editor_content[1], "teland");
 
         // Assert that the selection is cleared after the operation
-        assert!(editor.text_selection_start.is_none());
-        assert!(editor.text_selection_end.is_none());
+        assert!(editor.selection.is_none());
 
         // Assert that the cursor is moved to the correct position
         assert_eq!(editor.cursor.x, 2);
@@ -1550,8 +3314,7 @@ mod unit_editor_delete_tests {
         let mut editor = create_editor_with_editor_content(vec!["".to_string()]);
 
         // Set a selection range (even though the text is empty)
-        editor.text_selection_start = Some(CursorPosition { x: 0, y: 0 });
-        editor.text_selection_end = Some(CursorPosition { x: 0, y: 0 });
+        editor.selection = Some(Range::new(Position::new(0, 0), Position::new(0, 0)));
 
         // Call the function to simulate a backspace with empty text
         editor.backspace_text_is_selected();
@@ -1560,8 +3323,7 @@ mod unit_editor_delete_tests {
         assert_eq!(editor.editor_content, vec!["".to_string()]);
 
         // Assert that the selection is cleared
-        assert!(editor.text_selection_start.is_none());
-        assert!(editor.text_selection_end.is_none());
+        assert!(editor.selection.is_none());
 
         // Assert that the cursor position is 0
         assert_eq!(editor.cursor.x, 0);
@@ -1574,8 +3336,7 @@ mod unit_editor_delete_tests {
         let mut editor = create_editor_with_editor_content(vec!["Hello Denmark".to_string()]);
 
         // Set a selection range for the entire text
-        editor.text_selection_start = Some(CursorPosition { x: 0, y: 0 });
-        editor.text_selection_end = Some(CursorPosition { x: 13, y: 0 });
+        editor.selection = Some(Range::new(Position::new(0, 0), Position::new(0, 13)));
 
         // Call the function to simulate a backspace with the entire text selected
         editor.backspace_text_is_selected();
@@ -1584,8 +3345,7 @@ mod unit_editor_delete_tests {
         assert_eq!(editor.editor_content, vec!["".to_string()]);
 
         // Assert that the selection is cleared
-        assert!(editor.text_selection_start.is_none());
-        assert!(editor.text_selection_end.is_none());
+        assert!(editor.selection.is_none());
 
         // Assert that the cursor position is 0
         assert_eq!(editor.cursor.x, 0);
@@ -1629,9 +3389,9 @@ mod unit_editor_delete_tests {
         let mut editor = create_editor_with_editor_content(vec!["Hello Denmark".to_string()]);
 
         // Set a selection range (e.g., "Denmark")
-        editor.text_selection_start = Some(CursorPosition { x: 6, y: 0 }); // Start of "Denmark"
-        editor.text_selection_end = Some(CursorPosition { x: 13, y: 0 }); // End of "Denmark"
-                                                                          // Call the function to simulate a backspace with text selected
+        //Start of "Denmark" to End of "Denmark"
+        editor.selection = Some(Range::new(Position::new(0, 6), Position::new(0, 13)));
+        // Call the function to simulate a backspace with text selected
         editor.delete_text_is_selected();
 
         // Assert that the selected text is removed
@@ -1639,8 +3399,7 @@ mod unit_editor_delete_tests {
         assert_eq!(editor.editor_content[0].len(), 13);
 
         // Assert that the selection is cleared after the operation
-        assert!(editor.text_selection_start.is_none());
-        assert!(editor.text_selection_end.is_none());
+        assert!(editor.selection.is_none());
 
         // Assert that the cursor is moved to the correct position
         assert_eq!(editor.cursor.x, 13);
@@ -1657,9 +3416,9 @@ mod unit_editor_delete_tests {
         ]);
 
         // Set a selection range (e.g., "Denmark")
-        editor.text_selection_start = Some(CursorPosition { x: 6, y: 1 }); // Start of "Denmark"
-        editor.text_selection_end = Some(CursorPosition { x: 13, y: 2 }); // End of "Denmark"
-                                                                          // Call the function to simulate a backspace with text selected
+        //Start of "Denmark" to End of "Denmark"
+        editor.selection = Some(Range::new(Position::new(1, 6), Position::new(2, 13)));
+        // Call the function to simulate a backspace with text selected
         editor.delete_text_is_selected();
 
         assert_eq!(editor.editor_content.len(), 3);
@@ -1670,8 +3429,7 @@ mod unit_editor_delete_tests {
         assert_eq!(editor.editor_content[2].len(), 17);
 
         // Assert that the selection is cleared after the operation
-        assert!(editor.text_selection_start.is_none());
-        assert!(editor.text_selection_end.is_none());
+        assert!(editor.selection.is_none());
 
         // Assert that the cursor is moved to the correct position
         assert_eq!(editor.cursor.x, 13);
@@ -1689,9 +3447,9 @@ mod unit_editor_delete_tests {
         ]);
 
         // Set a selection range (e.g., "Denmark")
-        editor.text_selection_start = Some(CursorPosition { x: 2, y: 1 }); // middle of "test"
-        editor.text_selection_end = Some(CursorPosition { x: 13, y: 3 }); // End of "sudeten"
-                                                                          // Call the function to simulate a backspace with text selected
+        //middle of "test" to End of "sudeten"
+        editor.selection = Some(Range::new(Position::new(1, 2), Position::new(3, 13)));
+        // Call the function to simulate a backspace with text selected
         editor.delete_text_is_selected();
 
         assert_eq!(editor.editor_content.len(), 4);
@@ -1702,8 +3460,7 @@ mod unit_editor_delete_tests {
         assert_eq!(editor.editor_content[3], "             land");
 
         // Assert that the selection is cleared after the operation
-        assert!(editor.text_selection_start.is_none());
-        assert!(editor.text_selection_end.is_none());
+        assert!(editor.selection.is_none());
 
         // Assert that the cursor is moved to the correct position
         assert_eq!(editor.cursor.x, 13);
@@ -1716,8 +3473,7 @@ mod unit_editor_delete_tests {
         let mut editor = create_editor_with_editor_content(vec!["".to_string()]);
 
         // Set a selection range (even though the text is empty)
-        editor.text_selection_start = Some(CursorPosition { x: 0, y: 0 });
-        editor.text_selection_end = Some(CursorPosition { x: 0, y: 0 });
+        editor.selection = Some(Range::new(Position::new(0, 0), Position::new(0, 0)));
 
         // Call the function to simulate a backspace with empty text
         editor.delete_text_is_selected();
@@ -1726,8 +3482,7 @@ mod unit_editor_delete_tests {
         assert_eq!(editor.editor_content, vec!["".to_string()]);
 
         // Assert that the selection is cleared
-        assert!(editor.text_selection_start.is_none());
-        assert!(editor.text_selection_end.is_none());
+        assert!(editor.selection.is_none());
 
         // Assert that the cursor position is 0
         assert_eq!(editor.cursor.x, 0);
@@ -1740,8 +3495,7 @@ mod unit_editor_delete_tests {
         let mut editor = create_editor_with_editor_content(vec!["Hello Denmark".to_string()]);
 
         // Set a selection range for the entire text
-        editor.text_selection_start = Some(CursorPosition { x: 0, y: 0 });
-        editor.text_selection_end = Some(CursorPosition { x: 13, y: 0 });
+        editor.selection = Some(Range::new(Position::new(0, 0), Position::new(0, 13)));
 
         // Call the function to simulate a backspace with the entire text selected
         editor.delete_text_is_selected();
@@ -1750,8 +3504,7 @@ mod unit_editor_delete_tests {
         assert_eq!(editor.editor_content[0].len(), 13);
 
         // Assert that the selection is cleared
-        assert!(editor.text_selection_start.is_none());
-        assert!(editor.text_selection_end.is_none());
+        assert!(editor.selection.is_none());
 
         // Assert that the cursor position is 0
         assert_eq!(editor.cursor.x, 13);
@@ -1762,10 +3515,11 @@ mod unit_editor_delete_tests {
 #[cfg(test)]
 mod unit_editor_cursor_tests {
     use super::super::editor::*;
+    use crate::input::input_action::{InputAction, Motion};
 
     fn create_editor_with_editor_content(vec: Vec<String>) -> Editor {
         let mut editor = Editor::new();
-        editor.editor_content = vec;
+        editor.editor_content = vec.into();
         editor.editor_height = 10; //since testing doesnt start ui.rs, height isnt set
         editor
     }
@@ -1834,10 +3588,10 @@ mod unit_editor_cursor_tests {
         let mut editor = create_editor_with_editor_content(vec![]);
         editor.move_selection_cursor(0, -1);
 
-        assert_eq!(editor.text_selection_start.unwrap().x, 0);
-        assert_eq!(editor.text_selection_start.unwrap().y, 0);
-        assert_eq!(editor.text_selection_end.unwrap().x, 0);
-        assert_eq!(editor.text_selection_end.unwrap().y, 0);
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 0), Position::new(0, 0)))
+        );
     }
 
     #[test]
@@ -1845,10 +3599,10 @@ mod unit_editor_cursor_tests {
         let mut editor = create_editor_with_editor_content(vec![]);
         editor.move_selection_cursor(0, 1);
 
-        assert_eq!(editor.text_selection_start.unwrap().x, 0);
-        assert_eq!(editor.text_selection_start.unwrap().y, 0);
-        assert_eq!(editor.text_selection_end.unwrap().x, 0);
-        assert_eq!(editor.text_selection_end.unwrap().y, 1);
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 0), Position::new(1, 0)))
+        );
     }
 
     #[test]
@@ -1856,10 +3610,10 @@ mod unit_editor_cursor_tests {
         let mut editor = create_editor_with_editor_content(vec![]);
         editor.move_selection_cursor(-1, 0);
 
-        assert_eq!(editor.text_selection_start.unwrap().x, 0);
-        assert_eq!(editor.text_selection_start.unwrap().y, 0);
-        assert_eq!(editor.text_selection_end.unwrap().x, 0);
-        assert_eq!(editor.text_selection_end.unwrap().y, 0);
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 0), Position::new(0, 0)))
+        );
     }
 
     #[test]
@@ -1867,10 +3621,10 @@ mod unit_editor_cursor_tests {
         let mut editor = create_editor_with_editor_content(vec![]);
         editor.move_selection_cursor(1, 0);
 
-        assert_eq!(editor.text_selection_start.unwrap().x, 0);
-        assert_eq!(editor.text_selection_start.unwrap().y, 0);
-        assert_eq!(editor.text_selection_end.unwrap().x, 0);
-        assert_eq!(editor.text_selection_end.unwrap().y, 0);
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 0), Position::new(0, 0)))
+        );
     }
 
     #[test]
@@ -1880,10 +3634,10 @@ mod unit_editor_cursor_tests {
         editor.cursor.y = 1;
         editor.move_selection_cursor(0, -1);
 
-        assert_eq!(editor.text_selection_start.unwrap().x, 0);
-        assert_eq!(editor.text_selection_start.unwrap().y, 0);
-        assert_eq!(editor.text_selection_end.unwrap().x, 0);
-        assert_eq!(editor.text_selection_end.unwrap().y, 1);
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 0), Position::new(1, 0)))
+        );
     }
 
     #[test]
@@ -1892,10 +3646,10 @@ mod unit_editor_cursor_tests {
             create_editor_with_editor_content(vec!["First".to_string(), "Second".to_string()]);
         editor.move_selection_cursor(0, 1);
 
-        assert_eq!(editor.text_selection_start.unwrap().x, 0);
-        assert_eq!(editor.text_selection_start.unwrap().y, 0);
-        assert_eq!(editor.text_selection_end.unwrap().x, 0);
-        assert_eq!(editor.text_selection_end.unwrap().y, 1);
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 0), Position::new(1, 0)))
+        );
     }
 
     #[test]
@@ -1904,10 +3658,10 @@ mod unit_editor_cursor_tests {
         editor.cursor.x = 1;
         editor.move_selection_cursor(-1, 0);
 
-        assert_eq!(editor.text_selection_start.unwrap().x, 0);
-        assert_eq!(editor.text_selection_start.unwrap().y, 0);
-        assert_eq!(editor.text_selection_end.unwrap().x, 1);
-        assert_eq!(editor.text_selection_end.unwrap().y, 0);
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 0), Position::new(0, 1)))
+        );
     }
 
     #[test]
@@ -1915,10 +3669,10 @@ mod unit_editor_cursor_tests {
         let mut editor = create_editor_with_editor_content(vec!["First".to_string()]);
         editor.move_selection_cursor(1, 0);
 
-        assert_eq!(editor.text_selection_start.unwrap().x, 0);
-        assert_eq!(editor.text_selection_start.unwrap().y, 0);
-        assert_eq!(editor.text_selection_end.unwrap().x, 1);
-        assert_eq!(editor.text_selection_end.unwrap().y, 0);
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 0), Position::new(0, 1)))
+        );
     }
 
     #[test]
@@ -1928,20 +3682,213 @@ mod unit_editor_cursor_tests {
         editor.move_selection_cursor(1, 0);
         editor.move_selection_cursor(1, 0);
 
-        assert_eq!(editor.text_selection_start.unwrap().x, 0);
-        assert_eq!(editor.text_selection_start.unwrap().y, 0);
-        assert_eq!(editor.text_selection_end.unwrap().x, 3);
-        assert_eq!(editor.text_selection_end.unwrap().y, 0);
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 0), Position::new(0, 3)))
+        );
+    }
+
+    //MOTIONS
+
+    #[test]
+    fn test_move_cursor_to_word_right_skips_word_and_whitespace() {
+        let mut editor = create_editor_with_editor_content(vec!["foo bar.baz".to_string()]);
+        editor.move_cursor_to(Motion::WordRight);
+        assert_eq!(editor.cursor.x, 3);
+        editor.move_cursor_to(Motion::WordRight);
+        assert_eq!(editor.cursor.x, 7);
+        editor.move_cursor_to(Motion::WordRight);
+        assert_eq!(editor.cursor.x, 8);
+    }
+
+    #[test]
+    fn test_word_motions_follow_word_chars() {
+        let mut editor = create_editor_with_editor_content(vec!["foo-bar baz".to_string()]);
+        editor.move_cursor_to(Motion::WordRight);
+        assert_eq!(editor.cursor.x, 3);
+
+        editor.cursor.x = 0;
+        editor.set_word_chars(WordChars::Extra("-".to_string()));
+        editor.move_cursor_to(Motion::WordRight);
+        assert_eq!(editor.cursor.x, 7);
+        editor.move_cursor_to(Motion::WordLeft);
+        assert_eq!(editor.cursor.x, 0);
+    }
+
+    #[test]
+    fn test_move_cursor_to_word_left_wraps_to_previous_line() {
+        let mut editor =
+            create_editor_with_editor_content(vec!["First".to_string(), "Second".to_string()]);
+        editor.cursor.y = 1;
+        editor.move_cursor_to(Motion::WordLeft);
+
+        assert_eq!(editor.cursor.x, 5);
+        assert_eq!(editor.cursor.y, 0);
+    }
+
+    #[test]
+    fn test_move_cursor_to_document_end_scrolls() {
+        let content: Vec<String> = (0..30).map(|i| format!("line {}", i)).collect();
+        let mut editor = create_editor_with_editor_content(content);
+        editor.move_cursor_to(Motion::DocumentEnd);
+
+        assert_eq!(editor.cursor.y, 29);
+        assert_eq!(editor.cursor.x, 7);
+        assert_eq!(editor.scroll_offset, 20);
+    }
+
+    fn create_paragraph_editor() -> Editor {
+        create_editor_with_editor_content(
+            ["a", "b", "", "", "c", "d", "", "e"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_paragraph_down_stops_once_per_blank_run() {
+        let mut editor = create_paragraph_editor();
+        editor.move_cursor_to(Motion::ParagraphDown);
+        assert_eq!(editor.cursor.y, 2);
+        editor.move_cursor_to(Motion::ParagraphDown);
+        assert_eq!(editor.cursor.y, 6);
+        editor.move_cursor_to(Motion::ParagraphDown);
+        assert_eq!((editor.cursor.x, editor.cursor.y), (1, 7));
+        editor.move_cursor_to(Motion::ParagraphDown);
+        assert_eq!((editor.cursor.x, editor.cursor.y), (1, 7));
+    }
+
+    #[test]
+    fn test_paragraph_up_stops_once_per_blank_run() {
+        let mut editor = create_paragraph_editor();
+        editor.cursor.y = 7;
+        editor.move_cursor_to(Motion::ParagraphUp);
+        assert_eq!(editor.cursor.y, 6);
+        editor.move_cursor_to(Motion::ParagraphUp);
+        assert_eq!(editor.cursor.y, 3);
+        editor.move_cursor_to(Motion::ParagraphUp);
+        assert_eq!((editor.cursor.x, editor.cursor.y), (0, 0));
+    }
+
+    #[test]
+    fn test_paragraph_down_scrolls_and_extends_selection() {
+        let mut content: Vec<String> = (0..20).map(|i| i.to_string()).collect();
+        content.push(String::new());
+        content.push("end".to_string());
+        let mut editor = create_editor_with_editor_content(content);
+        editor.move_selection_cursor_to(Motion::ParagraphDown);
+
+        assert_eq!(editor.cursor.y, 20);
+        assert_eq!(editor.scroll_offset, 11);
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 0), Position::new(20, 0)))
+        );
+    }
+
+    //SELECTION MOTIONS
+
+    #[test]
+    fn test_selection_to_line_end_and_back_to_line_start_reverses_across_anchor() {
+        let mut editor = create_editor_with_editor_content(vec!["Hello World".to_string()]);
+        editor.cursor.x = 5;
+        editor.move_selection_cursor_to(Motion::LineEnd);
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 5), Position::new(0, 11)))
+        );
+
+        editor.move_selection_cursor_to(Motion::LineStart);
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 0), Position::new(0, 5)))
+        );
+        assert_eq!(editor.cursor.x, 0);
+    }
+
+    #[test]
+    fn test_selection_word_left_then_word_right_reverses_across_anchor() {
+        let mut editor = create_editor_with_editor_content(vec!["one two three".to_string()]);
+        editor.cursor.x = 7;
+        editor.move_selection_cursor_to(Motion::WordLeft);
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 4), Position::new(0, 7)))
+        );
+
+        editor.move_selection_cursor_to(Motion::WordRight);
+        editor.move_selection_cursor_to(Motion::WordRight);
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 7), Position::new(0, 13)))
+        );
+    }
+
+    #[test]
+    fn test_selection_document_end_then_document_start_reverses_across_anchor() {
+        let mut editor = create_editor_with_editor_content(vec![
+            "First".to_string(),
+            "Second".to_string(),
+            "Third".to_string(),
+        ]);
+        editor.cursor.x = 2;
+        editor.cursor.y = 1;
+        editor.move_selection_cursor_to(Motion::DocumentEnd);
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(1, 2), Position::new(2, 5)))
+        );
+
+        editor.move_selection_cursor_to(Motion::DocumentStart);
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 0), Position::new(1, 2)))
+        );
+    }
+
+    #[test]
+    fn test_selection_motion_combines_with_shift_arrows() {
+        let mut editor = create_editor_with_editor_content(vec!["Hello World".to_string()]);
+        editor.cursor.x = 6;
+        editor.move_selection_cursor(1, 0);
+        editor.move_selection_cursor_to(Motion::LineStart);
+
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 0), Position::new(0, 6)))
+        );
+
+        editor.move_selection_cursor(1, 0);
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 1), Position::new(0, 6)))
+        );
+    }
+
+    #[test]
+    fn test_plain_motion_clears_selection() {
+        let mut editor = create_editor_with_editor_content(vec!["Hello World".to_string()]);
+        editor.move_selection_cursor_to(Motion::LineEnd);
+        editor
+            .handle_input_action(InputAction::MoveCursorTo(Motion::LineStart))
+            .unwrap();
+
+        assert!(!editor.is_text_selected());
+        assert_eq!(editor.cursor.x, 0);
     }
 }
 #[cfg(test)]
 mod unit_editor_cutcopy_tests {
     use crate::core::cursor::CursorPosition;
     use crate::core::editor::Editor;
+    use crate::core::errors::editor_errors::EditorError;
+    use crate::core::position::{Position, Range};
+    use crate::input::input_action::{Direction, InputAction};
 
     fn create_editor_with_editor_content(vec: Vec<String>) -> Editor {
         let mut editor = Editor::new();
-        editor.editor_content = vec;
+        editor.editor_content = vec.into();
         editor.editor_height = 10; //since testing doesnt start ui.rs, height isnt set
         editor
     }
@@ -1950,8 +3897,7 @@ mod unit_editor_cutcopy_tests {
     #[test]
     fn test_copy_single_line_selection() {
         let mut app = create_editor_with_editor_content(vec!["Hello, world!".to_string()]);
-        app.text_selection_start = Some(CursorPosition { x: 7, y: 0 });
-        app.text_selection_end = Some(CursorPosition { x: 12, y: 0 });
+        app.selection = Some(Range::new(Position::new(0, 7), Position::new(0, 12)));
 
         let result = app.copy();
 
@@ -1966,8 +3912,7 @@ mod unit_editor_cutcopy_tests {
             " world!".to_string(),
             " Rust".to_string(),
         ]);
-        app.text_selection_start = Some(CursorPosition { x: 4, y: 0 });
-        app.text_selection_end = Some(CursorPosition { x: 3, y: 2 });
+        app.selection = Some(Range::new(Position::new(0, 4), Position::new(2, 3)));
 
         let result = app.copy();
 
@@ -1984,28 +3929,90 @@ mod unit_editor_cutcopy_tests {
     #[test]
     fn test_copy_no_selection() {
         let mut app = create_editor_with_editor_content(vec!["Hello, world!".to_string()]);
-        app.text_selection_start = None;
-        app.text_selection_end = None;
+        app.selection = None;
 
         let result = app.copy();
 
-        assert!(result.is_err());
+        assert_eq!(result, Err(EditorError::NoSelection));
         assert!(app.clipboard.copied_text.is_empty());
     }
 
-    //cut selected text
+    #[test]
+    fn test_select_enclosing_then_cut_takes_argument_list() {
+        let mut app = create_editor_with_editor_content(vec!["call(a, b);".to_string()]);
+        app.cursor.x = 6;
+        app.select_enclosing();
+        app.cut().unwrap();
+
+        assert_eq!(app.clipboard.copied_text, vec!["a, b".to_string()]);
+        assert_eq!(app.editor_content, vec!["call();".to_string()]);
+    }
+
+    #[test]
+    fn test_select_enclosing_twice_includes_quotes() {
+        let mut app = create_editor_with_editor_content(vec![r#"x = "text";"#.to_string()]);
+        app.cursor.x = 6;
+        app.select_enclosing();
+        app.select_enclosing();
+        app.copy().unwrap();
+
+        assert_eq!(app.clipboard.copied_text, vec![r#""text""#.to_string()]);
+    }
+
+    #[test]
+    fn test_copy_selection_past_line_end_is_out_of_bounds() {
+        let mut app = create_editor_with_editor_content(vec!["Hello".to_string()]);
+        app.selection = Some(Range::new(Position::new(0, 2), Position::new(0, 9)));
+
+        assert_eq!(
+            app.copy(),
+            Err(EditorError::CursorOutOfBounds {
+                x: 9,
+                y: 0,
+                line_len: 5
+            })
+        );
+    }
+
+    #[test]
+    fn test_cut_selection_past_last_line_is_out_of_bounds() {
+        let mut app = create_editor_with_editor_content(vec!["Hello".to_string()]);
+        app.selection = Some(Range::new(Position::new(0, 0), Position::new(3, 0)));
+
+        assert_eq!(
+            app.cut(),
+            Err(EditorError::CursorOutOfBounds {
+                x: 0,
+                y: 3,
+                line_len: 0
+            })
+        );
+        assert_eq!(app.editor_content, vec!["Hello".to_string()]);
+    }
+
+    #[test]
+    fn test_paste_single_line_below_last_line_extends_content() {
+        let mut app = create_editor_with_editor_content(vec!["Hello".to_string()]);
+        app.clipboard.copy(&["x".to_string()]);
+        app.cursor.y = 1;
+
+        app.paste().unwrap();
+        assert_eq!(
+            app.editor_content,
+            vec!["Hello".to_string(), "x".to_string()]
+        );
+    }
+
     #[test]
     fn test_cut_single_line_selection() {
         let mut app = create_editor_with_editor_content(vec!["Hello, world!".to_string()]);
-        app.text_selection_start = Some(CursorPosition { x: 7, y: 0 });
-        app.text_selection_end = Some(CursorPosition { x: 12, y: 0 });
+        app.selection = Some(Range::new(Position::new(0, 7), Position::new(0, 12)));
 
         let result = app.cut();
 
         assert!(result.is_ok());
         assert_eq!(app.clipboard.copied_text, vec!["world".to_string()]);
-        assert!(app.text_selection_start.is_none());
-        assert!(app.text_selection_end.is_none());
+        assert!(app.selection.is_none());
     }
 
     #[test]
@@ -2015,8 +4022,7 @@ mod unit_editor_cutcopy_tests {
             " world!".to_string(),
             " Rust".to_string(),
         ]);
-        app.text_selection_start = Some(CursorPosition { x: 4, y: 0 });
-        app.text_selection_end = Some(CursorPosition { x: 3, y: 2 });
+        app.selection = Some(Range::new(Position::new(0, 4), Position::new(2, 3)));
 
         let result = app.cut();
 
@@ -2028,22 +4034,39 @@ mod unit_editor_cutcopy_tests {
                 .map(String::from)
                 .collect::<Vec<String>>()
         );
-        assert!(app.text_selection_start.is_none());
-        assert!(app.text_selection_end.is_none());
+        assert!(app.selection.is_none());
+        assert_eq!(app.editor_content, vec!["Hellst"]);
+        assert_eq!(app.cursor(), CursorPosition::new(4, 0));
+
+        app.undo().unwrap();
+        assert_eq!(app.editor_content, vec!["Hello,", " world!", " Rust"]);
+    }
+
+    #[test]
+    fn test_cut_everything_leaves_empty_line_and_undoes() {
+        let mut app = create_editor_with_editor_content(vec!["ab".to_string(), "cd".to_string()]);
+        app.selection = Some(Range::new(Position::new(0, 0), Position::new(1, 2)));
+
+        app.cut().unwrap();
+        assert_eq!(app.editor_content, vec![""]);
+        assert_eq!(app.cursor(), CursorPosition::new(0, 0));
+
+        app.undo().unwrap();
+        assert_eq!(app.editor_content, vec!["ab", "cd"]);
+        app.redo().unwrap();
+        assert_eq!(app.editor_content, vec![""]);
     }
 
     #[test]
     fn test_cut_no_selection() {
         let mut app = create_editor_with_editor_content(vec!["Hello, world!".to_string()]);
-        app.text_selection_start = None;
-        app.text_selection_end = None;
+        app.selection = None;
 
         let result = app.cut();
 
-        assert!(result.is_err());
+        assert_eq!(result, Err(EditorError::NoSelection));
         assert!(app.clipboard.copied_text.is_empty());
-        assert!(app.text_selection_start.is_none());
-        assert!(app.text_selection_end.is_none());
+        assert!(app.selection.is_none());
     }
 
     //paste selected text
@@ -2054,7 +4077,7 @@ mod unit_editor_cutcopy_tests {
             "This is a test.".to_string(),
             "Another line.".to_string(),
         ]);
-        app.clipboard.copy(&vec!["PASTED".to_string()]);
+        app.clipboard.copy(&["PASTED".to_string()]);
         app.cursor.x = 8;
         app.cursor.y = 0;
 
@@ -2077,7 +4100,7 @@ mod unit_editor_cutcopy_tests {
             "Another line.".to_string(),
         ]);
         app.clipboard
-            .copy(&vec!["First".to_string(), "Second ".to_string()]);
+            .copy(&["First".to_string(), "Second ".to_string()]);
         app.cursor.x = 5;
         app.cursor.y = 1;
 
@@ -2100,7 +4123,7 @@ mod unit_editor_cutcopy_tests {
             "This is a test.".to_string(),
             "Another line.".to_string(),
         ]);
-        app.clipboard.copy(&vec!["PASTED".to_string()]);
+        app.clipboard.copy(&["PASTED".to_string()]);
         app.cursor.x = 10;
         app.cursor.y = 0;
 
@@ -2123,7 +4146,7 @@ mod unit_editor_cutcopy_tests {
             "Another line.".to_string(),
         ]);
         app.clipboard
-            .copy(&vec!["First".to_string(), "Second ".to_string()]);
+            .copy(&["First".to_string(), "Second ".to_string()]);
         app.cursor.x = 7;
         app.cursor.y = 1;
 
@@ -2146,7 +4169,7 @@ mod unit_editor_cutcopy_tests {
             "This is a test.".to_string(),
             "Another line.".to_string(),
         ]);
-        app.clipboard.copy(&vec!["NewStart".to_string()]);
+        app.clipboard.copy(&["NewStart".to_string()]);
         app.cursor.x = 0;
         app.cursor.y = 2;
 
@@ -2169,7 +4192,7 @@ mod unit_editor_cutcopy_tests {
             "This is a test.".to_string(),
             "Another line.".to_string(),
         ]);
-        app.clipboard.copy(&vec!["END".to_string()]);
+        app.clipboard.copy(&["END".to_string()]);
         app.cursor.x = 13;
         app.cursor.y = 0;
 
@@ -2191,11 +4214,11 @@ mod unit_editor_cutcopy_tests {
             "This is a test.".to_string(),
             "Another line.".to_string(),
         ]);
-        app.clipboard.copy(&vec![]);
+        app.clipboard.copy(&[]);
         app.cursor.x = 5;
         app.cursor.y = 1;
 
-        assert!(app.paste().is_err());
+        assert_eq!(app.paste(), Err(EditorError::EmptyClipboard));
         assert_eq!(
             app.editor_content,
             vec![
@@ -2207,28 +4230,167 @@ mod unit_editor_cutcopy_tests {
     }
 
     #[test]
-    fn test_paste_into_empty_editor() {
-        let mut app = create_editor_with_editor_content(vec![]);
-        app.clipboard
-            .copy(&vec!["Hello".to_string(), "World".to_string()]);
+    fn test_paste_into_empty_editor() {
+        let mut app = create_editor_with_editor_content(vec![]);
+        app.clipboard
+            .copy(&["Hello".to_string(), "World".to_string()]);
+
+        app.paste().unwrap();
+        assert_eq!(
+            app.editor_content,
+            vec!["Hello".to_string(), "World".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_cut_line_and_undo() {
+        let mut app = create_editor_with_editor_content(vec![
+            "first".to_string(),
+            "second".to_string(),
+            "third".to_string(),
+        ]);
+        app.cursor.y = 1;
+        app.cut_line();
+
+        assert_eq!(app.editor_content, vec!["first", "third"]);
+        assert_eq!(app.clipboard.copied_text, vec!["second", ""]);
+        assert_eq!(app.cursor.y, 1);
+
+        app.undo().unwrap();
+        assert_eq!(app.editor_content, vec!["first", "second", "third"]);
+    }
+
+    #[test]
+    fn test_cut_only_line_leaves_empty_line() {
+        let mut app = create_editor_with_editor_content(vec!["only".to_string()]);
+        app.cut_line();
+        assert_eq!(app.editor_content, vec![""]);
+        app.undo().unwrap();
+        assert_eq!(app.editor_content, vec!["only"]);
+    }
+
+    #[test]
+    fn test_copy_line() {
+        let mut app = create_editor_with_editor_content(vec!["first".to_string()]);
+        app.copy_line();
+        assert_eq!(app.clipboard.copied_text, vec!["first", ""]);
+        assert_eq!(app.editor_content, vec!["first"]);
+    }
+
+    //selecting in both directions
+    const TEXT: [&str; 4] = ["fn main() {", "    let é = 1;", "", "}"];
+
+    ///position of the char at i of the text joined by newlines
+    fn position_of(i: usize) -> CursorPosition {
+        let mut i = i;
+        for (y, line) in TEXT.iter().enumerate() {
+            let len = line.chars().count();
+            if i <= len {
+                return CursorPosition::new(i, y);
+            }
+            i -= len + 1;
+        }
+        panic!("{} is past the text", i);
+    }
+
+    ///selects from char from to char to of the text with shift arrows, backwards if to is
+    /// before from
+    fn select_with_arrows(from: usize, to: usize) -> Editor {
+        let mut editor =
+            create_editor_with_editor_content(TEXT.iter().map(|s| s.to_string()).collect());
+        editor.set_cursor(position_of(from)).unwrap();
+        let direction = if to > from {
+            Direction::Right
+        } else {
+            Direction::Left
+        };
+        for _ in 0..from.abs_diff(to) {
+            editor
+                .handle_input_action(InputAction::MoveSelectionCursor(direction.clone()))
+                .unwrap();
+        }
+        editor
+    }
+
+    #[test]
+    fn test_selection_direction_does_not_change_copy_or_cut() {
+        let chars: Vec<char> = TEXT.join("\n").chars().collect();
+        let mut rng = fastrand::Rng::with_seed(7);
+        for _ in 0..200 {
+            let a = rng.usize(..=chars.len());
+            let b = rng.usize(..=chars.len());
+            let (start, end) = (a.min(b), a.max(b));
+            if start == end {
+                continue;
+            }
+            let expected: String = chars[start..end].iter().collect();
+            let mut forward = select_with_arrows(start, end);
+            let mut backward = select_with_arrows(end, start);
+            assert_eq!(forward.selection(), backward.selection());
+            assert_eq!(forward.selected_text(), Some(expected.clone()));
+            assert_eq!(backward.selected_text(), Some(expected));
+
+            forward.cut().unwrap();
+            backward.cut().unwrap();
+            assert_eq!(
+                forward.clipboard.copied_text,
+                backward.clipboard.copied_text
+            );
+            assert_eq!(forward.editor_content, backward.editor_content);
+            assert_eq!(forward.cursor_position(), backward.cursor_position());
+        }
+    }
 
-        app.paste().unwrap();
-        assert_eq!(
-            app.editor_content,
-            vec!["Hello".to_string(), "World".to_string()]
-        );
+    #[test]
+    fn test_swap_selection_ends_extends_the_other_side() {
+        //"main" selected backwards, the cursor on its start
+        let mut editor = select_with_arrows(7, 3);
+        assert_eq!(editor.cursor_position(), CursorPosition::new(3, 0));
+
+        editor
+            .handle_input_action(InputAction::SwapSelectionEnds)
+            .unwrap();
+        assert_eq!(editor.cursor_position(), CursorPosition::new(7, 0));
+        editor
+            .handle_input_action(InputAction::MoveSelectionCursor(Direction::Right))
+            .unwrap();
+        editor
+            .handle_input_action(InputAction::MoveSelectionCursor(Direction::Right))
+            .unwrap();
+        assert_eq!(editor.selected_text().as_deref(), Some("main()"));
+
+        //and back to the start, shrinking it from there
+        editor
+            .handle_input_action(InputAction::SwapSelectionEnds)
+            .unwrap();
+        editor
+            .handle_input_action(InputAction::MoveSelectionCursor(Direction::Right))
+            .unwrap();
+        assert_eq!(editor.selected_text().as_deref(), Some("ain()"));
+    }
+
+    #[test]
+    fn test_swap_selection_ends_without_selection_keeps_cursor() {
+        let mut editor = select_with_arrows(5, 5);
+        editor
+            .handle_input_action(InputAction::SwapSelectionEnds)
+            .unwrap();
+        assert_eq!(editor.cursor_position(), CursorPosition::new(5, 0));
+        assert!(editor.selection().is_none());
     }
 }
 #[cfg(test)]
 mod unit_editor_undoredo_tests {
     use super::super::super::cursor::CursorPosition;
+    use super::super::super::errors::editor_errors::EditorError;
+    use super::super::edit_events::EditEvent;
     use super::super::editor::EditAction;
     use super::super::editor::Editor;
 
     //init functions
     fn create_editor_with_editor_content(vec: Vec<String>) -> Editor {
         let mut editor = Editor::new();
-        editor.editor_content = vec;
+        editor.editor_content = vec.into();
         editor.editor_height = 10; //since testing doesnt start ui.rs, height isnt set
         editor
     }
@@ -2323,8 +4485,8 @@ mod unit_editor_undoredo_tests {
         editor.undo_redo_manager.record_undo(EditAction::Replace {
             start,
             end,
-            old: old.clone(),
-            new: new.clone(),
+            old,
+            new,
         });
         editor.editor_content[0].replace_range(2..3, "x");
         assert_eq!(editor.editor_content[0], "fox");
@@ -2423,7 +4585,7 @@ mod unit_editor_undoredo_tests {
             "2".to_string(),
             "3".to_string(),
         ]);
-        let removed = editor.editor_content.clone();
+        let removed = editor.editor_content.to_vec();
         let pos = CursorPosition { x: 0, y: 0 };
         editor
             .undo_redo_manager
@@ -2555,7 +4717,7 @@ mod unit_editor_undoredo_tests {
             });
 
         // apply manually: "abc", "def", "ghi" => delete selection => "ai"
-        editor.editor_content = vec!["ai".to_string()];
+        editor.editor_content = vec!["ai".to_string()].into();
         assert_eq!(editor.editor_content, vec!["ai"]);
 
         editor.undo().unwrap();
@@ -2816,15 +4978,15 @@ mod unit_editor_undoredo_tests {
     fn undo_redo_stack_behavior() {
         let mut editor = create_editor_with_editor_content(vec!["".to_string()]);
         // Undo & redo stack empty
-        assert!(editor.undo().is_err());
-        assert!(editor.redo().is_err());
+        assert_eq!(editor.undo(), Err(EditorError::NothingToUndo));
+        assert_eq!(editor.redo(), Err(EditorError::NothingToRedo));
         // Normal sequence
         editor.write_char('t');
         editor.undo().unwrap();
         assert!(editor.redo().is_ok());
         // After new action, redo stack cleared
         editor.write_char('z');
-        assert!(editor.redo().is_err());
+        assert_eq!(editor.redo(), Err(EditorError::NothingToRedo));
     }
 
     #[test]
@@ -2838,7 +5000,7 @@ mod unit_editor_undoredo_tests {
         assert_eq!(editor.editor_content[0], "ab");
         editor.write_char('Z');
         assert_eq!(editor.editor_content[0], "abZ");
-        assert!(editor.redo().is_err()); // Redo stack cleared
+        assert_eq!(editor.redo(), Err(EditorError::NothingToRedo)); // Redo stack cleared
         editor.undo().unwrap();
         assert_eq!(editor.editor_content[0], "ab");
         editor.undo().unwrap();
@@ -2846,4 +5008,501 @@ mod unit_editor_undoredo_tests {
         editor.redo().unwrap();
         assert_eq!(editor.editor_content[0], "ab");
     }
+
+    // ========== Groups ==========
+    #[test]
+    fn undo_redo_group_is_single_step() {
+        let mut editor = create_editor_with_editor_content(vec!["".to_string()]);
+        editor.write_char('a');
+        editor.begin_undo_group();
+        editor.write_char('b');
+        editor.write_char('c');
+        editor.end_undo_group();
+        assert_eq!(editor.editor_content[0], "abc");
+
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content[0], "a");
+        editor.redo().unwrap();
+        assert_eq!(editor.editor_content[0], "abc");
+    }
+
+    #[test]
+    fn undo_redo_nested_group_collapses_into_outer() {
+        let mut editor = create_editor_with_editor_content(vec!["".to_string()]);
+        editor.begin_undo_group();
+        editor.write_char('a');
+        editor.begin_undo_group();
+        editor.write_char('b');
+        editor.end_undo_group();
+        editor.write_char('c');
+        editor.end_undo_group();
+
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content[0], "");
+        assert_eq!(editor.undo(), Err(EditorError::NothingToUndo));
+    }
+
+    #[test]
+    fn undo_redo_empty_group_leaves_no_entry() {
+        let mut editor = create_editor_with_editor_content(vec!["".to_string()]);
+        editor.begin_undo_group();
+        editor.end_undo_group();
+        assert_eq!(editor.undo(), Err(EditorError::NothingToUndo));
+    }
+
+    #[test]
+    fn undo_redo_group_ends_when_it_fails() {
+        let mut editor = create_editor_with_editor_content(vec!["".to_string()]);
+        let failed = editor.undo_group(|_| Err::<(), _>(EditorError::NothingToUndo));
+        assert!(failed.is_err());
+        assert_eq!(editor.undo_depth(), 0);
+
+        //edits made before failing still undo as one step
+        let failed = editor.undo_group(|editor| {
+            editor.write_char('a');
+            editor.enter();
+            editor.write_char('b');
+            Err::<(), _>(EditorError::NothingToUndo)
+        });
+        assert!(failed.is_err());
+        editor.write_char('c');
+        assert_eq!(editor.undo_depth(), 2);
+        editor.undo_steps(2).unwrap();
+        assert_eq!(editor.editor_content, vec![""]);
+    }
+
+    #[test]
+    fn undo_redo_backspace_joining_lines() {
+        let mut editor =
+            create_editor_with_editor_content(vec!["ab".to_string(), "cd".to_string()]);
+        editor.cursor.y = 1;
+        editor.backspace();
+        assert_eq!(editor.editor_content, vec!["abcd"]);
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content, vec!["ab", "cd"]);
+        editor.redo().unwrap();
+        assert_eq!(editor.editor_content, vec!["abcd"]);
+    }
+
+    #[test]
+    fn undo_redo_delete_joining_lines() {
+        let mut editor =
+            create_editor_with_editor_content(vec!["ab".to_string(), "cd".to_string()]);
+        editor.cursor.x = 2;
+        editor.delete();
+        assert_eq!(editor.editor_content, vec!["abcd"]);
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content, vec!["ab", "cd"]);
+    }
+
+    #[test]
+    fn undo_and_redo_of_a_step_not_fitting_the_content_fail() {
+        let mut editor = create_editor_with_editor_content(vec!["ab".to_string()]);
+        //a split of a line the content doesn't have, undoing it joins lines that aren't there
+        editor.undo_redo_manager.record_undo(EditAction::SplitLine {
+            pos: CursorPosition { x: 0, y: 5 },
+            left: String::new(),
+            right: String::new(),
+        });
+        assert_eq!(
+            editor.undo(),
+            Err(EditorError::LineOutOfBounds {
+                line: 6,
+                line_count: 1
+            })
+        );
+        assert!(editor.can_undo());
+        assert!(!editor.can_redo());
+        assert_eq!(editor.editor_content, vec!["ab"]);
+
+        editor.undo_redo_manager.clear();
+        editor.undo_redo_manager.record_undo(EditAction::JoinLine {
+            pos: CursorPosition { x: 0, y: 5 },
+            merged: String::new(),
+        });
+        editor.undo_redo_manager.undo().unwrap();
+        assert!(editor.redo().is_err());
+        assert!(editor.can_redo());
+        assert_eq!(editor.editor_content, vec!["ab"]);
+    }
+
+    #[test]
+    fn undo_reports_inverse_events() {
+        let mut editor = create_editor_with_editor_content(vec!["ab".to_string()]);
+        editor.watch_events();
+        editor.cursor.x = 1;
+        editor.enter();
+        let done = editor.take_events();
+        assert_eq!(
+            done,
+            vec![EditEvent::Inserted {
+                at: CursorPosition { x: 1, y: 0 },
+                text: "\n".to_string(),
+            }]
+        );
+        editor.undo().unwrap();
+        assert_eq!(
+            editor.take_events(),
+            vec![EditEvent::Deleted {
+                range: CursorPosition { x: 1, y: 0 }..CursorPosition { x: 0, y: 1 },
+                text: "\n".to_string(),
+            }]
+        );
+        editor.redo().unwrap();
+        assert_eq!(editor.take_events(), done);
+    }
+
+    #[test]
+    fn unwatched_editor_keeps_no_events() {
+        let mut editor = create_editor_with_editor_content(vec!["ab".to_string()]);
+        editor.enter();
+        assert!(!editor.has_events());
+        assert!(editor.take_events().is_empty());
+    }
+
+    #[test]
+    fn undo_and_redo_move_marks_with_their_inverse_events() {
+        let mut editor =
+            create_editor_with_editor_content(vec!["ab".to_string(), "cd".to_string()]);
+        editor.marks.set('a', CursorPosition { x: 1, y: 1 });
+        editor.cursor.x = 1;
+        editor.enter();
+        assert_eq!(editor.marks.get('a'), Some(CursorPosition { x: 1, y: 2 }));
+        editor.undo().unwrap();
+        assert_eq!(editor.marks.get('a'), Some(CursorPosition { x: 1, y: 1 }));
+        editor.redo().unwrap();
+        assert_eq!(editor.marks.get('a'), Some(CursorPosition { x: 1, y: 2 }));
+    }
+
+    #[test]
+    fn test_revert_to_composes_undos_and_redos() {
+        let mut editor = create_editor_with_editor_content(vec![String::new()]);
+        editor.write_char('a');
+        editor.write_char('b');
+        editor.enter();
+        editor.write_char('c');
+        let (history, applied) = editor.undo_history();
+        assert_eq!(applied, 4);
+        assert_eq!(history[2].kind(), "insert");
+        assert_eq!(history[2].preview(), "↵");
+        assert_eq!(history[3].preview(), "c");
+
+        editor.revert_to(1).unwrap();
+        assert_eq!(editor.editor_content, vec!["a"]);
+        assert_eq!(editor.cursor(), CursorPosition::new(0, 0));
+        assert_eq!(editor.undo_history().0.len(), 4);
+
+        //forward again, the cursor goes where the chosen step typed
+        editor.revert_to(4).unwrap();
+        assert_eq!(editor.editor_content, vec!["ab", "c"]);
+        assert_eq!(editor.cursor(), CursorPosition::new(0, 1));
+        editor.revert_to(0).unwrap();
+        assert_eq!(editor.editor_content, vec![""]);
+        assert!(editor.revert_to(5).is_err());
+    }
+
+    #[test]
+    fn test_undo_and_redo_depths() {
+        let mut editor = create_editor_with_editor_content(vec![String::new()]);
+        assert!(!editor.can_undo() && !editor.can_redo());
+        editor.write_char('a');
+        editor.write_char('b');
+        assert_eq!((editor.undo_depth(), editor.redo_depth()), (2, 0));
+        editor.undo().unwrap();
+        assert!(editor.can_undo() && editor.can_redo());
+        assert_eq!((editor.undo_depth(), editor.redo_depth()), (1, 1));
+        //a new step drops the undone ones
+        editor.write_char('c');
+        assert_eq!((editor.undo_depth(), editor.redo_depth()), (2, 0));
+    }
+
+    #[test]
+    fn test_modified_since_mark_follows_undo_history() {
+        let mut editor = create_editor_with_editor_content(vec![String::new()]);
+        editor.write_char('a');
+        let mark = editor.undo_mark();
+        assert!(!editor.is_modified_since(mark));
+
+        editor.write_char('b');
+        assert!(editor.is_modified_since(mark));
+        editor.undo().unwrap();
+        assert!(!editor.is_modified_since(mark));
+        editor.undo().unwrap();
+        assert!(editor.is_modified_since(mark));
+        editor.redo().unwrap();
+        assert!(!editor.is_modified_since(mark));
+
+        //the same depth reached by another edit is another state
+        editor.undo().unwrap();
+        editor.write_char('a');
+        assert_eq!(editor.editor_content, vec!["a"]);
+        assert!(editor.is_modified_since(mark));
+    }
+
+    #[test]
+    fn test_restored_history_keeps_marks_apart() {
+        let mut editor = create_editor_with_editor_content(vec![String::new()]);
+        editor.write_char('a');
+        editor.write_char('b');
+        let (history, applied) = editor.undo_history();
+
+        let mut restored = create_editor_with_editor_content(vec!["a".to_string()]);
+        restored.restore_undo_history(history, applied - 1);
+        let mark = restored.undo_mark();
+        restored.redo().unwrap();
+        assert!(restored.is_modified_since(mark));
+        restored.undo().unwrap();
+        assert!(!restored.is_modified_since(mark));
+        restored.undo().unwrap();
+        assert!(restored.is_modified_since(mark));
+    }
+}
+
+#[cfg(test)]
+mod unit_editor_repeat_tests {
+    use super::super::editor::Editor;
+    use crate::input::input_action::{Direction, InputAction};
+
+    //init functions
+    fn create_editor_with_editor_content(vec: Vec<String>) -> Editor {
+        let mut editor = Editor::new();
+        editor.editor_content = vec.into();
+        editor.editor_height = 10; //since testing doesnt start ui.rs, height isnt set
+        editor
+    }
+
+    fn type_text(editor: &mut Editor, text: &str) {
+        for c in text.chars() {
+            editor
+                .handle_input_action(InputAction::WriteChar(c))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_repeat_insertion_on_three_lines() {
+        let mut editor =
+            create_editor_with_editor_content(vec![String::new(), String::new(), String::new()]);
+        type_text(&mut editor, "foo");
+        for _ in 0..2 {
+            editor
+                .handle_input_action(InputAction::MoveCursor(Direction::Down))
+                .unwrap();
+            editor
+                .handle_input_action(InputAction::RepeatLastChange)
+                .unwrap();
+        }
+
+        assert_eq!(editor.editor_content, vec!["foo", "foo", "foo"]);
+    }
+
+    #[test]
+    fn test_movement_ends_insertion_run() {
+        let mut editor = create_editor_with_editor_content(vec![String::new()]);
+        type_text(&mut editor, "ab");
+        editor
+            .handle_input_action(InputAction::MoveCursor(Direction::Left))
+            .unwrap();
+        type_text(&mut editor, "x");
+
+        assert_eq!(editor.last_change, vec![InputAction::WriteChar('x')]);
+    }
+
+    #[test]
+    fn test_failed_action_keeps_last_change() {
+        let mut editor = create_editor_with_editor_content(vec![String::new()]);
+        type_text(&mut editor, "ab");
+        //nothing was copied, so the paste fails
+        assert!(editor.handle_input_action(InputAction::PASTE).is_err());
+        assert_eq!(
+            editor.last_change,
+            vec![InputAction::WriteChar('a'), InputAction::WriteChar('b')]
+        );
+
+        editor
+            .handle_input_action(InputAction::RepeatLastChange)
+            .unwrap();
+        assert_eq!(editor.editor_content[0], "abab");
+    }
+
+    #[test]
+    fn test_repeat_delete() {
+        let mut editor = create_editor_with_editor_content(vec!["abcd".to_string()]);
+        editor.handle_input_action(InputAction::DELETE).unwrap();
+        editor
+            .handle_input_action(InputAction::RepeatLastChange)
+            .unwrap();
+
+        assert_eq!(editor.editor_content[0], "ad");
+    }
+
+    #[test]
+    fn test_repeat_is_single_undo_step() {
+        let mut editor = create_editor_with_editor_content(vec![String::new()]);
+        type_text(&mut editor, "foo");
+        editor
+            .handle_input_action(InputAction::RepeatLastChange)
+            .unwrap();
+        assert_eq!(editor.editor_content[0], "foofoo");
+
+        editor.undo().unwrap();
+        assert_eq!(editor.editor_content[0], "foo");
+    }
+
+    #[test]
+    fn test_repeat_with_no_change_does_nothing() {
+        let mut editor = create_editor_with_editor_content(vec!["abc".to_string()]);
+        editor
+            .handle_input_action(InputAction::RepeatLastChange)
+            .unwrap();
+        assert_eq!(editor.editor_content[0], "abc");
+    }
+}
+
+#[cfg(test)]
+mod unit_editor_completion_tests {
+    use super::super::editor::Editor;
+    use crate::input::input_action::{Direction, InputAction};
+
+    //init functions
+    fn create_editor_with_editor_content(vec: Vec<String>) -> Editor {
+        let mut editor = Editor::new();
+        editor.editor_content = vec.into();
+        editor.editor_height = 10; //since testing doesnt start ui.rs, height isnt set
+        editor
+    }
+
+    fn type_text(editor: &mut Editor, text: &str) {
+        for c in text.chars() {
+            editor
+                .handle_input_action(InputAction::WriteChar(c))
+                .unwrap();
+        }
+    }
+
+    fn editor_with_words() -> Editor {
+        let mut editor =
+            create_editor_with_editor_content(vec!["hello help world".to_string(), String::new()]);
+        editor.cursor.y = 1;
+        editor
+    }
+
+    #[test]
+    fn test_complete_cycles_through_buffer_words() {
+        let mut editor = editor_with_words();
+        type_text(&mut editor, "he");
+
+        editor
+            .handle_input_action(InputAction::CompleteNext)
+            .unwrap();
+        assert_eq!(editor.editor_content[1], "hello");
+        assert_eq!(editor.cursor.x, 5);
+        editor
+            .handle_input_action(InputAction::CompleteNext)
+            .unwrap();
+        assert_eq!(editor.editor_content[1], "help");
+        editor
+            .handle_input_action(InputAction::CompleteNext)
+            .unwrap();
+        assert_eq!(editor.editor_content[1], "he");
+        editor
+            .handle_input_action(InputAction::CompletePrevious)
+            .unwrap();
+        assert_eq!(editor.editor_content[1], "help");
+    }
+
+    #[test]
+    fn test_complete_keeps_case_of_typed_prefix() {
+        let mut editor = editor_with_words();
+        type_text(&mut editor, "WO");
+        editor
+            .handle_input_action(InputAction::CompleteNext)
+            .unwrap();
+        assert_eq!(editor.editor_content[1], "WOrld");
+    }
+
+    #[test]
+    fn test_cancel_restores_partial_word() {
+        let mut editor = editor_with_words();
+        type_text(&mut editor, "he");
+        editor
+            .handle_input_action(InputAction::CompleteNext)
+            .unwrap();
+        editor
+            .handle_input_action(InputAction::CancelCompletion)
+            .unwrap();
+        assert_eq!(editor.editor_content[1], "he");
+        assert_eq!(editor.cursor.x, 2);
+        assert!(!editor.is_completing());
+    }
+
+    #[test]
+    fn test_enter_accepts_without_new_line() {
+        let mut editor = editor_with_words();
+        type_text(&mut editor, "he");
+        editor
+            .handle_input_action(InputAction::CompleteNext)
+            .unwrap();
+        editor.handle_input_action(InputAction::ENTER).unwrap();
+        assert_eq!(editor.editor_content.len(), 2);
+        assert_eq!(editor.editor_content[1], "hello");
+        assert!(!editor.is_completing());
+    }
+
+    #[test]
+    fn test_typing_accepts_and_continues() {
+        let mut editor = editor_with_words();
+        type_text(&mut editor, "he");
+        editor
+            .handle_input_action(InputAction::CompleteNext)
+            .unwrap();
+        type_text(&mut editor, "!");
+        assert_eq!(editor.editor_content[1], "hello!");
+
+        editor
+            .handle_input_action(InputAction::MoveCursor(Direction::Left))
+            .unwrap();
+        assert!(!editor.is_completing());
+    }
+
+    #[test]
+    fn test_accepted_completion_undoes_as_one_step() {
+        let mut editor = editor_with_words();
+        type_text(&mut editor, "he");
+        editor
+            .handle_input_action(InputAction::CompleteNext)
+            .unwrap();
+        editor.handle_input_action(InputAction::ENTER).unwrap();
+
+        editor.handle_input_action(InputAction::UNDO).unwrap();
+        assert_eq!(editor.editor_content[1], "he");
+        editor.handle_input_action(InputAction::REDO).unwrap();
+        assert_eq!(editor.editor_content[1], "hello");
+    }
+
+    #[test]
+    fn test_completion_sees_words_typed_since_last_completion() {
+        let mut editor = editor_with_words();
+        type_text(&mut editor, "he");
+        editor
+            .handle_input_action(InputAction::CompleteNext)
+            .unwrap();
+        editor.handle_input_action(InputAction::ENTER).unwrap();
+        type_text(&mut editor, " zebra ze");
+        editor
+            .handle_input_action(InputAction::CompleteNext)
+            .unwrap();
+        assert_eq!(editor.editor_content[1], "hello zebra zebra");
+    }
+
+    #[test]
+    fn test_no_match_leaves_content() {
+        let mut editor = editor_with_words();
+        type_text(&mut editor, "xy");
+        editor
+            .handle_input_action(InputAction::CompleteNext)
+            .unwrap();
+        assert_eq!(editor.editor_content[1], "xy");
+        assert!(!editor.is_completing());
+    }
 }
@@ -0,0 +1,172 @@
+//guessing how a text is indented, so an opened file is edited in its own style
+
+use std::fmt;
+
+///space widths a text can be detected as indented by
+const SPACE_WIDTHS: [usize; 3] = [2, 4, 8];
+
+///indentation style of a text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+    Tabs,
+    //levels this many spaces apart
+    Spaces(usize),
+}
+
+impl fmt::Display for Indent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Indent::Tabs => write!(f, "tabs"),
+            Indent::Spaces(width) => write!(f, "{} spaces", width),
+        }
+    }
+}
+
+///guesses the indentation of lines. lines starting with a tab count for tabs, the others
+/// for spaces, with the width taken from how far each indents past the line before it.
+/// None if no line is indented or the space steps match none of 2, 4 or 8.
+///
+/// ```
+/// use calliglyph::core::editor::indent::{detect, Indent};
+///
+/// let python = ["def f():", "    if x:", "        return 1", "    return 2"];
+/// assert_eq!(detect(python), Some(Indent::Spaces(4)));
+/// assert_eq!(detect(["all:", "\tcc main.c"]), Some(Indent::Tabs));
+/// assert_eq!(detect(["no", "indentation"]), None);
+/// ```
+pub fn detect<'a>(lines: impl IntoIterator<Item = &'a str>) -> Option<Indent> {
+    let mut tab_lines = 0;
+    let mut space_lines = 0;
+    let mut steps = [0; SPACE_WIDTHS.len()];
+    //spaces indenting the last line that wasn't blank, None after a tab indented one
+    let mut previous = Some(0);
+    for line in lines {
+        let text = line.trim_start_matches([' ', '\t']);
+        if text.is_empty() {
+            continue;
+        }
+        let leading = &line[..line.len() - text.len()];
+        if leading.starts_with('\t') {
+            tab_lines += 1;
+            previous = None;
+            continue;
+        }
+        let spaces = leading.len();
+        //the " * " going on a block comment is alignment, not a level
+        if leading.contains('\t') || (text.starts_with('*') && spaces % 2 == 1) {
+            continue;
+        }
+        if spaces > 0 {
+            space_lines += 1;
+        }
+        if let Some(step) = previous.and_then(|previous| spaces.checked_sub(previous)) {
+            if let Some(i) = SPACE_WIDTHS.iter().position(|&width| width == step) {
+                steps[i] += 1;
+            }
+        }
+        previous = Some(spaces);
+    }
+    if tab_lines == 0 && space_lines == 0 {
+        return None;
+    }
+    if tab_lines >= space_lines {
+        return Some(Indent::Tabs);
+    }
+    //the narrowest width wins a tie, a file stepping by 2 and 4 alike nests by 2
+    let (i, &count) = steps
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by_key(|&(_, count)| count)?;
+    (count > 0).then_some(Indent::Spaces(SPACE_WIDTHS[i]))
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_indent_tests {
+    use super::*;
+
+    fn detect_text(text: &str) -> Option<Indent> {
+        detect(text.lines())
+    }
+
+    #[test]
+    fn test_python_four_spaces() {
+        let text = "\
+import os
+
+class Walker:
+    def __init__(self, root):
+        self.root = root
+
+    def walk(self):
+        for dir, _, files in os.walk(self.root):
+            for name in files:
+                yield os.path.join(dir, name)
+        return None
+";
+        assert_eq!(detect_text(text), Some(Indent::Spaces(4)));
+    }
+
+    #[test]
+    fn test_js_two_spaces_with_block_comment() {
+        let text = "\
+/**
+ * adds up the sizes
+ */
+function total(items) {
+  let sum = 0;
+  for (const item of items) {
+    if (item.size) {
+      sum += item.size;
+    }
+  }
+  return sum;
+}
+";
+        assert_eq!(detect_text(text), Some(Indent::Spaces(2)));
+    }
+
+    #[test]
+    fn test_makefile_tabs() {
+        let text = "\
+CC = cc
+
+all: main.o util.o
+\t$(CC) -o app main.o util.o
+
+clean:
+\trm -f *.o app
+";
+        assert_eq!(detect_text(text), Some(Indent::Tabs));
+    }
+
+    #[test]
+    fn test_mixed_goes_with_the_majority() {
+        let mostly_tabs = "fn a() {\n\tone();\n\ttwo();\n    three();\n}";
+        assert_eq!(detect_text(mostly_tabs), Some(Indent::Tabs));
+        let mostly_spaces = "fn a() {\n    one();\n    two();\n\tthree();\n}";
+        assert_eq!(detect_text(mostly_spaces), Some(Indent::Spaces(4)));
+        //continuation lines aligned past the level don't outvote it
+        let aligned = "a:\n    b\n    c(x,\n      y)\n    d:\n        e\n";
+        assert_eq!(detect_text(aligned), Some(Indent::Spaces(4)));
+    }
+
+    #[test]
+    fn test_eight_spaces() {
+        let text = "main:\n        push rbp\n        mov rbp, rsp\n";
+        assert_eq!(detect_text(text), Some(Indent::Spaces(8)));
+    }
+
+    #[test]
+    fn test_unindented_or_odd_steps_detect_nothing() {
+        assert_eq!(detect_text("one\ntwo\n\n   \nthree"), None);
+        assert_eq!(detect_text(""), None);
+        assert_eq!(detect_text("a\n   b\n      c"), None);
+    }
+}
@@ -0,0 +1,174 @@
+//checks that the cursor and the selection stay inside the content after an operation.
+// debug builds and the validate feature run them, a broken state panics in debug builds
+// and is clamped back into the content otherwise
+
+use super::line_index;
+use crate::core::position::Position;
+
+///checks run in debug builds, and in release builds with the validate feature
+pub(crate) const ENABLED: bool = cfg!(any(debug_assertions, feature = "validate"));
+
+///checks if pos is on a line of content and at most at its end. empty content only has
+/// its start
+pub(crate) fn in_bounds(content: &[String], pos: Position) -> bool {
+    match content.get(pos.line) {
+        Some(line) => pos.col <= line_index::char_count(line),
+        None => content.is_empty() && pos == Position::default(),
+    }
+}
+
+///the position nearest to pos inside content
+pub(crate) fn clamp(content: &[String], pos: Position) -> Position {
+    let Some(last) = content.len().checked_sub(1) else {
+        return Position::default();
+    };
+    let line = pos.line.min(last);
+    Position::new(line, pos.col.min(line_index::char_count(&content[line])))
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_invariants_tests {
+    use super::*;
+    use crate::core::cursor::CursorPosition;
+    use crate::core::editor::Editor;
+    use crate::core::position::Range;
+    use crate::input::input_action::{Direction, InputAction, Motion};
+    use std::panic::{self, AssertUnwindSafe};
+
+    fn lines(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_bounds_and_clamping() {
+        let content = lines(&["ab", "héllo"]);
+        assert!(in_bounds(&content, Position::new(0, 2)));
+        assert!(in_bounds(&content, Position::new(1, 5)));
+        assert!(!in_bounds(&content, Position::new(0, 3)));
+        assert!(!in_bounds(&content, Position::new(2, 0)));
+        assert!(in_bounds(&[], Position::default()));
+
+        assert_eq!(clamp(&content, Position::new(0, 9)), Position::new(0, 2));
+        assert_eq!(clamp(&content, Position::new(7, 9)), Position::new(1, 5));
+        assert_eq!(clamp(&[], Position::new(3, 3)), Position::default());
+    }
+
+    #[test]
+    fn test_clamping_repairs_positions_past_the_content() {
+        let mut editor = Editor::new();
+        editor.editor_content = lines(&["ab", "c"]).into();
+        editor.cursor.x = 7;
+        editor.cursor.y = 4;
+        editor.selection = Some(Range::new(Position::new(0, 5), Position::new(3, 0)));
+        assert_eq!(editor.invariant_violations().len(), 3);
+
+        editor.clamp_positions();
+        assert!(editor.invariant_violations().is_empty());
+        assert_eq!(
+            editor.selection,
+            Some(Range::new(Position::new(0, 2), Position::new(1, 0)))
+        );
+        assert_eq!((editor.cursor.x, editor.cursor.y), (1, 1));
+    }
+
+    #[test]
+    fn test_replacing_content_keeps_every_position_inside() {
+        let mut editor = Editor::new();
+        editor.editor_height = 10;
+        editor.content_replaced((0..100).map(|i| format!("line {}", i)).collect());
+        editor.set_cursor(CursorPosition::new(6, 80)).unwrap();
+        editor
+            .handle_input_action(InputAction::MoveSelectionCursor(Direction::Down))
+            .unwrap();
+        editor.toggle_bookmark();
+        editor.write_char('x');
+        assert_eq!(editor.scroll_offset, 72);
+
+        editor.content_replaced(lines(&["a", "bc", ""]));
+        assert!(editor.invariant_violations().is_empty());
+        assert_eq!((editor.cursor.x, editor.cursor.y), (0, 2));
+        assert_eq!(editor.visual_cursor_x, 0);
+        assert_eq!(editor.scroll_offset, 0);
+        assert!(editor.bookmarks.is_empty());
+        assert!(!editor.can_undo());
+
+        //the next key presses work on the new content
+        editor
+            .handle_input_action(InputAction::MoveCursor(Direction::Up))
+            .unwrap();
+        editor
+            .handle_input_action(InputAction::WriteChar('d'))
+            .unwrap();
+        assert_eq!(editor.editor_content, vec!["a", "dbc", ""]);
+    }
+
+    fn random_action(rng: &mut fastrand::Rng) -> InputAction {
+        let direction = match rng.u8(..4) {
+            0 => Direction::Up,
+            1 => Direction::Down,
+            2 => Direction::Left,
+            _ => Direction::Right,
+        };
+        let motion = match rng.u8(..6) {
+            0 => Motion::LineStart,
+            1 => Motion::LineEnd,
+            2 => Motion::WordLeft,
+            3 => Motion::WordRight,
+            4 => Motion::ParagraphUp,
+            _ => Motion::ParagraphDown,
+        };
+        match rng.u8(..20) {
+            0..=3 => InputAction::WriteChar(rng.choice(['a', ' ', 'é', '(', ')']).unwrap()),
+            4 => InputAction::ENTER,
+            5 => InputAction::BACKSPACE,
+            6 => InputAction::DELETE,
+            7 => InputAction::TAB,
+            8 => InputAction::MoveCursor(direction.clone()),
+            9 | 10 => InputAction::MoveSelectionCursor(direction.clone()),
+            11 => InputAction::MoveCursorTo(motion),
+            12 => InputAction::MoveSelectionCursorTo(motion),
+            13 => rng
+                .choice([InputAction::CUT, InputAction::COPY, InputAction::PASTE])
+                .unwrap(),
+            14 => rng.choice([InputAction::UNDO, InputAction::REDO]).unwrap(),
+            15 => InputAction::CutLine,
+            16 => InputAction::DuplicateSelection,
+            17 => InputAction::OpenLine(rng.bool()),
+            18 => rng
+                .choice([InputAction::SelectEnclosing, InputAction::SwapSelectionEnds])
+                .unwrap(),
+            _ => InputAction::InsertText("x\ny".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_random_edits_keep_cursor_and_selection_in_bounds() {
+        for seed in 0..500 {
+            let mut rng = fastrand::Rng::with_seed(seed);
+            let mut editor = Editor::new();
+            editor.editor_content = lines(&["fn main() {", "    (a, é)", "", "}"]).into();
+            editor.editor_height = 10;
+            let mut done = vec![];
+            for _ in 0..200 {
+                let action = random_action(&mut rng);
+                done.push(action.clone());
+                //a panic names the actions leading to it, so it can be replayed
+                let step = panic::catch_unwind(AssertUnwindSafe(|| {
+                    let _ = editor.handle_input_action(action);
+                    editor.invariant_violations()
+                }));
+                match step {
+                    Ok(violations) if violations.is_empty() => {}
+                    Ok(violations) => panic!("seed {}: {:?} after {:?}", seed, violations, done),
+                    Err(_) => panic!("seed {}: panicked after {:?}", seed, done),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//char and column lookups on a line, with fast paths so editing very long lines, like
+// minified json, doesn't decode the whole line on every keystroke
+
+use std::borrow::Cow;
+
+///byte index of the char at index x of line, the line length if x is past its end.
+/// only the bytes up to x are looked at when they are ascii.
+pub fn byte_index(line: &str, x: usize) -> usize {
+    let ascii_end = x.min(line.len());
+    if line.as_bytes()[..ascii_end].is_ascii() {
+        return ascii_end;
+    }
+    line.char_indices()
+        .nth(x)
+        .map(|(i, _)| i)
+        .unwrap_or(line.len())
+}
+
+///number of chars in line
+pub fn char_count(line: &str) -> usize {
+    if line.is_ascii() {
+        line.len()
+    } else {
+        line.chars().count()
+    }
+}
+
+///the ^X a control char other than tab is shown as, like ^L for a form feed. drawn as they
+/// are they would move the terminal cursor or start escape sequences.
+pub fn control_placeholder(c: char) -> Option<[char; 2]> {
+    match c {
+        '\t' => None,
+        '\0'..='\x1f' | '\x7f' => Some(['^', char::from(c as u8 ^ 0x40)]),
+        _ => None,
+    }
+}
+
+///checks if text has tabs or control chars, which take other than one column
+fn has_wide_chars(text: &str) -> bool {
+    //bytes of multibyte chars are all above these
+    text.bytes().any(|b| b < b' ' || b == 0x7f)
+}
+
+///column reached after c at column col, tabs jump to the next tab stop and control chars
+/// take the two of their placeholder
+fn advance(col: usize, c: char, tab_width: usize) -> usize {
+    if c == '\t' {
+        col + tab_width - col % tab_width
+    } else if control_placeholder(c).is_some() {
+        col + 2
+    } else {
+        col + 1
+    }
+}
+
+///column where the char at index x of line starts, tabs jump to the next tab stop and
+/// control chars are as wide as their placeholder
+pub fn visual_column(line: &str, x: usize, tab_width: usize) -> usize {
+    let prefix = &line[..byte_index(line, x)];
+    if !has_wide_chars(prefix) {
+        return char_count(prefix);
+    }
+    prefix.chars().fold(0, |col, c| advance(col, c, tab_width))
+}
+
+///the column of the char last looked up on a line, so moving the cursor along a long line
+/// only measures the chars it moved over instead of everything before it
+#[derive(Debug, Default)]
+pub struct ColumnCache {
+    //revision, line index, address and length of the line the lookup was made on
+    key: Option<(u64, usize, usize, usize)>,
+    x: usize,
+    byte: usize,
+    col: usize,
+}
+
+impl ColumnCache {
+    ///column where the char at index x of line y starts, like visual_column. starts from
+    /// the last lookup on the same line at the same revision.
+    pub fn visual_column(
+        &mut self,
+        revision: u64,
+        y: usize,
+        line: &str,
+        x: usize,
+        tab_width: usize,
+    ) -> usize {
+        let key = (revision, y, line.as_ptr() as usize, line.len());
+        if self.key != Some(key) {
+            (self.x, self.byte, self.col) = (0, 0, 0);
+            self.key = Some(key);
+        }
+        if x < self.x && !self.back_to(line, x) {
+            (self.x, self.byte, self.col) = (0, 0, 0);
+        }
+        for c in line[self.byte..].chars().take(x - self.x) {
+            self.x += 1;
+            self.byte += c.len_utf8();
+            self.col = advance(self.col, c, tab_width);
+        }
+        self.col
+    }
+
+    ///moves the lookup back to x, false if a tab is on the way. the width of a tab depends
+    /// on where it starts, so the column before it can't be told from the one after it.
+    fn back_to(&mut self, line: &str, x: usize) -> bool {
+        let passed: Vec<char> = line[..self.byte].chars().rev().take(self.x - x).collect();
+        if passed.contains(&'\t') {
+            return false;
+        }
+        for c in passed {
+            self.x -= 1;
+            self.byte -= c.len_utf8();
+            self.col -= if control_placeholder(c).is_some() {
+                2
+            } else {
+                1
+            };
+        }
+        true
+    }
+}
+
+///the part of line shown from column start on, at most width columns wide, with tabs
+/// expanded to spaces and control chars replaced by their placeholder. only the chars up
+/// to the end of the shown part are looked at, and a start of the line without either is
+/// borrowed rather than copied.
+pub fn visible_slice(line: &str, start: usize, width: usize, tab_width: usize) -> Cow<'_, str> {
+    if start == 0 {
+        let shown = &line[..byte_index(line, width)];
+        if !has_wide_chars(shown) {
+            return Cow::Borrowed(shown);
+        }
+    }
+    let end = start.saturating_add(width);
+    let mut out = String::new();
+    let mut col = 0;
+    for c in line.chars() {
+        if col >= end {
+            break;
+        }
+        let next = advance(col, c, tab_width);
+        if c == '\t' {
+            //a tab cut by the left edge only shows its remaining spaces
+            let shown = next.min(end) - col.max(start).min(next);
+            out.extend(std::iter::repeat_n(' ', shown));
+        } else if let Some(placeholder) = control_placeholder(c) {
+            //like a tab, a placeholder cut by either edge shows what is left of it
+            out.extend(
+                (col..next)
+                    .zip(placeholder)
+                    .filter(|(at, _)| (start..end).contains(at))
+                    .map(|(_, c)| c),
+            );
+        } else if col >= start {
+            out.push(c);
+        }
+        col = next;
+    }
+    Cow::Owned(out)
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_line_index_tests {
+    use super::*;
+
+    #[test]
+    fn test_column_cache_matches_visual_column_both_ways() {
+        let line = "a\tbæ\x01cd\tef";
+        let mut cache = ColumnCache::default();
+        for x in [3, 5, 9, 12, 11, 6, 2, 0, 20, 7] {
+            assert_eq!(
+                cache.visual_column(0, 0, line, x, 4),
+                visual_column(line, x, 4),
+                "x {}",
+                x
+            );
+        }
+        //another revision measures from the start again
+        let edited = "\t\tx";
+        assert_eq!(cache.visual_column(1, 0, edited, 3, 4), 9);
+    }
+
+    #[test]
+    fn test_byte_index_of_ascii_and_multibyte_lines() {
+        assert_eq!(byte_index("abc", 2), 2);
+        assert_eq!(byte_index("abc", 9), 3);
+        assert_eq!(byte_index("æøå", 2), 4);
+        assert_eq!(byte_index("æøå", 9), 6);
+        //only the ascii start of a line is looked at
+        assert_eq!(byte_index("ab æ", 2), 2);
+        assert_eq!(byte_index("ab æ", 4), 5);
+    }
+
+    #[test]
+    fn test_char_count() {
+        assert_eq!(char_count("æøå"), 3);
+        assert_eq!(char_count("abc"), 3);
+    }
+
+    #[test]
+    fn test_visual_column_expands_tabs() {
+        assert_eq!(visual_column("abc", 2, 4), 2);
+        assert_eq!(visual_column("a\tb", 2, 4), 4);
+        assert_eq!(visual_column("\t\tx", 2, 4), 8);
+    }
+
+    #[test]
+    fn test_visible_slice_cuts_both_ends() {
+        assert_eq!(visible_slice("abcdefgh", 0, 3, 4), "abc");
+        assert_eq!(visible_slice("abcdefgh", 5, 10, 4), "fgh");
+        assert_eq!(visible_slice("a\tb", 0, 10, 4), "a   b");
+        assert_eq!(visible_slice("a\tb", 2, 10, 4), "  b");
+        assert_eq!(visible_slice("a\tb", 0, 2, 4), "a ");
+    }
+
+    #[test]
+    fn test_control_chars_show_as_two_column_placeholders() {
+        assert_eq!(control_placeholder('\x0c'), Some(['^', 'L']));
+        assert_eq!(control_placeholder('\x1b'), Some(['^', '[']));
+        assert_eq!(control_placeholder('\x7f'), Some(['^', '?']));
+        assert_eq!(control_placeholder('\t'), None);
+        assert_eq!(control_placeholder('a'), None);
+
+        assert_eq!(visual_column("a\x0cb", 2, 4), 3);
+        assert_eq!(visual_column("\x1b\tx", 2, 4), 4);
+        assert_eq!(visible_slice("a\x0cb\x1b[0m", 0, 20, 4), "a^Lb^[[0m");
+        assert_eq!(visible_slice("a\x0cb", 2, 10, 4), "Lb");
+        assert_eq!(visible_slice("a\x0cb", 0, 2, 4), "a^");
+    }
+
+    #[test]
+    fn test_visible_slice_borrows_start_of_line_without_tabs() {
+        assert!(matches!(
+            visible_slice("abcdef", 0, 3, 4),
+            Cow::Borrowed("abc")
+        ));
+        assert!(matches!(visible_slice("a\tb", 0, 9, 4), Cow::Owned(_)));
+    }
+}
@@ -0,0 +1,232 @@
+use super::edit_events::LineShift;
+use crate::core::cursor::CursorPosition;
+use crate::input::input_action::InputAction;
+use std::collections::BTreeMap;
+
+///state of a mark key chord waiting for the mark name to be typed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MarkChord {
+    Set,
+    Jump,
+}
+
+/// handles named positions in the editor content, which follow their text when lines
+/// are inserted or deleted above them
+#[derive(Debug, Default)]
+pub struct Marks {
+    marks: BTreeMap<char, CursorPosition>,
+    pub pending_chord: Option<MarkChord>,
+}
+
+impl Marks {
+    pub fn new() -> Self {
+        Self {
+            marks: BTreeMap::new(),
+            pending_chord: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.marks.is_empty()
+    }
+
+    ///stores pos under name, replacing an older mark with the same name
+    pub fn set(&mut self, name: char, pos: CursorPosition) {
+        self.marks.insert(name, pos);
+    }
+
+    pub fn get(&self, name: char) -> Option<CursorPosition> {
+        self.marks.get(&name).copied()
+    }
+
+    ///feeds an action to a pending chord, returns the chord once a mark name is typed
+    pub fn feed_chord(&mut self, action: &InputAction) -> Option<(MarkChord, char)> {
+        let chord = self.pending_chord.take()?;
+        match action {
+            InputAction::WriteChar(c) | InputAction::ModalKey(c) if c.is_ascii_alphabetic() => {
+                Some((chord, *c))
+            }
+            //any other action aborts the chord
+            _ => None,
+        }
+    }
+
+    ///lists the marks with their position and the text of their line
+    pub fn describe(&self, content: &[String]) -> Vec<String> {
+        self.marks
+            .iter()
+            .map(|(name, pos)| {
+                let line = content.get(pos.y).map(|l| l.trim()).unwrap_or("");
+                format!("{}: ({}, {}) {}", name, pos.x, pos.y, line)
+            })
+            .collect()
+    }
+
+    ///moves marks along with the lines an edit inserted or deleted. marks on deleted lines
+    /// move to the nearest surviving line.
+    pub fn shift(&mut self, shift: &LineShift) {
+        for pos in self.marks.values_mut() {
+            match shift.line(pos.y) {
+                Some(y) => pos.y = y,
+                None => {
+                    //the last line the deleted ones were replaced by, or the one after them
+                    pos.y = shift.first + shift.added.saturating_sub(1);
+                    pos.x = 0;
+                }
+            }
+        }
+    }
+
+    ///moves marks along with lines inserted or deleted between old and new content, for
+    /// content replaced as a whole where no edits tell what changed. marks on deleted
+    /// lines move to the nearest surviving line.
+    pub fn adjust(&mut self, old: &[String], new: &[String]) {
+        let Some((prefix, old_end, new_end)) = changed_lines(old, new) else {
+            return;
+        };
+        let last_line = new.len().saturating_sub(1);
+
+        for pos in self.marks.values_mut() {
+            if pos.y < prefix {
+                continue;
+            }
+            if pos.y >= old_end {
+                pos.y = pos.y + new_end - old_end;
+            } else if pos.y >= new_end {
+                //line of the mark was deleted
+                pos.y = if new_end > prefix {
+                    new_end - 1
+                } else {
+                    new_end
+                };
+                pos.x = 0;
+            }
+            pos.y = pos.y.min(last_line);
+        }
+    }
+}
+
+///the block of lines that changed between old and new content, as the first changed line
+/// and the end of the block in old and in new. None if no lines were inserted or deleted,
+/// edits within lines keep everything on its line.
+pub(crate) fn changed_lines(old: &[String], new: &[String]) -> Option<(usize, usize, usize)> {
+    if old.len() == new.len() {
+        return None;
+    }
+    //the changed block sits between the unchanged lines at the start and the end
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let suffix = old
+        .iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    Some((prefix, old.len() - suffix, new.len() - suffix))
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_marks_tests {
+    use super::*;
+
+    fn lines(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn marks_at(y: usize) -> Marks {
+        let mut marks = Marks::new();
+        marks.set('a', CursorPosition { x: 2, y });
+        marks
+    }
+
+    #[test]
+    fn test_insert_above_moves_mark_down() {
+        let mut marks = marks_at(2);
+        marks.adjust(
+            &lines(&["one", "two", "three"]),
+            &lines(&["new", "one", "two", "three"]),
+        );
+        assert_eq!(marks.get('a'), Some(CursorPosition { x: 2, y: 3 }));
+    }
+
+    #[test]
+    fn test_delete_above_moves_mark_up() {
+        let mut marks = marks_at(2);
+        marks.adjust(&lines(&["one", "two", "three"]), &lines(&["two", "three"]));
+        assert_eq!(marks.get('a'), Some(CursorPosition { x: 2, y: 1 }));
+    }
+
+    #[test]
+    fn test_change_below_keeps_mark() {
+        let mut marks = marks_at(0);
+        marks.adjust(&lines(&["one", "two"]), &lines(&["one", "two", "three"]));
+        assert_eq!(marks.get('a'), Some(CursorPosition { x: 2, y: 0 }));
+    }
+
+    #[test]
+    fn test_split_of_marked_line_keeps_mark() {
+        let mut marks = marks_at(1);
+        marks.adjust(
+            &lines(&["one", "two", "three"]),
+            &lines(&["one", "t", "wo", "three"]),
+        );
+        assert_eq!(marks.get('a'), Some(CursorPosition { x: 2, y: 1 }));
+    }
+
+    #[test]
+    fn test_deleted_line_clamps_to_next_surviving_line() {
+        let mut marks = marks_at(1);
+        marks.adjust(&lines(&["one", "two", "three"]), &lines(&["one", "three"]));
+        assert_eq!(marks.get('a'), Some(CursorPosition { x: 0, y: 1 }));
+    }
+
+    #[test]
+    fn test_deleted_last_line_clamps_to_previous_line() {
+        let mut marks = marks_at(2);
+        marks.adjust(&lines(&["one", "two", "three"]), &lines(&["one", "two"]));
+        assert_eq!(marks.get('a'), Some(CursorPosition { x: 0, y: 1 }));
+    }
+
+    #[test]
+    fn test_shift_moves_marks_below_and_clamps_deleted_ones() {
+        let mut marks = Marks::new();
+        marks.set('a', CursorPosition { x: 2, y: 1 });
+        marks.set('b', CursorPosition { x: 2, y: 3 });
+        marks.set('c', CursorPosition { x: 2, y: 5 });
+        marks.shift(&LineShift {
+            first: 2,
+            removed: 3,
+            added: 1,
+        });
+        assert_eq!(marks.get('a'), Some(CursorPosition { x: 2, y: 1 }));
+        assert_eq!(marks.get('b'), Some(CursorPosition { x: 0, y: 2 }));
+        assert_eq!(marks.get('c'), Some(CursorPosition { x: 2, y: 3 }));
+
+        marks.shift(&LineShift {
+            first: 3,
+            removed: 1,
+            added: 0,
+        });
+        assert_eq!(marks.get('c'), Some(CursorPosition { x: 0, y: 3 }));
+    }
+
+    #[test]
+    fn test_chord_takes_letter_and_aborts_on_other_action() {
+        let mut marks = Marks::new();
+        marks.pending_chord = Some(MarkChord::Set);
+        assert_eq!(
+            marks.feed_chord(&InputAction::WriteChar('b')),
+            Some((MarkChord::Set, 'b'))
+        );
+        marks.pending_chord = Some(MarkChord::Jump);
+        assert_eq!(marks.feed_chord(&InputAction::ENTER), None);
+        assert!(marks.pending_chord.is_none());
+    }
+}
@@ -1,7 +1,40 @@
+//keywords like TODO in the content
+pub mod annotations;
+//line storage and the primitive edits on it
+pub mod buffer;
+//bookmarked lines shown in the gutter
+pub mod bookmarks;
+//letter case and naming convention conversions
+pub mod case;
+//word completion from the buffer contents
+pub mod completion;
+//change notifications for edits of the content
+pub mod edit_events;
 //main core editor
+#[allow(clippy::module_inception)]
 pub mod editor;
+//guessing the indentation style of a text
+pub mod indent;
+//cursor and selection bounds checks after operations
+pub mod invariants;
+//char, byte and column lookups on long lines
+pub mod line_index;
+//named positions following their text
+pub mod marks;
+//incrementing and decrementing numbers in lines
+pub mod numbers;
+//re-breaking paragraphs at a width
+pub mod reflow;
+//converting indentation between tabs and spaces
+pub mod retab;
+//line, word and byte counts
+pub mod stats;
+//bracket and quote matching for selections
+pub mod text_objects;
 //undo redo utility
 pub mod undo_redo;
+//which chars make up a word
+pub mod words;
 
 // Re-export the Editor struct for simpler imports elsewhere
 pub use editor::Editor;
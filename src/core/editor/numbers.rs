@@ -0,0 +1,148 @@
+///a number found in a line, positions in chars with end exclusive
+#[derive(Debug, Clone, PartialEq)]
+struct NumberSpan {
+    start: usize,
+    end: usize,
+    hex: bool,
+}
+
+///finds the first number that contains x or ends right before it, or else the next one after x.
+/// decimals may have a leading minus sign, hex numbers need a 0x prefix.
+fn find_number(chars: &[char], x: usize) -> Option<NumberSpan> {
+    let mut i = 0;
+    while i < chars.len() {
+        let is_hex = chars[i] == '0'
+            && matches!(chars.get(i + 1), Some('x' | 'X'))
+            && chars.get(i + 2).is_some_and(|c| c.is_ascii_hexdigit());
+        let span = if is_hex {
+            let end = run_end(chars, i + 2, |c| c.is_ascii_hexdigit());
+            Some(NumberSpan {
+                start: i,
+                end,
+                hex: true,
+            })
+        } else if chars[i].is_ascii_digit() {
+            let start = if i > 0 && chars[i - 1] == '-' {
+                i - 1
+            } else {
+                i
+            };
+            let end = run_end(chars, i, |c| c.is_ascii_digit());
+            Some(NumberSpan {
+                start,
+                end,
+                hex: false,
+            })
+        } else {
+            None
+        };
+
+        match span {
+            Some(span) if span.end >= x => return Some(span),
+            Some(span) => i = span.end,
+            None => i += 1,
+        }
+    }
+    None
+}
+
+///index after the run of chars matching f starting at i
+fn run_end(chars: &[char], i: usize, f: impl Fn(char) -> bool) -> usize {
+    i + chars[i..].iter().take_while(|c| f(**c)).count()
+}
+
+///adds delta to the number at or after x in line, returns the new line and the position
+/// right after the changed number. leading zeros keep the width of the number,
+/// hex numbers keep their width and letter case.
+pub fn change_number(line: &str, x: usize, delta: i64) -> Option<(String, usize)> {
+    let chars: Vec<char> = line.chars().collect();
+    let span = find_number(&chars, x)?;
+    let text: String = chars[span.start..span.end].iter().collect();
+
+    let replacement = if span.hex {
+        let digits = &text[2..];
+        let value = u64::from_str_radix(digits, 16).ok()?;
+        let new = value.wrapping_add(delta as u64);
+        let width = digits.len();
+        let formatted = if digits.chars().any(|c| c.is_ascii_uppercase()) {
+            format!("{:0width$X}", new)
+        } else {
+            format!("{:0width$x}", new)
+        };
+        format!("{}{}", &text[..2], formatted)
+    } else {
+        let value: i128 = text.parse().ok()?;
+        let new = value + delta as i128;
+        let digits = text.trim_start_matches('-');
+        //only zero padded numbers keep their width
+        let width = if digits.len() > 1 && digits.starts_with('0') {
+            digits.len()
+        } else {
+            0
+        };
+        let sign = if new < 0 { "-" } else { "" };
+        format!("{}{:0width$}", sign, new.unsigned_abs())
+    };
+
+    let end = span.start + replacement.chars().count();
+    let new_line: String = chars[..span.start]
+        .iter()
+        .copied()
+        .chain(replacement.chars())
+        .chain(chars[span.end..].iter().copied())
+        .collect();
+    Some((new_line, end))
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_numbers_tests {
+    use super::*;
+
+    fn change(line: &str, x: usize, delta: i64) -> Option<String> {
+        change_number(line, x, delta).map(|(line, _)| line)
+    }
+
+    #[test]
+    fn test_number_under_and_after_cursor() {
+        assert_eq!(change("a 41 b", 2, 1), Some("a 42 b".to_string()));
+        assert_eq!(change("a 41 b", 0, 1), Some("a 42 b".to_string()));
+        //cursor right after the number
+        assert_eq!(change("a 41 b", 4, 1), Some("a 42 b".to_string()));
+        assert_eq!(change("1 and 9", 3, 1), Some("1 and 10".to_string()));
+        assert_eq!(change("no numbers", 0, 1), None);
+    }
+
+    #[test]
+    fn test_negative_numbers() {
+        assert_eq!(change("x = -1", 0, 3), Some("x = 2".to_string()));
+        assert_eq!(change("x = 2", 0, -5), Some("x = -3".to_string()));
+    }
+
+    #[test]
+    fn test_leading_zeros_are_kept() {
+        assert_eq!(change("file007", 0, 1), Some("file008".to_string()));
+        assert_eq!(change("099", 0, 1), Some("100".to_string()));
+        assert_eq!(change("010", 0, -11), Some("-001".to_string()));
+    }
+
+    #[test]
+    fn test_hex_numbers() {
+        assert_eq!(change("0x0f", 0, 1), Some("0x10".to_string()));
+        assert_eq!(change("0xFF", 0, 1), Some("0x100".to_string()));
+        assert_eq!(change("0x00FF", 0, -1), Some("0x00FE".to_string()));
+    }
+
+    #[test]
+    fn test_returns_position_after_number() {
+        assert_eq!(
+            change_number("a 9 b", 0, 1),
+            Some(("a 10 b".to_string(), 4))
+        );
+    }
+}
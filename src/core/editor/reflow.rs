@@ -0,0 +1,121 @@
+///checks if c can be part of a line prefix kept by reflow, like the > of a quote or // of a comment
+fn is_prefix_char(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '>' | '/' | '#' | ';' | '%' | '!')
+}
+
+///checks if line separates paragraphs, having nothing besides prefix chars
+pub fn is_blank(line: &str) -> bool {
+    line.chars().all(is_prefix_char)
+}
+
+///longest prefix made of prefix chars shared by every line
+fn common_prefix(lines: &[String]) -> String {
+    let mut prefix: Vec<char> = lines
+        .first()
+        .map(|line| line.chars().take_while(|c| is_prefix_char(*c)).collect())
+        .unwrap_or_default();
+    for line in &lines[1.min(lines.len())..] {
+        let shared = prefix
+            .iter()
+            .zip(line.chars())
+            .take_while(|(a, b)| *a == b)
+            .count();
+        prefix.truncate(shared);
+    }
+    prefix.into_iter().collect()
+}
+
+///re-breaks each paragraph of lines at word boundaries so no line exceeds width chars,
+/// unless a single word is longer. blank lines are kept, and every produced line gets the
+/// prefix shared by the lines of its paragraph.
+pub fn reflow_lines(lines: Vec<String>, width: usize) -> Vec<String> {
+    let mut out = vec![];
+    let mut paragraph: Vec<String> = vec![];
+    for line in lines {
+        if is_blank(&line) {
+            out.extend(reflow_paragraph(&std::mem::take(&mut paragraph), width));
+            out.push(line);
+        } else {
+            paragraph.push(line);
+        }
+    }
+    out.extend(reflow_paragraph(&paragraph, width));
+    out
+}
+
+fn reflow_paragraph(lines: &[String], width: usize) -> Vec<String> {
+    if lines.is_empty() {
+        return vec![];
+    }
+    let prefix = common_prefix(lines);
+    let prefix_len = prefix.chars().count();
+
+    let mut out = vec![];
+    let mut current = prefix.clone();
+    let mut current_len = prefix_len;
+    for word in lines
+        .iter()
+        .flat_map(|line| line[prefix.len()..].split_whitespace())
+    {
+        let word_len = word.chars().count();
+        if current_len > prefix_len && current_len + 1 + word_len > width {
+            out.push(std::mem::replace(&mut current, prefix.clone()));
+            current_len = prefix_len;
+        }
+        if current_len > prefix_len {
+            current.push(' ');
+            current_len += 1;
+        }
+        current.push_str(word);
+        current_len += word_len;
+    }
+    out.push(current);
+    out
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_reflow_tests {
+    use super::*;
+
+    fn lines(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_reflow_breaks_at_width() {
+        let reflowed = reflow_lines(lines(&["one two three four", "five six"]), 10);
+        assert_eq!(reflowed, lines(&["one two", "three four", "five six"]));
+    }
+
+    #[test]
+    fn test_reflow_joins_short_lines() {
+        let reflowed = reflow_lines(lines(&["a", "b", "c"]), 80);
+        assert_eq!(reflowed, lines(&["a b c"]));
+    }
+
+    #[test]
+    fn test_reflow_keeps_common_prefix() {
+        let reflowed = reflow_lines(lines(&["// alpha beta", "// gamma delta"]), 14);
+        assert_eq!(reflowed, lines(&["// alpha beta", "// gamma delta"]));
+        let reflowed = reflow_lines(lines(&["> a b c d e f"]), 7);
+        assert_eq!(reflowed, lines(&["> a b c", "> d e f"]));
+    }
+
+    #[test]
+    fn test_reflow_keeps_blank_lines_between_paragraphs() {
+        let reflowed = reflow_lines(lines(&["a", "b", "", "c", "d"]), 80);
+        assert_eq!(reflowed, lines(&["a b", "", "c d"]));
+    }
+
+    #[test]
+    fn test_long_word_gets_its_own_line() {
+        let reflowed = reflow_lines(lines(&["a verylongword b"]), 5);
+        assert_eq!(reflowed, lines(&["a", "verylongword", "b"]));
+    }
+}
@@ -0,0 +1,116 @@
+///column reached after c at column col, tabs jump to the next tab stop
+fn advance(col: usize, c: char, tab_width: usize) -> usize {
+    if c == '\t' {
+        col + tab_width - col % tab_width
+    } else {
+        col + 1
+    }
+}
+
+///whitespace spanning from column start to end, as spaces or as tabs filled up with spaces
+fn whitespace(start: usize, end: usize, tab_width: usize, insert_spaces: bool) -> String {
+    if insert_spaces {
+        return " ".repeat(end - start);
+    }
+    let mut out = String::new();
+    let mut col = start;
+    //a lone space is kept even if it happens to end on a tab stop
+    while end - start > 1 && col + tab_width - col % tab_width <= end {
+        out.push('\t');
+        col += tab_width - col % tab_width;
+    }
+    out.push_str(&" ".repeat(end - col));
+    out
+}
+
+///rewrites the leading indentation of line with tabs or spaces, keeping its width.
+/// with all, whitespace between words is converted too, keeping every char in its column.
+pub fn retab_line(line: &str, tab_width: usize, insert_spaces: bool, all: bool) -> String {
+    let mut out = String::new();
+    let mut col = 0;
+    let mut run_start: Option<usize> = None;
+    let mut in_indent = true;
+
+    for c in line.chars() {
+        let convert = c == ' ' || c == '\t';
+        if convert && (in_indent || all) {
+            run_start.get_or_insert(col);
+        } else {
+            if let Some(start) = run_start.take() {
+                out.push_str(&whitespace(start, col, tab_width, insert_spaces));
+            }
+            if !convert {
+                in_indent = false;
+            }
+            out.push(c);
+        }
+        col = advance(col, c, tab_width);
+    }
+    if let Some(start) = run_start {
+        out.push_str(&whitespace(start, col, tab_width, insert_spaces));
+    }
+    out
+}
+
+///column where the char at index x of line starts
+pub fn column_at(line: &str, x: usize, tab_width: usize) -> usize {
+    line.chars()
+        .take(x)
+        .fold(0, |col, c| advance(col, c, tab_width))
+}
+
+///index of the last char of line starting at or before column
+pub fn index_at_column(line: &str, column: usize, tab_width: usize) -> usize {
+    let mut col = 0;
+    for (i, c) in line.chars().enumerate() {
+        let next = advance(col, c, tab_width);
+        if next > column {
+            return i;
+        }
+        col = next;
+    }
+    line.chars().count()
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_retab_tests {
+    use super::*;
+
+    #[test]
+    fn test_tabs_to_spaces_in_indent_only() {
+        assert_eq!(retab_line("\t\tx\ty", 4, true, false), "        x\ty");
+    }
+
+    #[test]
+    fn test_spaces_to_tabs_in_indent_only() {
+        assert_eq!(retab_line("      x  y", 4, false, false), "\t  x  y");
+    }
+
+    #[test]
+    fn test_mixed_indent_normalises_by_width() {
+        //space then tab reaches the first tab stop
+        assert_eq!(retab_line(" \t  x", 4, false, false), "\t  x");
+        assert_eq!(retab_line(" \t  x", 4, true, false), "      x");
+    }
+
+    #[test]
+    fn test_all_converts_body_keeping_columns() {
+        assert_eq!(retab_line("ab\tc", 4, true, true), "ab  c");
+        assert_eq!(retab_line("ab  c d", 4, false, true), "ab\tc d");
+    }
+
+    #[test]
+    fn test_column_mapping() {
+        assert_eq!(column_at("\tx", 1, 4), 4);
+        assert_eq!(index_at_column("    x", 4, 4), 4);
+        assert_eq!(index_at_column("\tx", 4, 4), 1);
+        //inside a tab maps to the tab itself
+        assert_eq!(index_at_column("\tx", 2, 4), 0);
+    }
+}
@@ -0,0 +1,261 @@
+use super::edit_events::EditEvent;
+use std::fmt;
+use std::ops::Range;
+
+///line, word, char and byte counts of some text, like wc
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TextStats {
+    pub lines: usize,
+    pub words: usize,
+    pub chars: usize,
+    ///size of the text in UTF-8, which differs from chars for non ascii text
+    pub bytes: usize,
+}
+
+impl TextStats {
+    ///counts lines joined by line breaks, which count as a char and a byte each.
+    /// words are split on unicode whitespace.
+    pub fn of_lines(lines: &[String]) -> Self {
+        let breaks = lines.len().saturating_sub(1);
+        let mut stats = Self {
+            lines: lines.len(),
+            words: 0,
+            chars: breaks,
+            bytes: breaks,
+        };
+        for line in lines {
+            let line = LineStats::of(line);
+            stats.words += line.words;
+            stats.chars += line.chars;
+            stats.bytes += line.bytes;
+        }
+        stats
+    }
+}
+
+///word, char and byte counts of a single line, its line break left out
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct LineStats {
+    words: usize,
+    chars: usize,
+    bytes: usize,
+}
+
+impl LineStats {
+    fn of(line: &str) -> Self {
+        Self {
+            words: line.split_whitespace().count(),
+            chars: line.chars().count(),
+            bytes: line.len(),
+        }
+    }
+
+    fn add(&mut self, other: Self) {
+        self.words += other.words;
+        self.chars += other.chars;
+        self.bytes += other.bytes;
+    }
+
+    fn sub(&mut self, other: Self) {
+        self.words -= other.words;
+        self.chars -= other.chars;
+        self.bytes -= other.bytes;
+    }
+}
+
+impl fmt::Display for TextStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} lines, {} words, {} chars, {} bytes",
+            self.lines, self.words, self.chars, self.bytes
+        )
+    }
+}
+
+///stats of the editor content, kept per line so an edit only has the lines it touched
+/// counted again instead of the whole content
+#[derive(Debug, Default)]
+pub struct StatsCache {
+    //counts of every line, None for lines changed since they were counted. empty until
+    // the content is first counted
+    lines: Vec<Option<LineStats>>,
+    //sum of the counted lines
+    total: LineStats,
+    //lines to count again, covering every None in lines
+    dirty: Option<Range<usize>>,
+}
+
+impl StatsCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///follows an edit of the content, the lines it touched are counted when next asked
+    pub fn apply(&mut self, event: &EditEvent) {
+        let breaks = |text: &str| text.matches('\n').count();
+        let (start, removed, added) = match event {
+            EditEvent::Inserted { at, text } => (at.y, 1, 1 + breaks(text)),
+            EditEvent::Deleted { range, .. } => (range.start.y, range.end.y - range.start.y + 1, 1),
+            EditEvent::Replaced { range, new, .. } => (
+                range.start.y,
+                range.end.y - range.start.y + 1,
+                1 + breaks(new),
+            ),
+        };
+        self.lines_replaced(start, removed, added);
+    }
+
+    ///marks line y as changed by something that isn't reported as an edit
+    pub fn line_changed(&mut self, y: usize) {
+        self.lines_replaced(y, 1, 1);
+    }
+
+    ///forgets every count, for content replaced as a whole
+    pub fn invalidate(&mut self) {
+        *self = Self::default();
+    }
+
+    ///removed lines from start on were replaced by added ones, counted when next asked
+    pub fn lines_replaced(&mut self, start: usize, removed: usize, added: usize) {
+        let end = start + removed;
+        if end > self.lines.len() {
+            //the content wasn't counted yet, or changed without being reported
+            self.invalidate();
+            return;
+        }
+        let old = self
+            .lines
+            .splice(start..end, std::iter::repeat_n(None, added));
+        for line in old.flatten() {
+            self.total.sub(line);
+        }
+        let shift = |y: usize| match y {
+            y if y <= start => y,
+            y if y >= end => y - removed + added,
+            _ => start + added,
+        };
+        let touched = start..start + added;
+        self.dirty = Some(match self.dirty.take() {
+            Some(dirty) => shift(dirty.start).min(touched.start)..shift(dirty.end).max(touched.end),
+            None => touched,
+        });
+    }
+
+    ///counts of content, counting only the lines changed since the last call
+    pub fn stats(&mut self, content: &[String]) -> TextStats {
+        if self.lines.len() != content.len() {
+            self.lines = vec![None; content.len()];
+            self.total = LineStats::default();
+            self.dirty = Some(0..content.len());
+        }
+        if let Some(dirty) = self.dirty.take() {
+            let end = dirty.end.min(content.len());
+            for (slot, text) in self.lines[dirty.start..end]
+                .iter_mut()
+                .zip(&content[dirty.start..end])
+            {
+                let line = LineStats::of(text);
+                if let Some(old) = slot.replace(line) {
+                    self.total.sub(old);
+                }
+                self.total.add(line);
+            }
+        }
+        let breaks = content.len().saturating_sub(1);
+        TextStats {
+            lines: content.len(),
+            words: self.total.words,
+            chars: self.total.chars + breaks,
+            bytes: self.total.bytes + breaks,
+        }
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_stats_tests {
+    use super::*;
+    use crate::core::cursor::CursorPosition;
+
+    fn lines(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_counts_lines_words_chars_and_bytes() {
+        let stats = TextStats::of_lines(&lines(&["hello world", "  two\twords "]));
+        assert_eq!(
+            stats,
+            TextStats {
+                lines: 2,
+                words: 4,
+                chars: 24,
+                bytes: 24
+            }
+        );
+    }
+
+    #[test]
+    fn test_bytes_count_utf8_encoding() {
+        let stats = TextStats::of_lines(&lines(&["blåbær ünïcode"]));
+        assert_eq!(stats.chars, 14);
+        assert_eq!(stats.bytes, 18);
+        assert_eq!(stats.words, 2);
+    }
+
+    #[test]
+    fn test_unicode_whitespace_splits_words() {
+        //no-break space and ideographic space
+        let stats = TextStats::of_lines(&lines(&["a\u{00A0}b\u{3000}c"]));
+        assert_eq!(stats.words, 3);
+    }
+
+    #[test]
+    fn test_cache_counts_only_lines_changed_by_events() {
+        let mut cache = StatsCache::new();
+        let mut content = lines(&["one", "two three", "four"]);
+        assert_eq!(cache.stats(&content).words, 4);
+
+        //an unreported change to a counted line isn't seen
+        content[0] = "one one".to_string();
+        assert_eq!(cache.stats(&content).words, 4);
+        cache.line_changed(0);
+        assert_eq!(cache.stats(&content).words, 5);
+
+        //"two three" split into two lines and "four" joined to the line above
+        content = lines(&["one one", "two", " three four"]);
+        let at = CursorPosition::new(3, 1);
+        cache.apply(&EditEvent::Inserted {
+            at,
+            text: "\n".to_string(),
+        });
+        cache.apply(&EditEvent::Deleted {
+            range: CursorPosition::new(6, 2)..CursorPosition::new(0, 3),
+            text: "\n".to_string(),
+        });
+        cache.apply(&EditEvent::Inserted {
+            at: CursorPosition::new(6, 2),
+            text: " ".to_string(),
+        });
+        assert_eq!(cache.stats(&content), TextStats::of_lines(&content));
+    }
+
+    #[test]
+    fn test_cache_recounts_everything_after_unknown_changes() {
+        let mut cache = StatsCache::new();
+        assert_eq!(cache.stats(&lines(&["a"])).words, 1);
+        assert_eq!(cache.stats(&lines(&["a b", "c"])).words, 3);
+        //an event past the counted lines can't be followed
+        cache.apply(&EditEvent::Deleted {
+            range: CursorPosition::new(0, 5)..CursorPosition::new(0, 6),
+            text: "\n".to_string(),
+        });
+        assert_eq!(cache.stats(&lines(&["x y z", "w"])).words, 4);
+    }
+}
@@ -0,0 +1,413 @@
+use crate::core::cursor::CursorPosition;
+use std::ops::Range;
+
+///a matching pair of delimiters, like brackets or quotes, positions of the delimiters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DelimiterPair {
+    pub open: CursorPosition,
+    pub close: CursorPosition,
+}
+
+impl DelimiterPair {
+    ///range between the delimiters, end exclusive
+    pub fn inside(&self) -> (CursorPosition, CursorPosition) {
+        let start = CursorPosition {
+            x: self.open.x + 1,
+            y: self.open.y,
+        };
+        (start, self.close)
+    }
+
+    ///range including the delimiters, end exclusive
+    pub fn around(&self) -> (CursorPosition, CursorPosition) {
+        let end = CursorPosition {
+            x: self.close.x + 1,
+            y: self.close.y,
+        };
+        (self.open, end)
+    }
+}
+
+///delimiters wrapping text in c. a bracket pairs up with its counterpart whichever side
+/// is given, other chars like quotes or backticks go on both sides.
+pub fn surround_pair(c: char) -> (char, char) {
+    match c {
+        '(' | ')' => ('(', ')'),
+        '[' | ']' => ('[', ']'),
+        '{' | '}' => ('{', '}'),
+        '<' | '>' => ('<', '>'),
+        _ => (c, c),
+    }
+}
+
+///opening and closing bracket of the pair c belongs to, None if c isn't a bracket. angle
+/// brackets are left out, in code they are comparisons more often than pairs.
+fn bracket_pair(c: char) -> Option<(char, char)> {
+    match c {
+        '(' | ')' => Some(('(', ')')),
+        '[' | ']' => Some(('[', ']')),
+        '{' | '}' => Some(('{', '}')),
+        _ => None,
+    }
+}
+
+///what the bracket next to the cursor pairs up with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BracketMatch {
+    Matched(DelimiterPair),
+    //no partner before the start or end of the content
+    Unmatched(CursorPosition),
+}
+
+///finds the partner of the bracket under the cursor, or else of the one just before it.
+/// nesting counts brackets of the same kind, quotes aren't looked at as a scan starting
+/// within the text can't tell what is quoted. gives up once limit chars are scanned and
+/// the scan left the visible lines.
+pub fn match_bracket(
+    content: &[String],
+    cursor: CursorPosition,
+    visible: Range<usize>,
+    limit: usize,
+) -> Option<BracketMatch> {
+    let line = content.get(cursor.y)?;
+    let (x, bracket) = [Some(cursor.x), cursor.x.checked_sub(1)]
+        .into_iter()
+        .flatten()
+        .find_map(|x| {
+            let c = line.chars().nth(x)?;
+            bracket_pair(c).map(|_| (x, c))
+        })?;
+    let (open, close) = bracket_pair(bracket)?;
+    let from = CursorPosition { x, y: cursor.y };
+    let forward = bracket == open;
+    let partner = if forward { close } else { open };
+
+    let mut depth = 0;
+    for (scanned, (pos, c)) in chars_beside(content, from, forward).enumerate() {
+        if scanned >= limit && !visible.contains(&pos.y) {
+            return None;
+        }
+        if c == bracket {
+            depth += 1;
+        } else if c == partner && depth > 0 {
+            depth -= 1;
+        } else if c == partner {
+            let pair = if forward {
+                DelimiterPair {
+                    open: from,
+                    close: pos,
+                }
+            } else {
+                DelimiterPair {
+                    open: pos,
+                    close: from,
+                }
+            };
+            return Some(BracketMatch::Matched(pair));
+        }
+    }
+    Some(BracketMatch::Unmatched(from))
+}
+
+///chars of content after from, or before it backwards, nearest first
+fn chars_beside(
+    content: &[String],
+    from: CursorPosition,
+    forward: bool,
+) -> Box<dyn Iterator<Item = (CursorPosition, char)> + '_> {
+    let positioned = |y: usize, line: &String| {
+        line.chars()
+            .enumerate()
+            .map(move |(x, c)| (CursorPosition { x, y }, c))
+            .collect::<Vec<_>>()
+    };
+    let line = &content[from.y];
+    if forward {
+        let rest = content.iter().enumerate().skip(from.y + 1);
+        Box::new(
+            positioned(from.y, line)
+                .into_iter()
+                .skip(from.x + 1)
+                .chain(rest.flat_map(move |(y, line)| positioned(y, line))),
+        )
+    } else {
+        let rest = content[..from.y].iter().enumerate().rev();
+        Box::new(
+            positioned(from.y, line)
+                .into_iter()
+                .take(from.x)
+                .rev()
+                .chain(rest.flat_map(move |(y, line)| positioned(y, line).into_iter().rev())),
+        )
+    }
+}
+
+///bracket match at the cursor, only looked for again once the content, the cursor or the
+/// visible lines changed
+#[derive(Debug, Default)]
+pub struct BracketMatchCache {
+    key: Option<(u64, CursorPosition, usize)>,
+    found: Option<BracketMatch>,
+}
+
+impl BracketMatchCache {
+    pub fn get(
+        &mut self,
+        revision: u64,
+        content: &[String],
+        cursor: CursorPosition,
+        visible: Range<usize>,
+        limit: usize,
+    ) -> Option<BracketMatch> {
+        let key = (revision, cursor, visible.start);
+        if self.key != Some(key) {
+            self.found = match_bracket(content, cursor, visible, limit);
+            self.key = Some(key);
+        }
+        self.found
+    }
+}
+
+///finds every bracket and quote pair in content. brackets nest across lines,
+/// quotes end on their line, skip escaped chars and hide brackets inside them.
+pub fn find_delimiter_pairs(content: &[String]) -> Vec<DelimiterPair> {
+    let mut pairs = vec![];
+    let mut open_brackets: Vec<(char, CursorPosition)> = vec![];
+
+    for (y, line) in content.iter().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        let mut open_quote: Option<(char, CursorPosition)> = None;
+        let mut x = 0;
+
+        while x < chars.len() {
+            let c = chars[x];
+            let pos = CursorPosition { x, y };
+
+            if let Some((quote, open)) = open_quote {
+                if c == '\\' {
+                    x += 1; //skip escaped char
+                } else if c == quote {
+                    pairs.push(DelimiterPair { open, close: pos });
+                    open_quote = None;
+                }
+            } else {
+                match c {
+                    '"' => open_quote = Some((c, pos)),
+                    //an apostrophe inside a word, like in don't, isn't a quote
+                    '\'' if x == 0 || !chars[x - 1].is_alphanumeric() => {
+                        open_quote = Some((c, pos))
+                    }
+                    '(' | '[' | '{' => open_brackets.push((c, pos)),
+                    ')' | ']' | '}' => {
+                        let wanted = bracket_pair(c).map(|(open, _)| open);
+                        //unbalanced closing brackets are ignored
+                        if let Some(&(bracket, open)) = open_brackets.last() {
+                            if Some(bracket) == wanted {
+                                open_brackets.pop();
+                                pairs.push(DelimiterPair { open, close: pos });
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            x += 1;
+        }
+    }
+    pairs
+}
+
+///returns the range the selection from start to end expands to: the inside of the
+/// innermost enclosing pair, or its delimiters too if the inside is already selected
+pub fn expand_to_enclosing(
+    content: &[String],
+    start: CursorPosition,
+    end: CursorPosition,
+) -> Option<(CursorPosition, CursorPosition)> {
+    let key = |p: CursorPosition| (p.y, p.x);
+    let contains = |(s, e): (CursorPosition, CursorPosition)| {
+        key(s) <= key(start) && key(end) <= key(e) && (key(s), key(e)) != (key(start), key(end))
+    };
+
+    //enclosing pairs are nested, so the one opening last is the innermost
+    let pair = find_delimiter_pairs(content)
+        .into_iter()
+        .filter(|pair| contains(pair.around()))
+        .max_by_key(|pair| key(pair.open))?;
+
+    let (inside_start, inside_end) = pair.inside();
+    let inside_is_empty = key(inside_start) == key(inside_end);
+    let selection_is_empty = key(start) == key(end);
+    if !inside_is_empty && (selection_is_empty || contains(pair.inside())) {
+        Some(pair.inside())
+    } else {
+        Some(pair.around())
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_text_objects_tests {
+    use super::*;
+
+    fn pos(x: usize, y: usize) -> CursorPosition {
+        CursorPosition { x, y }
+    }
+
+    fn lines(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_nested_brackets_are_matched() {
+        let content = lines(&["f(a[1], {b})"]);
+        let pairs = find_delimiter_pairs(&content);
+        assert!(pairs.contains(&DelimiterPair {
+            open: pos(1, 0),
+            close: pos(11, 0)
+        }));
+        assert!(pairs.contains(&DelimiterPair {
+            open: pos(3, 0),
+            close: pos(5, 0)
+        }));
+        assert!(pairs.contains(&DelimiterPair {
+            open: pos(8, 0),
+            close: pos(10, 0)
+        }));
+    }
+
+    #[test]
+    fn test_brackets_match_across_lines() {
+        let content = lines(&["fn main() {", "    x", "}"]);
+        let pairs = find_delimiter_pairs(&content);
+        assert!(pairs.contains(&DelimiterPair {
+            open: pos(10, 0),
+            close: pos(0, 2)
+        }));
+    }
+
+    #[test]
+    fn test_escaped_quote_and_brackets_in_strings_are_skipped() {
+        let content = lines(&[r#"("a\"(b")"#]);
+        let pairs = find_delimiter_pairs(&content);
+        assert_eq!(
+            pairs,
+            vec![
+                DelimiterPair {
+                    open: pos(1, 0),
+                    close: pos(7, 0)
+                },
+                DelimiterPair {
+                    open: pos(0, 0),
+                    close: pos(8, 0)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apostrophe_in_word_is_not_a_quote() {
+        let content = lines(&["(don't)"]);
+        let pairs = find_delimiter_pairs(&content);
+        assert_eq!(
+            pairs,
+            vec![DelimiterPair {
+                open: pos(0, 0),
+                close: pos(6, 0)
+            }]
+        );
+    }
+
+    #[test]
+    fn test_expand_goes_inside_then_around_then_outer_pair() {
+        let content = lines(&["f(a, [b, c])"]);
+        let cursor = pos(7, 0);
+
+        let inside = expand_to_enclosing(&content, cursor, cursor).unwrap();
+        assert_eq!(inside, (pos(6, 0), pos(10, 0)));
+
+        let around = expand_to_enclosing(&content, inside.0, inside.1).unwrap();
+        assert_eq!(around, (pos(5, 0), pos(11, 0)));
+
+        let outer = expand_to_enclosing(&content, around.0, around.1).unwrap();
+        assert_eq!(outer, (pos(2, 0), pos(11, 0)));
+
+        let outer_around = expand_to_enclosing(&content, outer.0, outer.1).unwrap();
+        assert_eq!(outer_around, (pos(1, 0), pos(12, 0)));
+
+        assert_eq!(
+            expand_to_enclosing(&content, outer_around.0, outer_around.1),
+            None
+        );
+    }
+
+    #[test]
+    fn test_bracket_under_or_before_cursor_finds_partner() {
+        let content = lines(&["f(a, [b])"]);
+        let matched = |open, close| {
+            Some(BracketMatch::Matched(DelimiterPair {
+                open: pos(open, 0),
+                close: pos(close, 0),
+            }))
+        };
+        assert_eq!(match_bracket(&content, pos(1, 0), 0..1, 100), matched(1, 8));
+        //right after the closing bracket
+        assert_eq!(match_bracket(&content, pos(9, 0), 0..1, 100), matched(1, 8));
+        assert_eq!(match_bracket(&content, pos(7, 0), 0..1, 100), matched(5, 7));
+        assert_eq!(match_bracket(&content, pos(3, 0), 0..1, 100), None);
+    }
+
+    #[test]
+    fn test_bracket_match_nests_across_lines() {
+        let content = lines(&["fn a() {", "    if b {}", "}"]);
+        assert_eq!(
+            match_bracket(&content, pos(0, 2), 0..3, 100),
+            Some(BracketMatch::Matched(DelimiterPair {
+                open: pos(7, 0),
+                close: pos(0, 2)
+            }))
+        );
+    }
+
+    #[test]
+    fn test_lone_bracket_is_unmatched() {
+        let content = lines(&["(a", "b"]);
+        assert_eq!(
+            match_bracket(&content, pos(0, 0), 0..2, 100),
+            Some(BracketMatch::Unmatched(pos(0, 0)))
+        );
+        let content = lines(&["a]"]);
+        assert_eq!(
+            match_bracket(&content, pos(1, 0), 0..1, 100),
+            Some(BracketMatch::Unmatched(pos(1, 0)))
+        );
+    }
+
+    #[test]
+    fn test_scan_gives_up_past_limit_outside_the_view() {
+        let mut content = lines(&["{"]);
+        content.extend((0..10).map(|_| "xxxx".to_string()));
+        content.push("}".to_string());
+        assert_eq!(match_bracket(&content, pos(0, 0), 0..3, 8), None);
+        //still visible lines are scanned whatever the limit
+        assert!(matches!(
+            match_bracket(&content, pos(0, 0), 0..12, 8),
+            Some(BracketMatch::Matched(_))
+        ));
+        assert!(matches!(
+            match_bracket(&content, pos(0, 0), 0..3, 100),
+            Some(BracketMatch::Matched(_))
+        ));
+    }
+
+    #[test]
+    fn test_expand_without_enclosing_pair_is_none() {
+        let content = lines(&["plain text"]);
+        assert_eq!(expand_to_enclosing(&content, pos(3, 0), pos(3, 0)), None);
+    }
+}
@@ -1,10 +1,154 @@
-use super::super::errors::editor_errors::{RedoError, UndoError};
+use super::super::cursor::CursorPosition;
+use super::super::errors::editor_errors::EditorError;
 use super::editor::EditAction;
+use std::time::{Duration, Instant};
+
+///chars of the changed text shown in an undo history entry
+const PREVIEW_CHARS: usize = 20;
+
+///an undoable step with the time it was recorded
+#[derive(Debug, Clone)]
+pub struct UndoEntry {
+    pub action: EditAction,
+    pub recorded: Instant,
+}
+
+impl UndoEntry {
+    fn new(action: EditAction) -> Self {
+        Self {
+            action,
+            recorded: Instant::now(),
+        }
+    }
+
+    ///what kind of change the step is, like insert or delete
+    pub fn kind(&self) -> &'static str {
+        kind_of(&self.action)
+    }
+
+    ///where the step changed the text
+    pub fn position(&self) -> CursorPosition {
+        position_of(&self.action)
+    }
+
+    ///start of the text the step inserted or removed, line breaks shown as ↵
+    pub fn preview(&self) -> String {
+        let text = text_of(&self.action);
+        let mut preview: String = text
+            .chars()
+            .take(PREVIEW_CHARS)
+            .map(|c| if c == '\n' { '↵' } else { c })
+            .collect();
+        if text.chars().count() > PREVIEW_CHARS {
+            preview.push('…');
+        }
+        preview
+    }
+}
+
+fn kind_of(action: &EditAction) -> &'static str {
+    match action {
+        EditAction::Insert { .. }
+        | EditAction::InsertLines { .. }
+        | EditAction::InsertRange { .. } => "insert",
+        EditAction::Delete { .. }
+        | EditAction::DeleteLines { .. }
+        | EditAction::DeleteRange { .. } => "delete",
+        EditAction::Replace { .. }
+        | EditAction::ReplaceRange { .. }
+        | EditAction::ReplaceLines { .. } => "replace",
+        EditAction::SplitLine { .. } => "split line",
+        EditAction::JoinLine { .. } => "join lines",
+        //a group both removing and adding text replaced it
+        EditAction::Batch { actions } => {
+            let kinds: Vec<&str> = actions.iter().map(kind_of).collect();
+            match kinds.first() {
+                Some(first) if kinds.iter().all(|kind| kind == first) => first,
+                _ if kinds.contains(&"delete") && kinds.contains(&"insert") => "replace",
+                _ => "edit",
+            }
+        }
+    }
+}
+
+fn position_of(action: &EditAction) -> CursorPosition {
+    match action {
+        EditAction::Insert { pos, .. }
+        | EditAction::Delete { pos, .. }
+        | EditAction::SplitLine { pos, .. }
+        | EditAction::JoinLine { pos, .. } => *pos,
+        EditAction::Replace { start, .. }
+        | EditAction::ReplaceRange { start, .. }
+        | EditAction::ReplaceLines { start, .. }
+        | EditAction::InsertLines { start, .. }
+        | EditAction::DeleteLines { start, .. }
+        | EditAction::InsertRange { start, .. }
+        | EditAction::DeleteRange { start, .. } => *start,
+        EditAction::Batch { actions } => actions
+            .first()
+            .map_or(CursorPosition::new(0, 0), position_of),
+    }
+}
+
+///the text a step adds, or removes if it adds none
+fn text_of(action: &EditAction) -> String {
+    match action {
+        EditAction::Insert { c, .. } => c.to_string(),
+        EditAction::Delete { deleted_char, .. } => deleted_char.to_string(),
+        EditAction::Replace { new, .. } => new.to_string(),
+        EditAction::ReplaceRange { new, .. } => new.join("\n"),
+        //whole lines end in a line break
+        EditAction::ReplaceLines { new, .. } => format!("{}\n", new.join("\n")),
+        EditAction::InsertLines { lines, .. } => format!("{}\n", lines.join("\n")),
+        EditAction::DeleteLines { deleted, .. } => format!("{}\n", deleted.join("\n")),
+        EditAction::InsertRange { lines, .. } => lines.join("\n"),
+        EditAction::DeleteRange { deleted, .. } => deleted.join("\n"),
+        EditAction::SplitLine { .. } => "\n".to_string(),
+        EditAction::JoinLine { merged, .. } => merged.clone(),
+        //the last step with text, a replace inserts after deleting
+        EditAction::Batch { actions } => actions
+            .iter()
+            .rev()
+            .map(text_of)
+            .find(|text| !text.is_empty())
+            .unwrap_or_default(),
+    }
+}
+
+///how long ago something happened, in the largest whole unit
+pub fn age(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    match secs {
+        0..60 => format!("{}s ago", secs),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+///a state of the content in the undo history, taken with UndoRedoManager::mark. undoing
+/// and redoing back to the state gives the same mark again, new edits never do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct UndoMark(u64);
 
 #[derive(Debug)]
 pub struct UndoRedoManager {
-    undo_stack: Vec<EditAction>,
-    redo_stack: Vec<EditAction>,
+    //each step with the mark of the state it leads to
+    undo_stack: Vec<(UndoMark, UndoEntry)>,
+    redo_stack: Vec<(UndoMark, UndoEntry)>,
+    //actions recorded while a group is open, collapsed into one batch when it closes
+    group_buffer: Vec<EditAction>,
+    group_depth: usize,
+    //state before the first step of the history
+    base_mark: UndoMark,
+    //last mark handed out
+    last_mark: u64,
+}
+
+impl Default for UndoRedoManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl UndoRedoManager {
@@ -12,40 +156,141 @@ impl UndoRedoManager {
         Self {
             undo_stack: vec![],
             redo_stack: vec![],
+            group_buffer: vec![],
+            group_depth: 0,
+            base_mark: UndoMark::default(),
+            last_mark: 0,
+        }
+    }
+
+    ///forgets every step, like for content replaced as a whole. a history that had steps
+    /// starts over in a state none of its marks name.
+    pub fn clear(&mut self) {
+        if !self.undo_stack.is_empty() || !self.redo_stack.is_empty() {
+            self.last_mark += 1;
+            self.base_mark = UndoMark(self.last_mark);
         }
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.group_buffer.clear();
+        self.group_depth = 0;
+    }
+
+    ///pushes entry as the latest applied step, leading to a state never seen before
+    fn push_step(&mut self, entry: UndoEntry) {
+        self.last_mark += 1;
+        self.undo_stack.push((UndoMark(self.last_mark), entry));
     }
 
     ///records and action done to the undo stack, and clears redo stack.
+    /// if a group is open the action is buffered until the group is closed.
     pub fn record_undo(&mut self, action: EditAction) {
-        self.undo_stack.push(action);
+        if self.group_depth > 0 {
+            self.group_buffer.push(action);
+        } else {
+            self.push_step(UndoEntry::new(action));
+        }
         self.redo_stack.clear();
     }
 
+    // UNDO GROUPING
+    ///opens an undo group, every action recorded until the matching end_group
+    /// is undone and redone as a single step
+    pub fn begin_group(&mut self) {
+        self.group_depth += 1;
+    }
+
+    ///closes an undo group, when the outermost group closes the buffered actions
+    /// are pushed as one batch. empty groups leave no entry.
+    pub fn end_group(&mut self) {
+        if self.group_depth == 0 {
+            return;
+        }
+        self.group_depth -= 1;
+        if self.group_depth == 0 && !self.group_buffer.is_empty() {
+            let actions = std::mem::take(&mut self.group_buffer);
+            self.push_step(UndoEntry::new(EditAction::Batch { actions }));
+        }
+    }
+
     // UNDO AND REDO FUNCTIONALITY
     /// undo's last action of user
-    pub fn undo(&mut self) -> Result<EditAction, UndoError> {
-        if let Some(last_action) = self.undo_stack.pop() {
-            let action_reversed = self.reverse_action(&last_action);
-            self.redo_stack.push(last_action);
+    pub fn undo(&mut self) -> Result<EditAction, EditorError> {
+        if let Some(last) = self.undo_stack.pop() {
+            let action_reversed = self.reverse_action(&last.1.action);
+            self.redo_stack.push(last);
             Ok(action_reversed)
         } else {
-            Err(UndoError::NoActionToUndo)
+            Err(EditorError::NothingToUndo)
         }
     }
 
     /// redo's last action of user
-    pub fn redo(&mut self) -> Result<EditAction, RedoError> {
-        if let Some(last_action) = self.redo_stack.pop() {
-            self.undo_stack.push(last_action.clone());
-            Ok(last_action)
+    pub fn redo(&mut self) -> Result<EditAction, EditorError> {
+        if let Some(last) = self.redo_stack.pop() {
+            let action = last.1.action.clone();
+            self.undo_stack.push(last);
+            Ok(action)
         } else {
-            Err(RedoError::NoActionToRedo)
+            Err(EditorError::NothingToRedo)
         }
     }
 
+    // HISTORY
+    ///every recorded step oldest first, the undone ones after the applied ones
+    pub fn history(&self) -> impl Iterator<Item = &UndoEntry> {
+        self.undo_stack
+            .iter()
+            .chain(self.redo_stack.iter().rev())
+            .map(|(_, entry)| entry)
+    }
+
+    ///number of steps of the history that are applied to the content
+    pub fn applied(&self) -> usize {
+        self.undo_stack.len()
+    }
+
+    ///number of undone steps that can be redone
+    pub fn undone(&self) -> usize {
+        self.redo_stack.len()
+    }
+
+    ///the state the content is in, steps of an open group count once it is closed
+    pub fn mark(&self) -> UndoMark {
+        self.undo_stack
+            .last()
+            .map_or(self.base_mark, |&(mark, _)| mark)
+    }
+
+    ///replaces the history with entries oldest first, the first applied of them applied
+    /// to the content and the rest undone
+    pub fn restore(&mut self, mut entries: Vec<UndoEntry>, applied: usize) {
+        let applied = applied.min(entries.len());
+        let undone = entries.split_off(applied);
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        for entry in entries {
+            self.push_step(entry);
+        }
+        for entry in undone.into_iter().rev() {
+            self.last_mark += 1;
+            self.redo_stack.push((UndoMark(self.last_mark), entry));
+        }
+        self.group_buffer.clear();
+        self.group_depth = 0;
+    }
+
     /// returns the reverse action of the given action
     fn reverse_action(&mut self, action: &EditAction) -> EditAction {
         match action {
+            //reverse every action and apply them in the opposite order
+            EditAction::Batch { actions } => EditAction::Batch {
+                actions: actions
+                    .iter()
+                    .rev()
+                    .map(|a| self.reverse_action(a))
+                    .collect(),
+            },
             EditAction::Insert { pos, c } => EditAction::Delete {
                 pos: *pos,
                 deleted_char: *c,
@@ -62,8 +307,8 @@ impl UndoRedoManager {
             } => EditAction::Replace {
                 start: *start,
                 end: *end,
-                old: new.clone(),
-                new: old.clone(),
+                old: *new,
+                new: *old,
             },
             EditAction::ReplaceRange {
                 start,
@@ -76,6 +321,11 @@ impl UndoRedoManager {
                 old: new.clone(),
                 new: old.clone(),
             },
+            EditAction::ReplaceLines { start, old, new } => EditAction::ReplaceLines {
+                start: *start,
+                old: new.clone(),
+                new: old.clone(),
+            },
             EditAction::InsertLines { start, lines } => EditAction::DeleteLines {
                 start: *start,
                 deleted: lines.clone(),
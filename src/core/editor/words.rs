@@ -0,0 +1,81 @@
+//what counts as a word, shared by word motions, completion and keyword matching
+
+///names :set wordboundary takes besides a list of extra chars, offered when completing it
+pub const WORD_CHARS_NAMES: &[&str] = &["code", "nonwhitespace"];
+
+///which chars make up a word
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum WordChars {
+    //letters, digits and underscores, like identifiers in code
+    #[default]
+    Code,
+    //anything but whitespace, like vim's WORD
+    NonWhitespace,
+    //letters, digits and underscores plus these chars, like - for css
+    Extra(String),
+}
+
+impl WordChars {
+    ///parses a :set wordboundary value, anything but a profile name is a list of extra chars
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "code" => Ok(Self::Code),
+            "nonwhitespace" | "word" => Ok(Self::NonWhitespace),
+            _ if value.chars().any(char::is_alphanumeric) => Err(format!(
+                "expected code, nonwhitespace or extra chars, got '{}'",
+                value
+            )),
+            _ => Ok(Self::Extra(value.to_string())),
+        }
+    }
+}
+
+///checks if c is part of a word under word_chars
+pub fn is_word_char(c: char, word_chars: &WordChars) -> bool {
+    match word_chars {
+        WordChars::Code => c.is_alphanumeric() || c == '_',
+        WordChars::NonWhitespace => !c.is_whitespace(),
+        WordChars::Extra(extra) => c.is_alphanumeric() || c == '_' || extra.contains(c),
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_words_tests {
+    use super::*;
+
+    fn split<'a>(text: &'a str, word_chars: &WordChars) -> Vec<&'a str> {
+        text.split(|c| !is_word_char(c, word_chars))
+            .filter(|word| !word.is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn test_dash_joins_words_only_with_css_profile() {
+        let css = WordChars::parse("-").unwrap();
+        assert_eq!(split("foo-bar", &WordChars::Code), vec!["foo", "bar"]);
+        assert_eq!(split("foo-bar", &css), vec!["foo-bar"]);
+    }
+
+    #[test]
+    fn test_non_whitespace_keeps_punctuation() {
+        let word = WordChars::NonWhitespace;
+        assert_eq!(split("a.b(c) d", &word), vec!["a.b(c)", "d"]);
+    }
+
+    #[test]
+    fn test_parse_profiles() {
+        assert_eq!(WordChars::parse("code"), Ok(WordChars::Code));
+        assert_eq!(WordChars::parse("word"), Ok(WordChars::NonWhitespace));
+        assert_eq!(
+            WordChars::parse("-$"),
+            Ok(WordChars::Extra("-$".to_string()))
+        );
+        assert!(WordChars::parse("abc").is_err());
+    }
+}
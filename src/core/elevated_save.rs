@@ -0,0 +1,156 @@
+//saving a file the user may not write through sudo or pkexec, for fixing a system file
+// without reopening it as root. the buffer is piped to tee running with the raised rights,
+// the terminal has to be handed back to the shell around it for the password prompt.
+
+use super::errors::error::AppError;
+use super::file_format::FileFormat;
+use super::file_io;
+use std::env;
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::process::{Command, Stdio};
+
+///programs tried in order for running tee with raised rights
+pub const PROGRAMS: &[&str] = &["sudo", "pkexec"];
+
+///checks if e is a write the user lacks the rights for, the only failure raised rights fix
+pub fn is_permission_denied(e: &AppError) -> bool {
+    matches!(e, AppError::FileWriteFailed { source, .. } if source.kind() == ErrorKind::PermissionDenied)
+}
+
+///the first of PROGRAMS found in PATH
+pub fn find_program() -> Option<&'static str> {
+    let path = env::var_os("PATH")?;
+    PROGRAMS
+        .iter()
+        .copied()
+        .find(|program| env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+}
+
+///writes lines to the file at path in format by piping them to `program tee path`. the
+/// program inherits the terminal for asking the password, what it or tee print on stderr
+/// is the reason given when it fails.
+pub fn write_with(
+    program: &str,
+    path: &str,
+    lines: &[String],
+    format: FileFormat,
+) -> Result<(), AppError> {
+    file_io::check_encodable(path, lines, format)?;
+    let failed = |reason: String| AppError::ElevatedWriteFailed {
+        path: path.to_string(),
+        program: program.to_string(),
+        reason,
+    };
+    let mut child = Command::new(program)
+        .args(["tee", "--", path])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| failed(e.to_string()))?;
+    //a program giving up early closes the pipe, its exit status tells why
+    let written = match child.stdin.take() {
+        Some(stdin) => {
+            let mut out = BufWriter::new(stdin);
+            let lines = lines.iter().map(String::as_str);
+            file_io::write_lines(&mut out, lines, format).and_then(|()| out.flush())
+        }
+        None => Err(io::Error::other("stdin of tee was not piped")),
+    };
+    let output = child
+        .wait_with_output()
+        .map_err(|e| failed(e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(failed(if stderr.is_empty() {
+            output.status.to_string()
+        } else {
+            stderr
+        }));
+    }
+    written.map_err(|e| failed(e.to_string()))
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(all(test, unix))]
+mod unit_elevated_save_tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_only_permission_errors_are_offered() {
+        let write_failed = |kind| AppError::FileWriteFailed {
+            path: "/etc/hosts".to_string(),
+            source: io::Error::from(kind),
+        };
+        assert!(is_permission_denied(&write_failed(
+            ErrorKind::PermissionDenied
+        )));
+        assert!(!is_permission_denied(&write_failed(ErrorKind::NotFound)));
+        assert!(!is_permission_denied(&AppError::FileReadFailed {
+            path: "/etc/hosts".to_string(),
+            source: io::Error::from(ErrorKind::PermissionDenied),
+        }));
+    }
+
+    //env runs tee with the rights it has, standing in for sudo
+    #[test]
+    fn test_writes_through_tee() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("hosts");
+        let path = path.to_str().unwrap();
+        write_with(
+            "env",
+            path,
+            &lines(&["127.0.0.1 localhost", "::1"]),
+            FileFormat::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(path).unwrap(),
+            "127.0.0.1 localhost\n::1"
+        );
+    }
+
+    #[test]
+    fn test_failure_reason_is_stderr() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("missing").join("hosts");
+        let e = write_with(
+            "env",
+            path.to_str().unwrap(),
+            &lines(&["x"]),
+            FileFormat::default(),
+        )
+        .unwrap_err();
+        let AppError::ElevatedWriteFailed {
+            program, reason, ..
+        } = e
+        else {
+            panic!("unexpected error {:?}", e);
+        };
+        assert_eq!(program, "env");
+        assert!(reason.contains("tee"), "{}", reason);
+    }
+
+    #[test]
+    fn test_missing_program_is_an_error() {
+        let result = write_with(
+            "calliglyph-no-such-program",
+            "/tmp/x",
+            &lines(&["x"]),
+            FileFormat::default(),
+        );
+        assert!(matches!(result, Err(AppError::ElevatedWriteFailed { .. })));
+    }
+}
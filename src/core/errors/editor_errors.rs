@@ -1,51 +1,29 @@
 use thiserror::Error;
 
-///general editor errors, from more specific ones
-#[derive(Error, Debug)]
+///editor errors, carrying enough context to tell the user what went wrong
+#[derive(Error, Debug, PartialEq)]
 pub enum EditorError {
-    #[error("Failed to copy text: {0}")]
-    TextSelectionFailure(#[from] TextSelectionError),
+    #[error("Cursor ({x}, {y}) is out of bounds, the line has {line_len} characters")]
+    CursorOutOfBounds { x: usize, y: usize, line_len: usize },
 
-    #[error("Clipboard failed: {0}")]
-    ClipboardFailure(#[from] ClipboardError),
+    #[error("Line {line} is out of bounds, the content has {line_count} lines")]
+    LineOutOfBounds { line: usize, line_count: usize },
 
-    #[error("File: {0} not found")]
-    FileNotFound(String),
+    #[error("No text selected, select text with shift and the arrow keys first")]
+    NoSelection,
 
-    #[error("UNDO failed: {0}")]
-    UndoFailure(#[from] UndoError),
+    #[error("Clipboard is empty, copy or cut text first")]
+    EmptyClipboard,
 
-    #[error("REDO failed: {0}")]
-    RedoFailure(#[from] RedoError),
-}
-
-#[derive(Debug, Error)]
-pub enum TextSelectionError {
-    #[error("No text currently selected in editor")]
-    NoTextSelected,
-
-    #[error("Invalid selection range.")]
-    InvalidRange,
-}
+    #[error("Nothing to undo")]
+    NothingToUndo,
 
-#[derive(Debug, Error)]
-pub enum ClipboardError {
-    #[error("No text currently copied")]
-    NoCopiedText,
-}
+    #[error("Nothing to redo")]
+    NothingToRedo,
 
-#[derive(Debug, Error)]
-pub enum UndoError {
-    #[error("No action to undo")]
-    NoActionToUndo,
-    #[error("failed to undo action")]
-    FailedToUndo,
-}
+    #[error("No bookmarks, toggle one on the cursor line with ctrl+f2")]
+    NoBookmarks,
 
-#[derive(Debug, Error)]
-pub enum RedoError {
-    #[error("No action to redo")]
-    NoActionToRedo,
-    #[error("failed to redo action")]
-    FailedToRedo,
+    #[error("Mark '{0}' is not set")]
+    UnknownMark(char),
 }
@@ -1,5 +1,6 @@
 use super::command_errors::CommandError;
 use super::editor_errors::EditorError;
+use std::path::PathBuf;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -13,6 +14,55 @@ pub enum AppError {
     #[error("Internal error: {0}")]
     InternalError(String),
 
+    #[error("Failed to read file '{path}': {source}")]
+    FileReadFailed {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to write file '{path}': {source}")]
+    FileWriteFailed {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to write file '{path}' with {program}: {reason}")]
+    ElevatedWriteFailed {
+        path: String,
+        program: String,
+        reason: String,
+    },
+
+    #[error("Failed to run '{command}': {reason}")]
+    ViewerFailed { command: String, reason: String },
+
+    #[error("Failed to change to directory '{path}': {source}")]
+    DirectoryChangeFailed {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[error("Can't write '{path}' as {encoding}: '{char}' at line {line}, column {col} has no {encoding} encoding")]
+    UnencodableChar {
+        path: String,
+        encoding: &'static str,
+        char: char,
+        line: usize,
+        col: usize,
+    },
+
+    #[error("Refusing to write '{path}', it is outside '{dir}' writes are restricted to")]
+    OutsideRestrictedDir { path: PathBuf, dir: PathBuf },
+
+    #[error("{}", .0.join("; "))]
+    SavesFailed(Vec<String>),
+
+    #[error("Still saving '{0}', try again once it is done")]
+    SaveInProgress(String),
+
+    #[error("Macro register '{0}' is empty")]
+    EmptyMacroRegister(char),
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 }
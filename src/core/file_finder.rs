@@ -0,0 +1,178 @@
+//walking a dir for the ctrl+p file finder on a separate thread, handing the files found
+// over in batches so the list fills in while a huge dir is still being walked
+
+use ignore::WalkBuilder;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+//files found are sent once this many are together, and at the end of the walk
+const BATCH_SIZE: usize = 256;
+//bytes read from the start of a file to tell if it looks binary, like git does
+const SNIFF_BYTES: usize = 8000;
+
+///a walk of dir running on its own thread, gitignored files, the .git dir and binary
+/// looking files left out
+#[derive(Debug)]
+pub struct FileFinder {
+    dir: PathBuf,
+    found: Receiver<Vec<String>>,
+    //paths relative to dir of the files found so far, in the order they were found
+    pub files: Vec<String>,
+    max_files: usize,
+    done: bool,
+}
+
+impl FileFinder {
+    ///starts walking dir for at most max_files files
+    pub fn start(dir: PathBuf, max_files: usize) -> Self {
+        let (sender, found) = mpsc::channel();
+        let walk_dir = dir.clone();
+        thread::spawn(move || {
+            let mut batch = vec![];
+            for file in walk(&walk_dir).take(max_files) {
+                batch.push(file);
+                if batch.len() == BATCH_SIZE {
+                    //the receiver is gone once the finder was closed, the walk stops with it
+                    if sender.send(std::mem::take(&mut batch)).is_err() {
+                        return;
+                    }
+                }
+            }
+            let _ = sender.send(batch);
+        });
+        Self {
+            dir,
+            found,
+            files: vec![],
+            max_files,
+            done: false,
+        }
+    }
+
+    ///takes the files found since the last poll, they are added to files as well
+    pub fn poll(&mut self) -> Vec<String> {
+        let mut new = vec![];
+        loop {
+            match self.found.try_recv() {
+                Ok(batch) => new.extend(batch),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.done = true;
+                    break;
+                }
+            }
+        }
+        self.files.extend(new.iter().cloned());
+        new
+    }
+
+    ///checks if the walk is over and every file it found was polled
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    ///checks if the walk stopped at the most files it lists, more may be left unlisted
+    pub fn is_capped(&self) -> bool {
+        self.files.len() >= self.max_files
+    }
+
+    ///path of a found file as it is opened, relative to the working dir it was found in
+    pub fn path(&self, file: &str) -> String {
+        if self.dir == Path::new(".") {
+            return file.to_string();
+        }
+        self.dir.join(file).to_string_lossy().into_owned()
+    }
+}
+
+///the text files under dir, as paths relative to it with / between their parts
+fn walk(dir: &Path) -> impl Iterator<Item = String> + '_ {
+    WalkBuilder::new(dir)
+        .hidden(false)
+        //a .gitignore counts outside of git repos too
+        .require_git(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|kind| kind.is_file()))
+        .filter(|entry| !looks_binary(entry.path()))
+        .filter_map(move |entry| {
+            let relative = entry.path().strip_prefix(dir).ok()?;
+            let parts: Vec<_> = relative.iter().map(|part| part.to_string_lossy()).collect();
+            Some(parts.join("/"))
+        })
+}
+
+///checks if the start of the file at path has a nul byte, which text files don't.
+/// files that can't be read are left out as well.
+fn looks_binary(path: &Path) -> bool {
+    let mut head = Vec::with_capacity(SNIFF_BYTES);
+    match File::open(path).and_then(|file| file.take(SNIFF_BYTES as u64).read_to_end(&mut head)) {
+        Ok(_) => head.contains(&0),
+        Err(_) => true,
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_file_finder_tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn finish(finder: &mut FileFinder) -> Vec<String> {
+        while !finder.is_done() {
+            finder.poll();
+            thread::yield_now();
+        }
+        let mut files = finder.files.clone();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_walk_skips_ignored_git_and_binary_files() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join("target")).unwrap();
+        fs::write(root.join(".gitignore"), "target/\n*.log\n").unwrap();
+        fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(root.join(".git/config"), "[core]").unwrap();
+        fs::write(root.join("target/out.rs"), "").unwrap();
+        fs::write(root.join("run.log"), "").unwrap();
+        fs::write(root.join("image.png"), b"\x89PNG\x00\x00").unwrap();
+        fs::write(root.join("notes.txt"), "").unwrap();
+
+        let mut finder = FileFinder::start(root.to_path_buf(), 100);
+        assert_eq!(
+            finish(&mut finder),
+            vec![".gitignore", "notes.txt", "src/main.rs"]
+        );
+        assert!(!finder.is_capped());
+        assert_eq!(
+            finder.path("src/main.rs"),
+            root.join("src/main.rs").to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn test_walk_stops_at_the_most_files() {
+        let dir = tempdir().unwrap();
+        for i in 0..BATCH_SIZE + 10 {
+            fs::write(dir.path().join(format!("{}.txt", i)), "").unwrap();
+        }
+        let mut finder = FileFinder::start(dir.path().to_path_buf(), BATCH_SIZE + 5);
+        assert_eq!(finish(&mut finder).len(), BATCH_SIZE + 5);
+        assert!(finder.is_capped());
+    }
+}
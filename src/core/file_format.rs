@@ -0,0 +1,174 @@
+//how a buffer's file is laid out on disk, its text encoding and whether it ends with a
+// newline. detected when the file is loaded so saving writes it back the same way.
+
+use std::borrow::Cow;
+
+///names :set fileencoding takes, offered when completing it
+pub const ENCODING_NAMES: &[&str] = &["utf8", "utf8bom", "latin1"];
+
+//byte order mark starting utf8bom files
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+///text encoding a file is read and written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FileEncoding {
+    #[default]
+    Utf8,
+    //utf8 starting with a byte order mark
+    Utf8Bom,
+    //iso-8859-1, one byte per char, only chars up to U+00FF
+    Latin1,
+}
+
+impl FileEncoding {
+    ///parses an encoding name as :set fileencoding takes it
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "utf8" | "utf-8" => Some(Self::Utf8),
+            "utf8bom" | "utf-8-bom" => Some(Self::Utf8Bom),
+            "latin1" | "iso-8859-1" => Some(Self::Latin1),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Utf8 => "utf8",
+            Self::Utf8Bom => "utf8bom",
+            Self::Latin1 => "latin1",
+        }
+    }
+
+    ///decodes the bytes of a file, telling the encoding from them. a byte order mark means
+    /// utf8bom, anything that isn't valid utf8 is read as latin1, which every byte is.
+    pub fn decode(bytes: Vec<u8>) -> (String, Self) {
+        if let Some(rest) = bytes.strip_prefix(BOM) {
+            if let Ok(text) = std::str::from_utf8(rest) {
+                return (text.to_string(), Self::Utf8Bom);
+            }
+        }
+        match String::from_utf8(bytes) {
+            Ok(text) => (text, Self::Utf8),
+            Err(e) => {
+                let text = e.into_bytes().into_iter().map(char::from).collect();
+                (text, Self::Latin1)
+            }
+        }
+    }
+
+    ///bytes written before the first line
+    pub fn preamble(self) -> &'static [u8] {
+        match self {
+            Self::Utf8Bom => BOM,
+            _ => b"",
+        }
+    }
+
+    ///checks if the encoding can write c
+    pub fn can_encode(self, c: char) -> bool {
+        self != Self::Latin1 || u32::from(c) <= 0xFF
+    }
+
+    ///the first char of lines the encoding can't write, with its line and char column
+    pub fn first_unencodable(self, lines: &[String]) -> Option<(usize, usize, char)> {
+        if self != Self::Latin1 {
+            return None;
+        }
+        lines.iter().enumerate().find_map(|(y, line)| {
+            line.chars()
+                .enumerate()
+                .find(|&(_, c)| !self.can_encode(c))
+                .map(|(x, c)| (y, x, c))
+        })
+    }
+
+    ///line in the encoding, chars it can't write have to be checked for beforehand
+    pub fn encode<'a>(self, line: &'a str) -> Cow<'a, [u8]> {
+        match self {
+            Self::Latin1 if !line.is_ascii() => {
+                Cow::Owned(line.chars().map(|c| u32::from(c) as u8).collect())
+            }
+            _ => Cow::Borrowed(line.as_bytes()),
+        }
+    }
+}
+
+///encoding and trailing newline of a buffer's file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FileFormat {
+    pub encoding: FileEncoding,
+    //the file ends with a newline after its last line
+    pub final_newline: bool,
+}
+
+impl FileFormat {
+    ///splits a file's bytes into lines, remembering how they were laid out
+    pub fn read(bytes: Vec<u8>) -> (Vec<String>, Self) {
+        let (text, encoding) = FileEncoding::decode(bytes);
+        let format = Self {
+            encoding,
+            final_newline: text.ends_with('\n'),
+        };
+        let lines = if text.is_empty() {
+            vec![String::new()]
+        } else {
+            text.lines().map(String::from).collect()
+        };
+        (lines, format)
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_file_format_tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_detects_encoding() {
+        assert_eq!(
+            FileEncoding::decode("héllo".as_bytes().to_vec()),
+            ("héllo".to_string(), FileEncoding::Utf8)
+        );
+        assert_eq!(
+            FileEncoding::decode(b"\xEF\xBB\xBFhi".to_vec()),
+            ("hi".to_string(), FileEncoding::Utf8Bom)
+        );
+        assert_eq!(
+            FileEncoding::decode(b"caf\xe9".to_vec()),
+            ("café".to_string(), FileEncoding::Latin1)
+        );
+    }
+
+    #[test]
+    fn test_latin1_encodes_chars_up_to_ff() {
+        let latin1 = FileEncoding::Latin1;
+        assert_eq!(latin1.encode("café ÿ").as_ref(), b"caf\xe9 \xff");
+        let lines = vec!["café".to_string(), "a €".to_string()];
+        assert_eq!(latin1.first_unencodable(&lines), Some((1, 2, '€')));
+        assert_eq!(FileEncoding::Utf8.first_unencodable(&lines), None);
+    }
+
+    #[test]
+    fn test_read_remembers_final_newline() {
+        let (lines, format) = FileFormat::read(b"a\nb\n".to_vec());
+        assert_eq!(lines, vec!["a", "b"]);
+        assert!(format.final_newline);
+        let (lines, format) = FileFormat::read(vec![]);
+        assert_eq!(lines, vec![""]);
+        assert!(!format.final_newline);
+    }
+
+    #[test]
+    fn test_parse_names() {
+        for name in ENCODING_NAMES {
+            assert_eq!(FileEncoding::parse(name).unwrap().name(), *name);
+        }
+        assert_eq!(FileEncoding::parse("UTF-8"), Some(FileEncoding::Utf8));
+        assert_eq!(FileEncoding::parse("ascii"), None);
+    }
+}
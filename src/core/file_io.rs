@@ -0,0 +1,400 @@
+//streaming file io for buffers, so saving or comparing a huge buffer doesn't need a
+// copy of it joined into one string or the whole file read into memory
+
+use crate::core::errors::error::AppError;
+use crate::core::file_format::{FileEncoding, FileFormat};
+use std::fs;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+//bytes read from a file at a time when comparing it to a buffer
+const CHUNK_SIZE: usize = 64 * 1024;
+
+///number of bytes lines take when written in format, joined with newlines
+pub fn content_len(lines: &[String], format: FileFormat) -> usize {
+    let encoded: usize = match format.encoding {
+        //every char is one byte
+        FileEncoding::Latin1 => lines.iter().map(|line| line.chars().count()).sum(),
+        _ => lines.iter().map(String::len).sum(),
+    };
+    format.encoding.preamble().len()
+        + encoded
+        + lines.len().saturating_sub(1)
+        + usize::from(format.final_newline && !lines.is_empty())
+}
+
+///writes lines to writer in format with a newline between them, like joining them would
+pub fn write_lines<'a, W: Write>(
+    writer: &mut W,
+    lines: impl IntoIterator<Item = &'a str>,
+    format: FileFormat,
+) -> io::Result<()> {
+    writer.write_all(format.encoding.preamble())?;
+    let mut any = false;
+    for (i, line) in lines.into_iter().enumerate() {
+        if i > 0 {
+            writer.write_all(b"\n")?;
+        }
+        writer.write_all(&format.encoding.encode(line))?;
+        any = true;
+    }
+    if format.final_newline && any {
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()
+}
+
+///the parent dir of path if it doesn't exist yet, writing the file would create it
+pub fn missing_parent(path: &str) -> Option<&Path> {
+    Path::new(path)
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+}
+
+///when the file at path was last modified, None if that can't be told
+pub fn modified_time(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+///path made absolute with its symlinks resolved. for a path that doesn't exist yet the
+/// closest existing dir is resolved and the rest appended, nothing there can be a link.
+/// a broken link fails, writing through it would create its target wherever that is.
+pub fn resolve_path(path: &Path) -> io::Result<PathBuf> {
+    let mut existing = path;
+    let mut rest = vec![];
+    loop {
+        let dir = match existing.as_os_str().is_empty() {
+            true => Path::new("."),
+            false => existing,
+        };
+        match fs::canonicalize(dir) {
+            Ok(mut resolved) => {
+                for component in rest.iter().rev() {
+                    match component {
+                        Component::ParentDir => {
+                            resolved.pop();
+                        }
+                        Component::Normal(name) => resolved.push(name),
+                        _ => {}
+                    }
+                }
+                return Ok(resolved);
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                if fs::symlink_metadata(dir).is_ok() {
+                    return Err(io::Error::new(io::ErrorKind::NotFound, "broken symlink"));
+                }
+                let Some(last) = existing.components().next_back() else {
+                    return Err(e);
+                };
+                rest.push(last);
+                existing = existing.parent().unwrap_or(Path::new(""));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+///checks every char of lines can be written in the encoding of format, the error names
+/// the first one that can't
+pub fn check_encodable(path: &str, lines: &[String], format: FileFormat) -> Result<(), AppError> {
+    match format.encoding.first_unencodable(lines) {
+        Some((line, col, char)) => Err(AppError::UnencodableChar {
+            path: path.to_string(),
+            encoding: format.encoding.name(),
+            char,
+            line: line + 1,
+            col: col + 1,
+        }),
+        None => Ok(()),
+    }
+}
+
+///creates the missing parent dirs of path. writing a file never does so by itself, a
+/// typo in a path would quietly leave dirs behind.
+pub fn create_parent_dirs(path: &str) -> Result<(), AppError> {
+    match missing_parent(path) {
+        Some(dir) => fs::create_dir_all(dir).map_err(|source| AppError::FileWriteFailed {
+            path: path.to_string(),
+            source,
+        }),
+        None => Ok(()),
+    }
+}
+
+///writes lines to the file at path in format, creating it if needed. its dir has to exist.
+/// a char the encoding can't write fails before the file is touched.
+pub fn write_file(path: &str, lines: &[String], format: FileFormat) -> Result<(), AppError> {
+    check_encodable(path, lines, format)?;
+    let write_failed = |source| AppError::FileWriteFailed {
+        path: path.to_string(),
+        source,
+    };
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+        .map_err(write_failed)?;
+    //lines are streamed into the file, joining them first would copy the whole buffer
+    let lines = lines.iter().map(String::as_str);
+    write_lines(&mut BufWriter::new(file), lines, format).map_err(write_failed)
+}
+
+///checks if what reader yields differs from lines written in format, reading it in
+/// chunks and stopping at the first difference
+pub fn content_differs<'a, R: Read>(
+    reader: R,
+    lines: impl IntoIterator<Item = &'a str>,
+    format: FileFormat,
+) -> io::Result<bool> {
+    let mut reader = BufReader::with_capacity(CHUNK_SIZE, reader);
+    if !consume_expected(&mut reader, format.encoding.preamble())? {
+        return Ok(true);
+    }
+    let mut any = false;
+    for (i, line) in lines.into_iter().enumerate() {
+        if i > 0 && !consume_expected(&mut reader, b"\n")? {
+            return Ok(true);
+        }
+        if !consume_expected(&mut reader, &format.encoding.encode(line))? {
+            return Ok(true);
+        }
+        any = true;
+    }
+    if format.final_newline && any && !consume_expected(&mut reader, b"\n")? {
+        return Ok(true);
+    }
+    //anything left after the last line is a change too
+    Ok(!reader.fill_buf()?.is_empty())
+}
+
+///reads past expected, false if reader yields anything else or ends before it
+fn consume_expected<R: BufRead>(reader: &mut R, mut expected: &[u8]) -> io::Result<bool> {
+    while !expected.is_empty() {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            return Ok(false);
+        }
+        let n = buf.len().min(expected.len());
+        if buf[..n] != expected[..n] {
+            return Ok(false);
+        }
+        reader.consume(n);
+        expected = &expected[n..];
+    }
+    Ok(true)
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_file_io_tests {
+    use super::*;
+
+    //a 256 MB buffer, made of one line repeated so the test doesn't hold it in memory
+    const LINE_LEN: usize = 1024;
+    const LINE_COUNT: usize = 256 * 1024;
+
+    ///writer that only counts bytes, remembering the largest single write it got
+    #[derive(Default)]
+    struct CountingWriter {
+        total: usize,
+        largest_write: usize,
+    }
+
+    impl Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.total += buf.len();
+            self.largest_write = self.largest_write.max(buf.len());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    ///reader producing line repeated count times with newlines between, generated lazily
+    struct RepeatReader {
+        line: Vec<u8>,
+        count: usize,
+        //position in the line currently read, the newline after it counts as its last byte
+        offset: usize,
+    }
+
+    impl Read for RepeatReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let mut written = 0;
+            while written < buf.len() && self.count > 0 {
+                if self.offset < self.line.len() {
+                    let n = (self.line.len() - self.offset).min(buf.len() - written);
+                    buf[written..written + n]
+                        .copy_from_slice(&self.line[self.offset..self.offset + n]);
+                    self.offset += n;
+                    written += n;
+                } else if self.count > 1 {
+                    buf[written] = b'\n';
+                    written += 1;
+                    self.offset = 0;
+                    self.count -= 1;
+                } else {
+                    self.count = 0;
+                }
+            }
+            Ok(written)
+        }
+    }
+
+    fn huge_lines(line: &str) -> impl Iterator<Item = &str> {
+        std::iter::repeat_n(line, LINE_COUNT)
+    }
+
+    #[test]
+    fn test_write_lines_joins_with_newlines() {
+        let mut out = vec![];
+        let lines = ["a".to_string(), String::new(), "b".to_string()];
+        let format = FileFormat::default();
+        write_lines(&mut out, lines.iter().map(String::as_str), format).unwrap();
+        assert_eq!(out, b"a\n\nb");
+        assert_eq!(content_len(&lines, format), 4);
+    }
+
+    #[test]
+    fn test_content_differs_finds_changes_anywhere() {
+        let lines = ["one".to_string(), "two".to_string()];
+        let differs = |file: &str| {
+            content_differs(
+                file.as_bytes(),
+                lines.iter().map(String::as_str),
+                FileFormat::default(),
+            )
+        };
+        assert!(!differs("one\ntwo").unwrap());
+        assert!(differs("one\ntwo\n").unwrap());
+        assert!(differs("one\ntw").unwrap());
+        assert!(differs("one two").unwrap());
+        assert!(differs("").unwrap());
+    }
+
+    #[test]
+    fn test_formats_are_written_and_compared_byte_exact() {
+        let lines = ["café".to_string(), "ÿ".to_string()];
+        let format = FileFormat {
+            encoding: FileEncoding::Latin1,
+            final_newline: true,
+        };
+        let mut out = vec![];
+        write_lines(&mut out, lines.iter().map(String::as_str), format).unwrap();
+        assert_eq!(out, b"caf\xe9\n\xff\n");
+        assert_eq!(content_len(&lines, format), out.len());
+        let differs = |file: &[u8], format| {
+            content_differs(file, lines.iter().map(String::as_str), format).unwrap()
+        };
+        assert!(!differs(&out, format));
+        assert!(differs(&out[..out.len() - 1], format));
+        assert!(differs("café\nÿ\n".as_bytes(), format));
+
+        let bom = FileFormat {
+            encoding: FileEncoding::Utf8Bom,
+            final_newline: false,
+        };
+        let mut out = vec![];
+        write_lines(&mut out, lines.iter().map(String::as_str), bom).unwrap();
+        assert_eq!(out, "\u{feff}café\nÿ".as_bytes());
+        assert_eq!(content_len(&lines, bom), out.len());
+        assert!(!differs(&out, bom));
+    }
+
+    #[test]
+    fn test_unencodable_char_fails_before_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("old.txt");
+        fs::write(&path, "kept").unwrap();
+        let lines = vec!["ok".to_string(), "price: 5€".to_string()];
+        let format = FileFormat {
+            encoding: FileEncoding::Latin1,
+            final_newline: false,
+        };
+        let err = write_file(path.to_str().unwrap(), &lines, format).unwrap_err();
+        assert!(err.to_string().contains("line 2, column 9"), "{}", err);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "kept");
+    }
+
+    #[test]
+    fn test_write_file_leaves_missing_dirs_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("srx/main.rs");
+        let path = path.to_str().unwrap();
+        let lines = vec!["fn main() {}".to_string()];
+
+        assert!(write_file(path, &lines, FileFormat::default()).is_err());
+        assert!(!dir.path().join("srx").exists());
+        create_parent_dirs(path).unwrap();
+        write_file(path, &lines, FileFormat::default()).unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "fn main() {}");
+    }
+
+    #[test]
+    fn test_resolve_path_follows_links_and_dots_of_missing_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = fs::canonicalize(dir.path()).unwrap();
+        fs::create_dir(root.join("sub")).unwrap();
+        assert_eq!(
+            resolve_path(&root.join("sub/new/../a.txt")).unwrap(),
+            root.join("sub/a.txt")
+        );
+        assert_eq!(
+            resolve_path(&root.join("sub/../../out.txt")).unwrap(),
+            root.parent().unwrap().join("out.txt")
+        );
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink("/tmp", root.join("link")).unwrap();
+            let resolved = resolve_path(&root.join("link/x.txt")).unwrap();
+            assert_eq!(resolved, fs::canonicalize("/tmp").unwrap().join("x.txt"));
+            std::os::unix::fs::symlink(root.join("gone"), root.join("broken")).unwrap();
+            assert!(resolve_path(&root.join("broken")).is_err());
+        }
+    }
+
+    #[test]
+    fn test_huge_buffer_is_streamed_without_materialising() {
+        let line = "x".repeat(LINE_LEN);
+        let expected_len = LINE_COUNT * (LINE_LEN + 1) - 1;
+
+        let mut counter = CountingWriter::default();
+        {
+            let mut writer = BufWriter::new(&mut counter);
+            write_lines(&mut writer, huge_lines(&line), FileFormat::default()).unwrap();
+        }
+        assert_eq!(counter.total, expected_len);
+        //the buffer reaches the writer in small pieces, never joined into one string
+        assert!(counter.largest_write <= CHUNK_SIZE);
+
+        let file = RepeatReader {
+            line: line.clone().into_bytes(),
+            count: LINE_COUNT,
+            offset: 0,
+        };
+        assert!(!content_differs(file, huge_lines(&line), FileFormat::default()).unwrap());
+
+        let changed_last_line = huge_lines(&line)
+            .take(LINE_COUNT - 1)
+            .chain(std::iter::once("y"));
+        let file = RepeatReader {
+            line: line.clone().into_bytes(),
+            count: LINE_COUNT,
+            offset: 0,
+        };
+        assert!(content_differs(file, changed_last_line, FileFormat::default()).unwrap());
+    }
+}
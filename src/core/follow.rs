@@ -0,0 +1,147 @@
+//following a growing file like tail -f, with the lines written to it appended to the buffer
+
+use crate::config::editor_settings;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::time::{Duration, Instant};
+
+///what changed in the followed file since it was last polled
+#[derive(Debug, PartialEq)]
+pub enum Update {
+    ///complete lines were written to it, the first one continuing the buffer's last line
+    /// if continues_line is set
+    Appended { text: String, continues_line: bool },
+    ///it got shorter than what was read, like a rotated log
+    Truncated,
+}
+
+///the followed file and how far it has been read
+#[derive(Debug)]
+pub struct Follow {
+    pub path: String,
+    ///bytes of the file already in the buffer
+    offset: u64,
+    ///the file doesn't end in a line break, the next line read completes the last one
+    continues_line: bool,
+    last_poll: Instant,
+}
+
+impl Follow {
+    ///follows path from its current end, the buffer holding what it has so far
+    pub fn start(path: &str) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let offset = file.metadata()?.len();
+        let continues_line = offset == 0 || {
+            let mut last = [0u8];
+            file.seek(SeekFrom::Start(offset - 1))?;
+            file.read_exact(&mut last)?;
+            last[0] != b'\n'
+        };
+        Ok(Self {
+            path: path.to_string(),
+            offset,
+            continues_line,
+            last_poll: Instant::now(),
+        })
+    }
+
+    ///checks if the file is due to be polled again
+    pub fn is_due(&self) -> bool {
+        self.last_poll.elapsed() >= Duration::from_millis(editor_settings::FOLLOW_POLL_MS)
+    }
+
+    ///reads what was written to the file since the last poll. a line still being written
+    /// is left for a later poll, so lines are never split.
+    pub fn poll(&mut self) -> io::Result<Option<Update>> {
+        self.last_poll = Instant::now();
+        let len = fs::metadata(&self.path)?.len();
+        if len < self.offset {
+            *self = Self::start(&self.path)?;
+            return Ok(Some(Update::Truncated));
+        }
+        if len == self.offset {
+            return Ok(None);
+        }
+
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut bytes = vec![];
+        file.take(len - self.offset).read_to_end(&mut bytes)?;
+        let Some(last_break) = bytes.iter().rposition(|&byte| byte == b'\n') else {
+            return Ok(None);
+        };
+        self.offset += last_break as u64 + 1;
+        let update = Update::Appended {
+            text: String::from_utf8_lossy(&bytes[..last_break]).into_owned(),
+            continues_line: self.continues_line,
+        };
+        self.continues_line = false;
+        Ok(Some(update))
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_follow_tests {
+    use super::*;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn append(path: &std::path::Path, text: &str) {
+        let mut file = OpenOptions::new().append(true).open(path).unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    fn appended(text: &str, continues_line: bool) -> Option<Update> {
+        Some(Update::Appended {
+            text: text.to_string(),
+            continues_line,
+        })
+    }
+
+    #[test]
+    fn test_only_complete_lines_are_read() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("log");
+        fs::write(&path, "first\n").unwrap();
+        let mut follow = Follow::start(path.to_str().unwrap()).unwrap();
+        assert_eq!(follow.poll().unwrap(), None);
+
+        append(&path, "second\nthi");
+        assert_eq!(follow.poll().unwrap(), appended("second", false));
+        assert_eq!(follow.poll().unwrap(), None);
+        append(&path, "rd\n\n");
+        assert_eq!(follow.poll().unwrap(), appended("third\n", false));
+    }
+
+    #[test]
+    fn test_unfinished_last_line_is_continued() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("log");
+        fs::write(&path, "par").unwrap();
+        let mut follow = Follow::start(path.to_str().unwrap()).unwrap();
+
+        append(&path, "tial\nnext\n");
+        assert_eq!(follow.poll().unwrap(), appended("tial\nnext", true));
+    }
+
+    #[test]
+    fn test_truncated_file_starts_over() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("log");
+        fs::write(&path, "old line\n").unwrap();
+        let mut follow = Follow::start(path.to_str().unwrap()).unwrap();
+
+        fs::write(&path, "new\n").unwrap();
+        assert_eq!(follow.poll().unwrap(), Some(Update::Truncated));
+        append(&path, "more\n");
+        assert_eq!(follow.poll().unwrap(), appended("more", false));
+        assert!(!follow.is_due());
+    }
+}
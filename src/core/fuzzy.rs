@@ -0,0 +1,164 @@
+//fuzzy subsequence matching for pickers like the file finder, the query's chars have to
+// appear in the candidate in order but not next to each other
+
+//score of every matched char, and the bonuses and penalties weighing where it matched
+const MATCH_SCORE: i64 = 16;
+const CONSECUTIVE_BONUS: i64 = 12;
+const BOUNDARY_BONUS: i64 = 8;
+//matches in the last part of a path, the file name, count more than in its dirs
+const FILE_NAME_BONUS: i64 = 4;
+const GAP_PENALTY: i64 = 1;
+
+///where query matched in a candidate and how well
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    //char indexes of the candidate the query's chars matched, in order
+    pub positions: Vec<usize>,
+}
+
+///matches query against candidate, None if its chars don't all appear in order. case is
+/// ignored unless query has an uppercase char, spaces in query are ignored.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let smart_case = query.chars().any(char::is_uppercase);
+    let fold = |c: char| {
+        if smart_case {
+            c
+        } else {
+            c.to_lowercase().next().unwrap_or(c)
+        }
+    };
+    let query: Vec<char> = query.chars().filter(|c| *c != ' ').map(fold).collect();
+    let chars: Vec<char> = candidate.chars().collect();
+    let folded: Vec<char> = chars.iter().copied().map(fold).collect();
+
+    //the first match going forward ends the shortest window, going back from there finds
+    // the window's start, so a stray early char doesn't stretch the match over the path
+    let mut end = 0;
+    for &q in &query {
+        end += folded[end..].iter().position(|&c| c == q)? + 1;
+    }
+    let mut positions = Vec::with_capacity(query.len());
+    let mut i = end;
+    for &q in query.iter().rev() {
+        i = folded[..i].iter().rposition(|&c| c == q)?;
+        positions.push(i);
+    }
+    positions.reverse();
+
+    let file_name_start = chars.iter().rposition(|&c| c == '/').map_or(0, |i| i + 1);
+    let mut score = 0;
+    for (n, &pos) in positions.iter().enumerate() {
+        score += MATCH_SCORE;
+        if n > 0 && positions[n - 1] + 1 == pos {
+            score += CONSECUTIVE_BONUS;
+        }
+        if is_boundary(&chars, pos) {
+            score += BOUNDARY_BONUS;
+        }
+        if pos >= file_name_start {
+            score += FILE_NAME_BONUS;
+        }
+    }
+    if let (Some(first), Some(last)) = (positions.first(), positions.last()) {
+        let gaps = last - first + 1 - positions.len();
+        score -= gaps as i64 * GAP_PENALTY;
+    }
+    Some(FuzzyMatch { score, positions })
+}
+
+///checks if the char at i starts a word, after a separator or as the upper case start
+/// of a camel case word
+fn is_boundary(chars: &[char], i: usize) -> bool {
+    let Some(before) = i.checked_sub(1).map(|i| chars[i]) else {
+        return true;
+    };
+    matches!(before, '/' | '\\' | '_' | '-' | '.' | ' ')
+        || (before.is_lowercase() && chars[i].is_uppercase())
+}
+
+///the candidates query matches, best first, by their index in candidates with the char
+/// indexes matched. equal scores rank shorter candidates first, then keep their order.
+pub fn rank<'a>(
+    query: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Vec<(usize, Vec<usize>)> {
+    let mut matches: Vec<(usize, usize, FuzzyMatch)> = candidates
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, candidate)| {
+            fuzzy_match(query, candidate).map(|m| (i, candidate.chars().count(), m))
+        })
+        .collect();
+    matches.sort_by(|(i, len, m), (other_i, other_len, other)| {
+        other
+            .score
+            .cmp(&m.score)
+            .then(len.cmp(other_len))
+            .then(i.cmp(other_i))
+    });
+    matches
+        .into_iter()
+        .map(|(i, _, m)| (i, m.positions))
+        .collect()
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_fuzzy_tests {
+    use super::*;
+
+    fn positions(query: &str, candidate: &str) -> Option<Vec<usize>> {
+        fuzzy_match(query, candidate).map(|m| m.positions)
+    }
+
+    #[test]
+    fn test_query_chars_have_to_appear_in_order() {
+        assert_eq!(positions("abc", "a_b_c"), Some(vec![0, 2, 4]));
+        assert_eq!(positions("cba", "a_b_c"), None);
+        assert_eq!(positions("abcd", "abc"), None);
+        assert_eq!(positions("", "abc"), Some(vec![]));
+    }
+
+    #[test]
+    fn test_case_is_ignored_unless_query_has_uppercase() {
+        assert_eq!(positions("app", "src/App.rs"), Some(vec![4, 5, 6]));
+        assert_eq!(positions("App", "src/app.rs"), None);
+        assert_eq!(positions("App", "src/App.rs"), Some(vec![4, 5, 6]));
+    }
+
+    //the match is pulled together at the end instead of starting at the first a
+    #[test]
+    fn test_matches_are_as_tight_as_possible() {
+        assert_eq!(positions("app", "a/src/app.rs"), Some(vec![6, 7, 8]));
+        assert_eq!(positions("ace", "a_ab_c_e"), Some(vec![2, 5, 7]));
+    }
+
+    #[test]
+    fn test_positions_are_char_indexes() {
+        assert_eq!(positions("ü", "näh/für.txt"), Some(vec![5]));
+    }
+
+    #[test]
+    fn test_file_names_and_word_starts_rank_first() {
+        let candidates = ["src/main.rs", "benches/render.rs", "src/core/app.rs"];
+        let ranked: Vec<usize> = rank("ar", candidates).into_iter().map(|(i, _)| i).collect();
+        assert_eq!(ranked, vec![2, 0]);
+
+        let candidates = ["src/ui/ui.rs", "src/ui/popups/list_popup.rs", "README.md"];
+        let ranked: Vec<usize> = rank("lp", candidates).into_iter().map(|(i, _)| i).collect();
+        assert_eq!(ranked, vec![1]);
+    }
+
+    #[test]
+    fn test_equal_scores_rank_shorter_candidates_first() {
+        let ranked = rank("main", ["src/main.rs.bak", "src/main.rs"]);
+        assert_eq!(ranked[0].0, 1);
+        assert_eq!(ranked[1].0, 0);
+    }
+}
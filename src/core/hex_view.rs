@@ -0,0 +1,164 @@
+//read-only hex dump of a file, shown in place of its text with :hexview
+
+use super::cursor::CursorPosition;
+use super::editor::Editor;
+
+///bytes shown per row of the dump
+pub const BYTES_PER_ROW: usize = 16;
+///chars before the first hex byte of a row, the offset and two spaces
+const HEX_START: usize = 10;
+///chars before the ascii gutter of a row
+const ASCII_START: usize = HEX_START + BYTES_PER_ROW * 3 + 3;
+
+///the file's bytes and the text editor they replace on screen, kept as it was so
+/// switching back restores its cursor and scroll position
+#[derive(Debug)]
+pub struct HexView {
+    pub bytes: Vec<u8>,
+    pub text_editor: Editor,
+}
+
+impl HexView {
+    pub fn new(bytes: Vec<u8>, text_editor: Editor) -> Self {
+        Self { bytes, text_editor }
+    }
+
+    ///editor holding the dump, sized like the text editor
+    pub fn dump_editor(&self) -> Editor {
+        let mut editor = Editor::from_text(&dump(&self.bytes).join("\n"));
+        editor.editor_height = self.text_editor.editor_height;
+        editor.editor_width = self.text_editor.editor_width;
+        editor
+    }
+
+    ///offset of the first occurrence of pattern after from, wrapping around to the start
+    pub fn find(&self, pattern: &[u8], from: usize) -> Option<usize> {
+        if pattern.is_empty() || pattern.len() > self.bytes.len() {
+            return None;
+        }
+        let matches_at = |offset: &usize| self.bytes[*offset..].starts_with(pattern);
+        let last = self.bytes.len() - pattern.len();
+        (from.min(last + 1)..=last)
+            .chain(0..from.min(last + 1))
+            .find(matches_at)
+    }
+}
+
+///rows of offset, hex bytes split in two groups of eight, and the bytes as ascii with
+/// anything unprintable shown as '.', like hexdump -C
+pub fn dump(bytes: &[u8]) -> Vec<String> {
+    if bytes.is_empty() {
+        return vec![format!("{:08x}", 0)];
+    }
+    bytes
+        .chunks(BYTES_PER_ROW)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let mut line = format!("{:08x}  ", row * BYTES_PER_ROW);
+            for i in 0..BYTES_PER_ROW {
+                match chunk.get(i) {
+                    Some(byte) => line.push_str(&format!("{:02x} ", byte)),
+                    None => line.push_str("   "),
+                }
+                if i == BYTES_PER_ROW / 2 - 1 {
+                    line.push(' ');
+                }
+            }
+            let ascii: String = chunk
+                .iter()
+                .map(|&byte| match byte {
+                    0x20..=0x7e => byte as char,
+                    _ => '.',
+                })
+                .collect();
+            line.push_str(&format!(" |{}|", ascii));
+            line
+        })
+        .collect()
+}
+
+///position of the byte at offset in the dump, on its first hex digit
+pub fn position_of(offset: usize) -> CursorPosition {
+    let i = offset % BYTES_PER_ROW;
+    let x = HEX_START + i * 3 + usize::from(i >= BYTES_PER_ROW / 2);
+    CursorPosition::new(x, offset / BYTES_PER_ROW)
+}
+
+///offset of the byte under pos, in the hex or ascii columns. the row's first byte
+/// elsewhere in the row.
+pub fn offset_at(pos: CursorPosition) -> usize {
+    let row_start = pos.y * BYTES_PER_ROW;
+    let i = if pos.x >= ASCII_START {
+        pos.x - ASCII_START
+    } else if pos.x >= HEX_START {
+        let x = pos.x - HEX_START;
+        //the gap between the two groups of eight
+        let x = if x >= BYTES_PER_ROW / 2 * 3 { x - 1 } else { x };
+        x / 3
+    } else {
+        0
+    };
+    row_start + i.min(BYTES_PER_ROW - 1)
+}
+
+///bytes of a search pattern, given as text or as hex bytes after 0x, like 0x0d0a
+pub fn parse_pattern(pattern: &str) -> Option<Vec<u8>> {
+    let Some(hex) = pattern.strip_prefix("0x") else {
+        return Some(pattern.as_bytes().to_vec());
+    };
+    let digits: Vec<char> = hex.chars().filter(|c| !c.is_whitespace()).collect();
+    if digits.is_empty() || !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    digits
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(&pair.iter().collect::<String>(), 16).ok())
+        .collect()
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_hex_view_tests {
+    use super::*;
+
+    #[test]
+    fn test_dump_rows_match_hexdump() {
+        let rows = dump(b"Hello, world!\r\n\x00tail");
+        assert_eq!(
+            rows,
+            vec![
+                "00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0d 0a 00  |Hello, world!...|",
+                "00000010  74 61 69 6c                                       |tail|",
+            ]
+        );
+        assert_eq!(dump(b""), vec!["00000000"]);
+    }
+
+    #[test]
+    fn test_offsets_and_positions_round_trip() {
+        for offset in [0, 7, 8, 15, 16, 42] {
+            assert_eq!(offset_at(position_of(offset)), offset);
+        }
+        //second digit of a byte and its ascii char are the same byte
+        assert_eq!(offset_at(CursorPosition::new(HEX_START + 8 * 3 + 2, 1)), 24);
+        assert_eq!(offset_at(CursorPosition::new(ASCII_START + 3, 2)), 35);
+    }
+
+    #[test]
+    fn test_find_wraps_and_accepts_hex_patterns() {
+        let view = HexView::new(b"ab\r\nab\r\n".to_vec(), Editor::new());
+        let crlf = parse_pattern("0x0d 0a").unwrap();
+        assert_eq!(view.find(&crlf, 0), Some(2));
+        assert_eq!(view.find(&crlf, 3), Some(6));
+        assert_eq!(view.find(&crlf, 7), Some(2));
+        assert_eq!(view.find(b"ab", 1), Some(4));
+        assert_eq!(view.find(b"zz", 0), None);
+        assert_eq!(parse_pattern("0x0d0"), None);
+        assert_eq!(parse_pattern("0xzz"), None);
+    }
+}
@@ -0,0 +1,82 @@
+//suspending the editor to the shell like ctrl-z does for other terminal programs, and
+// noticing when the shell continues it, unix only. in raw mode the terminal sends no stop
+// signal for ctrl-z, the editor stops itself once it gave the terminal back.
+
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+///tells the run loop the process was continued after being stopped, by fg after the editor
+/// suspended itself or after a stop signal sent from elsewhere left the terminal as it was
+#[derive(Debug)]
+pub struct JobControl {
+    continued: Arc<AtomicBool>,
+}
+
+impl JobControl {
+    ///starts watching for the process being continued
+    #[cfg(unix)]
+    pub fn install() -> io::Result<Self> {
+        let continued = Arc::new(AtomicBool::new(false));
+        signal_hook::flag::register(signal_hook::consts::SIGCONT, Arc::clone(&continued))?;
+        Ok(Self { continued })
+    }
+
+    #[cfg(not(unix))]
+    pub fn install() -> io::Result<Self> {
+        Ok(Self {
+            continued: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    ///checks if the process was continued since the last call, the terminal then needs
+    /// setting up and redrawing
+    pub fn take_continued(&self) -> bool {
+        self.continued.swap(false, Ordering::Relaxed)
+    }
+}
+
+///checks if suspending to the shell is possible here
+pub fn can_suspend() -> bool {
+    cfg!(unix)
+}
+
+///stops the editor's process group like ctrl-z in a shell, returning once it is continued.
+/// the caller gives the terminal back to the shell first.
+#[cfg(unix)]
+pub fn suspend() -> io::Result<()> {
+    //the whole group, so programs piping into the editor stop with it
+    match unsafe { libc::kill(0, libc::SIGTSTP) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn suspend() -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "suspending needs a unix shell",
+    ))
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(all(test, unix))]
+mod unit_job_control_tests {
+    use super::*;
+
+    //a continue signal on a running process changes nothing but the flag
+    #[test]
+    fn test_continue_is_noticed_once() {
+        let job_control = JobControl::install().unwrap();
+        assert!(!job_control.take_continued());
+        signal_hook::low_level::raise(signal_hook::consts::SIGCONT).unwrap();
+        assert!(job_control.take_continued());
+        assert!(!job_control.take_continued());
+    }
+}
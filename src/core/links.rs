@@ -0,0 +1,145 @@
+//file paths and urls in the content, found under the cursor and opened from the editor
+
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+const URL_SCHEMES: &[&str] = &["http://", "https://", "ftp://", "file://", "mailto:"];
+///chars around a token that belong to the surrounding text, like brackets or quotes
+const ENCLOSING_CHARS: &[char] = &['"', '\'', '`', '(', ')', '[', ']', '{', '}', '<', '>'];
+///chars ending a sentence or list that a token is trimmed of at its end
+const TRAILING_CHARS: &[char] = &['.', ',', ';', ':', '!', '?'];
+
+///something under the cursor that can be opened
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+    Url(String),
+    ///a file, with the line to jump to counted from 1 like compilers report them
+    File {
+        path: PathBuf,
+        line: Option<usize>,
+    },
+}
+
+///the url or path of the token at col in line, None if the cursor isn't on a token.
+/// relative paths are resolved against base_dir.
+pub fn target_at(line: &str, col: usize, base_dir: &Path) -> Option<Target> {
+    let token = token_at(line, col)?;
+    if URL_SCHEMES.iter().any(|scheme| token.starts_with(scheme)) {
+        return Some(Target::Url(token.to_string()));
+    }
+    let (path, line) = split_line_suffix(token);
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => Path::new(&env::var_os("HOME")?).join(rest),
+        None => base_dir.join(path),
+    };
+    Some(Target::File { path, line })
+}
+
+///opens url with the platform opener, detached and without output so the terminal
+/// isn't disturbed
+pub fn open_url(url: &str) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+///the whitespace separated token at col, without enclosing brackets or quotes and
+/// punctuation at its end
+fn token_at(line: &str, col: usize) -> Option<&str> {
+    let chars: Vec<(usize, char)> = line.char_indices().collect();
+    let is_token_char = |i: usize| chars.get(i).is_some_and(|(_, c)| !c.is_whitespace());
+    //a cursor right after a token counts as on it
+    let col = if !is_token_char(col) && col > 0 && is_token_char(col - 1) {
+        col - 1
+    } else {
+        col
+    };
+    if !is_token_char(col) {
+        return None;
+    }
+    let start = (0..col)
+        .rev()
+        .take_while(|&i| is_token_char(i))
+        .last()
+        .unwrap_or(col);
+    let end = (col..chars.len())
+        .take_while(|&i| is_token_char(i))
+        .last()?
+        + 1;
+    let end_byte = chars.get(end).map_or(line.len(), |(byte, _)| *byte);
+    let token = line[chars[start].0..end_byte]
+        .trim_start_matches(ENCLOSING_CHARS)
+        .trim_end_matches(|c| ENCLOSING_CHARS.contains(&c) || TRAILING_CHARS.contains(&c));
+    (!token.is_empty()).then_some(token)
+}
+
+///splits a line suffix like main.rs:12 or main.rs:12:5 from a path
+fn split_line_suffix(token: &str) -> (&str, Option<usize>) {
+    let mut parts = token.splitn(3, ':');
+    let path = parts.next().unwrap_or(token);
+    match parts.next().map(str::parse::<usize>) {
+        Some(Ok(line)) if !path.is_empty() => (path, Some(line)),
+        _ => (token, None),
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_links_tests {
+    use super::*;
+
+    fn file(path: &str, line: Option<usize>) -> Option<Target> {
+        Some(Target::File {
+            path: PathBuf::from(path),
+            line,
+        })
+    }
+
+    #[test]
+    fn test_tokens_are_trimmed_of_surrounding_punctuation() {
+        let line = "see (docs/guide.md), or \"https://example.com/a?b=1\".";
+        assert_eq!(token_at(line, 8), Some("docs/guide.md"));
+        assert_eq!(token_at(line, 30), Some("https://example.com/a?b=1"));
+        assert_eq!(token_at("a  b", 2), None);
+        //right after a token
+        assert_eq!(token_at("open x.txt", 10), Some("x.txt"));
+    }
+
+    #[test]
+    fn test_urls_and_paths_with_line_numbers() {
+        let base = Path::new("/work");
+        assert_eq!(
+            target_at("at https://x.org/page", 5, base),
+            Some(Target::Url("https://x.org/page".to_string()))
+        );
+        assert_eq!(
+            target_at("error: src/main.rs:12:5: oops", 10, base),
+            file("/work/src/main.rs", Some(12))
+        );
+        assert_eq!(
+            target_at("/etc/hosts:3", 0, base),
+            file("/etc/hosts", Some(3))
+        );
+        assert_eq!(target_at("notes.md", 0, base), file("/work/notes.md", None));
+    }
+}
@@ -0,0 +1,351 @@
+//advisory locks on open files, so a second calliglyph editing the same file can warn
+// before one save clobbers the other's work
+
+use super::session::{self, absolute_path, path_hash};
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const LOCK_SUFFIX: &str = "calli-lock";
+///dir in the runtime dir holding locks of files in dirs that can't be written
+const REGISTRY_DIR: &str = "locks";
+
+///the instance holding a lock, as written in the lock file
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockOwner {
+    pub pid: u32,
+    ///unix time the lock was taken, in seconds
+    pub since: u64,
+}
+
+impl LockOwner {
+    fn current() -> Self {
+        Self {
+            pid: process::id(),
+            since: now().as_secs(),
+        }
+    }
+
+    ///how long the lock has been held
+    pub fn held_for(&self) -> Duration {
+        now().saturating_sub(Duration::from_secs(self.since))
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        let (pid, since) = text.trim_end().split_once('\t')?;
+        Some(Self {
+            pid: pid.parse().ok()?,
+            since: since.parse().ok()?,
+        })
+    }
+}
+
+///why a lock couldn't be taken
+#[derive(Debug)]
+pub enum LockError {
+    ///another running instance holds it
+    Held(LockOwner),
+    ///neither next to the file nor in the registry could a lock be written
+    Io(io::Error),
+}
+
+///a lock held by this instance, removed again when dropped
+#[derive(Debug)]
+pub struct FileLock {
+    ///absolute path of the locked file
+    file: String,
+    path: PathBuf,
+}
+
+impl FileLock {
+    ///checks if the lock is the one of file
+    pub fn is_for(&self, file: &str) -> bool {
+        self.file == absolute_path(file)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        //a lock taken over after it was found stale belongs to someone else now
+        if read_owner(&self.path).is_some_and(|owner| owner.pid == process::id()) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+///locks file for this instance. the lock goes next to the file as .<name>.calli-lock, or
+/// into the registry in the runtime dir if the file's dir can't be written. locks of
+/// instances no longer running are taken over.
+pub fn acquire(file: &str) -> Result<FileLock, LockError> {
+    let sibling = sibling_path(file);
+    let registry = registry_path(file);
+    //a lock in either place counts, an instance may have fallen back to the registry
+    for path in [&sibling, &registry] {
+        if let Some(owner) = live_owner(path) {
+            return Err(LockError::Held(owner));
+        }
+    }
+    match create(&sibling) {
+        Ok(()) => Ok(FileLock {
+            file: absolute_path(file),
+            path: sibling,
+        }),
+        Err(LockError::Held(owner)) => Err(LockError::Held(owner)),
+        Err(LockError::Io(_)) => {
+            if let Some(dir) = registry.parent() {
+                fs::create_dir_all(dir).map_err(LockError::Io)?;
+            }
+            create(&registry)?;
+            Ok(FileLock {
+                file: absolute_path(file),
+                path: registry,
+            })
+        }
+    }
+}
+
+///the lock file next to file
+fn sibling_path(file: &str) -> PathBuf {
+    let path = Path::new(file);
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.{}", name, LOCK_SUFFIX))
+}
+
+///the lock file of file in the registry, named by the hash of its absolute path
+fn registry_path(file: &str) -> PathBuf {
+    session::runtime_dir()
+        .join(REGISTRY_DIR)
+        .join(format!("{}.{}", path_hash(file), LOCK_SUFFIX))
+}
+
+///owner of the lock at path if it is another instance that is still running. a stale
+/// lock is removed, so is one that can't be read: locks appear only once fully written.
+fn live_owner(path: &Path) -> Option<LockOwner> {
+    if !path.exists() {
+        return None;
+    }
+    match read_owner(path) {
+        Some(owner) if owner.pid == process::id() => None,
+        Some(owner) if is_running(owner.pid) => Some(owner),
+        _ => {
+            let _ = fs::remove_file(path);
+            None
+        }
+    }
+}
+
+fn read_owner(path: &Path) -> Option<LockOwner> {
+    LockOwner::parse(&fs::read_to_string(path).ok()?)
+}
+
+///writes a lock of this instance to path. a lock already there is never written over: one
+/// of this instance is kept, a stale one is removed and creating the lock tried once more,
+/// and one still there after that is held by another instance.
+fn create(path: &Path) -> Result<(), LockError> {
+    match create_new(path) {
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+        result => return result.map_err(LockError::Io),
+    }
+    if let Some(owner) = live_owner(path) {
+        return Err(LockError::Held(owner));
+    }
+    if read_owner(path).is_some_and(|owner| owner.pid == process::id()) {
+        return Ok(());
+    }
+    match create_new(path) {
+        //another instance took the stale lock first
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => match read_owner(path) {
+            Some(owner) => Err(LockError::Held(owner)),
+            None => Err(LockError::Io(e)),
+        },
+        result => result.map_err(LockError::Io),
+    }
+}
+
+///writes a lock of this instance to a new file at path, failing if there is one. the lock
+/// is written in full to a file of its own first and then linked in as path, so another
+/// instance never reads a lock that is still empty and takes it for a broken one.
+fn create_new(path: &Path) -> io::Result<()> {
+    let owner = LockOwner::current();
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp = path.with_file_name(format!("{}.{}.tmp", name, owner.pid));
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&temp)?;
+    let written = writeln!(file, "{}\t{}", owner.pid, owner.since)
+        .and_then(|()| file.sync_all())
+        //a link is never made over an existing file, unlike a rename
+        .and_then(|()| fs::hard_link(&temp, path));
+    let _ = fs::remove_file(&temp);
+    written
+}
+
+///checks if a process with pid is running. one that exists but can't be signalled by this
+/// user is running too.
+#[cfg(unix)]
+fn is_running(pid: u32) -> bool {
+    //0 and negative pids would signal process groups instead
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    if pid == 0 {
+        return false;
+    }
+    match unsafe { libc::kill(pid, 0) } {
+        0 => true,
+        _ => io::Error::last_os_error().raw_os_error() == Some(libc::EPERM),
+    }
+}
+
+///without a way to tell the process is assumed to be running, so a lock is never taken
+/// from a live instance
+#[cfg(not(unix))]
+fn is_running(_pid: u32) -> bool {
+    true
+}
+
+fn now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_lock_tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    ///pid of a process that has exited
+    fn dead_pid() -> u32 {
+        let mut child = process::Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        child.wait().unwrap();
+        pid
+    }
+
+    fn write_lock(path: &Path, pid: u32) {
+        fs::write(path, format!("{}\t{}\n", pid, now().as_secs())).unwrap();
+    }
+
+    #[test]
+    fn test_lock_is_written_next_to_file_and_removed_on_drop() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        let file = file.to_str().unwrap();
+
+        let lock = acquire(file).unwrap();
+        let path = dir.path().join(".notes.txt.calli-lock");
+        assert_eq!(lock.path(), path);
+        assert_eq!(read_owner(&path).unwrap().pid, process::id());
+        //this instance may lock it again
+        assert!(acquire(file).is_ok());
+
+        drop(lock);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_live_lock_is_reported_and_stale_one_taken_over() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("notes.txt");
+        let file = file.to_str().unwrap();
+        let path = sibling_path(file);
+
+        //pid 1 is always running
+        write_lock(&path, 1);
+        match acquire(file) {
+            Err(LockError::Held(owner)) => assert_eq!(owner.pid, 1),
+            other => panic!("expected a held lock, got {:?}", other),
+        }
+
+        write_lock(&path, dead_pid());
+        let lock = acquire(file).unwrap();
+        assert_eq!(read_owner(lock.path()).unwrap().pid, process::id());
+
+        fs::write(&path, "garbage").unwrap();
+        assert!(acquire(file).is_ok());
+    }
+
+    #[test]
+    fn test_create_never_writes_over_a_live_lock() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".notes.txt.calli-lock");
+
+        write_lock(&path, 1);
+        match create(&path) {
+            Err(LockError::Held(owner)) => assert_eq!(owner.pid, 1),
+            other => panic!("expected a held lock, got {:?}", other),
+        }
+        assert_eq!(read_owner(&path).unwrap().pid, 1);
+
+        write_lock(&path, dead_pid());
+        create(&path).unwrap();
+        assert_eq!(read_owner(&path).unwrap().pid, process::id());
+        //a lock of this instance is kept as it is
+        create(&path).unwrap();
+        assert_eq!(read_owner(&path).unwrap().pid, process::id());
+    }
+
+    #[test]
+    fn test_create_new_links_a_whole_lock_and_leaves_no_temp_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".notes.txt.calli-lock");
+
+        create_new(&path).unwrap();
+        assert_eq!(read_owner(&path).unwrap().pid, process::id());
+        let err = create_new(&path).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+        let names: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, vec![".notes.txt.calli-lock"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_is_running_signals_the_pid_itself() {
+        assert!(is_running(process::id()));
+        //pid 1 is always running, even where it can't be signalled
+        assert!(is_running(1));
+        assert!(!is_running(dead_pid()));
+        assert!(!is_running(0));
+        assert!(!is_running(u32::MAX));
+    }
+
+    #[test]
+    fn test_owner_parses_pid_and_time() {
+        assert_eq!(
+            LockOwner::parse("42\t1700000000\n"),
+            Some(LockOwner {
+                pid: 42,
+                since: 1_700_000_000
+            })
+        );
+        assert_eq!(LockOwner::parse("42"), None);
+        assert_eq!(
+            sibling_path("/tmp/a.rs"),
+            PathBuf::from("/tmp/.a.rs.calli-lock")
+        );
+    }
+}
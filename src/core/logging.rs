@@ -0,0 +1,82 @@
+//optional debug log written to a file, for diagnosing reports about the tui. nothing is
+// logged anywhere unless it is turned on with --log. buffer contents are only ever logged at
+// debug level or below.
+
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::level_filters::LevelFilter;
+use tracing::Subscriber;
+
+///names --log-level takes, from least to most verbose
+pub const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+
+///parses a --log-level name
+pub fn parse_level(name: &str) -> Option<LevelFilter> {
+    match name.to_ascii_lowercase().as_str() {
+        "off" => Some(LevelFilter::OFF),
+        "error" => Some(LevelFilter::ERROR),
+        "warn" => Some(LevelFilter::WARN),
+        "info" => Some(LevelFilter::INFO),
+        "debug" => Some(LevelFilter::DEBUG),
+        "trace" => Some(LevelFilter::TRACE),
+        _ => None,
+    }
+}
+
+///subscriber appending events up to level to file, without colors
+fn subscriber(file: File, level: LevelFilter) -> impl Subscriber + Send + Sync {
+    tracing_subscriber::fmt()
+        .with_writer(Mutex::new(file))
+        .with_max_level(level)
+        .with_target(false)
+        .finish()
+}
+
+///starts logging events up to level to the file at path, appending to it and creating it
+/// and its dir if needed. can only be started once per run.
+pub fn init(path: &Path, level: LevelFilter) -> io::Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    tracing::subscriber::set_global_default(subscriber(file, level)).map_err(io::Error::other)?;
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), %level, "logging started");
+    Ok(())
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_logging_tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_levels() {
+        for name in LOG_LEVELS {
+            assert!(parse_level(name).is_some(), "{}", name);
+        }
+        assert_eq!(parse_level("DEBUG"), Some(LevelFilter::DEBUG));
+        assert_eq!(parse_level("loud"), None);
+    }
+
+    #[test]
+    fn test_events_above_level_are_left_out() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("calliglyph.log");
+        let file = File::create(&path).unwrap();
+        tracing::subscriber::with_default(subscriber(file, LevelFilter::INFO), || {
+            tracing::info!(command = "w", "running command");
+            tracing::debug!(line = "secret text", "buffer line");
+        });
+        let log = fs::read_to_string(&path).unwrap();
+        assert!(log.contains("running command command=\"w\""));
+        assert!(!log.contains("secret text"));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::input::input_action::InputAction;
+use std::collections::BTreeMap;
+
+///state of a macro key chord waiting for its register (and count) to be typed
+#[derive(Debug, Clone, PartialEq)]
+pub enum MacroChord {
+    Record,
+    Play { count: usize },
+}
+
+/// handles recording and storing keyboard macros in named registers
+#[derive(Debug, Default)]
+pub struct MacroRecorder {
+    pub registers: BTreeMap<char, Vec<InputAction>>,
+    recording: Option<(char, Vec<InputAction>)>,
+    pub pending_chord: Option<MacroChord>,
+}
+
+impl MacroRecorder {
+    pub fn new() -> Self {
+        Self {
+            registers: BTreeMap::new(),
+            recording: None,
+            pending_chord: None,
+        }
+    }
+
+    ///returns the register currently being recorded into, if any
+    pub fn recording_register(&self) -> Option<char> {
+        self.recording.as_ref().map(|(register, _)| *register)
+    }
+
+    ///starts recording into register, discarding what was recorded there before
+    pub fn start_recording(&mut self, register: char) {
+        self.recording = Some((register, vec![]));
+    }
+
+    ///stops recording and stores the recorded actions in its register
+    pub fn stop_recording(&mut self) {
+        if let Some((register, actions)) = self.recording.take() {
+            self.registers.insert(register, actions);
+        }
+    }
+
+    ///appends an action to the macro currently being recorded
+    pub fn record(&mut self, action: &InputAction) {
+        if let Some((_, actions)) = self.recording.as_mut() {
+            actions.push(action.clone());
+        }
+    }
+
+    ///returns a copy of the actions stored in register
+    pub fn get(&self, register: char) -> Option<Vec<InputAction>> {
+        self.registers.get(&register).cloned()
+    }
+
+    ///feeds an action to a pending chord, returns the chord once a register is chosen.
+    /// digits typed after the play chord build up the replay count.
+    pub fn feed_chord(&mut self, action: &InputAction) -> Option<(MacroChord, char)> {
+        let chord = self.pending_chord.take()?;
+        match (chord, action) {
+            (MacroChord::Play { count }, InputAction::WriteChar(c) | InputAction::ModalKey(c))
+                if c.is_ascii_digit() =>
+            {
+                let digit = c.to_digit(10).unwrap_or(0) as usize;
+                self.pending_chord = Some(MacroChord::Play {
+                    count: count.saturating_mul(10).saturating_add(digit),
+                });
+                None
+            }
+            (chord, InputAction::WriteChar(c) | InputAction::ModalKey(c))
+                if c.is_ascii_alphabetic() =>
+            {
+                Some((chord, *c))
+            }
+            //any other action aborts the chord
+            _ => None,
+        }
+    }
+
+    ///lists the recorded registers with a short preview of their actions
+    pub fn describe_registers(&self) -> Vec<String> {
+        self.registers
+            .iter()
+            .map(|(register, actions)| {
+                let preview: String = actions.iter().map(describe_action).collect();
+                format!("{}: ({} actions) {}", register, actions.len(), preview)
+            })
+            .collect()
+    }
+}
+
+///short human-readable form of an action, used to preview macros
+fn describe_action(action: &InputAction) -> String {
+    match action {
+        InputAction::WriteChar(c) => c.to_string(),
+        InputAction::MoveCursor(direction) => format!("<{:?}>", direction),
+        InputAction::MoveSelectionCursor(direction) => format!("<S-{:?}>", direction),
+        other => format!("<{:?}>", other),
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_macro_tests {
+    use super::*;
+    use crate::input::input_action::Direction;
+
+    #[test]
+    fn test_record_stores_actions_in_register() {
+        let mut recorder = MacroRecorder::new();
+        recorder.start_recording('a');
+        recorder.record(&InputAction::WriteChar('x'));
+        recorder.record(&InputAction::MoveCursor(Direction::Down));
+        assert_eq!(recorder.recording_register(), Some('a'));
+        recorder.stop_recording();
+
+        assert_eq!(recorder.recording_register(), None);
+        assert_eq!(
+            recorder.get('a'),
+            Some(vec![
+                InputAction::WriteChar('x'),
+                InputAction::MoveCursor(Direction::Down)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_record_without_recording_does_nothing() {
+        let mut recorder = MacroRecorder::new();
+        recorder.record(&InputAction::WriteChar('x'));
+        assert!(recorder.registers.is_empty());
+    }
+
+    #[test]
+    fn test_play_chord_collects_count_and_register() {
+        let mut recorder = MacroRecorder::new();
+        recorder.pending_chord = Some(MacroChord::Play { count: 0 });
+        assert_eq!(recorder.feed_chord(&InputAction::WriteChar('1')), None);
+        assert_eq!(recorder.feed_chord(&InputAction::WriteChar('2')), None);
+        assert_eq!(
+            recorder.feed_chord(&InputAction::WriteChar('q')),
+            Some((MacroChord::Play { count: 12 }, 'q'))
+        );
+        assert!(recorder.pending_chord.is_none());
+    }
+
+    #[test]
+    fn test_chord_aborted_by_other_action() {
+        let mut recorder = MacroRecorder::new();
+        recorder.pending_chord = Some(MacroChord::Record);
+        assert_eq!(recorder.feed_chord(&InputAction::ENTER), None);
+        assert!(recorder.pending_chord.is_none());
+    }
+}
@@ -1,6 +1,32 @@
 pub mod app;
+pub mod background_save;
+pub mod batch;
+pub mod bell;
+pub mod calc;
 pub mod clipboard;
 pub mod command_line;
 pub mod cursor;
+pub mod diff;
 pub mod editor;
+pub mod elevated_save;
 pub mod errors;
+pub mod file_finder;
+pub mod file_format;
+pub mod file_io;
+pub mod follow;
+pub mod fuzzy;
+pub mod hex_view;
+pub mod job_control;
+pub mod links;
+pub mod lock;
+pub mod logging;
+pub mod macros;
+pub mod modal;
+pub mod position;
+pub mod session;
+pub mod settings;
+pub mod spell;
+pub mod terminal;
+pub mod undo_file;
+pub mod unicode;
+pub mod viewer;
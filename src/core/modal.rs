@@ -0,0 +1,207 @@
+use crate::input::input_action::{Direction, InputAction, ViewAlign};
+
+/// modes of the optional vim-style modal editing
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum EditorMode {
+    #[default]
+    Normal,
+    Insert,
+    Visual,
+}
+
+impl EditorMode {
+    ///label shown in the status area
+    pub fn label(&self) -> &'static str {
+        match self {
+            EditorMode::Normal => "NORMAL",
+            EditorMode::Insert => "INSERT",
+            EditorMode::Visual => "VISUAL",
+        }
+    }
+}
+
+/// handles the current mode and operator keys waiting for their motion, like the first d of dd
+#[derive(Debug, Default)]
+pub struct ModalState {
+    pub mode: EditorMode,
+    pending_operator: Option<char>,
+}
+
+impl ModalState {
+    pub fn new() -> Self {
+        Self {
+            mode: EditorMode::Normal,
+            pending_operator: None,
+        }
+    }
+
+    ///returns to normal mode, dropping any pending operator
+    pub fn enter_normal_mode(&mut self) {
+        self.mode = EditorMode::Normal;
+        self.pending_operator = None;
+    }
+
+    ///resolves a key typed in normal or visual mode to the editor actions it stands for,
+    /// updating the mode on the way. at_line_end tells if the cursor is past the last char.
+    pub fn resolve_key(&mut self, c: char, at_line_end: bool) -> Vec<InputAction> {
+        if let Some(operator) = self.pending_operator.take() {
+            return match (operator, c) {
+                ('d', 'd') => vec![InputAction::CutLine],
+                ('y', 'y') => vec![InputAction::CopyLine],
+                ('z', 'z') => vec![InputAction::AlignView(ViewAlign::Center)],
+                ('z', 't') => vec![InputAction::AlignView(ViewAlign::Top)],
+                ('z', 'b') => vec![InputAction::AlignView(ViewAlign::Bottom)],
+                _ => vec![],
+            };
+        }
+
+        let visual = self.mode == EditorMode::Visual;
+        let motion = |direction: Direction| {
+            if visual {
+                vec![InputAction::MoveSelectionCursor(direction)]
+            } else {
+                vec![InputAction::MoveCursor(direction)]
+            }
+        };
+
+        match c {
+            'h' => motion(Direction::Left),
+            'j' => motion(Direction::Down),
+            'k' => motion(Direction::Up),
+            'l' if !at_line_end => motion(Direction::Right),
+            'i' => {
+                self.mode = EditorMode::Insert;
+                vec![]
+            }
+            'a' => {
+                self.mode = EditorMode::Insert;
+                if at_line_end {
+                    vec![]
+                } else {
+                    vec![InputAction::MoveCursor(Direction::Right)]
+                }
+            }
+            'v' if visual => {
+                self.mode = EditorMode::Normal;
+                vec![]
+            }
+            'v' => {
+                self.mode = EditorMode::Visual;
+                vec![]
+            }
+            'x' | 'd' if visual => {
+                self.mode = EditorMode::Normal;
+                vec![InputAction::CUT]
+            }
+            'y' if visual => {
+                self.mode = EditorMode::Normal;
+                vec![InputAction::COPY]
+            }
+            'o' if visual => vec![InputAction::SwapSelectionEnds],
+            'x' if !at_line_end => vec![
+                InputAction::MoveSelectionCursor(Direction::Right),
+                InputAction::CUT,
+            ],
+            'd' | 'y' | 'z' => {
+                self.pending_operator = Some(c);
+                vec![]
+            }
+            'p' => vec![InputAction::PASTE],
+            'u' => vec![InputAction::UNDO],
+            _ => vec![],
+        }
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_modal_tests {
+    use super::*;
+
+    #[test]
+    fn test_hjkl_resolve_to_cursor_moves() {
+        let mut state = ModalState::new();
+        assert_eq!(
+            state.resolve_key('j', false),
+            vec![InputAction::MoveCursor(Direction::Down)]
+        );
+        assert_eq!(
+            state.resolve_key('h', false),
+            vec![InputAction::MoveCursor(Direction::Left)]
+        );
+    }
+
+    #[test]
+    fn test_visual_mode_motions_extend_selection() {
+        let mut state = ModalState::new();
+        state.resolve_key('v', false);
+        assert_eq!(state.mode, EditorMode::Visual);
+        assert_eq!(
+            state.resolve_key('l', false),
+            vec![InputAction::MoveSelectionCursor(Direction::Right)]
+        );
+        assert_eq!(state.resolve_key('y', false), vec![InputAction::COPY]);
+        assert_eq!(state.mode, EditorMode::Normal);
+    }
+
+    #[test]
+    fn test_o_in_visual_mode_swaps_selection_ends() {
+        let mut state = ModalState::new();
+        assert_eq!(state.resolve_key('o', false), vec![]);
+        state.resolve_key('v', false);
+        assert_eq!(
+            state.resolve_key('o', false),
+            vec![InputAction::SwapSelectionEnds]
+        );
+        assert_eq!(state.mode, EditorMode::Visual);
+    }
+
+    #[test]
+    fn test_dd_and_yy_need_two_keys() {
+        let mut state = ModalState::new();
+        assert!(state.resolve_key('d', false).is_empty());
+        assert_eq!(state.resolve_key('d', false), vec![InputAction::CutLine]);
+        assert!(state.resolve_key('y', false).is_empty());
+        assert_eq!(state.resolve_key('y', false), vec![InputAction::CopyLine]);
+    }
+
+    #[test]
+    fn test_z_chords_align_the_view() {
+        let mut state = ModalState::new();
+        assert!(state.resolve_key('z', false).is_empty());
+        assert_eq!(
+            state.resolve_key('z', false),
+            vec![InputAction::AlignView(ViewAlign::Center)]
+        );
+        state.resolve_key('z', false);
+        assert_eq!(
+            state.resolve_key('b', false),
+            vec![InputAction::AlignView(ViewAlign::Bottom)]
+        );
+    }
+
+    #[test]
+    fn test_i_and_a_enter_insert_mode() {
+        let mut state = ModalState::new();
+        assert!(state.resolve_key('i', false).is_empty());
+        assert_eq!(state.mode, EditorMode::Insert);
+
+        let mut state = ModalState::new();
+        assert_eq!(
+            state.resolve_key('a', false),
+            vec![InputAction::MoveCursor(Direction::Right)]
+        );
+        assert_eq!(state.mode, EditorMode::Insert);
+    }
+
+    #[test]
+    fn test_x_at_line_end_does_nothing() {
+        let mut state = ModalState::new();
+        assert!(state.resolve_key('x', true).is_empty());
+    }
+}
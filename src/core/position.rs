@@ -0,0 +1,127 @@
+//typed positions and ranges in the editor content, so coordinates don't have to be passed
+// around as loose x/y pairs and cast between integer types
+
+use crate::core::cursor::{Cursor, CursorPosition};
+
+///a position in the editor content, col counted in chars. positions order by line first,
+/// then by column, like they appear in the text.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, col: usize) -> Self {
+        Self { line, col }
+    }
+}
+
+impl From<CursorPosition> for Position {
+    fn from(pos: CursorPosition) -> Self {
+        Self::new(pos.y, pos.x)
+    }
+}
+
+impl From<Position> for CursorPosition {
+    fn from(pos: Position) -> Self {
+        CursorPosition::new(pos.col, pos.line)
+    }
+}
+
+impl From<Cursor> for Position {
+    fn from(cursor: Cursor) -> Self {
+        Self::new(cursor.y, cursor.x)
+    }
+}
+
+impl From<Position> for Cursor {
+    fn from(pos: Position) -> Self {
+        Cursor {
+            x: pos.col,
+            y: pos.line,
+        }
+    }
+}
+
+///the text between two positions, end excluded. start may come after end, like a
+/// selection made backwards, normalized turns it around.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Range {
+    pub fn new(start: Position, end: Position) -> Self {
+        Self { start, end }
+    }
+
+    ///the same range with start before end
+    pub fn normalized(self) -> Self {
+        if self.start <= self.end {
+            self
+        } else {
+            Self::new(self.end, self.start)
+        }
+    }
+
+    ///checks if the range holds no text
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    ///checks if pos is in the range, at its start or before its end
+    pub fn contains(&self, pos: Position) -> bool {
+        let range = self.normalized();
+        range.start <= pos && pos < range.end
+    }
+
+    ///indexes of the lines the range touches
+    pub fn lines(&self) -> std::ops::RangeInclusive<usize> {
+        let range = self.normalized();
+        range.start.line..=range.end.line
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_position_tests {
+    use super::*;
+
+    #[test]
+    fn test_positions_order_by_line_then_column() {
+        assert!(Position::new(0, 9) < Position::new(1, 0));
+        assert!(Position::new(2, 1) < Position::new(2, 3));
+        assert_eq!(
+            Position::new(1, 5).max(Position::new(1, 2)),
+            Position::new(1, 5)
+        );
+    }
+
+    #[test]
+    fn test_backwards_range_normalizes_and_contains() {
+        let range = Range::new(Position::new(3, 2), Position::new(1, 4));
+        let normalized = range.normalized();
+        assert_eq!(normalized.start, Position::new(1, 4));
+        assert_eq!(normalized.end, Position::new(3, 2));
+        assert!(range.contains(Position::new(1, 4)));
+        assert!(range.contains(Position::new(2, 100)));
+        assert!(!range.contains(Position::new(3, 2)));
+        assert_eq!(range.lines(), 1..=3);
+        assert!(Range::new(Position::new(1, 1), Position::new(1, 1)).is_empty());
+    }
+
+    #[test]
+    fn test_conversions_swap_x_and_y_into_col_and_line() {
+        let pos = Position::from(CursorPosition::new(7, 40_000));
+        assert_eq!(pos, Position::new(40_000, 7));
+        assert_eq!(CursorPosition::from(pos), CursorPosition::new(7, 40_000));
+        assert_eq!(Position::from(Cursor::from(pos)), pos);
+    }
+}
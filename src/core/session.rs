@@ -0,0 +1,295 @@
+use crate::config::editor_settings;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const POSITIONS_FILE: &str = "positions";
+const RECENT_FILE: &str = "recent";
+
+///position to restore when a file is opened again
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FilePosition {
+    pub x: usize,
+    pub y: usize,
+    pub scroll_offset: usize,
+}
+
+/// remembered cursor positions per file and recently used files, most recent first,
+/// stored in the state dir
+#[derive(Debug, Default)]
+pub struct Session {
+    ///dir the session is stored in, None keeps it in memory only
+    dir: Option<PathBuf>,
+    positions: Vec<(String, FilePosition)>,
+    recent: Vec<String>,
+}
+
+impl Session {
+    ///session that is never written to disk
+    pub fn in_memory() -> Self {
+        Self {
+            dir: None,
+            positions: vec![],
+            recent: vec![],
+        }
+    }
+
+    ///loads the session stored in dir, missing or unreadable files give an empty session
+    pub fn load(dir: PathBuf) -> Self {
+        let read_lines = |name: &str| {
+            fs::read_to_string(dir.join(name))
+                .map(|contents| contents.lines().map(String::from).collect::<Vec<_>>())
+                .unwrap_or_default()
+        };
+        let positions = read_lines(POSITIONS_FILE)
+            .iter()
+            .filter_map(|line| parse_line(line))
+            .collect();
+        let recent = read_lines(RECENT_FILE);
+        Self {
+            dir: Some(dir),
+            positions,
+            recent,
+        }
+    }
+
+    ///loads the session from the default state dir, in memory if there is none
+    pub fn load_default() -> Self {
+        match state_dir() {
+            Some(dir) => Self::load(dir),
+            None => Self::in_memory(),
+        }
+    }
+
+    ///dir the session is stored in, None for one kept in memory
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    pub fn get(&self, file: &str) -> Option<FilePosition> {
+        let key = absolute_path(file);
+        self.positions
+            .iter()
+            .find(|(path, _)| *path == key)
+            .map(|(_, position)| *position)
+    }
+
+    ///stores position for file as the most recent one, forgetting the oldest files over the limit
+    pub fn remember(&mut self, file: &str, position: FilePosition) {
+        let key = absolute_path(file);
+        self.positions.retain(|(path, _)| *path != key);
+        self.positions.insert(0, (key, position));
+        self.positions.truncate(editor_settings::SESSION_MAX_FILES);
+    }
+
+    ///recently opened or saved files, most recent first
+    pub fn recent_files(&self) -> &[String] {
+        &self.recent
+    }
+
+    ///stores file as the most recently used one
+    pub fn add_recent(&mut self, file: &str) {
+        let key = absolute_path(file);
+        self.recent.retain(|path| *path != key);
+        self.recent.insert(0, key);
+        self.recent.truncate(editor_settings::RECENT_FILES_MAX);
+    }
+
+    pub fn remove_recent(&mut self, file: &str) {
+        self.recent.retain(|path| path != file);
+    }
+
+    ///writes the session to its files, creating the state dir if needed
+    pub fn save(&self) -> io::Result<()> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        fs::create_dir_all(dir)?;
+        let positions: String = self
+            .positions
+            .iter()
+            .map(|(file, p)| format!("{}\t{}\t{}\t{}\n", p.y, p.x, p.scroll_offset, file))
+            .collect();
+        fs::write(dir.join(POSITIONS_FILE), positions)?;
+        let recent: String = self
+            .recent
+            .iter()
+            .map(|file| format!("{}\n", file))
+            .collect();
+        fs::write(dir.join(RECENT_FILE), recent)
+    }
+}
+
+///parses a "line column scroll path" line, separated by tabs with the path last
+fn parse_line(line: &str) -> Option<(String, FilePosition)> {
+    let mut parts = line.splitn(4, '\t');
+    let y = parts.next()?.parse().ok()?;
+    let x = parts.next()?.parse().ok()?;
+    let scroll_offset = parts.next()?.parse().ok()?;
+    let file = parts.next()?.to_string();
+    Some((
+        file,
+        FilePosition {
+            x,
+            y,
+            scroll_offset,
+        },
+    ))
+}
+
+///absolute form of path, so the same file opened from different dirs shares its entry
+pub(crate) fn absolute_path(path: &str) -> String {
+    fs::canonicalize(path)
+        .or_else(|_| env::current_dir().map(|dir| dir.join(path)))
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+///hash of the absolute form of path in hex, to name files kept per file. stable between
+/// runs, unlike the std hasher.
+pub(crate) fn path_hash(path: &str) -> String {
+    format!("{:016x}", fnv1a(absolute_path(path).as_bytes(), FNV_OFFSET))
+}
+
+pub(crate) const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+///64 bit fnv-1a hash of bytes, continuing from hash
+pub(crate) fn fnv1a(bytes: &[u8], hash: u64) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+///shortens path to at most max_chars for display, writing the home dir as ~ and
+/// dropping leading dirs so the file name stays visible
+pub fn shorten_path(path: &str, home: Option<&str>, max_chars: usize) -> String {
+    let path = match home.and_then(|home| path.strip_prefix(home)) {
+        Some(rest) if rest.starts_with('/') => format!("~{}", rest),
+        _ => path.to_string(),
+    };
+    let len = path.chars().count();
+    if len <= max_chars {
+        return path;
+    }
+    let tail: String = path.chars().skip(len + 1 - max_chars).collect();
+    format!("…{}", tail)
+}
+
+///dir for user configuration, $XDG_CONFIG_HOME/calliglyph or ~/.config/calliglyph
+pub fn config_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(&env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("calliglyph"))
+}
+
+///dir for state kept between runs, $XDG_STATE_HOME/calliglyph or ~/.local/state/calliglyph
+pub fn state_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_STATE_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => Path::new(&env::var_os("HOME")?)
+            .join(".local")
+            .join("state"),
+    };
+    Some(base.join("calliglyph"))
+}
+
+///dir for files only valid while the system runs, $XDG_RUNTIME_DIR/calliglyph or
+/// calliglyph in the temp dir
+pub fn runtime_dir() -> PathBuf {
+    let base = env::var_os("XDG_RUNTIME_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map_or_else(env::temp_dir, PathBuf::from);
+    base.join("calliglyph")
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_session_tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn position(y: usize) -> FilePosition {
+        FilePosition {
+            x: 1,
+            y,
+            scroll_offset: 0,
+        }
+    }
+
+    #[test]
+    fn test_positions_survive_save_and_load() {
+        let dir = tempdir().unwrap();
+        let state = dir.path().join("state");
+        let file = dir.path().join("file with\ttab.txt");
+        let file = file.to_str().unwrap();
+
+        let mut session = Session::load(state.clone());
+        session.remember(file, position(7));
+        session.add_recent(file);
+        session.save().unwrap();
+
+        let loaded = Session::load(state);
+        assert_eq!(loaded.get(file), Some(position(7)));
+        assert_eq!(loaded.recent_files(), &[file.to_string()]);
+    }
+
+    #[test]
+    fn test_remember_moves_file_to_front_and_is_bounded() {
+        let mut session = Session::in_memory();
+        for i in 0..editor_settings::SESSION_MAX_FILES + 5 {
+            session.remember(&format!("/tmp/file{}", i), position(i));
+        }
+        session.remember("/tmp/file10", position(99));
+
+        assert_eq!(session.positions.len(), editor_settings::SESSION_MAX_FILES);
+        assert_eq!(session.positions[0].1, position(99));
+        assert_eq!(session.get("/tmp/file0"), None);
+    }
+
+    #[test]
+    fn test_recent_files_are_deduplicated_most_recent_first() {
+        let mut session = Session::in_memory();
+        session.add_recent("/tmp/a");
+        session.add_recent("/tmp/b");
+        session.add_recent("/tmp/a");
+        assert_eq!(session.recent_files(), &["/tmp/a", "/tmp/b"]);
+
+        session.remove_recent("/tmp/a");
+        assert_eq!(session.recent_files(), &["/tmp/b"]);
+    }
+
+    #[test]
+    fn test_shorten_path_uses_tilde_and_keeps_file_name() {
+        assert_eq!(
+            shorten_path("/home/me/notes.txt", Some("/home/me"), 40),
+            "~/notes.txt"
+        );
+        assert_eq!(shorten_path("/a/b/c/file.rs", None, 10), "…c/file.rs");
+        assert_eq!(shorten_path("/a/file.rs", None, 10), "/a/file.rs");
+    }
+
+    #[test]
+    fn test_corrupt_lines_are_skipped() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("positions"), "garbage\n3\t2\t1\t/tmp/x\n").unwrap();
+        let session = Session::load(dir.path().to_path_buf());
+        assert_eq!(session.positions.len(), 1);
+        assert_eq!(
+            session.get("/tmp/x"),
+            Some(FilePosition {
+                x: 2,
+                y: 3,
+                scroll_offset: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use crate::config::editor_settings;
+use crate::core::editor::words::{WordChars, WORD_CHARS_NAMES};
+use crate::core::file_format::ENCODING_NAMES;
+
+///values offered when completing an on/off setting
+const BOOL_VALUES: &[&str] = &["on", "off"];
+///values offered when completing visualbell
+const BELL_VALUES: &[&str] = &["flash", "beep", "off"];
+///names of the settings :set knows, with the values offered when completing their value.
+/// settings taking numbers or lists offer none.
+pub const SETTINGS: &[(&str, &[&str])] = &[
+    ("modal", BOOL_VALUES),
+    ("wordcount", BOOL_VALUES),
+    ("hints", BOOL_VALUES),
+    ("color", BOOL_VALUES),
+    ("tabwidth", &[]),
+    ("insertspaces", BOOL_VALUES),
+    ("restoreposition", BOOL_VALUES),
+    ("persistentundo", BOOL_VALUES),
+    ("autochdir", BOOL_VALUES),
+    ("autosave_on_focus_lost", BOOL_VALUES),
+    ("elevatedsave", BOOL_VALUES),
+    ("visualbell", BELL_VALUES),
+    ("backgroundsave", &[]),
+    ("annotations", &[]),
+    ("longlinemode", BOOL_VALUES),
+    ("longlinebytes", &[]),
+    ("wordboundary", WORD_CHARS_NAMES),
+];
+///settings of the open buffer rather than the editor, :set changes them on the app
+pub const BUFFER_SETTINGS: &[(&str, &[&str])] = &[("fileencoding", ENCODING_NAMES)];
+
+///how the bell rings on key presses that do nothing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BellMode {
+    Off,
+    //the info bar shows inverted for a moment
+    Flash,
+    //the terminal bell
+    Beep,
+}
+
+/// runtime editor settings, changeable with the :set command
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub modal: bool,
+    pub word_count: bool,
+    //key hints of the active area in the bottom row
+    pub hints: bool,
+    //colored styles rather than the monochrome theme
+    pub color: bool,
+    pub tab_width: usize,
+    //indent with spaces instead of tabs, used by :retab
+    pub insert_spaces: bool,
+    pub restore_position: bool,
+    //undo history kept between runs for files
+    pub persistent_undo: bool,
+    //working dir follows the dir of the opened file
+    pub auto_chdir: bool,
+    //named buffers are saved when the terminal loses focus
+    pub autosave_on_focus_lost: bool,
+    //a save denied for lack of rights offers to retry it with sudo, unix only
+    pub elevated_save: bool,
+    pub visual_bell: BellMode,
+    //buffers larger than this many bytes are saved on a separate thread
+    pub background_save_bytes: usize,
+    //keywords like TODO that are highlighted and listed by :todos
+    pub annotation_keywords: Vec<String>,
+    pub word_chars: WordChars,
+    //lines longer than long_line_bytes are rendered cheaply, without highlighting
+    pub long_line_mode: bool,
+    pub long_line_bytes: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        Self {
+            modal: editor_settings::MODAL_EDITING,
+            word_count: editor_settings::SHOW_WORD_COUNT,
+            hints: editor_settings::SHOW_KEY_HINTS,
+            color: editor_settings::COLOR,
+            tab_width: editor_settings::TAB_WIDTH as usize,
+            insert_spaces: editor_settings::INSERT_SPACES,
+            restore_position: editor_settings::RESTORE_POSITION,
+            persistent_undo: editor_settings::PERSISTENT_UNDO,
+            auto_chdir: editor_settings::AUTO_CHDIR,
+            autosave_on_focus_lost: editor_settings::AUTOSAVE_ON_FOCUS_LOST,
+            elevated_save: editor_settings::ELEVATED_SAVE,
+            visual_bell: editor_settings::VISUAL_BELL,
+            background_save_bytes: editor_settings::BACKGROUND_SAVE_BYTES,
+            annotation_keywords: editor_settings::ANNOTATION_KEYWORDS
+                .iter()
+                .map(|keyword| keyword.to_string())
+                .collect(),
+            word_chars: editor_settings::WORD_CHARS,
+            long_line_mode: editor_settings::LONG_LINE_MODE,
+            long_line_bytes: editor_settings::LONG_LINE_BYTES,
+        }
+    }
+
+    ///sets setting with name key to value, returns reason on failure
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "modal" => self.modal = parse_bool(value)?,
+            "wordcount" => self.word_count = parse_bool(value)?,
+            "hints" => self.hints = parse_bool(value)?,
+            "color" => self.color = parse_bool(value)?,
+            "tabwidth" => self.tab_width = parse_width(value)?,
+            "insertspaces" => self.insert_spaces = parse_bool(value)?,
+            "restoreposition" => self.restore_position = parse_bool(value)?,
+            "persistentundo" => self.persistent_undo = parse_bool(value)?,
+            "autochdir" => self.auto_chdir = parse_bool(value)?,
+            "autosave_on_focus_lost" => self.autosave_on_focus_lost = parse_bool(value)?,
+            "elevatedsave" => self.elevated_save = parse_bool(value)?,
+            "visualbell" => self.visual_bell = parse_bell(value)?,
+            "backgroundsave" => self.background_save_bytes = parse_width(value)?,
+            "annotations" => self.annotation_keywords = parse_list(value),
+            "wordboundary" => self.word_chars = WordChars::parse(value)?,
+            "longlinemode" => self.long_line_mode = parse_bool(value)?,
+            "longlinebytes" => self.long_line_bytes = parse_width(value)?,
+            _ => return Err(format!("unknown setting '{}'", key)),
+        }
+        Ok(())
+    }
+
+    ///length in bytes above which lines are rendered in long-line mode, none when it is off
+    pub fn long_line_limit(&self) -> Option<usize> {
+        self.long_line_mode.then_some(self.long_line_bytes)
+    }
+}
+
+///parses on/off style boolean setting values
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value {
+        "on" | "true" | "yes" | "1" => Ok(true),
+        "off" | "false" | "no" | "0" => Ok(false),
+        _ => Err(format!("expected on or off, got '{}'", value)),
+    }
+}
+
+///parses the visualbell setting, on flashes like vim's visualbell
+fn parse_bell(value: &str) -> Result<BellMode, String> {
+    match value {
+        "flash" | "on" => Ok(BellMode::Flash),
+        "beep" => Ok(BellMode::Beep),
+        "off" => Ok(BellMode::Off),
+        _ => Err(format!("expected flash, beep or off, got '{}'", value)),
+    }
+}
+
+///parses a comma separated list setting, an empty value gives an empty list
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+///parses a width setting, which has to be at least 1
+fn parse_width(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(width) if width > 0 => Ok(width),
+        _ => Err(format!("expected a positive number, got '{}'", value)),
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_settings_tests {
+    use super::*;
+
+    #[test]
+    fn test_set_modal_on_and_off() {
+        let mut settings = Settings::new();
+        settings.set("modal", "on").unwrap();
+        assert!(settings.modal);
+        settings.set("modal", "off").unwrap();
+        assert!(!settings.modal);
+    }
+
+    #[test]
+    fn test_set_word_count() {
+        let mut settings = Settings::new();
+        settings.set("wordcount", "on").unwrap();
+        assert!(settings.word_count);
+    }
+
+    #[test]
+    fn test_set_hints_off() {
+        let mut settings = Settings::new();
+        settings.set("hints", "off").unwrap();
+        assert!(!settings.hints);
+    }
+
+    #[test]
+    fn test_set_tab_settings() {
+        let mut settings = Settings::new();
+        settings.set("tabwidth", "8").unwrap();
+        settings.set("insertspaces", "on").unwrap();
+        assert_eq!(settings.tab_width, 8);
+        assert!(settings.insert_spaces);
+        assert!(settings.set("tabwidth", "0").is_err());
+    }
+
+    #[test]
+    fn test_set_annotation_keywords() {
+        let mut settings = Settings::new();
+        settings.set("annotations", "TODO, XXX,").unwrap();
+        assert_eq!(settings.annotation_keywords, vec!["TODO", "XXX"]);
+    }
+
+    #[test]
+    fn test_long_line_limit_follows_mode() {
+        let mut settings = Settings::new();
+        settings.set("longlinebytes", "100").unwrap();
+        assert_eq!(settings.long_line_limit(), Some(100));
+        settings.set("longlinemode", "off").unwrap();
+        assert_eq!(settings.long_line_limit(), None);
+    }
+
+    #[test]
+    fn test_every_listed_setting_can_be_set() {
+        let mut settings = Settings::new();
+        for (name, values) in SETTINGS {
+            let value = values.first().copied().unwrap_or("1");
+            assert!(settings.set(name, value).is_ok(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_set_unknown_setting_fails() {
+        let mut settings = Settings::new();
+        assert!(settings.set("nope", "on").is_err());
+    }
+
+    #[test]
+    fn test_set_invalid_bool_fails() {
+        let mut settings = Settings::new();
+        assert!(settings.set("modal", "maybe").is_err());
+    }
+}
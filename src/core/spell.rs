@@ -0,0 +1,217 @@
+//spell checking of prose, words are looked up in a bundled english word list and a user
+// dictionary kept in the config dir
+
+use crate::config::editor_settings;
+use crate::core::editor::words::WordChars;
+use crate::core::session;
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+const BUNDLED_WORDS: &str = include_str!("../../assets/dictionary_en.txt");
+const USER_DICTIONARY_FILE: &str = "dictionary";
+
+///endings tried when a word isn't in the word list itself, with what replaces them,
+/// so the word list only needs the base forms
+const SUFFIXES: &[(&str, &str)] = &[
+    ("'s", ""),
+    ("ies", "y"),
+    ("ied", "y"),
+    ("es", ""),
+    ("s", ""),
+    ("ed", ""),
+    ("ed", "e"),
+    ("d", ""),
+    ("ing", ""),
+    ("ing", "e"),
+    ("ly", ""),
+    ("er", ""),
+    ("est", ""),
+];
+
+/// known words, all lowercase
+#[derive(Debug, Default)]
+pub struct SpellChecker {
+    words: HashSet<String>,
+    ///file words added with :spell add go to, None keeps them in memory only
+    user_dictionary: Option<PathBuf>,
+}
+
+impl SpellChecker {
+    ///spell checker knowing the bundled words and the words in user_dictionary, if it exists
+    pub fn new(user_dictionary: Option<PathBuf>) -> Self {
+        let mut words: HashSet<String> = BUNDLED_WORDS.lines().map(str::to_lowercase).collect();
+        if let Some(contents) = user_dictionary
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+        {
+            words.extend(
+                contents
+                    .lines()
+                    .map(str::trim)
+                    .filter(|word| !word.is_empty())
+                    .map(str::to_lowercase),
+            );
+        }
+        Self {
+            words,
+            user_dictionary,
+        }
+    }
+
+    ///spell checker with the user dictionary of the default config dir
+    pub fn load_default() -> Self {
+        Self::new(session::config_dir().map(|dir| dir.join(USER_DICTIONARY_FILE)))
+    }
+
+    ///checks if word is spelled right. words with digits and all caps words, like
+    /// acronyms, aren't checked
+    pub fn is_known(&self, word: &str) -> bool {
+        if word.chars().any(|c| !c.is_alphabetic() && c != '\'')
+            || (word.chars().count() > 1 && !word.chars().any(char::is_lowercase))
+        {
+            return true;
+        }
+        let word = word.to_lowercase();
+        self.words.contains(&word)
+            || SUFFIXES.iter().any(|(suffix, replacement)| {
+                word.strip_suffix(suffix)
+                    .filter(|stem| !stem.is_empty())
+                    .is_some_and(|stem| self.words.contains(&format!("{}{}", stem, replacement)))
+            })
+    }
+
+    ///char columns of the misspelled words in line
+    pub fn misspelled(&self, line: &str) -> Vec<Range<usize>> {
+        words(line)
+            .filter(|(_, word)| !self.is_known(word))
+            .map(|(range, _)| range)
+            .collect()
+    }
+
+    ///adds word to the known words and appends it to the user dictionary file
+    pub fn add_word(&mut self, word: &str) -> io::Result<()> {
+        if let Some(path) = &self.user_dictionary {
+            append_line(path, word)?;
+        }
+        self.words.insert(word.to_lowercase());
+        Ok(())
+    }
+}
+
+///checks if files at path get spell checked, by their extension
+pub fn checks_file(path: Option<&str>) -> bool {
+    path.and_then(|path| Path::new(path).extension())
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            editor_settings::SPELL_FILETYPES
+                .iter()
+                .any(|filetype| filetype.eq_ignore_ascii_case(ext))
+        })
+}
+
+///the words of line with their char columns. words are letters, digits and underscores,
+/// with apostrophes allowed inside them like in don't
+pub fn words(line: &str) -> impl Iterator<Item = (Range<usize>, &str)> {
+    let mut chars = line.char_indices().enumerate().peekable();
+    std::iter::from_fn(move || {
+        //skip to the start of the next word
+        while chars.peek().is_some_and(|(_, (_, c))| !is_word_char(*c)) {
+            chars.next();
+        }
+        let (start_col, (start_byte, _)) = chars.next()?;
+        let (mut end_col, mut end_byte) = (start_col + 1, line.len());
+        while let Some(&(col, (byte, c))) = chars.peek() {
+            let inner_apostrophe =
+                c == '\'' && line[byte + 1..].chars().next().is_some_and(is_word_char);
+            if !is_word_char(c) && !inner_apostrophe {
+                end_byte = byte;
+                break;
+            }
+            end_col = col + 1;
+            chars.next();
+        }
+        Some((start_col..end_col, &line[start_byte..end_byte]))
+    })
+}
+
+///the word at col in line, a cursor right after a word counts as on it
+pub fn word_at(line: &str, col: usize) -> Option<(Range<usize>, &str)> {
+    words(line).find(|(range, _)| range.start <= col && col <= range.end)
+}
+
+//spelling is about letters, so words stay the code ones whatever :set wordboundary says
+fn is_word_char(c: char) -> bool {
+    crate::core::editor::words::is_word_char(c, &WordChars::Code)
+}
+
+///appends word as a line to the file at path, creating it and its dir if needed
+fn append_line(path: &Path, word: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", word)
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_spell_tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_words_have_char_columns_and_keep_inner_apostrophes() {
+        let found: Vec<_> = words("«don't» stop, 'quoted' x2").collect();
+        assert_eq!(
+            found,
+            vec![
+                (1..6, "don't"),
+                (8..12, "stop"),
+                (15..21, "quoted"),
+                (23..25, "x2"),
+            ]
+        );
+        assert_eq!(word_at("one two", 3), Some((0..3, "one")));
+        assert_eq!(word_at("one two", 4), Some((4..7, "two")));
+        assert_eq!(word_at("one  two", 4), None);
+    }
+
+    #[test]
+    fn test_misspelled_words_are_flagged() {
+        let spell = SpellChecker::new(None);
+        assert_eq!(spell.misspelled("The quick brwn fox jumped"), vec![10..14]);
+        //inflections, acronyms and words with digits pass
+        assert!(spell
+            .misspelled("Files saved, editing HTML in v2")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_added_words_are_kept_in_the_user_dictionary() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config").join(USER_DICTIONARY_FILE);
+        let mut spell = SpellChecker::new(Some(path.clone()));
+        assert!(!spell.is_known("calliglyph"));
+
+        spell.add_word("calliglyph").unwrap();
+        assert!(spell.is_known("Calliglyph"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "calliglyph\n");
+        assert!(SpellChecker::new(Some(path)).is_known("calliglyph"));
+    }
+
+    #[test]
+    fn test_only_configured_filetypes_are_checked() {
+        assert!(checks_file(Some("notes/README.md")));
+        assert!(checks_file(Some("todo.TXT")));
+        assert!(!checks_file(Some("src/main.rs")));
+        assert!(!checks_file(None));
+    }
+}
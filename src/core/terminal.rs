@@ -0,0 +1,135 @@
+use crossterm::cursor::{SetCursorStyle, Show};
+use crossterm::event::{
+    DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+    EnableFocusChange, EnableMouseCapture,
+};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use std::io;
+use std::io::Write;
+use std::panic;
+
+///puts the terminal into the state the editor draws in: raw mode, alternate screen, mouse
+/// capture, bracketed paste and focus reports. undone by restore_terminal
+pub fn setup_terminal() -> io::Result<()> {
+    enable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste,
+        EnableFocusChange
+    )
+}
+
+///puts the terminal back into the state the shell expects: raw mode off, main screen,
+/// no mouse capture, bracketed paste or focus reports and the users cursor shape
+pub fn restore_terminal() -> io::Result<()> {
+    disable_raw_mode()?;
+    reset_terminal_output(&mut io::stdout())
+}
+
+///writes the escape sequences that undo the editors terminal setup to out
+fn reset_terminal_output<W: Write>(out: &mut W) -> io::Result<()> {
+    execute!(
+        out,
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        DisableFocusChange,
+        SetCursorStyle::DefaultUserShape,
+        Show
+    )
+}
+
+type PanicHook = Box<dyn Fn(&panic::PanicHookInfo<'_>) + Sync + Send + 'static>;
+
+///installs color-eyre's report hooks, with a panic hook that restores the terminal
+/// before the panic report is printed, so a panic never leaves the terminal in raw mode
+pub fn install_hooks() -> color_eyre::Result<()> {
+    let (panic_hook, eyre_hook) = color_eyre::config::HookBuilder::default().into_hooks();
+    eyre_hook.install()?;
+    panic::set_hook(restoring_panic_hook(
+        io::stdout,
+        panic_hook.into_panic_hook(),
+    ));
+    Ok(())
+}
+
+///wraps report so the terminal output from out is reset before the panic is reported
+fn restoring_panic_hook<W, F>(out: F, report: PanicHook) -> PanicHook
+where
+    W: Write,
+    F: Fn() -> W + Sync + Send + 'static,
+{
+    Box::new(move |info| {
+        //errors are ignored, the report should be printed no matter what
+        let _ = disable_raw_mode();
+        let _ = reset_terminal_output(&mut out());
+        report(info);
+    })
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_terminal_tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_reset_output_leaves_alternate_screen_and_mouse_capture() {
+        let mut out: Vec<u8> = vec![];
+        reset_terminal_output(&mut out).unwrap();
+        let written = String::from_utf8(out).unwrap();
+
+        assert!(written.contains("\x1b[?1049l")); //leave alternate screen
+        assert!(written.contains("\x1b[?1000l")); //disable mouse capture
+        assert!(written.contains("\x1b[?2004l")); //disable bracketed paste
+        assert!(written.contains("\x1b[?1004l")); //disable focus reports
+        assert!(written.contains("\x1b[0 q")); //default cursor shape
+        assert!(written.contains("\x1b[?25h")); //show cursor
+    }
+
+    ///writer appending to a shared buffer, so the hook output can be inspected
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_panic_resets_terminal_before_report() {
+        let log: Arc<Mutex<Vec<u8>>> = Arc::new(Mutex::new(vec![]));
+        let out_log = Arc::clone(&log);
+        let report_log = Arc::clone(&log);
+
+        let hook = restoring_panic_hook(
+            move || SharedBuffer(Arc::clone(&out_log)),
+            Box::new(move |_| report_log.lock().unwrap().extend_from_slice(b"report")),
+        );
+        let previous = panic::take_hook();
+        panic::set_hook(hook);
+        let result = panic::catch_unwind(|| panic!("deliberate panic"));
+        panic::set_hook(previous);
+
+        assert!(result.is_err());
+        let written = String::from_utf8(log.lock().unwrap().clone()).unwrap();
+        let reset_at = written.find("\x1b[?1049l").unwrap();
+        assert!(written.contains("\x1b[?1000l"));
+        assert!(reset_at < written.find("report").unwrap());
+    }
+}
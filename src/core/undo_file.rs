@@ -0,0 +1,489 @@
+//undo history kept between runs, one file per edited file in the state dir. each file
+// records a hash of the content its history leads to, and is only restored onto that
+// same content.
+
+use super::cursor::CursorPosition;
+use super::editor::editor::EditAction;
+use super::editor::undo_redo::UndoEntry;
+use super::session::{fnv1a, path_hash, FNV_OFFSET};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+///dir in the state dir holding the history files
+const UNDO_DIR: &str = "undo";
+///first line of a history file, a different one is a format this version can't read
+const HEADER: &str = "calliglyph undo 1";
+
+///path of the history file of file in state_dir, named by the hash of its absolute path
+pub fn history_path(state_dir: &Path, file: &str) -> PathBuf {
+    state_dir.join(UNDO_DIR).join(path_hash(file))
+}
+
+///hash of content as it is saved, lines joined by line breaks. stable between runs, unlike
+/// the std hasher.
+pub fn content_hash(lines: &[String]) -> u64 {
+    lines
+        .iter()
+        .enumerate()
+        .fold(FNV_OFFSET, |hash, (i, line)| {
+            let hash = if i > 0 { fnv1a(b"\n", hash) } else { hash };
+            fnv1a(line.as_bytes(), hash)
+        })
+}
+
+///writes history, oldest first with its first applied entries applied, for content to
+/// path. redo steps and then the oldest steps are dropped until the file is at most
+/// max_bytes. an empty history removes the file.
+pub fn write(
+    path: &Path,
+    content: &[String],
+    history: &[UndoEntry],
+    applied: usize,
+    max_bytes: usize,
+) -> io::Result<()> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let mut lines: Vec<String> = history
+        .iter()
+        .map(|entry| {
+            let recorded = now.saturating_sub(entry.recorded.elapsed()).as_secs();
+            let mut tokens = vec![recorded.to_string()];
+            encode(&entry.action, &mut tokens);
+            format!("{}\n", tokens.join("\t"))
+        })
+        .collect();
+    let mut applied = applied.min(lines.len());
+    let header =
+        |applied: usize| format!("{}\n{:016x}\t{}\n", HEADER, content_hash(content), applied);
+
+    let mut len = header(applied).len() + lines.iter().map(String::len).sum::<usize>();
+    while len > max_bytes && !lines.is_empty() {
+        //an undone step is worth less than one that can still be undone
+        let dropped = if lines.len() > applied {
+            lines.pop()
+        } else {
+            applied -= 1;
+            Some(lines.remove(0))
+        };
+        len -= dropped.map_or(0, |line| line.len());
+    }
+    if lines.is_empty() {
+        return match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut out = header(applied);
+    out.extend(lines);
+    fs::write(path, out)
+}
+
+///reads the history at path, oldest first with how many of its steps are applied. None if
+/// there is none, it can't be read, or it leads to other content than content.
+pub fn read(path: &Path, content: &[String]) -> Option<(Vec<UndoEntry>, usize)> {
+    let text = fs::read_to_string(path).ok()?;
+    let mut lines = text.lines();
+    if lines.next()? != HEADER {
+        return None;
+    }
+    let (hash, applied) = lines.next()?.split_once('\t')?;
+    if u64::from_str_radix(hash, 16).ok()? != content_hash(content) {
+        return None;
+    }
+    let applied: usize = applied.parse().ok()?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let history = lines
+        .map(|line| {
+            let mut tokens = line.split('\t');
+            let recorded: u64 = tokens.next()?.parse().ok()?;
+            let action = decode(&mut tokens)?;
+            if tokens.next().is_some() {
+                return None;
+            }
+            let age = now.saturating_sub(Duration::from_secs(recorded));
+            Some(UndoEntry {
+                action,
+                recorded: Instant::now().checked_sub(age).unwrap_or_else(Instant::now),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    (applied <= history.len()).then_some((history, applied))
+}
+
+///appends the fields of action to tokens, starting with a tag naming its kind
+fn encode(action: &EditAction, tokens: &mut Vec<String>) {
+    let pos = |tokens: &mut Vec<String>, pos: &CursorPosition| {
+        tokens.push(pos.x.to_string());
+        tokens.push(pos.y.to_string());
+    };
+    let lines = |tokens: &mut Vec<String>, lines: &[String]| {
+        tokens.push(lines.len().to_string());
+        tokens.extend(lines.iter().map(|line| escape(line)));
+    };
+    match action {
+        EditAction::Insert { pos: p, c } => {
+            tokens.push("i".to_string());
+            pos(tokens, p);
+            tokens.push(escape(&c.to_string()));
+        }
+        EditAction::Delete {
+            pos: p,
+            deleted_char,
+        } => {
+            tokens.push("d".to_string());
+            pos(tokens, p);
+            tokens.push(escape(&deleted_char.to_string()));
+        }
+        EditAction::Replace {
+            start,
+            end,
+            old,
+            new,
+        } => {
+            tokens.push("r".to_string());
+            pos(tokens, start);
+            pos(tokens, end);
+            tokens.push(escape(&old.to_string()));
+            tokens.push(escape(&new.to_string()));
+        }
+        EditAction::ReplaceRange {
+            start,
+            end,
+            old,
+            new,
+        } => {
+            tokens.push("rr".to_string());
+            pos(tokens, start);
+            pos(tokens, end);
+            lines(tokens, old);
+            lines(tokens, new);
+        }
+        EditAction::ReplaceLines { start, old, new } => {
+            tokens.push("rl".to_string());
+            pos(tokens, start);
+            lines(tokens, old);
+            lines(tokens, new);
+        }
+        EditAction::InsertLines { start, lines: l } => {
+            tokens.push("il".to_string());
+            pos(tokens, start);
+            lines(tokens, l);
+        }
+        EditAction::DeleteLines { start, deleted } => {
+            tokens.push("dl".to_string());
+            pos(tokens, start);
+            lines(tokens, deleted);
+        }
+        EditAction::InsertRange {
+            start,
+            end,
+            lines: l,
+        } => {
+            tokens.push("ir".to_string());
+            pos(tokens, start);
+            pos(tokens, end);
+            lines(tokens, l);
+        }
+        EditAction::DeleteRange {
+            start,
+            end,
+            deleted,
+        } => {
+            tokens.push("dr".to_string());
+            pos(tokens, start);
+            pos(tokens, end);
+            lines(tokens, deleted);
+        }
+        EditAction::SplitLine {
+            pos: p,
+            left,
+            right,
+        } => {
+            tokens.push("s".to_string());
+            pos(tokens, p);
+            tokens.push(escape(left));
+            tokens.push(escape(right));
+        }
+        EditAction::JoinLine { pos: p, merged } => {
+            tokens.push("j".to_string());
+            pos(tokens, p);
+            tokens.push(escape(merged));
+        }
+        EditAction::Batch { actions } => {
+            tokens.push("b".to_string());
+            tokens.push(actions.len().to_string());
+            for action in actions {
+                encode(action, tokens);
+            }
+        }
+    }
+}
+
+///reads an action written by encode from tokens, None if they don't form one
+fn decode<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Option<EditAction> {
+    fn number<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Option<usize> {
+        tokens.next()?.parse().ok()
+    }
+    fn pos<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Option<CursorPosition> {
+        Some(CursorPosition::new(number(tokens)?, number(tokens)?))
+    }
+    fn text<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Option<String> {
+        unescape(tokens.next()?)
+    }
+    fn char<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Option<char> {
+        let text = text(tokens)?;
+        let mut chars = text.chars();
+        let c = chars.next()?;
+        chars.next().is_none().then_some(c)
+    }
+    fn lines<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Option<Vec<String>> {
+        let count = number(tokens)?;
+        (0..count).map(|_| text(tokens)).collect()
+    }
+
+    let action = match tokens.next()? {
+        "i" => EditAction::Insert {
+            pos: pos(tokens)?,
+            c: char(tokens)?,
+        },
+        "d" => EditAction::Delete {
+            pos: pos(tokens)?,
+            deleted_char: char(tokens)?,
+        },
+        "r" => EditAction::Replace {
+            start: pos(tokens)?,
+            end: pos(tokens)?,
+            old: char(tokens)?,
+            new: char(tokens)?,
+        },
+        "rr" => EditAction::ReplaceRange {
+            start: pos(tokens)?,
+            end: pos(tokens)?,
+            old: lines(tokens)?,
+            new: lines(tokens)?,
+        },
+        "rl" => EditAction::ReplaceLines {
+            start: pos(tokens)?,
+            old: lines(tokens)?,
+            new: lines(tokens)?,
+        },
+        "il" => EditAction::InsertLines {
+            start: pos(tokens)?,
+            lines: lines(tokens)?,
+        },
+        "dl" => EditAction::DeleteLines {
+            start: pos(tokens)?,
+            deleted: lines(tokens)?,
+        },
+        "ir" => EditAction::InsertRange {
+            start: pos(tokens)?,
+            end: pos(tokens)?,
+            lines: lines(tokens)?,
+        },
+        "dr" => EditAction::DeleteRange {
+            start: pos(tokens)?,
+            end: pos(tokens)?,
+            deleted: lines(tokens)?,
+        },
+        "s" => EditAction::SplitLine {
+            pos: pos(tokens)?,
+            left: text(tokens)?,
+            right: text(tokens)?,
+        },
+        "j" => EditAction::JoinLine {
+            pos: pos(tokens)?,
+            merged: text(tokens)?,
+        },
+        "b" => {
+            let count = number(tokens)?;
+            EditAction::Batch {
+                actions: (0..count).map(|_| decode(tokens)).collect::<Option<_>>()?,
+            }
+        }
+        _ => return None,
+    };
+    Some(action)
+}
+
+///text with backslashes, tabs and line breaks escaped, so it fits in a tab separated field
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(field: &str) -> Option<String> {
+    let mut text = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        text.push(match chars.next()? {
+            '\\' => '\\',
+            't' => '\t',
+            'n' => '\n',
+            'r' => '\r',
+            _ => return None,
+        });
+    }
+    Some(text)
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_undo_file_tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn entry(action: EditAction) -> UndoEntry {
+        UndoEntry {
+            action,
+            recorded: Instant::now(),
+        }
+    }
+
+    fn lines(text: &[&str]) -> Vec<String> {
+        text.iter().map(|line| line.to_string()).collect()
+    }
+
+    ///one of every kind of action, with text that needs escaping
+    fn history() -> Vec<UndoEntry> {
+        let p = CursorPosition::new;
+        vec![
+            entry(EditAction::Insert {
+                pos: p(0, 0),
+                c: '\t',
+            }),
+            entry(EditAction::Delete {
+                pos: p(1, 0),
+                deleted_char: '\\',
+            }),
+            entry(EditAction::ReplaceRange {
+                start: p(0, 0),
+                end: p(2, 1),
+                old: lines(&["a\tb", ""]),
+                new: lines(&["ü"]),
+            }),
+            entry(EditAction::Batch {
+                actions: vec![
+                    EditAction::SplitLine {
+                        pos: p(1, 0),
+                        left: "x".to_string(),
+                        right: String::new(),
+                    },
+                    EditAction::DeleteLines {
+                        start: p(0, 2),
+                        deleted: lines(&["gone", "too"]),
+                    },
+                    EditAction::ReplaceLines {
+                        start: p(0, 3),
+                        old: lines(&["x", "y"]),
+                        new: lines(&["z"]),
+                    },
+                ],
+            }),
+            entry(EditAction::JoinLine {
+                pos: p(3, 1),
+                merged: "a\\nb".to_string(),
+            }),
+        ]
+    }
+
+    ///actions in the debug format, EditAction has no PartialEq
+    fn actions(history: &[UndoEntry]) -> Vec<String> {
+        history
+            .iter()
+            .map(|entry| format!("{:?}", entry.action))
+            .collect()
+    }
+
+    #[test]
+    fn test_history_survives_write_and_read() {
+        let dir = tempdir().unwrap();
+        let path = history_path(dir.path(), "/tmp/notes.txt");
+        let content = lines(&["first", "second"]);
+
+        write(&path, &content, &history(), 3, usize::MAX).unwrap();
+        let (read_back, applied) = read(&path, &content).unwrap();
+        assert_eq!(applied, 3);
+        assert_eq!(actions(&read_back), actions(&history()));
+    }
+
+    #[test]
+    fn test_history_of_changed_content_is_ignored() {
+        let dir = tempdir().unwrap();
+        let path = history_path(dir.path(), "/tmp/notes.txt");
+        write(&path, &lines(&["a", "b"]), &history(), 5, usize::MAX).unwrap();
+
+        assert!(read(&path, &lines(&["a", "b", ""])).is_none());
+        assert!(read(&path, &lines(&["a", "b"])).is_some());
+        fs::write(&path, "garbage").unwrap();
+        assert!(read(&path, &lines(&["a", "b"])).is_none());
+    }
+
+    #[test]
+    fn test_oversized_history_drops_redo_then_oldest_steps() {
+        let dir = tempdir().unwrap();
+        let path = history_path(dir.path(), "/tmp/notes.txt");
+        let content = lines(&["x"]);
+        let file_len = |path: &Path| fs::metadata(path).unwrap().len() as usize;
+        write(&path, &content, &history(), 3, usize::MAX).unwrap();
+        let full = file_len(&path);
+
+        //the two undone steps go first
+        write(&path, &content, &history(), 3, full - 1).unwrap();
+        let (kept, applied) = read(&path, &content).unwrap();
+        assert!(file_len(&path) < full);
+        assert_eq!((kept.len(), applied), (4, 3));
+        assert_eq!(actions(&kept), actions(&history()[..kept.len()]));
+
+        write(&path, &content, &history(), 5, 200).unwrap();
+        let (kept, applied) = read(&path, &content).unwrap();
+        assert!(!kept.is_empty());
+        assert_eq!(applied, kept.len());
+        assert_eq!(actions(&kept), actions(&history()[5 - kept.len()..]));
+
+        //nothing fits, the file goes away
+        write(&path, &content, &history(), 5, 1).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_files_are_named_by_path() {
+        let dir = Path::new("/state");
+        assert_eq!(
+            history_path(dir, "/tmp/a.txt"),
+            history_path(dir, "/tmp/a.txt")
+        );
+        assert_ne!(
+            history_path(dir, "/tmp/a.txt"),
+            history_path(dir, "/tmp/b.txt")
+        );
+        assert_eq!(content_hash(&[]), FNV_OFFSET);
+    }
+}
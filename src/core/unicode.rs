@@ -0,0 +1,208 @@
+//unicode characters typed by their codepoint or looked up by name
+
+///longest codepoint typed, U+10FFFF has six hex digits
+pub const MAX_HEX_DIGITS: usize = 6;
+
+///names of characters worth looking up, searched by :unicode name
+const NAMES: &[(&str, char)] = &[
+    ("rightwards arrow", '→'),
+    ("leftwards arrow", '←'),
+    ("upwards arrow", '↑'),
+    ("downwards arrow", '↓'),
+    ("left right arrow", '↔'),
+    ("up down arrow", '↕'),
+    ("rightwards double arrow", '⇒'),
+    ("leftwards double arrow", '⇐'),
+    ("left right double arrow", '⇔'),
+    ("north east arrow", '↗'),
+    ("south east arrow", '↘'),
+    ("return arrow", '↵'),
+    ("black right-pointing triangle", '▶'),
+    ("black left-pointing triangle", '◀'),
+    ("check mark", '✓'),
+    ("heavy check mark", '✔'),
+    ("ballot x", '✗'),
+    ("multiplication sign", '×'),
+    ("division sign", '÷'),
+    ("plus-minus sign", '±'),
+    ("minus sign", '−'),
+    ("not equal to", '≠'),
+    ("almost equal to", '≈'),
+    ("identical to", '≡'),
+    ("less-than or equal to", '≤'),
+    ("greater-than or equal to", '≥'),
+    ("infinity", '∞'),
+    ("square root", '√'),
+    ("n-ary summation", '∑'),
+    ("n-ary product", '∏'),
+    ("integral", '∫'),
+    ("partial differential", '∂'),
+    ("nabla", '∇'),
+    ("for all", '∀'),
+    ("there exists", '∃'),
+    ("element of", '∈'),
+    ("not an element of", '∉'),
+    ("empty set", '∅'),
+    ("intersection", '∩'),
+    ("union", '∪'),
+    ("subset of", '⊂'),
+    ("superset of", '⊃'),
+    ("logical and", '∧'),
+    ("logical or", '∨'),
+    ("not sign", '¬'),
+    ("degree sign", '°'),
+    ("micro sign", 'µ'),
+    ("per mille sign", '‰'),
+    ("superscript two", '²'),
+    ("superscript three", '³'),
+    ("vulgar fraction one half", '½'),
+    ("vulgar fraction one quarter", '¼'),
+    ("greek small letter alpha", 'α'),
+    ("greek small letter beta", 'β'),
+    ("greek small letter gamma", 'γ'),
+    ("greek small letter delta", 'δ'),
+    ("greek small letter epsilon", 'ε'),
+    ("greek small letter theta", 'θ'),
+    ("greek small letter lambda", 'λ'),
+    ("greek small letter mu", 'μ'),
+    ("greek small letter pi", 'π'),
+    ("greek small letter sigma", 'σ'),
+    ("greek small letter tau", 'τ'),
+    ("greek small letter phi", 'φ'),
+    ("greek small letter omega", 'ω'),
+    ("greek capital letter delta", 'Δ'),
+    ("greek capital letter sigma", 'Σ'),
+    ("greek capital letter omega", 'Ω'),
+    ("em dash", '—'),
+    ("en dash", '–'),
+    ("horizontal ellipsis", '…'),
+    ("bullet", '•'),
+    ("middle dot", '·'),
+    ("left double quotation mark", '“'),
+    ("right double quotation mark", '”'),
+    ("left single quotation mark", '‘'),
+    ("right single quotation mark", '’'),
+    ("left-pointing double angle quotation mark", '«'),
+    ("right-pointing double angle quotation mark", '»'),
+    ("section sign", '§'),
+    ("pilcrow sign", '¶'),
+    ("dagger", '†'),
+    ("double dagger", '‡'),
+    ("copyright sign", '©'),
+    ("registered sign", '®'),
+    ("trade mark sign", '™'),
+    ("euro sign", '€'),
+    ("pound sign", '£'),
+    ("yen sign", '¥'),
+    ("cent sign", '¢'),
+    ("no-break space", '\u{a0}'),
+    ("zero width space", '\u{200b}'),
+    ("box drawings light horizontal", '─'),
+    ("box drawings light vertical", '│'),
+    ("full block", '█'),
+    ("black star", '★'),
+    ("white star", '☆'),
+    ("black heart suit", '♥'),
+    ("snowman", '☃'),
+    ("warning sign", '⚠'),
+    ("high voltage sign", '⚡'),
+    ("replacement character", '\u{fffd}'),
+];
+
+///the char at a hex codepoint like 2192, U+2192 or 0x2192. surrogates and values above
+/// U+10FFFF aren't chars and are refused.
+pub fn parse_codepoint(input: &str) -> Result<char, String> {
+    let hex = input
+        .strip_prefix("U+")
+        .or_else(|| input.strip_prefix("u+"))
+        .or_else(|| input.strip_prefix("0x"))
+        .unwrap_or(input);
+    if hex.is_empty() || hex.len() > MAX_HEX_DIGITS {
+        return Err(format!(
+            "'{}' isn't a codepoint, expected 1 to {} hex digits",
+            input, MAX_HEX_DIGITS
+        ));
+    }
+    //from_str_radix would also take a sign
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "'{}' isn't a codepoint, expected hex digits",
+            input
+        ));
+    }
+    let value = u32::from_str_radix(hex, 16).unwrap_or(u32::MAX);
+    char::from_u32(value).ok_or_else(|| match value {
+        0xd800..=0xdfff => format!("U+{:04X} is a surrogate, not a character", value),
+        _ => format!("U+{:04X} is above U+10FFFF", value),
+    })
+}
+
+///characters whose name contains every word of query. names with more of the words as
+/// whole words come first, then shorter ones.
+pub fn lookup(query: &str) -> Vec<(&'static str, char)> {
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if words.is_empty() {
+        return vec![];
+    }
+    let mut found: Vec<(&str, char)> = NAMES
+        .iter()
+        .filter(|(name, _)| words.iter().all(|word| name.contains(word.as_str())))
+        .copied()
+        .collect();
+    found.sort_by_key(|(name, _)| {
+        let whole_words = words
+            .iter()
+            .filter(|word| name.split(' ').any(|part| part == word.as_str()))
+            .count();
+        (std::cmp::Reverse(whole_words), name.len())
+    });
+    found
+}
+
+///entry of a character in the :unicode name list
+pub fn describe(name: &str, c: char) -> String {
+    format!("{}  U+{:04X}  {}", c, c as u32, name)
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_unicode_tests {
+    use super::*;
+
+    #[test]
+    fn test_codepoints_parse_with_or_without_prefix() {
+        assert_eq!(parse_codepoint("2192"), Ok('→'));
+        assert_eq!(parse_codepoint("U+1F600"), Ok('😀'));
+        assert_eq!(parse_codepoint("0x41"), Ok('A'));
+        assert_eq!(parse_codepoint("10FFFF"), Ok('\u{10ffff}'));
+    }
+
+    #[test]
+    fn test_surrogates_and_out_of_range_are_refused() {
+        assert!(parse_codepoint("D800").unwrap_err().contains("surrogate"));
+        assert!(parse_codepoint("110000")
+            .unwrap_err()
+            .contains("above U+10FFFF"));
+        assert!(parse_codepoint("1234567").is_err());
+        assert!(parse_codepoint("zz").is_err());
+        assert!(parse_codepoint("+41").is_err());
+        assert!(parse_codepoint("").is_err());
+    }
+
+    #[test]
+    fn test_lookup_matches_every_word_shortest_first() {
+        let arrows = lookup("Arrow right");
+        assert_eq!(arrows[0], ("left right arrow", '↔'));
+        assert!(arrows.iter().any(|(_, c)| *c == '⇒'));
+        assert_eq!(lookup("rightwards")[0], ("rightwards arrow", '→'));
+        //pilcrow contains pi too, but not as a word
+        assert_eq!(lookup("pi")[0], ("greek small letter pi", 'π'));
+        assert!(lookup("   ").is_empty());
+        assert!(lookup("no such thing").is_empty());
+    }
+}
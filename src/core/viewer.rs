@@ -0,0 +1,148 @@
+//piping the buffer to a pager or other external viewer with :view, like :view less. the
+// viewer draws on the terminal itself, the run loop hands it over around it.
+
+use super::errors::error::AppError;
+use super::file_format::FileFormat;
+use super::file_io;
+use std::io::{BufWriter, ErrorKind, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+
+///runs command, its program followed by its args, with lines piped to its stdin as utf8
+/// ending in a newline, and waits for it to exit. what it prints on stderr is the reason
+/// given when it fails, a viewer quit before reading everything is not a failure.
+pub fn pipe_to(command: &[String], lines: &[String]) -> Result<(), AppError> {
+    let Some((program, args)) = command.split_first() else {
+        return Err(AppError::InternalError("no viewer to run".to_string()));
+    };
+    let failed = |reason: String| AppError::ViewerFailed {
+        command: command.join(" "),
+        reason,
+    };
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| failed(e.to_string()))?;
+    let Some(stdin) = child.stdin.take() else {
+        return Err(failed("stdin was not piped".to_string()));
+    };
+    //written on its own thread, a pager reads only as far as it is scrolled, and quitting
+    // it closes the pipe, ending the write with a broken pipe
+    let lines = lines.to_vec();
+    let writer = thread::spawn(move || {
+        let mut out = BufWriter::new(stdin);
+        let format = FileFormat {
+            final_newline: true,
+            ..FileFormat::default()
+        };
+        let lines = lines.iter().map(String::as_str);
+        file_io::write_lines(&mut out, lines, format).and_then(|()| out.flush())
+    });
+    let output = interrupts_ignored(|| child.wait_with_output()).map_err(|e| failed(e.to_string()));
+    let written = writer
+        .join()
+        .map_err(|_| failed("writing to it panicked".to_string()))?;
+    let output = output?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(failed(if stderr.is_empty() {
+            output.status.to_string()
+        } else {
+            stderr
+        }));
+    }
+    match written {
+        Err(e) if e.kind() != ErrorKind::BrokenPipe => Err(failed(e.to_string())),
+        _ => Ok(()),
+    }
+}
+
+///runs f with ctrl-c and ctrl-\ caught instead of ending the editor. outside raw mode the
+/// terminal sends them to the viewer and the editor alike, only the viewer should react.
+#[cfg(unix)]
+fn interrupts_ignored<T>(f: impl FnOnce() -> T) -> T {
+    use signal_hook::consts::{SIGINT, SIGQUIT};
+    use signal_hook::low_level;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    //the handlers only set a flag nobody reads, exec resets them, so the viewer gets
+    // the default ones
+    let caught = Arc::new(AtomicBool::new(false));
+    let ids: Vec<_> = [SIGINT, SIGQUIT]
+        .into_iter()
+        .filter_map(|signal| signal_hook::flag::register(signal, Arc::clone(&caught)).ok())
+        .collect();
+    let result = f();
+    for id in ids {
+        low_level::unregister(id);
+    }
+    result
+}
+
+#[cfg(not(unix))]
+fn interrupts_ignored<T>(f: impl FnOnce() -> T) -> T {
+    f()
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(all(test, unix))]
+mod unit_viewer_tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn words(text: &str) -> Vec<String> {
+        text.split(' ').map(String::from).collect()
+    }
+
+    ///sh running script, which gets no args of its own
+    fn sh(script: &str) -> Vec<String> {
+        vec!["sh".to_string(), "-c".to_string(), script.to_string()]
+    }
+
+    //cat stands in for a pager, writing what it was piped somewhere it can be checked
+    #[test]
+    fn test_buffer_is_piped_to_viewer() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("piped.txt");
+        let command = sh(&format!("cat > '{}'", path.display()));
+        pipe_to(&command, &words("first second")).unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "first\nsecond\n");
+    }
+
+    #[test]
+    fn test_viewer_quitting_early_is_fine() {
+        let lines = vec!["x".repeat(1000); 1000];
+        pipe_to(&words("head -c 0"), &lines).unwrap();
+    }
+
+    #[test]
+    fn test_failure_reason_is_stderr() {
+        let e = pipe_to(&words("cat /calliglyph/no/such/file"), &words("x")).unwrap_err();
+        let AppError::ViewerFailed { command, reason } = e else {
+            panic!("unexpected error {:?}", e);
+        };
+        assert_eq!(command, "cat /calliglyph/no/such/file");
+        assert!(reason.contains("No such file"), "{}", reason);
+    }
+
+    #[test]
+    fn test_killed_viewer_is_an_error() {
+        let e = pipe_to(&sh("kill -9 $$"), &words("x"));
+        assert!(matches!(e, Err(AppError::ViewerFailed { .. })));
+    }
+
+    #[test]
+    fn test_missing_program_is_an_error() {
+        let result = pipe_to(&words("calliglyph-no-such-viewer"), &words("x"));
+        assert!(matches!(result, Err(AppError::ViewerFailed { .. })));
+    }
+}
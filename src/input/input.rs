@@ -1,18 +1,40 @@
 use super::input_action::*;
-use crate::config::key_binds;
+use crate::config::{editor_settings, key_binds};
 use crate::core::app::ActiveArea;
 use crate::core::app::App;
+use crate::core::job_control;
+use crate::core::modal::EditorMode;
 use crossterm::event;
 use crossterm::event::{
     Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent, MouseEventKind,
 };
+use std::time::Duration;
 
-/// Reads the crossterm events and updates the state of [`App`].
+/// Waits for crossterm events and updates the state of [`App`].
 ///
-/// If your application needs to perform work in between handling events, you can use the
-/// [`event::poll`] function to check if there are any events available with a timeout.
-pub(crate) fn handle_input(app: &mut App) -> color_eyre::Result<()> {
-    match event::read()? {
+/// Polls with a timeout so the main loop can run timers when no input arrives, and drains
+/// every pending event before returning so bursts like key repeat are drawn once.
+/// Returns true if any event was handled.
+pub(crate) fn handle_input(app: &mut App) -> color_eyre::Result<bool> {
+    let timeout = Duration::from_millis(editor_settings::INPUT_POLL_TIMEOUT_MS);
+    if !event::poll(timeout)? {
+        return Ok(false);
+    }
+
+    let mut handled = 0;
+    loop {
+        app.handle_event(event::read()?);
+        handled += 1;
+        if handled >= editor_settings::MAX_EVENTS_PER_FRAME || !event::poll(Duration::ZERO)? {
+            break;
+        }
+    }
+    Ok(true)
+}
+
+///updates the state of [`App`] with a single crossterm event
+pub(crate) fn handle_event(app: &mut App, event: Event) {
+    match event {
         // it's important to check KeyEventKind::Press to avoid handling key release events
         Event::Key(key) if key.kind == KeyEventKind::Press => on_key_event(app, key),
         Event::Mouse(mouse)
@@ -21,20 +43,23 @@ pub(crate) fn handle_input(app: &mut App) -> color_eyre::Result<()> {
         {
             on_scroll_events(app, mouse)
         }
+        //bracketed paste delivers pasted text in one event instead of as keys
+        Event::Paste(text) => app.process_input_action(InputAction::InsertText(text)),
+        Event::FocusLost => app.on_focus_lost(),
+        //nothing waits for the terminal to come back
+        Event::FocusGained => {}
         Event::Resize(_, _) => {}
         _ => {}
     }
-    Ok(())
 }
 
 fn on_scroll_events(app: &mut App, mouse: MouseEvent) {
-    match app.active_area {
-        ActiveArea::Editor => match mouse.kind {
+    if app.active_area == ActiveArea::Editor {
+        match mouse.kind {
             MouseEventKind::ScrollDown => app.move_scroll_offset(1),
             MouseEventKind::ScrollUp => app.move_scroll_offset(-1),
             _ => {}
-        },
-        _ => {}
+        }
     }
 }
 
@@ -45,9 +70,85 @@ fn on_key_event(app: &mut App, key: KeyEvent) {
     app.process_input_action(input_action);
 }
 
+///checks if pressing bind does action in the app's current state, so hints show only keys
+/// that work, like leaving out esc for the command line while modal editing takes it
+pub(crate) fn binds_to(app: &App, bind: (KeyModifiers, KeyCode), action: &InputAction) -> bool {
+    let (modifiers, code) = bind;
+    map_key_to_action(app, KeyEvent::new(code, modifiers)) == *action
+}
+
+///short name of a key binding, like ^S for ctrl+s or M-o for alt+o
+///
+/// ```
+/// use calliglyph::config::key_binds::{KEYBIND_SAVE, KEYBIND_TOGGLE_AREA};
+/// use calliglyph::input::input::key_label;
+///
+/// assert_eq!(key_label(KEYBIND_SAVE), "^S");
+/// assert_eq!(key_label(KEYBIND_TOGGLE_AREA), "Esc");
+/// ```
+pub fn key_label((modifiers, code): (KeyModifiers, KeyCode)) -> String {
+    let mut label = String::new();
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        label.push('^');
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        label.push_str("M-");
+    }
+    //a shifted char is told by its case already
+    let shifted_char = matches!(code, KeyCode::Char(_));
+    if modifiers.contains(KeyModifiers::SHIFT) && !shifted_char {
+        label.push_str("S-");
+    }
+    match code {
+        KeyCode::Char(' ') => label.push_str("Space"),
+        KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+            label.extend(c.to_uppercase())
+        }
+        KeyCode::Char(c) => label.push(c),
+        KeyCode::Up => label.push('↑'),
+        KeyCode::Down => label.push('↓'),
+        KeyCode::Left => label.push('←'),
+        KeyCode::Right => label.push('→'),
+        KeyCode::Backspace => label.push_str("Bksp"),
+        KeyCode::Delete => label.push_str("Del"),
+        KeyCode::PageUp => label.push_str("PgUp"),
+        KeyCode::PageDown => label.push_str("PgDn"),
+        KeyCode::F(n) => label.push_str(&format!("F{}", n)),
+        code => label.push_str(&code.to_string()),
+    }
+    label
+}
+
+///maps keys that behave differently in modal editing,
+/// returns None for keys that keep their non-modal meaning
+fn map_modal_key_to_action(app: &App, key: KeyEvent) -> Option<InputAction> {
+    let plain = key.modifiers == KeyModifiers::NONE || key.modifiers == KeyModifiers::SHIFT;
+    match (app.modal.mode, key.code) {
+        (EditorMode::Insert, KeyCode::Esc) if app.editor.is_completing() => {
+            Some(InputAction::CancelCompletion)
+        }
+        (_, KeyCode::Esc) => Some(InputAction::EnterNormalMode),
+        (EditorMode::Insert, _) => None,
+        _ if (key.modifiers, key.code) == key_binds::KEYBIND_SUSPEND
+            && job_control::can_suspend() =>
+        {
+            Some(InputAction::Suspend)
+        }
+        (_, KeyCode::Char(':')) if plain => Some(InputAction::ToggleActiveArea),
+        (_, KeyCode::Char(c)) if plain => Some(InputAction::ModalKey(c)),
+        _ => None,
+    }
+}
+
 fn map_key_to_action(app: &App, key: KeyEvent) -> InputAction {
     use key_binds::*;
 
+    if app.active_area == ActiveArea::Editor && app.settings.modal {
+        if let Some(action) = map_modal_key_to_action(app, key) {
+            return action;
+        }
+    }
+
     match app.active_area {
         ActiveArea::Editor => match (key.modifiers, key.code) {
             KEYBIND_UP => InputAction::MoveCursor(Direction::Up),
@@ -58,15 +159,76 @@ fn map_key_to_action(app: &App, key: KeyEvent) -> InputAction {
             KEYBIND_SELECTION_DOWN => InputAction::MoveSelectionCursor(Direction::Down),
             KEYBIND_SELECTION_LEFT => InputAction::MoveSelectionCursor(Direction::Left),
             KEYBIND_SELECTION_RIGHT => InputAction::MoveSelectionCursor(Direction::Right),
+            KEYBIND_LINE_START => InputAction::MoveCursorTo(Motion::LineStart),
+            KEYBIND_LINE_END => InputAction::MoveCursorTo(Motion::LineEnd),
+            KEYBIND_WORD_LEFT => InputAction::MoveCursorTo(Motion::WordLeft),
+            KEYBIND_WORD_RIGHT => InputAction::MoveCursorTo(Motion::WordRight),
+            KEYBIND_DOCUMENT_START => InputAction::MoveCursorTo(Motion::DocumentStart),
+            KEYBIND_DOCUMENT_END => InputAction::MoveCursorTo(Motion::DocumentEnd),
+            KEYBIND_PARAGRAPH_UP => InputAction::MoveCursorTo(Motion::ParagraphUp),
+            KEYBIND_PARAGRAPH_DOWN => InputAction::MoveCursorTo(Motion::ParagraphDown),
+            KEYBIND_SELECTION_LINE_START => InputAction::MoveSelectionCursorTo(Motion::LineStart),
+            KEYBIND_SELECTION_LINE_END => InputAction::MoveSelectionCursorTo(Motion::LineEnd),
+            KEYBIND_SELECTION_WORD_LEFT => InputAction::MoveSelectionCursorTo(Motion::WordLeft),
+            KEYBIND_SELECTION_WORD_RIGHT => InputAction::MoveSelectionCursorTo(Motion::WordRight),
+            KEYBIND_SELECTION_DOCUMENT_START => {
+                InputAction::MoveSelectionCursorTo(Motion::DocumentStart)
+            }
+            KEYBIND_SELECTION_DOCUMENT_END => {
+                InputAction::MoveSelectionCursorTo(Motion::DocumentEnd)
+            }
+            KEYBIND_SELECTION_PARAGRAPH_UP => {
+                InputAction::MoveSelectionCursorTo(Motion::ParagraphUp)
+            }
+            KEYBIND_SELECTION_PARAGRAPH_DOWN => {
+                InputAction::MoveSelectionCursorTo(Motion::ParagraphDown)
+            }
             KEYBIND_TAB => InputAction::TAB,
             KEYBIND_ENTER => InputAction::ENTER,
+            KEYBIND_OPEN_LINE_BELOW => InputAction::OpenLine(true),
+            KEYBIND_OPEN_LINE_ABOVE => InputAction::OpenLine(false),
             KEYBIND_BACKSPACE => InputAction::BACKSPACE,
             KEYBIND_DELETE => InputAction::DELETE,
             KEYBIND_COPY => InputAction::COPY,
+            KEYBIND_COPY_APPEND => InputAction::CopyAppend,
+            KEYBIND_UNICODE => InputAction::StartUnicodeInput,
             KEYBIND_CUT => InputAction::CUT,
+            KEYBIND_DUPLICATE_SELECTION => InputAction::DuplicateSelection,
             KEYBIND_PASTE => InputAction::PASTE,
             KEYBIND_UNDO => InputAction::UNDO,
             KEYBIND_REDO => InputAction::REDO,
+            KEYBIND_REPEAT_LAST_CHANGE => InputAction::RepeatLastChange,
+            KEYBIND_MACRO_RECORD => InputAction::ToggleMacroRecording,
+            KEYBIND_MACRO_PLAY => InputAction::PlayMacro,
+            KEYBIND_SELECT_ENCLOSING => InputAction::SelectEnclosing,
+            KEYBIND_SWAP_SELECTION_ENDS => InputAction::SwapSelectionEnds,
+            KEYBIND_LINE_INSERT => InputAction::StartLineInsert,
+            KEYBIND_MARK_SET => InputAction::SetMark,
+            KEYBIND_MARK_JUMP => InputAction::JumpToMark,
+            KEYBIND_BOOKMARK_TOGGLE => InputAction::ToggleBookmark,
+            KEYBIND_BOOKMARK_NEXT => InputAction::JumpToBookmark(true),
+            KEYBIND_BOOKMARK_PREVIOUS => InputAction::JumpToBookmark(false),
+            KEYBIND_INCREMENT => InputAction::IncrementNumber(1),
+            KEYBIND_DECREMENT => InputAction::IncrementNumber(-1),
+            KEYBIND_COMPLETE_NEXT => InputAction::CompleteNext,
+            KEYBIND_COMPLETE_PREVIOUS if app.editor.is_completing() => {
+                InputAction::CompletePrevious
+            }
+            KEYBIND_FIND_FILE => InputAction::FindFile,
+            KEYBIND_SWITCH_BUFFER => InputAction::SwitchBuffer,
+            KEYBIND_VIEW_CENTER => InputAction::AlignView(ViewAlign::Center),
+            KEYBIND_VIEW_TOP => InputAction::AlignView(ViewAlign::Top),
+            KEYBIND_VIEW_BOTTOM => InputAction::AlignView(ViewAlign::Bottom),
+            KEYBIND_SCROLL_LINE_UP => InputAction::ScrollLines(-1),
+            KEYBIND_SCROLL_LINE_DOWN => InputAction::ScrollLines(1),
+            KEYBIND_SCROLL_HALF_PAGE_UP => InputAction::ScrollHalfPages(-1),
+            KEYBIND_SCROLL_HALF_PAGE_DOWN => InputAction::ScrollHalfPages(1),
+            KEYBIND_OPEN_UNDER_CURSOR => InputAction::OpenUnderCursor,
+            KEYBIND_SAVE => InputAction::Save,
+            KEYBIND_QUIT => InputAction::RequestQuit,
+            //esc drops an active completion or selection before it toggles to the command line
+            KEYBIND_TOGGLE_AREA if app.editor.is_completing() => InputAction::CancelCompletion,
+            KEYBIND_TOGGLE_AREA if app.editor.is_text_selected() => InputAction::ClearSelection,
             KEYBIND_TOGGLE_AREA => InputAction::ToggleActiveArea,
             (_, KeyCode::Char(c)) => InputAction::WriteChar(c),
             _ => InputAction::NoOp,
@@ -77,7 +239,8 @@ fn map_key_to_action(app: &App, key: KeyEvent) -> InputAction {
             KEYBIND_BACKSPACE => InputAction::BACKSPACE,
             KEYBIND_DELETE => InputAction::DELETE,
             KEYBIND_ENTER => InputAction::ENTER,
-            (_, KeyCode::Tab | KeyCode::Esc) => InputAction::ToggleActiveArea,
+            KEYBIND_TAB => InputAction::TAB,
+            (_, KeyCode::Esc) => InputAction::ToggleActiveArea,
             (KeyModifiers::CONTROL, KeyCode::Char('c')) => InputAction::QUIT,
             (_, KeyCode::Char(c)) => InputAction::WriteChar(c),
             _ => InputAction::NoOp,
@@ -88,7 +251,150 @@ fn map_key_to_action(app: &App, key: KeyEvent) -> InputAction {
             KEYBIND_LEFT => InputAction::MoveCursor(Direction::Left),
             KEYBIND_RIGHT => InputAction::MoveCursor(Direction::Right),
             KEYBIND_ENTER => InputAction::ENTER,
+            KEYBIND_BACKSPACE => InputAction::BACKSPACE,
+            KEYBIND_DELETE => InputAction::DELETE,
+            KEYBIND_TOGGLE_AREA => InputAction::ToggleActiveArea,
+            (_, KeyCode::Char(c)) => InputAction::WriteChar(c),
             _ => InputAction::NoOp,
         },
     }
 }
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_input_tests {
+    use super::*;
+    use crate::core::app::TerminalTask;
+    use crate::core::position::{Position, Range};
+
+    fn key(modifiers: KeyModifiers, code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn create_app_with_selection() -> App {
+        let mut app = App::new();
+        app.editor.editor_content = vec!["hello world".to_string()].into();
+        app.editor.cursor.x = 5;
+        app.editor.selection = Some(Range::new(Position::new(0, 0), Position::new(0, 5)));
+        app
+    }
+
+    #[test]
+    fn test_batch_of_events_is_handled_in_order() {
+        let mut app = App::new();
+        app.editor.editor_content = vec![String::new()].into();
+        for c in ['a', 'b', 'c'] {
+            handle_event(
+                &mut app,
+                Event::Key(key(KeyModifiers::NONE, KeyCode::Char(c))),
+            );
+        }
+        handle_event(&mut app, Event::Key(key(KeyModifiers::NONE, KeyCode::Left)));
+
+        assert_eq!(app.editor.editor_content[0], "abc");
+        assert_eq!(app.editor.cursor.x, 2);
+    }
+
+    #[test]
+    fn test_paste_goes_into_the_command_line() {
+        let mut app = App::new();
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.insert_text(":e ");
+        handle_event(&mut app, Event::Paste("~/ß/notes.md\n".to_string()));
+        assert_eq!(app.command_line.input, ":e ~/ß/notes.md ");
+
+        app.command_line.clear();
+        app.editor
+            .clipboard
+            .copy(&["a".to_string(), "b".to_string()]);
+        app.process_input_action(InputAction::PASTE);
+        assert_eq!(app.command_line.input, "a b");
+        assert_eq!(app.command_line.cursor.x, 3);
+        assert!(app.editor.editor_content.is_empty());
+    }
+
+    #[test]
+    fn test_esc_with_selection_clears_selection() {
+        let mut app = create_app_with_selection();
+        let action = map_key_to_action(&app, key(KeyModifiers::NONE, KeyCode::Esc));
+        assert_eq!(action, InputAction::ClearSelection);
+
+        app.process_input_action(action);
+        assert!(app.editor.selection.is_none());
+        assert_eq!(app.editor.cursor.x, 5);
+        assert_eq!(app.active_area, ActiveArea::Editor);
+    }
+
+    #[test]
+    fn test_esc_without_selection_toggles_area() {
+        let app = App::new();
+        let action = map_key_to_action(&app, key(KeyModifiers::NONE, KeyCode::Esc));
+        assert_eq!(action, InputAction::ToggleActiveArea);
+    }
+
+    #[test]
+    fn test_esc_while_completing_cancels_completion() {
+        let mut app = App::new();
+        app.editor.editor_content = vec!["hello".to_string(), String::new()].into();
+        app.editor.cursor.y = 1;
+        for c in "he".chars() {
+            app.process_input_action(InputAction::WriteChar(c));
+        }
+        let action = map_key_to_action(&app, key(KeyModifiers::CONTROL, KeyCode::Char('n')));
+        assert_eq!(action, InputAction::CompleteNext);
+        app.process_input_action(action);
+        assert_eq!(app.editor.editor_content[1], "hello");
+
+        let action = map_key_to_action(&app, key(KeyModifiers::NONE, KeyCode::Esc));
+        assert_eq!(action, InputAction::CancelCompletion);
+        app.process_input_action(action);
+        assert_eq!(app.editor.editor_content[1], "he");
+        assert_eq!(app.active_area, ActiveArea::Editor);
+    }
+
+    #[test]
+    fn test_ctrl_shift_c_appends_and_ctrl_c_copies() {
+        let app = create_app_with_selection();
+        let control_shift = KeyModifiers::CONTROL | KeyModifiers::SHIFT;
+        let action = map_key_to_action(&app, key(control_shift, KeyCode::Char('C')));
+        assert_eq!(action, InputAction::CopyAppend);
+        let action = map_key_to_action(&app, key(KeyModifiers::CONTROL, KeyCode::Char('c')));
+        assert_eq!(action, InputAction::COPY);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ctrl_z_suspends_only_in_modal_normal_mode() {
+        let mut app = App::new();
+        let ctrl_z = key(KeyModifiers::CONTROL, KeyCode::Char('z'));
+        assert_eq!(map_key_to_action(&app, ctrl_z), InputAction::UNDO);
+
+        app.settings.modal = true;
+        assert_eq!(map_key_to_action(&app, ctrl_z), InputAction::Suspend);
+        app.process_input_action(InputAction::Suspend);
+        assert_eq!(app.take_terminal_task(), Some(TerminalTask::Suspend));
+
+        app.modal.mode = EditorMode::Insert;
+        assert_eq!(map_key_to_action(&app, ctrl_z), InputAction::UNDO);
+    }
+
+    #[test]
+    fn test_ctrl_p_finds_files_unless_completing() {
+        let mut app = App::new();
+        app.editor.editor_content = vec!["apple ap".to_string()].into();
+        app.editor.cursor.x = 8;
+        let ctrl_p = key(KeyModifiers::CONTROL, KeyCode::Char('p'));
+        assert_eq!(map_key_to_action(&app, ctrl_p), InputAction::FindFile);
+
+        app.editor.start_completion(true);
+        assert_eq!(
+            map_key_to_action(&app, ctrl_p),
+            InputAction::CompletePrevious
+        );
+    }
+}
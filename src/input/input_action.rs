@@ -3,21 +3,102 @@
 pub enum InputAction {
     MoveCursor(Direction),
     MoveSelectionCursor(Direction),
+    MoveCursorTo(Motion),
+    MoveSelectionCursorTo(Motion),
+    ClearSelection,
+    //scrolls so the cursor line is at the top, middle or bottom of the viewport
+    AlignView(ViewAlign),
+    //scrolls the view by lines or half pages, negative up, leaving the cursor where it is
+    ScrollLines(isize),
+    ScrollHalfPages(isize),
+    SelectEnclosing,
+    //moves the cursor to the other end of the selection, so extending moves that end
+    SwapSelectionEnds,
+    //types text once for every selected line, until enter or esc copies it to them
+    StartLineInsert,
+    //word completion from the buffer
+    CompleteNext,
+    CompletePrevious,
+    CancelCompletion,
     TAB,
     ENTER,
     BACKSPACE,
     DELETE,
     COPY,
+    //copies the selection below the clipboard content instead of replacing it
+    CopyAppend,
     CUT,
+    CutLine,
+    CopyLine,
     PASTE,
     UNDO,
     REDO,
+    RepeatLastChange,
+    //adds the value to the number at the cursor, negative to decrement
+    IncrementNumber(i64),
+    //opens an indented empty line below the cursor line if true, else above it
+    OpenLine(bool),
+    //inserts a copy of the selection right after it
+    DuplicateSelection,
     ToggleActiveArea,
+    ToggleMacroRecording,
+    PlayMacro,
+    SetMark,
+    //starts typing a unicode codepoint in hex, inserted on enter
+    StartUnicodeInput,
+    JumpToMark,
+    //bookmarks the cursor line or removes its bookmark
+    ToggleBookmark,
+    //jumps to the next bookmarked line if true, else the previous one
+    JumpToBookmark(bool),
+    //modal editing
+    ModalKey(char),
+    EnterNormalMode,
     WriteChar(char),
+    //text pasted into the terminal, inserted at once
+    InsertText(String),
     QUIT,
+    //opens the file path or url under the cursor
+    OpenUnderCursor,
+    //opens the fuzzy finder over the files under the working dir
+    FindFile,
+    //lists the buffers to switch to, the one used before the open one picked
+    SwitchBuffer,
+    //save and quit from the editor, checking for unsaved changes
+    Save,
+    RequestQuit,
+    //stops the editor until the shell continues it, unix only
+    Suspend,
     NoOp,
 }
 
+impl InputAction {
+    ///checks if the action changes the editor content, these are refused in read-only views
+    pub fn edits_content(&self) -> bool {
+        matches!(
+            self,
+            InputAction::TAB
+                | InputAction::ENTER
+                | InputAction::BACKSPACE
+                | InputAction::DELETE
+                | InputAction::CUT
+                | InputAction::CutLine
+                | InputAction::PASTE
+                | InputAction::UNDO
+                | InputAction::REDO
+                | InputAction::RepeatLastChange
+                | InputAction::IncrementNumber(_)
+                | InputAction::OpenLine(_)
+                | InputAction::DuplicateSelection
+                | InputAction::CompleteNext
+                | InputAction::CompletePrevious
+                | InputAction::WriteChar(_)
+                | InputAction::InsertText(_)
+                | InputAction::StartLineInsert
+        )
+    }
+}
+
 ///direction enum to use in action enum values
 #[derive(Clone, Debug, PartialEq)]
 pub enum Direction {
@@ -27,9 +108,30 @@ pub enum Direction {
     Right,
 }
 
-///convert direction to (x,y) vector with i16 values
+///motions jumping the cursor further than one step
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Motion {
+    LineStart,
+    LineEnd,
+    WordLeft,
+    WordRight,
+    DocumentStart,
+    DocumentEnd,
+    ParagraphUp,
+    ParagraphDown,
+}
+
+///where the cursor line is placed in the viewport by AlignView
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ViewAlign {
+    Top,
+    Center,
+    Bottom,
+}
+
+///convert direction to (x,y) vector of signed deltas
 impl Direction {
-    pub fn to_vector(self) -> (i16, i16) {
+    pub fn to_vector(self) -> (isize, isize) {
         match self {
             Direction::Up => (0, -1),
             Direction::Down => (0, 1),
@@ -1,2 +1,3 @@
+#[allow(clippy::module_inception)]
 pub mod input;
 pub mod input_action;
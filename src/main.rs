@@ -1,18 +1,3 @@
-pub use core::app::App;
-
-//███╗   ███╗ ██████╗ ██████╗ ██╗   ██╗██╗     ███████╗███████╗
-//████╗ ████║██╔═══██╗██╔══██╗██║   ██║██║     ██╔════╝██╔════╝
-//██╔████╔██║██║   ██║██║  ██║██║   ██║██║     █████╗  ███████╗
-//██║╚██╔╝██║██║   ██║██║  ██║██║   ██║██║     ██╔══╝  ╚════██║
-//██║ ╚═╝ ██║╚██████╔╝██████╔╝╚██████╔╝███████╗███████╗███████║
-//╚═╝     ╚═╝ ╚═════╝ ╚═════╝  ╚═════╝ ╚══════╝╚══════╝╚══════╝
-
-mod config;
-pub mod core; //expose app module
-pub mod input; //expose input module
-
-pub mod ui;
-
 //███╗   ███╗ █████╗ ██╗███╗   ██╗
 //████╗ ████║██╔══██╗██║████╗  ██║
 //██╔████╔██║███████║██║██╔██╗ ██║
@@ -20,36 +5,100 @@ pub mod ui;
 //██║ ╚═╝ ██║██║  ██║██║██║ ╚████║
 //╚═╝     ╚═╝╚═╝  ╚═╝╚═╝╚═╝  ╚═══╝
 
-use crossterm::event::DisableMouseCapture;
-use crossterm::terminal::LeaveAlternateScreen;
-use ratatui::crossterm::event::EnableMouseCapture;
-use ratatui::crossterm::execute;
-use ratatui::crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen};
-use std::{env, io};
+//the binary is a thin shell around the library, using only its public api
+use calliglyph::config::editor_settings;
+use calliglyph::core::app::App;
+use calliglyph::core::{batch, logging, terminal};
+use calliglyph::ui::theme;
+use color_eyre::eyre::eyre;
+use std::path::PathBuf;
+use std::{env, fs, io, process};
 
 fn main() -> color_eyre::Result<()> {
     env::set_var("RUST_BACKTRACE", "1"); //more verbose error codes
 
-    let args: Vec<String> = env::args().collect();
+    let mut show_recent = false;
+    let mut file_path = None;
+    let mut log_file = editor_settings::LOG_FILE.map(PathBuf::from);
+    let mut log_level = editor_settings::LOG_LEVEL.to_string();
+    let mut restrict_dir = editor_settings::RESTRICT_TO_DIR.map(PathBuf::from);
+    let mut batch_script = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            //--recent opens the recent files picker instead of a file
+            "--recent" => show_recent = true,
+            "--log" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| eyre!("--log expects a file path"))?;
+                log_file = Some(PathBuf::from(path));
+            }
+            //--batch runs the commands in a script on the file without the tui
+            "--batch" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| eyre!("--batch expects a script path"))?;
+                batch_script = Some(path);
+            }
+            "--restrict" => {
+                let dir = args
+                    .next()
+                    .ok_or_else(|| eyre!("--restrict expects a dir"))?;
+                restrict_dir = Some(PathBuf::from(dir));
+            }
+            "--log-level" => {
+                log_level = args
+                    .next()
+                    .ok_or_else(|| eyre!("--log-level expects a level"))?;
+            }
+            _ if file_path.is_none() => file_path = Some(arg),
+            _ => {}
+        }
+    }
 
-    let file_path = if args.len() > 1 {
-        Some(args[1].clone())
-    } else {
-        None
-    };
+    //set up before the terminal is taken over, so a bad log path is reported readably
+    if let Some(path) = log_file {
+        let level = logging::parse_level(&log_level).ok_or_else(|| {
+            eyre!(
+                "unknown log level '{}', expected one of {}",
+                log_level,
+                logging::LOG_LEVELS.join(", ")
+            )
+        })?;
+        logging::init(&path, level)?;
+    }
+    let mut app = App::new();
+    //NO_COLOR and terminals without colors get the monochrome theme
+    app.settings.color &= theme::terminal_has_colors();
+    if let Some(dir) = restrict_dir {
+        app.restrict_writes_to(&dir)
+            .map_err(|e| eyre!("can't restrict writes to {}: {}", dir.display(), e))?;
+    }
 
-    enable_raw_mode().expect("Failed to enable raw mode");
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    if let Some(path) = batch_script {
+        let script = fs::read_to_string(&path)
+            .map_err(|e| eyre!("can't read batch script {}: {}", path, e))?;
+        let result = batch::run(&mut app, file_path, &script, &mut io::stdout());
+        //exiting skips destructors, the app has to release the file lock first
+        drop(app);
+        if let Err(e) = result {
+            eprintln!("calliglyph: {}", e);
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    //hooks go first, so a panic anywhere after entering raw mode restores the terminal
+    terminal::install_hooks()?;
+
+    terminal::setup_terminal()?;
 
-    color_eyre::install()?;
     let terminal = ratatui::init();
-    let result = App::new().run(terminal, file_path);
+    let result = app.run(terminal, file_path, show_recent);
     //let result = ui::ui(&mut terminal, &app);
     ratatui::restore();
-
-    disable_raw_mode()?;
-    execute!(stdout, LeaveAlternateScreen, DisableMouseCapture)?;
+    terminal::restore_terminal()?;
 
     result
 }
@@ -0,0 +1,132 @@
+//the hint line of keys worth knowing in the active area, shown at the end of the bottom row.
+// keys come from the keymap and are checked against it, so they are the ones that work.
+
+use super::popups::popup::PopupType;
+use crate::config::key_binds::*;
+use crate::core::app::{ActiveArea, App};
+use crate::input::input::{binds_to, key_label};
+use crate::input::input_action::{Direction, InputAction};
+use crossterm::event::{KeyCode, KeyModifiers};
+
+///keys doing one thing together, like the arrows choosing, with the actions they do
+#[derive(Debug, Clone)]
+pub struct KeyHint {
+    keys: Vec<((KeyModifiers, KeyCode), InputAction)>,
+    label: &'static str,
+}
+
+impl KeyHint {
+    pub fn new(label: &'static str) -> Self {
+        Self {
+            keys: vec![],
+            label,
+        }
+    }
+
+    ///adds bind, shown as long as it does action
+    pub fn key(mut self, bind: (KeyModifiers, KeyCode), action: InputAction) -> Self {
+        self.keys.push((bind, action));
+        self
+    }
+
+    ///the hint with the keys that do their action in app now, None if none does
+    fn describe(&self, app: &App) -> Option<String> {
+        let keys: Vec<String> = self
+            .keys
+            .iter()
+            .filter(|(bind, action)| binds_to(app, *bind, action))
+            .map(|&(bind, _)| key_label(bind))
+            .collect();
+        (!keys.is_empty()).then(|| format!("{} {}", keys.join("/"), self.label))
+    }
+}
+
+///hints of a popup of popup_type, what its keys do in every popup of the type
+pub fn popup_hints(popup_type: &PopupType) -> Vec<KeyHint> {
+    let cancel =
+        |label| KeyHint::new(label).key(KEYBIND_TOGGLE_AREA, InputAction::ToggleActiveArea);
+    match popup_type {
+        PopupType::Confirmation | PopupType::Warning => vec![
+            KeyHint::new("choose")
+                .key(KEYBIND_LEFT, InputAction::MoveCursor(Direction::Left))
+                .key(KEYBIND_RIGHT, InputAction::MoveCursor(Direction::Right)),
+            KeyHint::new("confirm").key(KEYBIND_ENTER, InputAction::ENTER),
+            cancel("cancel"),
+        ],
+        PopupType::List => vec![
+            KeyHint::new("choose")
+                .key(KEYBIND_UP, InputAction::MoveCursor(Direction::Up))
+                .key(KEYBIND_DOWN, InputAction::MoveCursor(Direction::Down)),
+            KeyHint::new("open").key(KEYBIND_ENTER, InputAction::ENTER),
+            cancel("close"),
+        ],
+        PopupType::Input => vec![
+            KeyHint::new("confirm").key(KEYBIND_ENTER, InputAction::ENTER),
+            cancel("cancel"),
+        ],
+        PopupType::Error | PopupType::Text => vec![KeyHint::new("close")
+            .key(KEYBIND_ENTER, InputAction::ENTER)
+            .key(KEYBIND_TOGGLE_AREA, InputAction::ToggleActiveArea)],
+        PopupType::None => vec![],
+    }
+}
+
+///hints of the active area of app, joined into one line
+pub fn hint_line(app: &App) -> String {
+    let hints = match app.active_area {
+        ActiveArea::Editor => vec![
+            KeyHint::new("save").key(KEYBIND_SAVE, InputAction::Save),
+            KeyHint::new("quit").key(KEYBIND_QUIT, InputAction::RequestQuit),
+            //modal editing opens the command line with a colon instead
+            KeyHint::new("commands")
+                .key(KEYBIND_TOGGLE_AREA, InputAction::ToggleActiveArea)
+                .key(
+                    (KeyModifiers::NONE, KeyCode::Char(':')),
+                    InputAction::ToggleActiveArea,
+                ),
+        ],
+        ActiveArea::CommandLine => vec![
+            KeyHint::new("run").key(KEYBIND_ENTER, InputAction::ENTER),
+            KeyHint::new("cancel").key(KEYBIND_TOGGLE_AREA, InputAction::ToggleActiveArea),
+            KeyHint::new("complete").key(KEYBIND_TAB, InputAction::TAB),
+        ],
+        ActiveArea::Popup => app
+            .popup
+            .as_ref()
+            .map_or_else(Vec::new, |popup| popup.key_hints()),
+    };
+    hints
+        .iter()
+        .filter_map(|hint| hint.describe(app))
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_hints_tests {
+    use super::*;
+    use crate::ui::popups::confirmation_popup::ConfirmationPopup;
+
+    #[test]
+    fn test_hints_follow_the_active_area() {
+        let mut app = App::new();
+        assert_eq!(hint_line(&app), "^S save  ^Q quit  Esc commands");
+        app.active_area = ActiveArea::CommandLine;
+        assert_eq!(hint_line(&app), "Enter run  Esc cancel  Tab complete");
+        app.open_popup(Box::new(ConfirmationPopup::new("Overwrite?")));
+        assert_eq!(hint_line(&app), "←/→ choose  Enter confirm  Esc cancel");
+    }
+
+    #[test]
+    fn test_keys_taken_by_modal_editing_are_left_out() {
+        let mut app = App::new();
+        app.settings.modal = true;
+        assert_eq!(hint_line(&app), "^S save  ^Q quit  : commands");
+    }
+}
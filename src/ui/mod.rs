@@ -1,2 +1,5 @@
+pub mod hints;
 pub mod popups;
+pub mod theme;
+#[allow(clippy::module_inception)]
 pub mod ui;
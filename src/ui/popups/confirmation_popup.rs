@@ -1,8 +1,8 @@
 use super::popup::{Popup, PopupResult, PopupType};
 use crate::input::input_action::Direction;
 use crate::input::input_action::InputAction;
+use crate::ui::theme::Theme;
 use ratatui::layout::{Alignment, Rect};
-use ratatui::prelude::{Color, Style};
 use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 use ratatui::Frame;
@@ -11,6 +11,8 @@ use ratatui::Frame;
 pub struct ConfirmationPopup {
     pub message: String,
     pub selected_option: bool, // true = Yes, false = No
+    //what the two options read, Yes and No unless a popup names them
+    pub labels: (String, String),
 }
 
 impl ConfirmationPopup {
@@ -18,13 +20,20 @@ impl ConfirmationPopup {
         Self {
             message: msg.to_string(),
             selected_option: true,
+            labels: ("Yes".to_string(), "No".to_string()),
         }
     }
+
+    ///names the options after what they do, the first one still answers true
+    pub fn with_labels(mut self, yes: &str, no: &str) -> Self {
+        self.labels = (yes.to_string(), no.to_string());
+        self
+    }
 }
 impl Popup for ConfirmationPopup {
-    fn render(&self, frame: &mut Frame, area: Rect) {
-        let selected_style = Style::default().bg(Color::White).fg(Color::Black);
-        let non_selected_style = Style::default().bg(Color::Black).fg(Color::White);
+    fn render(&self, frame: &mut Frame, area: Rect, theme: Theme) {
+        let selected_style = theme.popup_selected();
+        let non_selected_style = theme.popup_unselected();
 
         // Highlight correct option
         let yes_style = if self.selected_option {
@@ -41,19 +50,19 @@ impl Popup for ConfirmationPopup {
         let popup_block = Block::default()
             .title("Confirm?")
             .borders(Borders::ALL)
-            .style(Style::default().fg(Color::White).bg(Color::Black));
+            .style(theme.popup());
 
         let popup = Paragraph::new(Text::from(vec![
             Line::from(Span::raw(&self.message)),
             Line::from(Span::raw("")), // Empty line
             Line::from(vec![
-                Span::styled(" Yes ", yes_style),
+                Span::styled(format!(" {} ", self.labels.0), yes_style),
                 Span::raw("  "), // Space between "Yes" and "No"
-                Span::styled(" No ", no_style),
+                Span::styled(format!(" {} ", self.labels.1), no_style),
             ]),
         ]))
         .block(popup_block)
-        .style(Style::default().fg(Color::White).bg(Color::Black))
+        .style(theme.popup())
         .alignment(Alignment::Center);
 
         // Render the popup in the centered `area`
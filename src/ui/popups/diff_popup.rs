@@ -0,0 +1,138 @@
+use super::popup::{Popup, PopupResult, PopupType};
+use crate::input::input_action::{Direction, InputAction};
+use crate::ui::theme::Theme;
+use ratatui::layout::Rect;
+use ratatui::prelude::Style;
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+/// scrollable unified diff, with added and removed lines colored
+#[derive(Debug)]
+pub struct DiffPopup {
+    pub title: String,
+    pub lines: Vec<String>,
+    ///index of the first line shown
+    pub scroll: usize,
+}
+
+impl DiffPopup {
+    pub fn new(title: &str, lines: Vec<String>) -> Self {
+        Self {
+            title: title.to_string(),
+            lines,
+            scroll: 0,
+        }
+    }
+
+    fn scroll_by(&mut self, offset: isize) {
+        let last = self.lines.len().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(offset).min(last);
+    }
+}
+
+///style of a unified diff line, by its first char. the two file name lines come first
+fn line_style(index: usize, line: &str, theme: Theme) -> Style {
+    match line.chars().next() {
+        _ if index < 2 => theme.diff_header(),
+        Some('+') => theme.diff_added(),
+        Some('-') => theme.diff_removed(),
+        Some('@') => theme.diff_hunk(),
+        _ => Style::default(),
+    }
+}
+
+impl Popup for DiffPopup {
+    fn render(&self, frame: &mut Frame, area: Rect, theme: Theme) {
+        let popup_block = Block::default()
+            .title(self.title.as_str())
+            .borders(Borders::ALL)
+            .style(theme.popup());
+
+        let visible_rows = area.height.saturating_sub(2) as usize;
+        let lines: Vec<Line> = if self.lines.is_empty() {
+            vec![Line::from(Span::raw("(no changes)"))]
+        } else {
+            self.lines
+                .iter()
+                .enumerate()
+                .skip(self.scroll)
+                .take(visible_rows)
+                .map(|(i, line)| {
+                    Line::from(Span::styled(line.as_str(), line_style(i, line, theme)))
+                })
+                .collect()
+        };
+
+        let popup = Paragraph::new(Text::from(lines))
+            .block(popup_block)
+            .style(theme.popup());
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(popup, area);
+    }
+
+    fn get_popup_type(&self) -> PopupType {
+        PopupType::Text
+    }
+
+    fn handle_input_action(&mut self, action: InputAction) -> PopupResult {
+        match action {
+            InputAction::MoveCursor(Direction::Up) => self.scroll_by(-1),
+            InputAction::MoveCursor(Direction::Down) => self.scroll_by(1),
+            //left and right page through longer diffs
+            InputAction::MoveCursor(Direction::Left) => self.scroll_by(-10),
+            InputAction::MoveCursor(Direction::Right) => self.scroll_by(10),
+            InputAction::ENTER => return PopupResult::Affirmed,
+            _ => {}
+        }
+        PopupResult::None
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_diff_popup_tests {
+    use super::*;
+    use ratatui::style::{Color, Modifier};
+
+    #[test]
+    fn test_scroll_stays_within_lines() {
+        let lines = (0..15).map(|i| format!(" {}", i)).collect();
+        let mut popup = DiffPopup::new("diff", lines);
+        popup.handle_input_action(InputAction::MoveCursor(Direction::Up));
+        assert_eq!(popup.scroll, 0);
+        popup.handle_input_action(InputAction::MoveCursor(Direction::Right));
+        popup.handle_input_action(InputAction::MoveCursor(Direction::Right));
+        assert_eq!(popup.scroll, 14);
+        popup.handle_input_action(InputAction::MoveCursor(Direction::Down));
+        assert_eq!(popup.scroll, 14);
+        assert_eq!(popup.dismiss_result(), PopupResult::Cancelled);
+    }
+
+    #[test]
+    fn test_headers_and_changed_lines_are_styled() {
+        let theme = Theme::Color;
+        assert_eq!(line_style(0, "--- a", theme).add_modifier, Modifier::BOLD);
+        assert_eq!(line_style(4, "-gone", theme).fg, Some(Color::Red));
+        assert_eq!(line_style(5, "+new", theme).fg, Some(Color::Green));
+        assert_eq!(line_style(6, " same", theme).fg, None);
+    }
+
+    //without colors added and removed lines still look different from the rest
+    #[test]
+    fn test_changed_lines_are_styled_without_colors() {
+        let theme = Theme::Mono;
+        assert_eq!(line_style(5, "+new", theme).add_modifier, Modifier::BOLD);
+        assert_eq!(
+            line_style(4, "-gone", theme).add_modifier,
+            Modifier::UNDERLINED
+        );
+        assert_eq!(line_style(4, "-gone", theme).fg, None);
+    }
+}
@@ -1,9 +1,10 @@
 use super::popup::{Popup, PopupResult, PopupType};
 use crate::core::errors::error::AppError;
 use crate::input::input_action::InputAction;
+use crate::ui::theme::Theme;
 use ratatui::layout::{Alignment, Rect};
-use ratatui::prelude::{Color, Line, Span, Style, Text};
-use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::prelude::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 use ratatui::Frame;
 
 pub struct ErrorPopup {
@@ -13,6 +14,8 @@ pub struct ErrorPopup {
 
 impl ErrorPopup {
     pub fn new(msg: &str, e: AppError) -> Self {
+        //every error shown to the user passes through here
+        tracing::warn!(title = msg, error = %e, "error shown");
         Self {
             message: msg.to_string(),
             error: e,
@@ -21,13 +24,13 @@ impl ErrorPopup {
 }
 
 impl Popup for ErrorPopup {
-    fn render(&self, frame: &mut Frame, area: Rect) {
-        let button_style = Style::default().bg(Color::White).fg(Color::Black);
+    fn render(&self, frame: &mut Frame, area: Rect, theme: Theme) {
+        let button_style = theme.popup_selected();
 
         let popup_block = Block::default()
             .title("Error?")
             .borders(Borders::ALL)
-            .style(Style::default().fg(Color::White).bg(Color::Black));
+            .style(theme.popup());
 
         let popup = Paragraph::new(Text::from(vec![
             Line::from(Span::raw(&self.message)),
@@ -35,8 +38,10 @@ impl Popup for ErrorPopup {
             Line::from(Span::styled(" OK ", button_style)),
         ]))
         .block(popup_block)
-        .style(Style::default().fg(Color::White).bg(Color::Black))
-        .alignment(Alignment::Center);
+        .style(theme.popup())
+        .alignment(Alignment::Center)
+        //errors of several saves at once don't fit on one line
+        .wrap(Wrap { trim: true });
 
         // Render the popup in the centered `area`
         frame.render_widget(Clear, area); // Clears the popup area to avoid overlap
@@ -0,0 +1,169 @@
+use super::popup::{Popup, PopupResult, PopupType};
+use crate::input::input_action::InputAction;
+use crate::ui::theme::Theme;
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+#[derive(Debug)]
+pub struct InputPopup {
+    pub title: String,
+    pub input: String,
+    //input is a suggestion that typing replaces, enter still takes it as is
+    suggested: bool,
+}
+
+impl InputPopup {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            input: String::new(),
+            suggested: false,
+        }
+    }
+
+    ///starts with input suggested, replaced by whatever is typed or pasted first
+    pub fn with_suggestion(mut self, input: &str) -> Self {
+        self.input = input.to_string();
+        self.suggested = true;
+        self
+    }
+
+    ///drops the suggestion before the first edit of the input
+    fn take_suggestion(&mut self) {
+        if std::mem::take(&mut self.suggested) {
+            self.input.clear();
+        }
+    }
+}
+
+impl Popup for InputPopup {
+    fn render(&self, frame: &mut Frame, area: Rect, theme: Theme) {
+        let popup_block = Block::default()
+            .title(self.title.as_str())
+            .borders(Borders::ALL)
+            .style(theme.popup());
+
+        let popup = Paragraph::new(Text::from(vec![
+            Line::from(vec![
+                Span::raw("> "),
+                if self.suggested {
+                    Span::styled(&self.input, theme.suggestion())
+                } else {
+                    Span::raw(&self.input)
+                },
+            ]),
+            Line::from(Span::raw("")), // Empty line
+            Line::from(Span::styled(
+                "Enter to confirm, Esc to cancel",
+                theme.dimmed(),
+            )),
+        ]))
+        .block(popup_block)
+        .style(theme.popup());
+
+        // Render the popup in the centered `area`
+        frame.render_widget(Clear, area); // Clears the popup area to avoid overlap
+        frame.render_widget(popup, area);
+    }
+
+    fn get_popup_type(&self) -> PopupType {
+        PopupType::Input
+    }
+
+    fn handle_input_action(&mut self, action: InputAction) -> PopupResult {
+        match action {
+            InputAction::WriteChar(c) => {
+                self.take_suggestion();
+                self.input.push(c);
+                PopupResult::None
+            }
+            //the input is a single line, line breaks in pasted text become spaces
+            InputAction::InsertText(text) => {
+                self.take_suggestion();
+                self.input
+                    .push_str(&text.replace("\r\n", " ").replace(['\n', '\r'], " "));
+                PopupResult::None
+            }
+            InputAction::BACKSPACE => {
+                self.take_suggestion();
+                self.input.pop();
+                PopupResult::None
+            }
+            //empty input can't be confirmed
+            InputAction::ENTER if self.input.trim().is_empty() => PopupResult::None,
+            InputAction::ENTER => PopupResult::String(self.input.trim().to_string()),
+            _ => PopupResult::None,
+        }
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_input_popup_tests {
+    use super::*;
+
+    #[test]
+    fn test_typed_input_is_returned_on_enter() {
+        let mut popup = InputPopup::new("test");
+        for c in "notes.txt".chars() {
+            popup.handle_input_action(InputAction::WriteChar(c));
+        }
+        popup.handle_input_action(InputAction::WriteChar('x'));
+        popup.handle_input_action(InputAction::BACKSPACE);
+
+        assert_eq!(
+            popup.handle_input_action(InputAction::ENTER),
+            PopupResult::String("notes.txt".to_string())
+        );
+    }
+
+    #[test]
+    fn test_pasted_text_is_added_on_one_line() {
+        let mut popup = InputPopup::new("test");
+        popup.handle_input_action(InputAction::WriteChar('~'));
+        popup.handle_input_action(InputAction::InsertText("/notes/\r\nä.txt\n".to_string()));
+        assert_eq!(popup.input, "~/notes/ ä.txt ");
+    }
+
+    #[test]
+    fn test_enter_with_empty_input_does_nothing() {
+        let mut popup = InputPopup::new("test");
+        assert_eq!(
+            popup.handle_input_action(InputAction::ENTER),
+            PopupResult::None
+        );
+    }
+
+    #[test]
+    fn test_suggestion_is_replaced_by_typing_or_taken_on_enter() {
+        let mut popup = InputPopup::new("test").with_suggestion("untitled.txt");
+        popup.handle_input_action(InputAction::WriteChar('a'));
+        assert_eq!(popup.input, "a");
+        popup.handle_input_action(InputAction::BACKSPACE);
+        popup.handle_input_action(InputAction::WriteChar('b'));
+        assert_eq!(popup.input, "b");
+
+        let mut popup = InputPopup::new("test").with_suggestion("untitled.txt");
+        popup.handle_input_action(InputAction::BACKSPACE);
+        assert_eq!(popup.input, "");
+
+        let mut popup = InputPopup::new("test").with_suggestion("untitled.txt");
+        assert_eq!(
+            popup.handle_input_action(InputAction::ENTER),
+            PopupResult::String("untitled.txt".to_string())
+        );
+    }
+
+    #[test]
+    fn test_esc_cancels() {
+        let popup = InputPopup::new("test");
+        assert_eq!(popup.dismiss_result(), PopupResult::Cancelled);
+    }
+}
@@ -0,0 +1,374 @@
+use super::popup::{Popup, PopupResult, PopupType};
+use crate::config::key_binds::KEYBIND_DELETE;
+use crate::core::fuzzy;
+use crate::input::input_action::Direction;
+use crate::input::input_action::InputAction;
+use crate::ui::hints::{popup_hints, KeyHint};
+use crate::ui::theme::Theme;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+#[derive(Debug)]
+pub struct ListPopup {
+    pub title: String,
+    pub items: Vec<String>,
+    ///index into the shown items
+    pub selected: usize,
+    ///items shown dimmed, like entries that no longer exist
+    pub dimmed: Vec<bool>,
+    ///typed text the items are fuzzy filtered and ranked by, None if typing doesn't filter
+    pub filter: Option<String>,
+    ///delete answers with the selected entry to remove it, instead of doing nothing
+    pub removable: bool,
+    //items shown, by their index in items, with the chars the filter matched
+    shown: Vec<(usize, Vec<usize>)>,
+}
+
+impl ListPopup {
+    pub fn new(title: &str, items: Vec<String>) -> Self {
+        let shown = (0..items.len()).map(|i| (i, vec![])).collect();
+        Self {
+            title: title.to_string(),
+            items,
+            selected: 0,
+            dimmed: vec![],
+            filter: None,
+            removable: false,
+            shown,
+        }
+    }
+
+    ///marks which items are shown dimmed, by index
+    pub fn with_dimmed(mut self, dimmed: Vec<bool>) -> Self {
+        self.dimmed = dimmed;
+        self
+    }
+
+    ///lets typing filter the items, the ones matching best first
+    pub fn with_filter(mut self) -> Self {
+        self.filter = Some(String::new());
+        self
+    }
+
+    ///lets delete answer with the selected entry, for lists whose entries can be removed
+    pub fn with_remove(mut self) -> Self {
+        self.removable = true;
+        self
+    }
+
+    ///selects the entry at index of the items, if it is shown
+    pub fn with_selected(mut self, index: usize) -> Self {
+        if let Some(row) = self.shown.iter().position(|(i, _)| *i == index) {
+            self.selected = row;
+        }
+        self
+    }
+
+    ///moves selection up or down, wrapping around at the ends
+    fn move_selection(&mut self, offset: isize) {
+        if self.shown.is_empty() {
+            return;
+        }
+        let len = self.shown.len() as isize;
+        self.selected = (self.selected as isize + offset).rem_euclid(len) as usize;
+    }
+
+    ///shows the items matching the filter, best first, or all of them in order without one
+    fn apply_filter(&mut self) {
+        self.shown = match self.filter.as_deref() {
+            Some(query) if !query.is_empty() => {
+                fuzzy::rank(query, self.items.iter().map(String::as_str))
+            }
+            _ => (0..self.items.len()).map(|i| (i, vec![])).collect(),
+        };
+        self.selected = self.selected.min(self.shown.len().saturating_sub(1));
+    }
+
+    ///changes the filter with edit, the best match is selected after
+    fn edit_filter(&mut self, edit: impl FnOnce(&mut String)) -> PopupResult {
+        if let Some(filter) = self.filter.as_mut() {
+            edit(filter);
+            self.selected = 0;
+            self.apply_filter();
+        }
+        PopupResult::None
+    }
+}
+
+///item split into spans at the matched chars, which are styled with matched on top of style
+fn highlighted<'a>(item: &'a str, matched: &[usize], style: Style, theme: Theme) -> Line<'a> {
+    if matched.is_empty() {
+        return Line::from(Span::styled(item, style));
+    }
+    let matched_style = theme.matched().patch(style);
+    let mut spans = vec![];
+    let mut done = 0;
+    for (i, (at, c)) in item.char_indices().enumerate() {
+        if !matched.contains(&i) {
+            continue;
+        }
+        spans.push(Span::styled(&item[done..at], style));
+        spans.push(Span::styled(&item[at..at + c.len_utf8()], matched_style));
+        done = at + c.len_utf8();
+    }
+    spans.push(Span::styled(&item[done..], style));
+    Line::from(spans)
+}
+
+impl Popup for ListPopup {
+    fn render(&self, frame: &mut Frame, area: Rect, theme: Theme) {
+        let selected_style = theme.popup_selected();
+        let dimmed_style = theme.dimmed();
+
+        let popup_block = Block::default()
+            .title(self.title.as_str())
+            .borders(Borders::ALL)
+            .style(theme.popup());
+
+        //the typed filter takes the first row
+        let mut lines = vec![];
+        if let Some(filter) = &self.filter {
+            lines.push(Line::from(vec![
+                Span::raw("> "),
+                Span::raw(filter.as_str()),
+            ]));
+        }
+
+        //keep the selected row inside the visible part of the popup
+        let visible_rows = (area.height.saturating_sub(2) as usize).saturating_sub(lines.len());
+        let first_row = (self.selected + 1).saturating_sub(visible_rows);
+
+        if self.items.is_empty() {
+            lines.push(Line::from(Span::raw("(empty)")));
+        } else if self.shown.is_empty() {
+            lines.push(Line::from(Span::raw("(no matches)")));
+        }
+        lines.extend(
+            self.shown
+                .iter()
+                .enumerate()
+                .skip(first_row)
+                .take(visible_rows)
+                .map(|(row, (i, matched))| {
+                    let style = if row == self.selected {
+                        selected_style
+                    } else if self.dimmed.get(*i) == Some(&true) {
+                        dimmed_style
+                    } else {
+                        Style::default()
+                    };
+                    highlighted(&self.items[*i], matched, style, theme)
+                }),
+        );
+
+        let popup = Paragraph::new(Text::from(lines))
+            .block(popup_block)
+            .style(theme.popup());
+
+        // Render the popup in the centered `area`
+        frame.render_widget(Clear, area); // Clears the popup area to avoid overlap
+        frame.render_widget(popup, area);
+    }
+
+    fn get_popup_type(&self) -> PopupType {
+        PopupType::List
+    }
+
+    fn handle_input_action(&mut self, action: InputAction) -> PopupResult {
+        match action {
+            InputAction::MoveCursor(Direction::Up) => {
+                self.move_selection(-1);
+                PopupResult::None
+            }
+            InputAction::MoveCursor(Direction::Down) => {
+                self.move_selection(1);
+                PopupResult::None
+            }
+            InputAction::WriteChar(c) => self.edit_filter(|filter| filter.push(c)),
+            //pasted line breaks can't be part of an entry
+            InputAction::InsertText(text) => {
+                self.edit_filter(|filter| filter.extend(text.chars().filter(|c| !c.is_control())))
+            }
+            InputAction::BACKSPACE => self.edit_filter(|filter| {
+                filter.pop();
+            }),
+            InputAction::ENTER if self.items.is_empty() => PopupResult::Affirmed,
+            InputAction::ENTER => match self.shown.get(self.selected) {
+                Some((i, _)) => PopupResult::Index(*i),
+                None => PopupResult::None,
+            },
+            InputAction::DELETE if self.removable => match self.shown.get(self.selected) {
+                Some((i, _)) => PopupResult::Removed(*i),
+                None => PopupResult::None,
+            },
+            _ => PopupResult::None,
+        }
+    }
+
+    fn key_hints(&self) -> Vec<KeyHint> {
+        let mut hints = popup_hints(&self.get_popup_type());
+        if self.removable {
+            hints.push(KeyHint::new("remove").key(KEYBIND_DELETE, InputAction::DELETE));
+        }
+        hints
+    }
+
+    fn add_items(&mut self, items: Vec<String>) {
+        self.items.extend(items);
+        self.apply_filter();
+    }
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_list_popup_tests {
+    use super::*;
+
+    fn create_list_popup() -> ListPopup {
+        ListPopup::new(
+            "test",
+            vec!["a".to_string(), "b".to_string(), "c".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_move_selection_wraps_around() {
+        let mut popup = create_list_popup();
+        popup.handle_input_action(InputAction::MoveCursor(Direction::Up));
+        assert_eq!(popup.selected, 2);
+        popup.handle_input_action(InputAction::MoveCursor(Direction::Down));
+        assert_eq!(popup.selected, 0);
+    }
+
+    #[test]
+    fn test_enter_returns_selected_index() {
+        let mut popup = create_list_popup();
+        popup.handle_input_action(InputAction::MoveCursor(Direction::Down));
+        assert_eq!(
+            popup.handle_input_action(InputAction::ENTER),
+            PopupResult::Index(1)
+        );
+    }
+
+    #[test]
+    fn test_delete_answers_with_entry_only_if_removable() {
+        let mut popup = create_list_popup();
+        assert_eq!(
+            popup.handle_input_action(InputAction::DELETE),
+            PopupResult::None
+        );
+        let mut popup = create_list_popup().with_remove().with_selected(2);
+        assert_eq!(
+            popup.handle_input_action(InputAction::DELETE),
+            PopupResult::Removed(2)
+        );
+    }
+
+    #[test]
+    fn test_escape_cancels() {
+        let popup = create_list_popup();
+        assert_eq!(popup.dismiss_result(), PopupResult::Cancelled);
+    }
+
+    fn type_filter(popup: &mut ListPopup, text: &str) {
+        for c in text.chars() {
+            popup.handle_input_action(InputAction::WriteChar(c));
+        }
+    }
+
+    fn shown(popup: &ListPopup) -> Vec<&str> {
+        popup
+            .shown
+            .iter()
+            .map(|(i, _)| popup.items[*i].as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_typing_filters_and_ranks_items() {
+        let items = ["src/main.rs", "src/core/app.rs", "README.md"];
+        let mut popup = ListPopup::new("test", items.map(String::from).to_vec()).with_filter();
+        type_filter(&mut popup, "ar");
+        assert_eq!(shown(&popup), vec!["src/core/app.rs", "src/main.rs"]);
+        assert_eq!(popup.shown[0].1, vec![9, 13]);
+
+        //enter answers with the index in the unfiltered items
+        assert_eq!(
+            popup.handle_input_action(InputAction::ENTER),
+            PopupResult::Index(1)
+        );
+        popup.handle_input_action(InputAction::BACKSPACE);
+        popup.handle_input_action(InputAction::BACKSPACE);
+        assert_eq!(shown(&popup), items.to_vec());
+    }
+
+    #[test]
+    fn test_typing_without_filter_changes_nothing() {
+        let mut popup = create_list_popup();
+        type_filter(&mut popup, "c");
+        assert_eq!(popup.filter, None);
+        assert_eq!(shown(&popup), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_no_match_can_not_be_entered() {
+        let mut popup = create_list_popup().with_filter();
+        type_filter(&mut popup, "x");
+        assert_eq!(shown(&popup), Vec::<&str>::new());
+        assert_eq!(
+            popup.handle_input_action(InputAction::ENTER),
+            PopupResult::None
+        );
+    }
+
+    //items found later are filtered like the ones there from the start
+    #[test]
+    fn test_added_items_are_filtered() {
+        let mut popup = ListPopup::new("test", vec![]).with_filter();
+        type_filter(&mut popup, "b");
+        popup.add_items(vec!["a".to_string(), "b".to_string()]);
+        popup.add_items(vec!["ab".to_string()]);
+        assert_eq!(shown(&popup), vec!["b", "ab"]);
+    }
+
+    #[test]
+    fn test_matched_chars_are_highlighted() {
+        use ratatui::backend::TestBackend;
+        use ratatui::style::Modifier;
+        use ratatui::Terminal;
+
+        let items = vec!["src/main.rs".to_string(), "src/app.rs".to_string()];
+        let mut popup = ListPopup::new("test", items).with_filter();
+        type_filter(&mut popup, "mn");
+        let mut terminal = Terminal::new(TestBackend::new(20, 5)).unwrap();
+        for theme in [Theme::Color, Theme::Mono] {
+            terminal
+                .draw(|frame| popup.render(frame, frame.area(), theme))
+                .unwrap();
+            let buffer = terminal.backend().buffer();
+            //inside the border, below the typed filter
+            let underlined = |x: u16| buffer[(x + 1, 2)].modifier.contains(Modifier::UNDERLINED);
+            assert_eq!(buffer[(1, 1)].symbol(), ">");
+            assert_eq!(buffer[(5, 2)].symbol(), "m");
+            assert!(underlined(4) && underlined(7));
+            assert!(!underlined(3) && !underlined(5) && !underlined(6));
+        }
+    }
+
+    #[test]
+    fn test_enter_on_empty_list_is_affirmed() {
+        let mut popup = ListPopup::new("test", vec![]);
+        assert_eq!(
+            popup.handle_input_action(InputAction::ENTER),
+            PopupResult::Affirmed
+        );
+    }
+}
@@ -1,3 +1,6 @@
 pub mod confirmation_popup;
+pub mod diff_popup;
 pub mod error_popup;
+pub mod input_popup;
+pub mod list_popup;
 pub mod popup;
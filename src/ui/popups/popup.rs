@@ -1,14 +1,32 @@
 use crate::input::input_action::InputAction;
+use crate::ui::hints::{popup_hints, KeyHint};
+use crate::ui::theme::Theme;
 use ratatui::layout::Rect;
 use ratatui::Frame;
 use std::fmt::{Debug, Formatter};
 
 pub trait Popup {
-    fn render(&self, frame: &mut Frame, area: Rect);
+    ///draws the popup into area, styled by theme
+    fn render(&self, frame: &mut Frame, area: Rect, theme: Theme);
     fn get_popup_type(&self) -> PopupType;
     ///function to handle input action on popup,
     /// responsible for dispatching action to correct internal method.
     fn handle_input_action(&mut self, action: InputAction) -> PopupResult;
+    ///what esc answers, the same for every popup of a type. each response handler reads it
+    /// as dismissed without action, errors are acknowledged and everything else cancelled.
+    fn dismiss_result(&self) -> PopupResult {
+        match self.get_popup_type() {
+            PopupType::Error => PopupResult::Affirmed,
+            _ => PopupResult::Cancelled,
+        }
+    }
+    ///adds entries found after the popup opened, like files of a walk still running.
+    /// popups without entries ignore them
+    fn add_items(&mut self, _items: Vec<String>) {}
+    ///keys shown in the hint line while the popup is open, those of its type by default
+    fn key_hints(&self) -> Vec<KeyHint> {
+        popup_hints(&self.get_popup_type())
+    }
 }
 
 impl Debug for dyn Popup {
@@ -22,12 +40,21 @@ pub enum PopupResult {
     None,
     Bool(bool),
     String(String),
+    Index(usize),
+    //the entry at the index is to be removed, from lists that allow it
+    Removed(usize),
     Affirmed,
+    Cancelled,
 }
 
+#[derive(Debug)]
 pub enum PopupType {
     None,
     Confirmation,
     Warning,
     Error,
+    List,
+    Input,
+    //read only text, closed with enter or esc
+    Text,
 }
@@ -0,0 +1,334 @@
+//the styles everything on screen is drawn with. besides the colored theme there is a
+// monochrome one for terminals with few colors or NO_COLOR set, using only bold, underline
+// and reverse, so nothing hides as dim text on a dark background.
+
+use ratatui::style::{Color, Modifier, Style};
+
+///terminal names that can't show colors, terminfo's -m and -mono variants included
+const MONO_TERMS: &[&str] = &["dumb", "vt52", "vt100", "vt102", "vt220"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Theme {
+    Color,
+    Mono,
+}
+
+impl Theme {
+    ///the colored theme, or the monochrome one if color is off
+    pub fn new(color: bool) -> Self {
+        if color {
+            Theme::Color
+        } else {
+            Theme::Mono
+        }
+    }
+
+    fn pick(self, color: Style, mono: Style) -> Style {
+        match self {
+            Theme::Color => color,
+            Theme::Mono => mono,
+        }
+    }
+
+    //INFO BAR
+    pub fn info_bar(self) -> Style {
+        self.pick(
+            Style::default().fg(Color::LightCyan).bg(Color::White),
+            reversed(),
+        )
+    }
+
+    ///editing modes in front of the info bar
+    pub fn info_mode(self) -> Style {
+        self.pick(Style::default().fg(Color::Red), bold())
+    }
+
+    pub fn info_file(self) -> Style {
+        self.pick(Style::default().fg(Color::LightCyan), Style::default())
+    }
+
+    ///position, encoding and word count
+    pub fn info_detail(self) -> Style {
+        self.pick(Style::default().fg(Color::Magenta), Style::default())
+    }
+
+    ///text telling something is wrong, like the terminal being too small
+    pub fn warning(self) -> Style {
+        self.pick(Style::default().fg(Color::Red), bold())
+    }
+
+    //EDITOR
+    pub fn text(self) -> Style {
+        self.pick(Style::default().fg(Color::White), Style::default())
+    }
+
+    ///selected text, hex search matches included
+    pub fn selection(self) -> Style {
+        self.pick(
+            Style::default()
+                .bg(Color::White)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+            reversed().add_modifier(Modifier::BOLD),
+        )
+    }
+
+    ///the highlighted row of a list, like the recent file picked on the welcome screen
+    pub fn highlighted(self) -> Style {
+        self.pick(
+            Style::default().bg(Color::White).fg(Color::Black),
+            reversed(),
+        )
+    }
+
+    pub fn title(self) -> Style {
+        self.pick(
+            Style::default()
+                .fg(Color::LightCyan)
+                .add_modifier(Modifier::BOLD),
+            bold(),
+        )
+    }
+
+    ///keys named in hints, like on the welcome screen
+    pub fn key(self) -> Style {
+        self.pick(Style::default().fg(Color::Magenta), underlined())
+    }
+
+    pub fn control_char(self) -> Style {
+        self.pick(
+            Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::DIM),
+            bold(),
+        )
+    }
+
+    pub fn annotation(self) -> Style {
+        self.pick(
+            Style::default()
+                .fg(Color::LightYellow)
+                .add_modifier(Modifier::BOLD),
+            bold(),
+        )
+    }
+
+    pub fn matched_bracket(self) -> Style {
+        self.pick(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+            bold().add_modifier(Modifier::UNDERLINED),
+        )
+    }
+
+    pub fn unmatched_bracket(self) -> Style {
+        self.pick(
+            Style::default().fg(Color::Black).bg(Color::LightRed),
+            reversed(),
+        )
+    }
+
+    pub fn misspelled(self) -> Style {
+        self.pick(
+            Style::default()
+                .fg(Color::LightRed)
+                .add_modifier(Modifier::UNDERLINED),
+            underlined(),
+        )
+    }
+
+    //GUTTER
+    pub fn gutter(self) -> Style {
+        self.pick(
+            Style::default().bg(Color::DarkGray).fg(Color::White),
+            Style::default(),
+        )
+    }
+
+    pub fn gutter_current_line(self) -> Style {
+        self.pick(
+            Style::default().bg(Color::White).fg(Color::Black),
+            reversed(),
+        )
+    }
+
+    pub fn bookmark(self) -> Style {
+        self.pick(Style::default().fg(Color::Yellow), bold())
+    }
+
+    ///marker of lines running past the editor's right edge
+    pub fn overflow_marker(self) -> Style {
+        self.pick(Style::default().fg(Color::Cyan), bold())
+    }
+
+    //COMMAND LINE
+    pub fn command_line(self) -> Style {
+        self.pick(
+            Style::default().fg(Color::White).bg(Color::Cyan),
+            reversed(),
+        )
+    }
+
+    ///key hints over the end of the command line, plain where the command line is reversed
+    pub fn hint_bar(self) -> Style {
+        self.pick(
+            Style::default().fg(Color::DarkGray).bg(Color::Cyan),
+            Style::default(),
+        )
+    }
+
+    pub fn completion_list(self) -> Style {
+        self.pick(
+            Style::default().fg(Color::White).bg(Color::DarkGray),
+            reversed(),
+        )
+    }
+
+    //POPUPS
+    pub fn popup(self) -> Style {
+        self.pick(
+            Style::default().fg(Color::White).bg(Color::Black),
+            Style::default(),
+        )
+    }
+
+    ///the chosen option or row of a popup
+    pub fn popup_selected(self) -> Style {
+        self.highlighted()
+    }
+
+    pub fn popup_unselected(self) -> Style {
+        self.pick(
+            Style::default().bg(Color::Black).fg(Color::White),
+            Style::default(),
+        )
+    }
+
+    ///less important text, like list entries that no longer exist. monochrome has no dim,
+    /// so it is plain there
+    pub fn dimmed(self) -> Style {
+        self.pick(Style::default().fg(Color::DarkGray), Style::default())
+    }
+
+    ///chars of a list entry matched by what was typed to filter the list
+    pub fn matched(self) -> Style {
+        let style = Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+        self.pick(style.fg(Color::Yellow), style)
+    }
+
+    ///input offered to be replaced by typing
+    pub fn suggestion(self) -> Style {
+        self.pick(Style::default().fg(Color::DarkGray), underlined())
+    }
+
+    pub fn diff_header(self) -> Style {
+        bold()
+    }
+
+    pub fn diff_added(self) -> Style {
+        self.pick(Style::default().fg(Color::Green), bold())
+    }
+
+    pub fn diff_removed(self) -> Style {
+        self.pick(Style::default().fg(Color::Red), underlined())
+    }
+
+    pub fn diff_hunk(self) -> Style {
+        self.pick(Style::default().fg(Color::Cyan), reversed())
+    }
+}
+
+fn bold() -> Style {
+    Style::default().add_modifier(Modifier::BOLD)
+}
+
+fn underlined() -> Style {
+    Style::default().add_modifier(Modifier::UNDERLINED)
+}
+
+fn reversed() -> Style {
+    Style::default().add_modifier(Modifier::REVERSED)
+}
+
+///checks if the terminal the editor runs in should be drawn in color, from the environment
+pub fn terminal_has_colors() -> bool {
+    let no_color = std::env::var("NO_COLOR").ok();
+    let term = std::env::var("TERM").ok();
+    has_colors(no_color.as_deref(), term.as_deref())
+}
+
+///checks if colors are wanted given the NO_COLOR and TERM variables. any non empty
+/// NO_COLOR turns them off, as does a terminal known to have none
+fn has_colors(no_color: Option<&str>, term: Option<&str>) -> bool {
+    if no_color.is_some_and(|value| !value.is_empty()) {
+        return false;
+    }
+    let Some(term) = term else {
+        return true;
+    };
+    let base = term
+        .strip_suffix("-mono")
+        .or_else(|| term.strip_suffix("-m"));
+    base.is_none() && !MONO_TERMS.contains(&term)
+}
+
+//████████╗███████╗███████╗████████╗███████╗
+//╚══██╔══╝██╔════╝██╔════╝╚══██╔══╝██╔════╝
+//   ██║   █████╗  ███████╗   ██║   ███████╗
+//   ██║   ██╔══╝  ╚════██║   ██║   ╚════██║
+//   ██║   ███████╗███████║   ██║   ███████║
+//   ╚═╝   ╚══════╝╚══════╝   ╚═╝   ╚══════╝
+#[cfg(test)]
+mod unit_theme_tests {
+    use super::*;
+
+    #[test]
+    fn test_no_color_turns_colors_off_unless_empty() {
+        assert!(!has_colors(Some("1"), Some("xterm-256color")));
+        assert!(has_colors(Some(""), Some("xterm-256color")));
+        assert!(has_colors(None, Some("xterm-256color")));
+        assert!(has_colors(None, None));
+    }
+
+    #[test]
+    fn test_terminals_without_colors_are_monochrome() {
+        assert!(!has_colors(None, Some("dumb")));
+        assert!(!has_colors(None, Some("vt100")));
+        assert!(!has_colors(None, Some("xterm-mono")));
+        assert!(!has_colors(None, Some("screen-m")));
+        assert!(has_colors(None, Some("screen")));
+    }
+
+    //every monochrome style is plain or made of the attributes any terminal has
+    #[test]
+    fn test_monochrome_styles_have_no_colors() {
+        let theme = Theme::Mono;
+        let styles = [
+            theme.info_bar(),
+            theme.selection(),
+            theme.highlighted(),
+            theme.control_char(),
+            theme.matched_bracket(),
+            theme.unmatched_bracket(),
+            theme.gutter(),
+            theme.gutter_current_line(),
+            theme.command_line(),
+            theme.hint_bar(),
+            theme.completion_list(),
+            theme.popup(),
+            theme.popup_selected(),
+            theme.dimmed(),
+            theme.matched(),
+            theme.suggestion(),
+            theme.diff_added(),
+            theme.diff_removed(),
+            theme.diff_hunk(),
+        ];
+        let allowed = Modifier::BOLD | Modifier::UNDERLINED | Modifier::REVERSED;
+        for style in styles {
+            assert_eq!((style.fg, style.bg), (None, None), "{:?}", style);
+            assert!(allowed.contains(style.add_modifier), "{:?}", style);
+        }
+    }
+}
@@ -1,10 +1,16 @@
 use crate::config::editor_settings;
 use crate::core::app::{ActiveArea, App};
-use crate::core::cursor::CursorPosition;
+use crate::core::editor::bookmarks::Bookmarks;
+use crate::core::editor::text_objects::BracketMatch;
+use crate::core::editor::{annotations, line_index};
+use crate::core::position::Range;
+use crate::core::session;
+use crate::ui::hints::hint_line;
+use crate::ui::theme::Theme;
 use ratatui::layout::{Alignment, Position, Rect};
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span, Text};
-use ratatui::widgets::Paragraph;
+use ratatui::widgets::{Clear, Paragraph, Wrap};
 use ratatui::{
     layout::{Constraint, Direction, Layout},
     widgets::Block,
@@ -14,87 +20,162 @@ use std::default::Default;
 use std::vec;
 
 pub fn ui(frame: &mut Frame, app: &mut App) {
-    app.terminal_height = frame.area().height as i16;
+    let area = frame.area();
+    let theme = Theme::new(app.settings.color);
+    app.terminal_height = area.height as usize;
+    if area.width < editor_settings::MIN_TERMINAL_WIDTH
+        || area.height < editor_settings::MIN_TERMINAL_HEIGHT
+    {
+        frame.render_widget(too_small(theme), area);
+        return;
+    }
+    //chrome is left out as space runs out, the info bar first and then the gutter
+    let info_bar_height = u16::from(area.height >= editor_settings::INFO_BAR_MIN_HEIGHT);
+    let gutter_width = if area.width >= editor_settings::GUTTER_MIN_WIDTH {
+        3
+    } else {
+        0
+    };
 
     let layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints(vec![
-            Constraint::Length(1),
-            Constraint::Percentage(95),
+            Constraint::Length(info_bar_height),
+            Constraint::Min(0),
             Constraint::Length(1),
         ])
-        .split(frame.area());
+        .split(area);
     app.editor.editor_height = layout[1].height;
 
     let editor_layout = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints(vec![Constraint::Length(3), Constraint::Percentage(100)])
+        .constraints(vec![
+            Constraint::Length(gutter_width),
+            Constraint::Percentage(100),
+        ])
         .split(layout[1]);
 
-    app.editor.editor_width = editor_layout[1].width as i16;
-
-    let editor_content: Text = handle_editor_content(
-        app.editor.editor_content.clone(),
-        app.editor.text_selection_start,
-        app.editor.text_selection_end,
-        editor_layout[1].width as usize,
-        app,
-    );
+    app.editor.editor_width = editor_layout[1].width as usize;
 
-    let command_input: String = app.command_line.input.to_string();
+    //status messages use the command line while it isn't being typed in, typed input
+    // scrolls sideways to keep the cursor in view
+    let command_width = area.width as usize;
+    app.command_line.scroll_to_cursor(command_width);
+    let (command_input, command_cursor_x) = match app.status() {
+        Some(status) if app.active_area != ActiveArea::CommandLine => (status.to_string(), 0),
+        _ => app.command_line.visible(command_width),
+    };
     let file_name_optional: Option<String> = app.file_path.clone();
-    let file_to_use = if let Some(file) = file_name_optional {
+    let mut file_to_use = if let Some(file) = file_name_optional {
         file
     } else {
         "untitled".to_string()
     };
+    if app.has_unsaved_changes() {
+        file_to_use.push_str(" [+]");
+    }
 
     //render widgets : infobar, editor side, editor and command line
     frame.render_widget(
         info_bar(
             file_to_use,
-            app.editor.cursor.x,
-            app.editor.cursor.y,
-            app.editor.visual_cursor_x,
-            app.editor.text_selection_start,
-            app.editor.text_selection_end,
+            position_status(app),
+            app.file_format.encoding.name(),
+            mode_status(app),
+            word_count_status(app),
+            theme,
         ),
         layout[0],
     );
+    if app.bell.is_flashing() {
+        let inverted = Style::default().add_modifier(Modifier::REVERSED);
+        frame.buffer_mut().set_style(layout[0], inverted);
+    }
+
+    //only the lines inside the viewport are built, so frame time doesn't grow with the file
+    let first_row = app.editor.scroll_offset;
+    let rows = editor_layout[1].height as usize;
+    let editor_width = editor_layout[1].width as usize;
+    //the hex view's dump has no brackets worth pairing, and pairing them would scan
+    // through the whole of any long line in view
+    let long_visible = app.settings.long_line_limit().is_some_and(|limit| {
+        let mut visible = app.editor.editor_content.iter().skip(first_row).take(rows);
+        visible.any(|line| line.len() > limit)
+    });
+    let brackets = match app.hex_view {
+        Some(_) => None,
+        None if long_visible => None,
+        None => app.editor.bracket_match(first_row..first_row + rows),
+    };
+    let editor_content: Text =
+        handle_editor_content(app, first_row, rows, editor_width, brackets, theme);
     frame.render_widget(
         editor_side_line(
-            editor_content.to_owned(),
-            app.editor.scroll_offset as u16,
-            editor_layout[1].width as usize,
+            &editor_content,
+            first_row,
+            editor_width,
             app.editor.cursor.y,
+            &app.editor.bookmarks,
+            theme,
         ),
         editor_layout[0],
     );
-    frame.render_widget(
-        editor(editor_content, app.editor.scroll_offset as u16),
-        editor_layout[1],
-    );
-    frame.render_widget(command_line(command_input), layout[2]);
+    if app.shows_welcome() {
+        frame.render_widget(welcome(app, theme), editor_layout[1]);
+    } else {
+        frame.render_widget(editor(editor_content, theme), editor_layout[1]);
+    }
+    let command_input_width = command_input.chars().count();
+    frame.render_widget(command_line(command_input, theme), layout[2]);
+
+    //key hints fill the end of the bottom row when they fit next to what it shows
+    if app.settings.hints {
+        let hints = hint_line(app);
+        let hints_width = hints.chars().count();
+        if !hints.is_empty() && command_input_width + hints_width + 2 <= command_width {
+            let area = Rect {
+                x: layout[2].right() - hints_width as u16,
+                width: hints_width as u16,
+                ..layout[2]
+            };
+            frame.render_widget(hint_bar(hints, theme), area);
+        }
+    }
+
+    //candidates of an ambiguous tab completion are listed above the command line
+    if app.active_area == ActiveArea::CommandLine && !app.command_line.completions.is_empty() {
+        let rows = completion_rows(&app.command_line.completions, layout[2].width as usize);
+        let height = (rows.len() as u16).min(layout[2].y);
+        let area = Rect {
+            y: layout[2].y - height,
+            height,
+            ..layout[2]
+        };
+        frame.render_widget(Clear, area);
+        frame.render_widget(completion_list(rows, theme), area);
+    }
 
     //if popup is any, then render it
     if let Some(popup) = &app.popup {
         let popup_area = centered_rect(60, 20, frame.area());
-        popup.render(frame, popup_area);
+        popup.render(frame, popup_area, theme);
     }
 
     //set cursor with position if it should be visiblie (determined by app logic)
     if app.cursor_visible {
         match app.active_area {
+            //a view scrolled away from the cursor shows no cursor
+            ActiveArea::Editor if !app.editor.is_cursor_in_view() => {}
             ActiveArea::Editor => {
                 let x = editor_layout[1].x + app.editor.visual_cursor_x as u16; //using visual x
                 let y = editor_layout[1].y
-                    + (app.editor.cursor.y - app.editor.scroll_offset).clamp(0, i16::MAX) as u16;
+                    + app.editor.cursor.y.saturating_sub(app.editor.scroll_offset) as u16;
                 let pos: Position = Position { x, y };
 
                 frame.set_cursor_position(pos);
             }
             ActiveArea::CommandLine => {
-                let x = layout[2].x + app.command_line.cursor.x as u16;
+                let x = layout[2].x + command_cursor_x as u16;
                 let y = layout[2].y + app.command_line.cursor.y as u16;
                 let pos: Position = Position { x, y };
                 frame.set_cursor_position(pos);
@@ -104,8 +185,10 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
     }
 }
 
-///returns centered rect based on height,width and current screen Rect to use in layout
+///returns centered rect based on height,width and current screen Rect to use in layout,
+/// shrunk to the screen if it is smaller
 fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let (width, height) = (width.min(area.width), height.min(area.height));
     let x = (area.width - width) / 2;
     let y = (area.height - height) / 2;
     Rect {
@@ -116,104 +199,211 @@ fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     }
 }
 
+///status of editing modes shown in front of the info bar, like the modal mode and macro recording
+fn mode_status(app: &App) -> String {
+    let mut status = String::new();
+    if app.hex_view.is_some() {
+        status.push_str("-- HEX -- ");
+    }
+    if app.follow.is_some() {
+        let paused = if app.is_follow_pinned() {
+            ""
+        } else {
+            " (paused)"
+        };
+        status.push_str(&format!("-- FOLLOW{} -- ", paused));
+    }
+    if app.settings.modal {
+        status.push_str(&format!("-- {} -- ", app.modal.mode.label()));
+    }
+    if let Some(register) = app.macros.recording_register() {
+        status.push_str(&format!("recording @{} ", register));
+    }
+    if !app.editor.is_cursor_in_view() {
+        status.push_str("cursor off-screen ");
+    }
+    if let Some(pending) = app.pending_summary() {
+        status.push_str(&format!("{} ", pending));
+    }
+    status
+}
+
+///cursor line and column, or how much is selected while there is a selection. the column
+/// is where the cursor is drawn, with the char index after it where tabs make them differ.
+fn position_status(app: &App) -> String {
+    if let Some((lines, chars)) = app.editor.selection_size() {
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        return format!(
+            "({} line{}, {} char{} selected)",
+            lines,
+            plural(lines),
+            chars,
+            plural(chars)
+        );
+    }
+    let (col, visual_col) = (app.editor.cursor.x + 1, app.editor.visual_cursor_x + 1);
+    let mut status = format!("Ln {}, Col {}", app.editor.cursor.y + 1, visual_col);
+    if col != visual_col {
+        status.push_str(&format!(" ({})", col));
+    }
+    status
+}
+
+///live word count shown at the end of the info bar, if enabled with :set wordcount on
+fn word_count_status(app: &mut App) -> String {
+    if !app.settings.word_count {
+        return String::new();
+    }
+    let words = match app.editor.selection_stats() {
+        Some(stats) => format!("{} of {}", stats.words, app.editor.content_stats().words),
+        None => app.editor.content_stats().words.to_string(),
+    };
+    format!("   Words: {}", words)
+}
+
 //COMPONENTS
 fn info_bar<'a>(
     file_name: String,
-    cursor_x: i16,
-    cursor_y: i16,
-    visual_x: i16,
-    selection_start: Option<CursorPosition>,
-    selection_end: Option<CursorPosition>,
+    position_status: String,
+    encoding: &'static str,
+    mode_status: String,
+    word_count_status: String,
+    theme: Theme,
 ) -> Paragraph<'a> {
-    let mut start_x: usize = 0;
-    let mut start_y: usize = 0;
-    let mut end_x: usize = 0;
-    let mut end_y: usize = 0;
-    if selection_start.is_some() && selection_end.is_some() {
-        start_x = selection_start.unwrap().x;
-        start_y = selection_start.unwrap().y;
-        end_x = selection_end.unwrap().x;
-        end_y = selection_end.unwrap().y;
-    }
     let line = Line::from(vec![
-        Span::styled(file_name, Style::default().fg(Color::LightCyan)),
+        Span::styled(mode_status, theme.info_mode()),
+        Span::styled(file_name, theme.info_file()),
         Span::raw(" - "), // Separator
-        Span::styled(
-            format!(
-                "Cursor: ({}, {})   Visual X Cursor ({})  Selection Cursor ({},{}) ({},{})",
-                cursor_x, cursor_y, visual_x, start_x, start_y, end_x, end_y
-            ),
-            Style::default().fg(Color::Magenta),
-        ),
+        Span::styled(position_status, theme.info_detail()),
+        Span::raw(" - "),
+        Span::styled(encoding, theme.info_detail()),
+        Span::styled(word_count_status, theme.info_detail()),
     ]);
     Paragraph::new("").block(
         Block::default()
             .title(line)
             .title_alignment(Alignment::Center)
-            .style(Style::default().fg(Color::LightCyan).bg(Color::White)),
+            .style(theme.info_bar()),
     )
 }
 
+///shown instead of the editor on terminals below the minimum size
+fn too_small<'a>(theme: Theme) -> Paragraph<'a> {
+    Paragraph::new(format!(
+        "terminal too small (need {}x{})",
+        editor_settings::MIN_TERMINAL_WIDTH,
+        editor_settings::MIN_TERMINAL_HEIGHT
+    ))
+    .wrap(Wrap { trim: true })
+    .style(theme.warning())
+}
+
+///welcome screen shown in place of an untitled, untouched buffer: the version, a few key
+/// hints and the recent files, the highlighted one opened with enter
+fn welcome<'a>(app: &App, theme: Theme) -> Paragraph<'a> {
+    let title_style = theme.title();
+    let key_style = theme.key();
+    let hint = |key: &str, what: &str| {
+        Line::from(vec![
+            Span::styled(format!("  {:<14}", key), key_style),
+            Span::raw(what.to_string()),
+        ])
+    };
+    let mut lines = vec![
+        Line::styled(
+            format!("calliglyph {}", env!("CARGO_PKG_VERSION")),
+            title_style,
+        ),
+        Line::raw(""),
+        hint(":open <file>", "open a file"),
+        hint(":recent", "pick a recently used file"),
+        hint("ctrl+s", "save"),
+        hint("ctrl+q", "quit"),
+        Line::raw(""),
+        Line::raw("start typing to write in a new buffer"),
+    ];
+
+    let recent = app.welcome_recent_files();
+    if !recent.is_empty() {
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(
+            "recent files, ↑/↓ and enter to open",
+            title_style,
+        ));
+        let home = std::env::var("HOME").ok();
+        let highlighted = theme.highlighted();
+        for (i, path) in recent.iter().enumerate() {
+            let path =
+                session::shorten_path(path, home.as_deref(), editor_settings::RECENT_PATH_WIDTH);
+            if i == app.welcome_selected {
+                lines.push(Line::styled(format!("> {}", path), highlighted));
+            } else {
+                lines.push(Line::raw(format!("  {}", path)));
+            }
+        }
+    }
+    Paragraph::new(lines).style(theme.text())
+}
+
 ///generates a side bar for line nr display as well as displaying line overflow if existing
-fn editor_side_line(
-    editor_content: Text,
-    scroll_offset: u16,
+fn editor_side_line<'a>(
+    editor_content: &Text,
+    first_row: usize,
     editor_width: usize,
-    cursor_y: i16,
-) -> Paragraph {
+    cursor_y: usize,
+    bookmarks: &Bookmarks,
+    theme: Theme,
+) -> Paragraph<'a> {
     let mut line_nrs: Text = Text::from(vec![]);
 
-    let overflow_marker_style = Style::default().fg(Color::Cyan);
-    let current_line_style = Style::default().bg(Color::White).fg(Color::Black);
+    let overflow_marker_style = theme.overflow_marker();
+    let current_line_style = theme.gutter_current_line();
+    let bookmark_style = theme.bookmark();
 
-    for (nr, s) in editor_content.iter().enumerate() {
-        let dest_to_cursor_y = cursor_y.abs_diff(nr as i16);
+    for (i, s) in editor_content.iter().enumerate() {
+        let nr = first_row + i;
+        let dest_to_cursor_y = cursor_y.abs_diff(nr);
 
-        if s.width() >= editor_width {
-            let line = Line::from(vec![
-                Span::raw(dest_to_cursor_y.to_string()),
-                Span::styled(">", overflow_marker_style),
-            ]);
-            //if is zero (current line), display actual line nr
-            if dest_to_cursor_y == 0 {
-                let line = Line::from(vec![
-                    Span::styled(dest_to_cursor_y.to_string(), current_line_style),
-                    Span::styled(">", overflow_marker_style),
-                ]);
-                line_nrs.push_line(line);
-            } else {
-                line_nrs.push_line(line);
-            }
-        } else if dest_to_cursor_y == 0 {
-            let line = Line::from(vec![Span::styled(nr.to_string(), current_line_style)]);
-            line_nrs.push_line(line);
+        let mut spans = vec![];
+        if bookmarks.contains(nr) {
+            spans.push(Span::styled(
+                editor_settings::BOOKMARK_MARKER,
+                bookmark_style,
+            ));
+        }
+        //if is zero (current line), display actual line nr
+        if dest_to_cursor_y == 0 {
+            spans.push(Span::styled(nr.to_string(), current_line_style));
         } else {
-            line_nrs.push_line(dest_to_cursor_y.to_string());
+            spans.push(Span::raw(dest_to_cursor_y.to_string()));
         }
+        if s.width() >= editor_width {
+            spans.push(Span::styled(">", overflow_marker_style));
+        }
+        line_nrs.push_line(Line::from(spans));
     }
 
-    Paragraph::new(line_nrs)
-        .style(Style::default().bg(Color::DarkGray).fg(Color::White))
-        .block(
-            Block::default(), //.borders(Borders::LEFT | Borders::RIGHT)
-                              //.border_type(BorderType::Rounded)
-        )
-        .scroll((scroll_offset, 0))
+    Paragraph::new(line_nrs).style(theme.gutter()).block(
+        Block::default(), //.borders(Borders::LEFT | Borders::RIGHT)
+                          //.border_type(BorderType::Rounded)
+    )
 }
 
-fn editor(editor_content: Text, scroll_offset: u16) -> Paragraph {
-    Paragraph::new(editor_content)
-        .style(Style::default().fg(Color::White))
-        .block(
-            Block::default(), //.borders(Borders::LEFT | Borders::RIGHT)
-                              //.border_type(BorderType::Rounded)
-        )
-        .scroll((scroll_offset, 0))
+fn editor(editor_content: Text, theme: Theme) -> Paragraph {
+    Paragraph::new(editor_content).style(theme.text()).block(
+        Block::default(), //.borders(Borders::LEFT | Borders::RIGHT)
+                          //.border_type(BorderType::Rounded)
+    )
+}
+
+///key hints of the active area, drawn over the end of the command line row
+fn hint_bar<'a>(hints: String, theme: Theme) -> Paragraph<'a> {
+    Paragraph::new(hints).style(theme.hint_bar())
 }
 
-fn command_line<'a>(command_input: String) -> Paragraph<'a> {
+fn command_line<'a>(command_input: String, theme: Theme) -> Paragraph<'a> {
     Paragraph::new(command_input)
-        .style(Style::default().fg(Color::White).bg(Color::Cyan))
+        .style(theme.command_line())
         .block(
             Block::default(), //.borders(Borders::ALL)
                               //.title("")
@@ -221,113 +411,232 @@ fn command_line<'a>(command_input: String) -> Paragraph<'a> {
         )
 }
 
+fn completion_list<'a>(rows: Vec<String>, theme: Theme) -> Paragraph<'a> {
+    Paragraph::new(rows.into_iter().map(Line::from).collect::<Vec<_>>())
+        .style(theme.completion_list())
+}
+
 //HELPER FUNCTIONS
 
+///candidates packed into rows of at most width chars, two spaces apart. rows beyond
+/// the completion rows setting are dropped.
+fn completion_rows(candidates: &[String], width: usize) -> Vec<String> {
+    let mut rows: Vec<String> = vec![];
+    for candidate in candidates {
+        match rows.last_mut() {
+            Some(row) if row.chars().count() + 2 + candidate.chars().count() <= width => {
+                row.push_str("  ");
+                row.push_str(candidate);
+            }
+            _ => rows.push(candidate.clone()),
+        }
+    }
+    rows.truncate(editor_settings::COMPLETION_ROWS_MAX);
+    rows
+}
+
 ///manipulates how the editor content is rendered, specifically how certain characters in the
-/// content is interpreted visually
-fn handle_editor_content<'a>(
-    vec: Vec<String>,
-    selection_start: Option<CursorPosition>,
-    selection_end: Option<CursorPosition>,
+/// content is interpreted visually. only the rows from first_row on that fit the viewport
+/// are built, and only the part of each line that fits the editor width
+fn handle_editor_content(
+    app: &App,
+    first_row: usize,
+    rows: usize,
     editor_width: usize,
-    app: &mut App,
-) -> Text<'a> {
-    let editor_vec: Vec<String> = vec.into_iter().map(handle_tab_rendering).collect();
+    brackets: Option<BracketMatch>,
+    theme: Theme,
+) -> Text<'_> {
+    let editor = &app.editor;
+    let visible = editor
+        .editor_content
+        .iter()
+        .enumerate()
+        .skip(first_row)
+        .take(rows);
+
+    let tab_width = editor_settings::TAB_WIDTH as usize;
+    let long_line = |line: &str| {
+        let limit = app.settings.long_line_limit();
+        limit.is_some_and(|limit| line.len() > limit)
+    };
+    if let Some(selection) = editor.selection {
+        let lines: Vec<(&str, Option<usize>)> = visible
+            .map(|(i, s)| {
+                //long lines are cut to their visible slice like when nothing is selected
+                let shown_from = long_line(s).then(|| scroll_start(app, i, editor_width));
+                (s.as_str(), shown_from)
+            })
+            .collect();
+        let selected = theme.selection();
+        return highlight_text(
+            &lines,
+            first_row,
+            selection,
+            editor_width,
+            tab_width,
+            selected,
+        );
+    }
 
+    //only the visible lines are checked for annotations and spelling, each frame. the hex
+    // view has neither.
+    let spell = app.active_spell_checker();
+    let keywords: &[String] = match app.hex_view {
+        Some(_) => &[],
+        None => &app.settings.annotation_keywords,
+    };
     let mut editor_text: Text = Text::default();
+    for (i, s) in visible {
+        let start = scroll_start(app, i, editor_width);
+        let shown = line_index::visible_slice(s, start, editor_width, tab_width);
+        //long lines are shown as they are, finding styles in them would walk the whole line
+        if long_line(s) {
+            editor_text.push_line(Line::from(shown));
+            continue;
+        }
 
-    if selection_start.is_some() {
-        editor_text = highlight_text(editor_vec.clone(), selection_start, selection_end);
-    } else {
-        for (i, s) in editor_vec.into_iter().enumerate() {
-            let visual_x = app.editor.visual_cursor_x;
-
-            // Only scroll the line the cursor is on
-            let line: Line = if i == app.editor.cursor.y as usize && visual_x > editor_width as i16
-            {
-                let start_idx = (visual_x - editor_width as i16).max(0) as usize;
-                Line::from(
-                    get_copy_of_editor_content_at_line_between_cursor_editor_width(s, start_idx),
-                )
-            } else {
-                Line::from(s)
-            };
-
-            editor_text.push_line(line);
+        //styled char columns of the line, annotations first so they win where both apply
+        let mut styled: Vec<(std::ops::Range<usize>, Style)> =
+            annotations::find_in_line(s, keywords, &app.settings.word_chars)
+                .into_iter()
+                .map(|range| (range, theme.annotation()))
+                .collect();
+        if let Some(brackets) = brackets {
+            styled.extend(bracket_styles(brackets, i, theme));
         }
+        styled.extend(control_char_styles(s, theme));
+        if let Some(spell) = spell {
+            styled.extend(
+                spell
+                    .misspelled(s)
+                    .into_iter()
+                    .map(|range| (range, theme.misspelled())),
+            );
+        }
+        if styled.is_empty() {
+            editor_text.push_line(Line::from(shown));
+            continue;
+        }
+        styled.sort_by_key(|(range, _)| range.start);
+        //as char indexes into the shown part of the line
+        let visual = |col| line_index::visual_column(s, col, tab_width).saturating_sub(start);
+        let styled = styled
+            .into_iter()
+            .map(|(range, style)| (visual(range.start)..visual(range.end), style));
+        editor_text.push_line(style_ranges(&shown, styled));
     }
-
     editor_text
 }
 
-///gets a copy of the text content at specific line and range of editor content
-pub(crate) fn get_copy_of_editor_content_at_line_between_cursor_editor_width(
-    s: String,
-    start: usize,
-) -> String {
-    let mut line_chars_vec: Vec<char> = s.chars().collect();
-    line_chars_vec.drain(start..).collect()
+///visual column line i is shown from, only the line the cursor is on scrolls
+fn scroll_start(app: &App, i: usize, editor_width: usize) -> usize {
+    let visual_x = app.editor.visual_cursor_x;
+    if i == app.editor.cursor.y && visual_x > editor_width {
+        visual_x - editor_width
+    } else {
+        0
+    }
 }
 
-///manipulates how the editor content \t character is rendered visually
-fn handle_tab_rendering(s: String) -> String {
-    let mut temp_string: Vec<char> = s.chars().collect();
-    let tab_width = editor_settings::TAB_WIDTH;
-
-    let mut i = 0;
-
-    while i < temp_string.len() {
-        if temp_string[i] == '\t' {
-            let spaces_needed = tab_width as usize - ((i as i16) as usize % tab_width as usize);
+///styled columns of the control chars in line, drawn as their placeholders
+fn control_char_styles(line: &str, theme: Theme) -> Vec<(std::ops::Range<usize>, Style)> {
+    //bytes of multibyte chars are all above the control chars
+    if !line.bytes().any(|b| (b < b' ' && b != b'\t') || b == 0x7f) {
+        return vec![];
+    }
+    line.chars()
+        .enumerate()
+        .filter(|&(_, c)| line_index::control_placeholder(c).is_some())
+        .map(|(x, _)| (x..x + 1, theme.control_char()))
+        .collect()
+}
 
-            temp_string.remove(i);
-            temp_string.splice(i..i, std::iter::repeat(' ').take(spaces_needed));
+///styled columns of the highlighted brackets on line y
+fn bracket_styles(
+    brackets: BracketMatch,
+    y: usize,
+    theme: Theme,
+) -> Vec<(std::ops::Range<usize>, Style)> {
+    let (positions, style) = match brackets {
+        BracketMatch::Matched(pair) => (vec![pair.open, pair.close], theme.matched_bracket()),
+        BracketMatch::Unmatched(pos) => (vec![pos], theme.unmatched_bracket()),
+    };
+    positions
+        .into_iter()
+        .filter(|pos| pos.y == y)
+        .map(|pos| (pos.x..pos.x + 1, style))
+        .collect()
+}
 
-            i += spaces_needed - 1; // Adjust index for added spaces
+///styles the chars of line in ranges, which are ordered by start. a range overlapping the
+/// one before it is skipped
+fn style_ranges<'a>(
+    line: &str,
+    ranges: impl Iterator<Item = (std::ops::Range<usize>, Style)>,
+) -> Line<'a> {
+    let mut spans = Vec::new();
+    let mut done = 0;
+    for (range, style) in ranges.filter(|(range, _)| !range.is_empty()) {
+        let start = line_index::byte_index(line, range.start);
+        let end = line_index::byte_index(line, range.end);
+        if start >= line.len() {
+            break;
+        }
+        if start < done {
+            continue;
         }
-        i += 1;
+        spans.push(Span::raw(line[done..start].to_string()));
+        spans.push(Span::styled(line[start..end].to_string(), style));
+        done = end;
     }
-
-    temp_string.into_iter().collect()
+    spans.push(Span::raw(line[done..].to_string()));
+    Line::from(spans)
 }
 
 //TEXT HIGHLIGTHING
 
+///highlights the selection in text with selected, text holding the lines from first_row on.
+/// tabs and control chars are expanded like outside a selection.
 fn highlight_text<'a>(
-    text: Vec<String>,
-    start: Option<CursorPosition>,
-    end: Option<CursorPosition>,
+    text: &[(&str, Option<usize>)],
+    first_row: usize,
+    selection: Range,
+    editor_width: usize,
+    tab_width: usize,
+    selected: Style,
 ) -> Text<'a> {
+    let Range { start, end } = selection.normalized();
     let mut highlighted_lines = Vec::new();
 
-    for (i, line) in text.iter().enumerate() {
+    for (row, &(original, shown_from)) in text.iter().enumerate() {
+        let i = first_row + row;
         let mut spans = Vec::new();
-
-        if i < start.unwrap().y || i > end.unwrap().y {
-            spans.push(Span::raw(line.clone())); // No selection on this line
+        //lines cut to a slice are shown from that visual column, the rest whole
+        let (from, width) = match shown_from {
+            Some(from) => (from, editor_width),
+            None => (0, usize::MAX),
+        };
+        let line = line_index::visible_slice(original, from, width, tab_width).into_owned();
+
+        if !selection.lines().contains(&i) {
+            spans.push(Span::raw(line)); // No selection on this line
         } else {
-            let start_col = if i == start.unwrap().y {
-                start.unwrap().x
-            } else {
-                0
+            let start_col = if i == start.line { start.col } else { 0 };
+            let end_col = if i == end.line { end.col } else { usize::MAX };
+
+            // Ensure selection is within valid bounds, columns are chars of the line and
+            // chars of the expanded line from there on
+            let visual = |col: usize| match col {
+                usize::MAX => usize::MAX,
+                col => line_index::visual_column(original, col, tab_width).saturating_sub(from),
             };
-            let end_col = if i == end.unwrap().y {
-                end.unwrap().x
-            } else {
-                line.len()
-            };
-
-            // Ensure selection is within valid bounds
-            let start_col = start_col.min(line.len());
-            let end_col = end_col.min(line.len());
+            let start_col = line_index::byte_index(&line, visual(start_col));
+            let end_col = line_index::byte_index(&line, visual(end_col));
 
             spans.push(Span::raw(line[..start_col].to_string())); // Before selection
             spans.push(Span::styled(
                 line[start_col..end_col].to_string(), // Highlighted text
-                Style::default()
-                    .bg(Color::White)
-                    .fg(Color::Black)
-                    .add_modifier(Modifier::BOLD),
+                selected,
             ));
             spans.push(Span::raw(line[end_col..].to_string())); // After selection
         }
@@ -2,6 +2,7 @@
 mod integration_app_tests {
     use calliglyph::config::command_binds::*;
     use calliglyph::core::app::*;
+    use calliglyph::core::editor::Editor;
     use calliglyph::input::input_action::InputAction;
     use calliglyph::ui::popups::popup::PopupResult;
     use std::fs;
@@ -10,8 +11,7 @@ mod integration_app_tests {
 
     //init functions
     fn create_app() -> App {
-        let app = App::new();
-        app
+        App::new()
     }
 
     fn test_save_path(filename: &str) -> String {
@@ -29,7 +29,7 @@ mod integration_app_tests {
     fn test_save_confirmation_saves_file_and_removes_state() {
         let mut app = create_app();
         let save_path = test_save_path("file1.txt");
-        app.editor.editor_content = vec![String::from("test")];
+        app.editor = Editor::from_text("test");
 
         app.pending_states
             .push(PendingState::Saving(save_path.clone()));
@@ -50,7 +50,7 @@ mod integration_app_tests {
     fn test_save_rejection_closes_popup_but_does_not_save() {
         let mut app = create_app();
         let save_path = test_save_path("file2.txt");
-        app.editor.editor_content = vec![String::from("test")];
+        app.editor = Editor::from_text("test");
 
         app.pending_states
             .push(PendingState::Saving(save_path.clone()));
@@ -76,7 +76,7 @@ mod integration_app_tests {
     fn test_save_then_quit_calls_save_then_quit() {
         let mut app = create_app();
         let save_path = test_save_path("file3.txt");
-        app.editor.editor_content = vec![String::from("test")];
+        app.editor = Editor::from_text("test");
         app.pending_states
             .push(PendingState::Saving(save_path.clone()));
         app.pending_states.push(PendingState::Quitting);
@@ -94,7 +94,7 @@ mod integration_app_tests {
 
     fn create_app_with_editor_content(vec: Vec<String>) -> App {
         let mut app = App::new();
-        app.editor.editor_content = vec;
+        app.editor = Editor::from_text(&vec.join("\n"));
         app
     }
     #[test]
@@ -132,7 +132,19 @@ mod integration_app_tests {
     }
 
     #[test]
-    fn test_save_with_no_file_path_defaults_to_untitled() {
+    fn test_save_with_no_file_path_asks_for_a_name() {
+        let mut app = create_app_with_editor_content(vec!["Default content".to_string()]);
+
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = ":".to_owned() + COMMAND_SAVE_DONT_EXIT;
+        app.process_input_action(InputAction::ENTER);
+
+        assert_eq!(app.pending_states, vec![PendingState::NamingFile]);
+        assert_eq!(app.active_area, ActiveArea::Popup);
+    }
+
+    #[test]
+    fn test_forced_save_with_no_file_path_defaults_to_untitled() {
         let mut app = create_app_with_editor_content(vec!["Default content".to_string()]);
 
         app.active_area = ActiveArea::CommandLine;
@@ -164,6 +176,60 @@ mod integration_app_tests {
         assert_eq!(saved_content, "Unchanged content"); // No overwrite happened
     }
 
+    #[test]
+    fn test_save_asks_before_overwriting_same_length_change() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let file_path = temp_file.path().to_str().unwrap().to_string();
+        fs::write(&file_path, "first\nline").unwrap();
+        let mut app = create_app_with_editor_content(vec!["first".to_string(), "LINE".to_string()]);
+        app.file_path = Some(file_path.clone());
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = ":".to_owned() + COMMAND_SAVE_DONT_EXIT;
+        app.process_input_action(InputAction::ENTER);
+
+        assert!(app.popup.is_some());
+        assert_eq!(
+            app.pending_states,
+            vec![PendingState::Saving(file_path.clone())]
+        );
+        assert_eq!(fs::read_to_string(file_path).unwrap(), "first\nline");
+    }
+
+    #[test]
+    fn test_save_of_identical_content_does_not_ask() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let file_path = temp_file.path().to_str().unwrap().to_string();
+        fs::write(&file_path, "first\nline").unwrap();
+        let mut app = create_app_with_editor_content(vec!["first".to_string(), "line".to_string()]);
+        app.file_path = Some(file_path.clone());
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = ":".to_owned() + COMMAND_SAVE_DONT_EXIT;
+        app.process_input_action(InputAction::ENTER);
+
+        assert!(app.popup.is_none());
+        assert!(app.pending_states.is_empty());
+        assert_eq!(fs::read_to_string(file_path).unwrap(), "first\nline");
+    }
+
+    #[test]
+    fn test_zero_byte_file_opens_edits_and_saves() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let file_path = temp_file.path().to_str().unwrap().to_string();
+        let mut app = create_app();
+        app.open_file(file_path.clone()).unwrap();
+        assert_eq!(app.editor.lines(), [""]);
+
+        app.process_input_action(InputAction::WriteChar('x'));
+        app.process_input_action(InputAction::ENTER);
+        app.process_input_action(InputAction::WriteChar('y'));
+        assert_eq!(app.editor.lines(), ["x", "y"]);
+
+        app.active_area = ActiveArea::CommandLine;
+        app.command_line.input = ":".to_owned() + COMMAND_SAVE_DONT_EXIT + " --force";
+        app.process_input_action(InputAction::ENTER);
+        assert_eq!(fs::read_to_string(file_path).unwrap(), "x\ny");
+    }
+
     #[test]
     fn test_save_creates_new_file_if_missing() {
         let temp_file_path = "new_test_file.txt".to_string();
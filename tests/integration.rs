@@ -0,0 +1,822 @@
+//drives App headless: synthesized crossterm events in, frames rendered into a TestBackend out
+
+use calliglyph::core::app::{ActiveArea, App};
+use calliglyph::core::cursor::CursorPosition;
+use calliglyph::core::editor::Editor;
+use calliglyph::core::spell::SpellChecker;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::backend::TestBackend;
+use ratatui::layout::Position;
+use ratatui::style::{Color, Modifier};
+use ratatui::Terminal;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+fn key(code: KeyCode) -> Event {
+    Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+fn ctrl(c: char) -> Event {
+    Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL))
+}
+
+fn type_text(app: &mut App, text: &str) {
+    for c in text.chars() {
+        app.handle_event(key(KeyCode::Char(c)));
+    }
+}
+
+///the working dir of the process, held by a test changing it or relying on it. the dir it
+/// had is restored when dropped, also if the test failed.
+struct CwdGuard {
+    start: PathBuf,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl Drop for CwdGuard {
+    fn drop(&mut self) {
+        let _ = std::env::set_current_dir(&self.start);
+    }
+}
+
+///waits until no other test uses the working dir and takes it
+fn lock_cwd() -> CwdGuard {
+    static CWD: Mutex<()> = Mutex::new(());
+    //a test failing while holding it still restored the dir
+    let lock = CWD.lock().unwrap_or_else(PoisonError::into_inner);
+    CwdGuard {
+        start: std::env::current_dir().unwrap(),
+        _lock: lock,
+    }
+}
+
+///renders app into terminal and returns the rows of the screen as text
+fn render(app: &mut App, terminal: &mut Terminal<TestBackend>) -> Vec<String> {
+    terminal.draw(|frame| app.render(frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        })
+        .collect()
+}
+
+fn screen_contains(screen: &[String], text: &str) -> bool {
+    screen.iter().any(|row| row.contains(text))
+}
+
+#[test]
+fn test_type_save_and_quit() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("notes.txt").to_string_lossy().to_string();
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+
+    let mut app = App::new();
+    app.start(Some(path.clone()), false);
+    assert!(app.is_running());
+    //like the main loop, draw before the first event, so the app knows the editor size
+    render(&mut app, &mut terminal);
+
+    type_text(&mut app, "hello");
+    app.handle_event(key(KeyCode::Enter));
+    type_text(&mut app, "world");
+
+    let screen = render(&mut app, &mut terminal);
+    assert!(screen_contains(&screen, "hello"));
+    assert!(screen_contains(&screen, "world"));
+    assert_eq!(app.editor.cursor(), CursorPosition::new(5, 1));
+    //the terminal cursor sits right after the typed text, in the row below the info bar
+    let cursor = terminal.get_cursor_position().unwrap();
+    let row = &screen[cursor.y as usize];
+    assert!(row.contains("world"));
+
+    app.handle_event(ctrl('s'));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "hello\nworld");
+    let screen = render(&mut app, &mut terminal);
+    assert!(screen_contains(&screen, &format!("saved {}", path)));
+
+    app.handle_event(ctrl('q'));
+    assert!(!app.is_running());
+}
+
+#[test]
+fn test_modified_marker_goes_away_when_undoing_to_saved_state() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("todo.txt").to_string_lossy().to_string();
+    fs::write(&path, "milk").unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    let mut app = App::new();
+    app.start(Some(path.clone()), false);
+    assert!(!render(&mut app, &mut terminal)[0].contains("[+]"));
+
+    type_text(&mut app, "oat ");
+    assert!(render(&mut app, &mut terminal)[0].contains(&format!("{} [+]", path)));
+    for _ in 0..4 {
+        app.handle_event(ctrl('z'));
+    }
+    assert!(!render(&mut app, &mut terminal)[0].contains("[+]"));
+
+    //back at the file's content, quitting doesn't ask about saving
+    app.handle_event(ctrl('q'));
+    assert!(!app.is_running());
+}
+
+#[test]
+fn test_quit_with_unsaved_changes_asks_then_saves() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("draft.txt").to_string_lossy().to_string();
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+
+    let mut app = App::new();
+    app.start(Some(path.clone()), false);
+    render(&mut app, &mut terminal);
+    type_text(&mut app, "draft");
+
+    app.handle_event(ctrl('q'));
+    assert!(app.is_running());
+    assert_eq!(app.active_area, ActiveArea::Popup);
+    let screen = render(&mut app, &mut terminal);
+    assert!(screen_contains(&screen, "Unsaved changes"));
+
+    //confirming saves, then quits
+    app.handle_event(key(KeyCode::Enter));
+    assert!(app.popup.is_none());
+    assert!(!app.is_running());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "draft");
+}
+
+#[test]
+fn test_cursor_position_follows_events() {
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    let mut app = App::new();
+    app.start(None, false);
+    render(&mut app, &mut terminal);
+
+    type_text(&mut app, "abc");
+    app.handle_event(key(KeyCode::Left));
+    render(&mut app, &mut terminal);
+
+    assert_eq!(app.editor.cursor(), CursorPosition::new(2, 0));
+    let cursor = terminal.get_cursor_position().unwrap();
+    let start = Position::new(cursor.x - 2, cursor.y);
+    assert_eq!(terminal.backend().buffer()[start].symbol(), "a");
+}
+
+#[test]
+fn test_edit_and_save_at_end_of_file_past_old_line_limit() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("big.log").to_string_lossy().to_string();
+    let lines: Vec<String> = (0..100_000).map(|i| format!("entry {}", i)).collect();
+    fs::write(&path, lines.join("\n")).unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+
+    let mut app = App::new();
+    app.start(Some(path.clone()), false);
+    render(&mut app, &mut terminal);
+
+    app.handle_event(Event::Key(KeyEvent::new(
+        KeyCode::End,
+        KeyModifiers::CONTROL,
+    )));
+    assert_eq!(app.editor.cursor(), CursorPosition::new(11, 99_999));
+    type_text(&mut app, " done");
+    app.handle_event(key(KeyCode::Enter));
+    type_text(&mut app, "tail");
+    let screen = render(&mut app, &mut terminal);
+    assert!(screen_contains(&screen, "entry 99999 done"));
+    assert!(screen_contains(&screen, "tail"));
+    assert_eq!(app.editor.cursor(), CursorPosition::new(4, 100_000));
+
+    app.handle_event(ctrl('s'));
+    //large buffers may be written in the background
+    while app.is_saving() {
+        app.on_tick();
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    let saved = fs::read_to_string(&path).unwrap();
+    let saved: Vec<&str> = saved.split('\n').collect();
+    assert_eq!(saved.len(), 100_001);
+    assert_eq!(saved[0], "entry 0");
+    assert_eq!(saved[99_999], "entry 99999 done");
+    assert_eq!(saved[100_000], "tail");
+}
+
+#[test]
+fn test_spell_checking_underlines_unknown_words_in_prose_files() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("notes.md").to_string_lossy().to_string();
+    fs::write(&path, "a wrold apart").unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+
+    let mut app = App::new();
+    app.start(Some(path), false);
+    app.spell = Some(SpellChecker::new(None));
+
+    let screen = render(&mut app, &mut terminal);
+    let row = screen.iter().position(|row| row.contains("wrold")).unwrap();
+    let col = screen[row].find("wrold").unwrap() as u16;
+    let buffer = terminal.backend().buffer();
+    let underlined = |x: u16| {
+        buffer[(x, row as u16)]
+            .modifier
+            .contains(Modifier::UNDERLINED)
+    };
+    assert!((col..col + 5).all(underlined));
+    assert!(!underlined(col - 2));
+    assert!(!underlined(col + 6));
+}
+
+#[test]
+fn test_annotation_keywords_are_highlighted() {
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    let mut app = App::new();
+    app.start(None, false);
+    type_text(&mut app, "x // TODO later");
+
+    let screen = render(&mut app, &mut terminal);
+    let row = screen.iter().position(|row| row.contains("TODO")).unwrap();
+    let col = screen[row].find("TODO").unwrap() as u16;
+    let buffer = terminal.backend().buffer();
+    let bold = |x: u16| buffer[(x, row as u16)].modifier.contains(Modifier::BOLD);
+    assert!((col..col + 4).all(bold));
+    assert!(!bold(col + 5));
+}
+
+#[test]
+fn test_long_lines_render_plain_until_long_line_mode_is_off() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("long.txt");
+    fs::write(&path, format!("// TODO {}\nshort\n", "x".repeat(30_000))).unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    let mut app = App::new();
+    app.start(Some(path.to_string_lossy().to_string()), false);
+    assert!(app.status().unwrap().contains("long-line mode enabled"));
+
+    let screen = render(&mut app, &mut terminal);
+    let row = screen.iter().position(|row| row.contains("TODO")).unwrap();
+    let col = screen[row].find("TODO").unwrap() as u16;
+    let bold = |terminal: &Terminal<TestBackend>| {
+        terminal.backend().buffer()[(col, row as u16)]
+            .modifier
+            .contains(Modifier::BOLD)
+    };
+    assert!(!bold(&terminal));
+
+    //a selection in the long line is drawn on its visible slice
+    let select_right = Event::Key(KeyEvent::new(KeyCode::Right, KeyModifiers::SHIFT));
+    app.handle_event(select_right.clone());
+    app.handle_event(select_right);
+    render(&mut app, &mut terminal);
+    let bg = |x: u16| terminal.backend().buffer()[(x, row as u16)].bg;
+    assert_eq!(bg(col - 3), Color::White);
+    assert_eq!(bg(col - 2), Color::White);
+    assert_ne!(bg(col - 1), Color::White);
+
+    //the first Esc drops the selection
+    app.handle_event(key(KeyCode::Esc));
+    app.handle_event(key(KeyCode::Esc));
+    type_text(&mut app, ":set longlinemode off");
+    app.handle_event(key(KeyCode::Enter));
+    render(&mut app, &mut terminal);
+    assert!(bold(&terminal));
+}
+
+#[test]
+fn test_brackets_next_to_cursor_are_highlighted() {
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    let mut app = App::new();
+    app.start(None, false);
+    type_text(&mut app, "f(a) (b");
+
+    //the cursor is after b, beside no bracket
+    let screen = render(&mut app, &mut terminal);
+    let row = screen.iter().position(|row| row.contains("f(a)")).unwrap();
+    let col = screen[row].find("f(a)").unwrap() as u16;
+    let bg = |x: u16| terminal.backend().buffer()[(x, row as u16)].bg;
+    assert!((col..col + 7).all(|x| bg(x) == Color::Reset));
+
+    //past the closing bracket both brackets of the pair light up
+    for _ in 0..3 {
+        app.handle_event(key(KeyCode::Left));
+    }
+    render(&mut app, &mut terminal);
+    let bg = |x: u16| terminal.backend().buffer()[(x, row as u16)].bg;
+    assert_eq!(bg(col + 1), Color::DarkGray);
+    assert_eq!(bg(col + 3), Color::DarkGray);
+    assert_eq!(bg(col + 2), Color::Reset);
+
+    //the bracket opened last has no partner
+    app.handle_event(key(KeyCode::Right));
+    render(&mut app, &mut terminal);
+    let bg = |x: u16| terminal.backend().buffer()[(x, row as u16)].bg;
+    assert_eq!(bg(col + 5), Color::LightRed);
+    assert_eq!(bg(col + 1), Color::Reset);
+}
+
+#[test]
+fn test_control_chars_render_as_dim_placeholders() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("page.txt").to_string_lossy().to_string();
+    fs::write(&path, "a\x0cb\x1b[0mc").unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    let mut app = App::new();
+    app.start(Some(path.clone()), false);
+
+    let screen = render(&mut app, &mut terminal);
+    let row = screen
+        .iter()
+        .position(|row| row.contains("a^Lb^[[0mc"))
+        .unwrap();
+    let col = screen[row].find("a^Lb").unwrap() as u16;
+    let modifier = |x: u16| terminal.backend().buffer()[(x, row as u16)].modifier;
+    assert!(modifier(col + 1).contains(Modifier::DIM));
+    assert!(modifier(col + 2).contains(Modifier::DIM));
+    assert!(!modifier(col + 3).contains(Modifier::DIM));
+    assert!(modifier(col + 5).contains(Modifier::DIM));
+
+    //the cursor after the escape lands on the [ following its placeholder
+    for _ in 0..4 {
+        app.handle_event(key(KeyCode::Right));
+    }
+    render(&mut app, &mut terminal);
+    let cursor = terminal.get_cursor_position().unwrap();
+    assert_eq!(cursor, Position::new(col + 6, row as u16));
+    assert_eq!(terminal.backend().buffer()[cursor].symbol(), "[");
+
+    //only the drawing changed, the text is saved back byte for byte
+    app.handle_event(ctrl('s'));
+    assert_eq!(fs::read(&path).unwrap(), b"a\x0cb\x1b[0mc");
+}
+
+///colors used anywhere on the screen, foreground and background
+fn screen_colors(terminal: &Terminal<TestBackend>) -> Vec<Color> {
+    let buffer = terminal.backend().buffer();
+    buffer
+        .content()
+        .iter()
+        .flat_map(|cell| [cell.fg, cell.bg])
+        .filter(|&color| color != Color::Reset)
+        .collect()
+}
+
+#[test]
+fn test_color_off_draws_everything_with_attributes_only() {
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    let mut app = App::new();
+    app.start(None, false);
+    type_text(&mut app, "f(a) TODO");
+    let select_left = Event::Key(KeyEvent::new(KeyCode::Left, KeyModifiers::SHIFT));
+    app.handle_event(select_left.clone());
+    app.handle_event(select_left);
+    //the quit confirmation draws a popup over it all
+    app.handle_event(ctrl('q'));
+    let screen = render(&mut app, &mut terminal);
+    let row = screen.iter().position(|row| row.contains("f(a)")).unwrap();
+    let col = screen[row].find("f(a)").unwrap() as u16;
+    assert!(!screen_colors(&terminal).is_empty());
+
+    app.settings.set("color", "off").unwrap();
+    let mono = render(&mut app, &mut terminal);
+    assert_eq!(mono, screen);
+    assert_eq!(screen_colors(&terminal), vec![]);
+    let modifier = |x: u16, y: u16| terminal.backend().buffer()[(x, y)].modifier;
+    //selection, info bar, gutter line number and command line stand out by reversing
+    assert!(modifier(col + 7, row as u16).contains(Modifier::REVERSED));
+    assert!(!modifier(col + 6, row as u16).contains(Modifier::REVERSED));
+    assert!(modifier(0, 0).contains(Modifier::REVERSED));
+    assert!(modifier(0, row as u16).contains(Modifier::REVERSED));
+    assert!(modifier(0, 23).contains(Modifier::REVERSED));
+    //and so does the chosen popup option
+    let (y, popup_row) = mono
+        .iter()
+        .enumerate()
+        .find(|(_, row)| row.contains(" Yes "))
+        .unwrap();
+    //the popup border before it is wider in bytes than in cells
+    let x = popup_row[..popup_row.find(" Yes ").unwrap()]
+        .chars()
+        .count() as u16;
+    assert!(modifier(x + 1, y as u16).contains(Modifier::REVERSED));
+    assert!(modifier(x + 5, y as u16).is_empty());
+}
+
+#[test]
+fn test_scrolling_away_from_cursor_and_back() {
+    let mut terminal = Terminal::new(TestBackend::new(160, 24)).unwrap();
+    let mut app = App::new();
+    app.start(None, false);
+    render(&mut app, &mut terminal);
+    for i in 0..60 {
+        type_text(&mut app, &format!("line {}", i));
+        app.handle_event(key(KeyCode::Enter));
+    }
+    app.handle_event(Event::Key(KeyEvent::new(
+        KeyCode::Home,
+        KeyModifiers::CONTROL,
+    )));
+
+    app.handle_event(ctrl('d'));
+    app.handle_event(ctrl('d'));
+    app.handle_event(Event::Key(KeyEvent::new(KeyCode::Down, KeyModifiers::ALT)));
+    let screen = render(&mut app, &mut terminal);
+    assert!(screen_contains(&screen, "cursor off-screen"));
+    assert!(!screen_contains(&screen, "line 0 "));
+    assert_eq!(app.editor.cursor(), CursorPosition::new(0, 0));
+
+    //typing brings the view back to the cursor
+    type_text(&mut app, "x");
+    let screen = render(&mut app, &mut terminal);
+    assert!(!screen_contains(&screen, "cursor off-screen"));
+    assert!(screen_contains(&screen, "xline 0"));
+}
+
+#[test]
+fn test_info_bar_shows_position_then_selection_size() {
+    let mut terminal = Terminal::new(TestBackend::new(120, 24)).unwrap();
+    let mut app = App::new();
+    app.start(None, false);
+    type_text(&mut app, "one");
+    app.handle_event(key(KeyCode::Enter));
+    app.handle_event(key(KeyCode::Tab));
+    type_text(&mut app, "two");
+    let screen = render(&mut app, &mut terminal);
+    //the tab moves the drawn column further than the char index
+    assert!(screen_contains(&screen, "Ln 2, Col 8 (5)"));
+
+    let shift = |code| Event::Key(KeyEvent::new(code, KeyModifiers::SHIFT));
+    app.handle_event(shift(KeyCode::Up));
+    let screen = render(&mut app, &mut terminal);
+    assert!(screen_contains(&screen, "(2 lines, 5 chars selected)"));
+}
+
+#[test]
+fn test_tab_completes_set_values_and_lists_ambiguous_ones() {
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    let mut app = App::new();
+    app.start(None, false);
+    app.handle_event(key(KeyCode::Esc));
+    type_text(&mut app, ":set wordc");
+    app.handle_event(key(KeyCode::Tab));
+    type_text(&mut app, "o");
+    app.handle_event(key(KeyCode::Tab));
+    assert_eq!(app.active_area, ActiveArea::CommandLine);
+    assert_eq!(app.command_line.input, ":set wordcount o");
+
+    let screen = render(&mut app, &mut terminal);
+    assert_eq!(screen[22].trim_end(), "off  on");
+
+    type_text(&mut app, "n");
+    app.handle_event(key(KeyCode::Enter));
+    let screen = render(&mut app, &mut terminal);
+    assert!(!screen_contains(&screen, "off  on"));
+    assert!(screen_contains(&screen, "Words: 0"));
+}
+
+#[test]
+fn test_set_wordboundary_changes_word_motions() {
+    let mut app = App::new();
+    app.start(None, false);
+    type_text(&mut app, "foo-bar baz");
+    let word_right = Event::Key(KeyEvent::new(KeyCode::Right, KeyModifiers::CONTROL));
+    app.handle_event(key(KeyCode::Home));
+    app.handle_event(word_right.clone());
+    assert_eq!(app.editor.cursor(), CursorPosition::new(3, 0));
+
+    app.handle_event(key(KeyCode::Esc));
+    type_text(&mut app, ":set wordboundary -");
+    app.handle_event(key(KeyCode::Enter));
+    app.handle_event(key(KeyCode::Home));
+    app.handle_event(word_right);
+    assert_eq!(app.editor.cursor(), CursorPosition::new(7, 0));
+}
+
+#[test]
+fn test_relative_save_after_cd_lands_in_new_dir() {
+    let dir = TempDir::new().unwrap();
+    let sub = dir.path().join("sub");
+    fs::create_dir(&sub).unwrap();
+    let _cwd = lock_cwd();
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    let mut app = App::new();
+    app.start(None, false);
+    render(&mut app, &mut terminal);
+    type_text(&mut app, "hello");
+
+    app.handle_event(key(KeyCode::Esc));
+    type_text(&mut app, &format!(":cd {}", sub.display()));
+    app.handle_event(key(KeyCode::Enter));
+    let screen = render(&mut app, &mut terminal);
+    assert!(screen_contains(&screen, "sub"));
+
+    app.handle_event(key(KeyCode::Esc));
+    type_text(&mut app, ":w notes.txt");
+    app.handle_event(key(KeyCode::Enter));
+    assert_eq!(fs::read_to_string(sub.join("notes.txt")).unwrap(), "hello");
+
+    //leaving the dir keeps saving the file where it was opened
+    app.handle_event(key(KeyCode::Esc));
+    type_text(&mut app, ":cd ..");
+    app.handle_event(key(KeyCode::Enter));
+    type_text(&mut app, "!");
+    app.handle_event(ctrl('s'));
+    assert_eq!(fs::read_to_string(sub.join("notes.txt")).unwrap(), "hello!");
+    assert!(!dir.path().join("notes.txt").exists());
+
+    //autochdir follows the dir of each opened file
+    app.settings.auto_chdir = true;
+    app.handle_event(key(KeyCode::Esc));
+    type_text(&mut app, ":open sub/notes.txt");
+    app.handle_event(key(KeyCode::Enter));
+    assert_eq!(
+        std::env::current_dir().unwrap(),
+        fs::canonicalize(&sub).unwrap()
+    );
+}
+
+#[test]
+fn test_welcome_screen_until_typing_and_enter_opens_recent_file() {
+    let dir = TempDir::new().unwrap();
+    let first = dir.path().join("first.txt");
+    let second = dir.path().join("second.txt");
+    fs::write(&first, "first file").unwrap();
+    fs::write(&second, "second file").unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+    let mut app = App::new();
+    app.session.add_recent(first.to_str().unwrap());
+    app.session.add_recent(second.to_str().unwrap());
+    app.start(None, false);
+
+    let screen = render(&mut app, &mut terminal);
+    assert!(screen_contains(&screen, env!("CARGO_PKG_VERSION")));
+    assert!(screen_contains(&screen, ":open <file>"));
+    assert!(screen_contains(&screen, "> "));
+    assert!(screen_contains(&screen, "second.txt"));
+    //the screen is drawn over the buffer, nothing is put into it
+    assert_eq!(app.editor.lines(), [""]);
+
+    app.handle_event(key(KeyCode::Down));
+    app.handle_event(key(KeyCode::Enter));
+    assert_eq!(app.file_path.as_deref(), first.to_str());
+    let screen = render(&mut app, &mut terminal);
+    assert!(screen_contains(&screen, "first file"));
+    assert!(!screen_contains(&screen, ":open <file>"));
+}
+
+#[test]
+fn test_typing_on_welcome_screen_writes_into_the_buffer() {
+    let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+    let mut app = App::new();
+    app.start(None, false);
+    assert!(screen_contains(
+        &render(&mut app, &mut terminal),
+        ":open <file>"
+    ));
+
+    type_text(&mut app, "hi");
+    let screen = render(&mut app, &mut terminal);
+    assert!(!screen_contains(&screen, ":open <file>"));
+    assert!(screen_contains(&screen, "hi"));
+    assert_eq!(app.editor.lines(), ["hi"]);
+
+    //deleting the text again doesn't bring the screen back, the buffer was touched
+    app.handle_event(key(KeyCode::Backspace));
+    app.handle_event(key(KeyCode::Backspace));
+    assert!(!app.shows_welcome());
+}
+
+#[test]
+fn test_tiny_terminals_degrade_without_panicking() {
+    let sizes = [(1, 1), (10, 3), (20, 5)];
+    let screens: Vec<Vec<String>> = sizes
+        .iter()
+        .map(|&(width, height)| {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            let mut app = App::new();
+            app.start(None, false);
+            type_text(&mut app, "hello");
+            let screen = render(&mut app, &mut terminal);
+            //a popup bigger than the terminal is shrunk to it
+            app.request_quit();
+            render(&mut app, &mut terminal);
+            screen
+        })
+        .collect();
+    //too small for anything but the start of the message
+    assert_eq!(screens[0], vec!["t"]);
+    //no info bar or gutter, the text starts in the top left corner
+    assert!(screens[1][0].starts_with("hello"));
+    //both come back with more room
+    assert!(screens[2][0].starts_with("untitled"));
+    assert!(screens[2][1].starts_with("0  hello"));
+}
+
+#[test]
+fn test_terminal_below_minimum_size_says_what_it_needs() {
+    let mut terminal = Terminal::new(TestBackend::new(40, 2)).unwrap();
+    let mut app = App::new();
+    app.start(None, false);
+    let screen = render(&mut app, &mut terminal);
+    assert_eq!(screen[0].trim_end(), "terminal too small (need 10x3)");
+}
+
+#[test]
+fn test_long_command_line_input_scrolls_to_keep_the_cursor_in_view() {
+    let mut terminal = Terminal::new(TestBackend::new(40, 10)).unwrap();
+    let mut app = App::new();
+    app.start(None, false);
+    app.handle_event(key(KeyCode::Esc));
+    //multi-byte chars take one column like the rest
+    let typed: String = ":"
+        .chars()
+        .chain("é€ab".chars().cycle().take(199))
+        .collect();
+    type_text(&mut app, &typed);
+    assert_eq!(app.command_line.input, typed);
+
+    let screen = render(&mut app, &mut terminal);
+    let row = &screen[9];
+    assert!(row.starts_with('<'));
+    //the cursor is after the last char, in the last column
+    assert_eq!(
+        terminal.get_cursor_position().unwrap(),
+        Position::new(39, 9)
+    );
+    let shown: String = row.chars().skip(1).take(38).collect();
+    let expected: String = typed.chars().skip(200 - 38).collect();
+    assert_eq!(shown, expected);
+
+    //back at the start the input is cut off on the right instead
+    for _ in 0..200 {
+        app.handle_event(key(KeyCode::Left));
+    }
+    let screen = render(&mut app, &mut terminal);
+    let row = &screen[9];
+    assert!(row.starts_with(":é€abé€ab"));
+    assert!(row.ends_with('>'));
+    assert_eq!(terminal.get_cursor_position().unwrap(), Position::new(0, 9));
+
+    //moving right scrolls once the cursor reaches the > marker
+    for _ in 0..45 {
+        app.handle_event(key(KeyCode::Right));
+    }
+    render(&mut app, &mut terminal);
+    assert_eq!(
+        terminal.get_cursor_position().unwrap(),
+        Position::new(38, 9)
+    );
+    let expected_under_cursor = typed.chars().nth(45).unwrap();
+    let screen = render(&mut app, &mut terminal);
+    assert_eq!(screen[9].chars().nth(38), Some(expected_under_cursor));
+}
+
+#[test]
+fn test_info_describes_file_and_unsaved_edits() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("notes.txt");
+    fs::write(&path, "hello\nworld\n").unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    let mut app = App::new();
+    app.start(Some(path.to_string_lossy().to_string()), false);
+    render(&mut app, &mut terminal);
+
+    app.handle_event(key(KeyCode::Esc));
+    type_text(&mut app, ":info");
+    app.handle_event(key(KeyCode::Enter));
+    let screen = render(&mut app, &mut terminal);
+    assert_eq!(app.active_area, ActiveArea::Popup);
+    let canonical = fs::canonicalize(&path).unwrap();
+    assert!(screen_contains(&screen, &canonical.to_string_lossy()));
+    assert!(screen_contains(&screen, "size on disk: 12 bytes"));
+    assert!(screen_contains(&screen, "lines: 2"));
+    assert!(screen_contains(&screen, "encoding: utf8"));
+    assert!(screen_contains(&screen, "line ending: lf, final newline"));
+    assert!(screen_contains(&screen, "differs from disk: no"));
+
+    app.handle_event(key(KeyCode::Esc));
+    type_text(&mut app, "!");
+    fs::remove_file(&path).unwrap();
+    app.handle_event(key(KeyCode::Esc));
+    app.command_line.input = ":info".to_string();
+    app.handle_event(key(KeyCode::Enter));
+    let screen = render(&mut app, &mut terminal);
+    //a deleted file leaves the in-memory details
+    assert!(screen_contains(&screen, "on disk: "));
+    assert!(screen_contains(&screen, "chars: 12"));
+    assert!(screen_contains(&screen, "differs from disk: unknown"));
+}
+
+#[test]
+fn test_info_on_untitled_buffer_shows_counts_only() {
+    let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+    let mut app = App::new();
+    app.start(None, false);
+    render(&mut app, &mut terminal);
+    type_text(&mut app, "abc");
+    app.handle_event(key(KeyCode::Esc));
+    type_text(&mut app, ":info");
+    app.handle_event(key(KeyCode::Enter));
+
+    let screen = render(&mut app, &mut terminal);
+    assert!(screen_contains(&screen, "untitled buffer"));
+    assert!(screen_contains(&screen, "chars: 3"));
+    assert!(!screen_contains(&screen, "size on disk"));
+    assert!(!screen_contains(&screen, "path:"));
+}
+
+#[test]
+fn test_batch_mode_edits_without_a_terminal_and_fails_with_status() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("list.txt");
+    let script = dir.path().join("script");
+    fs::write(&path, "one\ntwo").unwrap();
+    fs::write(&script, "reverse\nw --force\n").unwrap();
+    let run = || {
+        //run in a dir of its own, the working dir of this process may be changed by a test
+        std::process::Command::new(env!("CARGO_BIN_EXE_calliglyph"))
+            .current_dir(dir.path())
+            .arg("--batch")
+            .arg(&script)
+            .arg(&path)
+            .output()
+            .unwrap()
+    };
+
+    let output = run();
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "two\none");
+
+    fs::write(&script, "reverse\nbogus\nw --force\n").unwrap();
+    let output = run();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("line 2, bogus"), "{}", stderr);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "two\none");
+}
+
+#[test]
+fn test_batch_mode_substitutes_trims_and_saves_the_opened_file() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("notes.txt");
+    let script = dir.path().join("script");
+    fs::write(&path, "a\nb\nc\nd\nfoo foo  \nfoo\t").unwrap();
+    fs::write(&script, "goto 5\ns/foo/bar/g\ntrim\nsave\n").unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_calliglyph"))
+        .current_dir(dir.path())
+        .arg("--batch")
+        .arg(&script)
+        .arg(&path)
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success(), "{}", stderr);
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "a\nb\nc\nd\nbar bar\nbar"
+    );
+}
+
+///time to draw a frame of the middle of a buffer with line_count lines, the fastest of a
+/// few batches so a busy machine doesn't make one size look slow
+fn frame_time(line_count: usize) -> Duration {
+    let text: Vec<String> = (0..line_count)
+        .map(|i| format!("line {} lorem ipsum", i))
+        .collect();
+    let mut app = App::new();
+    app.editor = Editor::from_text(&text.join("\n"));
+    app.editor
+        .set_cursor(CursorPosition::new(0, line_count / 2))
+        .unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(160, 50)).unwrap();
+    //the first frame sets up the viewport and caches
+    render(&mut app, &mut terminal);
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..20 {
+                terminal.draw(|frame| app.render(frame)).unwrap();
+            }
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+#[test]
+fn test_frame_time_does_not_grow_with_the_line_count() {
+    let small = frame_time(1_000);
+    let large = frame_time(300_000);
+    //300 times the lines, a frame builds only those in view
+    let slowdown = large.as_secs_f64() / small.as_secs_f64();
+    assert!(
+        slowdown < 3.0,
+        "frame time grew {:.1}x with the line count",
+        slowdown
+    );
+}